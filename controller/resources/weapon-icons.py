@@ -0,0 +1,481 @@
+#!/usr/bin/env python3
+# Generates weapon-icons.ttf, the icon font used to render weapons in the player ESP.
+#
+# The glyphs are simple silhouettes drawn for this project and are distributed
+# under the same license as the rest of the repository (GPL-2.0).
+# Each weapon class has one glyph within the private use area starting at U+E000.
+# The mapping from weapon ids to these codepoints lives in `controller/src/enhancements/player.rs`.
+#
+# Usage: python3 weapon-icons.py [output path]
+
+import math
+import struct
+import sys
+
+UNITS_PER_EM = 1000
+ASCENT = 800
+DESCENT = -200
+CODEPOINT_BASE = 0xE000
+
+# creation date (2026-10-17) in seconds since 1904-01-01, fixed to keep the output reproducible
+TIMESTAMP = 3875040000
+
+
+def rect(x0, y0, x1, y1):
+    return [(x0, y0), (x0, y1), (x1, y1), (x1, y0)]
+
+
+def circle(cx, cy, radius, segments=16):
+    return [
+        (
+            round(cx + radius * math.cos(2 * math.pi * index / segments)),
+            round(cy + radius * math.sin(2 * math.pi * index / segments)),
+        )
+        for index in range(segments)
+    ]
+
+
+# Glyphs in codepoint order: (name, advance width, contours)
+GLYPHS = [
+    (
+        "pistol",
+        900,
+        [
+            rect(40, 440, 820, 620),
+            [(90, 440), (330, 440), (270, 60), (40, 60)],
+            [(330, 440), (460, 440), (460, 330), (330, 330)],
+        ],
+    ),
+    (
+        "smg",
+        1250,
+        [
+            rect(40, 380, 1060, 580),
+            rect(1060, 470, 1210, 540),
+            [(360, 380), (500, 380), (450, 90), (310, 90)],
+            [(640, 380), (770, 380), (740, 40), (620, 40)],
+            rect(0, 420, 60, 560),
+        ],
+    ),
+    (
+        "rifle",
+        1700,
+        [
+            [(0, 270), (340, 400), (340, 570), (0, 560)],
+            rect(340, 390, 1160, 590),
+            rect(1160, 480, 1680, 540),
+            [(560, 390), (690, 390), (640, 130), (510, 130)],
+            [(760, 390), (900, 390), (980, 110), (850, 70)],
+            rect(1220, 540, 1260, 640),
+        ],
+    ),
+    (
+        "sniper",
+        1900,
+        [
+            [(0, 270), (360, 400), (360, 570), (0, 560)],
+            rect(360, 390, 1160, 580),
+            rect(1160, 490, 1880, 540),
+            [(560, 390), (690, 390), (640, 130), (510, 130)],
+            rect(800, 300, 920, 390),
+            rect(560, 640, 1060, 740),
+            rect(760, 580, 840, 640),
+        ],
+    ),
+    (
+        "shotgun",
+        1700,
+        [
+            [(0, 270), (360, 400), (360, 580), (0, 570)],
+            rect(360, 420, 900, 600),
+            rect(900, 500, 1680, 590),
+            rect(1000, 400, 1320, 500),
+            [(560, 420), (690, 420), (640, 150), (510, 150)],
+        ],
+    ),
+    (
+        "machine_gun",
+        1800,
+        [
+            [(0, 270), (340, 400), (340, 580), (0, 570)],
+            rect(340, 380, 1160, 600),
+            rect(1160, 470, 1780, 540),
+            [(520, 380), (650, 380), (600, 130), (470, 130)],
+            rect(720, 130, 1020, 380),
+            [(1500, 470), (1560, 470), (1660, 100), (1600, 100)],
+            [(1500, 470), (1560, 470), (1460, 100), (1400, 100)],
+        ],
+    ),
+    (
+        "knife",
+        1100,
+        [
+            rect(40, 390, 340, 510),
+            rect(340, 320, 390, 580),
+            [(390, 380), (390, 540), (820, 540), (1080, 470), (820, 380)],
+        ],
+    ),
+    (
+        "grenade",
+        720,
+        [
+            circle(360, 290, 260),
+            rect(280, 520, 440, 640),
+            [(440, 640), (520, 640), (600, 320), (560, 300), (440, 600)],
+        ],
+    ),
+    (
+        "flashbang",
+        720,
+        [
+            rect(190, 0, 530, 560),
+            rect(260, 560, 460, 650),
+            [(460, 650), (520, 650), (600, 160), (560, 150), (460, 600)],
+        ],
+    ),
+    (
+        "smoke",
+        720,
+        [
+            rect(140, 0, 580, 500),
+            rect(250, 500, 470, 600),
+            [(470, 600), (530, 600), (640, 160), (600, 150), (470, 550)],
+        ],
+    ),
+    (
+        "molotov",
+        720,
+        [
+            [(170, 0), (170, 340), (290, 430), (430, 430), (550, 340), (550, 0)],
+            rect(300, 430, 420, 620),
+            [(300, 620), (340, 780), (360, 700), (390, 800), (420, 620)],
+        ],
+    ),
+    (
+        "decoy",
+        720,
+        [
+            rect(170, 0, 550, 240),
+            rect(170, 290, 550, 540),
+            rect(280, 540, 440, 640),
+        ],
+    ),
+    (
+        "c4",
+        1000,
+        [
+            rect(40, 40, 300, 440),
+            rect(370, 40, 630, 440),
+            rect(700, 40, 960, 440),
+            rect(40, 470, 960, 520),
+            rect(780, 520, 820, 740),
+        ],
+    ),
+    (
+        "taser",
+        950,
+        [
+            rect(40, 420, 700, 600),
+            rect(700, 380, 900, 640),
+            [(100, 420), (320, 420), (260, 60), (40, 60)],
+        ],
+    ),
+    (
+        "healthshot",
+        850,
+        [
+            rect(120, 240, 620, 420),
+            rect(0, 200, 60, 460),
+            rect(60, 300, 120, 360),
+            rect(620, 300, 660, 360),
+            rect(660, 318, 830, 342),
+        ],
+    ),
+]
+
+
+def clockwise(contour):
+    """TrueType expects outer contours in clockwise order"""
+    area = sum(
+        x0 * y1 - x1 * y0
+        for (x0, y0), (x1, y1) in zip(contour, contour[1:] + contour[:1])
+    )
+    return contour if area < 0 else list(reversed(contour))
+
+
+def encode_glyph(contours):
+    if not contours:
+        return b""
+
+    contours = [clockwise(contour) for contour in contours]
+    points = [point for contour in contours for point in contour]
+    xs = [x for x, _ in points]
+    ys = [y for _, y in points]
+
+    data = struct.pack(">hhhhh", len(contours), min(xs), min(ys), max(xs), max(ys))
+    end_point = -1
+    for contour in contours:
+        end_point += len(contour)
+        data += struct.pack(">H", end_point)
+
+    # no instructions, every point is on the curve and uses 16 bit deltas
+    data += struct.pack(">H", 0)
+    data += bytes([0x01] * len(points))
+
+    last = 0
+    for x in xs:
+        data += struct.pack(">h", x - last)
+        last = x
+
+    last = 0
+    for y in ys:
+        data += struct.pack(">h", y - last)
+        last = y
+
+    return data
+
+
+def pad(data):
+    return data + b"\0" * (-len(data) % 4)
+
+
+def table_checksum(data):
+    data = pad(data)
+    return sum(struct.unpack(">%dI" % (len(data) // 4), data)) & 0xFFFFFFFF
+
+
+def build_name_table():
+    names = {
+        0: "Copyright the Valthrun-CHS contributors",
+        1: "Valthrun Weapon Icons",
+        2: "Regular",
+        3: "Valthrun Weapon Icons Regular",
+        4: "Valthrun Weapon Icons Regular",
+        5: "Version 1.000",
+        6: "ValthrunWeaponIcons-Regular",
+        13: "Licensed under the GNU General Public License version 2",
+    }
+
+    records = b""
+    strings = b""
+    for name_id, value in names.items():
+        encoded = value.encode("utf-16-be")
+        records += struct.pack(">HHHHHH", 3, 1, 0x409, name_id, len(encoded), len(strings))
+        strings += encoded
+
+    header = struct.pack(">HHH", 0, len(names), 6 + len(records))
+    return header + records + strings
+
+
+def build_cmap_table(glyph_count):
+    first = CODEPOINT_BASE
+    last = CODEPOINT_BASE + glyph_count - 1
+    segments = [(first, last, (1 - first) & 0xFFFF), (0xFFFF, 0xFFFF, 1)]
+
+    seg_count = len(segments)
+    search_range = 2 * (1 << (seg_count.bit_length() - 1))
+    subtable = struct.pack(
+        ">HHHHHHH",
+        4,
+        16 + 8 * seg_count,
+        0,
+        seg_count * 2,
+        search_range,
+        (search_range // 2).bit_length() - 1,
+        seg_count * 2 - search_range,
+    )
+    subtable += b"".join(struct.pack(">H", end) for _, end, _ in segments)
+    subtable += struct.pack(">H", 0)
+    subtable += b"".join(struct.pack(">H", start) for start, _, _ in segments)
+    subtable += b"".join(struct.pack(">H", delta) for _, _, delta in segments)
+    subtable += b"".join(struct.pack(">H", 0) for _ in segments)
+
+    header = struct.pack(">HH", 0, 2)
+    header += struct.pack(">HHI", 0, 3, 4 + 8 * 2)
+    header += struct.pack(">HHI", 3, 1, 4 + 8 * 2)
+    return header + subtable
+
+
+def build_font():
+    # glyph 0 is the empty .notdef glyph
+    glyphs = [(".notdef", 500, [])] + GLYPHS
+    encoded = [pad(encode_glyph(contours)) for _, _, contours in glyphs]
+
+    glyf = b"".join(encoded)
+    loca = b""
+    offset = 0
+    for data in encoded:
+        loca += struct.pack(">I", offset)
+        offset += len(data)
+    loca += struct.pack(">I", offset)
+
+    all_points = [point for _, _, contours in glyphs for contour in contours for point in contour]
+    x_min = min(x for x, _ in all_points)
+    y_min = min(y for _, y in all_points)
+    x_max = max(x for x, _ in all_points)
+    y_max = max(y for _, y in all_points)
+
+    hmtx = b""
+    min_rsb = None
+    for _, advance, contours in glyphs:
+        xs = [x for contour in contours for x, _ in contour]
+        lsb = min(xs) if xs else 0
+        hmtx += struct.pack(">Hh", advance, lsb)
+        if xs:
+            rsb = advance - max(xs)
+            min_rsb = rsb if min_rsb is None else min(min_rsb, rsb)
+
+    max_points = max(sum(len(contour) for contour in contours) for _, _, contours in glyphs)
+    max_contours = max(len(contours) for _, _, contours in glyphs)
+    advance_max = max(advance for _, advance, _ in glyphs)
+
+    head = struct.pack(
+        ">IIIIHHqqhhhhHHhhh",
+        0x00010000,
+        0x00010000,
+        0,
+        0x5F0F3CF5,
+        0x000B,
+        UNITS_PER_EM,
+        TIMESTAMP,
+        TIMESTAMP,
+        x_min,
+        y_min,
+        x_max,
+        y_max,
+        0,
+        8,
+        2,
+        1,
+        0,
+    )
+
+    hhea = struct.pack(
+        ">IhhhHhhhhhhhhhhhH",
+        0x00010000,
+        ASCENT,
+        DESCENT,
+        0,
+        advance_max,
+        0,
+        min_rsb or 0,
+        x_max,
+        1,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        len(glyphs),
+    )
+
+    maxp = struct.pack(
+        ">IHHHHHHHHHHHHHH",
+        0x00010000,
+        len(glyphs),
+        max_points,
+        max_contours,
+        0,
+        0,
+        2,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+    )
+
+    os2 = struct.pack(
+        ">HhHHHhhhhhhhhhhh",
+        4,
+        sum(advance for _, advance, _ in glyphs) // len(glyphs),
+        400,
+        5,
+        0,
+        650,
+        600,
+        0,
+        75,
+        650,
+        600,
+        0,
+        350,
+        50,
+        250,
+        0,
+    )
+    os2 += bytes(10)  # panose
+    os2 += struct.pack(">IIII", 0, 0x10000000, 0, 0)  # unicode ranges: private use area
+    os2 += b"NONE"
+    os2 += struct.pack(
+        ">HHHhhhHHIIhhHHH",
+        0x0040,
+        CODEPOINT_BASE,
+        CODEPOINT_BASE + len(GLYPHS) - 1,
+        ASCENT,
+        DESCENT,
+        0,
+        ASCENT,
+        -DESCENT,
+        1,
+        0,
+        500,
+        700,
+        0,
+        0,
+        0,
+    )
+
+    post = struct.pack(">IIhhIIIII", 0x00030000, 0, -100, 50, 0, 0, 0, 0, 0)
+
+    tables = {
+        b"OS/2": os2,
+        b"cmap": build_cmap_table(len(GLYPHS)),
+        b"glyf": glyf,
+        b"head": head,
+        b"hhea": hhea,
+        b"hmtx": hmtx,
+        b"loca": loca,
+        b"maxp": maxp,
+        b"name": build_name_table(),
+        b"post": post,
+    }
+
+    table_count = len(tables)
+    search_range = 16 * (1 << (table_count.bit_length() - 1))
+    font = struct.pack(
+        ">IHHHH",
+        0x00010000,
+        table_count,
+        search_range,
+        (search_range // 16).bit_length() - 1,
+        table_count * 16 - search_range,
+    )
+
+    offset = 12 + 16 * table_count
+    directory = b""
+    body = b""
+    head_offset = 0
+    for tag in sorted(tables):
+        data = tables[tag]
+        if tag == b"head":
+            head_offset = offset
+        directory += struct.pack(">4sIII", tag, table_checksum(data), offset, len(data))
+        body += pad(data)
+        offset += len(pad(data))
+
+    font = bytearray(font + directory + body)
+    adjustment = (0xB1B0AFBA - table_checksum(bytes(font))) & 0xFFFFFFFF
+    struct.pack_into(">I", font, head_offset + 8, adjustment)
+    return bytes(font)
+
+
+if __name__ == "__main__":
+    output = sys.argv[1] if len(sys.argv) > 1 else "weapon-icons.ttf"
+    with open(output, "wb") as file:
+        file.write(build_font())
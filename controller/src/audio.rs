@@ -0,0 +1,164 @@
+use std::{
+    f32::consts::TAU,
+    sync::{
+        mpsc::{
+            self,
+            Sender,
+        },
+        Mutex,
+        OnceLock,
+    },
+};
+
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::HMODULE,
+        Media::Audio::{
+            PlaySoundW,
+            SND_MEMORY,
+            SND_NODEFAULT,
+            SND_SYNC,
+        },
+    },
+};
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Fade in/out time of every tone to avoid audible clicks
+const TONE_RAMP_MS: u32 = 5;
+
+/// Short sounds the enhancements can play as cues
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioCue {
+    TriggerBotEnabled,
+    TriggerBotDisabled,
+    BombWarning,
+    ProximityAlert,
+    AimWarning,
+    SpectatorJoined,
+}
+
+impl AudioCue {
+    /// Tones (frequency in Hz, duration in ms) played after each other.
+    /// A frequency of zero is a pause.
+    fn tones(&self) -> &'static [(f32, u32)] {
+        match self {
+            Self::TriggerBotEnabled => &[(660.0, 60), (880.0, 90)],
+            Self::TriggerBotDisabled => &[(880.0, 60), (660.0, 90)],
+            Self::BombWarning => &[
+                (1200.0, 90),
+                (0.0, 60),
+                (1200.0, 90),
+                (0.0, 60),
+                (1200.0, 90),
+            ],
+            Self::ProximityAlert => &[(520.0, 150), (0.0, 40), (520.0, 150)],
+            Self::AimWarning => &[(980.0, 120), (740.0, 160)],
+            Self::SpectatorJoined => &[(440.0, 80), (554.0, 80), (659.0, 120)],
+        }
+    }
+}
+
+/// Render the tones as 16 bit mono PCM wave file
+fn render_wave(tones: &[(f32, u32)], volume: f32) -> Vec<u8> {
+    let ramp_samples = (SAMPLE_RATE * TONE_RAMP_MS / 1000) as f32;
+
+    let mut samples = Vec::new();
+    for (frequency, duration) in tones {
+        let sample_count = SAMPLE_RATE * duration / 1000;
+        for index in 0..sample_count {
+            if *frequency <= 0.0 {
+                samples.push(0i16);
+                continue;
+            }
+
+            let envelope = (index as f32 / ramp_samples)
+                .min((sample_count - index) as f32 / ramp_samples)
+                .min(1.0);
+            let value = (TAU * frequency * index as f32 / SAMPLE_RATE as f32).sin();
+            samples.push((value * envelope * volume * i16::MAX as f32) as i16);
+        }
+    }
+
+    let data_length = (samples.len() * 2) as u32;
+    let mut wave = Vec::with_capacity(44 + data_length as usize);
+    wave.extend_from_slice(b"RIFF");
+    wave.extend_from_slice(&(36 + data_length).to_le_bytes());
+    wave.extend_from_slice(b"WAVE");
+
+    wave.extend_from_slice(b"fmt ");
+    wave.extend_from_slice(&16u32.to_le_bytes());
+    wave.extend_from_slice(&1u16.to_le_bytes()); /* PCM */
+    wave.extend_from_slice(&1u16.to_le_bytes()); /* mono */
+    wave.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wave.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wave.extend_from_slice(&2u16.to_le_bytes());
+    wave.extend_from_slice(&16u16.to_le_bytes());
+
+    wave.extend_from_slice(b"data");
+    wave.extend_from_slice(&data_length.to_le_bytes());
+    for sample in samples {
+        wave.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wave
+}
+
+/// Sounds are played one after another by a dedicated thread
+/// so the update loop never waits for the audio device.
+fn audio_queue() -> &'static Mutex<Sender<Vec<u8>>> {
+    static AUDIO_QUEUE: OnceLock<Mutex<Sender<Vec<u8>>>> = OnceLock::new();
+    AUDIO_QUEUE.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+        std::thread::spawn(move || {
+            for wave in receiver {
+                let played = unsafe {
+                    PlaySoundW(
+                        PCWSTR::from_raw(wave.as_ptr() as *const u16),
+                        HMODULE::default(),
+                        SND_MEMORY | SND_SYNC | SND_NODEFAULT,
+                    )
+                };
+
+                if !played.as_bool() {
+                    log::warn!("播放提示声音失败");
+                }
+            }
+        });
+
+        Mutex::new(sender)
+    })
+}
+
+/// Play a cue with the given master volume (0 - 100)
+pub fn play_cue(cue: AudioCue, volume: u32) {
+    if volume == 0 {
+        return;
+    }
+
+    let wave = render_wave(cue.tones(), volume.min(100) as f32 / 100.0);
+    let _ = audio_queue().lock().unwrap().send(wave);
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        render_wave,
+        SAMPLE_RATE,
+    };
+
+    #[test]
+    fn wave_header_matches_samples() {
+        let wave = render_wave(&[(440.0, 100), (0.0, 50)], 0.5);
+        let sample_count = SAMPLE_RATE * 150 / 1000;
+
+        assert_eq!(&wave[0..4], b"RIFF");
+        assert_eq!(&wave[8..12], b"WAVE");
+        assert_eq!(wave.len(), 44 + sample_count as usize * 2);
+        assert_eq!(
+            u32::from_le_bytes(wave[40..44].try_into().unwrap()),
+            sample_count * 2
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::Local;
+use overlay::{
+    notify,
+    CapturedFrame,
+    FrameCapture,
+    NotificationLevel,
+    SystemRuntimeController,
+};
+
+use crate::{
+    i18n::tr,
+    settings::get_settings_path,
+    NOTIFICATION_DURATION,
+};
+
+const CAPTURE_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+/// Captures are stored in `captures` next to the default config
+pub fn get_capture_directory() -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    Ok(settings_path
+        .parent()
+        .context("could not get config directory")?
+        .join("captures"))
+}
+
+fn save_capture(frame: CapturedFrame) -> anyhow::Result<PathBuf> {
+    let directory = get_capture_directory()?;
+    std::fs::create_dir_all(&directory).context("failed to create the captures directory")?;
+
+    let path = directory.join(format!(
+        "{}.png",
+        Local::now().format(CAPTURE_TIMESTAMP_FORMAT)
+    ));
+    image::save_buffer(
+        &path,
+        &frame.data,
+        frame.width,
+        frame.height,
+        image::ColorType::Rgba8,
+    )
+    .context("failed to encode the capture")?;
+
+    Ok(path)
+}
+
+/// Captures the overlay (and optionally the game below) into a PNG file
+pub struct ScreenshotCapture {
+    requested: bool,
+    pending: Option<FrameCapture>,
+}
+
+impl ScreenshotCapture {
+    pub fn new() -> Self {
+        Self {
+            requested: false,
+            pending: None,
+        }
+    }
+
+    /// Capture the next rendered frame
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    pub fn update(&mut self, controller: &mut SystemRuntimeController, include_game: bool) {
+        if self.requested && self.pending.is_none() {
+            self.requested = false;
+            self.pending = Some(controller.capture_frame(include_game));
+        }
+
+        let result = match self.pending.as_ref().and_then(FrameCapture::take_result) {
+            Some(result) => result,
+            None => return,
+        };
+        self.pending = None;
+
+        let frame = match result {
+            Ok(frame) => frame,
+            Err(error) => {
+                log::warn!("截图失败: {}", error);
+                notify(
+                    NotificationLevel::Error,
+                    &tr!("截图失败"),
+                    NOTIFICATION_DURATION,
+                );
+                return;
+            }
+        };
+
+        /* encoding a full screen PNG takes a few frames */
+        std::thread::spawn(move || match save_capture(frame) {
+            Ok(path) => {
+                log::info!("截图已保存至 {}", path.display());
+                notify(
+                    NotificationLevel::Success,
+                    &format!("{} {}", tr!("截图已保存至"), path.display()),
+                    NOTIFICATION_DURATION,
+                );
+            }
+            Err(error) => {
+                log::warn!("截图失败: {:#}", error);
+                notify(
+                    NotificationLevel::Error,
+                    &tr!("截图失败"),
+                    NOTIFICATION_DURATION,
+                );
+            }
+        });
+    }
+}
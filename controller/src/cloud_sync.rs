@@ -0,0 +1,487 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use anyhow::Context;
+use obfstr::obfstr;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::settings::{
+    get_profile_settings_path,
+    get_settings_path,
+    reset_fields,
+    AppSettings,
+    CloudSyncProvider,
+    SECRET_SETTINGS,
+};
+
+/// Interval in which the remote config will be checked for changes
+const SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Delay between saving the settings and uploading them,
+/// so dragging a slider does not cause an upload for every step.
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(10);
+
+/// Settings which only apply to the current machine and will never be synchronized.
+/// Secrets (`SECRET_SETTINGS`) are never synchronized either.
+const LOCAL_FIELDS: &[&str] = &["imgui", "ui_scale", "cloud_sync", "cloud_sync_provider"];
+
+/// Document stored at the remote endpoint
+#[derive(Serialize, Deserialize)]
+struct CloudDocument {
+    /// Unix timestamp (milliseconds) of the upload
+    modified: u64,
+
+    /// Serialized settings without the local only fields
+    settings: String,
+}
+
+/// State of the last successful synchronization of a profile
+#[derive(Default, Serialize, Deserialize)]
+struct SyncState {
+    remote_modified: u64,
+    content: String,
+}
+
+#[derive(Clone)]
+struct Endpoint {
+    provider: CloudSyncProvider,
+    url: String,
+    username: String,
+    token: String,
+}
+
+#[derive(Clone)]
+pub enum CloudSyncStatus {
+    Idle,
+    Syncing,
+    Synced(SystemTime),
+    Failed(String),
+}
+
+/// Remote settings which have been downloaded for a profile
+struct DownloadedSettings {
+    profile: Option<String>,
+    content: String,
+}
+
+enum SyncOutcome {
+    UpToDate,
+    Uploaded,
+    Downloaded(String),
+}
+
+/// Synchronizes the settings of the active profile with a WebDAV directory or a GitHub Gist.
+///
+/// Conflicts are resolved by last-writer-wins. The overwritten version is kept as a local backup.
+pub struct CloudSync {
+    status: Arc<Mutex<CloudSyncStatus>>,
+    downloaded: Arc<Mutex<Option<DownloadedSettings>>>,
+    next_sync: Option<Instant>,
+}
+
+impl CloudSync {
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(Mutex::new(CloudSyncStatus::Idle)),
+            downloaded: Default::default(),
+            next_sync: Some(Instant::now()),
+        }
+    }
+
+    pub fn status(&self) -> CloudSyncStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Synchronize with the next update
+    pub fn request_sync(&mut self) {
+        self.next_sync = Some(Instant::now());
+    }
+
+    /// The settings have been saved and should be uploaded soon
+    pub fn notify_saved(&mut self) {
+        let debounced = Instant::now() + SAVE_DEBOUNCE;
+        if self
+            .next_sync
+            .map_or(true, |next_sync| next_sync > debounced)
+        {
+            self.next_sync = Some(debounced);
+        }
+    }
+
+    /// Settings downloaded for the active profile which should replace the current settings
+    pub fn take_downloaded_settings(
+        &self,
+        profile: Option<&str>,
+        current: &AppSettings,
+    ) -> Option<AppSettings> {
+        let downloaded = self.downloaded.lock().unwrap().take()?;
+        if downloaded.profile.as_deref() != profile {
+            /* the profile has been switched while synchronizing */
+            log::debug!(
+                "云同步: 丢弃配置方案 {} 的下载结果",
+                downloaded.profile.as_deref().unwrap_or("config")
+            );
+
+            /* forget the sync state so the remote settings will be downloaded again */
+            if let Err(error) = forget_sync_state(downloaded.profile.as_deref()) {
+                log::warn!("重置云同步状态失败: {:#}", error);
+            }
+            return None;
+        }
+
+        match parse_settings(&downloaded.content, current) {
+            Ok(settings) => Some(settings),
+            Err(error) => {
+                log::warn!("解析云同步配置失败: {:#}", error);
+                *self.status.lock().unwrap() = CloudSyncStatus::Failed(format!("{:#}", error));
+                None
+            }
+        }
+    }
+
+    pub fn update(&mut self, profile: Option<&str>, settings: &AppSettings) {
+        if !settings.cloud_sync {
+            return;
+        }
+
+        if self
+            .next_sync
+            .map_or(false, |next_sync| next_sync > Instant::now())
+        {
+            return;
+        }
+
+        if matches!(*self.status.lock().unwrap(), CloudSyncStatus::Syncing) {
+            return;
+        }
+
+        self.next_sync = Some(Instant::now() + SYNC_INTERVAL);
+
+        let content = match serialize_settings(settings) {
+            Ok(content) => content,
+            Err(error) => {
+                *self.status.lock().unwrap() = CloudSyncStatus::Failed(format!("{:#}", error));
+                return;
+            }
+        };
+
+        let endpoint = Endpoint {
+            provider: settings.cloud_sync_provider,
+            url: settings.cloud_sync_url.trim().to_string(),
+            username: settings.cloud_sync_username.clone(),
+            token: settings.cloud_sync_token.clone(),
+        };
+        let profile = profile.map(str::to_string);
+
+        *self.status.lock().unwrap() = CloudSyncStatus::Syncing;
+        let status = self.status.clone();
+        let downloaded = self.downloaded.clone();
+        tokio::spawn(async move {
+            let result = synchronize(&endpoint, profile.as_deref(), content).await;
+            *status.lock().unwrap() = match result {
+                Ok(outcome) => {
+                    match outcome {
+                        SyncOutcome::UpToDate => log::debug!("云同步: 配置已是最新"),
+                        SyncOutcome::Uploaded => log::info!("云同步: 已上传本地配置"),
+                        SyncOutcome::Downloaded(content) => {
+                            log::info!("云同步: 已下载远程配置");
+                            *downloaded.lock().unwrap() = Some(DownloadedSettings {
+                                profile: profile.clone(),
+                                content,
+                            });
+                        }
+                    }
+
+                    CloudSyncStatus::Synced(SystemTime::now())
+                }
+                Err(error) => {
+                    log::warn!("云同步失败: {:#}", error);
+                    CloudSyncStatus::Failed(format!("{:#}", error))
+                }
+            };
+        });
+    }
+}
+
+fn unix_timestamp_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Serialize the settings without the local only fields
+fn serialize_settings(settings: &AppSettings) -> anyhow::Result<String> {
+    let mut value = serde_json::to_value(settings)?;
+    let fields = value.as_object_mut().context("expected a struct")?;
+    for field in LOCAL_FIELDS.iter().chain(SECRET_SETTINGS) {
+        fields.remove(*field);
+    }
+
+    Ok(serde_yaml::to_string(&value)?)
+}
+
+/// Parse synchronized settings while keeping the local only fields of the current settings
+fn parse_settings(content: &str, current: &AppSettings) -> anyhow::Result<AppSettings> {
+    let mut settings: AppSettings = serde_yaml::from_str(content)?;
+    reset_fields(&mut settings, current, LOCAL_FIELDS)?;
+    reset_fields(&mut settings, current, SECRET_SETTINGS)?;
+    Ok(settings)
+}
+
+fn get_cloud_sync_directory() -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    let base_dir = settings_path
+        .parent()
+        .context("could not get config directory")?;
+
+    Ok(base_dir.join("cloud_sync"))
+}
+
+fn document_name(profile: Option<&str>) -> String {
+    format!("valthrun-{}.json", profile.unwrap_or("config"))
+}
+
+fn load_sync_state(profile: Option<&str>) -> anyhow::Result<SyncState> {
+    let path = get_cloud_sync_directory()?.join(document_name(profile));
+    if !path.is_file() {
+        return Ok(Default::default());
+    }
+
+    Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+}
+
+fn store_sync_state(profile: Option<&str>, state: &SyncState) -> anyhow::Result<()> {
+    let directory = get_cloud_sync_directory()?;
+    std::fs::create_dir_all(&directory)?;
+    std::fs::write(
+        directory.join(document_name(profile)),
+        serde_json::to_vec(state)?,
+    )?;
+    Ok(())
+}
+
+fn forget_sync_state(profile: Option<&str>) -> anyhow::Result<()> {
+    let path = get_cloud_sync_directory()?.join(document_name(profile));
+    if path.is_file() {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// Keep a copy of the settings which are about to be overwritten
+fn store_backup(profile: Option<&str>, origin: &str, content: &str) -> anyhow::Result<()> {
+    let directory = get_cloud_sync_directory()?.join("backups");
+    std::fs::create_dir_all(&directory)?;
+
+    let file_name = format!(
+        "{}-{}-{}.yaml",
+        profile.unwrap_or("config"),
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        origin
+    );
+    std::fs::write(directory.join(&file_name), content)?;
+    log::info!("云同步: 已备份被覆盖的配置 {}", file_name);
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Gist {
+    files: std::collections::HashMap<String, GistFile>,
+}
+
+#[derive(Deserialize)]
+struct GistFile {
+    content: Option<String>,
+}
+
+fn gist_url(endpoint: &Endpoint) -> String {
+    format!("https://api.github.com/gists/{}", endpoint.url)
+}
+
+fn webdav_url(endpoint: &Endpoint, profile: Option<&str>) -> String {
+    format!(
+        "{}/{}",
+        endpoint.url.trim_end_matches('/'),
+        document_name(profile)
+    )
+}
+
+async fn fetch_document(
+    client: &reqwest::Client,
+    endpoint: &Endpoint,
+    profile: Option<&str>,
+) -> anyhow::Result<Option<CloudDocument>> {
+    let content = match endpoint.provider {
+        CloudSyncProvider::WebDav => {
+            let response = client
+                .get(webdav_url(endpoint, profile))
+                .basic_auth(&endpoint.username, Some(&endpoint.token))
+                .send()
+                .await?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            response.error_for_status()?.text().await?
+        }
+        CloudSyncProvider::Gist => {
+            let mut gist = client
+                .get(gist_url(endpoint))
+                .bearer_auth(&endpoint.token)
+                .header(reqwest::header::USER_AGENT, obfstr!("Valthrun-CHS"))
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Gist>()
+                .await?;
+
+            match gist
+                .files
+                .remove(&document_name(profile))
+                .and_then(|file| file.content)
+            {
+                Some(content) => content,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    let document = serde_json::from_str(&content).context("invalid remote document")?;
+    Ok(Some(document))
+}
+
+async fn upload_document(
+    client: &reqwest::Client,
+    endpoint: &Endpoint,
+    profile: Option<&str>,
+    document: &CloudDocument,
+) -> anyhow::Result<()> {
+    let content = serde_json::to_string(document)?;
+    match endpoint.provider {
+        CloudSyncProvider::WebDav => {
+            client
+                .put(webdav_url(endpoint, profile))
+                .basic_auth(&endpoint.username, Some(&endpoint.token))
+                .body(content)
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+        CloudSyncProvider::Gist => {
+            let body = serde_json::json!({
+                "files": {
+                    document_name(profile): { "content": content }
+                }
+            });
+            client
+                .patch(gist_url(endpoint))
+                .bearer_auth(&endpoint.token)
+                .header(reqwest::header::USER_AGENT, obfstr!("Valthrun-CHS"))
+                .json(&body)
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn synchronize(
+    endpoint: &Endpoint,
+    profile: Option<&str>,
+    local_content: String,
+) -> anyhow::Result<SyncOutcome> {
+    if endpoint.url.is_empty() {
+        anyhow::bail!("{}", obfstr!("missing sync endpoint"));
+    }
+
+    let client = reqwest::Client::new();
+    let state = load_sync_state(profile)?;
+    let remote = fetch_document(&client, endpoint, profile).await?;
+
+    let local_changed = state.content != local_content;
+    let remote = match remote {
+        Some(remote) if remote.modified != state.remote_modified => remote,
+        Some(_) if !local_changed => return Ok(SyncOutcome::UpToDate),
+        _ => {
+            /* only the local settings changed or nothing has been uploaded yet */
+            return upload(&client, endpoint, profile, local_content).await;
+        }
+    };
+
+    if remote.settings == local_content {
+        store_sync_state(
+            profile,
+            &SyncState {
+                remote_modified: remote.modified,
+                content: local_content,
+            },
+        )?;
+        return Ok(SyncOutcome::UpToDate);
+    }
+
+    let local_wins = local_changed && {
+        /* both sides changed: the most recent modification wins */
+        let local_modified = get_profile_settings_path(profile)
+            .and_then(|path| Ok(std::fs::metadata(path)?.modified()?))
+            .map(unix_timestamp_millis)
+            .unwrap_or_default();
+        local_modified > remote.modified
+    };
+
+    if local_wins {
+        store_backup(profile, "remote", &remote.settings)?;
+        upload(&client, endpoint, profile, local_content).await
+    } else {
+        if local_changed {
+            store_backup(profile, "local", &local_content)?;
+        }
+
+        store_sync_state(
+            profile,
+            &SyncState {
+                remote_modified: remote.modified,
+                content: remote.settings.clone(),
+            },
+        )?;
+        Ok(SyncOutcome::Downloaded(remote.settings))
+    }
+}
+
+async fn upload(
+    client: &reqwest::Client,
+    endpoint: &Endpoint,
+    profile: Option<&str>,
+    content: String,
+) -> anyhow::Result<SyncOutcome> {
+    let document = CloudDocument {
+        modified: unix_timestamp_millis(SystemTime::now()),
+        settings: content,
+    };
+    upload_document(client, endpoint, profile, &document).await?;
+
+    store_sync_state(
+        profile,
+        &SyncState {
+            remote_modified: document.modified,
+            content: document.settings,
+        },
+    )?;
+    Ok(SyncOutcome::Uploaded)
+}
@@ -2,7 +2,9 @@ use anyhow::Context;
 use cs2::{
     EntitySystem,
     Globals,
+    MouseSensitivity,
 };
+use cs2_schema_generated::cs2::client::CCSPlayerBase_CameraServices;
 use valthrun_kernel_interface::MouseState;
 
 use super::Enhancement;
@@ -31,6 +33,16 @@ impl AntiAimPunsh {
 }
 
 impl Enhancement for AntiAimPunsh {
+    fn injects_input(&self) -> bool {
+        true
+    }
+
+    fn suspend_input(&mut self, _ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+        self.mouse_adjustment_x = 0;
+        self.mouse_adjustment_y = 0;
+        Ok(())
+    }
+
     fn update(&mut self, ctx: &crate::UpdateContext) -> anyhow::Result<()> {
         let settings = ctx.states.resolve::<AppSettings>(())?;
         if !settings.aim_assist_recoil {
@@ -73,7 +85,16 @@ impl Enhancement for AntiAimPunsh {
             nalgebra::Vector4::<f32>::zeros()
         };
 
-        let deg_one = settings.mouse_x_360 as f32 / 360.0;
+        let zoom_fov = local_pawn
+            .m_pCameraServices()?
+            .cast::<CCSPlayerBase_CameraServices>()
+            .try_reference_schema()?
+            .map(|camera| camera.m_iFOV())
+            .transpose()?
+            .map(|fov| fov as f32);
+
+        let sensitivity = ctx.states.resolve::<MouseSensitivity>(())?;
+        let deg_one = sensitivity.counts_per_degree(zoom_fov);
         let target_mouse_y = (total_punch_angle.x * deg_one * -2.25).round() as i32;
         let delta_mouse_y = target_mouse_y - self.mouse_adjustment_y;
         self.mouse_adjustment_y = target_mouse_y;
@@ -83,7 +104,7 @@ impl Enhancement for AntiAimPunsh {
         self.mouse_adjustment_x = target_mouse_x;
 
         if delta_mouse_y != 0 || delta_mouse_x != 0 {
-            ctx.cs2.send_mouse_state(&[MouseState {
+            ctx.memory.send_mouse_state(&[MouseState {
                 last_y: delta_mouse_y,
                 last_x: delta_mouse_x,
                 ..Default::default()
@@ -0,0 +1,241 @@
+use std::time::Instant;
+
+use cs2::{
+    CEntityIdentityEx,
+    CS2Model,
+    ClassNameCache,
+    EntitySystem,
+    LocalCameraControllerTarget,
+    PlayerPawnState,
+};
+
+use super::Enhancement;
+use crate::{
+    audio::{
+        play_cue,
+        AudioCue,
+    },
+    i18n::tr,
+    settings::AppSettings,
+    UpdateContext,
+};
+
+/// Height of the players eyes relative to the players origin
+const PLAYER_EYE_HEIGHT: f32 = 64.0;
+
+/// Additional tolerance (in units) around the local players hull
+const HULL_TOLERANCE: f32 = 8.0;
+
+/// Minimum time between two warning sounds in milliseconds
+const SOUND_INTERVAL: u128 = 2_000;
+
+/// Distance of the direction indicators from the screen center (% of the screen height)
+const INDICATOR_RADIUS: f32 = 0.12;
+
+/// Check if the ray starting at origin intersects with the axis aligned bounding box (slab method).
+fn ray_intersects_aabb(
+    origin: &nalgebra::Vector3<f32>,
+    direction: &nalgebra::Vector3<f32>,
+    vmin: &nalgebra::Vector3<f32>,
+    vmax: &nalgebra::Vector3<f32>,
+) -> bool {
+    let mut t_min = 0.0f32;
+    let mut t_max = f32::MAX;
+
+    for axis in 0..3 {
+        if direction[axis].abs() < f32::EPSILON {
+            if origin[axis] < vmin[axis] || origin[axis] > vmax[axis] {
+                return false;
+            }
+
+            continue;
+        }
+
+        let inv_direction = 1.0 / direction[axis];
+        let mut t0 = (vmin[axis] - origin[axis]) * inv_direction;
+        let mut t1 = (vmax[axis] - origin[axis]) * inv_direction;
+        if t0 > t1 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+
+        t_min = t_min.max(t0);
+        t_max = t_max.min(t1);
+        if t_min > t_max {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Calculate the view direction from the pitch and yaw (in degrees)
+fn view_direction(pitch: f32, yaw: f32) -> nalgebra::Vector3<f32> {
+    let pitch = pitch.to_radians();
+    let yaw = yaw.to_radians();
+
+    nalgebra::Vector3::new(
+        pitch.cos() * yaw.cos(),
+        pitch.cos() * yaw.sin(),
+        -pitch.sin(),
+    )
+}
+
+pub struct AimingAtYouIndicator {
+    /// Directions of the enemies aiming at the local player
+    /// relative to the local players view yaw (in radians).
+    threat_directions: Vec<f32>,
+
+    last_sound: Option<Instant>,
+}
+
+impl AimingAtYouIndicator {
+    pub fn new() -> Self {
+        Self {
+            threat_directions: Default::default(),
+            last_sound: None,
+        }
+    }
+
+    fn play_warning_sound(&mut self, volume: u32) {
+        if self
+            .last_sound
+            .map(|time| time.elapsed().as_millis() < SOUND_INTERVAL)
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        self.last_sound = Some(Instant::now());
+        play_cue(AudioCue::AimWarning, volume);
+    }
+}
+
+impl Enhancement for AimingAtYouIndicator {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        let had_threats = !self.threat_directions.is_empty();
+        self.threat_directions.clear();
+
+        if !settings.aim_warning {
+            return Ok(());
+        }
+
+        let entities = ctx.states.resolve::<EntitySystem>(())?;
+        let class_name_cache = ctx.states.resolve::<ClassNameCache>(())?;
+
+        let local_player_controller = entities.get_local_player_controller()?;
+        if local_player_controller.is_null()? {
+            return Ok(());
+        }
+
+        let local_team_id = local_player_controller
+            .reference_schema()?
+            .m_iPendingTeamNum()?;
+
+        let view_target = ctx.states.resolve::<LocalCameraControllerTarget>(())?;
+        let target_entity_id = match &view_target.target_entity_id {
+            Some(value) => *value,
+            None => return Ok(()),
+        };
+
+        let local_pawn = ctx.states.resolve::<PlayerPawnState>(target_entity_id)?;
+        let local_pawn = match &*local_pawn {
+            PlayerPawnState::Alive(info) => info,
+            PlayerPawnState::Dead => return Ok(()),
+        };
+
+        let local_model = ctx.states.resolve::<CS2Model>(local_pawn.model_address)?;
+        let tolerance = nalgebra::Vector3::repeat(HULL_TOLERANCE);
+        let hull_min = local_pawn.position + local_model.vhull_min - tolerance;
+        let hull_max = local_pawn.position + local_model.vhull_max + tolerance;
+
+        for entity_identity in entities.all_identities() {
+            let entity_index = entity_identity.handle::<()>()?.get_entity_index();
+            if entity_index == target_entity_id {
+                continue;
+            }
+
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "C_CSPlayerPawn")
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let player = match ctx.states.resolve::<PlayerPawnState>(entity_index) {
+                Ok(info) => info,
+                Err(_) => continue,
+            };
+            let player = match &*player {
+                PlayerPawnState::Alive(info) => info,
+                PlayerPawnState::Dead => continue,
+            };
+
+            if player.team_id == local_team_id {
+                continue;
+            }
+
+            let eye_position =
+                player.position + nalgebra::Vector3::new(0.0, 0.0, PLAYER_EYE_HEIGHT);
+            let direction = view_direction(player.pitch, player.rotation);
+            if !ray_intersects_aabb(&eye_position, &direction, &hull_min, &hull_max) {
+                continue;
+            }
+
+            let delta = player.position - local_pawn.position;
+            let world_angle = delta.y.atan2(delta.x);
+            self.threat_directions
+                .push(world_angle - local_pawn.rotation.to_radians());
+        }
+
+        if settings.aim_warning_sound && !had_threats && !self.threat_directions.is_empty() {
+            self.play_warning_sound(settings.audio_volume);
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, _states: &utils_state::StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        if self.threat_directions.is_empty() {
+            return Ok(());
+        }
+
+        const WARNING_COLOR: [f32; 4] = [1.0, 0.2, 0.2, 1.0];
+
+        let draw = ui.get_window_draw_list();
+        let [screen_width, screen_height] = ui.io().display_size;
+        let center = nalgebra::Vector2::new(screen_width / 2.0, screen_height / 2.0);
+        let radius = screen_height * INDICATOR_RADIUS;
+
+        for direction in self.threat_directions.iter() {
+            /* a relative angle of zero is straight ahead which is upwards on the screen */
+            let screen_direction = nalgebra::Vector2::new(-direction.sin(), -direction.cos());
+            let normal = nalgebra::Vector2::new(-screen_direction.y, screen_direction.x);
+
+            let tip = center + screen_direction * (radius + 20.0);
+            let base = center + screen_direction * radius;
+            let left = base + normal * 10.0;
+            let right = base - normal * 10.0;
+
+            draw.add_triangle(
+                [tip.x, tip.y],
+                [left.x, left.y],
+                [right.x, right.y],
+                WARNING_COLOR,
+            )
+            .filled(true)
+            .build();
+        }
+
+        let text = tr!("被瞄准!").to_string();
+        let [text_width, _] = ui.calc_text_size(&text);
+        ui.set_cursor_pos([
+            center.x - text_width / 2.0,
+            center.y - radius - 20.0 - ui.text_line_height_with_spacing() * 2.0,
+        ]);
+        ui.text_colored(WARNING_COLOR, text);
+
+        Ok(())
+    }
+}
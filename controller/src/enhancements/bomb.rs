@@ -5,28 +5,139 @@ use cs2::{
 
 use super::Enhancement;
 use crate::{
-    settings::AppSettings,
+    audio::{
+        play_cue,
+        AudioCue,
+    },
+    i18n::tr,
+    settings::{
+        AppSettings,
+        HotKeyAction,
+    },
     utils::ImguiUiEx,
 };
-pub struct BombInfoIndicator {}
+pub struct BombInfoIndicator {
+    /// The warning sound has been played for the currently planted bomb
+    warning_played: bool,
+}
 
 impl BombInfoIndicator {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            warning_played: false,
+        }
     }
 }
 
+/// Remaining time (in seconds) until detonation when the warning sound is played
+const BOMB_WARNING_TIME: f32 = 10.0;
+
 /// % of the screens height
 const PLAYER_AVATAR_TOP_OFFSET: f32 = 0.004;
 
 /// % of the screens height
 const PLAYER_AVATAR_SIZE: f32 = 0.05;
 
+/// A single line of the bomb info indicator
+#[derive(Debug, PartialEq)]
+pub struct BombInfoLine {
+    pub color: Option<[f32; 4]>,
+    pub text: String,
+}
+
+impl BombInfoLine {
+    fn new(text: String) -> Self {
+        Self { color: None, text }
+    }
+}
+
+/// Generate the lines to display for the planted C4.
+/// Returns an empty list if the bomb has not been planted.
+pub fn bomb_info_lines(bomb_state: &PlantedC4) -> Vec<BombInfoLine> {
+    if matches!(bomb_state.state, PlantedC4State::NotPlanted) {
+        return vec![];
+    }
+
+    let mut lines = Vec::with_capacity(3);
+    lines.push(BombInfoLine::new(format!(
+        "{} {}",
+        tr!("炸弹安放在"),
+        if bomb_state.bomb_site == 0 { "A" } else { "B" }
+    )));
+
+    match &bomb_state.state {
+        PlantedC4State::Active { time_detonation } => {
+            lines.push(BombInfoLine::new(format!(
+                "{}: {:.3}",
+                tr!("倒计时"),
+                time_detonation
+            )));
+            if let Some(defuser) = &bomb_state.defuser {
+                let color = if defuser.time_remaining > *time_detonation {
+                    [0.79, 0.11, 0.11, 1.0]
+                } else {
+                    [0.11, 0.79, 0.26, 1.0]
+                };
+
+                lines.push(BombInfoLine {
+                    color: Some(color),
+                    text: format!(
+                        "{} {} {:.3} {}",
+                        defuser.player_name,
+                        tr!("正在拆除... 需要"),
+                        defuser.time_remaining,
+                        tr!("秒")
+                    ),
+                });
+            } else {
+                lines.push(BombInfoLine::new(tr!("未拆除").to_string()));
+            }
+        }
+        PlantedC4State::Defused => lines.push(BombInfoLine::new(tr!("炸弹已拆除").to_string())),
+        PlantedC4State::Detonated => lines.push(BombInfoLine::new(tr!("炸了").to_string())),
+        PlantedC4State::NotPlanted => unreachable!(),
+    }
+
+    lines
+}
+
+const HOTKEY_BOMB_TIMER: HotKeyAction = HotKeyAction {
+    id: "bomb_timer",
+    name: "切换炸弹计时器",
+    default_key: None,
+};
+
 impl Enhancement for BombInfoIndicator {
-    fn update(&mut self, _ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+    fn update(&mut self, ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.bomb_timer || !settings.bomb_timer_sound {
+            return Ok(());
+        }
+
+        let bomb_state = ctx.states.resolve::<PlantedC4>(())?;
+        match &bomb_state.state {
+            PlantedC4State::Active { time_detonation } if *time_detonation <= BOMB_WARNING_TIME => {
+                if !self.warning_played {
+                    self.warning_played = true;
+                    play_cue(AudioCue::BombWarning, settings.audio_volume);
+                }
+            }
+            PlantedC4State::Active { .. } => self.warning_played = false,
+            _ => {}
+        }
+
         Ok(())
     }
 
+    fn hotkey_actions(&self) -> &'static [HotKeyAction] {
+        &[HOTKEY_BOMB_TIMER]
+    }
+
+    fn on_hotkey_pressed(&mut self, _action: &HotKeyAction, settings: &mut AppSettings) -> bool {
+        settings.bomb_timer = !settings.bomb_timer;
+        true
+    }
+
     fn render(&self, states: &utils_state::StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
         let settings = states.resolve::<AppSettings>(())?;
         if !settings.bomb_timer {
@@ -34,18 +145,14 @@ impl Enhancement for BombInfoIndicator {
         }
 
         let bomb_state = states.resolve::<PlantedC4>(())?;
-        if matches!(bomb_state.state, PlantedC4State::NotPlanted) {
+        let lines = bomb_info_lines(&bomb_state);
+        if lines.is_empty() {
             return Ok(());
         }
 
         let group = ui.begin_group();
 
-        let line_count = match &bomb_state.state {
-            PlantedC4State::Active { .. } => 3,
-            PlantedC4State::Defused | PlantedC4State::Detonated => 2,
-            PlantedC4State::NotPlanted => unreachable!(),
-        };
-        let text_height = ui.text_line_height_with_spacing() * line_count as f32;
+        let text_height = ui.text_line_height_with_spacing() * lines.len() as f32;
 
         /* align to be on the right side after the players */
         let offset_x = ui.io().display_size[0] * 1730.0 / 2560.0;
@@ -54,47 +161,95 @@ impl Enhancement for BombInfoIndicator {
             + 0_f32.max((ui.io().display_size[1] * PLAYER_AVATAR_SIZE - text_height) / 2.0);
 
         ui.set_cursor_pos([offset_x, offset_y]);
-        ui.text(&format!(
-            "炸弹安放在 {}",
-            if bomb_state.bomb_site == 0 { "A" } else { "B" }
-        ));
-
-        match &bomb_state.state {
-            PlantedC4State::Active { time_detonation } => {
-                ui.set_cursor_pos_x(offset_x);
-                ui.text(&format!("倒计时: {:.3}", time_detonation));
-                if let Some(defuser) = &bomb_state.defuser {
-                    let color = if defuser.time_remaining > *time_detonation {
-                        [0.79, 0.11, 0.11, 1.0]
-                    } else {
-                        [0.11, 0.79, 0.26, 1.0]
-                    };
-
-                    ui.set_cursor_pos_x(offset_x);
-                    ui.text_colored(
-                        color,
-                        &format!(
-                            "{} 正在拆除... 需要 {:.3} 秒",
-                            defuser.player_name, defuser.time_remaining
-                        ),
-                    );
-                } else {
-                    ui.set_cursor_pos_x(offset_x);
-                    ui.text("未拆除");
-                }
-            }
-            PlantedC4State::Defused => {
-                ui.set_cursor_pos_x(offset_x);
-                ui.text("炸弹已拆除");
+        for line in lines {
+            ui.set_cursor_pos_x(offset_x);
+            match line.color {
+                Some(color) => ui.text_colored(color, &line.text),
+                None => ui.text(&line.text),
             }
-            PlantedC4State::Detonated => {
-                ui.set_cursor_pos_x(offset_x);
-                ui.text("炸了");
-            }
-            PlantedC4State::NotPlanted => unreachable!(),
         }
 
         group.end();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use cs2::{
+        BombDefuser,
+        PlantedC4,
+        PlantedC4State,
+    };
+
+    use super::{
+        bomb_info_lines,
+        BombInfoLine,
+    };
+
+    fn planted_c4(state: PlantedC4State, defuser: Option<BombDefuser>) -> PlantedC4 {
+        PlantedC4 {
+            bomb_site: 1,
+            state,
+            defuser,
+        }
+    }
+
+    fn line_texts(lines: &[BombInfoLine]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn planted() {
+        let lines = bomb_info_lines(&planted_c4(
+            PlantedC4State::Active {
+                time_detonation: 30.5,
+            },
+            None,
+        ));
+        assert_eq!(
+            line_texts(&lines),
+            vec!["炸弹安放在 B", "倒计时: 30.500", "未拆除"]
+        );
+        assert!(lines.iter().all(|line| line.color.is_none()));
+
+        let lines = bomb_info_lines(&planted_c4(PlantedC4State::NotPlanted, None));
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn defusing() {
+        let defuser = |time_remaining| BombDefuser {
+            time_remaining,
+            player_name: "Player".to_string(),
+        };
+
+        let lines = bomb_info_lines(&planted_c4(
+            PlantedC4State::Active {
+                time_detonation: 8.0,
+            },
+            Some(defuser(5.0)),
+        ));
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2].text, "Player 正在拆除... 需要 5.000 秒");
+        assert_eq!(lines[2].color, Some([0.11, 0.79, 0.26, 1.0]));
+
+        /* the defuse will not finish in time */
+        let lines = bomb_info_lines(&planted_c4(
+            PlantedC4State::Active {
+                time_detonation: 3.0,
+            },
+            Some(defuser(5.0)),
+        ));
+        assert_eq!(lines[2].color, Some([0.79, 0.11, 0.11, 1.0]));
+    }
+
+    #[test]
+    fn defused() {
+        let lines = bomb_info_lines(&planted_c4(PlantedC4State::Defused, None));
+        assert_eq!(line_texts(&lines), vec!["炸弹安放在 B", "炸弹已拆除"]);
+
+        let lines = bomb_info_lines(&planted_c4(PlantedC4State::Detonated, None));
+        assert_eq!(line_texts(&lines), vec!["炸弹安放在 B", "炸了"]);
+    }
+}
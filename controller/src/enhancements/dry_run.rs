@@ -0,0 +1,216 @@
+use std::{
+    collections::VecDeque,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+use valthrun_kernel_interface::MouseState;
+
+use super::Enhancement;
+use crate::{
+    i18n::tr,
+    settings::AppSettings,
+    Clock,
+    GameMemory,
+    MetricsClient,
+    UpdateContext,
+};
+
+/// Duration how long a recorded input will be visualized
+const EVENT_LIFETIME: Duration = Duration::from_millis(600);
+
+/// Time span of mouse movements which are summed up into the correction arrow
+const MOUSE_MOVE_WINDOW: Duration = Duration::from_millis(250);
+
+/// Maximum length of the correction arrow in pixels
+const MOUSE_MOVE_MAX_LENGTH: f32 = 150.0;
+
+const FIRE_MARKER_RADIUS: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy)]
+pub enum DryRunInput {
+    Fire,
+    MouseMove { x: i32, y: i32 },
+}
+
+/// Inputs which would have been sent to the game while the dry run mode is active
+pub struct DryRunInputLog {
+    events: VecDeque<(Instant, DryRunInput)>,
+}
+
+impl DryRunInputLog {
+    pub fn push(&mut self, time: Instant, input: DryRunInput) {
+        self.events.push_back((time, input));
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &(Instant, DryRunInput)> {
+        self.events.iter()
+    }
+
+    fn remove_expired(&mut self, now: Instant) {
+        while let Some((time, _)) = self.events.front() {
+            if now.duration_since(*time) < EVENT_LIFETIME {
+                break;
+            }
+
+            self.events.pop_front();
+        }
+    }
+}
+
+impl State for DryRunInputLog {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        Ok(Self {
+            events: Default::default(),
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+/// Game memory which records all mouse inputs into the `DryRunInputLog` instead of sending them to the game
+pub struct DryRunMemory<'a> {
+    inner: &'a dyn GameMemory,
+    states: &'a StateRegistry,
+    clock: &'a dyn Clock,
+}
+
+impl<'a> DryRunMemory<'a> {
+    pub fn new(inner: &'a dyn GameMemory, states: &'a StateRegistry, clock: &'a dyn Clock) -> Self {
+        Self {
+            inner,
+            states,
+            clock,
+        }
+    }
+}
+
+impl MetricsClient for DryRunMemory<'_> {
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
+        self.inner.add_metrics_record(record_type, record_payload)
+    }
+}
+
+impl GameMemory for DryRunMemory<'_> {
+    fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
+        let mut log = self.states.resolve_mut::<DryRunInputLog>(())?;
+        let now = self.clock.now();
+
+        for state in states {
+            if state.buttons[0] == Some(true) {
+                log.push(now, DryRunInput::Fire);
+            }
+
+            if state.last_x != 0 || state.last_y != 0 {
+                log.push(
+                    now,
+                    DryRunInput::MouseMove {
+                        x: state.last_x,
+                        y: state.last_y,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Visualizes the inputs recorded while the dry run mode is active
+pub struct DryRunVisualizer {
+    now: Instant,
+}
+
+impl DryRunVisualizer {
+    pub fn new() -> Self {
+        Self {
+            now: Instant::now(),
+        }
+    }
+}
+
+impl Enhancement for DryRunVisualizer {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        self.now = ctx.clock.now();
+
+        let mut log = ctx.states.resolve_mut::<DryRunInputLog>(())?;
+        log.remove_expired(self.now);
+        Ok(())
+    }
+
+    fn render(&self, states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.input_dry_run {
+            return Ok(());
+        }
+
+        let [screen_width, screen_height] = ui.io().display_size;
+        let center = [screen_width / 2.0, screen_height / 2.0];
+
+        let label = tr!("演练模式 (不会发送任何输入)").to_string();
+        let [label_width, _] = ui.calc_text_size(&label);
+        ui.set_cursor_pos([center[0] - label_width / 2.0, screen_height * 0.08]);
+        ui.text_colored([1.0, 0.76, 0.03, 1.0], &label);
+
+        let draw = ui.get_window_draw_list();
+        let log = states.resolve::<DryRunInputLog>(())?;
+
+        let mut mouse_move = [0.0f32; 2];
+        for (time, input) in log.events() {
+            let age = self.now.saturating_duration_since(*time);
+            match input {
+                DryRunInput::Fire => {
+                    /* the marker expands and fades out after the would-be shot */
+                    let progress = age.as_secs_f32() / EVENT_LIFETIME.as_secs_f32();
+                    draw.add_circle(
+                        center,
+                        FIRE_MARKER_RADIUS * (1.0 + progress),
+                        [1.0, 0.2, 0.2, 1.0 - progress.min(1.0)],
+                    )
+                    .thickness(2.0)
+                    .build();
+                }
+                DryRunInput::MouseMove { x, y } => {
+                    if age < MOUSE_MOVE_WINDOW {
+                        mouse_move[0] += *x as f32;
+                        mouse_move[1] += *y as f32;
+                    }
+                }
+            }
+        }
+
+        let length = (mouse_move[0] * mouse_move[0] + mouse_move[1] * mouse_move[1]).sqrt();
+        if length >= 1.0 {
+            let scale = length.min(MOUSE_MOVE_MAX_LENGTH) / length;
+            let end = [
+                center[0] + mouse_move[0] * scale,
+                center[1] + mouse_move[1] * scale,
+            ];
+            let color = [0.2, 0.8, 1.0, 0.9];
+            draw.add_line(center, end, color).thickness(2.0).build();
+
+            /* arrow head */
+            let direction = [mouse_move[0] / length, mouse_move[1] / length];
+            for side in [-1.0, 1.0] {
+                let head = [
+                    end[0] - direction[0] * 8.0 - direction[1] * 5.0 * side,
+                    end[1] - direction[1] * 8.0 + direction[0] * 5.0 * side,
+                ];
+                draw.add_line(end, head, color).thickness(2.0).build();
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use cs2::LocalPlayerFlash;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    i18n::tr,
+    settings::AppSettings,
+    utils::DrawListEx,
+    UpdateContext,
+};
+
+/// Radius of the countdown ring in pixels
+const RING_RADIUS: f32 = 24.0;
+const RING_THICKNESS: f32 = 4.0;
+const RING_SEGMENTS: usize = 48;
+
+/// Vertical position of the countdown ring (% of the screen height)
+const RING_OFFSET_Y: f32 = 0.35;
+
+/// Shows a countdown while the local player is flashed
+pub struct FlashIndicator;
+
+impl FlashIndicator {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Enhancement for FlashIndicator {
+    fn update(&mut self, _ctx: &UpdateContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn render(&self, states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.flash_indicator {
+            return Ok(());
+        }
+
+        let flash = states.resolve::<LocalPlayerFlash>(())?;
+        if !flash.is_flashed() {
+            return Ok(());
+        }
+
+        let [screen_width, screen_height] = ui.io().display_size;
+        let center = [screen_width / 2.0, screen_height * RING_OFFSET_Y];
+
+        let draw = ui.get_window_draw_list();
+        draw.add_circle(center, RING_RADIUS, [0.2, 0.2, 0.2, 0.6])
+            .num_segments(RING_SEGMENTS as u32)
+            .thickness(RING_THICKNESS)
+            .build();
+
+        /* the ring shrinks clockwise starting at the top */
+        let angle_start = -std::f32::consts::FRAC_PI_2;
+        draw.add_arc(
+            center,
+            RING_RADIUS,
+            angle_start,
+            angle_start + std::f32::consts::TAU * flash.remaining_fraction(),
+            [1.0, 1.0, 1.0, 0.9],
+            RING_THICKNESS,
+        );
+
+        let text = format!("{:.1}s", flash.remaining_time);
+        let [text_width, text_height] = ui.calc_text_size(&text);
+        ui.set_cursor_pos([center[0] - text_width / 2.0, center[1] - text_height / 2.0]);
+        ui.text(&text);
+
+        let label = tr!("致盲中").to_string();
+        let [label_width, _] = ui.calc_text_size(&label);
+        ui.set_cursor_pos([
+            center[0] - label_width / 2.0,
+            center[1] + RING_RADIUS + RING_THICKNESS + 2.0,
+        ]);
+        ui.text(&label);
+
+        Ok(())
+    }
+}
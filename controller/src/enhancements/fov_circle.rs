@@ -0,0 +1,112 @@
+use cs2::CS2Model;
+use imgui::ImColor32;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    view::{
+        AimTarget,
+        ViewController,
+    },
+    UpdateContext,
+};
+
+/// Distance of the target indicator from the targets bounding box in pixels
+const TARGET_INDICATOR_PADDING: f32 = 4.0;
+
+/// Length of the target indicator corners (% of the box size)
+const TARGET_INDICATOR_CORNER: f32 = 0.25;
+
+/// Draws the aim FOV around the crosshair and highlights the player
+/// which is currently targeted by the aim features.
+pub struct FovCircle;
+
+impl FovCircle {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Enhancement for FovCircle {
+    fn update(&mut self, _ctx: &UpdateContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn render(&self, states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        let view = states.resolve::<ViewController>(())?;
+        let draw = ui.get_window_draw_list();
+
+        if settings.fov_circle {
+            let [screen_width, screen_height] = ui.io().display_size;
+            draw.add_circle(
+                [screen_width / 2.0, screen_height / 2.0],
+                view.fov_radius(settings.aim_fov),
+                settings.fov_circle_color.as_f32(),
+            )
+            .num_segments(64)
+            .thickness(1.0)
+            .build();
+        }
+
+        if !settings.target_indicator && !settings.target_snapline {
+            return Ok(());
+        }
+
+        let aim_target = states.resolve::<AimTarget>(())?;
+        let target = match &aim_target.target {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+
+        let color = ImColor32::from(settings.target_indicator_color.as_f32());
+        if settings.target_snapline {
+            let [screen_width, screen_height] = ui.io().display_size;
+            draw.add_line(
+                [screen_width / 2.0, screen_height],
+                [target.screen_position.x, target.screen_position.y],
+                color,
+            )
+            .thickness(1.0)
+            .build();
+        }
+
+        if settings.target_indicator {
+            let model = states.resolve::<CS2Model>(target.model_address)?;
+            let bounds = view.calculate_box_2d(
+                &(model.vhull_min + target.position),
+                &(model.vhull_max + target.position),
+            );
+
+            if let Some((vmin, vmax)) = bounds {
+                let [min_x, min_y] = [
+                    vmin.x - TARGET_INDICATOR_PADDING,
+                    vmin.y - TARGET_INDICATOR_PADDING,
+                ];
+                let [max_x, max_y] = [
+                    vmax.x + TARGET_INDICATOR_PADDING,
+                    vmax.y + TARGET_INDICATOR_PADDING,
+                ];
+                let corner_x = (max_x - min_x) * TARGET_INDICATOR_CORNER;
+                let corner_y = (max_y - min_y) * TARGET_INDICATOR_CORNER;
+
+                for ([x, y], [dir_x, dir_y]) in [
+                    ([min_x, min_y], [1.0, 1.0]),
+                    ([max_x, min_y], [-1.0, 1.0]),
+                    ([min_x, max_y], [1.0, -1.0]),
+                    ([max_x, max_y], [-1.0, -1.0]),
+                ] {
+                    draw.add_line([x, y], [x + corner_x * dir_x, y], color)
+                        .thickness(2.0)
+                        .build();
+                    draw.add_line([x, y], [x, y + corner_y * dir_y], color)
+                        .thickness(2.0)
+                        .build();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
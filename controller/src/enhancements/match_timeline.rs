@@ -0,0 +1,381 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    path::PathBuf,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+use cs2::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    CurrentMapState,
+    EntitySystem,
+    GameRules,
+    PlantedC4,
+    PlantedC4State,
+    PlayerPawnState,
+    RoundPhase,
+    WeaponId,
+};
+use serde::Serialize;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    settings::{
+        get_settings_path,
+        AppSettings,
+    },
+    UpdateContext,
+};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TimelinePlayer {
+    pub name: String,
+    pub team: u8,
+    pub health: i32,
+    pub position: [f32; 3],
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    RoundStart,
+    RoundEnd,
+    PlayerDeath {
+        player: String,
+        team: u8,
+        /// Last known position of the player
+        position: [f32; 3],
+    },
+    UtilityUsed {
+        player: String,
+        team: u8,
+        grenade: String,
+        position: [f32; 3],
+    },
+    BombPlanted {
+        site: String,
+    },
+    BombDefused,
+    BombDetonated,
+    Positions {
+        players: Vec<TimelinePlayer>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineEvent {
+    /// Seconds since the recording has been started
+    pub time: f32,
+
+    #[serde(flatten)]
+    pub kind: TimelineEventKind,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatchTimeline {
+    pub map: String,
+    pub started_at: String,
+    pub events: Vec<TimelineEvent>,
+}
+
+struct TrackedPlayer {
+    info: TimelinePlayer,
+    alive: bool,
+    grenades: Vec<WeaponId>,
+}
+
+struct MatchRecording {
+    timeline: MatchTimeline,
+    file_path: PathBuf,
+    started: Instant,
+
+    players: BTreeMap<u32, TrackedPlayer>,
+    last_positions: Option<Instant>,
+    last_phase: Option<RoundPhase>,
+    bomb_state: PlantedC4State,
+}
+
+impl MatchRecording {
+    fn new(map: String, now: Instant) -> anyhow::Result<Self> {
+        let started_at = chrono::Local::now();
+        let file_name = format!(
+            "{}_{}.json",
+            started_at.format("%Y%m%d-%H%M%S"),
+            map.replace(|c: char| !c.is_ascii_alphanumeric() && c != '_', "_")
+        );
+
+        Ok(Self {
+            timeline: MatchTimeline {
+                map,
+                started_at: started_at.to_rfc3339(),
+                events: Vec::new(),
+            },
+            file_path: get_match_timeline_directory()?.join(file_name),
+            started: now,
+
+            players: Default::default(),
+            last_positions: None,
+            last_phase: None,
+            bomb_state: PlantedC4State::NotPlanted,
+        })
+    }
+
+    fn push_event(&mut self, now: Instant, kind: TimelineEventKind) {
+        self.timeline.events.push(TimelineEvent {
+            time: now.duration_since(self.started).as_secs_f32(),
+            kind,
+        });
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        if let Some(directory) = self.file_path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        let file = File::create(&self.file_path).with_context(|| {
+            format!(
+                "failed to create match timeline at {}",
+                self.file_path.to_string_lossy()
+            )
+        })?;
+        serde_json::to_writer_pretty(file, &self.timeline)?;
+        Ok(())
+    }
+
+    fn update_players(&mut self, states: &StateRegistry, now: Instant) -> anyhow::Result<()> {
+        let entities = states.resolve::<EntitySystem>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+
+        for entity_identity in entities.all_identities() {
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "C_CSPlayerPawn")
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let entity_index = entity_identity.handle::<()>()?.get_entity_index();
+            let pawn = match states.resolve::<PlayerPawnState>(entity_index) {
+                Ok(pawn) => pawn,
+                Err(_) => continue,
+            };
+
+            let pawn = match &*pawn {
+                PlayerPawnState::Alive(pawn) => pawn,
+                PlayerPawnState::Dead => {
+                    if let Some(player) = self.players.get_mut(&entity_index) {
+                        if player.alive {
+                            player.alive = false;
+
+                            let kind = TimelineEventKind::PlayerDeath {
+                                player: player.info.name.clone(),
+                                team: player.info.team,
+                                position: player.info.position,
+                            };
+                            self.push_event(now, kind);
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            let info = TimelinePlayer {
+                name: pawn.player_name.clone(),
+                team: pawn.team_id,
+                health: pawn.player_health,
+                position: [pawn.position.x, pawn.position.y, pawn.position.z],
+            };
+
+            let mut used_grenades = Vec::new();
+            if let Some(player) = self.players.get(&entity_index) {
+                if player.alive {
+                    /* grenades which are no longer in the inventory have been thrown */
+                    let mut remaining = pawn.grenades.clone();
+                    for grenade in player.grenades.iter() {
+                        match remaining.iter().position(|value| value == grenade) {
+                            Some(index) => {
+                                remaining.remove(index);
+                            }
+                            None => used_grenades.push(*grenade),
+                        }
+                    }
+                }
+            }
+
+            for grenade in used_grenades {
+                self.push_event(
+                    now,
+                    TimelineEventKind::UtilityUsed {
+                        player: info.name.clone(),
+                        team: info.team,
+                        grenade: grenade.name().to_string(),
+                        position: info.position,
+                    },
+                );
+            }
+
+            self.players.insert(
+                entity_index,
+                TrackedPlayer {
+                    info,
+                    alive: true,
+                    grenades: pawn.grenades.clone(),
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn update_bomb(&mut self, states: &StateRegistry, now: Instant) -> anyhow::Result<()> {
+        let bomb = states.resolve::<PlantedC4>(())?;
+        let event = match (&self.bomb_state, &bomb.state) {
+            (PlantedC4State::NotPlanted, PlantedC4State::Active { .. }) => {
+                Some(TimelineEventKind::BombPlanted {
+                    site: if bomb.bomb_site == 0 { "A" } else { "B" }.to_string(),
+                })
+            }
+            (PlantedC4State::Active { .. }, PlantedC4State::Defused) => {
+                Some(TimelineEventKind::BombDefused)
+            }
+            (PlantedC4State::Active { .. }, PlantedC4State::Detonated) => {
+                Some(TimelineEventKind::BombDetonated)
+            }
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            self.push_event(now, event);
+        }
+
+        self.bomb_state = bomb.state.clone();
+        Ok(())
+    }
+}
+
+pub fn get_match_timeline_directory() -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    let base_dir = settings_path
+        .parent()
+        .context("could not get config directory")?;
+
+    Ok(base_dir.join("timelines"))
+}
+
+/// Records significant match events into a JSON file per match for later review
+pub struct MatchTimelineRecorder {
+    recording: Option<MatchRecording>,
+}
+
+impl MatchTimelineRecorder {
+    pub fn new() -> Self {
+        Self { recording: None }
+    }
+
+    fn finish_recording(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            if let Err(error) = recording.save() {
+                log::warn!("保存比赛时间线失败: {:#}", error);
+            } else {
+                log::info!(
+                    "比赛时间线已保存到 {}",
+                    recording.file_path.to_string_lossy()
+                );
+            }
+        }
+    }
+}
+
+impl Enhancement for MatchTimelineRecorder {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.match_timeline {
+            self.finish_recording();
+            return Ok(());
+        }
+
+        let current_map = ctx.states.resolve::<CurrentMapState>(())?;
+        let current_map = match &current_map.current_map {
+            Some(map) if map != "<empty>" => map.clone(),
+            _ => {
+                self.finish_recording();
+                return Ok(());
+            }
+        };
+
+        if self
+            .recording
+            .as_ref()
+            .map(|recording| recording.timeline.map != current_map)
+            .unwrap_or(false)
+        {
+            self.finish_recording();
+        }
+
+        let now = ctx.clock.now();
+        if self.recording.is_none() {
+            self.recording = Some(MatchRecording::new(current_map, now)?);
+        }
+        let recording = self.recording.as_mut().context("missing match recording")?;
+
+        recording.update_players(ctx.states, now)?;
+        recording.update_bomb(ctx.states, now)?;
+
+        let position_interval =
+            Duration::from_secs(settings.match_timeline_position_interval as u64);
+        if recording
+            .last_positions
+            .map(|last| now.duration_since(last) >= position_interval)
+            .unwrap_or(true)
+        {
+            recording.last_positions = Some(now);
+
+            let players = recording
+                .players
+                .values()
+                .filter(|player| player.alive)
+                .map(|player| player.info.clone())
+                .collect::<Vec<_>>();
+            if !players.is_empty() {
+                recording.push_event(now, TimelineEventKind::Positions { players });
+            }
+        }
+
+        let phase = ctx
+            .states
+            .resolve::<GameRules>(())
+            .ok()
+            .map(|rules| rules.phase);
+        if phase != recording.last_phase {
+            match phase {
+                Some(RoundPhase::FreezeTime) => {
+                    recording.push_event(now, TimelineEventKind::RoundStart)
+                }
+                Some(RoundPhase::RoundOver) => {
+                    recording.push_event(now, TimelineEventKind::RoundEnd);
+
+                    /* save after every round so nothing gets lost if the controller is closed */
+                    if let Err(error) = recording.save() {
+                        log::warn!("保存比赛时间线失败: {:#}", error);
+                    }
+                }
+                _ => {}
+            }
+
+            recording.last_phase = phase;
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, _states: &StateRegistry, _ui: &imgui::Ui) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
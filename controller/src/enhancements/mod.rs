@@ -1,6 +1,15 @@
-use crate::settings::AppSettings;
+use crate::settings::{
+    AppSettings,
+    HotKeyAction,
+};
 
 pub trait Enhancement {
+    /// Name shown within the performance statistics
+    fn name(&self) -> &'static str {
+        let type_name = std::any::type_name::<Self>();
+        type_name.rsplit("::").next().unwrap_or(type_name)
+    }
+
     /* FIXME: Remove the update method! */
     fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()>;
     fn update_settings(
@@ -11,6 +20,29 @@ pub trait Enhancement {
         Ok(false)
     }
 
+    /// Actions of this enhancement which can be bound to a hotkey
+    fn hotkey_actions(&self) -> &'static [HotKeyAction] {
+        &[]
+    }
+
+    /// Called when the hotkey of one of the enhancements actions has been pressed.
+    /// Returns true if the settings have been changed.
+    fn on_hotkey_pressed(&mut self, _action: &HotKeyAction, _settings: &mut AppSettings) -> bool {
+        false
+    }
+
+    /// Enhancements which send inputs to the game.
+    /// They will not be updated while there is no alive local player.
+    fn injects_input(&self) -> bool {
+        false
+    }
+
+    /// Called once when the inputs get suspended.
+    /// Release all pressed buttons and reset the input state here.
+    fn suspend_input(&mut self, _ctx: &UpdateContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     fn render(&self, states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()>;
     fn render_debug_window(&mut self, _states: &StateRegistry, _ui: &imgui::Ui) {}
 }
@@ -29,6 +61,42 @@ pub use spectators_list::*;
 
 mod aim;
 pub use aim::*;
+
+mod aim_warning;
+pub use aim_warning::*;
+
+mod session_timer;
+pub use session_timer::*;
+
+mod movement_hud;
+pub use movement_hud::*;
+
+mod round_info;
+pub use round_info::*;
+
+mod round_stats;
+pub use round_stats::*;
+
+mod proximity_alert;
+pub use proximity_alert::*;
+
+mod scoreboard;
+pub use scoreboard::*;
+
+mod fov_circle;
+pub use fov_circle::*;
+
+mod recoil_control;
+pub use recoil_control::*;
+
+mod flash_indicator;
+pub use flash_indicator::*;
+
+mod dry_run;
+pub use dry_run::*;
+
+mod match_timeline;
+pub use match_timeline::*;
 use utils_state::StateRegistry;
 
 use crate::UpdateContext;
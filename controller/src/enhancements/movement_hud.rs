@@ -0,0 +1,184 @@
+use anyhow::Context;
+use cs2::{
+    EntitySystem,
+    PlayerPawnState,
+};
+use imgui::Key;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    i18n::{
+        self,
+        tr,
+    },
+    settings::AppSettings,
+    UpdateContext,
+};
+
+struct MovementInfo {
+    position: nalgebra::Vector3<f32>,
+    velocity: nalgebra::Vector3<f32>,
+    pitch: f32,
+    yaw: f32,
+}
+
+/// Keys displayed within the keypress overlay
+const MOVEMENT_KEYS: [(Key, &'static str); 6] = [
+    (Key::W, "W"),
+    (Key::A, "A"),
+    (Key::S, "S"),
+    (Key::D, "D"),
+    (Key::Space, "跳"),
+    (Key::LeftCtrl, "蹲"),
+];
+
+pub struct MovementHud {
+    info: Option<MovementInfo>,
+    keys_down: [bool; MOVEMENT_KEYS.len()],
+}
+
+impl MovementHud {
+    pub fn new() -> Self {
+        Self {
+            info: None,
+            keys_down: Default::default(),
+        }
+    }
+
+    fn read_movement_info(&self, ctx: &UpdateContext) -> anyhow::Result<Option<MovementInfo>> {
+        let entities = ctx.states.resolve::<EntitySystem>(())?;
+        let local_controller = entities.get_local_player_controller()?;
+        if local_controller.is_null()? {
+            return Ok(None);
+        }
+
+        let pawn_handle = local_controller.reference_schema()?.m_hPlayerPawn()?;
+        let pawn_info = ctx
+            .states
+            .resolve::<PlayerPawnState>(pawn_handle.get_entity_index())?;
+        let pawn_info = match &*pawn_info {
+            PlayerPawnState::Alive(info) => info,
+            PlayerPawnState::Dead => return Ok(None),
+        };
+
+        let local_pawn = entities
+            .get_by_handle(&pawn_handle)?
+            .context("missing local player pawn")?
+            .entity()?
+            .reference_schema()?;
+
+        Ok(Some(MovementInfo {
+            position: pawn_info.position,
+            velocity: nalgebra::Vector3::from_row_slice(&local_pawn.m_vecAbsVelocity()?),
+            pitch: pawn_info.pitch,
+            yaw: pawn_info.rotation,
+        }))
+    }
+}
+
+impl Enhancement for MovementHud {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.movement_hud {
+            self.info = None;
+            return Ok(());
+        }
+
+        self.info = self.read_movement_info(ctx)?;
+        for (index, (key, _)) in MOVEMENT_KEYS.iter().enumerate() {
+            self.keys_down[index] = ctx.input.is_key_down(*key);
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, _states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let info = match &self.info {
+            Some(info) => info,
+            None => return Ok(()),
+        };
+
+        let horizontal_speed = info.velocity.xy().norm();
+        let lines = [
+            format!(
+                "{}: {:.0} u/s ({} {:.0})",
+                tr!("速度"),
+                horizontal_speed,
+                tr!("垂直"),
+                info.velocity.z
+            ),
+            format!(
+                "{}: {:.1} {:.1} {:.1}",
+                tr!("位置"),
+                info.position.x,
+                info.position.y,
+                info.position.z
+            ),
+            format!("{}: {:.2} {:.2}", tr!("视角"), info.pitch, info.yaw),
+        ];
+
+        let [screen_width, screen_height] = ui.io().display_size;
+        let line_height = ui.text_line_height_with_spacing();
+        let mut offset_y = screen_height * 0.7;
+        for line in lines.iter() {
+            let [text_width, _] = ui.calc_text_size(line);
+            ui.set_cursor_pos([(screen_width - text_width) / 2.0, offset_y]);
+            ui.text(line);
+            offset_y += line_height;
+        }
+
+        /* keypress overlay */
+        const KEY_SIZE: f32 = 28.0;
+        const KEY_SPACING: f32 = 4.0;
+
+        let draw = ui.get_window_draw_list();
+        let overlay_width = KEY_SIZE * 4.0 + KEY_SPACING * 3.0;
+        let overlay_x = (screen_width - overlay_width) / 2.0;
+        let overlay_y = offset_y + KEY_SPACING;
+
+        /* W on top, A S D below, jump and duck in a third row */
+        let key_positions = [
+            (1.0, 0.0, 1.0),
+            (0.0, 1.0, 1.0),
+            (1.0, 1.0, 1.0),
+            (2.0, 1.0, 1.0),
+            (0.0, 2.0, 2.0),
+            (2.0, 2.0, 2.0),
+        ];
+
+        for (index, (column, row, width)) in key_positions.into_iter().enumerate() {
+            let key_width = KEY_SIZE * width + KEY_SPACING * (width - 1.0);
+            let x = overlay_x + column * (KEY_SIZE + KEY_SPACING);
+            let y = overlay_y + row * (KEY_SIZE + KEY_SPACING);
+
+            let color = if self.keys_down[index] {
+                [1.0, 1.0, 1.0, 0.8]
+            } else {
+                [0.2, 0.2, 0.2, 0.6]
+            };
+            draw.add_rect([x, y], [x + key_width, y + KEY_SIZE], color)
+                .filled(true)
+                .rounding(3.0)
+                .build();
+
+            let label = i18n::translate(MOVEMENT_KEYS[index].1);
+            let [label_width, label_height] = ui.calc_text_size(&label);
+            let label_color = if self.keys_down[index] {
+                [0.0, 0.0, 0.0, 1.0]
+            } else {
+                [1.0, 1.0, 1.0, 1.0]
+            };
+            draw.add_text(
+                [
+                    x + (key_width - label_width) / 2.0,
+                    y + (KEY_SIZE - label_height) / 2.0,
+                ],
+                label_color,
+                label,
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,11 @@
+use std::{
+    collections::BTreeMap,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
 use cs2::{
     BoneFlags,
     CEntityIdentityEx,
@@ -5,43 +13,154 @@ use cs2::{
     ClassNameCache,
     EntitySystem,
     LocalCameraControllerTarget,
+    LocalPlayerFlash,
     PlayerPawnInfo,
     PlayerPawnState,
+    WeaponId,
 };
 use cs2_schema_generated::cs2::client::C_CSPlayerPawn;
-use imgui::ImColor32;
+use imgui::{
+    FontId,
+    ImColor32,
+};
 use obfstr::obfstr;
 
 use super::Enhancement;
 use crate::{
+    i18n::tr,
     settings::{
         AppSettings,
         EspBoxType,
+        EspColor,
         EspConfig,
         EspHealthBar,
         EspPlayerSettings,
+        EspPriority,
         EspSelector,
+        EspSkeletonBoneGroup,
+        EspTextEffect,
+        EspTextStyle,
         EspTracePosition,
+        HotKeyAction,
     },
+    utils::ImguiTextEx,
     view::{
         KeyToggle,
+        KeyToggleTimings,
         ViewController,
     },
+    AppFontsHandle,
+    MetricsClient,
 };
 
+/// Duration of the health bar flash after a player took damage
+const DAMAGE_FLASH_DURATION: Duration = Duration::from_millis(500);
+
+/// Health lost by a player which will be highlighted within the health bar
+struct DamageFlash {
+    /// Health of the player before taking the damage
+    health_before: i32,
+    timestamp: Instant,
+
+    /// Animation progress from 0.0 (just took damage) to 1.0 (finished)
+    progress: f32,
+}
+
+struct PlayerHealthState {
+    last_health: i32,
+    damage_flash: Option<DamageFlash>,
+}
+
 pub struct PlayerESP {
     toggle: KeyToggle,
     players: Vec<PlayerPawnInfo>,
     local_team_id: u8,
+
+    /// Health tracking of the players by their controller entity id
+    player_health: BTreeMap<u32, PlayerHealthState>,
+
+    fonts: AppFontsHandle,
 }
 
 impl PlayerESP {
-    pub fn new() -> Self {
+    pub fn new(fonts: AppFontsHandle) -> Self {
         PlayerESP {
             toggle: KeyToggle::new(),
             players: Default::default(),
             local_team_id: 0,
+
+            player_health: Default::default(),
+
+            fonts,
+        }
+    }
+
+    /// Players collected within the last update (excluding the local player)
+    pub fn players(&self) -> &[PlayerPawnInfo] {
+        &self.players
+    }
+
+    pub fn local_team_id(&self) -> u8 {
+        self.local_team_id
+    }
+
+    fn update_player_health(&mut self, now: Instant) {
+        let mut player_health = BTreeMap::new();
+        for player in self.players.iter() {
+            let mut state = self
+                .player_health
+                .remove(&player.controller_entity_id)
+                .unwrap_or(PlayerHealthState {
+                    last_health: player.player_health,
+                    damage_flash: None,
+                });
+
+            if player.player_health < state.last_health {
+                /* extend an active flash so consecutive hits are shown as one segment */
+                let health_before = state
+                    .damage_flash
+                    .as_ref()
+                    .map(|flash| flash.health_before)
+                    .unwrap_or(state.last_health);
+
+                state.damage_flash = Some(DamageFlash {
+                    health_before,
+                    timestamp: now,
+                    progress: 0.0,
+                });
+            }
+            state.last_health = player.player_health;
+
+            if let Some(flash) = &mut state.damage_flash {
+                flash.progress = now.duration_since(flash.timestamp).as_secs_f32()
+                    / DAMAGE_FLASH_DURATION.as_secs_f32();
+                if flash.progress >= 1.0 {
+                    state.damage_flash = None;
+                }
+            }
+
+            player_health.insert(player.controller_entity_id, state);
+        }
+
+        /* players which are no longer tracked (e.g. died) will be dropped */
+        self.player_health = player_health;
+    }
+
+    /// Score how dangerous a player is for the local player (lower is more dangerous).
+    /// Enemies looking towards the camera are the most dangerous, team mates are not dangerous at all.
+    fn danger_score(
+        &self,
+        target: &PlayerPawnInfo,
+        view_world_position: &nalgebra::Vector3<f32>,
+    ) -> f32 {
+        if target.team_id == self.local_team_id {
+            return f32::MAX;
         }
+
+        let delta = view_world_position - target.position;
+        let yaw_to_view = delta.y.atan2(delta.x).to_degrees();
+        let yaw_difference = (yaw_to_view - target.rotation).rem_euclid(360.0);
+        yaw_difference.min(360.0 - yaw_difference)
     }
 
     fn resolve_esp_player_config<'a>(
@@ -85,6 +204,7 @@ struct PlayerInfoLayout<'a> {
     vmax: nalgebra::Vector2<f32>,
 
     line_count: usize,
+    line_offset: f32,
     font_scale: f32,
 
     has_2d_box: bool,
@@ -101,7 +221,6 @@ impl<'a> PlayerInfoLayout<'a> {
     ) -> Self {
         let target_scale_raw = (vmax.y - vmin.y) / screen_bounds.y * 8.0;
         let target_scale = target_scale_raw.clamp(0.5, 1.25);
-        ui.set_window_font_scale(target_scale);
 
         Self {
             ui,
@@ -111,14 +230,17 @@ impl<'a> PlayerInfoLayout<'a> {
             vmax,
 
             line_count: 0,
+            line_offset: 0.0,
             font_scale: target_scale,
 
             has_2d_box,
         }
     }
 
-    pub fn add_line(&mut self, color: impl Into<ImColor32>, text: &str) {
-        let [text_width, _] = self.ui.calc_text_size(text);
+    pub fn add_line(&mut self, color: impl Into<ImColor32>, text: &str, style: &EspTextStyle) {
+        let color = color.into();
+        let font_size = self.ui.current_font_size() * self.font_scale * style.font_size;
+        let [text_width, text_height] = self.ui.calc_text_size_with_font_size(text, font_size);
 
         let mut pos = if self.has_2d_box {
             let mut pos = self.vmin;
@@ -130,32 +252,659 @@ impl<'a> PlayerInfoLayout<'a> {
             pos.x -= text_width / 2.0;
             pos
         };
-        pos.y += self.line_count as f32 * self.font_scale * (self.ui.text_line_height())
-            + 4.0 * self.line_count as f32;
+        pos.y += self.line_offset + 4.0 * self.line_count as f32;
+
+        if style.background {
+            self.draw
+                .add_rect(
+                    [pos.x - 2.0, pos.y - 1.0],
+                    [pos.x + text_width + 2.0, pos.y + text_height + 1.0],
+                    [0.0, 0.0, 0.0, 0.5],
+                )
+                .filled(true)
+                .rounding(2.0)
+                .build();
+        }
 
-        self.draw.add_text([pos.x, pos.y], color, text);
+        let shadow_color = ImColor32::from_rgba_f32s(0.0, 0.0, 0.0, color.to_rgba_f32s()[3]);
+        /* outlines and shadows grow with the text so they stay visible on large fonts */
+        let effect_size = (font_size / 18.0).max(1.0).round();
+        match style.effect {
+            EspTextEffect::None => {
+                self.ui
+                    .add_text_with_font_size([pos.x, pos.y], color, text, font_size);
+            }
+            EspTextEffect::Bold => {
+                self.ui
+                    .add_text_with_font_size([pos.x + 1.0, pos.y], color, text, font_size);
+                self.ui
+                    .add_text_with_font_size([pos.x, pos.y], color, text, font_size);
+            }
+            EspTextEffect::Outline => {
+                self.ui.add_text_outlined(
+                    [pos.x, pos.y],
+                    color,
+                    shadow_color,
+                    text,
+                    font_size,
+                    effect_size,
+                );
+            }
+            EspTextEffect::Shadow => {
+                self.ui.add_text_shadowed(
+                    [pos.x, pos.y],
+                    color,
+                    shadow_color,
+                    text,
+                    font_size,
+                    effect_size,
+                );
+            }
+        }
+
+        self.line_offset += text_height;
         self.line_count += 1;
     }
 }
 
-impl Drop for PlayerInfoLayout<'_> {
-    fn drop(&mut self) {
-        self.ui.set_window_font_scale(1.0);
+/// Codepoint of the weapon icon within `resources/weapon-icons.ttf`.
+/// The font contains one icon per weapon class, see `resources/weapon-icons.py`.
+fn weapon_icon_codepoint(weapon: WeaponId) -> Option<u32> {
+    const PISTOL: u32 = 0xE000;
+    const SMG: u32 = 0xE001;
+    const RIFLE: u32 = 0xE002;
+    const SNIPER_RIFLE: u32 = 0xE003;
+    const SHOTGUN: u32 = 0xE004;
+    const MACHINE_GUN: u32 = 0xE005;
+    const KNIFE: u32 = 0xE006;
+    const HE_GRENADE: u32 = 0xE007;
+    const FLASHBANG: u32 = 0xE008;
+    const SMOKE_GRENADE: u32 = 0xE009;
+    const MOLOTOV: u32 = 0xE00A;
+    const DECOY: u32 = 0xE00B;
+    const C4: u32 = 0xE00C;
+    const TASER: u32 = 0xE00D;
+    const HEALTHSHOT: u32 = 0xE00E;
+
+    let codepoint = match weapon {
+        WeaponId::Unknown => return None,
+
+        WeaponId::Deagle
+        | WeaponId::Elite
+        | WeaponId::FiveSeven
+        | WeaponId::Glock
+        | WeaponId::Tec9
+        | WeaponId::HKP200
+        | WeaponId::P250
+        | WeaponId::USPS
+        | WeaponId::CZ75a
+        | WeaponId::Revolver => PISTOL,
+
+        WeaponId::Mac10
+        | WeaponId::P90
+        | WeaponId::MP5SD
+        | WeaponId::Ump45
+        | WeaponId::Bizon
+        | WeaponId::MP7
+        | WeaponId::MP9 => SMG,
+
+        WeaponId::Ak47
+        | WeaponId::Aug
+        | WeaponId::Famas
+        | WeaponId::Galilar
+        | WeaponId::M4A4
+        | WeaponId::M4A1Silencer
+        | WeaponId::Sg553 => RIFLE,
+
+        WeaponId::AWP | WeaponId::G3SG1 | WeaponId::Scar20 | WeaponId::Ssg08 => SNIPER_RIFLE,
+        WeaponId::XM1014 | WeaponId::Mag7 | WeaponId::SawedOff | WeaponId::Nova => SHOTGUN,
+        WeaponId::M249 | WeaponId::Negev => MACHINE_GUN,
+
+        WeaponId::Knife
+        | WeaponId::KnifeT
+        | WeaponId::KnifeBayonet
+        | WeaponId::KnifesClassic
+        | WeaponId::KnifeFlip
+        | WeaponId::KnifeGut
+        | WeaponId::KnifeKarambit
+        | WeaponId::KnifeM9Bayonet
+        | WeaponId::KnifeTactical
+        | WeaponId::KnifeFalchion
+        | WeaponId::KnifeSurvivalBowie
+        | WeaponId::KnifeButterfly
+        | WeaponId::KnifePush
+        | WeaponId::KnifeCord
+        | WeaponId::KnifeSurvival
+        | WeaponId::KnifeUrsus
+        | WeaponId::KnifesNavaja
+        | WeaponId::KnifesNomad
+        | WeaponId::KnifesStiletto
+        | WeaponId::KnifesTalon
+        | WeaponId::KnifesSkeleton => KNIFE,
+
+        WeaponId::HZGranade => HE_GRENADE,
+        WeaponId::Flashbang => FLASHBANG,
+        WeaponId::SmokeGranade => SMOKE_GRENADE,
+        WeaponId::Molotov | WeaponId::Incendiary => MOLOTOV,
+        WeaponId::Decoy => DECOY,
+        WeaponId::C4 => C4,
+        WeaponId::Taser => TASER,
+        WeaponId::Healthshot => HEALTHSHOT,
+    };
+
+    Some(codepoint)
+}
+
+fn weapon_icon(fonts: &AppFontsHandle, weapon: WeaponId) -> Option<(FontId, char)> {
+    let font = fonts.borrow().as_ref()?.weapon_icons;
+    let icon = char::from_u32(weapon_icon_codepoint(weapon)?)?;
+    Some((font, icon))
+}
+
+/// Armor line of the player info
+fn armor_text(armor: i32, has_helmet: bool) -> String {
+    if has_helmet {
+        format!("{} AP + {}", armor, tr!("头盔"))
+    } else {
+        format!("{} AP", armor)
     }
 }
 
 const HEALTH_BAR_MAX_HEALTH: f32 = 100.0;
 const HEALTH_BAR_BORDER_WIDTH: f32 = 1.0;
+const ARMOR_BAR_MAX_ARMOR: f32 = 100.0;
+
+/// Calculate the bounds (x, y, width, height) of a bar attached to the players 2D box.
+/// The offset moves the bar further away from the box.
+fn calculate_bar_bounds(
+    position: EspHealthBar,
+    vmin: &nalgebra::Vector2<f32>,
+    vmax: &nalgebra::Vector2<f32>,
+    box_width: f32,
+    bar_width: f32,
+    offset: f32,
+) -> Option<[f32; 4]> {
+    match position {
+        EspHealthBar::None => None,
+        EspHealthBar::Left => {
+            let xoffset = vmin.x - box_width / 2.0 - bar_width - offset;
+
+            Some([
+                xoffset,
+                vmin.y - box_width / 2.0,
+                bar_width,
+                vmax.y - vmin.y + box_width,
+            ])
+        }
+        EspHealthBar::Right => {
+            let xoffset = vmax.x + box_width / 2.0 + offset;
+
+            Some([
+                xoffset,
+                vmin.y - box_width / 2.0,
+                bar_width,
+                vmax.y - vmin.y + box_width,
+            ])
+        }
+        EspHealthBar::Top => {
+            let yoffset = vmin.y - box_width / 2.0 - bar_width - offset;
+
+            Some([
+                vmin.x - box_width / 2.0,
+                yoffset,
+                vmax.x - vmin.x + box_width,
+                bar_width,
+            ])
+        }
+        EspHealthBar::Bottom => {
+            let yoffset = vmax.y + box_width / 2.0 + offset;
+
+            Some([
+                vmin.x - box_width / 2.0,
+                yoffset,
+                vmax.x - vmin.x + box_width,
+                bar_width,
+            ])
+        }
+    }
+}
+
+const BAR_BORDER_WIDTH: f32 = 1.0;
+
+/// Bounds of the bar content without the border
+fn bar_inner_bounds([box_x, box_y, box_width, box_height]: [f32; 4]) -> [f32; 4] {
+    [
+        box_x + BAR_BORDER_WIDTH / 2.0 + 1.0,
+        box_y + BAR_BORDER_WIDTH / 2.0 + 1.0,
+        box_width - BAR_BORDER_WIDTH - 2.0,
+        box_height - BAR_BORDER_WIDTH - 2.0,
+    ]
+}
+
+/// Highlight the segment between the values `from` and `to` of a bar drawn with `draw_bar`
+fn draw_bar_segment(
+    draw: &imgui::DrawListMut,
+    bounds: [f32; 4],
+    from: f32,
+    to: f32,
+    color: [f32; 4],
+) {
+    let [box_x, box_y, box_width, box_height] = bar_inner_bounds(bounds);
+    if box_width < box_height {
+        /* vertical */
+        draw.add_rect(
+            [box_x, box_y + (1.0 - to) * box_height],
+            [box_x + box_width, box_y + (1.0 - from) * box_height],
+            color,
+        )
+        .filled(true)
+        .build();
+    } else {
+        /* horizontal */
+        draw.add_rect(
+            [box_x + (1.0 - to) * box_width, box_y],
+            [box_x + (1.0 - from) * box_width, box_y + box_height],
+            color,
+        )
+        .filled(true)
+        .build();
+    }
+}
+
+/// Draw a bordered bar which is filled relative to value (0.0 - 1.0).
+fn draw_bar(
+    draw: &imgui::DrawListMut,
+    [mut box_x, mut box_y, mut box_width, mut box_height]: [f32; 4],
+    value: f32,
+    fill_color: [f32; 4],
+    empty_color: [f32; 4],
+) {
+    draw.add_rect(
+        [
+            box_x + BAR_BORDER_WIDTH / 2.0,
+            box_y + BAR_BORDER_WIDTH / 2.0,
+        ],
+        [
+            box_x + box_width - BAR_BORDER_WIDTH / 2.0,
+            box_y + box_height - BAR_BORDER_WIDTH / 2.0,
+        ],
+        [0.0, 0.0, 0.0, 1.0],
+    )
+    .filled(false)
+    .thickness(BAR_BORDER_WIDTH)
+    .build();
+
+    [box_x, box_y, box_width, box_height] = bar_inner_bounds([box_x, box_y, box_width, box_height]);
+    if box_width < box_height {
+        /* vertical */
+        let yoffset = box_y + (1.0 - value) * box_height;
+        draw.add_rect([box_x, box_y], [box_x + box_width, yoffset], empty_color)
+            .filled(true)
+            .build();
+
+        draw.add_rect(
+            [box_x, yoffset],
+            [box_x + box_width, box_y + box_height],
+            fill_color,
+        )
+        .filled(true)
+        .build();
+    } else {
+        /* horizontal */
+        let xoffset = box_x + (1.0 - value) * box_width;
+        draw.add_rect([box_x, box_y], [xoffset, box_y + box_height], empty_color)
+            .filled(true)
+            .build();
+
+        draw.add_rect(
+            [xoffset, box_y],
+            [box_x + box_width, box_y + box_height],
+            fill_color,
+        )
+        .filled(true)
+        .build();
+    }
+}
+
+/// A player drawn by the ESP.
+/// Shared by the live ESP and the settings preview so both use the same layout.
+struct EspPlayerDraw<'a> {
+    settings: &'a EspPlayerSettings,
+    player: &'a PlayerPawnInfo,
+    distance: f32,
+
+    /// Opacity of the whole ESP (0.0 - 1.0)
+    alpha: f32,
+    damage_flash: Option<&'a DamageFlash>,
+
+    /// 2D box of the player on the screen
+    bounds: Option<(nalgebra::Vector2<f32>, nalgebra::Vector2<f32>)>,
+    /// Screen position the tracer line points to
+    tracer_target: Option<[f32; 2]>,
+
+    /// Screen area the ESP is drawn in
+    area_min: [f32; 2],
+    area_max: [f32; 2],
+}
+
+impl EspPlayerDraw<'_> {
+    fn relative_health(&self) -> f32 {
+        (self.player.player_health as f32 / HEALTH_BAR_MAX_HEALTH).clamp(0.0, 1.0)
+    }
+
+    fn color(&self, color: &EspColor) -> [f32; 4] {
+        let mut color = color.calculate_color(
+            self.relative_health(),
+            self.distance,
+            self.player.player_color,
+        );
+        color[3] *= self.alpha;
+        color
+    }
+
+    /// Draw the health and armor bars, the info lines and the tracer
+    fn draw_details(&self, ui: &imgui::Ui, draw: &imgui::DrawListMut, fonts: &AppFontsHandle) {
+        if let Some((vmin, vmax)) = &self.bounds {
+            self.draw_bars(draw, vmin, vmax);
+            self.draw_info(ui, draw, fonts, vmin, vmax);
+        }
+
+        self.draw_tracer(draw);
+    }
+
+    fn draw_bars(
+        &self,
+        draw: &imgui::DrawListMut,
+        vmin: &nalgebra::Vector2<f32>,
+        vmax: &nalgebra::Vector2<f32>,
+    ) {
+        let esp_settings = self.settings;
+        let player_rel_health = self.relative_health();
+
+        let health_bar_bounds = calculate_bar_bounds(
+            esp_settings.health_bar,
+            vmin,
+            vmax,
+            esp_settings.box_width,
+            esp_settings.health_bar_width,
+            0.0,
+        );
+        if let Some(bounds) = health_bar_bounds {
+            draw_bar(
+                draw,
+                bounds,
+                player_rel_health,
+                [0.0, 1.0, 0.0, self.alpha],
+                [1.0, 0.0, 0.0, self.alpha],
+            );
+
+            if let Some(flash) = self
+                .damage_flash
+                .filter(|_| esp_settings.health_bar_damage_flash)
+            {
+                /* the lost segment shrinks towards the current health while fading from white to red */
+                let health_before =
+                    (flash.health_before as f32 / HEALTH_BAR_MAX_HEALTH).clamp(0.0, 1.0);
+                let segment_end = player_rel_health
+                    + (health_before - player_rel_health) * (1.0 - flash.progress);
+                let fade = 1.0 - flash.progress;
+
+                draw_bar_segment(
+                    draw,
+                    bounds,
+                    player_rel_health,
+                    segment_end,
+                    [1.0, fade, fade, (0.4 + 0.6 * fade) * self.alpha],
+                );
+            }
+        }
+
+        if esp_settings.armor_bar {
+            /* place the armor bar next to the health bar (or left if there is no health bar) */
+            let (position, offset) = match esp_settings.health_bar {
+                EspHealthBar::None => (EspHealthBar::Left, 0.0),
+                position => (position, esp_settings.health_bar_width),
+            };
+
+            let player_rel_armor =
+                (self.player.player_armor as f32 / ARMOR_BAR_MAX_ARMOR).clamp(0.0, 1.0);
+            if let Some(bounds) = calculate_bar_bounds(
+                position,
+                vmin,
+                vmax,
+                esp_settings.box_width,
+                esp_settings.health_bar_width,
+                offset,
+            ) {
+                draw_bar(
+                    draw,
+                    bounds,
+                    player_rel_armor,
+                    [0.0, 0.5, 1.0, self.alpha],
+                    [0.3, 0.3, 0.3, self.alpha],
+                );
+            }
+        }
+    }
+
+    fn draw_info(
+        &self,
+        ui: &imgui::Ui,
+        draw: &imgui::DrawListMut,
+        fonts: &AppFontsHandle,
+        vmin: &nalgebra::Vector2<f32>,
+        vmax: &nalgebra::Vector2<f32>,
+    ) {
+        let esp_settings = self.settings;
+        let entry = self.player;
+
+        let mut player_info = PlayerInfoLayout::new(
+            ui,
+            draw,
+            mint::Vector2 {
+                x: self.area_max[0] - self.area_min[0],
+                y: self.area_max[1] - self.area_min[1],
+            },
+            *vmin,
+            *vmax,
+            esp_settings.box_type == EspBoxType::Box2D,
+        );
+
+        let info_name_color = self.color(&esp_settings.info_name_color);
+
+        let mut name_parts = Vec::with_capacity(3);
+        if esp_settings.info_clan_tag && !entry.player_clan_tag.is_empty() {
+            name_parts.push(format!("[{}]", entry.player_clan_tag));
+        }
+        if esp_settings.info_name {
+            name_parts.push(entry.player_name.clone());
+        }
+        if esp_settings.info_steam_name && entry.player_steam_name != entry.player_name {
+            name_parts.push(format!("({})", entry.player_steam_name));
+        }
+        if !name_parts.is_empty() {
+            player_info.add_line(
+                info_name_color,
+                &name_parts.join(" "),
+                &esp_settings.info_name_style,
+            );
+        }
+
+        if esp_settings.info_rank {
+            if let Some(rank) = &entry.player_rank {
+                player_info.add_line(
+                    info_name_color,
+                    &rank.display_text(),
+                    &esp_settings.info_name_style,
+                );
+            }
+        }
+
+        if esp_settings.info_weapon {
+            let color = self.color(&esp_settings.info_weapon_color);
+
+            let weapon_icon = if esp_settings.info_weapon_icon {
+                weapon_icon(fonts, entry.weapon)
+            } else {
+                None
+            };
+
+            if let Some((font, icon)) = weapon_icon {
+                let _font = ui.push_font(font);
+                player_info.add_line(color, &icon.to_string(), &esp_settings.info_weapon_style);
+            } else {
+                let text = entry.weapon.display_name();
+                player_info.add_line(color, text, &esp_settings.info_weapon_style);
+            }
+        }
+
+        if esp_settings.info_grenades && !entry.grenades.is_empty() {
+            let color = self.color(&esp_settings.info_weapon_color);
+
+            let icons = if esp_settings.info_weapon_icon {
+                entry
+                    .grenades
+                    .iter()
+                    .map(|grenade| weapon_icon(fonts, *grenade))
+                    .collect::<Option<Vec<_>>>()
+            } else {
+                None
+            };
+
+            match icons {
+                Some(icons) => {
+                    let (font, _) = icons[0];
+                    let text = icons.iter().map(|(_, icon)| icon).collect::<String>();
+
+                    let _font = ui.push_font(font);
+                    player_info.add_line(color, &text, &esp_settings.info_weapon_style);
+                }
+                None => {
+                    let text = entry
+                        .grenades
+                        .iter()
+                        .map(|grenade| grenade.display_name())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    player_info.add_line(color, &text, &esp_settings.info_weapon_style);
+                }
+            }
+        }
+
+        if esp_settings.info_hp_text {
+            let text = format!("{} HP", entry.player_health);
+            player_info.add_line(
+                self.color(&esp_settings.info_hp_text_color),
+                &text,
+                &esp_settings.info_hp_text_style,
+            );
+        }
+
+        if esp_settings.info_armor {
+            let text = armor_text(entry.player_armor, entry.player_has_helmet);
+            player_info.add_line(
+                self.color(&esp_settings.info_armor_color),
+                &text,
+                &esp_settings.info_hp_text_style,
+            );
+        }
+
+        let mut player_flags = Vec::new();
+        if esp_settings.info_flag_kit && entry.player_has_defuser {
+            player_flags.push("Kit");
+        }
+
+        if esp_settings.info_flag_flashed && entry.player_flashtime > 0.0 {
+            player_flags.push("flashed");
+        }
+
+        if esp_settings.info_flag_scoped && entry.player_is_scoped {
+            player_flags.push("scoped");
+        }
+
+        if esp_settings.info_flag_reloading && entry.player_is_reloading {
+            player_flags.push("reloading");
+        }
+
+        if esp_settings.info_flag_defusing && entry.player_is_defusing {
+            player_flags.push("defusing");
+        }
+
+        if esp_settings.info_flag_planting && entry.player_is_planting {
+            player_flags.push("planting");
+        }
+
+        if !player_flags.is_empty() {
+            player_info.add_line(
+                self.color(&esp_settings.info_flags_color),
+                &player_flags.join(", "),
+                &Default::default(),
+            );
+        }
+        if esp_settings.info_distance {
+            let text = format!("{:.0}m", self.distance);
+            player_info.add_line(
+                self.color(&esp_settings.info_distance_color),
+                &text,
+                &esp_settings.info_distance_style,
+            );
+        }
+    }
+
+    fn draw_tracer(&self, draw: &imgui::DrawListMut) {
+        let target = match self.tracer_target {
+            Some(target) => target,
+            None => return,
+        };
+
+        let [min_x, min_y] = self.area_min;
+        let [max_x, max_y] = self.area_max;
+        let center_x = (min_x + max_x) / 2.0;
+        let tracer_origin = match self.settings.tracer_lines {
+            EspTracePosition::TopLeft => Some([min_x, min_y]),
+            EspTracePosition::TopCenter => Some([center_x, min_y]),
+            EspTracePosition::TopRight => Some([max_x, min_y]),
+            EspTracePosition::Center => Some([center_x, (min_y + max_y) / 2.0]),
+            EspTracePosition::BottomLeft => Some([min_x, max_y]),
+            EspTracePosition::BottomCenter => Some([center_x, max_y]),
+            EspTracePosition::BottomRight => Some([max_x, max_y]),
+            EspTracePosition::None => None,
+        };
+
+        if let Some(origin) = tracer_origin {
+            draw.add_line(
+                origin,
+                target,
+                self.color(&self.settings.tracer_lines_color),
+            )
+            .thickness(self.settings.tracer_lines_width)
+            .build();
+        }
+    }
+}
+
+pub const HOTKEY_ESP: HotKeyAction = HotKeyAction {
+    id: "esp",
+    name: "ESP 切换/触发",
+    default_key: None,
+};
+
 impl Enhancement for PlayerESP {
+    fn hotkey_actions(&self) -> &'static [HotKeyAction] {
+        &[HOTKEY_ESP]
+    }
+
     fn update(&mut self, ctx: &crate::UpdateContext) -> anyhow::Result<()> {
         let entities = ctx.states.resolve::<EntitySystem>(())?;
         let class_name_cache = ctx.states.resolve::<ClassNameCache>(())?;
         let settings = ctx.states.resolve::<AppSettings>(())?;
-        if self
-            .toggle
-            .update(&settings.esp_mode, ctx.input, &settings.esp_toogle)
-        {
-            ctx.cs2.add_metrics_record(
+        if self.toggle.update(
+            &settings.esp_mode,
+            ctx.input,
+            settings.hotkey(&HOTKEY_ESP),
+            &KeyToggleTimings::from_settings(&settings),
+        ) {
+            ctx.memory.add_metrics_record(
                 obfstr!("feature-esp-toggle"),
                 &format!(
                     "enabled: {}, mode: {:?}",
@@ -218,6 +967,7 @@ impl Enhancement for PlayerESP {
             }
         }
 
+        self.update_player_health(ctx.clock.now());
         Ok(())
     }
 
@@ -233,6 +983,16 @@ impl Enhancement for PlayerESP {
             _ => return Ok(()),
         };
 
+        /* fade the ESP out while being flashed so it does not blind the player */
+        let esp_alpha = if settings.flash_indicator && settings.flash_indicator_dim_esp {
+            let flash = states.resolve::<LocalPlayerFlash>(())?;
+            let min_alpha = settings.flash_indicator_esp_alpha as f32 / 100.0;
+            1.0 - (1.0 - min_alpha) * flash.remaining_fraction()
+        } else {
+            1.0
+        };
+
+        let mut entries = Vec::with_capacity(self.players.len());
         for entry in self.players.iter() {
             let distance = (entry.position - view_world_position).norm() * UNITS_TO_METERS;
             let esp_settings = match self.resolve_esp_player_config(&settings, entry) {
@@ -245,14 +1005,55 @@ impl Enhancement for PlayerESP {
                 }
             }
 
-            let player_rel_health = (entry.player_health as f32 / 100.0).clamp(0.0, 1.0);
+            entries.push((entry, esp_settings, distance));
+        }
 
+        let max_entries = settings.esp_max_entries as usize;
+        if max_entries > 0 && entries.len() > max_entries {
+            match settings.esp_priority {
+                EspPriority::Distance => {
+                    entries.sort_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+                }
+                EspPriority::Danger => {
+                    entries.sort_by(|(a, _, a_distance), (b, _, b_distance)| {
+                        self.danger_score(a, &view_world_position)
+                            .total_cmp(&self.danger_score(b, &view_world_position))
+                            .then(a_distance.total_cmp(b_distance))
+                    });
+                }
+            }
+            entries.truncate(max_entries);
+        }
+
+        if settings.esp_sort_by_distance {
+            /* draw far players first, so nearer players will be drawn on top */
+            entries.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+        }
+
+        for (entry, esp_settings, distance) in entries {
             let entry_model = states.resolve::<CS2Model>(entry.model_address)?;
             let player_2d_box = view.calculate_box_2d(
                 &(entry_model.vhull_min + entry.position),
                 &(entry_model.vhull_max + entry.position),
             );
 
+            let player_draw = EspPlayerDraw {
+                settings: esp_settings,
+                player: entry,
+                distance,
+                alpha: esp_alpha,
+                damage_flash: self
+                    .player_health
+                    .get(&entry.controller_entity_id)
+                    .and_then(|state| state.damage_flash.as_ref()),
+
+                bounds: player_2d_box,
+                tracer_target: view.world_to_screen(&entry.position, false).map(Into::into),
+                area_min: [0.0, 0.0],
+                area_max: [view.screen_bounds.x, view.screen_bounds.y],
+            };
+            let esp_color = |color: &EspColor| player_draw.color(color);
+
             if esp_settings.skeleton {
                 let bones = entry_model.bones.iter().zip(entry.bone_states.iter());
 
@@ -278,15 +1079,26 @@ impl Enhancement for PlayerESP {
                         None => continue,
                     };
 
-                    draw.add_line(
-                        parent_position,
-                        bone_position,
-                        esp_settings
-                            .skeleton_color
-                            .calculate_color(player_rel_health, distance),
-                    )
-                    .thickness(esp_settings.skeleton_width)
-                    .build();
+                    let group_style = esp_settings
+                        .skeleton_group_styles
+                        .get(EspSkeletonBoneGroup::from_bone_name(&bone.name));
+                    let (color, width) = if group_style.enabled {
+                        (&group_style.color, group_style.width)
+                    } else {
+                        (&esp_settings.skeleton_color, esp_settings.skeleton_width)
+                    };
+
+                    draw.add_line(parent_position, bone_position, esp_color(color))
+                        .thickness(width)
+                        .build();
+                    if settings.esp_glow {
+                        overlay::glow_line(
+                            parent_position.into(),
+                            bone_position.into(),
+                            esp_color(color),
+                            width,
+                        );
+                    }
                 }
             }
 
@@ -296,235 +1108,201 @@ impl Enhancement for PlayerESP {
                         draw.add_rect(
                             [vmin.x, vmin.y],
                             [vmax.x, vmax.y],
-                            esp_settings
-                                .box_color
-                                .calculate_color(player_rel_health, distance),
+                            esp_color(&esp_settings.box_color),
                         )
                         .thickness(esp_settings.box_width)
                         .build();
+
+                        if settings.esp_glow {
+                            overlay::glow_rect(
+                                [vmin.x, vmin.y],
+                                [vmax.x, vmax.y],
+                                esp_color(&esp_settings.box_color),
+                                esp_settings.box_width,
+                            );
+                        }
                     }
                 }
                 EspBoxType::Box3D => {
+                    let vmin = entry_model.vhull_min + entry.position;
+                    let vmax = entry_model.vhull_max + entry.position;
                     view.draw_box_3d(
                         &draw,
-                        &(entry_model.vhull_min + entry.position),
-                        &(entry_model.vhull_max + entry.position),
-                        esp_settings
-                            .box_color
-                            .calculate_color(player_rel_health, distance)
-                            .into(),
+                        &vmin,
+                        &vmax,
+                        esp_color(&esp_settings.box_color).into(),
                         esp_settings.box_width,
                     );
-                }
-                EspBoxType::None => {}
-            }
 
-            if let Some((vmin, vmax)) = &player_2d_box {
-                let box_bounds = match esp_settings.health_bar {
-                    EspHealthBar::None => None,
-                    EspHealthBar::Left => {
-                        let xoffset =
-                            vmin.x - esp_settings.box_width / 2.0 - esp_settings.health_bar_width;
-
-                        Some([
-                            xoffset,
-                            vmin.y - esp_settings.box_width / 2.0,
-                            esp_settings.health_bar_width,
-                            vmax.y - vmin.y + esp_settings.box_width,
-                        ])
-                    }
-                    EspHealthBar::Right => {
-                        let xoffset = vmax.x + esp_settings.box_width / 2.0;
-
-                        Some([
-                            xoffset,
-                            vmin.y - esp_settings.box_width / 2.0,
-                            esp_settings.health_bar_width,
-                            vmax.y - vmin.y + esp_settings.box_width,
-                        ])
-                    }
-                    EspHealthBar::Top => {
-                        let yoffset =
-                            vmin.y - esp_settings.box_width / 2.0 - esp_settings.health_bar_width;
-
-                        Some([
-                            vmin.x - esp_settings.box_width / 2.0,
-                            yoffset,
-                            vmax.x - vmin.x + esp_settings.box_width,
-                            esp_settings.health_bar_width,
-                        ])
-                    }
-                    EspHealthBar::Bottom => {
-                        let yoffset = vmax.y + esp_settings.box_width / 2.0;
-
-                        Some([
-                            vmin.x - esp_settings.box_width / 2.0,
-                            yoffset,
-                            vmax.x - vmin.x + esp_settings.box_width,
-                            esp_settings.health_bar_width,
-                        ])
-                    }
-                };
-
-                if let Some([mut box_x, mut box_y, mut box_width, mut box_height]) = box_bounds {
-                    const BORDER_WIDTH: f32 = 1.0;
-                    draw.add_rect(
-                        [box_x + BORDER_WIDTH / 2.0, box_y + BORDER_WIDTH / 2.0],
-                        [
-                            box_x + box_width - BORDER_WIDTH / 2.0,
-                            box_y + box_height - BORDER_WIDTH / 2.0,
-                        ],
-                        [0.0, 0.0, 0.0, 1.0],
-                    )
-                    .filled(false)
-                    .thickness(BORDER_WIDTH)
-                    .build();
-
-                    box_x += BORDER_WIDTH / 2.0 + 1.0;
-                    box_y += BORDER_WIDTH / 2.0 + 1.0;
-
-                    box_width -= BORDER_WIDTH + 2.0;
-                    box_height -= BORDER_WIDTH + 2.0;
-
-                    if box_width < box_height {
-                        /* vertical */
-                        let yoffset = box_y + (1.0 - player_rel_health) * box_height;
-                        draw.add_rect(
-                            [box_x, box_y],
-                            [box_x + box_width, yoffset],
-                            [1.0, 0.0, 0.0, 1.0],
-                        )
-                        .filled(true)
-                        .build();
-
-                        draw.add_rect(
-                            [box_x, yoffset],
-                            [box_x + box_width, box_y + box_height],
-                            [0.0, 1.0, 0.0, 1.0],
-                        )
-                        .filled(true)
-                        .build();
-                    } else {
-                        /* horizontal */
-                        let xoffset = box_x + (1.0 - player_rel_health) * box_width;
-                        draw.add_rect(
-                            [box_x, box_y],
-                            [xoffset, box_y + box_height],
-                            [1.0, 0.0, 0.0, 1.0],
-                        )
-                        .filled(true)
-                        .build();
-
-                        draw.add_rect(
-                            [xoffset, box_y],
-                            [box_x + box_width, box_y + box_height],
-                            [0.0, 1.0, 0.0, 1.0],
-                        )
-                        .filled(true)
-                        .build();
+                    if settings.esp_glow {
+                        for (start, end) in view.box_3d_lines(&vmin, &vmax) {
+                            overlay::glow_line(
+                                start.into(),
+                                end.into(),
+                                esp_color(&esp_settings.box_color),
+                                esp_settings.box_width,
+                            );
+                        }
                     }
                 }
+                EspBoxType::None => {}
             }
 
-            if let Some((vmin, vmax)) = player_2d_box {
-                let mut player_info = PlayerInfoLayout::new(
-                    ui,
-                    &draw,
-                    view.screen_bounds,
-                    vmin,
-                    vmax,
-                    esp_settings.box_type == EspBoxType::Box2D,
-                );
-
-                if esp_settings.info_name {
-                    player_info.add_line(
-                        esp_settings
-                            .info_name_color
-                            .calculate_color(player_rel_health, distance),
-                        &entry.player_name,
-                    );
-                }
-
-                if esp_settings.info_weapon {
-                    let text = entry.weapon.display_name();
-                    player_info.add_line(
-                        esp_settings
-                            .info_weapon_color
-                            .calculate_color(player_rel_health, distance),
-                        &text,
-                    );
-                }
+            player_draw.draw_details(ui, &draw, &self.fonts);
+        }
 
-                if esp_settings.info_hp_text {
-                    let text = format!("{} HP", entry.player_health);
-                    player_info.add_line(
-                        esp_settings
-                            .info_hp_text_color
-                            .calculate_color(player_rel_health, distance),
-                        &text,
-                    );
-                }
+        Ok(())
+    }
+}
 
-                let mut player_flags = Vec::new();
-                if esp_settings.info_flag_kit && entry.player_has_defuser {
-                    player_flags.push("Kit");
-                }
+/// Joints of the preview player relative to its box (0.0 - 1.0)
+const PREVIEW_SKELETON: [([f32; 2], [f32; 2], EspSkeletonBoneGroup); 13] = [
+    ([0.50, 0.08], [0.50, 0.17], EspSkeletonBoneGroup::Head),
+    ([0.50, 0.17], [0.50, 0.50], EspSkeletonBoneGroup::Spine),
+    ([0.50, 0.20], [0.34, 0.22], EspSkeletonBoneGroup::Arms),
+    ([0.34, 0.22], [0.27, 0.37], EspSkeletonBoneGroup::Arms),
+    ([0.27, 0.37], [0.24, 0.50], EspSkeletonBoneGroup::Arms),
+    ([0.50, 0.20], [0.66, 0.22], EspSkeletonBoneGroup::Arms),
+    ([0.66, 0.22], [0.73, 0.37], EspSkeletonBoneGroup::Arms),
+    ([0.73, 0.37], [0.76, 0.50], EspSkeletonBoneGroup::Arms),
+    ([0.50, 0.50], [0.42, 0.73], EspSkeletonBoneGroup::Legs),
+    ([0.42, 0.73], [0.40, 0.96], EspSkeletonBoneGroup::Legs),
+    ([0.50, 0.50], [0.58, 0.73], EspSkeletonBoneGroup::Legs),
+    ([0.58, 0.73], [0.60, 0.96], EspSkeletonBoneGroup::Legs),
+    ([0.40, 0.96], [0.36, 0.98], EspSkeletonBoneGroup::Legs),
+];
+
+/// Render a fake player with the given ESP settings into the current window.
+/// Used to preview the ESP settings without being in a match.
+pub fn render_esp_preview(
+    ui: &imgui::Ui,
+    fonts: &AppFontsHandle,
+    esp_settings: &EspPlayerSettings,
+) {
+    const PLAYER_HEALTH: i32 = 72;
+    const PLAYER_ARMOR: i32 = 100;
+    const PLAYER_DISTANCE: f32 = 12.0;
+    const PLAYER_COLOR: i32 = 3;
+
+    let draw = ui.get_window_draw_list();
+    let [origin_x, origin_y] = ui.cursor_screen_pos();
+    let [area_width, area_height] = ui.content_region_avail();
+    if area_width < 50.0 || area_height < 50.0 {
+        return;
+    }
 
-                if esp_settings.info_flag_flashed && entry.player_flashtime > 0.0 {
-                    player_flags.push("flashed");
-                }
+    let area_min = [origin_x, origin_y];
+    let area_max = [origin_x + area_width, origin_y + area_height];
+    draw.add_rect(area_min, area_max, [0.08, 0.08, 0.08, 1.0])
+        .filled(true)
+        .build();
+
+    /* leave some space for the info text on the right */
+    let box_height = area_height * 0.6;
+    let box_width = box_height * 0.45;
+    let vmin = nalgebra::Vector2::new(
+        origin_x + (area_width - box_width) * 0.35,
+        origin_y + area_height * 0.15,
+    );
+    let vmax = vmin + nalgebra::Vector2::new(box_width, box_height);
+
+    /* all flags are set so every enabled info line will be shown */
+    let player = PlayerPawnInfo {
+        controller_entity_id: 0,
+        team_id: 0,
+
+        player_health: PLAYER_HEALTH,
+        player_has_defuser: true,
+        player_armor: PLAYER_ARMOR,
+        player_has_helmet: true,
+        player_name: "Player".to_string(),
+        player_steam_name: "Steam".to_string(),
+        player_clan_tag: "VT".to_string(),
+        player_rank: None,
+        player_color: PLAYER_COLOR,
+        weapon: WeaponId::Ak47,
+        player_flashtime: 1.0,
+
+        grenades: vec![WeaponId::Flashbang],
+
+        player_is_scoped: true,
+        player_is_reloading: true,
+        player_is_defusing: true,
+        player_is_planting: true,
+
+        position: Default::default(),
+        rotation: 0.0,
+        pitch: 0.0,
+
+        model_address: 0,
+        bone_states: Vec::new(),
+    };
+    let player_draw = EspPlayerDraw {
+        settings: esp_settings,
+        player: &player,
+        distance: PLAYER_DISTANCE,
+        alpha: 1.0,
+        damage_flash: None,
+
+        bounds: Some((vmin, vmax)),
+        tracer_target: Some([(vmin.x + vmax.x) / 2.0, vmax.y]),
+        area_min,
+        area_max,
+    };
+    let esp_color = |color: &EspColor| player_draw.color(color);
+    let joint_position = |[x, y]: [f32; 2]| [vmin.x + x * box_width, vmin.y + y * box_height];
+
+    if esp_settings.skeleton {
+        for (from, to, group) in PREVIEW_SKELETON {
+            let group_style = esp_settings.skeleton_group_styles.get(group);
+            let (color, width) = if group_style.enabled {
+                (&group_style.color, group_style.width)
+            } else {
+                (&esp_settings.skeleton_color, esp_settings.skeleton_width)
+            };
 
-                if !player_flags.is_empty() {
-                    player_info.add_line(
-                        esp_settings
-                            .info_flags_color
-                            .calculate_color(player_rel_health, distance),
-                        &player_flags.join(", "),
-                    );
-                }
-                if esp_settings.info_distance {
-                    let text = format!("{:.0}m", distance);
-                    player_info.add_line(
-                        esp_settings
-                            .info_distance_color
-                            .calculate_color(player_rel_health, distance),
-                        &text,
-                    );
-                }
-            }
+            draw.add_line(joint_position(from), joint_position(to), esp_color(color))
+                .thickness(width)
+                .build();
+        }
+    }
 
-            if let Some(pos) = view.world_to_screen(&entry.position, false) {
-                let tracer_origin = match esp_settings.tracer_lines {
-                    EspTracePosition::TopLeft => Some([0.0, 0.0]),
-                    EspTracePosition::TopCenter => Some([view.screen_bounds.x / 2.0, 0.0]),
-                    EspTracePosition::TopRight => Some([view.screen_bounds.x, 0.0]),
-                    EspTracePosition::Center => {
-                        Some([view.screen_bounds.x / 2.0, view.screen_bounds.y / 2.0])
-                    }
-                    EspTracePosition::BottomLeft => Some([0.0, view.screen_bounds.y]),
-                    EspTracePosition::BottomCenter => {
-                        Some([view.screen_bounds.x / 2.0, view.screen_bounds.y])
-                    }
-                    EspTracePosition::BottomRight => {
-                        Some([view.screen_bounds.x, view.screen_bounds.y])
-                    }
-                    EspTracePosition::None => None,
-                };
-
-                if let Some(origin) = tracer_origin {
-                    draw.add_line(
-                        origin,
-                        pos,
-                        esp_settings
-                            .tracer_lines_color
-                            .calculate_color(player_rel_health, distance),
-                    )
-                    .thickness(esp_settings.tracer_lines_width)
+    match esp_settings.box_type {
+        EspBoxType::Box2D => {
+            draw.add_rect(
+                [vmin.x, vmin.y],
+                [vmax.x, vmax.y],
+                esp_color(&esp_settings.box_color),
+            )
+            .thickness(esp_settings.box_width)
+            .build();
+        }
+        EspBoxType::Box3D => {
+            /* fake perspective by shifting the back face */
+            let color = esp_color(&esp_settings.box_color);
+            let depth = box_width * 0.3;
+            let back_min = [vmin.x + depth, vmin.y - depth * 0.5];
+            let back_max = [vmax.x + depth, vmax.y - depth * 0.5];
+
+            draw.add_rect([vmin.x, vmin.y], [vmax.x, vmax.y], color)
+                .thickness(esp_settings.box_width)
+                .build();
+            draw.add_rect(back_min, back_max, color)
+                .thickness(esp_settings.box_width)
+                .build();
+            for (front, back) in [
+                ([vmin.x, vmin.y], back_min),
+                ([vmax.x, vmin.y], [back_max[0], back_min[1]]),
+                ([vmin.x, vmax.y], [back_min[0], back_max[1]]),
+                ([vmax.x, vmax.y], back_max),
+            ] {
+                draw.add_line(front, back, color)
+                    .thickness(esp_settings.box_width)
                     .build();
-                }
             }
         }
-
-        Ok(())
+        EspBoxType::None => {}
     }
+
+    player_draw.draw_details(ui, &draw, fonts);
 }
@@ -0,0 +1,157 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::Instant,
+};
+
+use cs2::{
+    LocalCameraControllerTarget,
+    PlayerPawnState,
+};
+use imgui::ImColor32;
+
+use super::{
+    Enhancement,
+    PlayerESP,
+};
+use crate::{
+    audio::{
+        play_cue,
+        AudioCue,
+    },
+    i18n::tr,
+    settings::AppSettings,
+    UpdateContext,
+};
+
+const UNITS_TO_METERS: f32 = 0.01905;
+
+/// Minimum time between two alert sounds in milliseconds
+const SOUND_INTERVAL: u128 = 2_000;
+
+/// Height of the pulse at the bottom of the screen (% of the screen height)
+const PULSE_HEIGHT: f32 = 0.08;
+
+/// Alerts the local player when an enemy gets close behind them.
+/// The enemy positions are taken from the player ESP, hence the ESP must be enabled.
+pub struct ProximityAlert {
+    player_esp: Rc<RefCell<PlayerESP>>,
+
+    /// Distance (in meters) of the closest enemy behind the local player
+    closest_enemy: Option<f32>,
+    last_sound: Option<Instant>,
+}
+
+impl ProximityAlert {
+    pub fn new(player_esp: Rc<RefCell<PlayerESP>>) -> Self {
+        Self {
+            player_esp,
+
+            closest_enemy: None,
+            last_sound: None,
+        }
+    }
+
+    fn play_alert_sound(&mut self, now: Instant, volume: u32) {
+        if self
+            .last_sound
+            .map(|time| now.duration_since(time).as_millis() < SOUND_INTERVAL)
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        self.last_sound = Some(now);
+        play_cue(AudioCue::ProximityAlert, volume);
+    }
+}
+
+impl Enhancement for ProximityAlert {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        let had_alert = self.closest_enemy.is_some();
+        self.closest_enemy = None;
+
+        if !settings.proximity_alert {
+            return Ok(());
+        }
+
+        let view_target = ctx.states.resolve::<LocalCameraControllerTarget>(())?;
+        let target_entity_id = match &view_target.target_entity_id {
+            Some(value) => *value,
+            None => return Ok(()),
+        };
+
+        let local_pawn = ctx.states.resolve::<PlayerPawnState>(target_entity_id)?;
+        let local_pawn = match &*local_pawn {
+            PlayerPawnState::Alive(info) => info,
+            PlayerPawnState::Dead => return Ok(()),
+        };
+
+        let player_esp = self.player_esp.borrow();
+        for player in player_esp.players() {
+            if player.team_id == player_esp.local_team_id() {
+                continue;
+            }
+
+            let delta = player.position - local_pawn.position;
+            let distance = delta.norm() * UNITS_TO_METERS;
+            if distance > settings.proximity_alert_distance as f32 {
+                continue;
+            }
+
+            let yaw_to_player = delta.y.atan2(delta.x).to_degrees();
+            let yaw_difference = (yaw_to_player - local_pawn.rotation).rem_euclid(360.0);
+            if yaw_difference.min(360.0 - yaw_difference) < 90.0 {
+                /* the enemy is in front of the local player */
+                continue;
+            }
+
+            if self
+                .closest_enemy
+                .map(|closest| distance < closest)
+                .unwrap_or(true)
+            {
+                self.closest_enemy = Some(distance);
+            }
+        }
+        drop(player_esp);
+
+        if settings.proximity_alert_sound && !had_alert && self.closest_enemy.is_some() {
+            self.play_alert_sound(ctx.clock.now(), settings.audio_volume);
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, _states: &utils_state::StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let distance = match self.closest_enemy {
+            Some(distance) => distance,
+            None => return Ok(()),
+        };
+
+        let [screen_width, screen_height] = ui.io().display_size;
+        let pulse = ((ui.time() * std::f64::consts::TAU * 2.0).sin() as f32 + 1.0) / 2.0;
+        let alpha = 0.25 + 0.45 * pulse;
+
+        let draw = ui.get_window_draw_list();
+        let top = screen_height * (1.0 - PULSE_HEIGHT);
+        let color_edge = ImColor32::from_rgba_f32s(1.0, 0.1, 0.1, alpha);
+        let color_center = ImColor32::from_rgba_f32s(1.0, 0.1, 0.1, 0.0);
+        draw.add_rect_filled_multicolor(
+            [0.0, top],
+            [screen_width, screen_height],
+            color_center,
+            color_center,
+            color_edge,
+            color_edge,
+        );
+
+        let text = format!("{} {:.0}m", tr!("身后有敌人!"), distance);
+        let [text_width, text_height] = ui.calc_text_size(&text);
+        ui.set_cursor_pos([(screen_width - text_width) / 2.0, top - text_height]);
+        ui.text_colored([1.0, 0.2, 0.2, 1.0], &text);
+
+        Ok(())
+    }
+}
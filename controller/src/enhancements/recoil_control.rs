@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use cs2::{
+    EntitySystem,
+    MouseSensitivity,
+    WeaponId,
+};
+use cs2_schema_generated::cs2::client::CCSPlayerBase_CameraServices;
+use obfstr::obfstr;
+use valthrun_kernel_interface::MouseState;
+
+use super::Enhancement;
+use crate::{
+    settings::AppSettings,
+    UpdateContext,
+};
+
+/// Recoil patterns shipped with the controller
+const DEFAULT_SPRAY_PATTERNS: &str = include_str!("../../resources/spray-patterns.yaml");
+
+/// Recoil pattern of each weapon by the weapon name.
+/// Each entry is the view offset [up, right] (in degrees) of the shot relative to the first shot.
+type SprayPatterns = BTreeMap<String, Vec<[f32; 2]>>;
+
+fn load_spray_patterns() -> anyhow::Result<Option<SprayPatterns>> {
+    let exe_file = std::env::current_exe().context("missing current exe path")?;
+    let pattern_file = exe_file
+        .parent()
+        .context("could not get exe directory")?
+        .join("spray-patterns.yaml");
+
+    if !pattern_file.is_file() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&pattern_file)
+        .with_context(|| format!("failed to read {}", pattern_file.to_string_lossy()))?;
+    let patterns = serde_yaml::from_str(&content)
+        .with_context(|| format!("failed to parse {}", pattern_file.to_string_lossy()))?;
+
+    Ok(Some(patterns))
+}
+
+/// Compensates the recoil of the current weapon by moving the mouse
+/// against the weapons spray pattern.
+pub struct RecoilControl {
+    patterns: SprayPatterns,
+
+    /// Weapon of the current spray
+    weapon: Option<WeaponId>,
+
+    /// Mouse movement which has already been applied for the current spray
+    mouse_adjustment_x: i32,
+    mouse_adjustment_y: i32,
+}
+
+impl RecoilControl {
+    pub fn new() -> Self {
+        let patterns = match load_spray_patterns() {
+            Ok(Some(patterns)) => {
+                log::info!("已加载自定义弹道数据 ({} 把武器)", patterns.len());
+                patterns
+            }
+            Ok(None) => Default::default(),
+            Err(error) => {
+                log::warn!("加载自定义弹道数据失败，将使用内置数据: {:#}", error);
+                Default::default()
+            }
+        };
+
+        let patterns = if patterns.is_empty() {
+            serde_yaml::from_str(DEFAULT_SPRAY_PATTERNS)
+                .expect("the default spray patterns to be valid")
+        } else {
+            patterns
+        };
+
+        Self {
+            patterns,
+
+            weapon: None,
+            mouse_adjustment_x: 0,
+            mouse_adjustment_y: 0,
+        }
+    }
+
+    fn reset_spray(&mut self, weapon: Option<WeaponId>) {
+        self.weapon = weapon;
+        self.mouse_adjustment_x = 0;
+        self.mouse_adjustment_y = 0;
+    }
+}
+
+impl Enhancement for RecoilControl {
+    fn injects_input(&self) -> bool {
+        true
+    }
+
+    fn suspend_input(&mut self, _ctx: &UpdateContext) -> anyhow::Result<()> {
+        self.reset_spray(None);
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.recoil_control {
+            self.reset_spray(None);
+            return Ok(());
+        }
+
+        let entities = ctx.states.resolve::<EntitySystem>(())?;
+        let local_controller = entities.get_local_player_controller()?;
+        if local_controller.is_null()? {
+            self.reset_spray(None);
+            return Ok(());
+        }
+
+        let local_pawn = entities
+            .get_by_handle(&local_controller.reference_schema()?.m_hPlayerPawn()?)?
+            .with_context(|| obfstr!("missing local player pawn").to_string())?
+            .entity()?
+            .read_schema()?;
+
+        let weapon = match local_pawn.m_pClippingWeapon()?.try_read_schema()? {
+            Some(weapon) => WeaponId::from_id(
+                weapon
+                    .m_AttributeManager()?
+                    .m_Item()?
+                    .m_iItemDefinitionIndex()?,
+            ),
+            None => None,
+        };
+
+        let shots_fired = local_pawn.m_iShotsFired()?.max(0) as usize;
+        if shots_fired == 0 || weapon != self.weapon {
+            /* spray ended or the weapon has been switched */
+            self.reset_spray(weapon);
+            return Ok(());
+        }
+
+        let pattern = match weapon.and_then(|weapon| self.patterns.get(weapon.name())) {
+            Some(pattern) if !pattern.is_empty() => pattern,
+            _ => return Ok(()),
+        };
+
+        /* compensate the offset of the next shot, shots beyond the pattern use the last offset */
+        let [offset_up, offset_right] = pattern[shots_fired.min(pattern.len() - 1)];
+
+        let zoom_fov = local_pawn
+            .m_pCameraServices()?
+            .cast::<CCSPlayerBase_CameraServices>()
+            .try_reference_schema()?
+            .map(|camera| camera.m_iFOV())
+            .transpose()?
+            .map(|fov| fov as f32);
+
+        let sensitivity = ctx.states.resolve::<MouseSensitivity>(())?;
+        let deg_one = sensitivity.counts_per_degree(zoom_fov);
+        let strength_x = settings.recoil_control_strength_x as f32 / 100.0;
+        let strength_y = settings.recoil_control_strength_y as f32 / 100.0;
+
+        /* move the mouse down and to the left to counter the recoil */
+        let target_mouse_y = (offset_up * deg_one * strength_y).round() as i32;
+        let target_mouse_x = (-offset_right * deg_one * strength_x).round() as i32;
+
+        let delta_mouse_x = target_mouse_x - self.mouse_adjustment_x;
+        let delta_mouse_y = target_mouse_y - self.mouse_adjustment_y;
+        self.mouse_adjustment_x = target_mouse_x;
+        self.mouse_adjustment_y = target_mouse_y;
+
+        if delta_mouse_x != 0 || delta_mouse_y != 0 {
+            ctx.memory.send_mouse_state(&[MouseState {
+                last_x: delta_mouse_x,
+                last_y: delta_mouse_y,
+                ..Default::default()
+            }])?;
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, _states: &utils_state::StateRegistry, _ui: &imgui::Ui) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cs2::WeaponId;
+
+    use super::{
+        SprayPatterns,
+        DEFAULT_SPRAY_PATTERNS,
+    };
+
+    #[test]
+    fn default_patterns_are_valid() {
+        let patterns: SprayPatterns = serde_yaml::from_str(DEFAULT_SPRAY_PATTERNS).unwrap();
+        for name in patterns.keys() {
+            assert!(
+                WeaponId::all_weapons()
+                    .iter()
+                    .any(|weapon| weapon.name() == name),
+                "unknown weapon {}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use cs2::{
+    GameRules,
+    RoundPhase,
+};
+
+use super::Enhancement;
+use crate::{
+    i18n::tr,
+    settings::AppSettings,
+};
+
+/// % of the screens height
+const ROUND_INFO_TOP_OFFSET: f32 = 0.004;
+
+pub struct RoundInfoIndicator {}
+
+impl RoundInfoIndicator {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+fn format_round_time(seconds: f32) -> String {
+    let seconds = seconds.ceil() as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+impl Enhancement for RoundInfoIndicator {
+    fn update(&mut self, _ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn render(&self, states: &utils_state::StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.round_info {
+            return Ok(());
+        }
+
+        let game_rules = states.resolve::<GameRules>(())?;
+
+        let mut lines = Vec::with_capacity(4);
+        if settings.round_info_phase {
+            lines.push(format!(
+                "{}: {}",
+                tr!("阶段"),
+                match game_rules.phase {
+                    RoundPhase::Warmup => tr!("热身").to_string(),
+                    RoundPhase::FreezeTime => tr!("冻结时间").to_string(),
+                    RoundPhase::Live => tr!("进行中").to_string(),
+                    RoundPhase::RoundOver => tr!("回合结束").to_string(),
+                }
+            ));
+        }
+
+        if settings.round_info_round_time && game_rules.phase != RoundPhase::Warmup {
+            lines.push(format!(
+                "{}: {}",
+                tr!("回合剩余"),
+                format_round_time(game_rules.round_time_remaining)
+            ));
+        }
+
+        if settings.round_info_freeze_time && game_rules.freeze_time_remaining > 0.0 {
+            lines.push(format!(
+                "{}: {:.1}",
+                tr!("冻结时间"),
+                game_rules.freeze_time_remaining
+            ));
+        }
+
+        if settings.round_info_buy_time {
+            if let Some(buy_time) = game_rules.buy_time_remaining {
+                lines.push(format!("{}: {:.1}", tr!("购买时间"), buy_time));
+            }
+        }
+
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        /* align to be on the left side of the players, mirroring the bomb timer */
+        let offset_x = ui.io().display_size[0] * 830.0 / 2560.0;
+        let mut offset_y = ui.io().display_size[1] * ROUND_INFO_TOP_OFFSET;
+        for line in lines {
+            let [text_width, _] = ui.calc_text_size(&line);
+            ui.set_cursor_pos([offset_x - text_width, offset_y]);
+            ui.text(&line);
+            offset_y += ui.text_line_height_with_spacing();
+        }
+
+        Ok(())
+    }
+}
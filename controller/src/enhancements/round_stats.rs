@@ -0,0 +1,387 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::Write,
+    path::PathBuf,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+use cs2::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    EntitySystem,
+    GameRules,
+    PlayerPawnState,
+    RoundPhase,
+};
+use cs2_schema_generated::cs2::client::CSMatchStats_t;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    i18n::tr,
+    settings::{
+        get_settings_path,
+        AppSettings,
+    },
+    UpdateContext,
+};
+
+/// Duration how long the round summary will be shown
+const SUMMARY_DURATION: Duration = Duration::from_secs(10);
+
+/// Accumulated match stats of the local player
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct PlayerStats {
+    kills: i32,
+    assists: i32,
+    headshot_kills: i32,
+    damage: i32,
+    utility_damage: i32,
+    enemies_flashed: i32,
+}
+
+impl PlayerStats {
+    fn read(stats: &CSMatchStats_t) -> anyhow::Result<Self> {
+        Ok(Self {
+            kills: stats.m_iKills()?,
+            assists: stats.m_iAssists()?,
+            headshot_kills: stats.m_iHeadShotKills()?,
+            damage: stats.m_iDamage()?,
+            utility_damage: stats.m_iUtilityDamage()?,
+            enemies_flashed: stats.m_iEnemiesFlashed()?,
+        })
+    }
+
+    /// Stats gained since the other stats have been recorded
+    fn since(&self, other: &Self) -> Self {
+        Self {
+            kills: (self.kills - other.kills).max(0),
+            assists: (self.assists - other.assists).max(0),
+            headshot_kills: (self.headshot_kills - other.headshot_kills).max(0),
+            damage: (self.damage - other.damage).max(0),
+            utility_damage: (self.utility_damage - other.utility_damage).max(0),
+            enemies_flashed: (self.enemies_flashed - other.enemies_flashed).max(0),
+        }
+    }
+
+    /// Match stats only increase. If not, a new match has been started.
+    fn is_continuation_of(&self, other: &Self) -> bool {
+        self.kills >= other.kills && self.damage >= other.damage
+    }
+}
+
+/// Summary of the local player's performance within a round.
+/// Grenades thrown ("utility used") are not tracked as the match stats only contain
+/// the utility damage and the amount of flashed enemies.
+pub struct RoundSummary {
+    stats: PlayerStats,
+
+    /// Damage dealt per enemy player name.
+    /// See `RoundStatsTracker::update_enemy_health` for how the damage is attributed.
+    damage_per_enemy: Vec<(String, i32)>,
+}
+
+impl RoundSummary {
+    fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::with_capacity(4 + self.damage_per_enemy.len());
+        lines.push(format!(
+            "{}: {} ({} {})  {}: {}",
+            tr!("击杀"),
+            self.stats.kills,
+            tr!("爆头"),
+            self.stats.headshot_kills,
+            tr!("助攻"),
+            self.stats.assists
+        ));
+        lines.push(format!("{}: {}", tr!("总伤害"), self.stats.damage));
+        for (enemy, damage) in self.damage_per_enemy.iter() {
+            lines.push(format!("  {}: {}", enemy, damage));
+        }
+        lines.push(format!(
+            "{}: {}",
+            tr!("闪光致盲敌人"),
+            self.stats.enemies_flashed
+        ));
+        lines.push(format!(
+            "{}: {}",
+            tr!("道具伤害"),
+            self.stats.utility_damage
+        ));
+        lines
+    }
+}
+
+struct EnemyHealth {
+    player_name: String,
+    health: i32,
+}
+
+pub struct RoundStatsTracker {
+    last_phase: Option<RoundPhase>,
+
+    round_start_stats: Option<PlayerStats>,
+    last_stats: PlayerStats,
+
+    /// Last known health of the enemies by their pawn entity index
+    enemy_health: BTreeMap<u32, EnemyHealth>,
+    round_damage: BTreeMap<String, i32>,
+
+    summary: Option<RoundSummary>,
+    summary_visible_until: Option<Instant>,
+}
+
+impl RoundStatsTracker {
+    pub fn new() -> Self {
+        Self {
+            last_phase: None,
+
+            round_start_stats: None,
+            last_stats: Default::default(),
+
+            enemy_health: Default::default(),
+            round_damage: Default::default(),
+
+            summary: None,
+            summary_visible_until: None,
+        }
+    }
+
+    fn start_round(&mut self, stats: PlayerStats) {
+        self.round_start_stats = Some(stats);
+        self.round_damage.clear();
+    }
+
+    fn finish_round(&mut self, stats: PlayerStats) -> Option<&RoundSummary> {
+        let start_stats = self.round_start_stats.take()?;
+
+        let mut damage_per_enemy = self
+            .round_damage
+            .iter()
+            .map(|(name, damage)| (name.clone(), *damage))
+            .collect::<Vec<_>>();
+        damage_per_enemy.sort_by(|a, b| b.1.cmp(&a.1));
+
+        self.summary = Some(RoundSummary {
+            stats: stats.since(&start_stats),
+            damage_per_enemy,
+        });
+        self.summary.as_ref()
+    }
+
+    /// Update the known enemy health and return the enemy which lost the most health since the last update.
+    ///
+    /// Note: The damage of the local player is attributed to this enemy. The game does not expose
+    /// who dealt the damage, hence damage teammates dealt within the same update can cause
+    /// the damage to be attributed to the wrong enemy.
+    fn update_enemy_health(
+        &mut self,
+        states: &StateRegistry,
+        local_team_id: u8,
+    ) -> anyhow::Result<Option<String>> {
+        let entities = states.resolve::<EntitySystem>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+
+        let mut current_health = BTreeMap::new();
+        for entity_identity in entities.all_identities() {
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "C_CSPlayerPawn")
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let entity_index = entity_identity.handle::<()>()?.get_entity_index();
+            let player = match states.resolve::<PlayerPawnState>(entity_index) {
+                Ok(info) => info,
+                Err(_) => continue,
+            };
+
+            if let PlayerPawnState::Alive(info) = &*player {
+                if info.team_id == local_team_id {
+                    continue;
+                }
+
+                current_health.insert(
+                    entity_index,
+                    EnemyHealth {
+                        player_name: info.player_name.clone(),
+                        health: info.player_health,
+                    },
+                );
+            }
+        }
+
+        let mut target: Option<(String, i32)> = None;
+        for (entity_index, previous) in self.enemy_health.iter() {
+            /* enemies which are no longer alive lost all their health */
+            let health = current_health
+                .get(entity_index)
+                .map(|enemy| enemy.health)
+                .unwrap_or(0);
+
+            let health_lost = previous.health - health;
+            if health_lost <= 0 {
+                continue;
+            }
+
+            if target
+                .as_ref()
+                .map(|(_, lost)| health_lost > *lost)
+                .unwrap_or(true)
+            {
+                target = Some((previous.player_name.clone(), health_lost));
+            }
+        }
+
+        self.enemy_health = current_health;
+        Ok(target.map(|(name, _)| name))
+    }
+}
+
+pub fn get_round_stats_log_path() -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    let base_dir = settings_path
+        .parent()
+        .context("could not get config directory")?;
+
+    Ok(base_dir.join("round_stats.log"))
+}
+
+fn log_round_summary(summary: &RoundSummary) -> anyhow::Result<()> {
+    let log_path = get_round_stats_log_path()?;
+    let mut log_file = File::options()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| {
+            format!(
+                "failed to open round stats log at {}",
+                log_path.to_string_lossy()
+            )
+        })?;
+
+    writeln!(
+        log_file,
+        "[{}]",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    )?;
+    for line in summary.lines() {
+        writeln!(log_file, "{}", line)?;
+    }
+    writeln!(log_file)?;
+
+    Ok(())
+}
+
+impl Enhancement for RoundStatsTracker {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.round_stats {
+            self.round_start_stats = None;
+            self.summary = None;
+            self.summary_visible_until = None;
+            return Ok(());
+        }
+
+        let entities = ctx.states.resolve::<EntitySystem>(())?;
+        let local_controller = entities.get_local_player_controller()?;
+        if local_controller.is_null()? {
+            return Ok(());
+        }
+
+        let local_controller = local_controller.reference_schema()?;
+        let local_team_id = local_controller.m_iPendingTeamNum()?;
+        let stats = PlayerStats::read(
+            &local_controller
+                .m_pActionTrackingServices()?
+                .reference_schema()?
+                .m_matchStats()?,
+        )?;
+
+        if !stats.is_continuation_of(&self.last_stats) {
+            /* a new match has been started */
+            self.round_start_stats = None;
+            self.last_stats = Default::default();
+        }
+
+        let damaged_enemy = self.update_enemy_health(ctx.states, local_team_id)?;
+        let damage_dealt = stats.damage - self.last_stats.damage;
+        if damage_dealt > 0 {
+            let enemy = damaged_enemy.unwrap_or_else(|| tr!("未知").to_string());
+            *self.round_damage.entry(enemy).or_default() += damage_dealt;
+        }
+        self.last_stats = stats;
+
+        let phase = ctx
+            .states
+            .resolve::<GameRules>(())
+            .ok()
+            .map(|rules| rules.phase);
+        if phase != self.last_phase {
+            match phase {
+                Some(RoundPhase::FreezeTime) => self.start_round(stats),
+                Some(RoundPhase::RoundOver) => {
+                    if let Some(summary) = self.finish_round(stats) {
+                        if settings.round_stats_log {
+                            if let Err(error) = log_round_summary(summary) {
+                                log::warn!("记录回合统计失败: {:#}", error);
+                            }
+                        }
+
+                        self.summary_visible_until = Some(ctx.clock.now() + SUMMARY_DURATION);
+                    }
+                }
+                _ => {}
+            }
+
+            self.last_phase = phase;
+        }
+
+        if self.round_start_stats.is_none() && phase == Some(RoundPhase::Live) {
+            /* joined in the middle of a round */
+            self.start_round(stats);
+        }
+
+        if self
+            .summary_visible_until
+            .map(|until| until <= ctx.clock.now())
+            .unwrap_or(false)
+        {
+            self.summary_visible_until = None;
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, _states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        if self.summary_visible_until.is_none() {
+            return Ok(());
+        }
+
+        let summary = match &self.summary {
+            Some(summary) => summary,
+            None => return Ok(()),
+        };
+
+        let offset_x = ui.io().display_size[0] * 0.01;
+        let mut offset_y = ui.io().display_size[1] * 0.35;
+
+        ui.set_cursor_pos([offset_x, offset_y]);
+        ui.text_colored([1.0, 0.76, 0.03, 1.0], tr!("回合统计"));
+        offset_y += ui.text_line_height_with_spacing();
+
+        for line in summary.lines() {
+            ui.set_cursor_pos([offset_x, offset_y]);
+            ui.text(&line);
+            offset_y += ui.text_line_height_with_spacing();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use cs2::PlayerControllerList;
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    i18n,
+    settings::{
+        AppSettings,
+        HotKeyAction,
+    },
+    UpdateContext,
+};
+
+const TEAM_ID_T: u8 = 2;
+const TEAM_ID_CT: u8 = 3;
+
+/// Spacing between two columns in pixels
+const COLUMN_SPACING: f32 = 15.0;
+
+const SCOREBOARD_COLUMNS: [&str; 4] = ["名称", "Steam 名称", "战队", "段位"];
+
+/// Shows all players of the current match together with their clan tag and rank
+pub struct Scoreboard;
+impl Scoreboard {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+const HOTKEY_SCOREBOARD: HotKeyAction = HotKeyAction {
+    id: "scoreboard",
+    name: "切换记分板",
+    default_key: None,
+};
+
+impl Enhancement for Scoreboard {
+    fn update(&mut self, _ctx: &UpdateContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn hotkey_actions(&self) -> &'static [HotKeyAction] {
+        &[HOTKEY_SCOREBOARD]
+    }
+
+    fn on_hotkey_pressed(&mut self, _action: &HotKeyAction, settings: &mut AppSettings) -> bool {
+        settings.scoreboard = !settings.scoreboard;
+        true
+    }
+
+    fn render(&self, states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+        if !settings.scoreboard {
+            return Ok(());
+        }
+
+        let player_list = states.resolve::<PlayerControllerList>(())?;
+        let mut players = player_list
+            .players
+            .iter()
+            .filter(|player| player.team_id == TEAM_ID_T || player.team_id == TEAM_ID_CT)
+            .collect::<Vec<_>>();
+        players.sort_by(|a, b| {
+            b.team_id
+                .cmp(&a.team_id)
+                .then_with(|| a.player_name.cmp(&b.player_name))
+        });
+
+        let rows = players
+            .iter()
+            .map(|player| {
+                let color = match (player.team_id, player.player_is_alive) {
+                    (_, false) => [0.6, 0.6, 0.6, 1.0],
+                    (TEAM_ID_CT, true) => [0.36, 0.6, 1.0, 1.0],
+                    _ => [1.0, 0.7, 0.3, 1.0],
+                };
+
+                let columns = [
+                    player.player_name.clone(),
+                    if player.player_steam_name != player.player_name {
+                        player.player_steam_name.clone()
+                    } else {
+                        "-".to_string()
+                    },
+                    if player.player_clan_tag.is_empty() {
+                        "-".to_string()
+                    } else {
+                        player.player_clan_tag.clone()
+                    },
+                    player
+                        .player_rank
+                        .map(|rank| rank.display_text())
+                        .unwrap_or_else(|| "-".to_string()),
+                ];
+
+                (color, columns)
+            })
+            .collect::<Vec<_>>();
+
+        let header = SCOREBOARD_COLUMNS.map(i18n::translate);
+        let mut column_widths = header.each_ref().map(|name| ui.calc_text_size(name)[0]);
+        for (_, columns) in rows.iter() {
+            for (width, text) in column_widths.iter_mut().zip(columns.iter()) {
+                *width = width.max(ui.calc_text_size(text)[0]);
+            }
+        }
+
+        let total_width =
+            column_widths.iter().sum::<f32>() + COLUMN_SPACING * (column_widths.len() - 1) as f32;
+        let offset_x = (ui.io().display_size[0] - total_width) / 2.0;
+        let mut offset_y = ui.io().display_size[1] * 0.15;
+
+        let render_row = |offset_y: f32, color: [f32; 4], columns: &[&str]| {
+            let mut column_x = offset_x;
+            for (text, width) in columns.iter().zip(column_widths.iter()) {
+                ui.set_cursor_pos([column_x, offset_y]);
+                ui.text_colored(color, text);
+                column_x += width + COLUMN_SPACING;
+            }
+        };
+
+        render_row(
+            offset_y,
+            [1.0, 0.76, 0.03, 1.0],
+            &header.each_ref().map(|name| name.as_ref()),
+        );
+        offset_y += ui.text_line_height_with_spacing();
+
+        for (color, columns) in rows.iter() {
+            let columns = columns.each_ref().map(|text| text.as_str());
+            render_row(offset_y, *color, &columns);
+            offset_y += ui.text_line_height_with_spacing();
+        }
+
+        Ok(())
+    }
+}
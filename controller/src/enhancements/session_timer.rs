@@ -0,0 +1,150 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use utils_state::StateRegistry;
+
+use super::Enhancement;
+use crate::{
+    i18n::tr,
+    settings::{
+        save_playtime_history,
+        AppSettings,
+        PlaytimeHistory,
+    },
+    UpdateContext,
+};
+
+/// Interval in which the playtime history will be saved
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Duration how long a break reminder will be shown
+const REMINDER_DURATION: Duration = Duration::from_secs(30);
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
+pub struct SessionTimer {
+    session_start: Instant,
+    last_update: Instant,
+    last_save: Instant,
+
+    /// Session duration at which the next break reminder will be shown
+    next_reminder: Duration,
+    reminder_visible_until: Option<Instant>,
+
+    session_duration: Duration,
+    today_playtime: Duration,
+}
+
+impl SessionTimer {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            session_start: now,
+            last_update: now,
+            last_save: now,
+
+            next_reminder: Duration::ZERO,
+            reminder_visible_until: None,
+
+            session_duration: Duration::ZERO,
+            today_playtime: Duration::ZERO,
+        }
+    }
+}
+
+impl Enhancement for SessionTimer {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let now = ctx.clock.now();
+        let elapsed = now.duration_since(self.last_update);
+        self.last_update = now;
+        self.session_duration = now.duration_since(self.session_start);
+
+        {
+            let today = chrono::Local::now().date_naive();
+            let mut history = ctx.states.resolve_mut::<PlaytimeHistory>(())?;
+            history.add_playtime(today, elapsed);
+            self.today_playtime = history.playtime(today);
+
+            if now.duration_since(self.last_save) > SAVE_INTERVAL {
+                self.last_save = now;
+                if let Err(error) = save_playtime_history(&history) {
+                    log::warn!("保存游戏时长记录失败: {:#}", error);
+                }
+            }
+        }
+
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if settings.session_break_interval == 0 {
+            self.next_reminder = Duration::ZERO;
+            self.reminder_visible_until = None;
+            return Ok(());
+        }
+
+        let interval = Duration::from_secs(settings.session_break_interval as u64 * 60);
+        if self.next_reminder.is_zero() {
+            self.next_reminder = interval;
+        }
+
+        if self.session_duration >= self.next_reminder {
+            self.reminder_visible_until = Some(now + REMINDER_DURATION);
+            while self.next_reminder <= self.session_duration {
+                self.next_reminder += interval;
+            }
+        }
+
+        if self
+            .reminder_visible_until
+            .map(|until| until <= now)
+            .unwrap_or(false)
+        {
+            self.reminder_visible_until = None;
+        }
+
+        Ok(())
+    }
+
+    fn render(&self, states: &StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let settings = states.resolve::<AppSettings>(())?;
+
+        if self.reminder_visible_until.is_some() {
+            let text = tr!("你已经连续游戏 {} 了，该休息一下了!")
+                .replace("{}", &format_duration(self.session_duration));
+            let [text_width, _] = ui.calc_text_size(&text);
+            ui.set_cursor_pos([
+                (ui.io().display_size[0] - text_width) / 2.0,
+                ui.io().display_size[1] * 0.2,
+            ]);
+            ui.text_colored([1.0, 0.76, 0.03, 1.0], &text);
+        }
+
+        if settings.session_timer {
+            let offset_x = ui.io().display_size[0] * 0.01;
+            let offset_y = ui.io().display_size[1] * 0.9 - ui.text_line_height_with_spacing() * 2.0;
+
+            ui.set_cursor_pos([offset_x, offset_y]);
+            ui.text(format!(
+                "{}: {}",
+                tr!("本次游戏"),
+                format_duration(self.session_duration)
+            ));
+            ui.set_cursor_pos([offset_x, offset_y + ui.text_line_height_with_spacing()]);
+            ui.text(format!(
+                "{}: {}",
+                tr!("今日游戏"),
+                format_duration(self.today_playtime)
+            ));
+        }
+
+        Ok(())
+    }
+}
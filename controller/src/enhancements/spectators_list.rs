@@ -1,49 +1,263 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
 use cs2::{
     LocalCameraControllerTarget,
     SpectatorList,
 };
+use overlay::TextureUploader;
 
 use super::Enhancement;
-use crate::settings::AppSettings;
+use crate::{
+    audio::{
+        play_cue,
+        AudioCue,
+    },
+    i18n::tr,
+    settings::{
+        AppSettings,
+        HotKey,
+        HotKeyAction,
+        ScreenAnchor,
+    },
+    steam_profile::SteamProfileCache,
+    UpdateContext,
+};
+
+/// Duration of the fade in/out animation of a spectator row
+const FADE_DURATION: Duration = Duration::from_millis(250);
+
+/// Distance to the screen edges (% of the screen size)
+const SCREEN_MARGIN: f32 = 0.01;
+
+const HOTKEY_SPECTATORS_LIST: HotKeyAction = HotKeyAction {
+    id: "spectators_list",
+    name: "切换旁观者名单",
+    default_key: None,
+};
+
+/// Key of the in game scoreboard. Used to hide the spectators list while the scoreboard is shown.
+const HOTKEY_GAME_SCOREBOARD: HotKeyAction = HotKeyAction {
+    id: "game_scoreboard",
+    name: "游戏记分板 (按住)",
+    default_key: Some(HotKey::new(imgui::Key::Tab)),
+};
+
+struct SpectatorRow {
+    spectator_name: String,
+    steam_id: u64,
+    visible_since: Instant,
+    hidden_since: Option<Instant>,
+
+    /// Current opacity of the row
+    alpha: f32,
+}
+
+pub struct SpectatorsListIndicator {
+    rows: Vec<SpectatorRow>,
+    hidden: bool,
+    steam_profiles: SteamProfileCache,
+}
 
-pub struct SpectatorsListIndicator;
 impl SpectatorsListIndicator {
-    pub fn new() -> Self {
-        Self
+    pub fn new(texture_uploader: TextureUploader) -> Self {
+        Self {
+            rows: Vec::new(),
+            hidden: false,
+            steam_profiles: SteamProfileCache::new(texture_uploader),
+        }
+    }
+
+    /// Update the rows with the current spectators.
+    /// Returns true if a new spectator has been added.
+    fn update_rows(&mut self, spectators: &[(String, u64)], now: Instant, fade: bool) -> bool {
+        for row in self.rows.iter_mut() {
+            let visible = spectators
+                .iter()
+                .any(|(name, _)| *name == row.spectator_name);
+            if visible && row.hidden_since.is_some() {
+                /* the spectator returned while fading out */
+                row.visible_since = now;
+                row.hidden_since = None;
+            } else if !visible && row.hidden_since.is_none() {
+                row.hidden_since = Some(now);
+            }
+        }
+
+        let mut spectator_added = false;
+        for (spectator_name, steam_id) in spectators {
+            if self
+                .rows
+                .iter()
+                .any(|row| row.spectator_name == *spectator_name)
+            {
+                continue;
+            }
+
+            spectator_added = true;
+            self.rows.push(SpectatorRow {
+                spectator_name: spectator_name.clone(),
+                steam_id: *steam_id,
+                visible_since: now,
+                hidden_since: None,
+                alpha: 0.0,
+            });
+        }
+
+        let fade_progress =
+            |since: Instant| now.duration_since(since).as_secs_f32() / FADE_DURATION.as_secs_f32();
+        self.rows.retain_mut(|row| {
+            row.alpha = match (fade, row.hidden_since) {
+                (false, None) => 1.0,
+                (false, Some(_)) => 0.0,
+                (true, None) => fade_progress(row.visible_since).min(1.0),
+                (true, Some(hidden_since)) => 1.0 - fade_progress(hidden_since),
+            };
+
+            row.alpha > 0.0
+        });
+
+        spectator_added
     }
 }
 
 impl Enhancement for SpectatorsListIndicator {
-    fn update(&mut self, _ctx: &crate::UpdateContext) -> anyhow::Result<()> {
+    fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if !settings.spectators_list {
+            self.rows.clear();
+            return Ok(());
+        }
+
+        self.hidden = settings.spectators_list_hide_on_scoreboard
+            && settings
+                .hotkey(&HOTKEY_GAME_SCOREBOARD)
+                .map(|key| key.is_down(ctx.input))
+                .unwrap_or(false);
+
+        let view_target = ctx.states.resolve::<LocalCameraControllerTarget>(())?;
+        let spectators = match &view_target.target_entity_id {
+            Some(target_entity_id) => ctx
+                .states
+                .resolve::<SpectatorList>(*target_entity_id)?
+                .spectators
+                .iter()
+                .map(|spectator| (spectator.spectator_name.clone(), spectator.steam_id))
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        let spectator_added =
+            self.update_rows(&spectators, ctx.clock.now(), settings.spectators_list_fade);
+        if spectator_added && settings.spectators_list_sound {
+            play_cue(AudioCue::SpectatorJoined, settings.audio_volume);
+        }
         Ok(())
     }
 
+    fn hotkey_actions(&self) -> &'static [HotKeyAction] {
+        &[HOTKEY_SPECTATORS_LIST, HOTKEY_GAME_SCOREBOARD]
+    }
+
+    fn on_hotkey_pressed(&mut self, action: &HotKeyAction, settings: &mut AppSettings) -> bool {
+        if action.id != HOTKEY_SPECTATORS_LIST.id {
+            return false;
+        }
+
+        settings.spectators_list = !settings.spectators_list;
+        true
+    }
+
     fn render(&self, states: &utils_state::StateRegistry, ui: &imgui::Ui) -> anyhow::Result<()> {
         let settings = states.resolve::<AppSettings>(())?;
-        if !settings.spectators_list {
+        if !settings.spectators_list || self.hidden || self.rows.is_empty() {
             return Ok(());
         }
 
-        let view_target = states.resolve::<LocalCameraControllerTarget>(())?;
-        let target_entity_id = match &view_target.target_entity_id {
-            Some(value) => *value,
-            None => return Ok(()),
+        let max_rows = settings.spectators_list_max_rows as usize;
+        let (rows, hidden_rows) = if max_rows > 0 && self.rows.len() > max_rows {
+            (&self.rows[..max_rows], self.rows.len() - max_rows)
+        } else {
+            (&self.rows[..], 0)
         };
-        let spectators = states.resolve::<SpectatorList>(target_entity_id)?;
 
-        let group = ui.begin_group();
+        let steam_profiles =
+            settings.spectators_list_steam_profiles && !settings.steam_web_api_key.is_empty();
+        let mut lines = rows
+            .iter()
+            .map(|row| {
+                let profile = if steam_profiles {
+                    self.steam_profiles
+                        .lookup(row.steam_id, &settings.steam_web_api_key)
+                } else {
+                    None
+                };
+
+                match profile {
+                    Some(profile) => (
+                        profile.persona_name.clone(),
+                        profile
+                            .avatar
+                            .as_ref()
+                            .and_then(|avatar| avatar.texture_id()),
+                        row.alpha,
+                    ),
+                    None => (row.spectator_name.clone(), None, row.alpha),
+                }
+            })
+            .collect::<Vec<_>>();
+        if hidden_rows > 0 {
+            lines.push((format!("+{} {}", hidden_rows, tr!("更多")), None, 1.0));
+        }
+
+        let line_height = ui.text_line_height_with_spacing();
+        let avatar_size = ui.text_line_height();
+        let avatar_width = if steam_profiles {
+            avatar_size + ui.clone_style().item_spacing[0]
+        } else {
+            0.0
+        };
+
+        let text_height = line_height * lines.len() as f32;
+        let text_width = lines
+            .iter()
+            .map(|(text, _, _)| ui.calc_text_size(text)[0])
+            .fold(0.0, f32::max)
+            + avatar_width;
+
+        let [screen_width, screen_height] = ui.io().display_size;
+        let margin_x = screen_width * SCREEN_MARGIN;
+        let margin_y = screen_height * SCREEN_MARGIN;
+        let (align_right, offset_y) = match settings.spectators_list_anchor {
+            ScreenAnchor::TopLeft => (false, margin_y),
+            ScreenAnchor::TopRight => (true, margin_y),
+            ScreenAnchor::Left => (false, (screen_height - text_height) * 0.5),
+            ScreenAnchor::Right => (true, (screen_height - text_height) * 0.5),
+            ScreenAnchor::BottomLeft => (false, screen_height - margin_y - text_height),
+            ScreenAnchor::BottomRight => (true, screen_height - margin_y - text_height),
+        };
+        let offset_x = if align_right {
+            screen_width - margin_x - text_width
+        } else {
+            margin_x
+        };
 
-        let line_count = spectators.spectators.iter().count();
-        let text_height = ui.text_line_height_with_spacing() * line_count as f32;
+        let group = ui.begin_group();
 
-        let offset_x = ui.io().display_size[0] * 0.01;
-        let offset_y = (ui.io().display_size[1] - text_height) * 0.5;
         let mut offset_y = offset_y;
+        for (text, avatar, alpha) in lines {
+            if let Some(avatar) = avatar {
+                ui.set_cursor_pos([offset_x, offset_y]);
+                imgui::Image::new(avatar, [avatar_size, avatar_size])
+                    .tint_col([1.0, 1.0, 1.0, alpha])
+                    .build(ui);
+            }
 
-        for spectator in &spectators.spectators {
-            ui.set_cursor_pos([offset_x, offset_y]);
-            ui.text(&spectator.spectator_name);
-            offset_y += ui.text_line_height_with_spacing();
+            ui.set_cursor_pos([offset_x + avatar_width, offset_y]);
+            ui.text_colored([1.0, 1.0, 1.0, alpha], &text);
+            offset_y += line_height;
         }
 
         group.end();
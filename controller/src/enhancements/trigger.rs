@@ -1,7 +1,14 @@
 use std::time::Instant;
 
 use anyhow::Context;
-use cs2::EntitySystem;
+use cs2::{
+    EntitySystem,
+    GameRules,
+    LocalCameraControllerTarget,
+    PlayerPawnState,
+    RoundPhase,
+    WeaponId,
+};
 use cs2_schema_generated::{
     cs2::client::C_CSPlayerPawn,
     EntityHandle,
@@ -16,24 +23,54 @@ use valthrun_kernel_interface::MouseState;
 
 use super::Enhancement;
 use crate::{
-    settings::AppSettings,
+    audio::{
+        play_cue,
+        AudioCue,
+    },
+    settings::{
+        AppSettings,
+        HotKey,
+        HotKeyAction,
+        TriggerBotProfile,
+        TriggerBotWarmupMode,
+        TriggerBotWeaponClass,
+    },
     view::{
+        AimTarget,
         KeyToggle,
+        KeyToggleTimings,
         LocalCrosshair,
     },
+    MetricsClient,
     UpdateContext,
 };
 
 enum TriggerState {
     Idle,
-    Pending { delay: u32, timestamp: Instant },
-    Active,
+    Pending {
+        delay: u32,
+        burst: u32,
+        timestamp: Instant,
+    },
+    Active {
+        burst: u32,
+        timestamp: Instant,
+    },
+}
+
+/// Weapon of the local player and whether the player is scoped in
+struct LocalWeapon {
+    weapon: WeaponId,
+    scoped: bool,
 }
 
 pub struct TriggerBot {
     toggle: KeyToggle,
     state: TriggerState,
     trigger_active: bool,
+
+    /// The match is currently in the warmup or a knife round
+    warmup_active: bool,
 }
 
 impl TriggerBot {
@@ -42,11 +79,92 @@ impl TriggerBot {
             toggle: KeyToggle::new(),
             state: TriggerState::Idle,
             trigger_active: false,
+
+            warmup_active: false,
         }
     }
 
-    fn should_be_active(&self, ctx: &UpdateContext) -> anyhow::Result<bool> {
-        let settings = ctx.states.resolve::<AppSettings>(())?;
+    fn update_warmup_state(&mut self, ctx: &UpdateContext) {
+        /* the game rules are not available while not connected to a match */
+        let warmup_active = ctx
+            .states
+            .resolve::<GameRules>(())
+            .map(|rules| rules.phase == RoundPhase::Warmup || rules.knife_round)
+            .unwrap_or(false);
+
+        if warmup_active != self.warmup_active {
+            self.warmup_active = warmup_active;
+            if warmup_active {
+                log::debug!("检测到热身或刀局, 切换自动开火设置");
+            } else {
+                log::debug!("比赛开始, 恢复自动开火设置");
+            }
+        }
+    }
+
+    /// Get the settings for the current match phase and weapon class.
+    /// Returns `None` if the trigger bot should be disabled.
+    fn active_profile(
+        &self,
+        settings: &AppSettings,
+        local_weapon: Option<&LocalWeapon>,
+    ) -> Option<TriggerBotProfile> {
+        let weapon_class = local_weapon
+            .and_then(|local_weapon| TriggerBotWeaponClass::from_weapon(local_weapon.weapon));
+        if !self.warmup_active {
+            return Some(settings.trigger_bot_class_profile(weapon_class));
+        }
+
+        match settings.trigger_bot_warmup_mode {
+            TriggerBotWarmupMode::Unchanged => {
+                Some(settings.trigger_bot_class_profile(weapon_class))
+            }
+            TriggerBotWarmupMode::Disabled => None,
+            TriggerBotWarmupMode::WarmupProfile => Some(settings.trigger_bot_warmup_profile),
+        }
+    }
+
+    /// Resolve the weapon of the local player.
+    /// Returns `None` if the local player is not alive or spectating someone else.
+    fn local_weapon(&self, ctx: &UpdateContext) -> anyhow::Result<Option<LocalWeapon>> {
+        let view_target = ctx.states.resolve::<LocalCameraControllerTarget>(())?;
+        let local_pawn_id = match view_target.target_entity_id {
+            Some(entity_id) if view_target.is_local_entity => entity_id,
+            _ => return Ok(None),
+        };
+
+        let local_pawn = ctx.states.resolve::<PlayerPawnState>(local_pawn_id)?;
+        Ok(match &*local_pawn {
+            PlayerPawnState::Alive(info) => Some(LocalWeapon {
+                weapon: info.weapon,
+                scoped: info.player_is_scoped,
+            }),
+            PlayerPawnState::Dead => None,
+        })
+    }
+
+    /// Check if the weapon of the local player is enabled in the weapon matrix
+    fn weapon_allowed(&self, settings: &AppSettings, local_weapon: Option<&LocalWeapon>) -> bool {
+        if settings.trigger_bot_weapons.is_unrestricted() {
+            return true;
+        }
+
+        let local_weapon = match local_weapon {
+            Some(local_weapon) => local_weapon,
+            None => return false,
+        };
+
+        match settings.trigger_bot_weapons.rule(local_weapon.weapon) {
+            Some(rule) => rule.enabled && (!rule.require_scope || local_weapon.scoped),
+            None => true,
+        }
+    }
+
+    fn should_be_active(
+        &self,
+        ctx: &UpdateContext,
+        profile: &TriggerBotProfile,
+    ) -> anyhow::Result<bool> {
         let crosshair = ctx.states.resolve::<LocalCrosshair>(())?;
         let entities = ctx.states.resolve::<EntitySystem>(())?;
 
@@ -64,7 +182,7 @@ impl TriggerBot {
             return Ok(false);
         }
 
-        if settings.trigger_bot_team_check {
+        if profile.team_check {
             let crosshair_entity = entities
                 .get_by_handle(&EntityHandle::<C_CSPlayerPawn>::from_index(
                     target.entity_id,
@@ -86,31 +204,93 @@ impl TriggerBot {
             }
         }
 
+        let settings = ctx.states.resolve::<AppSettings>(())?;
+        if settings.trigger_bot_selected_target_only {
+            let aim_target = ctx.states.resolve::<AimTarget>(())?;
+            if aim_target.target.as_ref().map(|target| target.entity_id) != Some(target.entity_id) {
+                return Ok(false);
+            }
+        }
+
         Ok(true)
     }
 }
 
+pub const HOTKEY_TRIGGER_BOT: HotKeyAction = HotKeyAction {
+    id: "trigger_bot",
+    name: "自动开火热键",
+    default_key: Some(HotKey::new(imgui::Key::MouseMiddle)),
+};
+
 impl Enhancement for TriggerBot {
+    fn hotkey_actions(&self) -> &'static [HotKeyAction] {
+        &[HOTKEY_TRIGGER_BOT]
+    }
+
+    fn injects_input(&self) -> bool {
+        true
+    }
+
+    fn suspend_input(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
+        self.state = TriggerState::Idle;
+        if self.trigger_active {
+            self.trigger_active = false;
+
+            let mut state = MouseState {
+                ..Default::default()
+            };
+            state.buttons[0] = Some(false);
+            ctx.memory.send_mouse_state(&[state])?;
+        }
+
+        Ok(())
+    }
+
     fn update(&mut self, ctx: &UpdateContext) -> anyhow::Result<()> {
         let settings = ctx.states.resolve::<AppSettings>(())?;
         if self.toggle.update(
             &settings.trigger_bot_mode,
             ctx.input,
-            &settings.key_trigger_bot,
+            settings.hotkey(&HOTKEY_TRIGGER_BOT),
+            &KeyToggleTimings::from_settings(&settings),
         ) {
-            ctx.cs2.add_metrics_record(
+            ctx.memory.add_metrics_record(
                 obfstr!("feature-trigger-bot-toggle"),
                 &format!(
                     "enabled: {}, mode: {:?}",
                     self.toggle.enabled, settings.trigger_bot_mode
                 ),
             );
+
+            if settings.trigger_bot_sound {
+                let cue = if self.toggle.enabled {
+                    AudioCue::TriggerBotEnabled
+                } else {
+                    AudioCue::TriggerBotDisabled
+                };
+                play_cue(cue, settings.audio_volume);
+            }
         }
 
-        let should_shoot: bool = if self.toggle.enabled {
-            self.should_be_active(ctx)?
+        self.update_warmup_state(ctx);
+
+        /* the local weapon is only required for weapon specific settings */
+        let local_weapon = if self.toggle.enabled
+            && (!settings.trigger_bot_weapons.is_unrestricted()
+                || settings.trigger_bot_class_timings.any_enabled())
+        {
+            self.local_weapon(ctx)?
         } else {
-            false
+            None
+        };
+        let profile = self.active_profile(&settings, local_weapon.as_ref());
+
+        let should_shoot: bool = match &profile {
+            Some(profile) if self.toggle.enabled => {
+                self.should_be_active(ctx, profile)?
+                    && self.weapon_allowed(&settings, local_weapon.as_ref())
+            }
+            _ => false,
         };
 
         loop {
@@ -121,12 +301,10 @@ impl Enhancement for TriggerBot {
                         break;
                     }
 
-                    let delay_min = settings
-                        .trigger_bot_delay_min
-                        .min(settings.trigger_bot_delay_max);
-                    let delay_max = settings
-                        .trigger_bot_delay_min
-                        .max(settings.trigger_bot_delay_max);
+                    /* should_shoot is only set if there is an active profile */
+                    let profile = profile.unwrap_or_else(|| settings.trigger_bot_profile());
+                    let delay_min = profile.delay_min.min(profile.delay_max);
+                    let delay_max = profile.delay_min.max(profile.delay_max);
                     let selected_delay = if delay_max == delay_min {
                         delay_min
                     } else {
@@ -140,11 +318,16 @@ impl Enhancement for TriggerBot {
                     );
                     self.state = TriggerState::Pending {
                         delay: selected_delay,
-                        timestamp: Instant::now(),
+                        burst: profile.burst,
+                        timestamp: ctx.clock.now(),
                     };
                 }
-                TriggerState::Pending { delay, timestamp } => {
-                    let time_elapsed = timestamp.elapsed().as_millis();
+                TriggerState::Pending {
+                    delay,
+                    burst,
+                    timestamp,
+                } => {
+                    let time_elapsed = ctx.clock.now().duration_since(*timestamp).as_millis();
                     if time_elapsed < *delay as u128 {
                         /* still waiting to be activated */
                         break;
@@ -153,23 +336,31 @@ impl Enhancement for TriggerBot {
                     if settings.trigger_bot_check_target_after_delay && !should_shoot {
                         self.state = TriggerState::Idle;
                     } else {
-                        self.state = TriggerState::Active;
+                        self.state = TriggerState::Active {
+                            burst: *burst,
+                            timestamp: ctx.clock.now(),
+                        };
                     }
                     /* regardsless of the next state, we always need to execute the current action */
                     break;
                 }
-                TriggerState::Active => {
-                    if should_shoot {
-                        /* nothing changed */
-                        break;
+                TriggerState::Active { burst, timestamp } => {
+                    if !should_shoot {
+                        self.state = TriggerState::Idle;
+                        continue;
                     }
 
-                    self.state = TriggerState::Idle;
+                    let time_elapsed = ctx.clock.now().duration_since(*timestamp).as_millis();
+                    if *burst > 0 && time_elapsed >= *burst as u128 {
+                        /* release the trigger, the next burst will be delayed again */
+                        self.state = TriggerState::Idle;
+                    }
+                    break;
                 }
             }
         }
 
-        let should_be_active = matches!(self.state, TriggerState::Active);
+        let should_be_active = matches!(self.state, TriggerState::Active { .. });
         if should_be_active != self.trigger_active {
             self.trigger_active = should_be_active;
 
@@ -177,7 +368,7 @@ impl Enhancement for TriggerBot {
                 ..Default::default()
             };
             state.buttons[0] = Some(self.trigger_active);
-            ctx.cs2.send_mouse_state(&[state])?;
+            ctx.memory.send_mouse_state(&[state])?;
             log::trace!("Setting shoot state to {}", self.trigger_active);
         }
 
@@ -188,3 +379,170 @@ impl Enhancement for TriggerBot {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::{
+            Cell,
+            RefCell,
+        },
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    use utils_state::StateRegistry;
+    use valthrun_kernel_interface::MouseState;
+
+    use super::TriggerBot;
+    use crate::{
+        enhancements::Enhancement,
+        settings::{
+            AppSettings,
+            KeyToggleMode,
+        },
+        view::{
+            CrosshairTarget,
+            LocalCrosshair,
+        },
+        Clock,
+        GameMemory,
+        KeyboardInput,
+        MetricsClient,
+        UpdateContext,
+    };
+
+    struct FakeClock(Cell<Instant>);
+    impl FakeClock {
+        fn advance(&self, millis: u64) {
+            self.0.set(self.0.get() + Duration::from_millis(millis));
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.0.get()
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeMemory {
+        shoot_states: RefCell<Vec<bool>>,
+    }
+
+    impl MetricsClient for FakeMemory {
+        fn add_metrics_record(&self, _record_type: &str, _record_payload: &str) {}
+    }
+
+    impl GameMemory for FakeMemory {
+        fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
+            let mut shoot_states = self.shoot_states.borrow_mut();
+            shoot_states.extend(states.iter().filter_map(|state| state.buttons[0]));
+            Ok(())
+        }
+    }
+
+    struct NoInput;
+    impl KeyboardInput for NoInput {
+        fn is_key_down(&self, _key: imgui::Key) -> bool {
+            false
+        }
+
+        fn is_key_pressed(&self, _key: imgui::Key, _repeating: bool) -> bool {
+            false
+        }
+    }
+
+    fn create_states(target: Option<&str>) -> StateRegistry {
+        let mut settings: AppSettings = serde_yaml::from_str("").unwrap();
+        settings.trigger_bot_mode = KeyToggleMode::AlwaysOn;
+        settings.trigger_bot_team_check = false;
+        settings.trigger_bot_delay_min = 10;
+        settings.trigger_bot_delay_max = 10;
+
+        let mut states = StateRegistry::new(16);
+        states.set(settings, ()).unwrap();
+        states
+            .set(
+                LocalCrosshair::new(target.map(|entity_type| CrosshairTarget {
+                    entity_id: 1,
+                    entity_type: Some(entity_type.to_string()),
+                    timestamp: Instant::now(),
+                })),
+                (),
+            )
+            .unwrap();
+        states
+    }
+
+    #[test]
+    fn shoots_after_delay() {
+        let states = create_states(Some("C_CSPlayerPawn"));
+        let memory = FakeMemory::default();
+        let clock = FakeClock(Cell::new(Instant::now()));
+        let ctx = UpdateContext {
+            input: &NoInput,
+            states: &states,
+            memory: &memory,
+            clock: &clock,
+        };
+
+        let mut trigger_bot = TriggerBot::new();
+        trigger_bot.update(&ctx).unwrap();
+        assert!(memory.shoot_states.borrow().is_empty());
+
+        clock.advance(5);
+        trigger_bot.update(&ctx).unwrap();
+        assert!(memory.shoot_states.borrow().is_empty());
+
+        clock.advance(5);
+        trigger_bot.update(&ctx).unwrap();
+        assert_eq!(*memory.shoot_states.borrow(), vec![true]);
+    }
+
+    #[test]
+    fn releases_after_burst() {
+        let states = create_states(Some("C_CSPlayerPawn"));
+        states.get_mut::<AppSettings>(()).unwrap().trigger_bot_burst = 20;
+
+        let memory = FakeMemory::default();
+        let clock = FakeClock(Cell::new(Instant::now()));
+        let ctx = UpdateContext {
+            input: &NoInput,
+            states: &states,
+            memory: &memory,
+            clock: &clock,
+        };
+
+        let mut trigger_bot = TriggerBot::new();
+        for _ in 0..6 {
+            trigger_bot.update(&ctx).unwrap();
+            clock.advance(10);
+        }
+
+        assert_eq!(*memory.shoot_states.borrow(), vec![true, false, true]);
+    }
+
+    #[test]
+    fn ignores_non_player_targets() {
+        let states = create_states(Some("C_Chicken"));
+        let memory = FakeMemory::default();
+        let clock = FakeClock(Cell::new(Instant::now()));
+        let ctx = UpdateContext {
+            input: &NoInput,
+            states: &states,
+            memory: &memory,
+            clock: &clock,
+        };
+
+        let mut trigger_bot = TriggerBot::new();
+        for _ in 0..3 {
+            trigger_bot.update(&ctx).unwrap();
+            clock.advance(10);
+        }
+
+        assert!(memory.shoot_states.borrow().is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use anyhow::Context;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Language of the user interface.
+/// All strings are written in Simplified Chinese and translated at runtime
+/// using the language files located in `resources/lang`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ChineseSimplified,
+
+    #[serde(rename = "en-US")]
+    English,
+}
+
+impl Language {
+    pub const VARIANTS: [Language; 2] = [Self::ChineseSimplified, Self::English];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ChineseSimplified => "zh-CN",
+            Self::English => "en-US",
+        }
+    }
+
+    /// Name of the language in the language itself
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::ChineseSimplified => "简体中文",
+            Self::English => "English",
+        }
+    }
+
+    fn embedded_translations(&self) -> Option<&'static str> {
+        match self {
+            Self::ChineseSimplified => None,
+            Self::English => Some(include_str!("../resources/lang/en-US.json")),
+        }
+    }
+}
+
+/// Translations of the source (Simplified Chinese) strings
+type Translations = HashMap<String, String>;
+
+struct ActiveLanguage {
+    language: Language,
+    translations: Arc<Translations>,
+}
+
+static ACTIVE_LANGUAGE: RwLock<Option<ActiveLanguage>> = RwLock::new(None);
+
+/// Language files placed in `lang/<code>.json` next to the executable
+/// extend or override the embedded translations.
+fn get_external_language_file(language: Language) -> anyhow::Result<PathBuf> {
+    let exe_file = std::env::current_exe().context("missing current exe path")?;
+    let base_dir = exe_file.parent().context("could not get exe directory")?;
+    Ok(base_dir
+        .join("lang")
+        .join(format!("{}.json", language.code())))
+}
+
+fn load_translations(language: Language) -> Translations {
+    let mut translations = match language.embedded_translations() {
+        Some(content) => serde_json::from_str(content).expect("valid embedded language file"),
+        None => Translations::new(),
+    };
+
+    let external_file = match get_external_language_file(language) {
+        Ok(file) if file.is_file() => file,
+        _ => return translations,
+    };
+
+    let external_translations = std::fs::read_to_string(&external_file)
+        .context("read")
+        .and_then(|content| serde_json::from_str::<Translations>(&content).context("parse"));
+    match external_translations {
+        Ok(external_translations) => {
+            log::debug!(
+                "已加载语言文件 {} ({} 条)",
+                external_file.to_string_lossy(),
+                external_translations.len()
+            );
+            translations.extend(external_translations);
+        }
+        Err(error) => log::warn!(
+            "加载语言文件 {} 失败: {:#}",
+            external_file.to_string_lossy(),
+            error
+        ),
+    }
+
+    translations
+}
+
+/// Switch the language of all translated strings
+pub fn set_language(language: Language) {
+    let translations = Arc::new(load_translations(language));
+    log::debug!("界面语言设置为 {}", language.code());
+
+    *ACTIVE_LANGUAGE.write().unwrap() = Some(ActiveLanguage {
+        language,
+        translations,
+    });
+}
+
+pub fn language() -> Language {
+    ACTIVE_LANGUAGE
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|active| active.language)
+        .unwrap_or_default()
+}
+
+fn lookup(translations: &Translations, text: &str) -> Option<String> {
+    /* Keep imgui ids (everything after ##) untouched */
+    let (visible, id) = match text.find("##") {
+        Some(index) => text.split_at(index),
+        None => (text, ""),
+    };
+
+    let translated = translations.get(visible)?;
+    Some(format!("{}{}", translated, id))
+}
+
+/// Translate a source string into the active language.
+/// Strings without a translation will be returned unchanged.
+pub fn translate(text: &str) -> Cow<'_, str> {
+    let active = ACTIVE_LANGUAGE.read().unwrap();
+    let translations = match active.as_ref() {
+        Some(active) if !active.translations.is_empty() => &active.translations,
+        _ => return Cow::Borrowed(text),
+    };
+
+    match lookup(translations, text) {
+        Some(translated) => Cow::Owned(translated),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// Obfuscate and translate a string literal
+macro_rules! tr {
+    ($text:literal) => {
+        $crate::i18n::translate(obfstr::obfstr!($text))
+    };
+}
+pub(crate) use tr;
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{
+        lookup,
+        Language,
+        Translations,
+    };
+
+    /// Collect the string literals passed to `tr!` within the given source
+    fn collect_translated_literals(source: &str, literals: &mut Vec<String>) {
+        /* split up so the scanner does not pick up its own pattern */
+        const MACRO_START: &str = concat!("tr", "!(");
+
+        let mut remaining = source;
+        while let Some(index) = remaining.find(MACRO_START) {
+            let is_macro_start = remaining[..index]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+            remaining = remaining[index + MACRO_START.len()..].trim_start();
+            if !is_macro_start || !remaining.starts_with('"') {
+                continue;
+            }
+
+            let mut literal = String::new();
+            let mut chars = remaining[1..].char_indices();
+            while let Some((offset, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        remaining = &remaining[offset + 2..];
+                        break;
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, 'n')) => literal.push('\n'),
+                        Some((_, 't')) => literal.push('\t'),
+                        Some((_, '\n')) => {
+                            /* line continuation, skip leading whitespace of the next line */
+                            while chars.clone().next().is_some_and(|(_, c)| c.is_whitespace()) {
+                                chars.next();
+                            }
+                        }
+                        Some((_, escaped)) => literal.push(escaped),
+                        None => break,
+                    },
+                    c => literal.push(c),
+                }
+            }
+
+            literals.push(literal);
+        }
+    }
+
+    fn collect_source_literals(directory: &Path, literals: &mut Vec<String>) {
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_source_literals(&path, literals);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                collect_translated_literals(&std::fs::read_to_string(&path).unwrap(), literals);
+            }
+        }
+    }
+
+    #[test]
+    fn embedded_language_files() {
+        for language in Language::VARIANTS {
+            if let Some(content) = language.embedded_translations() {
+                serde_json::from_str::<Translations>(content).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_imgui_ids() {
+        let translations = Translations::from([("声音提示".to_string(), "Sound".to_string())]);
+        assert_eq!(
+            lookup(&translations, "声音提示##proximity_alert").as_deref(),
+            Some("Sound##proximity_alert")
+        );
+        assert_eq!(lookup(&translations, "其他"), None);
+    }
+
+    #[test]
+    fn translates_all_strings() {
+        let mut literals = Vec::new();
+        collect_source_literals(
+            Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src")),
+            &mut literals,
+        );
+        assert!(!literals.is_empty());
+
+        for language in Language::VARIANTS {
+            let translations = match language.embedded_translations() {
+                Some(content) => serde_json::from_str::<Translations>(content).unwrap(),
+                None => continue,
+            };
+
+            let mut missing = literals
+                .iter()
+                .filter(|literal| lookup(&translations, literal).is_none())
+                .collect::<Vec<_>>();
+            missing.sort();
+            missing.dedup();
+            assert!(
+                missing.is_empty(),
+                "missing {} translations: {:?}",
+                language.code(),
+                missing
+            );
+        }
+    }
+}
@@ -0,0 +1,1807 @@
+//! Valthrun-CHS overlay controller.
+//! The overlay can be embedded into other applications using [`run_with_options`].
+
+#![allow(dead_code)]
+#![feature(const_fn_floating_point_arithmetic)]
+
+use std::{
+    cell::{
+        Ref,
+        RefCell,
+        RefMut,
+    },
+    error::Error,
+    mem,
+    net::SocketAddr,
+    path::{
+        Path,
+        PathBuf,
+    },
+    rc::Rc,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+use capture::ScreenshotCapture;
+use cloud_sync::CloudSync;
+use cs2::{
+    offsets_runtime,
+    BuildInfo,
+    CS2Handle,
+    CS2HandleState,
+    CS2Offsets,
+    CurrentMapState,
+    EntitySystem,
+    LocalCameraControllerTarget,
+};
+use enhancements::Enhancement;
+use imgui::{
+    Condition,
+    FontConfig,
+    FontGlyphRanges,
+    FontId,
+    FontSource,
+    TableFlags,
+    Ui,
+    WindowFlags,
+};
+use libloading::Library;
+use obfstr::obfstr;
+use overlay::{
+    notify,
+    FramePacingMode,
+    GlowSettings,
+    LoadingError,
+    MergedFont,
+    NotificationLevel,
+    OverlayColorSpace,
+    OverlayError,
+    OverlayMonitor,
+    OverlayOptions,
+    OverlayTarget,
+    PhysicalDeviceSelector,
+    RenderBackendType,
+    SystemRuntimeController,
+};
+use perf_stats::PerformanceStats;
+use radar::WebRadar;
+use read_monitor::ReadErrorMonitor;
+use remote::RemoteControl;
+use settings::{
+    get_profile_settings_path,
+    load_active_profile,
+    load_app_settings,
+    read_config_file,
+    save_active_profile,
+    AppSettings,
+    ConfigWatcher,
+    RenderBackend,
+    ScreenAnchor,
+    SettingsUI,
+    DEFAULT_PROFILE_NAME,
+    SECRET_SETTINGS,
+};
+use updater::UpdateChecker;
+use utils_state::{
+    StateCacheType,
+    StateRegistry,
+};
+use valthrun_kernel_interface::{
+    KInterfaceError,
+    MouseState,
+};
+use view::ViewController;
+use windows::{
+    core::PCSTR,
+    Win32::{
+        System::{
+            ApplicationInstallationAndServicing::{
+                ActivateActCtx,
+                CreateActCtxA,
+                ACTCTXA,
+            },
+            Console::GetConsoleProcessList,
+            LibraryLoader::GetModuleHandleA,
+        },
+        UI::Shell::IsUserAnAdmin,
+    },
+};
+
+use crate::{
+    enhancements::{
+        AimingAtYouIndicator,
+        AntiAimPunsh,
+        BombInfoIndicator,
+        DryRunMemory,
+        DryRunVisualizer,
+        FlashIndicator,
+        FovCircle,
+        MatchTimelineRecorder,
+        MovementHud,
+        PlayerESP,
+        ProximityAlert,
+        RecoilControl,
+        RoundInfoIndicator,
+        RoundStatsTracker,
+        Scoreboard,
+        SessionTimer,
+        SpectatorsListIndicator,
+        TriggerBot,
+    },
+    i18n::tr,
+    settings::{
+        save_app_settings,
+        save_playtime_history,
+        ColorSpace,
+        FpsLimitMode,
+        FramePacing,
+        PlaytimeHistory,
+    },
+    winver::version_info,
+};
+
+pub mod audio;
+mod cache;
+mod capture;
+mod cloud_sync;
+pub mod enhancements;
+pub mod i18n;
+mod perf_stats;
+mod radar;
+mod read_monitor;
+mod remote;
+pub mod settings;
+mod steam_profile;
+mod updater;
+mod utils;
+pub mod view;
+mod winver;
+
+/// How long notifications about config and connection changes are shown
+pub(crate) const NOTIFICATION_DURATION: Duration = Duration::from_secs(4);
+
+/// All metrics records sent by the controller (type, content)
+pub const METRICS_RECORD_TYPES: &[(&str, &str)] = &[
+    ("controller-status", "控制器版本、Git 提交和 Windows 版本"),
+    ("cs2-version", "CS2 的版本号"),
+    (
+        "settings-updated",
+        "修改后的设置 (不含密钥、云同步账号和窗口布局)",
+    ),
+    ("settings-toggled", "设置窗口是否打开"),
+    ("feature-esp-toggle", "ESP 的开关状态和模式"),
+    ("feature-trigger-bot-toggle", "自动开火的开关状态和模式"),
+];
+
+pub trait MetricsClient {
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str);
+}
+
+impl MetricsClient for CS2Handle {
+    fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
+        self.add_metrics_record(record_type, record_payload)
+    }
+}
+
+pub trait KeyboardInput {
+    fn is_key_down(&self, key: imgui::Key) -> bool;
+    fn is_key_pressed(&self, key: imgui::Key, repeating: bool) -> bool;
+}
+
+impl KeyboardInput for imgui::Ui {
+    fn is_key_down(&self, key: imgui::Key) -> bool {
+        Ui::is_key_down(self, key)
+    }
+
+    fn is_key_pressed(&self, key: imgui::Key, repeating: bool) -> bool {
+        if repeating {
+            Ui::is_key_pressed(self, key)
+        } else {
+            Ui::is_key_pressed_no_repeat(self, key)
+        }
+    }
+}
+
+/// Access to the game process for enhancements which need to interact with it
+pub trait GameMemory: MetricsClient {
+    fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()>;
+}
+
+impl GameMemory for CS2Handle {
+    fn send_mouse_state(&self, states: &[MouseState]) -> anyhow::Result<()> {
+        self.send_mouse_state(states)
+    }
+}
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+pub struct UpdateContext<'a> {
+    pub input: &'a dyn KeyboardInput,
+    pub states: &'a StateRegistry,
+    pub memory: &'a dyn GameMemory,
+    pub clock: &'a dyn Clock,
+}
+
+/// Reason why enhancements which send inputs to the game are currently suspended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSuspendReason {
+    /// Not connected to a match (e.g. main menu)
+    NotInGame,
+
+    /// The local player is dead
+    Dead,
+
+    /// The local player is spectating another player
+    Spectating,
+
+    /// The game window is minimized or hidden
+    GameHidden,
+}
+
+impl InputSuspendReason {
+    pub fn display_text(&self) -> String {
+        match self {
+            Self::NotInGame => tr!("不在游戏中"),
+            Self::Dead => tr!("玩家已死亡"),
+            Self::Spectating => tr!("正在观战"),
+            Self::GameHidden => tr!("游戏已最小化"),
+        }
+        .to_string()
+    }
+
+    /// Check if there is an alive local player pawn which can receive inputs
+    fn resolve(states: &StateRegistry) -> anyhow::Result<Option<Self>> {
+        let entities = states.resolve::<EntitySystem>(())?;
+        let local_controller = entities.get_local_player_controller()?;
+        if local_controller.is_null()? {
+            return Ok(Some(Self::NotInGame));
+        }
+
+        if !local_controller.reference_schema()?.m_bPawnIsAlive()? {
+            return Ok(Some(Self::Dead));
+        }
+
+        let view_target = states.resolve::<LocalCameraControllerTarget>(())?;
+        if !view_target.is_local_entity {
+            return Ok(Some(Self::Spectating));
+        }
+
+        Ok(None)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct AppFonts {
+    valthrun: FontId,
+    weapon_icons: FontId,
+}
+
+/// Private use codepoints of the icons within `weapon-icons.ttf`.
+/// See `enhancements::player::weapon_icon_codepoint` for the weapon mapping.
+const WEAPON_ICON_GLYPH_RANGES: [u32; 3] = [0xE000, 0xE00E, 0];
+
+/// The font atlas gets rebuild when the UI scale changes.
+/// Font ids must therefore always be resolved through this handle.
+pub type AppFontsHandle = Rc<RefCell<Option<AppFonts>>>;
+
+pub struct Application {
+    pub fonts: AppFontsHandle,
+    pub app_state: StateRegistry,
+
+    pub cs2: Arc<CS2Handle>,
+    pub enhancements: Vec<Rc<RefCell<dyn Enhancement>>>,
+
+    /// Enable state of each enhancement during the last update
+    pub enhancements_enabled: Vec<bool>,
+
+    pub frame_read_calls: usize,
+    pub last_total_read_calls: usize,
+    pub read_error_monitor: ReadErrorMonitor,
+    pub perf_stats: PerformanceStats,
+
+    /// Set while enhancements which send inputs are not updated
+    pub input_suspend_reason: Option<InputSuspendReason>,
+
+    /// Dry run mode the input enhancements have been updated with
+    pub input_dry_run: bool,
+
+    /// Deadline of the next game state update if the update rate is limited
+    next_game_update: Option<Instant>,
+
+    /// The game is hidden, hence no game state is read and the enhancements are not updated
+    overlay_idle: bool,
+
+    /// Name of the active settings profile (`None` for the default profile)
+    pub active_profile: Option<String>,
+    pending_profile_switch: RefCell<Option<Option<String>>>,
+    pending_config_restore: RefCell<Option<PathBuf>>,
+    config_watcher: RefCell<ConfigWatcher>,
+
+    /// All visuals are hidden until the panic key is pressed again.
+    /// This is intentionally not part of the settings so the config stays untouched.
+    pub panic_mode: bool,
+
+    screenshot_capture: ScreenshotCapture,
+
+    pub settings_visible: bool,
+    pub settings_dirty: bool,
+    /// Show a notification after the next save (the settings window has been closed)
+    notify_settings_saved: bool,
+    pub settings_ui: RefCell<SettingsUI>,
+    pub settings_screen_capture_changed: AtomicBool,
+    pub settings_render_debug_window_changed: AtomicBool,
+
+    pub web_radar: RefCell<Option<Arc<Mutex<WebRadar>>>>,
+    pub remote_control: Option<Arc<Mutex<RemoteControl>>>,
+    pub cloud_sync: RefCell<CloudSync>,
+    pub update_checker: UpdateChecker,
+
+    /// Names of the GPUs the overlay can be rendered on
+    pub physical_devices: Vec<String>,
+
+    /// Names of the connected monitors
+    pub monitors: Vec<String>,
+}
+
+impl Application {
+    pub fn settings(&self) -> Ref<'_, AppSettings> {
+        self.app_state
+            .get::<AppSettings>(())
+            .expect("app settings to be present")
+    }
+
+    pub fn settings_mut(&self) -> RefMut<'_, AppSettings> {
+        self.app_state
+            .get_mut::<AppSettings>(())
+            .expect("app settings to be present")
+    }
+
+    /// Switch to another settings profile at the beginning of the next frame
+    pub fn request_profile_switch(&self, profile: Option<String>) {
+        *self.pending_profile_switch.borrow_mut() = Some(profile);
+    }
+
+    /// Replace the settings with a config backup at the beginning of the next frame
+    pub fn request_config_restore(&self, path: PathBuf) {
+        *self.pending_config_restore.borrow_mut() = Some(path);
+    }
+
+    fn restore_config_backup(&mut self, path: &Path) -> anyhow::Result<()> {
+        let mut settings: AppSettings =
+            read_config_file(path).context("failed to parse config backup")?;
+        settings.migrate_legacy_hotkeys();
+        *self.settings_mut() = settings;
+
+        log::info!("已恢复配置备份 {}", path.to_string_lossy());
+        notify(
+            NotificationLevel::Success,
+            &tr!("已恢复配置备份"),
+            NOTIFICATION_DURATION,
+        );
+        self.settings_dirty = true;
+        self.settings_screen_capture_changed
+            .store(true, Ordering::Relaxed);
+        self.settings_render_debug_window_changed
+            .store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn switch_profile(
+        &mut self,
+        controller: &mut SystemRuntimeController,
+        profile: Option<String>,
+    ) -> anyhow::Result<()> {
+        if profile == self.active_profile {
+            return Ok(());
+        }
+
+        let mut settings = load_app_settings(profile.as_deref())?;
+        {
+            let mut current_settings = self.settings_mut();
+
+            /* persist the current profile before switching */
+            let mut imgui_settings = String::new();
+            controller.imgui.save_ini_settings(&mut imgui_settings);
+            current_settings.imgui = Some(imgui_settings);
+            save_app_settings(self.active_profile.as_deref(), &current_settings)?;
+
+            settings.profile_hotkeys = current_settings.profile_hotkeys.clone();
+            *current_settings = settings;
+        }
+
+        if let Ok(path) = get_profile_settings_path(profile.as_deref()) {
+            self.config_watcher.borrow_mut().set_path(path);
+        }
+
+        log::info!(
+            "切换到配置方案 {}",
+            profile.as_deref().unwrap_or(DEFAULT_PROFILE_NAME)
+        );
+        notify(
+            NotificationLevel::Info,
+            &format!(
+                "{} {}",
+                tr!("已切换到配置方案"),
+                profile.as_deref().unwrap_or(DEFAULT_PROFILE_NAME)
+            ),
+            NOTIFICATION_DURATION,
+        );
+        self.active_profile = profile;
+        save_active_profile(self.active_profile.as_deref())?;
+
+        self.settings_dirty = true;
+        self.settings_screen_capture_changed
+            .store(true, Ordering::Relaxed);
+        self.settings_render_debug_window_changed
+            .store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Apply external modifications of the config file.
+    /// Changes made within the settings UI which have not yet been saved take precedence.
+    fn reload_settings_from_disk(&mut self) {
+        if !self.settings().config_hot_reload {
+            return;
+        }
+
+        if self
+            .config_watcher
+            .borrow()
+            .has_local_changes(&self.settings())
+        {
+            log::warn!("配置文件已被外部修改，但存在未保存的更改。忽略外部修改。");
+            return;
+        }
+
+        let settings = match load_app_settings(self.active_profile.as_deref()) {
+            Ok(settings) => settings,
+            Err(error) => {
+                log::warn!("重新加载配置文件失败: {:#}", error);
+                notify(
+                    NotificationLevel::Warning,
+                    &tr!("重新加载配置文件失败"),
+                    NOTIFICATION_DURATION,
+                );
+                return;
+            }
+        };
+
+        {
+            let mut current_settings = self.settings_mut();
+            *current_settings = settings;
+            self.config_watcher
+                .borrow_mut()
+                .mark_synced(&current_settings);
+        }
+
+        self.settings_screen_capture_changed
+            .store(true, Ordering::Relaxed);
+        self.settings_render_debug_window_changed
+            .store(true, Ordering::Relaxed);
+        log::info!("配置文件已被外部修改，已重新加载。");
+        notify(
+            NotificationLevel::Info,
+            &tr!("配置文件已重新加载"),
+            NOTIFICATION_DURATION,
+        );
+    }
+
+    fn update_remote_control(&mut self) {
+        let (enabled, address, token) = {
+            let settings = self.settings();
+            (
+                settings.remote_control,
+                settings.remote_control_address.clone(),
+                settings.remote_control_token.clone(),
+            )
+        };
+
+        if !enabled {
+            if let Some(remote_control) = self.remote_control.take() {
+                remote_control.lock().unwrap().close();
+            }
+
+            return;
+        }
+
+        let token = if token.is_empty() {
+            /* generate the access token on first start or when it has been reset */
+            let token = remote::generate_remote_control_token();
+            self.settings_mut().remote_control_token = token.clone();
+            self.settings_dirty = true;
+            token
+        } else {
+            token
+        };
+
+        if self.remote_control.is_none() {
+            let remote_control = address
+                .parse::<SocketAddr>()
+                .with_context(|| format!("invalid address {}", address))
+                .and_then(|address| {
+                    remote::create_remote_control(address, token.clone(), &*self.settings())
+                });
+
+            match remote_control {
+                Ok(remote_control) => self.remote_control = Some(remote_control),
+                Err(error) => {
+                    log::error!("无法启动远程控制网页: {:#}", error);
+
+                    /* disable the remote control to avoid retrying every frame */
+                    self.settings_mut().remote_control = false;
+                    self.settings_dirty = true;
+                    return;
+                }
+            }
+        }
+
+        let remote_control = match &self.remote_control {
+            Some(remote_control) => remote_control.clone(),
+            None => return,
+        };
+
+        let mut remote_control = remote_control.lock().unwrap();
+        if remote_control.token() != token {
+            remote_control.set_token(token);
+        }
+
+        if let Some(update) = remote_control.take_pending_update() {
+            log::debug!("通过远程控制网页更新设置: {:?}", update);
+            update.apply(&mut *self.settings_mut());
+            self.settings_dirty = true;
+        }
+
+        remote_control.publish_settings(&*self.settings());
+    }
+
+    fn update_cloud_sync(&mut self) {
+        let downloaded = {
+            let settings = self.settings();
+            let mut cloud_sync = self.cloud_sync.borrow_mut();
+            cloud_sync.update(self.active_profile.as_deref(), &settings);
+            cloud_sync.take_downloaded_settings(self.active_profile.as_deref(), &settings)
+        };
+
+        if let Some(settings) = downloaded {
+            *self.settings_mut() = settings;
+            self.settings_dirty = true;
+            self.settings_screen_capture_changed
+                .store(true, Ordering::Relaxed);
+            self.settings_render_debug_window_changed
+                .store(true, Ordering::Relaxed);
+            log::info!("已应用云同步的配置。");
+            notify(
+                NotificationLevel::Info,
+                &tr!("已应用云同步的配置"),
+                NOTIFICATION_DURATION,
+            );
+        }
+    }
+
+    pub fn pre_update(&mut self, controller: &mut SystemRuntimeController) -> anyhow::Result<()> {
+        let profile_switch = self.pending_profile_switch.borrow_mut().take();
+        if let Some(profile) = profile_switch {
+            if let Err(error) = self.switch_profile(controller, profile) {
+                log::warn!("切换配置方案失败: {:#}", error);
+                notify(
+                    NotificationLevel::Error,
+                    &tr!("切换配置方案失败"),
+                    NOTIFICATION_DURATION,
+                );
+            }
+        }
+
+        let config_restore = self.pending_config_restore.borrow_mut().take();
+        if let Some(path) = config_restore {
+            if let Err(error) = self.restore_config_backup(&path) {
+                log::warn!("恢复配置备份失败: {:#}", error);
+                notify(
+                    NotificationLevel::Error,
+                    &tr!("恢复配置备份失败"),
+                    NOTIFICATION_DURATION,
+                );
+            }
+        }
+
+        if self.config_watcher.borrow_mut().poll_external_change() {
+            self.reload_settings_from_disk();
+        }
+
+        self.update_remote_control();
+        self.update_cloud_sync();
+
+        let metrics = self.settings().metrics;
+        if self.cs2.metrics_enabled() != metrics {
+            self.cs2.set_metrics_enabled(metrics);
+            log::info!("使用数据上报已{}", if metrics { "启用" } else { "关闭" });
+        }
+
+        if self.settings_dirty {
+            self.settings_dirty = false;
+            let mut settings = self.settings_mut();
+
+            settings.imgui = None;
+            if let Ok(mut value) = serde_json::to_value(&*settings) {
+                if let Some(fields) = value.as_object_mut() {
+                    for field in SECRET_SETTINGS {
+                        fields.remove(*field);
+                    }
+                }
+
+                self.cs2
+                    .add_metrics_record("settings-updated", &value.to_string());
+            }
+
+            let mut imgui_settings = String::new();
+            controller.imgui.save_ini_settings(&mut imgui_settings);
+            settings.imgui = Some(imgui_settings);
+
+            let notify_saved = std::mem::take(&mut self.notify_settings_saved);
+            match save_app_settings(self.active_profile.as_deref(), &*settings) {
+                Ok(_) => {
+                    self.config_watcher.borrow_mut().mark_synced(&settings);
+                    self.cloud_sync.borrow_mut().notify_saved();
+                    if notify_saved {
+                        notify(
+                            NotificationLevel::Success,
+                            &tr!("配置已保存"),
+                            NOTIFICATION_DURATION,
+                        );
+                    }
+                }
+                Err(error) => {
+                    log::warn!("保存用户设置失败: {}", error);
+                    notify(
+                        NotificationLevel::Error,
+                        &tr!("保存用户设置失败"),
+                        NOTIFICATION_DURATION,
+                    );
+                }
+            };
+        }
+
+        if self
+            .settings_screen_capture_changed
+            .swap(false, Ordering::Relaxed)
+        {
+            let settings = self.settings();
+            controller.toggle_screen_capture_visibility(!settings.hide_overlay_from_screen_capture);
+            log::debug!(
+                "将屏幕截图的可见性更新至 {}",
+                !settings.hide_overlay_from_screen_capture
+            );
+        }
+
+        if self
+            .settings_render_debug_window_changed
+            .swap(false, Ordering::Relaxed)
+        {
+            let settings = self.settings();
+            controller.toggle_debug_overlay(settings.render_debug_window);
+        }
+
+        let capture_include_game = self.settings().capture_include_game;
+        self.screenshot_capture
+            .update(controller, capture_include_game);
+
+        controller.set_notifications_visible(!self.panic_mode && self.settings().notifications);
+        controller.set_interactive(self.settings_visible && self.settings().settings_interactive);
+        controller.set_docking(self.settings().window_docking);
+        controller.set_gamepad_input(self.settings().gamepad_hotkeys);
+        controller.set_ui_scale(self.settings().ui_scale as f32 / 100.0);
+        controller.set_monitor(match self.settings().overlay_monitor {
+            Some(index) => OverlayMonitor::Pinned(index),
+            None => OverlayMonitor::FollowTarget,
+        });
+        if controller.monitor_names() != self.monitors.as_slice() {
+            self.monitors = controller.monitor_names().to_vec();
+        }
+
+        let language = self.settings().language;
+        if i18n::language() != language {
+            i18n::set_language(language);
+        }
+
+        let input_hook = self.settings().input_hook;
+        if controller.input_hook() != input_hook {
+            if let Err(error) = controller.set_input_hook(input_hook) {
+                log::warn!("安装输入钩子失败，回退到按键状态轮询: {}", error);
+                notify(
+                    NotificationLevel::Warning,
+                    &tr!("安装输入钩子失败，回退到按键状态轮询"),
+                    NOTIFICATION_DURATION,
+                );
+                self.settings_mut().input_hook = false;
+                self.settings_dirty = true;
+            }
+        }
+
+        let overlay_idle = self.settings().overlay_idle_when_hidden && controller.target_hidden();
+        if overlay_idle != self.overlay_idle {
+            log::debug!("叠加层空闲模式: {}", overlay_idle);
+            self.overlay_idle = overlay_idle;
+        }
+
+        {
+            let settings = self.settings();
+            let frame_limit = if self.overlay_idle {
+                Some(settings.overlay_fps_limit_idle)
+            } else if !controller.target_focused() && settings.overlay_fps_limit_background > 0 {
+                Some(settings.overlay_fps_limit_background)
+            } else {
+                match settings.fps_limit_mode() {
+                    FpsLimitMode::MonitorRefreshRate => controller.monitor_refresh_rate(),
+                    FpsLimitMode::Custom => Some(settings.fps_limit()),
+                    FpsLimitMode::Unlimited => None,
+                }
+            }
+            .filter(|limit| *limit > 0);
+
+            if controller.frame_limit() != frame_limit {
+                log::debug!("叠加层 FPS 限制已更新为 {:?}", frame_limit);
+                controller.set_frame_limit(frame_limit);
+            }
+
+            controller.set_frame_pacing(match settings.overlay_frame_pacing {
+                FramePacing::Sleep => FramePacingMode::Sleep,
+                FramePacing::Hybrid => FramePacingMode::Hybrid,
+            });
+            controller.set_vsync(settings.overlay_vsync);
+            controller.set_color_space(match settings.overlay_color_space {
+                ColorSpace::Auto => OverlayColorSpace::Auto,
+                ColorSpace::Srgb => OverlayColorSpace::Srgb,
+                ColorSpace::Hdr => OverlayColorSpace::Hdr,
+            });
+            controller.set_hdr_white_level(settings.overlay_hdr_brightness as f32);
+            controller.set_glow(settings.esp_glow.then(|| GlowSettings {
+                radius: settings.esp_glow_radius as f32,
+                intensity: settings.esp_glow_intensity as f32 / 100.0,
+            }));
+        }
+
+        self.perf_stats.record_present(controller.present_timing());
+
+        Ok(())
+    }
+
+    /// Resolve which enhancements have been enabled within the enhancement manager
+    fn resolve_enhancements_enabled(&self) -> Vec<bool> {
+        let settings = self.settings();
+        self.enhancements
+            .iter()
+            .map(|enhancement| settings.enhancement(enhancement.borrow().name()).enabled)
+            .collect()
+    }
+
+    pub fn update(&mut self, ui: &imgui::Ui) -> anyhow::Result<()> {
+        let enhancements_enabled = self.resolve_enhancements_enabled();
+        {
+            for (index, enhancement) in self.enhancements.iter().enumerate() {
+                if !enhancements_enabled[index] {
+                    continue;
+                }
+
+                let mut hack = enhancement.borrow_mut();
+                if hack.update_settings(ui, &mut *self.settings_mut())? {
+                    self.settings_dirty = true;
+                }
+            }
+        }
+
+        for (index, enhancement) in self.enhancements.iter().enumerate() {
+            if !enhancements_enabled[index] {
+                continue;
+            }
+
+            let mut enhancement = enhancement.borrow_mut();
+            for action in enhancement.hotkey_actions() {
+                let pressed = match self.settings().hotkey(action) {
+                    Some(key) => key.is_pressed(ui, false),
+                    None => continue,
+                };
+
+                if !pressed {
+                    continue;
+                }
+
+                log::debug!("Hotkey action {}", action.id);
+                if enhancement.on_hotkey_pressed(action, &mut *self.settings_mut()) {
+                    self.settings_dirty = true;
+                }
+            }
+        }
+
+        let profile_hotkey = self
+            .settings()
+            .profile_hotkeys
+            .iter()
+            .find(|(_, key)| key.is_pressed(ui, false))
+            .map(|(profile, _)| profile.clone());
+        if let Some(profile) = profile_hotkey {
+            if profile == DEFAULT_PROFILE_NAME {
+                self.request_profile_switch(None);
+            } else {
+                self.request_profile_switch(Some(profile));
+            }
+        }
+
+        let panic_key = self.settings().key_panic.clone();
+        if panic_key.map_or(false, |key| key.is_pressed(ui, false)) {
+            self.panic_mode = !self.panic_mode;
+            log::debug!("Panic mode: {}", self.panic_mode);
+
+            if self.panic_mode && self.settings().panic_close_settings {
+                self.settings_visible = false;
+            }
+        }
+
+        let capture_key = self.settings().key_capture.clone();
+        if capture_key.map_or(false, |key| key.is_pressed(ui, false)) {
+            self.screenshot_capture.request();
+        }
+
+        if self.settings().key_settings.is_pressed(ui, false) {
+            log::debug!("Toogle settings");
+            self.settings_visible = !self.settings_visible;
+            self.cs2.add_metrics_record(
+                "settings-toggled",
+                &format!("visible: {}", self.settings_visible),
+            );
+
+            if !self.settings_visible {
+                /* overlay has just been closed */
+                self.settings_dirty = true;
+                self.notify_settings_saved = true;
+            }
+        }
+
+        if !self.game_update_due() {
+            /* render the states of the last update */
+            self.frame_read_calls = 0;
+            return Ok(());
+        }
+
+        self.app_state.invalidate_states();
+        if let Ok(mut view_controller) = self.app_state.resolve_mut::<ViewController>(()) {
+            view_controller.update_screen_bounds(mint::Vector2::from_slice(&ui.io().display_size));
+        }
+
+        /* in dry run mode the input features only record what they would have sent */
+        let dry_run_memory = DryRunMemory::new(&*self.cs2, &self.app_state, &SystemClock);
+        let input_dry_run = self.settings().input_dry_run;
+        let memory: &dyn GameMemory = if input_dry_run {
+            &dry_run_memory
+        } else {
+            &*self.cs2
+        };
+
+        let update_context = UpdateContext {
+            states: &self.app_state,
+            input: ui,
+            memory,
+            clock: &SystemClock,
+        };
+
+        if input_dry_run != self.input_dry_run {
+            log::debug!("Input dry run mode changed to {}", input_dry_run);
+            self.input_dry_run = input_dry_run;
+
+            /* release all inputs which have been sent within the previous mode */
+            let previous_context = UpdateContext {
+                memory: if input_dry_run {
+                    &*self.cs2
+                } else {
+                    &dry_run_memory
+                },
+                ..update_context
+            };
+            for enhancement in self.enhancements.iter() {
+                let mut hack = enhancement.borrow_mut();
+                if hack.injects_input() {
+                    hack.suspend_input(&previous_context)?;
+                }
+            }
+        }
+
+        let input_suspend_reason = if self.overlay_idle {
+            Some(InputSuspendReason::GameHidden)
+        } else {
+            InputSuspendReason::resolve(&self.app_state)?
+        };
+        if input_suspend_reason != self.input_suspend_reason {
+            log::debug!("Input suspend reason changed to {:?}", input_suspend_reason);
+            self.input_suspend_reason = input_suspend_reason;
+
+            if input_suspend_reason.is_some() {
+                for enhancement in self.enhancements.iter() {
+                    let mut hack = enhancement.borrow_mut();
+                    if hack.injects_input() {
+                        hack.suspend_input(&update_context)?;
+                    }
+                }
+            }
+        }
+
+        for (index, enhancement) in self.enhancements.iter().enumerate() {
+            let was_enabled = self
+                .enhancements_enabled
+                .get(index)
+                .copied()
+                .unwrap_or(true);
+            if !was_enabled || enhancements_enabled[index] {
+                continue;
+            }
+
+            let mut hack = enhancement.borrow_mut();
+            log::debug!("Enhancement {} disabled", hack.name());
+            if hack.injects_input() {
+                hack.suspend_input(&update_context)?;
+            }
+        }
+        self.enhancements_enabled = enhancements_enabled;
+
+        for (index, enhancement) in self.enhancements.iter().enumerate() {
+            if !self.enhancements_enabled[index] || self.overlay_idle {
+                continue;
+            }
+
+            let mut hack = enhancement.borrow_mut();
+            if input_suspend_reason.is_some() && hack.injects_input() {
+                continue;
+            }
+
+            let update_start = Instant::now();
+            hack.update(&update_context)?;
+            self.perf_stats
+                .record_enhancement_update(index, update_start.elapsed());
+        }
+
+        let read_calls = self.cs2.ke_interface.total_read_calls();
+        self.frame_read_calls = read_calls - self.last_total_read_calls;
+        self.last_total_read_calls = read_calls;
+
+        Ok(())
+    }
+
+    /// Check if the game state should be read and the enhancements updated within this frame.
+    /// Keeps a fixed cadence independent of the render FPS. The update itself still runs on the
+    /// render thread, so a slow read still delays the frame it happens in.
+    fn game_update_due(&mut self) -> bool {
+        let update_rate = self.settings().update_rate;
+        if update_rate == 0 {
+            self.next_game_update = None;
+            return true;
+        }
+
+        let now = Instant::now();
+        let interval = Duration::from_secs_f64(1.0 / update_rate as f64);
+        match self.next_game_update {
+            Some(next_update) if now < next_update => false,
+            Some(next_update) if next_update + interval > now => {
+                self.next_game_update = Some(next_update + interval);
+                true
+            }
+            _ => {
+                /* first update or we fell behind by more than an update */
+                self.next_game_update = Some(now + interval);
+                true
+            }
+        }
+    }
+
+    /// Track the read failure rate of the driver.
+    /// Must be called after every update, even if the update failed.
+    pub fn update_read_monitor(&mut self, update_error: Option<&anyhow::Error>) {
+        if let Some(error) = update_error {
+            self.read_error_monitor.record_error(error);
+        }
+
+        self.read_error_monitor.record(
+            Instant::now(),
+            self.cs2.ke_interface.total_read_calls(),
+            self.cs2.total_failed_read_calls(),
+        );
+    }
+
+    /// Dump diagnostics for a bug report. Returns the path of the diagnostics file.
+    pub fn dump_diagnostics(&self) -> anyhow::Result<PathBuf> {
+        let build_info = self.app_state.resolve::<BuildInfo>(()).ok();
+        let current_map = self
+            .app_state
+            .resolve::<CurrentMapState>(())
+            .ok()
+            .and_then(|state| state.current_map.clone());
+
+        self.read_error_monitor.dump_diagnostics(&[
+            (
+                "Controller",
+                format!("{} ({})", env!("CARGO_PKG_VERSION"), env!("GIT_HASH")),
+            ),
+            (
+                "CS2",
+                build_info
+                    .as_ref()
+                    .map(|info| format!("{} ({})", info.revision, info.build_datetime))
+                    .unwrap_or_else(|| "unknown".to_string()),
+            ),
+            ("Map", current_map.unwrap_or_else(|| "-".to_string())),
+            (
+                "Windows",
+                version_info()
+                    .map(|info| info.dwBuildNumber.to_string())
+                    .unwrap_or_else(|_| "unknown".to_string()),
+            ),
+        ])
+    }
+
+    pub fn render(&self, ui: &imgui::Ui) {
+        if self.panic_mode {
+            if self.settings_visible {
+                let mut settings_ui = self.settings_ui.borrow_mut();
+                settings_ui.render(self, ui)
+            }
+
+            return;
+        }
+
+        if !self.overlay_idle {
+            ui.window("overlay")
+                .flags(WindowFlags::NO_DOCKING)
+                .draw_background(false)
+                .no_decoration()
+                .no_inputs()
+                .size(ui.io().display_size, Condition::Always)
+                .position([0.0, 0.0], Condition::Always)
+                .build(|| self.render_overlay(ui));
+        }
+
+        if self.settings().render_debug_window {
+            ui.window(format!("{}###debug_info", tr!("调试信息")))
+                .size([250.0, 0.0], Condition::FirstUseEver)
+                .build(|| {
+                    ui.text(format!("{}: {}", tr!("内存读取"), self.frame_read_calls));
+                    match &self.input_suspend_reason {
+                        Some(reason) => ui.text(format!(
+                            "{}: {} ({})",
+                            tr!("输入功能"),
+                            tr!("已暂停"),
+                            reason.display_text()
+                        )),
+                        None => ui.text(format!("{}: {}", tr!("输入功能"), tr!("运行中"))),
+                    }
+                });
+
+            ui.window(format!("{}###state_inspector", tr!("状态缓存")))
+                .size([600.0, 300.0], Condition::FirstUseEver)
+                .build(|| self.render_state_inspector(ui));
+        }
+
+        {
+            for (index, enhancement) in self.enhancements.iter().enumerate() {
+                if !self
+                    .enhancements_enabled
+                    .get(index)
+                    .copied()
+                    .unwrap_or(true)
+                {
+                    continue;
+                }
+
+                let mut enhancement = enhancement.borrow_mut();
+                enhancement.render_debug_window(&self.app_state, ui);
+            }
+        }
+
+        if self.settings_visible {
+            if self.settings().settings_dim_background {
+                ui.get_background_draw_list()
+                    .add_rect([0.0, 0.0], ui.io().display_size, [0.0, 0.0, 0.0, 0.5])
+                    .filled(true)
+                    .build();
+            }
+
+            let mut settings_ui = self.settings_ui.borrow_mut();
+            settings_ui.render(self, ui)
+        }
+    }
+
+    /// List all cached states to diagnose states which fail to resolve
+    fn render_state_inspector(&self, ui: &imgui::Ui) {
+        let states = self.app_state.inspect();
+        let cached_states = states
+            .iter()
+            .filter(|state| state.cache_type.is_some() || state.borrowed)
+            .count();
+        ui.text(format!(
+            "{}: {} / {}, {}: {}",
+            tr!("缓存"),
+            cached_states,
+            self.app_state.capacity(),
+            tr!("错误"),
+            states
+                .iter()
+                .filter(|state| state.last_error.is_some())
+                .count()
+        ));
+
+        let _table = match ui.begin_table_with_flags(
+            "states",
+            5,
+            TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::RESIZABLE,
+        ) {
+            Some(table) => table,
+            None => return,
+        };
+
+        ui.table_setup_column(tr!("类型"));
+        ui.table_setup_column(tr!("缓存"));
+        ui.table_setup_column(tr!("存在时间"));
+        ui.table_setup_column(tr!("解析耗时"));
+        ui.table_setup_column(tr!("最后错误"));
+        ui.table_headers_row();
+
+        for state in states.iter() {
+            ui.table_next_row();
+            ui.table_next_column();
+            ui.text(format!(
+                "{} ({:08X})",
+                state.short_type_name(),
+                state.params_hash as u32
+            ));
+            if ui.is_item_hovered() {
+                ui.tooltip_text(state.type_name);
+            }
+
+            ui.table_next_column();
+            match state.cache_type {
+                _ if state.borrowed => ui.text(tr!("使用中")),
+                Some(StateCacheType::Persistent) => ui.text(tr!("持久")),
+                Some(StateCacheType::Timed(timeout)) => {
+                    ui.text(format!("{} {:.1}s", tr!("定时"), timeout.as_secs_f32()))
+                }
+                Some(StateCacheType::Volatile) => ui.text(tr!("每帧")),
+                None => ui.text("-"),
+            }
+
+            ui.table_next_column();
+            match state.age {
+                Some(age) => ui.text(format!("{:.1}s", age.as_secs_f32())),
+                None => ui.text("-"),
+            }
+
+            ui.table_next_column();
+            match state.resolve_latency {
+                Some(latency) => ui.text(format!("{:.3}ms", latency.as_secs_f32() * 1000.0)),
+                None => ui.text("-"),
+            }
+
+            ui.table_next_column();
+            match &state.last_error {
+                Some((message, age)) => ui.text_colored(
+                    [1.0, 0.3, 0.3, 1.0],
+                    format!("{} ({:.0}s)", message, age.as_secs_f32()),
+                ),
+                None => ui.text("-"),
+            }
+        }
+    }
+
+    fn render_watermark(&self, settings: &AppSettings, ui: &imgui::Ui) {
+        let mut lines = Vec::with_capacity(5);
+        if settings.watermark_brand {
+            lines.push(tr!("Valthrun-CHS 叠加层").to_string());
+        }
+        if settings.watermark_fps {
+            lines.push(format!("{:.2} FPS", ui.io().framerate));
+        }
+        if settings.watermark_read_calls {
+            lines.push(format!("{} Reads", self.frame_read_calls));
+        }
+        if settings.watermark_time {
+            lines.push(chrono::Local::now().format("%H:%M:%S").to_string());
+        }
+        if settings.watermark_profile {
+            if let Some(profile) = &self.active_profile {
+                lines.push(format!("{}: {}", tr!("配置"), profile));
+            }
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+
+        const MARGIN: f32 = 10.0;
+        const PADDING: f32 = 4.0;
+
+        let line_height = ui.text_line_height_with_spacing();
+        let text_height = line_height * lines.len() as f32;
+        let text_width = lines
+            .iter()
+            .map(|line| ui.calc_text_size(line)[0])
+            .fold(0.0, f32::max);
+
+        let [screen_width, screen_height] = ui.window_size();
+        let (align_right, offset_y) = match settings.watermark_anchor {
+            ScreenAnchor::TopLeft => (false, MARGIN),
+            ScreenAnchor::TopRight => (true, MARGIN),
+            ScreenAnchor::Left => (false, (screen_height - text_height) * 0.5),
+            ScreenAnchor::Right => (true, (screen_height - text_height) * 0.5),
+            ScreenAnchor::BottomLeft => (false, screen_height - MARGIN - text_height),
+            ScreenAnchor::BottomRight => (true, screen_height - MARGIN - text_height),
+        };
+        let block_x = if align_right {
+            screen_width - MARGIN - text_width
+        } else {
+            MARGIN
+        };
+
+        if settings.watermark_background {
+            ui.get_window_draw_list()
+                .add_rect(
+                    [block_x - PADDING, offset_y - PADDING],
+                    [
+                        block_x + text_width + PADDING,
+                        offset_y + text_height + PADDING,
+                    ],
+                    settings.watermark_background_color.as_f32(),
+                )
+                .filled(true)
+                .rounding(PADDING)
+                .build();
+        }
+
+        let color = settings.watermark_color.as_f32();
+        for (index, line) in lines.iter().enumerate() {
+            let line_x = if align_right {
+                screen_width - MARGIN - ui.calc_text_size(line)[0]
+            } else {
+                MARGIN
+            };
+
+            ui.set_cursor_pos([line_x, offset_y + line_height * index as f32]);
+            ui.text_colored(color, line);
+        }
+    }
+
+    fn render_overlay(&self, ui: &imgui::Ui) {
+        let settings = self.settings();
+
+        if self.read_error_monitor.is_alerting() {
+            let lines = [
+                format!(
+                    "{} ({:.1}%)",
+                    tr!("内存读取失败率过高"),
+                    self.read_error_monitor.failure_rate() * 100.0
+                ),
+                tr!("游戏可能已更新。请在设置菜单的 \"信息\" 页面导出诊断信息。").to_string(),
+            ];
+
+            let line_height = ui.text_line_height_with_spacing();
+            let text_width = lines
+                .iter()
+                .map(|line| ui.calc_text_size(line)[0])
+                .fold(0.0, f32::max);
+            let toast_x = (ui.window_size()[0] - text_width) / 2.0;
+            let toast_y = ui.window_size()[1] * 0.12;
+
+            ui.get_window_draw_list()
+                .add_rect(
+                    [toast_x - 10.0, toast_y - 6.0],
+                    [
+                        toast_x + text_width + 10.0,
+                        toast_y + line_height * lines.len() as f32 + 6.0,
+                    ],
+                    [0.5, 0.05, 0.05, 0.85],
+                )
+                .filled(true)
+                .rounding(4.0)
+                .build();
+
+            for (index, line) in lines.iter().enumerate() {
+                ui.set_cursor_pos([toast_x, toast_y + line_height * index as f32]);
+                ui.text(line);
+            }
+        }
+
+        if settings.valthrun_watermark {
+            self.render_watermark(&settings, ui);
+        }
+
+        /* enhancements with the same render order keep their registration order */
+        let mut render_order = self
+            .enhancements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, hack)| {
+                let config = settings.enhancement(hack.borrow().name());
+                config.enabled.then_some((config.render_order, index))
+            })
+            .collect::<Vec<_>>();
+        render_order.sort();
+
+        for (_, index) in render_order {
+            let hack = self.enhancements[index].borrow();
+            if let Err(err) = hack.render(&self.app_state, ui) {
+                log::error!("{:?}", err);
+            }
+        }
+    }
+}
+
+impl Drop for Application {
+    fn drop(&mut self) {
+        /* the session timer only saves the playtime history periodically */
+        if let Some(history) = self.app_state.get::<PlaytimeHistory>(()) {
+            if let Err(error) = save_playtime_history(&history) {
+                log::warn!("保存游戏时长记录失败: {:#}", error);
+            }
+        }
+    }
+}
+
+/// Log the error and show it in a message box if the controller
+/// has not been started from a console.
+pub fn show_critical_error(message: &str) {
+    for line in message.lines() {
+        log::error!("{}", line);
+    }
+
+    if !is_console_invoked() {
+        overlay::show_error_message(&tr!("Valthrun-CHS 控制器"), message);
+    }
+}
+
+pub fn is_console_invoked() -> bool {
+    let console_count = unsafe {
+        let mut result = [0u32; 128];
+        GetConsoleProcessList(&mut result)
+    };
+    console_count > 1
+}
+
+fn preload_vulkan_with_act_ctx() -> anyhow::Result<()> {
+    unsafe {
+        let mut act_ctx = mem::zeroed::<ACTCTXA>();
+        act_ctx.cbSize = mem::size_of_val(&act_ctx) as u32;
+        act_ctx.dwFlags = 0x80 | 0x08;
+        act_ctx.hModule = GetModuleHandleA(PCSTR::null()).context("GetModuleHandleA")?;
+        act_ctx.lpResourceName = PCSTR::from_raw(1 as *const u8);
+
+        let mut cookie = 0;
+        let ctx = CreateActCtxA(&act_ctx).context("CreateActCtxA")?;
+        ActivateActCtx(ctx, &mut cookie).context("ActivateActCtx")?;
+        Library::new("vulkan-1").context("vulkan-1")?;
+    }
+
+    Ok(())
+}
+
+/// Options for embedding the overlay into another application
+pub struct ControllerOptions {
+    /// Register the enhancements shipped with the controller (ESP, trigger bot, ...)
+    pub default_enhancements: bool,
+
+    /// Additional enhancements which will be updated and rendered after the default enhancements
+    pub enhancements: Vec<Rc<RefCell<dyn Enhancement>>>,
+
+    /// Overrides the render backend configured within the settings
+    pub render_backend: Option<RenderBackendType>,
+
+    /// Overrides the GPU configured within the settings
+    pub vulkan_device: Option<PhysicalDeviceSelector>,
+}
+
+impl Default for ControllerOptions {
+    fn default() -> Self {
+        Self {
+            default_enhancements: true,
+            enhancements: Vec::new(),
+            render_backend: None,
+            vulkan_device: None,
+        }
+    }
+}
+
+/// Create the CS2 handle, load the settings and run the overlay until it gets closed.
+pub fn run_with_options(options: ControllerOptions) -> anyhow::Result<()> {
+    let build_info = version_info()?;
+    let active_profile = load_active_profile();
+    let settings = load_app_settings(active_profile.as_deref())?;
+    i18n::set_language(settings.language);
+
+    log::info!(
+        "{} 版本 {} ({})，Windows 内部版本 {}。",
+        obfstr!("Valthrun-CHS"),
+        env!("CARGO_PKG_VERSION"),
+        env!("GIT_HASH"),
+        build_info.dwBuildNumber
+    );
+    log::info!("{} {}", tr!("当前可执行文件构建于"), env!("BUILD_TIME"));
+
+    if unsafe { IsUserAnAdmin().as_bool() } {
+        log::warn!(
+            "{}",
+            tr!("当前以管理员身份运行，可能会导致图形驱动程序出现故障。")
+        );
+    }
+
+    updater::cleanup_previous_update();
+
+    if let Err(err) = preload_vulkan_with_act_ctx() {
+        log::warn!("Act CTX preload failed: {:#}", err);
+    }
+
+    let mut config_watcher =
+        ConfigWatcher::new(get_profile_settings_path(active_profile.as_deref())?);
+    config_watcher.mark_synced(&settings);
+    let cs2 = match CS2Handle::create(settings.metrics) {
+        Ok(handle) => handle,
+        Err(err) => {
+            if let Some(err) = err.downcast_ref::<KInterfaceError>() {
+                if let KInterfaceError::DeviceUnavailable(error) = &err {
+                    if error.code().0 as u32 == 0x80070002 {
+                        /* The system cannot find the file specified. */
+                        show_critical_error(&tr!("** 请仔细阅读 **\n无法找到内核驱动程序接口。\n在启动控制器之前，请确保已成功加载或映射内核驱动程序 (valthrun-driver.sys)。请明确检查驱动程序入口状态代码，该代码应为 0x0。\n\n如需更多帮助，请查阅文档中的疑难解答部分: \nhttps://wiki.valth.run/troubleshooting/overlay/driver_has_not_been_loaded"));
+                        return Ok(());
+                    }
+                } else if let KInterfaceError::DriverTooOld {
+                    driver_version_string,
+                    requested_version_string,
+                    ..
+                } = &err
+                {
+                    let message = tr!(
+                        "\n已加载的 Valthrun-CHS 驱动程序版本太低。\n请确保已加载对应当前版本的驱动程序。\n注意: 如果手动映射了驱动程序，则需要先卸载驱动才能加载新版本。如果你使用的驱动映射器不支持卸载驱动，请重启计算机。"
+                    ).to_string();
+
+                    show_critical_error(&format!(
+                        "{}\n\n{}: {}\n{}: {}",
+                        message,
+                        tr!("已加载驱动版本"),
+                        driver_version_string,
+                        tr!("需要驱动版本"),
+                        requested_version_string
+                    ));
+                    return Ok(());
+                } else if let KInterfaceError::DriverTooNew {
+                    driver_version_string,
+                    requested_version_string,
+                    ..
+                } = &err
+                {
+                    let message = tr!(
+                        "\n已加载的 Valthrun-CHS 驱动程序版本太高。\n请确保你使用了对应驱动版本的控制器。"
+                    ).to_string();
+
+                    show_critical_error(&format!(
+                        "{}\n\n{}: {}\n{}: {}",
+                        message,
+                        tr!("已加载驱动版本"),
+                        driver_version_string,
+                        tr!("需要驱动版本"),
+                        requested_version_string
+                    ));
+                    return Ok(());
+                } else if let KInterfaceError::ProcessDoesNotExists = &err {
+                    show_critical_error(&tr!("无法找到游戏进程。\n请在启动本程序前先启动游戏！"));
+                    return Ok(());
+                }
+            }
+
+            return Err(err);
+        }
+    };
+
+    cs2.add_metrics_record(obfstr!("controller-status"), "initializing");
+
+    let mut app_state = StateRegistry::new(1024 * 8);
+    app_state.set(CS2HandleState::new(cs2.clone()), ())?;
+    app_state.set(settings, ())?;
+
+    {
+        let cs2_build_info = app_state.resolve::<BuildInfo>(()).with_context(|| {
+            tr!("加载 CS2 构建信息失败。CS2 版本可能高于或低于预期").to_string()
+        })?;
+
+        log::info!(
+            "已找到 {} 修订版本 {} 来自 {}。",
+            obfstr!("Counter-Strike 2"),
+            cs2_build_info.revision,
+            cs2_build_info.build_datetime
+        );
+        cs2.add_metrics_record(
+            obfstr!("cs2-version"),
+            &format!("revision: {}", cs2_build_info.revision),
+        );
+    }
+
+    offsets_runtime::setup_provider(&cs2)?;
+    app_state
+        .resolve::<CS2Offsets>(())
+        .with_context(|| tr!("无法加载 CS2 偏移量").to_string())?;
+
+    log::debug!("初始化叠加层");
+    let render_backend = match options.render_backend {
+        Some(render_backend) => render_backend,
+        None => match app_state.resolve::<AppSettings>(())?.render_backend {
+            RenderBackend::Vulkan => RenderBackendType::Vulkan,
+            RenderBackend::D3D11 => RenderBackendType::D3D11,
+        },
+    };
+    let vulkan_device = match options.vulkan_device {
+        Some(vulkan_device) => vulkan_device,
+        None => match &app_state.resolve::<AppSettings>(())?.overlay_gpu {
+            Some(name) => PhysicalDeviceSelector::Name(name.clone()),
+            None => PhysicalDeviceSelector::Auto,
+        },
+    };
+
+    let mut merged_fonts = Vec::new();
+    for font in &app_state.resolve::<AppSettings>(())?.custom_fonts {
+        let font_file = match font.resolve_path() {
+            Ok(path) => path,
+            Err(error) => {
+                log::warn!("无法解析字体路径 {}: {:#}", font.path, error);
+                continue;
+            }
+        };
+
+        match MergedFont::load(&font_file, font.size as f32, &font.glyph_ranges()) {
+            Ok(merged_font) => {
+                log::debug!("已加载自定义字体 {}", font_file.display());
+                merged_fonts.push(merged_font);
+            }
+            Err(error) => log::warn!("加载自定义字体 {} 失败: {}", font_file.display(), error),
+        }
+    }
+
+    let app_fonts: AppFontsHandle = Default::default();
+    let overlay_options = OverlayOptions {
+        title: obfstr!("C2OL").to_string(),
+        target: OverlayTarget::WindowOfProcess(cs2.process_id() as u32),
+        font_init: Some(Rc::new({
+            let app_fonts = app_fonts.clone();
+
+            move |imgui, scale| {
+                let mut app_fonts = app_fonts.borrow_mut();
+
+                let font_size = 18.0 * scale;
+                let valthrun_font = imgui.fonts().add_font(&[FontSource::TtfData {
+                    data: include_bytes!("../resources/Valthrun-Regular.ttf"),
+                    size_pixels: font_size,
+                    config: Some(FontConfig {
+                        rasterizer_multiply: 1.5,
+                        oversample_h: 4,
+                        oversample_v: 4,
+                        ..FontConfig::default()
+                    }),
+                }]);
+
+                let weapon_icons = imgui.fonts().add_font(&[FontSource::TtfData {
+                    data: include_bytes!("../resources/weapon-icons.ttf"),
+                    size_pixels: font_size,
+                    config: Some(FontConfig {
+                        glyph_ranges: FontGlyphRanges::from_slice(&WEAPON_ICON_GLYPH_RANGES),
+                        rasterizer_multiply: 1.5,
+                        oversample_h: 4,
+                        oversample_v: 4,
+                        ..FontConfig::default()
+                    }),
+                }]);
+
+                *app_fonts = Some(AppFonts {
+                    valthrun: valthrun_font,
+                    weapon_icons,
+                });
+            }
+        })),
+        merged_fonts,
+        render_backend,
+        vulkan_device,
+    };
+
+    let mut overlay = match overlay::init(&overlay_options) {
+        Err(OverlayError::VulkanDllNotFound(LoadingError::LibraryLoadFailure(source))) => {
+            match &source {
+                libloading::Error::LoadLibraryExW { .. } => {
+                    let error = source.source().context("LoadLibraryExW to have a source")?;
+                    let message = format!(
+                        "{}\n{}: {:#}\n\n{}",
+                        tr!("加载 vulkan-1.dll 失败。"),
+                        tr!("错误"),
+                        error,
+                        tr!("可使用 --d3d11 参数启动，或在配置中设置 render_backend: D3D11 以使用 Direct3D 11 渲染。")
+                    );
+                    show_critical_error(&message);
+                }
+                error => {
+                    let message = format!(
+                        "{}\n{}: {:#}\n\n{}",
+                        tr!("加载 vulkan-1.dll 时发生错误。"),
+                        tr!("错误"),
+                        error,
+                        tr!("可使用 --d3d11 参数启动，或在配置中设置 render_backend: D3D11 以使用 Direct3D 11 渲染。")
+                    );
+                    show_critical_error(&message);
+                }
+            }
+            return Ok(());
+        }
+        value => value?,
+    };
+
+    {
+        let settings = app_state.resolve::<AppSettings>(())?;
+        if let Some(imgui_settings) = &settings.imgui {
+            overlay.imgui.load_ini_settings(imgui_settings);
+        }
+    }
+
+    if app_fonts.borrow().is_none() {
+        anyhow::bail!("初始化应用程序字体失败");
+    }
+
+    let mut enhancements: Vec<Rc<RefCell<dyn Enhancement>>> = if options.default_enhancements {
+        let player_esp = Rc::new(RefCell::new(PlayerESP::new(app_fonts.clone())));
+        vec![
+            player_esp.clone(),
+            Rc::new(RefCell::new(SpectatorsListIndicator::new(
+                overlay.texture_uploader(),
+            ))),
+            Rc::new(RefCell::new(BombInfoIndicator::new())),
+            Rc::new(RefCell::new(TriggerBot::new())),
+            Rc::new(RefCell::new(AntiAimPunsh::new())),
+            Rc::new(RefCell::new(AimingAtYouIndicator::new())),
+            Rc::new(RefCell::new(SessionTimer::new())),
+            Rc::new(RefCell::new(MovementHud::new())),
+            Rc::new(RefCell::new(RoundInfoIndicator::new())),
+            Rc::new(RefCell::new(RoundStatsTracker::new())),
+            Rc::new(RefCell::new(MatchTimelineRecorder::new())),
+            Rc::new(RefCell::new(ProximityAlert::new(player_esp))),
+            Rc::new(RefCell::new(Scoreboard::new())),
+            Rc::new(RefCell::new(FovCircle::new())),
+            Rc::new(RefCell::new(FlashIndicator::new())),
+            Rc::new(RefCell::new(DryRunVisualizer::new())),
+        ]
+    } else {
+        Vec::new()
+    };
+    enhancements.extend(options.enhancements);
+
+    let update_checker = UpdateChecker::new();
+    if app_state.resolve::<AppSettings>(())?.update_check {
+        update_checker.check();
+    }
+
+    let app = Application {
+        app_state,
+
+        cs2: cs2.clone(),
+        web_radar: Default::default(),
+        remote_control: None,
+        cloud_sync: RefCell::new(CloudSync::new()),
+        update_checker,
+        physical_devices: overlay.physical_devices(),
+        monitors: Vec::new(),
+
+        enhancements,
+        enhancements_enabled: Vec::new(),
+
+        fonts: app_fonts,
+
+        last_total_read_calls: 0,
+        frame_read_calls: 0,
+        read_error_monitor: ReadErrorMonitor::new(),
+        perf_stats: PerformanceStats::new(),
+        input_suspend_reason: None,
+        input_dry_run: false,
+        next_game_update: None,
+        overlay_idle: false,
+
+        active_profile,
+        pending_profile_switch: Default::default(),
+        pending_config_restore: Default::default(),
+        config_watcher: RefCell::new(config_watcher),
+
+        panic_mode: false,
+
+        screenshot_capture: ScreenshotCapture::new(),
+
+        settings_visible: false,
+        settings_dirty: false,
+        notify_settings_saved: false,
+        settings_ui: RefCell::new(SettingsUI::new()),
+        /* set the screen capture visibility at the beginning of the first update */
+        settings_screen_capture_changed: AtomicBool::new(true),
+        settings_render_debug_window_changed: AtomicBool::new(true),
+    };
+    let app = Rc::new(RefCell::new(app));
+
+    cs2.add_metrics_record(
+        obfstr!("controller-status"),
+        &format!(
+            "initialized, version: CHS-{}, git-hash: {}, win-build: {}",
+            env!("CARGO_PKG_VERSION"),
+            env!("GIT_HASH"),
+            build_info.dwBuildNumber
+        ),
+    );
+
+    log::info!("{}", tr!("应用程序已初始化。正在生成叠加层..."));
+    let mut update_fail_count = 0;
+    let mut update_timeout: Option<(Instant, Duration)> = None;
+    overlay.main_loop(
+        {
+            let app = app.clone();
+            move |controller| {
+                let mut app = app.borrow_mut();
+                if let Err(err) = app.pre_update(controller) {
+                    show_critical_error(&format!("{:#}", err));
+                    false
+                } else {
+                    true
+                }
+            }
+        },
+        move |ui| {
+            let mut app = app.borrow_mut();
+
+            if let Some((timeout, target)) = &update_timeout {
+                if timeout.elapsed() > *target {
+                    update_timeout = None;
+                } else {
+                    /* Not updating. On timeout... */
+                    return true;
+                }
+            }
+
+            let update_start = Instant::now();
+            let update_result = app.update(ui);
+            app.perf_stats.record_update(update_start.elapsed());
+            app.update_read_monitor(update_result.as_ref().err());
+            if let Err(err) = update_result {
+                if update_fail_count >= 10 {
+                    log::error!("出现 10 多个错误。等待 1 秒后再试。");
+                    log::error!("最后一个错误: {:#}", err);
+
+                    update_timeout = Some((Instant::now(), Duration::from_millis(1000)));
+                    update_fail_count = 0;
+                    return true;
+                } else {
+                    update_fail_count += 1;
+                }
+            }
+
+            let render_start = Instant::now();
+            app.render(ui);
+
+            let frame_read_calls = app.frame_read_calls;
+            app.perf_stats.record_render(render_start.elapsed());
+            app.perf_stats.finish_frame(
+                Duration::from_secs_f32(ui.io().delta_time.max(0.0)),
+                frame_read_calls,
+            );
+            true
+        },
+    )
+}
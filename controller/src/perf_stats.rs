@@ -0,0 +1,195 @@
+use std::{
+    collections::VecDeque,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use overlay::PresentTiming;
+
+/// Time span of the recorded frame timings
+pub const PERFORMANCE_HISTORY: Duration = Duration::from_secs(30);
+
+struct FrameSample {
+    time: Instant,
+    frame_time: f32,
+    update_time: f32,
+    render_time: f32,
+    read_calls: f32,
+
+    /* present timings of the overlay */
+    cpu_time: f32,
+    gpu_time: Option<f32>,
+    present_time: f32,
+    latency: f32,
+
+    /// Update time of each enhancement in the order of `Application::enhancements`
+    enhancement_times: Vec<f32>,
+}
+
+/// Summary of a timing series within the requested time span
+pub struct TimingSeries {
+    pub values: Vec<f32>,
+    pub average: f32,
+    pub max: f32,
+}
+
+impl TimingSeries {
+    fn new(values: Vec<f32>) -> Self {
+        let max = values.iter().copied().fold(0.0, f32::max);
+        let average = if values.is_empty() {
+            0.0
+        } else {
+            values.iter().sum::<f32>() / values.len() as f32
+        };
+
+        Self {
+            values,
+            average,
+            max,
+        }
+    }
+}
+
+fn duration_ms(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+/// Frame timings of the controller.
+/// All times are recorded in milliseconds.
+pub struct PerformanceStats {
+    samples: VecDeque<FrameSample>,
+
+    update_time: Duration,
+    render_time: Duration,
+    enhancement_times: Vec<Duration>,
+    present_timing: PresentTiming,
+}
+
+impl PerformanceStats {
+    pub fn new() -> Self {
+        Self {
+            samples: Default::default(),
+
+            update_time: Duration::ZERO,
+            render_time: Duration::ZERO,
+            enhancement_times: Vec::new(),
+            present_timing: Default::default(),
+        }
+    }
+
+    /// Timings of the last frame presented by the overlay
+    pub fn record_present(&mut self, timing: &PresentTiming) {
+        self.present_timing = *timing;
+    }
+
+    pub fn record_update(&mut self, duration: Duration) {
+        self.update_time += duration;
+    }
+
+    pub fn record_render(&mut self, duration: Duration) {
+        self.render_time += duration;
+    }
+
+    pub fn record_enhancement_update(&mut self, index: usize, duration: Duration) {
+        if self.enhancement_times.len() <= index {
+            self.enhancement_times.resize(index + 1, Duration::ZERO);
+        }
+
+        self.enhancement_times[index] += duration;
+    }
+
+    /// Store the timings recorded since the last call as a new sample
+    pub fn finish_frame(&mut self, frame_time: Duration, read_calls: usize) {
+        let now = Instant::now();
+        self.samples.push_back(FrameSample {
+            time: now,
+            frame_time: duration_ms(frame_time),
+            update_time: duration_ms(std::mem::take(&mut self.update_time)),
+            render_time: duration_ms(std::mem::take(&mut self.render_time)),
+            read_calls: read_calls as f32,
+            cpu_time: duration_ms(self.present_timing.cpu_time),
+            gpu_time: self.present_timing.gpu_time.map(duration_ms),
+            present_time: duration_ms(self.present_timing.present_time),
+            latency: duration_ms(self.present_timing.latency),
+            enhancement_times: self
+                .enhancement_times
+                .iter_mut()
+                .map(|time| duration_ms(std::mem::take(time)))
+                .collect(),
+        });
+
+        while let Some(sample) = self.samples.front() {
+            if now.duration_since(sample.time) <= PERFORMANCE_HISTORY {
+                break;
+            }
+
+            self.samples.pop_front();
+        }
+    }
+
+    fn series(&self, window: Duration, value: impl Fn(&FrameSample) -> f32) -> TimingSeries {
+        let now = Instant::now();
+        TimingSeries::new(
+            self.samples
+                .iter()
+                .filter(|sample| now.duration_since(sample.time) <= window)
+                .map(value)
+                .collect(),
+        )
+    }
+
+    pub fn frame_times(&self, window: Duration) -> TimingSeries {
+        self.series(window, |sample| sample.frame_time)
+    }
+
+    pub fn update_times(&self, window: Duration) -> TimingSeries {
+        self.series(window, |sample| sample.update_time)
+    }
+
+    pub fn render_times(&self, window: Duration) -> TimingSeries {
+        self.series(window, |sample| sample.render_time)
+    }
+
+    pub fn read_calls(&self, window: Duration) -> TimingSeries {
+        self.series(window, |sample| sample.read_calls)
+    }
+
+    pub fn cpu_times(&self, window: Duration) -> TimingSeries {
+        self.series(window, |sample| sample.cpu_time)
+    }
+
+    /// None if the render backend is not able to measure the GPU time
+    pub fn gpu_times(&self, window: Duration) -> Option<TimingSeries> {
+        self.samples.back()?.gpu_time?;
+        Some(self.series(window, |sample| sample.gpu_time.unwrap_or_default()))
+    }
+
+    pub fn present_times(&self, window: Duration) -> TimingSeries {
+        self.series(window, |sample| sample.present_time)
+    }
+
+    pub fn latencies(&self, window: Duration) -> TimingSeries {
+        self.series(window, |sample| sample.latency)
+    }
+
+    pub fn enhancement_update_times(&self, window: Duration, index: usize) -> TimingSeries {
+        self.series(window, |sample| {
+            sample
+                .enhancement_times
+                .get(index)
+                .copied()
+                .unwrap_or_default()
+        })
+    }
+
+    /// Update time of the enhancement within the last recorded frame
+    pub fn last_enhancement_update_time(&self, index: usize) -> f32 {
+        self.samples
+            .back()
+            .and_then(|sample| sample.enhancement_times.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+}
@@ -8,6 +8,10 @@ use cs2::{
     CS2Handle,
     CS2HandleState,
 };
+use overlay::{
+    notify,
+    NotificationLevel,
+};
 use radar_client::{
     CS2RadarGenerator,
     WebRadarPublisher,
@@ -21,6 +25,11 @@ use tokio::{
 use url::Url;
 use utils_state::StateRegistry;
 
+use crate::{
+    i18n::tr,
+    NOTIFICATION_DURATION,
+};
+
 pub enum WebRadarState {
     Connecting,
     Connected { session_id: String },
@@ -31,6 +40,7 @@ pub struct WebRadar {
     ref_self: Weak<Mutex<WebRadar>>,
 
     endpoint: Url,
+    password_protected: bool,
     connection_state: WebRadarState,
 
     disconnect_tx: Option<oneshot::Sender<()>>,
@@ -39,6 +49,7 @@ pub struct WebRadar {
 impl WebRadar {
     async fn create_connection(
         endpoint: &Url,
+        password: Option<&str>,
         cs2: Arc<CS2Handle>,
     ) -> anyhow::Result<WebRadarPublisher> {
         let radar_generator = {
@@ -48,13 +59,18 @@ impl WebRadar {
             Box::new(CS2RadarGenerator::new(states)?)
         };
 
-        WebRadarPublisher::connect(radar_generator, endpoint).await
+        WebRadarPublisher::connect(radar_generator, endpoint, password).await
     }
 
     pub fn endpoint(&self) -> &Url {
         &self.endpoint
     }
 
+    /// Viewers have to enter a password to see the radar
+    pub fn is_password_protected(&self) -> bool {
+        self.password_protected
+    }
+
     pub fn connection_state(&self) -> &WebRadarState {
         &self.connection_state
     }
@@ -66,7 +82,13 @@ impl WebRadar {
     }
 }
 
-pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRadar>> {
+/// Publish the current game. An empty password disables the password protection.
+pub fn create_web_radar(
+    endpoint: Url,
+    password: String,
+    cs2: Arc<CS2Handle>,
+) -> Arc<Mutex<WebRadar>> {
+    let password = Some(password).filter(|password| !password.is_empty());
     let (disconnect_tx, disconnect_rx) = oneshot::channel();
     let instance = Arc::new_cyclic(|ref_self| {
         Mutex::new(WebRadar {
@@ -74,6 +96,7 @@ pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRada
 
             connection_state: WebRadarState::Connecting,
             endpoint: endpoint.clone(),
+            password_protected: password.is_some(),
 
             disconnect_tx: Some(disconnect_tx),
         })
@@ -83,9 +106,15 @@ pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRada
         let instance = instance.clone();
 
         async move {
-            let mut publisher = match WebRadar::create_connection(&endpoint, cs2).await {
+            let connection = WebRadar::create_connection(&endpoint, password.as_deref(), cs2).await;
+            let mut publisher = match connection {
                 Ok(publisher) => {
                     log::info!("Web 雷达已启动。会话ID: {}", publisher.session_id);
+                    notify(
+                        NotificationLevel::Success,
+                        &tr!("Web 雷达已连接"),
+                        NOTIFICATION_DURATION,
+                    );
                     let mut instance = instance.lock().unwrap();
                     instance.connection_state = WebRadarState::Connected {
                         session_id: publisher.session_id.clone(),
@@ -94,6 +123,11 @@ pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRada
                 }
                 Err(err) => {
                     log::error!("无法创建 Web 雷达会话: {:?}", err);
+                    notify(
+                        NotificationLevel::Error,
+                        &tr!("无法创建 Web 雷达会话"),
+                        NOTIFICATION_DURATION,
+                    );
                     let mut instance = instance.lock().unwrap();
                     instance.connection_state = WebRadarState::Disconnected {
                         message: format!("{:#}", err),
@@ -107,6 +141,11 @@ pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRada
                     match result {
                         None => {
                             log::error!("Web 雷达连接关闭");
+                            notify(
+                                NotificationLevel::Warning,
+                                &tr!("Web 雷达连接已断开"),
+                                NOTIFICATION_DURATION,
+                            );
 
                             let mut instance = instance.lock().unwrap();
                             instance.connection_state = WebRadarState::Disconnected {
@@ -115,6 +154,11 @@ pub fn create_web_radar(endpoint: Url, cs2: Arc<CS2Handle>) -> Arc<Mutex<WebRada
                         }
                         Some(error) => {
                             log::error!("Web 雷达已退出: {:#}", error);
+                            notify(
+                                NotificationLevel::Error,
+                                &tr!("Web 雷达连接出错"),
+                                NOTIFICATION_DURATION,
+                            );
 
                             let mut instance = instance.lock().unwrap();
                             instance.connection_state = WebRadarState::Disconnected {
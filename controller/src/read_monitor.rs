@@ -0,0 +1,190 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::Write,
+    path::PathBuf,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Context;
+
+use crate::settings::get_settings_path;
+
+/// Time span over which the read failure rate is calculated
+const SAMPLE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Minimum amount of reads within the sample window before raising an alert
+const MIN_READ_CALLS: usize = 500;
+
+/// Failure rate at which the alert will be raised
+const ALERT_THRESHOLD: f32 = 0.05;
+
+/// Failure rate below which the alert will be cleared again
+const ALERT_CLEAR_THRESHOLD: f32 = 0.01;
+
+/// Amount of recent update errors kept for the diagnostics dump
+const RECENT_ERROR_COUNT: usize = 10;
+
+struct ReadSample {
+    time: Instant,
+    read_calls: usize,
+    failed_read_calls: usize,
+}
+
+/// Tracks the rolling failure rate of the kernel memory reads.
+/// A rising failure rate is often the first symptom of a game update which changed structures or offsets.
+pub struct ReadErrorMonitor {
+    samples: VecDeque<ReadSample>,
+    last_total_read_calls: Option<usize>,
+    last_total_failed_read_calls: usize,
+
+    total_read_calls: usize,
+    total_failed_read_calls: usize,
+
+    alert_since: Option<Instant>,
+    recent_errors: VecDeque<String>,
+}
+
+impl ReadErrorMonitor {
+    pub fn new() -> Self {
+        Self {
+            samples: Default::default(),
+            last_total_read_calls: None,
+            last_total_failed_read_calls: 0,
+
+            total_read_calls: 0,
+            total_failed_read_calls: 0,
+
+            alert_since: None,
+            recent_errors: Default::default(),
+        }
+    }
+
+    /// Record the current read counters of the driver interface.
+    pub fn record(
+        &mut self,
+        now: Instant,
+        total_read_calls: usize,
+        total_failed_read_calls: usize,
+    ) {
+        if let Some(last_total_read_calls) = self.last_total_read_calls {
+            self.samples.push_back(ReadSample {
+                time: now,
+                read_calls: total_read_calls.saturating_sub(last_total_read_calls),
+                failed_read_calls: total_failed_read_calls
+                    .saturating_sub(self.last_total_failed_read_calls),
+            });
+        }
+        self.last_total_read_calls = Some(total_read_calls);
+        self.last_total_failed_read_calls = total_failed_read_calls;
+
+        while let Some(sample) = self.samples.front() {
+            if now.duration_since(sample.time) <= SAMPLE_WINDOW {
+                break;
+            }
+
+            self.samples.pop_front();
+        }
+
+        self.total_read_calls = self.samples.iter().map(|sample| sample.read_calls).sum();
+        self.total_failed_read_calls = self
+            .samples
+            .iter()
+            .map(|sample| sample.failed_read_calls)
+            .sum();
+
+        let failure_rate = self.failure_rate();
+        if self.alert_since.is_none()
+            && self.total_read_calls >= MIN_READ_CALLS
+            && failure_rate >= ALERT_THRESHOLD
+        {
+            log::warn!(
+                "内存读取失败率过高: {:.1}% ({}/{})",
+                failure_rate * 100.0,
+                self.total_failed_read_calls,
+                self.total_read_calls
+            );
+            self.alert_since = Some(now);
+        } else if self.alert_since.is_some() && failure_rate < ALERT_CLEAR_THRESHOLD {
+            log::info!("内存读取失败率已恢复正常");
+            self.alert_since = None;
+        }
+    }
+
+    pub fn record_error(&mut self, error: &anyhow::Error) {
+        if self.recent_errors.len() >= RECENT_ERROR_COUNT {
+            self.recent_errors.pop_front();
+        }
+
+        self.recent_errors.push_back(format!(
+            "[{}] {:#}",
+            chrono::Local::now().format("%H:%M:%S"),
+            error
+        ));
+    }
+
+    /// Failure rate of the reads within the sample window in [0.0;1.0]
+    pub fn failure_rate(&self) -> f32 {
+        if self.total_read_calls == 0 {
+            0.0
+        } else {
+            self.total_failed_read_calls as f32 / self.total_read_calls as f32
+        }
+    }
+
+    pub fn is_alerting(&self) -> bool {
+        self.alert_since.is_some()
+    }
+
+    /// Write diagnostics for a bug report next to the config.
+    /// Returns the path of the created file.
+    pub fn dump_diagnostics(&self, header: &[(&str, String)]) -> anyhow::Result<PathBuf> {
+        let settings_path = get_settings_path()?;
+        let base_dir = settings_path
+            .parent()
+            .context("could not get config directory")?;
+        let file_path = base_dir.join(format!(
+            "diagnostics-{}.txt",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+
+        let mut file = File::create(&file_path).with_context(|| {
+            format!(
+                "failed to create diagnostics file at {}",
+                file_path.to_string_lossy()
+            )
+        })?;
+
+        for (name, value) in header {
+            writeln!(file, "{}: {}", name, value)?;
+        }
+        writeln!(file)?;
+
+        writeln!(
+            file,
+            "Read failures ({}s): {}/{} ({:.2}%)",
+            SAMPLE_WINDOW.as_secs(),
+            self.total_failed_read_calls,
+            self.total_read_calls,
+            self.failure_rate() * 100.0
+        )?;
+        if let Some(alert_since) = &self.alert_since {
+            writeln!(
+                file,
+                "Alert active since {:.1}s",
+                alert_since.elapsed().as_secs_f32()
+            )?;
+        }
+        writeln!(file)?;
+
+        writeln!(file, "Recent errors:")?;
+        for error in self.recent_errors.iter() {
+            writeln!(file, "{}", error)?;
+        }
+
+        Ok(file_path)
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use rand::{
+    distributions::Alphanumeric,
+    Rng,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tokio::{
+    sync::oneshot,
+    task,
+};
+use warp::{
+    http::StatusCode,
+    reject::Reject,
+    Filter,
+    Rejection,
+    Reply,
+};
+
+use crate::settings::{
+    AppSettings,
+    KeyToggleMode,
+};
+
+const REMOTE_CONTROL_PAGE: &str = include_str!("../resources/remote.html");
+const REMOTE_CONTROL_TOKEN_LENGTH: usize = 32;
+
+/// Generate a new random access token for the remote control page.
+pub fn generate_remote_control_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(REMOTE_CONTROL_TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// The subset of the app settings which can be changed via the remote control page.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSettings {
+    pub esp_mode: KeyToggleMode,
+    pub trigger_bot_mode: KeyToggleMode,
+    pub bomb_timer: bool,
+    pub spectators_list: bool,
+    pub valthrun_watermark: bool,
+}
+
+impl RemoteSettings {
+    pub fn from_app_settings(settings: &AppSettings) -> Self {
+        Self {
+            esp_mode: settings.esp_mode,
+            trigger_bot_mode: settings.trigger_bot_mode,
+            bomb_timer: settings.bomb_timer,
+            spectators_list: settings.spectators_list,
+            valthrun_watermark: settings.valthrun_watermark,
+        }
+    }
+
+    pub fn apply(&self, settings: &mut AppSettings) {
+        settings.esp_mode = self.esp_mode;
+        settings.trigger_bot_mode = self.trigger_bot_mode;
+        settings.bomb_timer = self.bomb_timer;
+        settings.spectators_list = self.spectators_list;
+        settings.valthrun_watermark = self.valthrun_watermark;
+    }
+}
+
+pub struct RemoteControl {
+    address: SocketAddr,
+
+    /// Access token every request has to provide
+    token: String,
+
+    /// Current settings as published by the main thread
+    settings: RemoteSettings,
+
+    /// Settings received from the web page which have not yet been applied
+    pending_update: Option<RemoteSettings>,
+
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl RemoteControl {
+    pub fn address(&self) -> &SocketAddr {
+        &self.address
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn set_token(&mut self, token: String) {
+        self.token = token;
+    }
+
+    pub fn publish_settings(&mut self, settings: &AppSettings) {
+        if self.pending_update.is_some() {
+            /* do not override settings which have not yet been applied */
+            return;
+        }
+
+        self.settings = RemoteSettings::from_app_settings(settings);
+    }
+
+    pub fn take_pending_update(&mut self) -> Option<RemoteSettings> {
+        self.pending_update.take()
+    }
+
+    pub fn close(&mut self) {
+        if let Some(shutdown) = self.shutdown_tx.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Forbidden;
+impl Reject for Forbidden {}
+
+/// Compare two strings without leaking the position of the first mismatch.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.bytes()
+        .zip(b.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+/// Reject requests without a valid access token or issued by a foreign web page.
+fn authorize(
+    instance: Arc<Mutex<RemoteControl>>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("origin")
+        .and(warp::header::optional::<String>("host"))
+        .and(warp::header::optional::<String>("x-access-token"))
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(
+            move |origin: Option<String>,
+                  host: Option<String>,
+                  header_token: Option<String>,
+                  query: HashMap<String, String>| {
+                let instance = instance.clone();
+                async move {
+                    if let Some(origin) = origin {
+                        /* browsers send the origin for cross site requests */
+                        let expected = host.map(|host| format!("http://{}", host));
+                        if expected.as_deref() != Some(origin.as_str()) {
+                            return Err(warp::reject::custom(Forbidden));
+                        }
+                    }
+
+                    let provided = header_token
+                        .as_deref()
+                        .or_else(|| query.get("token").map(String::as_str))
+                        .unwrap_or_default();
+                    if !constant_time_eq(provided, instance.lock().unwrap().token()) {
+                        return Err(warp::reject::custom(Forbidden));
+                    }
+
+                    Ok(())
+                }
+            },
+        )
+        .untuple_one()
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let status = if rejection.find::<Forbidden>().is_some() {
+        StatusCode::FORBIDDEN
+    } else if rejection.is_not_found() {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+
+    Ok(warp::reply::with_status(
+        status.canonical_reason().unwrap_or_default(),
+        status,
+    ))
+}
+
+pub fn create_remote_control(
+    address: SocketAddr,
+    token: String,
+    settings: &AppSettings,
+) -> anyhow::Result<Arc<Mutex<RemoteControl>>> {
+    if token.is_empty() {
+        anyhow::bail!("missing access token");
+    }
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let instance = Arc::new(Mutex::new(RemoteControl {
+        address,
+        token,
+
+        settings: RemoteSettings::from_app_settings(settings),
+        pending_update: None,
+
+        shutdown_tx: Some(shutdown_tx),
+    }));
+
+    let authorize = authorize(instance.clone());
+    let route_index = warp::path::end()
+        .and(warp::get())
+        .map(|| warp::reply::html(REMOTE_CONTROL_PAGE));
+
+    let route_settings_get = warp::path!("api" / "settings").and(warp::get()).map({
+        let instance = instance.clone();
+        move || {
+            let instance = instance.lock().unwrap();
+            warp::reply::json(&instance.settings)
+        }
+    });
+
+    let route_settings_set = warp::path!("api" / "settings")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(1024 * 16))
+        .and(warp::body::json())
+        .map({
+            let instance = instance.clone();
+            move |update: RemoteSettings| {
+                let mut instance = instance.lock().unwrap();
+                instance.settings = update.clone();
+                instance.pending_update = Some(update);
+                warp::reply::json(&instance.settings)
+            }
+        });
+
+    let routes = authorize
+        .and(route_index.or(route_settings_get).or(route_settings_set))
+        .recover(handle_rejection);
+    let (bound_address, server) =
+        warp::serve(routes).try_bind_with_graceful_shutdown(address, async move {
+            let _ = shutdown_rx.await;
+        })?;
+
+    instance.lock().unwrap().address = bound_address;
+    task::spawn(async move {
+        server.await;
+        log::info!("远程控制网页已关闭");
+    });
+
+    log::info!(
+        "远程控制网页已启动: http://{}/?token=<访问令牌>",
+        bound_address
+    );
+    Ok(instance)
+}
@@ -0,0 +1,121 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::Context;
+use chrono::{
+    Local,
+    NaiveDateTime,
+};
+
+use super::{
+    get_profile_settings_path,
+    get_settings_path,
+    ConfigFormat,
+};
+
+/// Amount of backups kept for every config file
+const MAX_CONFIG_BACKUPS: usize = 10;
+
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A previous version of a config file
+#[derive(Debug, Clone)]
+pub struct ConfigBackup {
+    pub path: PathBuf,
+    pub timestamp: NaiveDateTime,
+}
+
+/// Backups are stored in `backups/default` or `backups/profiles/<name>` next to the default config
+fn get_backup_directory(profile: Option<&str>) -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    let base_dir = settings_path
+        .parent()
+        .context("could not get config directory")?
+        .join("backups");
+
+    Ok(match profile {
+        Some(profile) => base_dir.join("profiles").join(profile),
+        None => base_dir.join("default"),
+    })
+}
+
+/// List all backups of the given profile, newest first
+pub fn list_config_backups(profile: Option<&str>) -> anyhow::Result<Vec<ConfigBackup>> {
+    let directory = get_backup_directory(profile)?;
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(&directory)? {
+        let path = entry?.path();
+        if ConfigFormat::from_path(&path).is_none() {
+            continue;
+        }
+
+        let timestamp = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .and_then(|name| NaiveDateTime::parse_from_str(name, BACKUP_TIMESTAMP_FORMAT).ok());
+        if let Some(timestamp) = timestamp {
+            backups.push(ConfigBackup { path, timestamp });
+        }
+    }
+
+    backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(backups)
+}
+
+fn is_same_content(a: &Path, b: &Path) -> bool {
+    match (std::fs::read(a), std::fs::read(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Copy the current config file of the profile into the backup directory
+/// and remove the oldest backups exceeding `MAX_CONFIG_BACKUPS`.
+pub fn backup_config_file(profile: Option<&str>) -> anyhow::Result<()> {
+    let config_path = get_profile_settings_path(profile)?;
+    if !config_path.is_file() {
+        return Ok(());
+    }
+
+    let backups = list_config_backups(profile)?;
+    if backups
+        .first()
+        .map_or(false, |latest| is_same_content(&latest.path, &config_path))
+    {
+        /* nothing changed since the last backup */
+        return Ok(());
+    }
+
+    let directory = get_backup_directory(profile)?;
+    std::fs::create_dir_all(&directory)?;
+
+    let format = ConfigFormat::from_path(&config_path).unwrap_or(ConfigFormat::Yaml);
+    let backup_path = directory.join(format!(
+        "{}.{}",
+        Local::now().format(BACKUP_TIMESTAMP_FORMAT),
+        format.extension()
+    ));
+    std::fs::copy(&config_path, &backup_path)
+        .with_context(|| format!("failed to copy config to {}", backup_path.to_string_lossy()))?;
+
+    for backup in list_config_backups(profile)?
+        .iter()
+        .skip(MAX_CONFIG_BACKUPS)
+    {
+        if let Err(error) = std::fs::remove_file(&backup.path) {
+            log::warn!(
+                "删除旧的配置备份 {} 失败: {}",
+                backup.path.to_string_lossy(),
+                error
+            );
+        }
+    }
+
+    Ok(())
+}
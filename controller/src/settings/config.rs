@@ -1,17 +1,23 @@
 use std::{
     collections::BTreeMap,
-    fs::File,
-    io::{
-        BufReader,
-        BufWriter,
-    },
     path::PathBuf,
+    sync::OnceLock,
 };
 
 use anyhow::Context;
+use cs2::{
+    WeaponId,
+    WEAPON_FLAG_TYPE_MACHINE_GUN,
+    WEAPON_FLAG_TYPE_PISTOL,
+    WEAPON_FLAG_TYPE_RIFLE,
+    WEAPON_FLAG_TYPE_SHOTGUN,
+    WEAPON_FLAG_TYPE_SMG,
+    WEAPON_FLAG_TYPE_SNIPER_RIFLE,
+};
 use imgui::Key;
 use serde::{
     Deserialize,
+    Deserializer,
     Serialize,
 };
 use utils_state::{
@@ -20,12 +26,36 @@ use utils_state::{
 };
 
 use super::{
+    backup_config_file,
+    find_config_file,
+    get_profile_settings_path,
+    read_config_file,
+    Color,
+    ConfigFormat,
+    CustomFont,
     EspConfig,
     EspPlayerSettings,
     EspSelector,
     HotKey,
+    HotKeyAction,
+};
+use crate::{
+    enhancements::{
+        HOTKEY_ESP,
+        HOTKEY_TRIGGER_BOT,
+    },
+    i18n::Language,
 };
 
+/// Distinguish a missing field (`None`) from an explicit null (`Some(None)`)
+fn double_option<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
 fn bool_true() -> bool {
     true
 }
@@ -42,11 +72,9 @@ fn default_i32<const V: i32>() -> i32 {
 fn default_key_settings() -> HotKey {
     Key::Pause.into()
 }
-fn default_key_trigger_bot() -> Option<HotKey> {
-    Some(Key::MouseMiddle.into())
-}
-fn default_key_none() -> Option<HotKey> {
-    None
+
+fn default_remote_control_address() -> String {
+    "127.0.0.1:7230".to_string()
 }
 
 fn default_esp_mode() -> KeyToggleMode {
@@ -57,6 +85,50 @@ fn default_trigger_bot_mode() -> KeyToggleMode {
     KeyToggleMode::Trigger
 }
 
+fn default_trigger_bot_warmup_mode() -> TriggerBotWarmupMode {
+    TriggerBotWarmupMode::Unchanged
+}
+
+fn default_esp_priority() -> EspPriority {
+    EspPriority::Distance
+}
+
+fn default_spectators_list_anchor() -> ScreenAnchor {
+    ScreenAnchor::Left
+}
+
+fn default_watermark_anchor() -> ScreenAnchor {
+    ScreenAnchor::TopRight
+}
+
+fn default_watermark_color() -> Color {
+    Color::from_f32([1.0, 1.0, 1.0, 1.0])
+}
+
+fn default_watermark_background_color() -> Color {
+    Color::from_f32([0.0, 0.0, 0.0, 0.5])
+}
+
+fn default_aim_fov() -> f32 {
+    5.0
+}
+
+fn default_aim_target_policy() -> TargetSelectionPolicy {
+    TargetSelectionPolicy::ClosestToCrosshair
+}
+
+fn default_fov_circle_color() -> Color {
+    Color::from_f32([1.0, 1.0, 1.0, 0.5])
+}
+
+fn default_target_indicator_color() -> Color {
+    Color::from_f32([1.0, 0.2, 0.2, 1.0])
+}
+
+fn default_cloud_sync_provider() -> CloudSyncProvider {
+    CloudSyncProvider::WebDav
+}
+
 fn default_esp_configs() -> BTreeMap<String, EspConfig> {
     let mut result: BTreeMap<String, EspConfig> = Default::default();
     result.insert(
@@ -81,6 +153,324 @@ pub enum KeyToggleMode {
     Trigger,
     TriggerInverted,
     Off,
+
+    /// Toggle the state by pressing the hotkey twice in quick succession
+    DoubleTap,
+
+    /// Active while the hotkey is held, but only after it has been held for a minimum duration
+    TriggerHold,
+}
+
+/// User overrides for a registered enhancement
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EnhancementSettings {
+    #[serde(default = "bool_true")]
+    pub enabled: bool,
+
+    /// Enhancements with a higher render order are drawn on top of the others
+    #[serde(default)]
+    pub render_order: i32,
+}
+
+impl Default for EnhancementSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            render_order: 0,
+        }
+    }
+}
+
+/// Which players to keep when limiting the amount of ESP entries
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum EspPriority {
+    /// Keep the closest players
+    Distance,
+
+    /// Keep the enemies which are looking towards the local player
+    Danger,
+}
+
+/// Position of an overlay element on the screen
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum ScreenAnchor {
+    TopLeft,
+    TopRight,
+
+    /// Left side, vertically centered
+    Left,
+
+    /// Right side, vertically centered
+    Right,
+
+    BottomLeft,
+    BottomRight,
+}
+
+/// How the aim features pick their target among all enemies within the aim FOV
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum TargetSelectionPolicy {
+    ClosestToCrosshair,
+    LowestHealth,
+    ClosestDistance,
+
+    /// The enemy looking most directly towards the local player
+    HighestThreat,
+}
+
+/// Behaviour of the trigger bot during the warmup and knife rounds
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum TriggerBotWarmupMode {
+    /// Use the regular trigger bot settings
+    Unchanged,
+
+    /// Disable the trigger bot until the match goes live
+    Disabled,
+
+    /// Use the settings of `trigger_bot_warmup_profile`
+    WarmupProfile,
+}
+
+/// Trigger bot settings which can be swapped depending on the match phase
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TriggerBotProfile {
+    pub delay_min: u32,
+    pub delay_max: u32,
+    pub team_check: bool,
+
+    /// Maximum duration of a single burst in ms (0 = shoot while the target is in the crosshair)
+    #[serde(default)]
+    pub burst: u32,
+}
+
+impl Default for TriggerBotProfile {
+    fn default() -> Self {
+        Self {
+            delay_min: 50,
+            delay_max: 100,
+            team_check: true,
+            burst: 0,
+        }
+    }
+}
+
+/// Weapon class with dedicated trigger bot timings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerBotWeaponClass {
+    Pistol,
+    Rifle,
+    Sniper,
+
+    /// Semi-automatic sniper rifles (G3SG1, SCAR-20)
+    AutoSniper,
+}
+
+impl TriggerBotWeaponClass {
+    pub fn from_weapon(weapon: WeaponId) -> Option<Self> {
+        let flags = weapon.flags();
+        if matches!(weapon, WeaponId::G3SG1 | WeaponId::Scar20) {
+            Some(Self::AutoSniper)
+        } else if flags & WEAPON_FLAG_TYPE_SNIPER_RIFLE > 0 {
+            Some(Self::Sniper)
+        } else if flags & WEAPON_FLAG_TYPE_RIFLE > 0 {
+            Some(Self::Rifle)
+        } else if flags & WEAPON_FLAG_TYPE_PISTOL > 0 {
+            Some(Self::Pistol)
+        } else {
+            None
+        }
+    }
+}
+
+/// Trigger bot timings overriding the global delay and burst for a weapon class
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct TriggerBotClassTiming {
+    pub enabled: bool,
+    pub delay_min: u32,
+    pub delay_max: u32,
+    pub burst: u32,
+}
+
+impl Default for TriggerBotClassTiming {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_min: 10,
+            delay_max: 20,
+            burst: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct TriggerBotClassTimings {
+    pub pistol: TriggerBotClassTiming,
+    pub rifle: TriggerBotClassTiming,
+    pub sniper: TriggerBotClassTiming,
+    pub auto_sniper: TriggerBotClassTiming,
+}
+
+impl TriggerBotClassTimings {
+    pub fn timing(&self, class: TriggerBotWeaponClass) -> &TriggerBotClassTiming {
+        match class {
+            TriggerBotWeaponClass::Pistol => &self.pistol,
+            TriggerBotWeaponClass::Rifle => &self.rifle,
+            TriggerBotWeaponClass::Sniper => &self.sniper,
+            TriggerBotWeaponClass::AutoSniper => &self.auto_sniper,
+        }
+    }
+
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (TriggerBotWeaponClass, &mut TriggerBotClassTiming)> {
+        [
+            (TriggerBotWeaponClass::Pistol, &mut self.pistol),
+            (TriggerBotWeaponClass::Rifle, &mut self.rifle),
+            (TriggerBotWeaponClass::Sniper, &mut self.sniper),
+            (TriggerBotWeaponClass::AutoSniper, &mut self.auto_sniper),
+        ]
+        .into_iter()
+    }
+
+    /// Check if any weapon class overrides the global timings
+    pub fn any_enabled(&self) -> bool {
+        [self.pistol, self.rifle, self.sniper, self.auto_sniper]
+            .iter()
+            .any(|timing| timing.enabled)
+    }
+}
+
+/// Activation of the trigger bot for a weapon class
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TriggerBotWeaponRule {
+    pub enabled: bool,
+
+    /// Only shoot while the local player is scoped in
+    #[serde(default = "bool_false")]
+    pub require_scope: bool,
+}
+
+impl TriggerBotWeaponRule {
+    const fn new(enabled: bool, require_scope: bool) -> Self {
+        Self {
+            enabled,
+            require_scope,
+        }
+    }
+}
+
+/// Trigger bot activation by the weapon class of the local player.
+/// Weapons which do not belong to any of these classes (knives, grenades, ...) are not restricted.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct TriggerBotWeaponMatrix {
+    pub pistol: TriggerBotWeaponRule,
+    pub shotgun: TriggerBotWeaponRule,
+    pub smg: TriggerBotWeaponRule,
+    pub rifle: TriggerBotWeaponRule,
+    pub sniper_rifle: TriggerBotWeaponRule,
+    pub machine_gun: TriggerBotWeaponRule,
+}
+
+impl TriggerBotWeaponMatrix {
+    /// Only activate the trigger bot while scoped in with a sniper rifle
+    pub const SNIPER_SCOPE_ONLY: Self = Self {
+        pistol: TriggerBotWeaponRule::new(false, false),
+        shotgun: TriggerBotWeaponRule::new(false, false),
+        smg: TriggerBotWeaponRule::new(false, false),
+        rifle: TriggerBotWeaponRule::new(false, false),
+        sniper_rifle: TriggerBotWeaponRule::new(true, true),
+        machine_gun: TriggerBotWeaponRule::new(false, false),
+    };
+
+    pub fn rule(&self, weapon: WeaponId) -> Option<&TriggerBotWeaponRule> {
+        let flags = weapon.flags();
+        let rule = if flags & WEAPON_FLAG_TYPE_PISTOL > 0 {
+            &self.pistol
+        } else if flags & WEAPON_FLAG_TYPE_SHOTGUN > 0 {
+            &self.shotgun
+        } else if flags & WEAPON_FLAG_TYPE_SMG > 0 {
+            &self.smg
+        } else if flags & WEAPON_FLAG_TYPE_RIFLE > 0 {
+            &self.rifle
+        } else if flags & WEAPON_FLAG_TYPE_SNIPER_RIFLE > 0 {
+            &self.sniper_rifle
+        } else if flags & WEAPON_FLAG_TYPE_MACHINE_GUN > 0 {
+            &self.machine_gun
+        } else {
+            return None;
+        };
+
+        Some(rule)
+    }
+
+    /// Check if the trigger bot is active for all weapons regardless of the scope state
+    pub fn is_unrestricted(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for TriggerBotWeaponMatrix {
+    fn default() -> Self {
+        let rule = TriggerBotWeaponRule::new(true, false);
+        Self {
+            pistol: rule,
+            shotgun: rule,
+            smg: rule,
+            rifle: rule,
+            sniper_rifle: rule,
+            machine_gun: rule,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum FpsLimitMode {
+    /// Limit the overlay to the refresh rate of the monitor it's displayed on
+    MonitorRefreshRate,
+    Custom,
+    Unlimited,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum FramePacing {
+    /// Only sleep between frames (lowest CPU usage)
+    Sleep,
+
+    /// Sleep and spin for the last milliseconds (most even frame times)
+    #[default]
+    Hybrid,
+}
+
+/// Graphics API used to render the overlay
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum RenderBackend {
+    #[default]
+    Vulkan,
+
+    /// Direct3D 11 for systems without a working vulkan-1.dll
+    D3D11,
+}
+
+/// Color space the overlay is presented in
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum ColorSpace {
+    /// HDR if it has been enabled for the monitor within the Windows display settings
+    #[default]
+    Auto,
+    Srgb,
+    Hdr,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum CloudSyncProvider {
+    /// The settings are stored within a WebDAV directory
+    WebDav,
+
+    /// The settings are stored within a (secret) GitHub Gist
+    Gist,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -88,11 +478,87 @@ pub struct AppSettings {
     #[serde(default = "default_key_settings")]
     pub key_settings: HotKey,
 
+    /// Key to instantly hide all visuals until pressed again
+    #[serde(default)]
+    pub key_panic: Option<HotKey>,
+
+    /// Close the settings window when the panic key is pressed
+    #[serde(default = "bool_true")]
+    pub panic_close_settings: bool,
+
+    /// Key to save the current overlay frame as PNG into the captures directory
+    #[serde(default)]
+    pub key_capture: Option<HotKey>,
+
+    /// Composite the captured overlay frame on top of the game
+    #[serde(default = "bool_false")]
+    pub capture_include_game: bool,
+
+    /// Capture all clicks while the settings are open instead of passing them through to CS2
+    #[serde(default = "bool_true")]
+    pub settings_interactive: bool,
+
+    /// Allow docking the settings and debug windows into each other
+    #[serde(default = "bool_true")]
+    pub window_docking: bool,
+
+    /// Dim the screen behind the settings window
+    #[serde(default = "bool_false")]
+    pub settings_dim_background: bool,
+
+    /// Volume (0 - 100) of all audio cues
+    #[serde(default = "default_u32::<50>")]
+    pub audio_volume: u32,
+
+    /// Show short messages for events like saving the config or connecting the web radar
+    #[serde(default = "bool_true")]
+    pub notifications: bool,
+
+    /// Receive hotkeys through a low level keyboard / mouse hook instead of polling the key states
+    #[serde(default = "bool_false")]
+    pub input_hook: bool,
+
+    /// Allow binding XInput controller buttons as hotkeys
+    #[serde(default = "bool_false")]
+    pub gamepad_hotkeys: bool,
+
+    /// Time in ms a hotkey must be held before a `TriggerHold` binding activates
+    #[serde(default = "default_u32::<200>")]
+    pub hotkey_hold_duration: u32,
+
+    /// Maximum time in ms between the two presses of a `DoubleTap` binding
+    #[serde(default = "default_u32::<300>")]
+    pub hotkey_double_tap_interval: u32,
+
+    /// Apply external modifications of the config file without restarting
+    #[serde(default = "bool_true")]
+    pub config_hot_reload: bool,
+
+    /// Show "(?)" markers with an explanation next to the settings
+    #[serde(default = "bool_false")]
+    pub settings_help: bool,
+
+    /// Hotkeys to switch to a settings profile by the profile name.
+    /// These are carried over when switching profiles.
+    #[serde(default)]
+    pub profile_hotkeys: BTreeMap<String, HotKey>,
+
     #[serde(default = "default_esp_mode")]
     pub esp_mode: KeyToggleMode,
 
-    #[serde(default = "default_key_none")]
-    pub esp_toogle: Option<HotKey>,
+    /// Hotkeys of the enhancement actions by their action id.
+    /// A `null` value unbinds the default hotkey of the action.
+    #[serde(default)]
+    pub hotkeys: BTreeMap<String, Option<HotKey>>,
+
+    /// Enable state and render order of the enhancements by their name
+    #[serde(default)]
+    pub enhancements: BTreeMap<String, EnhancementSettings>,
+
+    /// Legacy hotkey setting, migrated into `hotkeys` when loading the config.
+    /// An explicit null unbinds the hotkey.
+    #[serde(default, deserialize_with = "double_option", skip_serializing)]
+    esp_toogle: Option<Option<HotKey>>,
 
     #[serde(default = "default_esp_configs")]
     pub esp_settings: BTreeMap<String, EspConfig>,
@@ -100,23 +566,177 @@ pub struct AppSettings {
     #[serde(default = "default_esp_configs_enabled")]
     pub esp_settings_enabled: BTreeMap<String, bool>,
 
+    /// Max amount of players rendered by the ESP (0 for unlimited)
+    #[serde(default = "default_u32::<0>")]
+    pub esp_max_entries: u32,
+
+    #[serde(default = "default_esp_priority")]
+    pub esp_priority: EspPriority,
+
+    #[serde(default = "bool_true")]
+    pub esp_sort_by_distance: bool,
+
+    /// Blurred glow around the ESP boxes and skeletons (Vulkan render backend only)
+    #[serde(default = "bool_false")]
+    pub esp_glow: bool,
+
+    /// Blur radius of the glow in pixels
+    #[serde(default = "default_u32::<8>")]
+    pub esp_glow_radius: u32,
+
+    /// Brightness of the glow in percent
+    #[serde(default = "default_u32::<150>")]
+    pub esp_glow_intensity: u32,
+
     #[serde(default = "bool_true")]
     pub bomb_timer: bool,
 
+    /// Play a sound 10 seconds before the bomb explodes
+    #[serde(default = "bool_false")]
+    pub bomb_timer_sound: bool,
+
+    #[serde(default = "bool_false")]
+    pub round_info: bool,
+
+    #[serde(default = "bool_true")]
+    pub round_info_phase: bool,
+
+    #[serde(default = "bool_true")]
+    pub round_info_round_time: bool,
+
+    #[serde(default = "bool_true")]
+    pub round_info_freeze_time: bool,
+
+    #[serde(default = "bool_true")]
+    pub round_info_buy_time: bool,
+
+    #[serde(default = "bool_false")]
+    pub round_stats: bool,
+
+    #[serde(default = "bool_false")]
+    pub round_stats_log: bool,
+
+    /// Record a timeline of the match events into a JSON file per match
+    #[serde(default = "bool_false")]
+    pub match_timeline: bool,
+
+    /// Interval (in seconds) in which the player positions are recorded
+    #[serde(default = "default_u32::<5>")]
+    pub match_timeline_position_interval: u32,
+
     #[serde(default = "bool_false")]
     pub spectators_list: bool,
 
+    #[serde(default = "default_spectators_list_anchor")]
+    pub spectators_list_anchor: ScreenAnchor,
+
+    /// Max amount of spectators shown (0 = unlimited)
+    #[serde(default = "default_u32::<8>")]
+    pub spectators_list_max_rows: u32,
+
+    #[serde(default = "bool_true")]
+    pub spectators_list_fade: bool,
+
+    /// Play a sound when somebody starts spectating the local player
+    #[serde(default = "bool_false")]
+    pub spectators_list_sound: bool,
+
+    /// Hide the spectators list while the in game scoreboard is shown
+    #[serde(default = "bool_false")]
+    pub spectators_list_hide_on_scoreboard: bool,
+
+    /// Resolve persona names and avatars of spectators using the Steam Web API
+    #[serde(default = "bool_false")]
+    pub spectators_list_steam_profiles: bool,
+
+    #[serde(default)]
+    pub steam_web_api_key: String,
+
+    #[serde(default = "bool_false")]
+    pub scoreboard: bool,
+
     #[serde(default = "bool_true")]
     pub valthrun_watermark: bool,
 
-    #[serde(default = "default_i32::<16364>")]
-    pub mouse_x_360: i32,
+    #[serde(default = "default_watermark_anchor")]
+    pub watermark_anchor: ScreenAnchor,
+
+    #[serde(default = "bool_true")]
+    pub watermark_brand: bool,
+
+    #[serde(default = "bool_true")]
+    pub watermark_fps: bool,
+
+    #[serde(default = "bool_true")]
+    pub watermark_read_calls: bool,
+
+    /// Show the local time
+    #[serde(default = "bool_false")]
+    pub watermark_time: bool,
+
+    /// Show the name of the active settings profile (if not the default profile)
+    #[serde(default = "bool_true")]
+    pub watermark_profile: bool,
+
+    #[serde(default = "default_watermark_color")]
+    pub watermark_color: Color,
+
+    /// Draw a plate behind the watermark lines
+    #[serde(default = "bool_false")]
+    pub watermark_background: bool,
+
+    #[serde(default = "default_watermark_background_color")]
+    pub watermark_background_color: Color,
+
+    #[serde(default = "bool_false")]
+    pub proximity_alert: bool,
+
+    /// Max distance (in meters) of enemies behind the local player to trigger the alert
+    #[serde(default = "default_u32::<10>")]
+    pub proximity_alert_distance: u32,
+
+    #[serde(default = "bool_false")]
+    pub proximity_alert_sound: bool,
+
+    #[serde(default = "bool_false")]
+    pub aim_warning: bool,
+
+    #[serde(default = "bool_false")]
+    pub aim_warning_sound: bool,
+
+    #[serde(default = "bool_false")]
+    pub movement_hud: bool,
+
+    /// Show the remaining blind time while being flashed
+    #[serde(default = "bool_false")]
+    pub flash_indicator: bool,
+
+    /// Reduce the ESP opacity while being flashed
+    #[serde(default = "bool_false")]
+    pub flash_indicator_dim_esp: bool,
+
+    /// ESP opacity (in percent) while being fully flashed
+    #[serde(default = "default_u32::<25>")]
+    pub flash_indicator_esp_alpha: u32,
+
+    #[serde(default = "bool_false")]
+    pub session_timer: bool,
+
+    /// Interval of the break reminders in minutes (0 = disabled)
+    #[serde(default = "default_u32::<0>")]
+    pub session_break_interval: u32,
 
     #[serde(default = "default_trigger_bot_mode")]
     pub trigger_bot_mode: KeyToggleMode,
 
-    #[serde(default = "default_key_trigger_bot")]
-    pub key_trigger_bot: Option<HotKey>,
+    /// Play a sound when the trigger bot gets enabled or disabled
+    #[serde(default = "bool_false")]
+    pub trigger_bot_sound: bool,
+
+    /// Legacy hotkey setting, migrated into `hotkeys` when loading the config.
+    /// An explicit null unbinds the hotkey.
+    #[serde(default, deserialize_with = "double_option", skip_serializing)]
+    key_trigger_bot: Option<Option<HotKey>>,
 
     #[serde(default = "bool_true")]
     pub trigger_bot_team_check: bool,
@@ -127,20 +747,144 @@ pub struct AppSettings {
     #[serde(default = "default_u32::<20>")]
     pub trigger_bot_delay_max: u32,
 
+    /// Maximum duration of a single burst in ms (0 = shoot while the target is in the crosshair)
+    #[serde(default = "default_u32::<0>")]
+    pub trigger_bot_burst: u32,
+
+    /// Delay and burst overrides for individual weapon classes
+    #[serde(default)]
+    pub trigger_bot_class_timings: TriggerBotClassTimings,
+
     #[serde(default = "bool_false")]
     pub trigger_bot_check_target_after_delay: bool,
 
+    /// Visualize the inputs of the trigger bot and the aim features instead of sending them to the game
+    #[serde(default = "bool_false")]
+    pub input_dry_run: bool,
+
+    /// Only shoot at the target selected by `aim_target_policy`
+    #[serde(default = "bool_false")]
+    pub trigger_bot_selected_target_only: bool,
+
+    #[serde(default)]
+    pub trigger_bot_weapons: TriggerBotWeaponMatrix,
+
+    #[serde(default = "default_trigger_bot_warmup_mode")]
+    pub trigger_bot_warmup_mode: TriggerBotWarmupMode,
+
+    #[serde(default)]
+    pub trigger_bot_warmup_profile: TriggerBotProfile,
+
     #[serde(default = "bool_false")]
     pub aim_assist_recoil: bool,
 
+    #[serde(default = "bool_false")]
+    pub recoil_control: bool,
+
+    /// Horizontal recoil compensation in percent
+    #[serde(default = "default_u32::<100>")]
+    pub recoil_control_strength_x: u32,
+
+    /// Vertical recoil compensation in percent
+    #[serde(default = "default_u32::<100>")]
+    pub recoil_control_strength_y: u32,
+
+    /// Angle (in degrees) around the crosshair in which the aim features pick their targets
+    #[serde(default = "default_aim_fov")]
+    pub aim_fov: f32,
+
+    #[serde(default = "default_aim_target_policy")]
+    pub aim_target_policy: TargetSelectionPolicy,
+
+    #[serde(default = "bool_false")]
+    pub fov_circle: bool,
+
+    #[serde(default = "default_fov_circle_color")]
+    pub fov_circle_color: Color,
+
+    /// Highlight the player currently selected as target
+    #[serde(default = "bool_false")]
+    pub target_indicator: bool,
+
+    #[serde(default = "default_target_indicator_color")]
+    pub target_indicator_color: Color,
+
+    /// Draw a line from the bottom of the screen to the selected target
+    #[serde(default = "bool_false")]
+    pub target_snapline: bool,
+
     #[serde(default = "bool_true")]
     pub hide_overlay_from_screen_capture: bool,
 
     #[serde(default = "bool_false")]
     pub render_debug_window: bool,
 
+    /// Language of the user interface
+    #[serde(default)]
+    pub language: Language,
+
+    /// Scale of the UI in percent.
+    /// The display scaling (DPI) is applied on top of this.
+    #[serde(default = "default_u32::<100>")]
+    pub ui_scale: u32,
+
+    /// None if not configured, use `fps_limit_mode` to resolve the effective mode
+    #[serde(default)]
+    pub overlay_fps_mode: Option<FpsLimitMode>,
+
+    /// Graphics API used by the overlay. Changes apply after a restart.
+    #[serde(default)]
+    pub render_backend: RenderBackend,
+
+    /// Name of the GPU the Vulkan overlay renders on (None to select it automatically).
+    /// Should match the GPU CS2 runs on. Changes apply after a restart.
+    #[serde(default)]
+    pub overlay_gpu: Option<String>,
+
+    /// Fonts filling glyphs missing in the default font (e.g. rare Chinese characters).
+    /// Changes apply after a restart.
+    #[serde(default)]
+    pub custom_fonts: Vec<CustomFont>,
+
+    /// Index of the monitor the overlay is pinned to (None to follow the CS2 window)
+    #[serde(default)]
+    pub overlay_monitor: Option<usize>,
+
+    /// FPS limit of `FpsLimitMode::Custom`.
+    /// None if not configured, use `fps_limit` to resolve the effective limit.
+    #[serde(default)]
+    pub overlay_fps_limit: Option<u32>,
+
+    /// FPS limit while CS2 is not focused (0 to use the regular limit)
+    #[serde(default = "default_u32::<30>")]
+    pub overlay_fps_limit_background: u32,
+
+    /// Pause the enhancements and render at `overlay_fps_limit_idle` while CS2 is minimized
+    #[serde(default = "bool_true")]
+    pub overlay_idle_when_hidden: bool,
+
+    #[serde(default = "default_u32::<2>")]
+    pub overlay_fps_limit_idle: u32,
+
+    /// How the overlay waits for the next frame when the FPS are limited
+    #[serde(default)]
+    pub overlay_frame_pacing: FramePacing,
+
+    #[serde(default = "bool_false")]
+    pub overlay_vsync: bool,
+
+    /// Only supported by the Vulkan render backend
+    #[serde(default)]
+    pub overlay_color_space: ColorSpace,
+
+    /// Brightness of white in nits when presenting in HDR
+    #[serde(default = "default_u32::<200>")]
+    pub overlay_hdr_brightness: u32,
+
+    /// Rate in Hz at which the game state is read and the enhancements are updated (0 for every frame).
+    /// Frames in between render the last read state. The reads still run on the render thread.
     #[serde(default = "default_u32::<0>")]
-    pub overlay_fps_limit: u32,
+    pub update_rate: u32,
 
     #[serde(default = "bool_true")]
     pub metrics: bool,
@@ -151,10 +895,121 @@ pub struct AppSettings {
     #[serde(default = "bool_false")]
     pub web_radar_advanced_settings: bool,
 
+    /// Password viewers have to enter before they can see the web radar (empty for none)
+    #[serde(default)]
+    pub web_radar_password: String,
+
+    #[serde(default = "bool_false")]
+    pub remote_control: bool,
+
+    #[serde(default = "default_remote_control_address")]
+    pub remote_control_address: String,
+
+    /// Access token required by the remote control page (generated on first start)
+    #[serde(default)]
+    pub remote_control_token: String,
+
+    /// Synchronize the settings of the active profile with a remote endpoint
+    #[serde(default = "bool_false")]
+    pub cloud_sync: bool,
+
+    #[serde(default = "default_cloud_sync_provider")]
+    pub cloud_sync_provider: CloudSyncProvider,
+
+    /// URL of the WebDAV directory or the id of the Gist
+    #[serde(default)]
+    pub cloud_sync_url: String,
+
+    /// WebDAV user name (unused for Gists)
+    #[serde(default)]
+    pub cloud_sync_username: String,
+
+    /// WebDAV password or GitHub access token
+    #[serde(default)]
+    pub cloud_sync_token: String,
+
+    /// Check for a new release on startup
+    #[serde(default = "bool_false")]
+    pub update_check: bool,
+
     #[serde(default)]
     pub imgui: Option<String>,
 }
 
+impl AppSettings {
+    /// Get the key bound to the action or the actions default key if the user has not configured it.
+    pub fn hotkey(&self, action: &HotKeyAction) -> Option<&HotKey> {
+        match self.hotkeys.get(action.id) {
+            Some(key) => key.as_ref(),
+            None => action.default_key.as_ref(),
+        }
+    }
+
+    pub fn enhancement(&self, name: &str) -> EnhancementSettings {
+        self.enhancements.get(name).copied().unwrap_or_default()
+    }
+
+    pub fn set_hotkey(&mut self, action: &HotKeyAction, key: Option<HotKey>) {
+        self.hotkeys.insert(action.id.to_string(), key);
+    }
+
+    /// Trigger bot settings of the regular match
+    pub fn trigger_bot_profile(&self) -> TriggerBotProfile {
+        TriggerBotProfile {
+            delay_min: self.trigger_bot_delay_min,
+            delay_max: self.trigger_bot_delay_max,
+            team_check: self.trigger_bot_team_check,
+            burst: self.trigger_bot_burst,
+        }
+    }
+
+    /// Trigger bot settings of the regular match using the timings of the weapon class if configured
+    pub fn trigger_bot_class_profile(
+        &self,
+        class: Option<TriggerBotWeaponClass>,
+    ) -> TriggerBotProfile {
+        let mut profile = self.trigger_bot_profile();
+        let timing = class
+            .map(|class| self.trigger_bot_class_timings.timing(class))
+            .filter(|timing| timing.enabled);
+
+        if let Some(timing) = timing {
+            profile.delay_min = timing.delay_min;
+            profile.delay_max = timing.delay_max;
+            profile.burst = timing.burst;
+        }
+
+        profile
+    }
+
+    /// FPS limit mode of the overlay.
+    /// Configs which predate the mode but contain an FPS limit keep using the fixed limit.
+    pub fn fps_limit_mode(&self) -> FpsLimitMode {
+        match (self.overlay_fps_mode, self.overlay_fps_limit) {
+            (Some(mode), _) => mode,
+            (None, Some(_)) => FpsLimitMode::Custom,
+            (None, None) => FpsLimitMode::MonitorRefreshRate,
+        }
+    }
+
+    /// FPS limit used by `FpsLimitMode::Custom`
+    pub fn fps_limit(&self) -> u32 {
+        self.overlay_fps_limit.unwrap_or(144)
+    }
+
+    /// Move the hotkeys of older config versions into the `hotkeys` map
+    pub fn migrate_legacy_hotkeys(&mut self) {
+        for (action, key) in [
+            (&HOTKEY_ESP, self.esp_toogle.take()),
+            (&HOTKEY_TRIGGER_BOT, self.key_trigger_bot.take()),
+        ] {
+            if let Some(key) = key {
+                self.hotkeys.entry(action.id.to_string()).or_insert(key);
+            }
+        }
+    }
+}
+
 impl State for AppSettings {
     type Parameter = ();
 
@@ -163,15 +1018,49 @@ impl State for AppSettings {
     }
 }
 
+/// Config path given on the command line
+static SETTINGS_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use the given config file instead of the default location.
+/// Must be called before the settings are loaded.
+pub fn set_settings_path_override(path: PathBuf) {
+    if SETTINGS_PATH_OVERRIDE.set(path).is_err() {
+        log::warn!("Config path override has already been set");
+    }
+}
+
+/// Resolve the config file location:
+/// 1. The path given by `--config`
+/// 2. Portable mode: `config.yaml` next to the executable if it exists or a `portable` file is present
+/// 3. `%APPDATA%\Valthrun-CHS\config.yaml`
+/// 4. `config.yaml` next to the executable if APPDATA is unavailable
+///
+/// Instead of `config.yaml` an existing `config.json` or `config.toml` will be used.
 pub fn get_settings_path() -> anyhow::Result<PathBuf> {
+    if let Some(path) = SETTINGS_PATH_OVERRIDE.get() {
+        return Ok(path.clone());
+    }
+
     let exe_file = std::env::current_exe().context("missing current exe path")?;
     let base_dir = exe_file.parent().context("could not get exe directory")?;
 
-    Ok(base_dir.join("config.yaml"))
+    let portable_config = find_config_file(base_dir, "config");
+    if portable_config.is_file() || base_dir.join("portable").is_file() {
+        return Ok(portable_config);
+    }
+
+    match std::env::var_os("APPDATA") {
+        Some(app_data) => Ok(find_config_file(
+            &PathBuf::from(app_data).join("Valthrun-CHS"),
+            "config",
+        )),
+        None => Ok(portable_config),
+    }
 }
 
-pub fn load_app_settings() -> anyhow::Result<AppSettings> {
-    let config_path = get_settings_path()?;
+/// Load the settings of the given profile (`None` for the default profile)
+pub fn load_app_settings(profile: Option<&str>) -> anyhow::Result<AppSettings> {
+    let config_path = get_profile_settings_path(profile)?;
     if !config_path.is_file() {
         log::info!(
             "应用程序配置文件 {} 不存在。",
@@ -184,37 +1073,45 @@ pub fn load_app_settings() -> anyhow::Result<AppSettings> {
         return Ok(config);
     }
 
-    let config = File::open(&config_path).with_context(|| {
-        format!(
-            "failed to open app config at {}",
-            config_path.to_string_lossy()
-        )
-    })?;
-    let mut config = BufReader::new(config);
-
-    let config: AppSettings =
-        serde_yaml::from_reader(&mut config).context("failed to parse app config")?;
+    let mut config: AppSettings =
+        read_config_file(&config_path).context("failed to parse app config")?;
+    config.migrate_legacy_hotkeys();
 
     log::info!("从 {} 加载应用程序配置", config_path.to_string_lossy());
     Ok(config)
 }
 
-pub fn save_app_settings(settings: &AppSettings) -> anyhow::Result<()> {
-    let config_path = get_settings_path()?;
-    let config = File::options()
-        .create(true)
-        .truncate(true)
-        .write(true)
-        .open(&config_path)
-        .with_context(|| {
-            format!(
-                "failed to open app config at {}",
-                config_path.to_string_lossy()
-            )
-        })?;
-    let mut config = BufWriter::new(config);
-
-    serde_yaml::to_writer(&mut config, settings).context("failed to serialize config")?;
+/// Save the settings to the given profile (`None` for the default profile).
+/// The previous config file is kept as a backup and the new config is written atomically.
+pub fn save_app_settings(profile: Option<&str>, settings: &AppSettings) -> anyhow::Result<()> {
+    let config_path = get_profile_settings_path(profile)?;
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if let Err(error) = backup_config_file(profile) {
+        log::warn!("备份配置文件失败: {:#}", error);
+    }
+
+    let format = ConfigFormat::from_path(&config_path).unwrap_or(ConfigFormat::Yaml);
+    let config = format
+        .serialize(settings)
+        .context("failed to serialize config")?;
+
+    /* write into a temporary file first so a crash does not leave a truncated config behind */
+    let temp_path = config_path.with_extension(format!("{}.tmp", format.extension()));
+    std::fs::write(&temp_path, config).with_context(|| {
+        format!(
+            "failed to write app config at {}",
+            temp_path.to_string_lossy()
+        )
+    })?;
+    std::fs::rename(&temp_path, &config_path).with_context(|| {
+        format!(
+            "failed to replace app config at {}",
+            config_path.to_string_lossy()
+        )
+    })?;
 
     log::debug!("保存应用配置。");
     Ok(())
@@ -1,3 +1,8 @@
+use std::{
+    sync::OnceLock,
+    time::Instant,
+};
+
 use cs2::{
     WeaponId,
     WEAPON_FLAG_TYPE_GRANADE,
@@ -14,6 +19,8 @@ use serde::{
     Serialize,
 };
 
+use crate::i18n::tr;
+
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
 pub struct Color(u32);
 impl Color {
@@ -52,13 +59,209 @@ impl From<[f32; 4]> for Color {
     }
 }
 
+/// Options of the rainbow color
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub struct EspRainbowOptions {
+    /// Color cycles per second (0 = only based on the health)
+    #[serde(default)]
+    pub speed: f32,
+
+    /// Saturation of the colors (0.0 - 1.0)
+    #[serde(default = "default_rainbow_saturation")]
+    pub saturation: f32,
+}
+
+fn default_rainbow_saturation() -> f32 {
+    1.0
+}
+
+impl Default for EspRainbowOptions {
+    fn default() -> Self {
+        Self {
+            speed: 0.0,
+            saturation: default_rainbow_saturation(),
+        }
+    }
+}
+
+/// Value which selects the position within a color gradient
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum EspGradientSource {
+    Health,
+    Distance,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub struct EspGradientStop {
+    /// Position of the stop within the gradient (0.0 - 1.0)
+    pub position: f32,
+    pub color: Color,
+}
+
+pub const ESP_GRADIENT_MAX_STOPS: usize = 6;
+
+/// Color gradient with 2 up to `ESP_GRADIENT_MAX_STOPS` stops.
+/// The stops are stored inline to keep `EspColor` copyable and serialized as a list.
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
+#[serde(try_from = "Vec<EspGradientStop>", into = "Vec<EspGradientStop>")]
+pub struct EspGradient {
+    stops: [EspGradientStop; ESP_GRADIENT_MAX_STOPS],
+    stop_count: usize,
+}
+
+impl EspGradient {
+    pub fn new(from: Color, to: Color) -> Self {
+        let mut stops = [EspGradientStop {
+            position: 1.0,
+            color: to,
+        }; ESP_GRADIENT_MAX_STOPS];
+        stops[0] = EspGradientStop {
+            position: 0.0,
+            color: from,
+        };
+
+        Self {
+            stops,
+            stop_count: 2,
+        }
+    }
+
+    pub fn stops(&self) -> &[EspGradientStop] {
+        &self.stops[..self.stop_count]
+    }
+
+    pub fn stops_mut(&mut self) -> &mut [EspGradientStop] {
+        &mut self.stops[..self.stop_count]
+    }
+
+    /// Insert a new stop in the center of the largest gap between two stops.
+    /// Returns false if the maximum amount of stops has been reached.
+    pub fn add_stop(&mut self) -> bool {
+        if self.stop_count >= ESP_GRADIENT_MAX_STOPS {
+            return false;
+        }
+
+        let mut positions = self
+            .stops()
+            .iter()
+            .map(|stop| stop.position.clamp(0.0, 1.0))
+            .collect::<Vec<_>>();
+        positions.sort_by(f32::total_cmp);
+
+        let (gap_start, gap_end) = positions
+            .windows(2)
+            .map(|window| (window[0], window[1]))
+            .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+            .unwrap_or((0.0, 1.0));
+
+        let position = (gap_start + gap_end) / 2.0;
+        self.stops[self.stop_count] = EspGradientStop {
+            position,
+            color: Color::from_f32(self.sample(position)),
+        };
+        self.stop_count += 1;
+        true
+    }
+
+    /// Remove a stop. Returns false if the gradient would have less then two stops.
+    pub fn remove_stop(&mut self, index: usize) -> bool {
+        if self.stop_count <= 2 || index >= self.stop_count {
+            return false;
+        }
+
+        self.stops[index..self.stop_count].rotate_left(1);
+        self.stop_count -= 1;
+        true
+    }
+
+    /// Sample the gradient at the given position (0.0 - 1.0)
+    pub fn sample(&self, position: f32) -> [f32; 4] {
+        let mut stops = self.stops().to_vec();
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+        let position = position.clamp(0.0, 1.0);
+        let upper_index = match stops.iter().position(|stop| stop.position >= position) {
+            Some(0) => return stops[0].color.as_f32(),
+            Some(index) => index,
+            None => return stops[stops.len() - 1].color.as_f32(),
+        };
+
+        let lower = &stops[upper_index - 1];
+        let upper = &stops[upper_index];
+        let range = upper.position - lower.position;
+        let t = if range > 0.0 {
+            (position - lower.position) / range
+        } else {
+            1.0
+        };
+
+        let lower = lower.color.as_f32();
+        let upper = upper.color.as_f32();
+        [
+            lower[0] + (upper[0] - lower[0]) * t,
+            lower[1] + (upper[1] - lower[1]) * t,
+            lower[2] + (upper[2] - lower[2]) * t,
+            lower[3] + (upper[3] - lower[3]) * t,
+        ]
+    }
+}
+
+impl TryFrom<Vec<EspGradientStop>> for EspGradient {
+    type Error = String;
+
+    fn try_from(value: Vec<EspGradientStop>) -> Result<Self, Self::Error> {
+        if value.len() < 2 || value.len() > ESP_GRADIENT_MAX_STOPS {
+            return Err(format!(
+                "a gradient requires 2 to {} stops but got {}",
+                ESP_GRADIENT_MAX_STOPS,
+                value.len()
+            ));
+        }
+
+        let mut result = Self::new(value[0].color, value[1].color);
+        result.stops[..value.len()].copy_from_slice(&value);
+        result.stop_count = value.len();
+        Ok(result)
+    }
+}
+
+impl From<EspGradient> for Vec<EspGradientStop> {
+    fn from(value: EspGradient) -> Self {
+        value.stops().to_vec()
+    }
+}
+
+/// Distance at which the distance based colors reach their final color
+const DISTANCE_COLOR_MAX_DISTANCE: f32 = 80.0;
+
+/// Reference time for animated colors
+fn color_animation_time() -> f32 {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f32()
+}
+
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
 #[serde(tag = "type", content = "options")]
 pub enum EspColor {
-    HealthBasedRainbow,
-    HealthBased { max: Color, min: Color },
-    Static { value: Color },
+    /// The options are missing for configs created before they have been introduced
+    HealthBasedRainbow(Option<EspRainbowOptions>),
+    HealthBased {
+        max: Color,
+        min: Color,
+    },
+    Static {
+        value: Color,
+    },
     DistanceBased,
+
+    /// Competitive teammate color as shown in the game HUD
+    PlayerColor {
+        fallback: Color,
+    },
+    Gradient {
+        source: EspGradientSource,
+        gradient: EspGradient,
+    },
 }
 
 impl Default for EspColor {
@@ -78,7 +281,7 @@ impl EspColor {
 
     /// Calculate the target color.
     /// Health should be in [0.0;1.0]
-    pub fn calculate_color(&self, health: f32, distance: f32) -> [f32; 4] {
+    pub fn calculate_color(&self, health: f32, distance: f32, player_color: i32) -> [f32; 4] {
         match self {
             Self::Static { value } => value.as_f32(),
             Self::HealthBased { max, min } => {
@@ -92,9 +295,12 @@ impl EspColor {
                     min_rgb[3] + (max_rgb[3] - min_rgb[3]) * health,
                 ]
             }
-            Self::HealthBasedRainbow => {
+            Self::HealthBasedRainbow(options) => {
+                let options = options.unwrap_or_default();
+                let phase = health * 0.75 + color_animation_time() * options.speed;
                 let sin_value = |offset: f32| {
-                    (2.0 * std::f32::consts::PI * health * 0.75 + offset).sin() * 0.5 + 1.0
+                    let value = (2.0 * std::f32::consts::PI * phase + offset).sin() * 0.5 + 1.0;
+                    1.0 + (value - 1.0) * options.saturation
                 };
                 let r: f32 = sin_value(0.0);
                 let g: f32 = sin_value(2.0 * std::f32::consts::PI / 3.0);
@@ -102,7 +308,7 @@ impl EspColor {
                 [r, g, b, 1.0]
             }
             Self::DistanceBased => {
-                let max_distance = 80.0;
+                let max_distance = DISTANCE_COLOR_MAX_DISTANCE;
                 let min_distance = 0.0;
 
                 let color_near = [1.0, 0.0, 0.0, 0.75];
@@ -118,8 +324,28 @@ impl EspColor {
                     0.75,
                 ]
             }
+            Self::PlayerColor { fallback } => match player_color {
+                0 => [0.97, 0.91, 0.25, 1.0],
+                1 => [0.75, 0.36, 0.89, 1.0],
+                2 => [0.26, 0.80, 0.36, 1.0],
+                3 => [0.36, 0.66, 0.96, 1.0],
+                4 => [0.96, 0.56, 0.15, 1.0],
+                _ => fallback.as_f32(),
+            },
+            Self::Gradient { source, gradient } => match source {
+                EspGradientSource::Health => gradient.sample(health),
+                EspGradientSource::Distance => {
+                    gradient.sample(distance / DISTANCE_COLOR_MAX_DISTANCE)
+                }
+            },
         }
     }
+
+    /// Color shown at the given position (0.0 - 1.0) of the gradient preview.
+    /// The position will be used as relative health and distance.
+    pub fn preview_color(&self, position: f32) -> [f32; 4] {
+        self.calculate_color(position, position * DISTANCE_COLOR_MAX_DISTANCE, -1)
+    }
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
@@ -128,6 +354,8 @@ pub enum EspColorType {
     HealthBased,
     HealthBasedRainbow,
     DistanceBased,
+    PlayerColor,
+    Gradient,
 }
 
 impl EspColorType {
@@ -135,8 +363,10 @@ impl EspColorType {
         match color {
             EspColor::Static { .. } => Self::Static,
             EspColor::HealthBased { .. } => Self::HealthBased,
-            EspColor::HealthBasedRainbow => Self::HealthBasedRainbow,
+            EspColor::HealthBasedRainbow(_) => Self::HealthBasedRainbow,
             EspColor::DistanceBased => Self::DistanceBased,
+            EspColor::PlayerColor { .. } => Self::PlayerColor,
+            EspColor::Gradient { .. } => Self::Gradient,
         }
     }
 }
@@ -174,6 +404,100 @@ pub enum EspTracePosition {
     BottomRight,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub enum EspTextEffect {
+    None,
+    Bold,
+    Outline,
+    Shadow,
+}
+
+/// Style of a single ESP text element
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub struct EspTextStyle {
+    /// Font size relative to the default ESP font size
+    pub font_size: f32,
+    pub effect: EspTextEffect,
+
+    /// Draw a dark plate behind the text
+    pub background: bool,
+}
+
+impl Default for EspTextStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 1.0,
+            effect: EspTextEffect::None,
+            background: false,
+        }
+    }
+}
+
+/// Group of skeleton segments, determined by the name of the segments child bone
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EspSkeletonBoneGroup {
+    Head,
+    Spine,
+    Arms,
+    Legs,
+}
+
+impl EspSkeletonBoneGroup {
+    pub fn from_bone_name(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        if name.starts_with("head") || name.starts_with("neck") {
+            Self::Head
+        } else if name.starts_with("arm")
+            || name.starts_with("hand")
+            || name.starts_with("clavicle")
+        {
+            Self::Arms
+        } else if name.starts_with("leg") || name.starts_with("ankle") || name.starts_with("foot") {
+            Self::Legs
+        } else {
+            Self::Spine
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub struct EspSkeletonGroupStyle {
+    /// Use this style instead of the default skeleton style
+    pub enabled: bool,
+    pub color: EspColor,
+    pub width: f32,
+}
+
+impl Default for EspSkeletonGroupStyle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: EspColor::from_rgba(1.0, 1.0, 1.0, 0.75),
+            width: 3.0,
+        }
+    }
+}
+
+/// Style overrides of the skeleton segments by their bone group
+#[derive(Clone, Copy, Default, Deserialize, Serialize, PartialEq, PartialOrd)]
+pub struct EspSkeletonGroupStyles {
+    pub head: EspSkeletonGroupStyle,
+    pub spine: EspSkeletonGroupStyle,
+    pub arms: EspSkeletonGroupStyle,
+    pub legs: EspSkeletonGroupStyle,
+}
+
+impl EspSkeletonGroupStyles {
+    pub fn get(&self, group: EspSkeletonBoneGroup) -> &EspSkeletonGroupStyle {
+        match group {
+            EspSkeletonBoneGroup::Head => &self.head,
+            EspSkeletonBoneGroup::Spine => &self.spine,
+            EspSkeletonBoneGroup::Arms => &self.arms,
+            EspSkeletonBoneGroup::Legs => &self.legs,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, PartialOrd)]
 pub struct EspPlayerSettings {
     pub box_type: EspBoxType,
@@ -183,9 +507,13 @@ pub struct EspPlayerSettings {
     pub skeleton: bool,
     pub skeleton_color: EspColor,
     pub skeleton_width: f32,
+    #[serde(default)]
+    pub skeleton_group_styles: EspSkeletonGroupStyles,
 
     pub health_bar: EspHealthBar,
     pub health_bar_width: f32,
+    #[serde(default = "default_health_bar_damage_flash")]
+    pub health_bar_damage_flash: bool,
 
     pub tracer_lines: EspTracePosition,
     pub tracer_lines_color: EspColor,
@@ -193,26 +521,73 @@ pub struct EspPlayerSettings {
 
     pub info_name: bool,
     pub info_name_color: EspColor,
+    #[serde(default)]
+    pub info_name_style: EspTextStyle,
+
+    #[serde(default)]
+    pub info_steam_name: bool,
+    #[serde(default)]
+    pub info_clan_tag: bool,
+    #[serde(default)]
+    pub info_rank: bool,
 
     pub info_distance: bool,
     pub info_distance_color: EspColor,
+    #[serde(default)]
+    pub info_distance_style: EspTextStyle,
 
     pub near_players: bool,
     pub near_players_distance: f32,
 
     pub info_weapon: bool,
     pub info_weapon_color: EspColor,
+    #[serde(default)]
+    pub info_weapon_style: EspTextStyle,
+
+    #[serde(default)]
+    pub info_weapon_icon: bool,
+
+    /// Show the grenades the player is carrying
+    #[serde(default)]
+    pub info_grenades: bool,
 
     pub info_hp_text: bool,
     pub info_hp_text_color: EspColor,
+    #[serde(default)]
+    pub info_hp_text_style: EspTextStyle,
+
+    #[serde(default)]
+    pub info_armor: bool,
+    #[serde(default = "default_info_armor_color")]
+    pub info_armor_color: EspColor,
+
+    #[serde(default)]
+    pub armor_bar: bool,
 
     pub info_flag_kit: bool,
     pub info_flag_flashed: bool,
+    #[serde(default)]
+    pub info_flag_scoped: bool,
+    #[serde(default)]
+    pub info_flag_reloading: bool,
+    #[serde(default)]
+    pub info_flag_defusing: bool,
+    #[serde(default)]
+    pub info_flag_planting: bool,
     pub info_flags_color: EspColor,
 }
 
 const ESP_COLOR_FRIENDLY: EspColor = EspColor::from_rgba(0.0, 1.0, 0.0, 0.75);
 const ESP_COLOR_ENEMY: EspColor = EspColor::from_rgba(1.0, 0.0, 0.0, 0.75);
+const ESP_COLOR_ARMOR: EspColor = EspColor::from_rgba(0.0, 0.5, 1.0, 0.75);
+
+fn default_info_armor_color() -> EspColor {
+    ESP_COLOR_ARMOR
+}
+
+fn default_health_bar_damage_flash() -> bool {
+    true
+}
 impl EspPlayerSettings {
     pub fn new(target: &EspSelector) -> Self {
         let color = match target {
@@ -241,9 +616,11 @@ impl EspPlayerSettings {
             skeleton: true,
             skeleton_color: color.clone(),
             skeleton_width: 3.0,
+            skeleton_group_styles: Default::default(),
 
             health_bar: EspHealthBar::None,
             health_bar_width: 10.0,
+            health_bar_damage_flash: true,
 
             tracer_lines: EspTracePosition::None,
             tracer_lines_color: color.clone(),
@@ -251,21 +628,39 @@ impl EspPlayerSettings {
 
             info_distance: false,
             info_distance_color: color.clone(),
+            info_distance_style: Default::default(),
 
             near_players: false,
             near_players_distance: 20.0,
 
             info_hp_text: false,
             info_hp_text_color: color.clone(),
+            info_hp_text_style: Default::default(),
+
+            info_armor: false,
+            info_armor_color: ESP_COLOR_ARMOR,
+
+            armor_bar: false,
 
             info_name: false,
             info_name_color: color.clone(),
+            info_name_style: Default::default(),
+            info_steam_name: false,
+            info_clan_tag: false,
+            info_rank: false,
 
             info_weapon: false,
             info_weapon_color: color.clone(),
+            info_weapon_style: Default::default(),
+            info_weapon_icon: false,
+            info_grenades: false,
 
             info_flag_kit: false,
             info_flag_flashed: false,
+            info_flag_scoped: false,
+            info_flag_reloading: false,
+            info_flag_defusing: false,
+            info_flag_planting: false,
             info_flags_color: color.clone(),
         }
     }
@@ -300,6 +695,69 @@ pub enum EspConfig {
     Weapon(EspWeaponSettings),
 }
 
+impl EspConfig {
+    /// Check if the config can be applied to the given ESP target
+    pub fn matches_target(&self, target: &EspSelector) -> bool {
+        match self {
+            Self::Player(_) => matches!(
+                target,
+                EspSelector::Player
+                    | EspSelector::PlayerTeam { .. }
+                    | EspSelector::PlayerTeamVisibility { .. }
+            ),
+            Self::Chicken(_) => matches!(target, EspSelector::Chicken),
+            Self::Weapon(_) => matches!(
+                target,
+                EspSelector::Weapon
+                    | EspSelector::WeaponGroup { .. }
+                    | EspSelector::WeaponSingle { .. }
+            ),
+        }
+    }
+}
+
+fn collect_config_changes(
+    path: &str,
+    current: &serde_json::Value,
+    new: &serde_json::Value,
+    changes: &mut Vec<String>,
+) {
+    match (current, new) {
+        (serde_json::Value::Object(current), serde_json::Value::Object(new)) => {
+            for (key, new_value) in new.iter() {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+
+                match current.get(key) {
+                    Some(current_value) => {
+                        collect_config_changes(&field, current_value, new_value, changes)
+                    }
+                    None => changes.push(format!("{}: {}", field, new_value)),
+                }
+            }
+        }
+        (current, new) if current != new => {
+            changes.push(format!("{}: {} -> {}", path, current, new));
+        }
+        _ => {}
+    }
+}
+
+/// Describe all fields which differ between the two configs ("field: old -> new")
+pub fn esp_config_changes(current: &EspConfig, new: &EspConfig) -> anyhow::Result<Vec<String>> {
+    let mut changes = Vec::new();
+    collect_config_changes(
+        "",
+        &serde_json::to_value(current)?,
+        &serde_json::to_value(new)?,
+        &mut changes,
+    );
+    Ok(changes)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum EspWeaponType {
     Pistol,
@@ -434,31 +892,26 @@ impl EspSelector {
         match self {
             EspSelector::None => obfstr!("ESP Configuration").to_string(),
 
-            EspSelector::Player => obfstr!("对所有玩家启用 ESP").to_string(),
-            EspSelector::PlayerTeam { enemy } => format!(
-                "对{}{}",
-                if *enemy { "敌人" } else { "友军" },
-                obfstr!("启用 ESP")
-            ),
-            EspSelector::PlayerTeamVisibility { enemy, visible } => format!(
-                "对{}的{}{}",
-                if *visible { "可见" } else { "被遮挡" },
-                if *enemy { "敌人" } else { "友军" },
-                obfstr!("启用 ESP")
-            ),
-
-            EspSelector::Chicken => obfstr!("对鸡启用 ESP").to_string(),
-
-            EspSelector::Weapon => obfstr!("对所有武器启用 ESP").to_string(),
+            EspSelector::Player => tr!("对所有玩家启用 ESP").to_string(),
+            EspSelector::PlayerTeam { enemy } => match enemy {
+                true => tr!("对敌人启用 ESP").to_string(),
+                false => tr!("对友军启用 ESP").to_string(),
+            },
+            EspSelector::PlayerTeamVisibility { enemy, visible } => match (enemy, visible) {
+                (true, true) => tr!("对可见的敌人启用 ESP").to_string(),
+                (true, false) => tr!("对被遮挡的敌人启用 ESP").to_string(),
+                (false, true) => tr!("对可见的友军启用 ESP").to_string(),
+                (false, false) => tr!("对被遮挡的友军启用 ESP").to_string(),
+            },
+
+            EspSelector::Chicken => tr!("对鸡启用 ESP").to_string(),
+
+            EspSelector::Weapon => tr!("对所有武器启用 ESP").to_string(),
             EspSelector::WeaponGroup { group } => {
-                format!(
-                    "对 {} {}",
-                    group.display_name().to_lowercase(),
-                    obfstr!("启用 ESP")
-                )
+                tr!("对 {} 启用 ESP").replace("{}", &group.display_name().to_lowercase())
             }
             EspSelector::WeaponSingle { target, .. } => {
-                format!("对 {} {}", target.display_name(), obfstr!("启用 ESP"))
+                tr!("对 {} 启用 ESP").replace("{}", target.display_name())
             }
         }
     }
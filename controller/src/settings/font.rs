@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::get_settings_path;
+
+/// Highest code point imgui is able to render (ImWchar is 16 bit)
+pub const MAX_GLYPH_CODE_POINT: u32 = 0xFFFF;
+
+/// Predefined glyph ranges for custom fonts
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum GlyphRangePreset {
+    Latin,
+    Greek,
+    Cyrillic,
+    Thai,
+    Vietnamese,
+    Japanese,
+    Korean,
+    /// CJK unified ideographs including the rare characters
+    ChineseFull,
+    CjkExtensionA,
+    CjkCompatibility,
+}
+
+impl GlyphRangePreset {
+    /// Inclusive code point ranges covered by this preset
+    pub fn ranges(&self) -> &'static [(u32, u32)] {
+        match self {
+            Self::Latin => &[(0x0020, 0x024F)],
+            Self::Greek => &[(0x0370, 0x03FF), (0x1F00, 0x1FFF)],
+            Self::Cyrillic => &[(0x0400, 0x052F), (0x2DE0, 0x2DFF), (0xA640, 0xA69F)],
+            Self::Thai => &[(0x2010, 0x205E), (0x0E00, 0x0E7F)],
+            Self::Vietnamese => &[(0x0102, 0x0103), (0x1EA0, 0x1EF9)],
+            Self::Japanese => &[(0x3000, 0x30FF), (0x31F0, 0x31FF), (0xFF00, 0xFFEF)],
+            Self::Korean => &[(0x3131, 0x3163), (0xAC00, 0xD7A3)],
+            Self::ChineseFull => &[
+                (0x2000, 0x206F),
+                (0x3000, 0x30FF),
+                (0x31F0, 0x31FF),
+                (0x4E00, 0x9FFF),
+                (0xFF00, 0xFFEF),
+            ],
+            Self::CjkExtensionA => &[(0x3400, 0x4DBF)],
+            Self::CjkCompatibility => &[(0xF900, 0xFAFF)],
+        }
+    }
+}
+
+fn default_font_size() -> u32 {
+    18
+}
+
+/// Additional font loaded from disk.
+/// Glyphs missing within the default font will be taken from this font.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CustomFont {
+    /// Path to the TTF/OTF file. Relative paths are resolved against the config directory.
+    pub path: String,
+
+    /// Font size in logical pixels
+    #[serde(default = "default_font_size")]
+    pub size: u32,
+
+    #[serde(default)]
+    pub glyph_presets: Vec<GlyphRangePreset>,
+
+    /// Additional inclusive code point ranges, e.g. [0x2600, 0x26FF]
+    #[serde(default)]
+    pub glyph_ranges: Vec<[u32; 2]>,
+}
+
+impl CustomFont {
+    /// All configured glyph ranges.
+    /// Without any configured range the whole basic multilingual plane will be used.
+    pub fn glyph_ranges(&self) -> Vec<(u32, u32)> {
+        let mut ranges = self
+            .glyph_presets
+            .iter()
+            .flat_map(|preset| preset.ranges().iter().copied())
+            .chain(self.glyph_ranges.iter().map(|[start, end]| (*start, *end)))
+            .filter(|(start, end)| *start > 0 && start <= end && *start <= MAX_GLYPH_CODE_POINT)
+            .map(|(start, end)| (start, end.min(MAX_GLYPH_CODE_POINT)))
+            .collect::<Vec<_>>();
+
+        if ranges.is_empty() {
+            ranges.push((0x0020, MAX_GLYPH_CODE_POINT));
+        }
+
+        ranges
+    }
+
+    pub fn resolve_path(&self) -> anyhow::Result<PathBuf> {
+        let path = PathBuf::from(&self.path);
+        if path.is_absolute() {
+            return Ok(path);
+        }
+
+        let settings_path = get_settings_path()?;
+        Ok(settings_path
+            .parent()
+            .context("could not get config directory")?
+            .join(path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        CustomFont,
+        GlyphRangePreset,
+        MAX_GLYPH_CODE_POINT,
+    };
+
+    #[test]
+    fn glyph_ranges_combine_presets() {
+        let mut font = CustomFont {
+            path: "font.ttf".to_string(),
+            size: 18,
+            glyph_presets: vec![],
+            glyph_ranges: vec![],
+        };
+        assert_eq!(font.glyph_ranges(), vec![(0x0020, MAX_GLYPH_CODE_POINT)]);
+
+        font.glyph_presets = vec![GlyphRangePreset::CjkExtensionA];
+        font.glyph_ranges = vec![[0x2600, 0x26FF], [0x30, 0x20], [0xE000, 0x1F600]];
+        assert_eq!(
+            font.glyph_ranges(),
+            vec![
+                (0x3400, 0x4DBF),
+                (0x2600, 0x26FF),
+                (0xE000, MAX_GLYPH_CODE_POINT)
+            ]
+        );
+    }
+}
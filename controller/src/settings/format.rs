@@ -0,0 +1,109 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use anyhow::Context;
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+
+/// File format of a config file, selected by the file extension.
+///
+/// Note: TOML has no null value. Hotkeys which have been explicitly unbound
+/// are therefore not persisted and fall back to their default key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub const VARIANTS: [ConfigFormat; 3] = [Self::Yaml, Self::Json, Self::Toml];
+
+    /// Default file extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Yaml => "yaml",
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Use the format of the file extension or guess the format based on the content
+    pub fn detect(path: &Path, content: &str) -> Self {
+        if let Some(format) = Self::from_path(path) {
+            return format;
+        }
+
+        if content.trim_start().starts_with('{') {
+            Self::Json
+        } else {
+            Self::Yaml
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> anyhow::Result<T> {
+        Ok(match self {
+            Self::Yaml => serde_yaml::from_str(content)?,
+            Self::Json => serde_json::from_str(content)?,
+            Self::Toml => toml::from_str(content)?,
+        })
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> anyhow::Result<String> {
+        Ok(match self {
+            Self::Yaml => serde_yaml::to_string(value)?,
+            Self::Json => serde_json::to_string_pretty(value)?,
+            Self::Toml => toml::to_string_pretty(value)?,
+        })
+    }
+}
+
+/// Find an existing config file with the given name and any supported extension.
+/// Returns the yaml path if no such file exists.
+pub fn find_config_file(directory: &Path, name: &str) -> PathBuf {
+    ConfigFormat::VARIANTS
+        .iter()
+        .map(|format| directory.join(format!("{}.{}", name, format.extension())))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| directory.join(format!("{}.{}", name, ConfigFormat::Yaml.extension())))
+}
+
+/// Read and parse a config file using the format of its extension
+pub fn read_config_file<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.to_string_lossy()))?;
+
+    ConfigFormat::detect(path, &content).parse(&content)
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConfigFormat;
+    use crate::settings::AppSettings;
+
+    #[test]
+    fn roundtrip_all_formats() {
+        let mut settings: AppSettings = serde_yaml::from_str("").unwrap();
+        settings.bomb_timer = !settings.bomb_timer;
+
+        for format in ConfigFormat::VARIANTS {
+            let content = format.serialize(&settings).unwrap();
+            let parsed: AppSettings = format.parse(&content).unwrap();
+            assert_eq!(parsed.bomb_timer, settings.bomb_timer, "{:?}", format);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+/// Help texts of the settings, keyed by the field name within `AppSettings`.
+/// The texts are translated at runtime like all other UI strings.
+const HELP_TEXTS: &[(&str, &str)] = &[
+    (
+        "esp_mode",
+        "ESP 的启用方式。\"按住键触发\" 只在按住热键时显示, \"按键切换\" 每次按下热键时切换显示状态。",
+    ),
+    (
+        "esp_max_entries",
+        "同时绘制的玩家数量上限。超出上限时按 \"优先显示\" 选择要绘制的玩家。",
+    ),
+    (
+        "esp_sort_by_distance",
+        "玩家重叠时, 距离较近的玩家绘制在较远的玩家之上。",
+    ),
+    (
+        "esp_glow",
+        "在方框和骨骼周围绘制模糊的发光轮廓, 使其在明亮的背景上更容易辨认。仅支持 Vulkan 渲染后端。",
+    ),
+    ("bomb_timer", "显示 C4 的爆炸倒计时和拆除进度。"),
+    ("bomb_timer_sound", "C4 爆炸前 10 秒播放提示音。"),
+    (
+        "audio_volume",
+        "所有提示音的音量 (扳机、C4、身后敌人、被瞄准、旁观者)。0 为静音。",
+    ),
+    ("round_info", "显示当前回合阶段和剩余时间。"),
+    (
+        "round_stats",
+        "统计本回合造成的伤害和击杀, 回合结束时显示。",
+    ),
+    (
+        "match_timeline",
+        "记录比赛中的击杀、回合结果和玩家位置, 比赛结束后保存到文件。",
+    ),
+    ("spectators_list", "显示正在观察你的玩家。"),
+    ("scoreboard", "显示带有额外玩家信息的记分板，可通过热键切换显示。"),
+    ("aim_warning", "敌人的准星对准你时显示警告。"),
+    ("proximity_alert", "视野外有敌人靠近时发出警报。"),
+    ("movement_hud", "显示当前速度等移动信息。"),
+    ("flash_indicator", "被闪光弹致盲时显示剩余的致盲时间。"),
+    (
+        "trigger_bot_mode",
+        "准星对准敌人时自动开火。\"按住键触发\" 只在按住热键时生效。",
+    ),
+    (
+        "trigger_bot_delay_min",
+        "准星对准目标后, 等待最小和最大延迟之间的随机时间再开火, 使开火时机更自然。",
+    ),
+    (
+        "trigger_bot_burst",
+        "每次开火按住鼠标的时间。0 表示只要目标在准星上就一直开火。",
+    ),
+    (
+        "trigger_bot_check_target_after_delay",
+        "开火延迟结束后再次检查准星是否仍在目标上。目标已经离开准星时取消这次开火, 避免延迟期间打空。",
+    ),
+    ("trigger_bot_team_check", "准星对准队友时不开火。"),
+    (
+        "trigger_bot_selected_target_only",
+        "只对当前目标选择规则选中的玩家开火。",
+    ),
+    (
+        "trigger_bot_warmup_mode",
+        "热身和刀局时自动开火的行为。可以关闭自动开火或使用单独的延迟设置。",
+    ),
+    (
+        "recoil_control",
+        "根据当前武器的弹道自动向反方向移动鼠标, 抵消连续射击的后坐力。",
+    ),
+    ("aim_fov", "目标选择和 FOV 圆使用的视野范围 (角度)。"),
+    (
+        "aim_target_policy",
+        "FOV 范围内有多个敌人时选择哪一个作为当前目标。",
+    ),
+    ("target_indicator", "在当前目标上绘制标记。"),
+    ("target_snapline", "从准星到当前目标绘制一条连线。"),
+    (
+        "config_hot_reload",
+        "在外部编辑配置文件后自动加载修改, 无需重启控制器。",
+    ),
+    (
+        "hide_overlay_from_screen_capture",
+        "截图和录屏软件无法捕获叠加层。部分录制软件可能不支持。",
+    ),
+    (
+        "notifications",
+        "在右下角短暂显示保存配置、连接 Web 雷达、截图等事件的提示。",
+    ),
+    (
+        "settings_interactive",
+        "设置菜单打开时, 点击菜单以外的区域不会传递给游戏。关闭菜单后自动恢复输入穿透。",
+    ),
+    (
+        "window_docking",
+        "拖动设置、调试等窗口的标题栏可将其停靠到其他窗口中作为标签页。拖动时按住 Shift 可避免停靠。布局随配置保存。",
+    ),
+    (
+        "overlay_fps_limit_background",
+        "CS2 不在前台时叠加层的帧率上限, 用于降低资源占用。",
+    ),
+    (
+        "overlay_idle_when_hidden",
+        "CS2 最小化或被隐藏时暂停读取游戏数据和所有功能, 并以极低帧率运行叠加层。游戏恢复后立即继续。",
+    ),
+    (
+        "overlay_frame_pacing",
+        "限制帧率时等待下一帧的方式。\"休眠 + 自旋\" 的帧时间更平稳, 但会占用更多 CPU。",
+    ),
+    (
+        "overlay_vsync",
+        "与显示器刷新同步呈现, 避免画面撕裂, 但会增加延迟。",
+    ),
+    (
+        "overlay_color_space",
+        "游戏以 HDR 运行时使用 HDR 呈现叠加层, 避免颜色发灰或过亮。\"自动\" 会在 Windows 显示设置中为该显示器启用 HDR 时使用 HDR。仅 Vulkan 渲染后端支持 HDR, 且 HDR 模式下无法截图。",
+    ),
+    (
+        "overlay_hdr_brightness",
+        "HDR 模式下白色的亮度。建议与 Windows 的 \"SDR 内容亮度\" 保持一致 (80 尼特对应 0%, 每 10% 约增加 40 尼特)。",
+    ),
+    (
+        "update_rate",
+        "每秒读取游戏数据并更新功能的次数。低于叠加层帧率时, 中间的帧跳过读取并绘制最近一次读取的数据。读取仍在渲染线程中执行, 执行更新的帧仍会受到读取延迟的影响。",
+    ),
+    (
+        "web_radar_password",
+        "观看雷达前需要输入的密码。仅知道会话链接不足以观看雷达。留空则不设置密码。",
+    ),
+];
+
+/// Get the help text of a setting
+pub fn setting_help_text(field: &str) -> Option<&'static str> {
+    HELP_TEXTS
+        .iter()
+        .find(|(key, _)| *key == field)
+        .map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::HELP_TEXTS;
+    use crate::settings::AppSettings;
+
+    #[test]
+    fn fields_exist() {
+        let settings: AppSettings = serde_yaml::from_str("").unwrap();
+        let settings = serde_json::to_value(&settings).unwrap();
+        for (field, _) in HELP_TEXTS {
+            assert!(settings.get(field).is_some(), "unknown field {}", field);
+        }
+    }
+}
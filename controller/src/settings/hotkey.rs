@@ -1,15 +1,132 @@
+use std::fmt::Display;
+
+use imgui::Key;
 use serde::{
     de::Visitor,
     Deserialize,
     Serialize,
 };
 
-#[derive(Clone, Debug)]
-pub struct HotKey(pub imgui::Key);
+use crate::KeyboardInput;
+
+/// Modifier which must be held down while pressing the hotkey.
+/// The left and the right modifier key are treated equally.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HotKeyModifier {
+    Ctrl,
+    Alt,
+    Shift,
+}
+
+impl HotKeyModifier {
+    const VARIANTS: [HotKeyModifier; 3] = [Self::Ctrl, Self::Alt, Self::Shift];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ctrl => "Ctrl",
+            Self::Alt => "Alt",
+            Self::Shift => "Shift",
+        }
+    }
+
+    pub fn keys(&self) -> [Key; 2] {
+        match self {
+            Self::Ctrl => [Key::LeftCtrl, Key::RightCtrl],
+            Self::Alt => [Key::LeftAlt, Key::RightAlt],
+            Self::Shift => [Key::LeftShift, Key::RightShift],
+        }
+    }
+
+    pub fn from_key(key: Key) -> Option<Self> {
+        Self::VARIANTS
+            .into_iter()
+            .find(|modifier| modifier.keys().contains(&key))
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::VARIANTS
+            .into_iter()
+            .find(|modifier| modifier.name() == name)
+    }
+
+    fn is_down(&self, input: &dyn KeyboardInput) -> bool {
+        self.keys().into_iter().any(|key| input.is_key_down(key))
+    }
+}
+
+/// A key optionally combined with modifiers (Ctrl/Alt/Shift) and other keys
+/// which must be held down while pressing the key (chord).
+///
+/// Serialized as the key names joined by `+` (e.g. `Ctrl+Shift+F`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct HotKey {
+    pub modifiers: Vec<HotKeyModifier>,
+
+    /// Additional keys which must be held down
+    pub chord: Vec<Key>,
+
+    pub key: Key,
+}
+
+impl HotKey {
+    pub const fn new(key: Key) -> Self {
+        Self {
+            modifiers: Vec::new(),
+            chord: Vec::new(),
+            key,
+        }
+    }
+
+    fn combination_down(&self, input: &dyn KeyboardInput) -> bool {
+        let extra_modifier_down = HotKeyModifier::VARIANTS.iter().any(|modifier| {
+            /* the key itself or a chord key might be a modifier key (e.g. binding LeftShift) */
+            let bound = self.modifiers.contains(modifier)
+                || modifier
+                    .keys()
+                    .iter()
+                    .any(|key| *key == self.key || self.chord.contains(key));
+
+            !bound && modifier.is_down(input)
+        });
+        if extra_modifier_down {
+            /* F must not trigger while pressing Ctrl+F */
+            return false;
+        }
+
+        self.modifiers
+            .iter()
+            .all(|modifier| modifier.is_down(input))
+            && self.chord.iter().all(|key| input.is_key_down(*key))
+    }
+
+    /// The key and exactly the configured modifiers are held down
+    pub fn is_down(&self, input: &dyn KeyboardInput) -> bool {
+        self.combination_down(input) && input.is_key_down(self.key)
+    }
+
+    /// The key has been pressed while holding exactly the configured modifiers
+    pub fn is_pressed(&self, input: &dyn KeyboardInput, repeating: bool) -> bool {
+        input.is_key_pressed(self.key, repeating) && self.combination_down(input)
+    }
+}
+
+impl From<Key> for HotKey {
+    fn from(value: Key) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Display for HotKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for modifier in self.modifiers.iter() {
+            write!(f, "{}+", modifier.name())?;
+        }
+
+        for key in self.chord.iter() {
+            write!(f, "{:?}+", key)?;
+        }
 
-impl From<imgui::Key> for HotKey {
-    fn from(value: imgui::Key) -> Self {
-        Self(value)
+        write!(f, "{:?}", self.key)
     }
 }
 
@@ -18,12 +135,19 @@ impl Serialize for HotKey {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&format!("{:?}", self.0))
+        serializer.serialize_str(&self.to_string())
     }
 }
 
 struct HotKeyVisitor;
 
+fn parse_key(value: &str) -> Option<Key> {
+    Key::VARIANTS
+        .iter()
+        .find(|key| format!("{:?}", key) == value)
+        .cloned()
+}
+
 impl<'de> Visitor<'de> for HotKeyVisitor {
     type Value = HotKey;
 
@@ -35,13 +159,26 @@ impl<'de> Visitor<'de> for HotKeyVisitor {
     where
         E: serde::de::Error,
     {
-        for key in imgui::Key::VARIANTS.iter() {
-            if format!("{:?}", key) == v {
-                return Ok(HotKey(key.clone()));
+        let mut parts = v.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = parts
+            .pop()
+            .and_then(parse_key)
+            .ok_or_else(|| E::custom("unknown key value"))?;
+
+        let mut hotkey = HotKey::new(key);
+        for part in parts {
+            if let Some(modifier) = HotKeyModifier::from_name(part) {
+                hotkey.modifiers.push(modifier);
+            } else if let Some(key) = parse_key(part) {
+                hotkey.chord.push(key);
+            } else {
+                return Err(E::custom("unknown key value"));
             }
         }
 
-        Err(E::custom("unknown key value"))
+        hotkey.modifiers.sort();
+        hotkey.modifiers.dedup();
+        Ok(hotkey)
     }
 }
 
@@ -53,3 +190,73 @@ impl<'de> Deserialize<'de> for HotKey {
         deserializer.deserialize_str(HotKeyVisitor)
     }
 }
+
+/// A named action of an enhancement which can be bound to a hotkey.
+/// All actions will be listed within the hotkeys settings tab.
+#[derive(Debug)]
+pub struct HotKeyAction {
+    /// Unique id of the action used within the `hotkeys` settings
+    pub id: &'static str,
+    pub name: &'static str,
+    pub default_key: Option<HotKey>,
+}
+
+#[cfg(test)]
+mod test {
+    use imgui::Key;
+
+    use super::{
+        HotKey,
+        HotKeyModifier,
+    };
+    use crate::KeyboardInput;
+
+    struct PressedKeys(Vec<Key>);
+
+    impl KeyboardInput for PressedKeys {
+        fn is_key_down(&self, key: Key) -> bool {
+            self.0.contains(&key)
+        }
+
+        fn is_key_pressed(&self, key: Key, _repeating: bool) -> bool {
+            self.0.contains(&key)
+        }
+    }
+
+    #[test]
+    fn parses_combinations() {
+        let hotkey: HotKey = serde_yaml::from_str("Shift+Ctrl+G+H").unwrap();
+        assert_eq!(
+            hotkey.modifiers,
+            vec![HotKeyModifier::Ctrl, HotKeyModifier::Shift]
+        );
+        assert_eq!(hotkey.chord, vec![Key::G]);
+        assert_eq!(hotkey.key, Key::H);
+        assert_eq!(hotkey.to_string(), "Ctrl+Shift+G+H");
+
+        let hotkey: HotKey = serde_yaml::from_str("Pause").unwrap();
+        assert_eq!(hotkey, HotKey::new(Key::Pause));
+    }
+
+    #[test]
+    fn rejects_additional_modifiers() {
+        let plain: HotKey = serde_yaml::from_str("F").unwrap();
+        let ctrl: HotKey = serde_yaml::from_str("Ctrl+F").unwrap();
+
+        let input = PressedKeys(vec![Key::F]);
+        assert!(plain.is_pressed(&input, false));
+        assert!(!ctrl.is_pressed(&input, false));
+
+        let input = PressedKeys(vec![Key::LeftCtrl, Key::F]);
+        assert!(!plain.is_pressed(&input, false));
+        assert!(!plain.is_down(&input));
+        assert!(ctrl.is_pressed(&input, false));
+        assert!(ctrl.is_down(&input));
+
+        let input = PressedKeys(vec![Key::RightCtrl, Key::LeftShift, Key::F]);
+        assert!(!ctrl.is_down(&input));
+
+        let shift = HotKey::new(Key::LeftShift);
+        assert!(shift.is_down(&PressedKeys(vec![Key::LeftShift])));
+    }
+}
@@ -1,6 +1,9 @@
 mod hotkey;
 pub use hotkey::*;
 
+mod backup;
+pub use backup::*;
+
 mod ui;
 pub use ui::*;
 
@@ -9,3 +12,30 @@ pub use config::*;
 
 mod esp;
 pub use esp::*;
+
+mod font;
+pub use font::*;
+
+mod format;
+pub use format::*;
+
+mod help;
+pub use help::*;
+
+mod playtime;
+pub use playtime::*;
+
+mod profile;
+pub use profile::*;
+
+mod reset;
+pub use reset::*;
+
+mod share_code;
+pub use share_code::*;
+
+mod validate;
+pub use validate::*;
+
+mod watcher;
+pub use watcher::*;
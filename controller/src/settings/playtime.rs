@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{
+        BufReader,
+        BufWriter,
+    },
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use super::get_settings_path;
+
+/// Playtime per day which is persisted next to the app config
+#[derive(Default, Deserialize, Serialize)]
+pub struct PlaytimeHistory {
+    /// Seconds played per day (YYYY-MM-DD)
+    days: BTreeMap<String, u64>,
+
+    /// Playtime which has not yet been added as it's less then a second
+    #[serde(skip)]
+    pending: Duration,
+}
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+impl PlaytimeHistory {
+    pub fn add_playtime(&mut self, day: NaiveDate, duration: Duration) {
+        self.pending += duration;
+
+        let seconds = self.pending.as_secs();
+        if seconds == 0 {
+            return;
+        }
+
+        self.pending -= Duration::from_secs(seconds);
+        *self.days.entry(day_key(day)).or_default() += seconds;
+    }
+
+    pub fn playtime(&self, day: NaiveDate) -> Duration {
+        Duration::from_secs(self.days.get(&day_key(day)).cloned().unwrap_or_default())
+    }
+
+    /// Returns the playtime of the most recent days, starting with the latest.
+    pub fn recent_days(&self, count: usize) -> impl Iterator<Item = (&str, Duration)> {
+        self.days
+            .iter()
+            .rev()
+            .take(count)
+            .map(|(day, seconds)| (day.as_str(), Duration::from_secs(*seconds)))
+    }
+}
+
+impl State for PlaytimeHistory {
+    type Parameter = ();
+
+    fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        match load_playtime_history() {
+            Ok(history) => Ok(history),
+            Err(error) => {
+                log::warn!("加载游戏时长记录失败: {:#}", error);
+                Ok(Default::default())
+            }
+        }
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+pub fn get_playtime_path() -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    let base_dir = settings_path
+        .parent()
+        .context("could not get config directory")?;
+
+    Ok(base_dir.join("playtime.yaml"))
+}
+
+pub fn load_playtime_history() -> anyhow::Result<PlaytimeHistory> {
+    let history_path = get_playtime_path()?;
+    if !history_path.is_file() {
+        return Ok(Default::default());
+    }
+
+    let history = File::open(&history_path).with_context(|| {
+        format!(
+            "failed to open playtime history at {}",
+            history_path.to_string_lossy()
+        )
+    })?;
+    let mut history = BufReader::new(history);
+
+    serde_yaml::from_reader(&mut history).context("failed to parse playtime history")
+}
+
+pub fn save_playtime_history(history: &PlaytimeHistory) -> anyhow::Result<()> {
+    let history_path = get_playtime_path()?;
+    let history_file = File::options()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(&history_path)
+        .with_context(|| {
+            format!(
+                "failed to open playtime history at {}",
+                history_path.to_string_lossy()
+            )
+        })?;
+    let mut history_file = BufWriter::new(history_file);
+
+    serde_yaml::to_writer(&mut history_file, history)
+        .context("failed to serialize playtime history")?;
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+use super::{
+    find_config_file,
+    get_settings_path,
+    ConfigFormat,
+};
+
+/// Name shown for the default profile (see `get_settings_path`)
+pub const DEFAULT_PROFILE_NAME: &str = "默认";
+
+/// Profiles are stored next to the default config file
+pub fn get_profiles_directory() -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    let base_dir = settings_path
+        .parent()
+        .context("could not get config directory")?;
+
+    Ok(base_dir.join("profiles"))
+}
+
+/// Path of the settings file for the given profile.
+/// `None` refers to the default profile.
+pub fn get_profile_settings_path(profile: Option<&str>) -> anyhow::Result<PathBuf> {
+    match profile {
+        Some(profile) => Ok(find_config_file(&get_profiles_directory()?, profile)),
+        None => get_settings_path(),
+    }
+}
+
+/// Profile names are used as file names and therefore only
+/// letters, digits, spaces, dashes and underscores are allowed.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    let name = name.trim();
+    !name.is_empty()
+        && name.len() <= 32
+        && name != DEFAULT_PROFILE_NAME
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+/// List all named profiles sorted by name
+pub fn list_profiles() -> anyhow::Result<Vec<String>> {
+    let profiles_dir = get_profiles_directory()?;
+    if !profiles_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in std::fs::read_dir(&profiles_dir)? {
+        let path = entry?.path();
+        if ConfigFormat::from_path(&path).is_none() {
+            continue;
+        }
+
+        if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+            profiles.push(name.to_string());
+        }
+    }
+
+    profiles.sort();
+    profiles.dedup();
+    Ok(profiles)
+}
+
+fn get_active_profile_path() -> anyhow::Result<PathBuf> {
+    Ok(get_profiles_directory()?.join("active"))
+}
+
+/// Load the name of the last active profile.
+/// Returns `None` for the default profile or if the profile no longer exists.
+pub fn load_active_profile() -> Option<String> {
+    let profile = std::fs::read_to_string(get_active_profile_path().ok()?).ok()?;
+    let profile = profile.trim();
+    if !is_valid_profile_name(profile) {
+        return None;
+    }
+
+    if !get_profile_settings_path(Some(profile)).ok()?.is_file() {
+        log::warn!("上次使用的配置方案 {} 不存在，使用默认配置。", profile);
+        return None;
+    }
+
+    Some(profile.to_string())
+}
+
+pub fn save_active_profile(profile: Option<&str>) -> anyhow::Result<()> {
+    let profiles_dir = get_profiles_directory()?;
+    std::fs::create_dir_all(&profiles_dir)?;
+    std::fs::write(get_active_profile_path()?, profile.unwrap_or_default())?;
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use anyhow::Context;
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+
+use super::{
+    AppSettings,
+    EspPlayerSettings,
+    EspSelector,
+};
+
+/// Settings shown within the "视觉" tab
+pub const VISUALS_FIELDS: &[&str] = &[
+    "esp_mode",
+    "esp_max_entries",
+    "esp_priority",
+    "esp_sort_by_distance",
+    "bomb_timer",
+    "bomb_timer_sound",
+    "round_info",
+    "round_info_phase",
+    "round_info_round_time",
+    "round_info_freeze_time",
+    "round_info_buy_time",
+    "round_stats",
+    "round_stats_log",
+    "match_timeline",
+    "match_timeline_position_interval",
+    "spectators_list",
+    "spectators_list_anchor",
+    "spectators_list_max_rows",
+    "spectators_list_fade",
+    "spectators_list_sound",
+    "spectators_list_hide_on_scoreboard",
+    "spectators_list_steam_profiles",
+    "scoreboard",
+    "aim_warning",
+    "aim_warning_sound",
+    "proximity_alert",
+    "proximity_alert_sound",
+    "proximity_alert_distance",
+    "movement_hud",
+    "flash_indicator",
+    "flash_indicator_dim_esp",
+    "flash_indicator_esp_alpha",
+];
+
+/// Settings shown within the "辅助瞄准" tab (trigger bot, recoil control and targeting)
+pub const AIM_ASSIST_FIELDS: &[&str] = &[
+    "input_dry_run",
+    "trigger_bot_mode",
+    "trigger_bot_sound",
+    "trigger_bot_delay_min",
+    "trigger_bot_delay_max",
+    "trigger_bot_burst",
+    "trigger_bot_class_timings",
+    "trigger_bot_check_target_after_delay",
+    "trigger_bot_team_check",
+    "trigger_bot_selected_target_only",
+    "trigger_bot_weapons",
+    "trigger_bot_warmup_mode",
+    "trigger_bot_warmup_profile",
+    "recoil_control",
+    "recoil_control_strength_x",
+    "recoil_control_strength_y",
+    "fov_circle",
+    "fov_circle_color",
+    "aim_fov",
+    "aim_target_policy",
+    "target_indicator",
+    "target_indicator_color",
+    "target_snapline",
+];
+
+/// Player ESP settings shown within the "功能" section
+pub const ESP_PLAYER_FEATURE_FIELDS: &[&str] = &[
+    "box_type",
+    "skeleton",
+    "tracer_lines",
+    "health_bar",
+    "health_bar_damage_flash",
+    "armor_bar",
+    "info_name",
+    "info_steam_name",
+    "info_clan_tag",
+    "info_rank",
+    "info_weapon",
+    "info_weapon_icon",
+    "info_grenades",
+    "info_distance",
+    "info_hp_text",
+    "info_armor",
+    "info_flag_kit",
+    "info_flag_flashed",
+    "info_flag_scoped",
+    "info_flag_reloading",
+    "info_flag_defusing",
+    "info_flag_planting",
+    "near_players",
+    "near_players_distance",
+];
+
+/// Player ESP settings shown within the "外观" section
+pub const ESP_PLAYER_STYLE_FIELDS: &[&str] = &[
+    "box_color",
+    "box_width",
+    "skeleton_color",
+    "skeleton_width",
+    "skeleton_group_styles",
+    "health_bar_width",
+    "tracer_lines_color",
+    "tracer_lines_width",
+    "info_name_color",
+    "info_name_style",
+    "info_distance_color",
+    "info_distance_style",
+    "info_weapon_color",
+    "info_weapon_style",
+    "info_hp_text_color",
+    "info_hp_text_style",
+    "info_armor_color",
+    "info_flags_color",
+];
+
+/// Credentials and other secrets which must never leave this machine.
+/// They are neither synchronized via the cloud sync nor included in any metrics record.
+pub const SECRET_SETTINGS: &[&str] = &[
+    "steam_web_api_key",
+    "cloud_sync_url",
+    "cloud_sync_username",
+    "cloud_sync_token",
+    "web_radar_password",
+    "remote_control_token",
+];
+
+/// Reset the given top level fields of `value` to their value within `defaults`.
+/// The fields are copied in their serialized form, all other fields stay untouched.
+pub fn reset_fields<T>(value: &mut T, defaults: &T, fields: &[&str]) -> anyhow::Result<()>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let defaults = serde_json::to_value(defaults)?;
+    let defaults = defaults.as_object().context("expected a struct")?;
+
+    let mut current = serde_json::to_value(&*value)?;
+    let current_fields = current.as_object_mut().context("expected a struct")?;
+    for field in fields {
+        match defaults.get(*field) {
+            Some(default) => current_fields.insert(field.to_string(), default.clone()),
+            None => current_fields.remove(*field),
+        };
+    }
+
+    *value = serde_json::from_value(current)?;
+    Ok(())
+}
+
+impl AppSettings {
+    /// Reset a subset of the settings to the defaults of a fresh config
+    pub fn reset_fields(&mut self, fields: &[&str]) -> anyhow::Result<()> {
+        let defaults: AppSettings =
+            serde_yaml::from_str("").context("failed to parse empty config")?;
+        reset_fields(self, &defaults, fields)
+    }
+}
+
+impl EspPlayerSettings {
+    /// Reset a subset of the settings to the defaults of the given target
+    pub fn reset_fields(&mut self, target: &EspSelector, fields: &[&str]) -> anyhow::Result<()> {
+        reset_fields(self, &EspPlayerSettings::new(target), fields)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        AIM_ASSIST_FIELDS,
+        ESP_PLAYER_FEATURE_FIELDS,
+        ESP_PLAYER_STYLE_FIELDS,
+        SECRET_SETTINGS,
+        VISUALS_FIELDS,
+    };
+    use crate::settings::{
+        AppSettings,
+        EspPlayerSettings,
+        EspSelector,
+    };
+
+    #[test]
+    fn fields_exist() {
+        let settings: AppSettings = serde_yaml::from_str("").unwrap();
+        let settings = serde_json::to_value(&settings).unwrap();
+        for field in VISUALS_FIELDS
+            .iter()
+            .chain(AIM_ASSIST_FIELDS)
+            .chain(SECRET_SETTINGS)
+        {
+            assert!(settings.get(field).is_some(), "unknown field {}", field);
+        }
+
+        let esp = serde_json::to_value(EspPlayerSettings::new(&EspSelector::Player)).unwrap();
+        for field in ESP_PLAYER_FEATURE_FIELDS
+            .iter()
+            .chain(ESP_PLAYER_STYLE_FIELDS)
+        {
+            assert!(esp.get(field).is_some(), "unknown field {}", field);
+        }
+    }
+
+    #[test]
+    fn resets_only_given_fields() {
+        let mut settings: AppSettings = serde_yaml::from_str("").unwrap();
+        settings.bomb_timer = !settings.bomb_timer;
+        settings.valthrun_watermark = !settings.valthrun_watermark;
+        let watermark = settings.valthrun_watermark;
+
+        settings.reset_fields(VISUALS_FIELDS).unwrap();
+        let defaults: AppSettings = serde_yaml::from_str("").unwrap();
+        assert_eq!(settings.bomb_timer, defaults.bomb_timer);
+        assert_eq!(settings.valthrun_watermark, watermark);
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use base64::{
+    engine::general_purpose::URL_SAFE_NO_PAD,
+    Engine,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::EspConfig;
+
+/// Prefix of all ESP share codes, the number is increased on breaking changes
+const SHARE_CODE_PREFIX: &str = "VESP1:";
+
+/// ESP settings which can be exchanged as a single line of text
+#[derive(Clone, Serialize, Deserialize)]
+pub enum EspShareCode {
+    /// Style of a single ESP target
+    Config { target: String, config: EspConfig },
+
+    /// Styles and enabled state of all ESP targets
+    Tree {
+        configs: BTreeMap<String, EspConfig>,
+        enabled: BTreeMap<String, bool>,
+    },
+}
+
+impl EspShareCode {
+    pub fn is_share_code(value: &str) -> bool {
+        value.trim().starts_with(SHARE_CODE_PREFIX)
+    }
+
+    pub fn encode(&self) -> anyhow::Result<String> {
+        let payload = serde_json::to_vec(self)?;
+        Ok(format!(
+            "{}{}",
+            SHARE_CODE_PREFIX,
+            URL_SAFE_NO_PAD.encode(payload)
+        ))
+    }
+
+    pub fn decode(value: &str) -> anyhow::Result<Self> {
+        let payload = value
+            .trim()
+            .strip_prefix(SHARE_CODE_PREFIX)
+            .context("not an ESP share code")?;
+
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload)
+            .context("invalid share code encoding")?;
+
+        serde_json::from_slice(&payload).context("invalid share code content")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EspShareCode;
+    use crate::settings::{
+        EspConfig,
+        EspPlayerSettings,
+        EspSelector,
+    };
+
+    #[test]
+    fn roundtrip() {
+        let config = EspConfig::Player(EspPlayerSettings::new(&EspSelector::Player));
+        let code = EspShareCode::Config {
+            target: EspSelector::Player.config_key(),
+            config,
+        }
+        .encode()
+        .unwrap();
+        assert!(EspShareCode::is_share_code(&code));
+
+        match EspShareCode::decode(&format!(" {}\n", code)).unwrap() {
+            EspShareCode::Config {
+                target,
+                config: decoded,
+            } => {
+                assert_eq!(target, "player");
+                assert!(decoded == config);
+            }
+            EspShareCode::Tree { .. } => panic!("expected a single config"),
+        }
+
+        assert!(EspShareCode::decode("VESP1:???").is_err());
+    }
+}
@@ -1,20 +1,29 @@
 use std::{
-    collections::btree_map::Entry,
+    collections::{
+        btree_map::Entry,
+        BTreeMap,
+    },
+    path::PathBuf,
     sync::{
         atomic::Ordering,
         Arc,
         Mutex,
     },
-    time::Instant,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use cs2::{
     BuildInfo,
     CS2Handle,
+    ServerInfo,
 };
 use imgui::{
     Condition,
     ImColor32,
+    MouseButton,
     SelectableFlags,
     StyleColor,
     StyleVar,
@@ -27,34 +36,96 @@ use obfstr::obfstr;
 use url::Url;
 
 use super::{
+    esp_config_changes,
+    CloudSyncProvider,
     Color,
+    ColorSpace,
     EspColor,
     EspColorType,
     EspConfig,
+    EspGradient,
+    EspGradientSource,
+    EspPriority,
     EspSelector,
+    EspShareCode,
+    EspTextEffect,
+    EspTextStyle,
+    FpsLimitMode,
+    FramePacing,
     KeyToggleMode,
+    RenderBackend,
+    ScreenAnchor,
+    TargetSelectionPolicy,
+    TriggerBotClassTimings,
+    TriggerBotWarmupMode,
+    TriggerBotWeaponClass,
+    TriggerBotWeaponMatrix,
+    ESP_GRADIENT_MAX_STOPS,
 };
 use crate::{
+    cloud_sync::CloudSyncStatus,
+    enhancements,
+    i18n::{
+        tr,
+        translate,
+        Language,
+    },
+    perf_stats::{
+        TimingSeries,
+        PERFORMANCE_HISTORY,
+    },
     radar::{
         self,
         WebRadar,
         WebRadarState,
     },
     settings::{
+        get_profile_settings_path,
+        is_valid_profile_name,
+        list_config_backups,
+        list_profiles,
+        save_app_settings,
+        setting_help_text,
         AppSettings,
+        ConfigBackup,
         EspBoxType,
         EspHealthBar,
         EspPlayerSettings,
         EspTracePosition,
+        PlaytimeHistory,
+        AIM_ASSIST_FIELDS,
+        DEFAULT_PROFILE_NAME,
+        ESP_PLAYER_FEATURE_FIELDS,
+        ESP_PLAYER_STYLE_FIELDS,
+        VISUALS_FIELDS,
     },
+    updater::UpdateStatus,
     utils::{
         self,
         ImGuiKey,
         ImguiComboEnum,
     },
     Application,
+    METRICS_RECORD_TYPES,
 };
 
+/// ESP style which has been pasted from the clipboard and is waiting to be confirmed
+enum EspStyleImport {
+    Invalid {
+        message: String,
+    },
+    Pending {
+        target: EspSelector,
+        config: EspConfig,
+        changes: Vec<String>,
+    },
+    PendingTree {
+        configs: BTreeMap<String, EspConfig>,
+        enabled: BTreeMap<String, bool>,
+        changes: Vec<String>,
+    },
+}
+
 enum EspPlayerActiveHeader {
     Features,
     Style,
@@ -63,30 +134,73 @@ enum EspPlayerActiveHeader {
 pub struct SettingsUI {
     discord_link_copied: Option<Instant>,
     radar_session_copied: Option<Instant>,
+    connect_command_copied: Option<Instant>,
+
+    /// Result of the last diagnostics dump (file path or error message)
+    diagnostics_dump: Option<Result<PathBuf, String>>,
 
     esp_selected_target: EspSelector,
     esp_pending_target: Option<EspSelector>,
+    esp_style_import: Option<EspStyleImport>,
 
     esp_player_active_header: EspPlayerActiveHeader,
+
+    /// Cached list of the named settings profiles
+    profiles: Option<Vec<String>>,
+    profile_name_input: String,
+
+    /// Cached list of the backups of the active profile
+    config_backups: Option<Vec<ConfigBackup>>,
+    config_backup_selected: usize,
+
+    /// UI scale while the slider is being dragged.
+    /// Rebuilding the font atlas is expensive, therefore the scale gets applied on release.
+    ui_scale_input: Option<u32>,
+
+    /// Time span (seconds) shown within the performance graphs
+    performance_window: u32,
+
+    /// Show the help markers next to the settings (mirrors `AppSettings::settings_help`)
+    help_markers: bool,
 }
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Width of the player preview next to the ESP settings
+const ESP_PREVIEW_WIDTH: f32 = 200.0;
+
 impl SettingsUI {
     pub fn new() -> Self {
         Self {
             discord_link_copied: None,
             radar_session_copied: None,
+            connect_command_copied: None,
+
+            diagnostics_dump: None,
 
             esp_selected_target: EspSelector::None,
             esp_pending_target: None,
+            esp_style_import: None,
 
             esp_player_active_header: EspPlayerActiveHeader::Features,
+
+            profiles: None,
+            profile_name_input: String::new(),
+
+            config_backups: None,
+            config_backup_selected: 0,
+
+            ui_scale_input: None,
+
+            performance_window: 10,
+
+            help_markers: false,
         }
     }
 
     pub fn render(&mut self, app: &Application, ui: &imgui::Ui) {
         let content_font = ui.current_font().id();
-        let _title_font = ui.push_font(app.fonts.valthrun);
+        let _title_font = app.fonts.borrow().map(|fonts| ui.push_font(fonts.valthrun));
         ui.window(obfstr!("Valthrun-CHS"))
             .size([600.0, 300.0], Condition::FirstUseEver)
             .title_bar(false)
@@ -116,30 +230,68 @@ impl SettingsUI {
 
                 let _content_font = ui.push_font(content_font);
                 let mut settings = app.settings_mut();
+                self.help_markers = settings.settings_help;
 
                 if let Some(_tab_bar) = ui.tab_bar("main") {
-                    if let Some(_tab) = ui.tab_item("信息") {
+                    if let Some(_tab) = ui.tab_item(tr!("信息")) {
                         let build_info = app.app_state.resolve::<BuildInfo>(()).ok();
 
-                        ui.text(obfstr!(
+                        ui.text(tr!(
                             "Valthrun-CHS 是一个开源的 CS2 外部只读内核游戏增强器。"
                         ));
                         ui.text(&format!(
-                            "{} 版本 {} ({})",
+                            "{} {} {} ({})",
                             obfstr!("Valthrun-CHS"),
+                            tr!("版本"),
                             VERSION,
                             env!("BUILD_TIME")
                         ));
                         ui.text(&format!(
-                            "{} 版本 {} ({})",
+                            "{} {} {} ({})",
                             obfstr!("CS2"),
+                            tr!("版本"),
                             build_info.as_ref().map_or("error", |info| &info.revision),
                             build_info
                                 .as_ref()
                                 .map_or("error", |info| &info.build_datetime)
                         ));
+                        if app.read_error_monitor.is_alerting() {
+                            ui.text(" ");
+                            ui.text_colored(
+                                [1.0, 0.3, 0.3, 1.0],
+                                &format!(
+                                    "{} ({:.1}%)",
+                                    tr!("警告: 内存读取失败率过高，游戏可能已更新。"),
+                                    app.read_error_monitor.failure_rate() * 100.0
+                                ),
+                            );
+                        }
+                        if ui.button(tr!("导出诊断信息")) {
+                            self.diagnostics_dump = Some(
+                                app.dump_diagnostics()
+                                    .map_err(|error| format!("{:#}", error)),
+                            );
+                        }
+                        match &self.diagnostics_dump {
+                            Some(Ok(path)) => {
+                                ui.same_line();
+                                ui.text(&format!("{} {}", tr!("已保存到"), path.to_string_lossy()));
+                            }
+                            Some(Err(error)) => {
+                                ui.same_line();
+                                ui.text_colored(
+                                    [1.0, 0.3, 0.3, 1.0],
+                                    &format!("{}: {}", tr!("导出失败"), error),
+                                );
+                            }
+                            None => {}
+                        }
+
+                        ui.text(" ");
+                        self.render_update_check(&mut settings, app, ui);
+
                         ui.text(" ");
-                        ui.text(obfstr!("由 NKXingXh 汉化"));
+                        ui.text(tr!("由 NKXingXh 汉化"));
                         ui.text(&format!(
                             "https://github.com/{}/{}",
                             obfstr!("nkxingxh"),
@@ -153,7 +305,7 @@ impl SettingsUI {
                         ui.dummy([0.0, ydummy]);
                         ui.separator();
 
-                        ui.text(obfstr!("加入 discord (English):"));
+                        ui.text(tr!("加入 discord (English):"));
                         ui.text_colored(
                             [0.18, 0.51, 0.97, 1.0],
                             obfstr!("https://discord.gg/ecKbpAPW5T"),
@@ -175,31 +327,84 @@ impl SettingsUI {
 
                         if show_copied {
                             ui.same_line();
-                            ui.text("(已复制)");
+                            ui.text(tr!("(已复制)"));
                         }
                     }
 
-                    if let Some(_) = ui.tab_item("热键") {
-                        ui.button_key(
-                            obfstr!("调出菜单"),
-                            &mut settings.key_settings,
+                    if let Some(_) = ui.tab_item(tr!("服务器")) {
+                        self.render_server_info(app, ui);
+                    }
+
+                    if let Some(_) = ui.tab_item(tr!("配置方案")) {
+                        self.render_profiles(app, &mut settings, ui);
+                    }
+
+                    if let Some(_) = ui.tab_item(tr!("热键")) {
+                        ui.button_key(&tr!("调出菜单"), &mut settings.key_settings, [150.0, 0.0]);
+                        ui.button_key_optional(
+                            &tr!("紧急隐藏"),
+                            &mut settings.key_panic,
                             [150.0, 0.0],
                         );
-
-                        {
-                            let _enabled = ui.begin_enabled(matches!(
-                                settings.esp_mode,
-                                KeyToggleMode::Toggle | KeyToggleMode::Trigger
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!(
+                                "立即隐藏所有视觉效果，再次按下恢复。不会修改配置"
                             ));
-                            ui.button_key_optional(
-                                obfstr!("ESP 切换/触发"),
-                                &mut settings.esp_toogle,
-                                [150.0, 0.0],
+                        }
+                        if settings.key_panic.is_some() {
+                            ui.indent();
+                            ui.checkbox(
+                                tr!("同时关闭设置菜单"),
+                                &mut settings.panic_close_settings,
                             );
+                            ui.unindent();
+                        }
+
+                        ui.button_key_optional(
+                            &tr!("截图"),
+                            &mut settings.key_capture,
+                            [150.0, 0.0],
+                        );
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!(
+                                "将当前画面保存为 PNG 到配置目录下的 captures 文件夹"
+                            ));
+                        }
+                        if settings.key_capture.is_some() {
+                            ui.indent();
+                            ui.checkbox(tr!("包含游戏画面"), &mut settings.capture_include_game);
+                            ui.unindent();
+                        }
+
+                        ui.set_next_item_width(150.0);
+                        ui.slider_config(tr!("长按触发时间 (ms)"), 0, 2000)
+                            .build(&mut settings.hotkey_hold_duration);
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!("\"长按触发\" 模式下需要按住热键的最短时间"));
+                        }
+                        ui.set_next_item_width(150.0);
+                        ui.slider_config(tr!("双击间隔 (ms)"), 100, 1000)
+                            .build(&mut settings.hotkey_double_tap_interval);
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!("\"双击切换\" 模式下两次按键之间的最长间隔"));
+                        }
+
+                        for enhancement in app.enhancements.iter() {
+                            let enhancement = enhancement.borrow();
+                            for action in enhancement.hotkey_actions() {
+                                let mut key = settings.hotkey(action).cloned();
+                                if ui.button_key_optional(
+                                    &format!("{}##{}", action.name, action.id),
+                                    &mut key,
+                                    [150.0, 0.0],
+                                ) {
+                                    settings.set_hotkey(action, key);
+                                }
+                            }
                         }
                     }
 
-                    if let Some(_tab) = ui.tab_item(obfstr!("视觉")) {
+                    if let Some(_tab) = ui.tab_item(tr!("视觉")) {
                         ui.set_next_item_width(150.0);
                         ui.combo_enum(
                             obfstr!("ESP"),
@@ -208,54 +413,230 @@ impl SettingsUI {
                                 (KeyToggleMode::Trigger, "按住键触发"),
                                 (KeyToggleMode::TriggerInverted, "反向触发"),
                                 (KeyToggleMode::Toggle, "按键切换"),
+                                (KeyToggleMode::DoubleTap, "双击切换"),
+                                (KeyToggleMode::TriggerHold, "长按触发"),
                                 (KeyToggleMode::AlwaysOn, "保持启用"),
                             ],
                             &mut settings.esp_mode,
                         );
+                        self.render_help_marker(ui, "esp_mode");
+
+                        if settings.esp_mode != KeyToggleMode::Off {
+                            ui.indent();
+                            ui.set_next_item_width(150.0);
+                            ui.slider_config(tr!("最大显示数量 (0 = 不限制)"), 0, 64)
+                                .build(&mut settings.esp_max_entries);
+                            self.render_help_marker(ui, "esp_max_entries");
+                            if settings.esp_max_entries > 0 {
+                                ui.set_next_item_width(150.0);
+                                ui.combo_enum(
+                                    tr!("优先显示"),
+                                    &[
+                                        (EspPriority::Distance, "最近的玩家"),
+                                        (EspPriority::Danger, "最危险的敌人"),
+                                    ],
+                                    &mut settings.esp_priority,
+                                );
+                            }
+                            ui.checkbox(
+                                tr!("近处玩家绘制在上层"),
+                                &mut settings.esp_sort_by_distance,
+                            );
+                            self.render_help_marker(ui, "esp_sort_by_distance");
+                            ui.checkbox(tr!("发光效果"), &mut settings.esp_glow);
+                            self.render_help_marker(ui, "esp_glow");
+                            if settings.esp_glow {
+                                ui.indent();
+                                ui.set_next_item_width(150.0);
+                                ui.slider_config(tr!("发光半径"), 1, 32)
+                                    .build(&mut settings.esp_glow_radius);
+                                ui.set_next_item_width(150.0);
+                                ui.slider_config(tr!("发光强度 (%)"), 50, 400)
+                                    .build(&mut settings.esp_glow_intensity);
+                                ui.unindent();
+                            }
+                            ui.unindent();
+                        }
 
-                        ui.checkbox(obfstr!("炸弹计时器"), &mut settings.bomb_timer);
-                        ui.checkbox(obfstr!("旁观者名单"), &mut settings.spectators_list);
+                        ui.checkbox(tr!("炸弹计时器"), &mut settings.bomb_timer);
+                        self.render_help_marker(ui, "bomb_timer");
+                        if settings.bomb_timer {
+                            ui.same_line();
+                            ui.checkbox(
+                                tr!("声音提示##bomb_timer"),
+                                &mut settings.bomb_timer_sound,
+                            );
+                            self.render_help_marker(ui, "bomb_timer_sound");
+                        }
+                        ui.checkbox(tr!("回合信息"), &mut settings.round_info);
+                        self.render_help_marker(ui, "round_info");
+                        if settings.round_info {
+                            ui.indent();
+                            ui.checkbox(tr!("回合阶段"), &mut settings.round_info_phase);
+                            ui.checkbox(tr!("回合剩余时间"), &mut settings.round_info_round_time);
+                            ui.checkbox(tr!("冻结时间"), &mut settings.round_info_freeze_time);
+                            ui.checkbox(tr!("购买时间"), &mut settings.round_info_buy_time);
+                            ui.unindent();
+                        }
+                        ui.checkbox(tr!("回合统计"), &mut settings.round_stats);
+                        self.render_help_marker(ui, "round_stats");
+                        if settings.round_stats {
+                            ui.same_line();
+                            ui.checkbox(tr!("记录到文件"), &mut settings.round_stats_log);
+                        }
+                        ui.checkbox(tr!("记录比赛时间线"), &mut settings.match_timeline);
+                        self.render_help_marker(ui, "match_timeline");
+                        if settings.match_timeline {
+                            ui.indent();
+                            ui.set_next_item_width(150.0);
+                            ui.slider_config(tr!("位置记录间隔"), 1, 60)
+                                .display_format("%ds")
+                                .build(&mut settings.match_timeline_position_interval);
+                            ui.unindent();
+                        }
+                        ui.checkbox(tr!("旁观者名单"), &mut settings.spectators_list);
+                        self.render_help_marker(ui, "spectators_list");
+                        if settings.spectators_list {
+                            ui.indent();
+                            ui.set_next_item_width(150.0);
+                            ui.combo_enum(
+                                tr!("位置"),
+                                &[
+                                    (ScreenAnchor::TopLeft, "左上"),
+                                    (ScreenAnchor::TopRight, "右上"),
+                                    (ScreenAnchor::Left, "左侧"),
+                                    (ScreenAnchor::Right, "右侧"),
+                                    (ScreenAnchor::BottomLeft, "左下"),
+                                    (ScreenAnchor::BottomRight, "右下"),
+                                ],
+                                &mut settings.spectators_list_anchor,
+                            );
+                            ui.set_next_item_width(150.0);
+                            ui.slider_config(tr!("最大行数 (0 = 不限制)"), 0, 32)
+                                .build(&mut settings.spectators_list_max_rows);
+                            ui.checkbox(tr!("淡入淡出"), &mut settings.spectators_list_fade);
+                            ui.checkbox(
+                                tr!("有人开始观战时声音提示"),
+                                &mut settings.spectators_list_sound,
+                            );
+                            ui.checkbox(
+                                tr!("按住记分板键时隐藏"),
+                                &mut settings.spectators_list_hide_on_scoreboard,
+                            );
+                            ui.checkbox(
+                                tr!("显示 Steam 头像和昵称"),
+                                &mut settings.spectators_list_steam_profiles,
+                            );
+                            if ui.is_item_hovered() {
+                                ui.tooltip_text(tr!(
+                                    "通过 Steam Web API 查询旁观者资料，结果会缓存在本地"
+                                ));
+                            }
+                            if settings.spectators_list_steam_profiles {
+                                ui.set_next_item_width(250.0);
+                                ui.input_text(
+                                    tr!("Steam Web API 密钥"),
+                                    &mut settings.steam_web_api_key,
+                                )
+                                .password(true)
+                                .build();
+                            }
+                            ui.unindent();
+                        }
+                        ui.checkbox(tr!("记分板"), &mut settings.scoreboard);
+                        self.render_help_marker(ui, "scoreboard");
+                        ui.checkbox(tr!("被瞄准警告"), &mut settings.aim_warning);
+                        self.render_help_marker(ui, "aim_warning");
+                        if settings.aim_warning {
+                            ui.same_line();
+                            ui.checkbox(tr!("声音提示"), &mut settings.aim_warning_sound);
+                        }
+                        ui.checkbox(tr!("身后敌人警报"), &mut settings.proximity_alert);
+                        self.render_help_marker(ui, "proximity_alert");
+                        if settings.proximity_alert {
+                            ui.same_line();
+                            ui.checkbox(
+                                tr!("声音提示##proximity_alert"),
+                                &mut settings.proximity_alert_sound,
+                            );
+                            ui.indent();
+                            ui.set_next_item_width(150.0);
+                            ui.slider_config(tr!("警报距离 (米)"), 1, 50)
+                                .build(&mut settings.proximity_alert_distance);
+                            ui.unindent();
+                        }
+                        ui.checkbox(tr!("移动信息 HUD"), &mut settings.movement_hud);
+                        self.render_help_marker(ui, "movement_hud");
+                        ui.checkbox(tr!("致盲倒计时"), &mut settings.flash_indicator);
+                        self.render_help_marker(ui, "flash_indicator");
+                        if settings.flash_indicator {
+                            ui.indent();
+                            ui.checkbox(
+                                tr!("致盲时降低 ESP 亮度"),
+                                &mut settings.flash_indicator_dim_esp,
+                            );
+                            if settings.flash_indicator_dim_esp {
+                                ui.set_next_item_width(150.0);
+                                ui.slider_config(tr!("ESP 最低不透明度"), 0, 100)
+                                    .display_format("%d%%")
+                                    .build(&mut settings.flash_indicator_esp_alpha);
+                            }
+                            ui.unindent();
+                        }
+
+                        ui.separator();
+                        if Self::render_reset_button(ui, "visuals", &tr!("将此页恢复默认")) {
+                            if let Err(error) = settings.reset_fields(VISUALS_FIELDS) {
+                                log::warn!("重置视觉设置失败: {:#}", error);
+                            }
+                        }
                     }
 
                     if let Some(_tab) = ui.tab_item(obfstr!("ESP")) {
                         if settings.esp_mode == KeyToggleMode::Off {
                             let _style =
                                 ui.push_style_color(StyleColor::Text, [1.0, 0.76, 0.03, 1.0]);
-                            ui.text("ESP 已经关闭。");
-                            ui.text("请在 \"视觉\" 菜单中启用 \"ESP\"");
+                            ui.text(tr!("ESP 已经关闭。"));
+                            ui.text(tr!("请在 \"视觉\" 菜单中启用 \"ESP\""));
                         } else {
-                            self.render_esp_settings(&mut *settings, ui);
+                            self.render_esp_settings(&mut *settings, app, ui);
                         }
                     }
 
-                    if let Some(_) = ui.tab_item(obfstr!("辅助瞄准")) {
+                    if let Some(_) = ui.tab_item(tr!("辅助瞄准")) {
+                        ui.checkbox(tr!("演练模式"), &mut settings.input_dry_run);
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!(
+                                "只显示自动开火和鼠标修正的效果，不向游戏发送任何输入"
+                            ));
+                        }
+                        ui.separator();
+
                         ui.set_next_item_width(150.0);
                         ui.combo_enum(
-                            obfstr!("自动开火"),
+                            tr!("自动开火"),
                             &[
                                 (KeyToggleMode::Off, "始终关闭"),
                                 (KeyToggleMode::Trigger, "按住键触发"),
                                 (KeyToggleMode::TriggerInverted, "反向触发"),
                                 (KeyToggleMode::Toggle, "按键切换"),
+                                (KeyToggleMode::DoubleTap, "双击切换"),
+                                (KeyToggleMode::TriggerHold, "长按触发"),
                                 (KeyToggleMode::AlwaysOn, "保持启用"),
                             ],
                             &mut settings.trigger_bot_mode,
                         );
+                        self.render_help_marker(ui, "trigger_bot_mode");
 
-                        if !matches!(
-                            settings.trigger_bot_mode,
-                            KeyToggleMode::Off | KeyToggleMode::AlwaysOn
-                        ) {
-                            ui.button_key_optional(
-                                obfstr!("自动开火热键"),
-                                &mut settings.key_trigger_bot,
-                                [150.0, 0.0],
-                            );
-                        }
                         if !matches!(settings.trigger_bot_mode, KeyToggleMode::Off) {
+                            ui.checkbox(
+                                tr!("开启/关闭时声音提示"),
+                                &mut settings.trigger_bot_sound,
+                            );
+
                             let mut values_updated = false;
 
-                            ui.text(obfstr!("开火延迟: "));
+                            ui.text(tr!("开火延迟: "));
                             ui.same_line();
 
                             let slider_width = (ui.current_column_width() / 2.0 - 20.0)
@@ -274,6 +655,7 @@ impl SettingsUI {
                                 .slider_config("##delay_max", 0, 250)
                                 .display_format("%dms")
                                 .build(&mut settings.trigger_bot_delay_max);
+                            self.render_help_marker(ui, "trigger_bot_delay_min");
 
                             if values_updated {
                                 /* fixup min/max */
@@ -288,49 +670,588 @@ impl SettingsUI {
                                 settings.trigger_bot_delay_max = delay_max;
                             }
 
+                            ui.set_next_item_width(150.0);
+                            ui.slider_config(tr!("连发时长 (0 = 持续开火)"), 0, 1000)
+                                .display_format("%dms")
+                                .build(&mut settings.trigger_bot_burst);
+                            self.render_help_marker(ui, "trigger_bot_burst");
+                            self.render_trigger_bot_class_timings(
+                                &mut settings.trigger_bot_class_timings,
+                                ui,
+                            );
+
                             ui.checkbox(
-                                obfstr!("延迟后重新测试触发目标"),
+                                tr!("延迟后重新测试触发目标"),
                                 &mut settings.trigger_bot_check_target_after_delay,
                             );
-                            ui.checkbox(obfstr!("不打友军"), &mut settings.trigger_bot_team_check);
+                            self.render_help_marker(ui, "trigger_bot_check_target_after_delay");
+                            ui.checkbox(tr!("不打友军"), &mut settings.trigger_bot_team_check);
+                            self.render_help_marker(ui, "trigger_bot_team_check");
+                            ui.checkbox(
+                                tr!("仅对选定目标开火"),
+                                &mut settings.trigger_bot_selected_target_only,
+                            );
+                            self.render_help_marker(ui, "trigger_bot_selected_target_only");
+                            self.render_trigger_bot_weapons(&mut settings.trigger_bot_weapons, ui);
+
+                            ui.set_next_item_width(150.0);
+                            ui.combo_enum(
+                                tr!("热身/刀局时"),
+                                &[
+                                    (TriggerBotWarmupMode::Unchanged, "不变"),
+                                    (TriggerBotWarmupMode::Disabled, "关闭自动开火"),
+                                    (TriggerBotWarmupMode::WarmupProfile, "使用热身设置"),
+                                ],
+                                &mut settings.trigger_bot_warmup_mode,
+                            );
+                            self.render_help_marker(ui, "trigger_bot_warmup_mode");
+                            if settings.trigger_bot_warmup_mode
+                                == TriggerBotWarmupMode::WarmupProfile
+                            {
+                                let profile = &mut settings.trigger_bot_warmup_profile;
+                                ui.text(tr!("热身开火延迟: "));
+                                ui.same_line();
+
+                                let slider_width = (ui.current_column_width() / 2.0 - 20.0)
+                                    .min(300.0)
+                                    .max(50.0);
+                                ui.set_next_item_width(slider_width);
+                                ui.slider_config("##warmup_delay_min", 0, 250)
+                                    .display_format("%dms")
+                                    .build(&mut profile.delay_min);
+                                ui.same_line();
+                                ui.text(" - ");
+                                ui.same_line();
+                                ui.set_next_item_width(slider_width);
+                                ui.slider_config("##warmup_delay_max", 0, 250)
+                                    .display_format("%dms")
+                                    .build(&mut profile.delay_max);
+                                profile.delay_max = profile.delay_max.max(profile.delay_min);
+
+                                ui.checkbox(tr!("热身时不打友军"), &mut profile.team_check);
+                            }
                             ui.separator();
                         }
 
+                        ui.checkbox(tr!("后坐力控制 (RCS)"), &mut settings.recoil_control);
+                        self.render_help_marker(ui, "recoil_control");
+                        if settings.recoil_control {
+                            ui.set_next_item_width(150.0);
+                            ui.slider_config(tr!("水平补偿强度"), 0, 100)
+                                .display_format("%d%%")
+                                .build(&mut settings.recoil_control_strength_x);
+                            ui.set_next_item_width(150.0);
+                            ui.slider_config(tr!("垂直补偿强度"), 0, 100)
+                                .display_format("%d%%")
+                                .build(&mut settings.recoil_control_strength_y);
+                            ui.text_disabled(tr!("根据当前武器自动选择弹道数据"));
+                        }
+                        ui.separator();
+
+                        ui.checkbox(tr!("显示 FOV 圆"), &mut settings.fov_circle);
+                        ui.same_line();
+                        Self::render_color_picker(
+                            ui,
+                            "##fov_circle_color",
+                            &mut settings.fov_circle_color,
+                        );
+                        ui.set_next_item_width(150.0);
+                        ui.slider_config(obfstr!("FOV"), 0.5, 30.0)
+                            .display_format("%.1f°")
+                            .build(&mut settings.aim_fov);
+                        self.render_help_marker(ui, "aim_fov");
+
+                        ui.set_next_item_width(150.0);
+                        ui.combo_enum(
+                            tr!("目标选择"),
+                            &[
+                                (TargetSelectionPolicy::ClosestToCrosshair, "离准星最近"),
+                                (TargetSelectionPolicy::LowestHealth, "血量最低"),
+                                (TargetSelectionPolicy::ClosestDistance, "距离最近"),
+                                (TargetSelectionPolicy::HighestThreat, "威胁最高"),
+                            ],
+                            &mut settings.aim_target_policy,
+                        );
+                        self.render_help_marker(ui, "aim_target_policy");
+
+                        ui.checkbox(tr!("高亮当前目标"), &mut settings.target_indicator);
+                        ui.same_line();
+                        Self::render_color_picker(
+                            ui,
+                            "##target_indicator_color",
+                            &mut settings.target_indicator_color,
+                        );
+                        self.render_help_marker(ui, "target_indicator");
+                        ui.checkbox(tr!("目标连线"), &mut settings.target_snapline);
+                        self.render_help_marker(ui, "target_snapline");
+
+                        ui.separator();
+                        if Self::render_reset_button(ui, "aim_assist", &tr!("将此页恢复默认"))
+                        {
+                            if let Err(error) = settings.reset_fields(AIM_ASSIST_FIELDS) {
+                                log::warn!("重置辅助瞄准设置失败: {:#}", error);
+                            }
+                        }
+
                         //ui.checkbox("Simle Recoil Helper", &mut settings.aim_assist_recoil);
                     }
 
-                    if let Some(_) = ui.tab_item("雷达") {
+                    if let Some(_) = ui.tab_item(tr!("雷达")) {
                         let mut web_radar = app.web_radar.borrow_mut();
                         self.render_web_radar(&mut settings, &mut web_radar, &app.cs2, ui);
                     }
 
-                    if let Some(_) = ui.tab_item("杂项") {
-                        ui.checkbox(obfstr!("Valthrun 水印"), &mut settings.valthrun_watermark);
+                    if let Some(_) = ui.tab_item(tr!("杂项")) {
+                        ui.set_next_item_width(150.0);
+                        ui.combo_enum(
+                            "语言 / Language",
+                            &Language::VARIANTS.map(|language| (language, language.display_name())),
+                            &mut settings.language,
+                        );
+
+                        ui.set_next_item_width(150.0);
+                        ui.slider_config(tr!("提示音量"), 0, 100)
+                            .display_format("%d%%")
+                            .build(&mut settings.audio_volume);
+                        self.render_help_marker(ui, "audio_volume");
+
+                        ui.checkbox(tr!("Valthrun 水印"), &mut settings.valthrun_watermark);
+                        if settings.valthrun_watermark {
+                            ui.indent();
+                            ui.set_next_item_width(150.0);
+                            ui.combo_enum(
+                                tr!("水印位置"),
+                                &[
+                                    (ScreenAnchor::TopLeft, "左上"),
+                                    (ScreenAnchor::TopRight, "右上"),
+                                    (ScreenAnchor::Left, "左侧"),
+                                    (ScreenAnchor::Right, "右侧"),
+                                    (ScreenAnchor::BottomLeft, "左下"),
+                                    (ScreenAnchor::BottomRight, "右下"),
+                                ],
+                                &mut settings.watermark_anchor,
+                            );
+                            ui.checkbox(tr!("名称"), &mut settings.watermark_brand);
+                            ui.same_line();
+                            ui.checkbox(obfstr!("FPS"), &mut settings.watermark_fps);
+                            ui.same_line();
+                            ui.checkbox(tr!("内存读取"), &mut settings.watermark_read_calls);
+                            ui.same_line();
+                            ui.checkbox(tr!("时间"), &mut settings.watermark_time);
+                            ui.same_line();
+                            ui.checkbox(tr!("配置方案"), &mut settings.watermark_profile);
+
+                            Self::render_color_picker(
+                                ui,
+                                "##watermark_color",
+                                &mut settings.watermark_color,
+                            );
+                            ui.same_line();
+                            ui.text(tr!("文字颜色"));
+
+                            ui.checkbox(tr!("背景"), &mut settings.watermark_background);
+                            if settings.watermark_background {
+                                ui.same_line();
+                                Self::render_color_picker(
+                                    ui,
+                                    "##watermark_background_color",
+                                    &mut settings.watermark_background_color,
+                                );
+                            }
+                            ui.unindent();
+                        }
+                        ui.checkbox(tr!("显示设置说明 (?)"), &mut settings.settings_help);
+                        ui.checkbox(
+                            tr!("配置文件修改后自动重新加载"),
+                            &mut settings.config_hot_reload,
+                        );
+                        self.render_help_marker(ui, "config_hot_reload");
 
                         if ui.checkbox(
-                            obfstr!("截图时隐藏叠加层"),
+                            tr!("截图时隐藏叠加层"),
                             &mut settings.hide_overlay_from_screen_capture,
                         ) {
                             app.settings_screen_capture_changed
                                 .store(true, Ordering::Relaxed);
                         }
+                        self.render_help_marker(ui, "hide_overlay_from_screen_capture");
 
-                        if ui.checkbox(
-                            obfstr!("显示渲染调试叠加层"),
-                            &mut settings.render_debug_window,
-                        ) {
+                        ui.checkbox(
+                            tr!("菜单打开时拦截点击"),
+                            &mut settings.settings_interactive,
+                        );
+                        self.render_help_marker(ui, "settings_interactive");
+                        ui.checkbox(tr!("窗口停靠"), &mut settings.window_docking);
+                        self.render_help_marker(ui, "window_docking");
+                        ui.checkbox(
+                            tr!("菜单打开时调暗背景"),
+                            &mut settings.settings_dim_background,
+                        );
+                        ui.checkbox(tr!("显示通知"), &mut settings.notifications);
+                        self.render_help_marker(ui, "notifications");
+
+                        if ui.checkbox(tr!("显示渲染调试叠加层"), &mut settings.render_debug_window)
+                        {
                             app.settings_render_debug_window_changed
                                 .store(true, Ordering::Relaxed);
                         }
 
+                        ui.checkbox(
+                            tr!("使用低级键盘/鼠标钩子接收热键"),
+                            &mut settings.input_hook,
+                        );
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!(
+                                "即使叠加层未获得输入或按键时间短于一帧，也不会遗漏热键"
+                            ));
+                        }
+
+                        ui.checkbox(tr!("允许绑定手柄按键为热键"), &mut settings.gamepad_hotkeys);
+
+                        let mut ui_scale = self.ui_scale_input.unwrap_or(settings.ui_scale);
+                        ui.set_next_item_width(150.0);
+                        if ui
+                            .slider_config(tr!("界面缩放 (%)"), 50, 300)
+                            .build(&mut ui_scale)
+                        {
+                            self.ui_scale_input = Some(ui_scale);
+                        }
+                        if ui.is_item_deactivated_after_edit() {
+                            if let Some(ui_scale) = self.ui_scale_input.take() {
+                                settings.ui_scale = ui_scale;
+                            }
+                        }
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!("系统显示缩放 (DPI) 会自动应用"));
+                        }
+
                         // FPS Limit
-                        ui.slider_config("叠加层 FPS 限制", 0, 960)
-                            .build(&mut settings.overlay_fps_limit);
+                        let mut fps_mode = settings.fps_limit_mode();
+                        ui.set_next_item_width(150.0);
+                        if ui.combo_enum(
+                            tr!("叠加层 FPS 限制"),
+                            &[
+                                (FpsLimitMode::MonitorRefreshRate, "显示器刷新率"),
+                                (FpsLimitMode::Custom, "自定义"),
+                                (FpsLimitMode::Unlimited, "无限制"),
+                            ],
+                            &mut fps_mode,
+                        ) {
+                            settings.overlay_fps_mode = Some(fps_mode);
+                        }
+                        if fps_mode == FpsLimitMode::Custom {
+                            let mut fps_limit = settings.fps_limit();
+                            if ui
+                                .slider_config(tr!("叠加层 FPS"), 1, 960)
+                                .build(&mut fps_limit)
+                            {
+                                settings.overlay_fps_limit = Some(fps_limit);
+                            }
+                        }
+                        ui.slider_config(tr!("后台 FPS 限制 (0 = 不限制)"), 0, 240)
+                            .build(&mut settings.overlay_fps_limit_background);
+                        self.render_help_marker(ui, "overlay_fps_limit_background");
+
+                        ui.checkbox(
+                            tr!("游戏最小化时进入空闲模式"),
+                            &mut settings.overlay_idle_when_hidden,
+                        );
+                        self.render_help_marker(ui, "overlay_idle_when_hidden");
+                        if settings.overlay_idle_when_hidden {
+                            ui.slider_config(tr!("空闲 FPS"), 1, 5)
+                                .build(&mut settings.overlay_fps_limit_idle);
+                        }
+
+                        ui.set_next_item_width(150.0);
+                        ui.combo_enum(
+                            tr!("帧速率控制方式"),
+                            &[
+                                (FramePacing::Hybrid, "休眠 + 自旋"),
+                                (FramePacing::Sleep, "仅休眠"),
+                            ],
+                            &mut settings.overlay_frame_pacing,
+                        );
+                        self.render_help_marker(ui, "overlay_frame_pacing");
+                        ui.checkbox(tr!("垂直同步"), &mut settings.overlay_vsync);
+                        self.render_help_marker(ui, "overlay_vsync");
+
+                        ui.set_next_item_width(150.0);
+                        ui.combo_enum(
+                            tr!("色彩空间"),
+                            &[
+                                (ColorSpace::Auto, "自动"),
+                                (ColorSpace::Srgb, "sRGB"),
+                                (ColorSpace::Hdr, "HDR"),
+                            ],
+                            &mut settings.overlay_color_space,
+                        );
+                        self.render_help_marker(ui, "overlay_color_space");
+                        if settings.overlay_color_space != ColorSpace::Srgb {
+                            ui.slider_config(tr!("HDR 亮度 (尼特)"), 80, 1000)
+                                .build(&mut settings.overlay_hdr_brightness);
+                            self.render_help_marker(ui, "overlay_hdr_brightness");
+                        }
+
+                        ui.slider_config(tr!("数据更新频率 (Hz, 0 = 每帧)"), 0, 500)
+                            .build(&mut settings.update_rate);
+                        self.render_help_marker(ui, "update_rate");
+
+                        ui.set_next_item_width(150.0);
+                        ui.combo_enum(
+                            tr!("渲染后端"),
+                            &[
+                                (RenderBackend::Vulkan, "Vulkan"),
+                                (RenderBackend::D3D11, "Direct3D 11"),
+                            ],
+                            &mut settings.render_backend,
+                        );
+                        if ui.is_item_hovered() {
+                            ui.tooltip_text(tr!(
+                                "如果无法加载 vulkan-1.dll，请使用 Direct3D 11。重启后生效"
+                            ));
+                        }
+                        if settings.render_backend == RenderBackend::Vulkan {
+                            self.render_gpu_selection(&mut settings, app, ui);
+                        }
+                        self.render_monitor_selection(&mut settings, app, ui);
+
+                        ui.separator();
+                        self.render_session_timer(&mut settings, app, ui);
+
+                        ui.separator();
+                        self.render_remote_control(&mut settings, app, ui);
+
+                        ui.separator();
+                        self.render_cloud_sync(&mut settings, app, ui);
+
+                        ui.separator();
+                        self.render_privacy(&mut settings, app, ui);
+                    }
+
+                    if let Some(_) = ui.tab_item(tr!("功能管理")) {
+                        self.render_enhancement_manager(&mut settings, app, ui);
+                    }
+
+                    if let Some(_) = ui.tab_item(tr!("性能")) {
+                        self.render_performance(app, ui);
                     }
                 }
             });
     }
 
+    /// Show a "(?)" marker with the help text of the setting next to the last item
+    fn render_help_marker(&self, ui: &imgui::Ui, field: &str) {
+        if !self.help_markers {
+            return;
+        }
+
+        let help_text = match setting_help_text(field) {
+            Some(text) => text,
+            None => return,
+        };
+
+        ui.same_line();
+        ui.text_disabled("(?)");
+        if ui.is_item_hovered() {
+            ui.tooltip(|| {
+                let _wrap = ui.push_text_wrap_pos_with_pos(ui.current_font_size() * 25.0);
+                ui.text(translate(help_text));
+            });
+        }
+    }
+
+    fn render_enhancement_manager(
+        &mut self,
+        settings: &mut AppSettings,
+        app: &Application,
+        ui: &imgui::Ui,
+    ) {
+        ui.text_wrapped(tr!(
+            "功能按注册顺序更新。渲染顺序较高的功能绘制在其他功能之上"
+        ));
+
+        if let Some(_table) = ui.begin_table_with_flags(
+            "enhancement_manager",
+            4,
+            TableFlags::BORDERS | TableFlags::ROW_BG,
+        ) {
+            ui.table_setup_column(tr!("启用"));
+            let mut column_name = TableColumnSetup::new(tr!("功能"));
+            column_name.flags = TableColumnFlags::WIDTH_STRETCH;
+            ui.table_setup_column_with(column_name);
+            ui.table_setup_column(tr!("渲染顺序"));
+            ui.table_setup_column(tr!("上次更新 (ms)"));
+            ui.table_headers_row();
+
+            for (index, enhancement) in app.enhancements.iter().enumerate() {
+                let name = enhancement.borrow().name();
+                let mut config = settings.enhancement(name);
+                let mut changed = false;
+
+                ui.table_next_row();
+                ui.table_next_column();
+                changed |= ui.checkbox(
+                    &format!("##enhancement_enabled_{}", name),
+                    &mut config.enabled,
+                );
+
+                ui.table_next_column();
+                if config.enabled {
+                    ui.text(name);
+                } else {
+                    ui.text_disabled(name);
+                }
+
+                ui.table_next_column();
+                ui.set_next_item_width(80.0);
+                changed |= ui
+                    .input_int(
+                        &format!("##enhancement_order_{}", name),
+                        &mut config.render_order,
+                    )
+                    .build();
+
+                ui.table_next_column();
+                if config.enabled {
+                    ui.text(&format!(
+                        "{:.3}",
+                        app.perf_stats.last_enhancement_update_time(index)
+                    ));
+                } else {
+                    ui.text_disabled("-");
+                }
+
+                if changed {
+                    settings.enhancements.insert(name.to_string(), config);
+                }
+            }
+        }
+
+        if ui.button(tr!("全部重置")) {
+            settings.enhancements.clear();
+        }
+    }
+
+    fn render_performance(&mut self, app: &Application, ui: &imgui::Ui) {
+        ui.set_next_item_width(150.0);
+        ui.slider_config(
+            tr!("时间范围 (秒)"),
+            1,
+            PERFORMANCE_HISTORY.as_secs() as u32,
+        )
+        .build(&mut self.performance_window);
+        let window = Duration::from_secs(self.performance_window as u64);
+
+        let graph_width = ui.content_region_avail()[0];
+        let graphs = [
+            (
+                tr!("帧时间").to_string(),
+                "ms",
+                app.perf_stats.frame_times(window),
+            ),
+            (
+                tr!("更新耗时").to_string(),
+                "ms",
+                app.perf_stats.update_times(window),
+            ),
+            (
+                tr!("渲染耗时").to_string(),
+                "ms",
+                app.perf_stats.render_times(window),
+            ),
+            (
+                tr!("每帧内存读取").to_string(),
+                "",
+                app.perf_stats.read_calls(window),
+            ),
+        ];
+        let render_graph = |title: &str, unit: &str, series: &TimingSeries| {
+            ui.text(&format!(
+                "{}: {} {:.2}{unit} / {} {:.2}{unit}",
+                title,
+                tr!("平均"),
+                series.average,
+                tr!("最大"),
+                series.max,
+            ));
+            ui.plot_lines(&format!("##{}", title), &series.values)
+                .graph_size([graph_width, 50.0])
+                .scale_min(0.0)
+                .build();
+        };
+        for (title, unit, series) in graphs.iter() {
+            render_graph(title, unit, series);
+        }
+
+        ui.dummy([0.0, 5.0]);
+        ui.text(tr!("叠加层呈现"));
+        if ui.is_item_hovered() {
+            ui.tooltip_text(tr!(
+                "延迟为读取输入到帧提交呈现的时间, 桌面合成器 (DWM) 会再增加约一个刷新周期"
+            ));
+        }
+        let present_graphs = [
+            (
+                tr!("CPU 帧构建").to_string(),
+                Some(app.perf_stats.cpu_times(window)),
+            ),
+            (
+                tr!("GPU 渲染").to_string(),
+                app.perf_stats.gpu_times(window),
+            ),
+            (
+                tr!("呈现阻塞").to_string(),
+                Some(app.perf_stats.present_times(window)),
+            ),
+            (
+                tr!("输入到呈现延迟").to_string(),
+                Some(app.perf_stats.latencies(window)),
+            ),
+        ];
+        for (title, series) in present_graphs.iter() {
+            match series {
+                Some(series) => render_graph(title, "ms", series),
+                None => ui.text_disabled(format!("{}: {}", title, tr!("当前渲染后端不支持"))),
+            }
+        }
+
+        ui.dummy([0.0, 5.0]);
+        ui.text(tr!("各功能更新耗时"));
+        if let Some(_table) = ui.begin_table_with_flags(
+            "performance_enhancements",
+            4,
+            TableFlags::BORDERS | TableFlags::ROW_BG,
+        ) {
+            ui.table_setup_column(tr!("功能"));
+            ui.table_setup_column(tr!("平均 (ms)"));
+            ui.table_setup_column(tr!("最大 (ms)"));
+            let mut column_graph = TableColumnSetup::new("##graph");
+            column_graph.flags = TableColumnFlags::WIDTH_STRETCH;
+            ui.table_setup_column_with(column_graph);
+            ui.table_headers_row();
+
+            for (index, enhancement) in app.enhancements.iter().enumerate() {
+                let series = app.perf_stats.enhancement_update_times(window, index);
+
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(enhancement.borrow().name());
+
+                ui.table_next_column();
+                ui.text(&format!("{:.3}", series.average));
+
+                ui.table_next_column();
+                ui.text(&format!("{:.3}", series.max));
+
+                ui.table_next_column();
+                ui.plot_lines(&format!("##enhancement_{}", index), &series.values)
+                    .graph_size([ui.content_region_avail()[0], ui.text_line_height()])
+                    .scale_min(0.0)
+                    .build();
+            }
+        }
+    }
+
     fn render_web_radar(
         &mut self,
         settings: &mut AppSettings,
@@ -343,8 +1264,8 @@ impl SettingsUI {
                 let mut radar = radar.lock().unwrap();
                 match radar.connection_state() {
                     WebRadarState::Connecting => {
-                        ui.text(format!("正在连接到 {}", radar.endpoint()));
-                        ui.text("请稍候...");
+                        ui.text(format!("{} {}", tr!("正在连接到"), radar.endpoint()));
+                        ui.text(tr!("请稍候..."));
                     }
                     WebRadarState::Connected { session_id } => {
                         let mut radar_url = radar.endpoint().clone();
@@ -355,10 +1276,10 @@ impl SettingsUI {
                             let _ = radar_url.set_scheme("http");
                         }
 
-                        ui.text(format!("正在分享当前游戏。"));
+                        ui.text(tr!("正在分享当前游戏。"));
                         {
                             let mut session_id = session_id.clone();
-                            ui.text("会话 ID");
+                            ui.text(tr!("会话 ID"));
 
                             ui.same_line_with_pos(100.0);
                             ui.set_next_item_width(300.0);
@@ -396,14 +1317,18 @@ impl SettingsUI {
                                 .build();
 
                             ui.same_line();
-                            if ui.button("打开 URL") {
+                            if ui.button(tr!("打开 URL")) {
                                 ui.set_clipboard_text(&radar_url);
                                 utils::open_url(&radar_url);
                             }
                         }
 
+                        if radar.is_password_protected() {
+                            ui.text(tr!("观看者需要输入密码才能查看雷达。"));
+                        }
+
                         ui.new_line();
-                        if ui.button("停止共享") {
+                        if ui.button(tr!("停止共享")) {
                             radar.close_connection();
                             drop(radar);
                             *web_radar = None;
@@ -431,14 +1356,18 @@ impl SettingsUI {
 
                 let url = Url::parse(&current_url);
                 ui.disabled(url.is_err(), || {
-                    if ui.button("启用 Web 雷达") {
+                    if ui.button(tr!("启用 Web 雷达")) {
                         let url = url.as_ref().unwrap();
-                        *web_radar = Some(radar::create_web_radar(url.clone(), cs2.clone()));
+                        *web_radar = Some(radar::create_web_radar(
+                            url.clone(),
+                            settings.web_radar_password.clone(),
+                            cs2.clone(),
+                        ));
                     }
                 });
 
                 ui.same_line();
-                ui.text(obfstr!("开始分享当前游戏"));
+                ui.text(tr!("开始分享当前游戏"));
                 {
                     let button_text = if settings.web_radar_advanced_settings {
                         "基础设置"
@@ -447,29 +1376,740 @@ impl SettingsUI {
                     };
                     let button_text_width = ui.calc_text_size(button_text)[0];
 
-                    let total_width = ui.content_region_avail()[0] + 2.0;
-                    ui.same_line_with_pos(total_width - button_text_width);
-                    if ui.button(button_text) {
-                        settings.web_radar_advanced_settings =
-                            !settings.web_radar_advanced_settings;
-                    }
-                }
+                    let total_width = ui.content_region_avail()[0] + 2.0;
+                    ui.same_line_with_pos(total_width - button_text_width);
+                    if ui.button(button_text) {
+                        settings.web_radar_advanced_settings =
+                            !settings.web_radar_advanced_settings;
+                    }
+                }
+
+                ui.text(tr!(
+                    "Web 雷达是一个全面详细的雷达，可以从任何地方进行访问。"
+                ));
+                ui.text(tr!(
+                    "这意味着您还可以将包含所有敌人信息的雷达显示给您的队友。"
+                ));
+
+                ui.new_line();
+                ui.text(tr!("观看密码:"));
+                ui.same_line_with_pos(100.0);
+                ui.set_next_item_width(200.0);
+                ui.input_text("##web_radar_password", &mut settings.web_radar_password)
+                    .password(true)
+                    .hint(tr!("无"))
+                    .build();
+                self.render_help_marker(ui, "web_radar_password");
+
+                if settings.web_radar_advanced_settings {
+                    ui.new_line();
+                    ui.text(tr!("高级设置"));
+                    ui.text(tr!("雷达服务器:"));
+                    ui.same_line();
+                    let _style_red_boarder =
+                        ui.push_style_color(StyleColor::Border, [1.0, 0.0, 0.0, 1.0]);
+                    ui.set_next_item_width(ui.content_region_avail()[0]);
+                    if ui.input_text("##url", &mut current_url).build() {
+                        settings.web_radar_url = Some(current_url);
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_update_check(
+        &mut self,
+        settings: &mut AppSettings,
+        app: &Application,
+        ui: &imgui::Ui,
+    ) {
+        ui.checkbox(tr!("启动时检查更新"), &mut settings.update_check);
+        ui.same_line();
+        if ui.small_button(tr!("立即检查")) {
+            app.update_checker.check();
+        }
+
+        match app.update_checker.status() {
+            UpdateStatus::Idle => {}
+            UpdateStatus::Checking => ui.text(tr!("正在检查更新...")),
+            UpdateStatus::UpToDate => ui.text(tr!("当前已是最新版本")),
+            UpdateStatus::Available(release) => {
+                ui.text_colored(
+                    [0.3, 1.0, 0.3, 1.0],
+                    &format!(
+                        "{}: {} ({})",
+                        tr!("发现新版本"),
+                        release.title,
+                        release.version
+                    ),
+                );
+                if ui.button(tr!("打开发布页面")) {
+                    utils::open_url(&release.url);
+                }
+
+                if release.download.is_some() {
+                    ui.same_line();
+                    if ui.button(tr!("下载并安装")) {
+                        app.update_checker.install(release.clone());
+                    }
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(tr!("替换当前程序文件, 重启控制器后生效"));
+                    }
+                } else {
+                    ui.text(tr!(
+                        "该版本未包含程序文件或校验文件, 请前往发布页面手动下载。"
+                    ));
+                }
+
+                if !release.changelog.is_empty()
+                    && ui.collapsing_header(tr!("更新日志"), TreeNodeFlags::empty())
+                {
+                    if let Some(_token) = ui
+                        .child_window("Changelog")
+                        .size([0.0, 150.0])
+                        .border(true)
+                        .begin()
+                    {
+                        ui.text_wrapped(&release.changelog);
+                    }
+                }
+            }
+            UpdateStatus::Downloading(release) => {
+                ui.text(&format!("{} {}...", tr!("正在下载"), release.version));
+            }
+            UpdateStatus::Installed(release) => ui.text_colored(
+                [0.3, 1.0, 0.3, 1.0],
+                &format!(
+                    "{} {}, {}",
+                    tr!("已安装版本"),
+                    release.version,
+                    tr!("重启控制器后生效。")
+                ),
+            ),
+            UpdateStatus::Failed(error) => ui.text_colored(
+                [1.0, 0.3, 0.3, 1.0],
+                &format!("{}: {}", tr!("更新失败"), error),
+            ),
+        }
+    }
+
+    fn render_profiles(&mut self, app: &Application, settings: &mut AppSettings, ui: &imgui::Ui) {
+        let profiles = self.profiles.get_or_insert_with(|| match list_profiles() {
+            Ok(profiles) => profiles,
+            Err(error) => {
+                log::warn!("读取配置方案列表失败: {:#}", error);
+                Vec::new()
+            }
+        });
+
+        let active_profile = app.active_profile.as_deref();
+        ui.text(format!(
+            "{}: {}",
+            tr!("当前配置方案"),
+            active_profile.unwrap_or(DEFAULT_PROFILE_NAME)
+        ));
+        ui.text_disabled(tr!("热键在所有配置方案之间共享"));
+        ui.separator();
+
+        let mut profiles_changed = false;
+        let entries = std::iter::once(None).chain(profiles.iter().map(|name| Some(name.as_str())));
+        for profile in entries {
+            let name = profile.unwrap_or(DEFAULT_PROFILE_NAME);
+            let _id = ui.push_id(name);
+
+            let is_active = profile == active_profile;
+            ui.disabled(is_active, || {
+                if ui.button_with_size(tr!("切换"), [60.0, 0.0]) {
+                    app.request_profile_switch(profile.map(str::to_string));
+                }
+            });
+            ui.same_line();
+
+            let mut key = settings.profile_hotkeys.get(name).cloned();
+            if ui.button_key_optional(name, &mut key, [150.0, 0.0]) {
+                match key {
+                    Some(key) => settings.profile_hotkeys.insert(name.to_string(), key),
+                    None => settings.profile_hotkeys.remove(name),
+                };
+            }
+
+            if let Some(profile) = profile {
+                ui.same_line();
+                ui.disabled(is_active, || {
+                    if ui.button(tr!("删除")) {
+                        let result = get_profile_settings_path(Some(profile))
+                            .and_then(|path| Ok(std::fs::remove_file(path)?));
+                        if let Err(error) = result {
+                            log::warn!("删除配置方案 {} 失败: {:#}", profile, error);
+                        }
+
+                        settings.profile_hotkeys.remove(profile);
+                        profiles_changed = true;
+                    }
+                });
+            }
+        }
+
+        ui.separator();
+        ui.set_next_item_width(200.0);
+        ui.input_text(obfstr!("##profile_name"), &mut self.profile_name_input)
+            .hint(tr!("配置方案名称"))
+            .build();
+        ui.same_line();
+
+        let profile_name = self.profile_name_input.trim().to_string();
+        let name_valid = is_valid_profile_name(&profile_name) && !profiles.contains(&profile_name);
+        ui.disabled(!name_valid, || {
+            if ui.button(tr!("以当前配置新建")) {
+                match save_app_settings(Some(&profile_name), settings) {
+                    Ok(_) => {
+                        app.request_profile_switch(Some(profile_name.clone()));
+                        self.profile_name_input.clear();
+                        profiles_changed = true;
+                    }
+                    Err(error) => log::warn!("创建配置方案失败: {:#}", error),
+                }
+            }
+        });
+        if !name_valid && !profile_name.is_empty() {
+            ui.text_disabled(tr!("名称无效或已存在。仅允许字母、数字、空格、- 和 _"));
+        }
+
+        if profiles_changed {
+            self.profiles = None;
+        }
+
+        ui.separator();
+        self.render_config_backups(app, ui);
+    }
+
+    fn render_gpu_selection(&self, settings: &mut AppSettings, app: &Application, ui: &imgui::Ui) {
+        let mut gpu_names = vec![tr!("自动").to_string()];
+        gpu_names.extend(app.physical_devices.iter().cloned());
+
+        /* keep a configured GPU selectable even if it's currently not available */
+        let mut selected = match &settings.overlay_gpu {
+            Some(name) => match gpu_names.iter().skip(1).position(|gpu| gpu == name) {
+                Some(index) => index + 1,
+                None => {
+                    gpu_names.push(name.clone());
+                    gpu_names.len() - 1
+                }
+            },
+            None => 0,
+        };
+
+        ui.set_next_item_width(250.0);
+        if ui.combo_simple_string(tr!("渲染 GPU"), &mut selected, &gpu_names) {
+            settings.overlay_gpu = match selected {
+                0 => None,
+                index => Some(gpu_names[index].clone()),
+            };
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(tr!(
+                "多显卡 (如笔记本混合显卡) 时请选择运行 CS2 的显卡，以避免跨显卡呈现导致的卡顿。重启后生效"
+            ));
+        }
+    }
+
+    fn render_monitor_selection(
+        &self,
+        settings: &mut AppSettings,
+        app: &Application,
+        ui: &imgui::Ui,
+    ) {
+        let mut monitor_names = vec![tr!("跟随游戏窗口").to_string()];
+        monitor_names.extend(app.monitors.iter().cloned());
+
+        let mut selected = match settings.overlay_monitor {
+            Some(index) if index < app.monitors.len() => index + 1,
+            Some(index) => {
+                monitor_names.push(format!("#{} ({})", index + 1, tr!("未连接")));
+                monitor_names.len() - 1
+            }
+            None => 0,
+        };
+
+        ui.set_next_item_width(250.0);
+        if ui.combo_simple_string(tr!("叠加层显示器"), &mut selected, &monitor_names) {
+            settings.overlay_monitor = match selected {
+                0 => None,
+                index if index <= app.monitors.len() => Some(index - 1),
+                _ => settings.overlay_monitor,
+            };
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text(tr!(
+                "将叠加层固定到指定显示器。仅适用于全屏游戏，窗口模式请跟随游戏窗口"
+            ));
+        }
+    }
+
+    fn render_config_backups(&mut self, app: &Application, ui: &imgui::Ui) {
+        ui.text(tr!("恢复之前的配置"));
+        ui.same_line();
+        if ui.small_button(tr!("刷新")) {
+            self.config_backups = None;
+        }
+
+        let backups = self.config_backups.get_or_insert_with(|| {
+            match list_config_backups(app.active_profile.as_deref()) {
+                Ok(backups) => backups,
+                Err(error) => {
+                    log::warn!("读取配置备份失败: {:#}", error);
+                    Vec::new()
+                }
+            }
+        });
+        if backups.is_empty() {
+            ui.text_disabled(tr!("当前配置方案没有备份"));
+            return;
+        }
+
+        self.config_backup_selected = self.config_backup_selected.min(backups.len() - 1);
+        let backup_names = backups
+            .iter()
+            .map(|backup| backup.timestamp.format("%Y-%m-%d %H:%M:%S").to_string())
+            .collect::<Vec<_>>();
+
+        ui.set_next_item_width(200.0);
+        ui.combo_simple_string(
+            "##config_backup",
+            &mut self.config_backup_selected,
+            &backup_names,
+        );
+        ui.same_line();
+        if ui.button(tr!("恢复")) {
+            ui.open_popup("config_restore_confirm");
+        }
+
+        let mut restored = false;
+        let backup_path = &backups[self.config_backup_selected].path;
+        ui.popup("config_restore_confirm", || {
+            ui.text(tr!("确定要恢复此备份吗? 当前配置会先被备份。"));
+            if ui.button(tr!("确定")) {
+                app.request_config_restore(backup_path.clone());
+                restored = true;
+                ui.close_current_popup();
+            }
+            ui.same_line();
+            if ui.button(tr!("取消")) {
+                ui.close_current_popup();
+            }
+        });
+
+        if restored {
+            /* the restore creates a new backup */
+            self.config_backups = None;
+        }
+    }
+
+    fn render_server_info(&mut self, app: &Application, ui: &imgui::Ui) {
+        let server_info = match app.app_state.resolve::<ServerInfo>(()) {
+            Ok(server_info) => server_info,
+            Err(_) => {
+                ui.text(tr!("无法读取服务器信息。"));
+                return;
+            }
+        };
+
+        let not_available = || "未知".to_string();
+        let rows = [
+            (
+                "服务器名称",
+                server_info
+                    .server_name
+                    .clone()
+                    .unwrap_or_else(not_available),
+            ),
+            (
+                "地址",
+                server_info
+                    .server_address
+                    .clone()
+                    .unwrap_or_else(not_available),
+            ),
+            (
+                "Tickrate",
+                server_info
+                    .tick_rate
+                    .map(|tick_rate| format!("{:.0}", tick_rate))
+                    .unwrap_or_else(not_available),
+            ),
+            (
+                "服务器类型",
+                if server_info.valve_server {
+                    "官方服务器".to_string()
+                } else {
+                    "社区服务器".to_string()
+                },
+            ),
+            (
+                "VAC 保护",
+                match server_info.vac_secured() {
+                    Some(true) => "是".to_string(),
+                    Some(false) => "否".to_string(),
+                    None => not_available(),
+                },
+            ),
+            (
+                "匹配模式",
+                if server_info.queued_matchmaking {
+                    "是".to_string()
+                } else {
+                    "否".to_string()
+                },
+            ),
+        ];
+
+        for (name, value) in rows {
+            ui.text(name);
+            ui.same_line_with_pos(120.0);
+            ui.text(value);
+        }
+
+        ui.new_line();
+        match server_info.connect_command() {
+            Some(connect_command) => {
+                let show_copied = self
+                    .connect_command_copied
+                    .as_ref()
+                    .map(|time| time.elapsed().as_millis() < 3_000)
+                    .unwrap_or(false);
+
+                let copy_text = if show_copied {
+                    "连接命令已复制"
+                } else {
+                    "复制连接命令"
+                };
+
+                if ui.button(copy_text) {
+                    ui.set_clipboard_text(&connect_command);
+                    self.connect_command_copied = Some(Instant::now());
+                }
+                ui.same_line();
+                ui.text_disabled(&connect_command);
+            }
+            None => {
+                let _disabled = ui.begin_disabled(true);
+                ui.button(tr!("复制连接命令"));
+            }
+        }
+    }
+
+    fn render_trigger_bot_weapons(&mut self, weapons: &mut TriggerBotWeaponMatrix, ui: &imgui::Ui) {
+        ui.text(tr!("武器类型:"));
+        ui.same_line();
+        if ui.button(tr!("全部武器")) {
+            *weapons = TriggerBotWeaponMatrix::default();
+        }
+        ui.same_line();
+        if ui.button(tr!("仅狙击枪开镜")) {
+            *weapons = TriggerBotWeaponMatrix::SNIPER_SCOPE_ONLY;
+        }
+
+        if let Some(_table) =
+            ui.begin_table_with_flags("trigger_bot_weapons", 3, TableFlags::BORDERS)
+        {
+            ui.table_setup_column("武器类型");
+            ui.table_setup_column("启用");
+            ui.table_setup_column("需要开镜");
+            ui.table_headers_row();
+
+            for (name, rule) in [
+                ("手枪", &mut weapons.pistol),
+                ("霰弹枪", &mut weapons.shotgun),
+                ("冲锋枪", &mut weapons.smg),
+                ("步枪", &mut weapons.rifle),
+                ("狙击枪", &mut weapons.sniper_rifle),
+                ("机枪", &mut weapons.machine_gun),
+            ] {
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(name);
+
+                ui.table_next_column();
+                ui.checkbox(&format!("##{}_enabled", name), &mut rule.enabled);
+
+                ui.table_next_column();
+                ui.checkbox(&format!("##{}_scope", name), &mut rule.require_scope);
+            }
+        }
+    }
+
+    fn render_trigger_bot_class_timings(
+        &mut self,
+        timings: &mut TriggerBotClassTimings,
+        ui: &imgui::Ui,
+    ) {
+        if !ui.collapsing_header(tr!("按武器类型设置延迟"), TreeNodeFlags::empty()) {
+            return;
+        }
+
+        ui.text_disabled(tr!("未启用的武器类型使用上方的全局设置"));
+        if let Some(_table) =
+            ui.begin_table_with_flags("trigger_bot_class_timings", 5, TableFlags::BORDERS)
+        {
+            ui.table_setup_column(tr!("武器类型"));
+            ui.table_setup_column(tr!("启用"));
+            ui.table_setup_column(tr!("最小延迟"));
+            ui.table_setup_column(tr!("最大延迟"));
+            ui.table_setup_column(tr!("连发时长"));
+            ui.table_headers_row();
+
+            for (class, timing) in timings.iter_mut() {
+                let name = match class {
+                    TriggerBotWeaponClass::Pistol => tr!("手枪").to_string(),
+                    TriggerBotWeaponClass::Rifle => tr!("步枪").to_string(),
+                    TriggerBotWeaponClass::Sniper => tr!("狙击枪").to_string(),
+                    TriggerBotWeaponClass::AutoSniper => tr!("连狙").to_string(),
+                };
+
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(&name);
+
+                ui.table_next_column();
+                ui.checkbox(format!("##{:?}_enabled", class), &mut timing.enabled);
+
+                let _disabled = ui.begin_disabled(!timing.enabled);
+                ui.table_next_column();
+                ui.set_next_item_width(-1.0);
+                ui.slider_config(format!("##{:?}_delay_min", class), 0, 250)
+                    .display_format("%dms")
+                    .build(&mut timing.delay_min);
+
+                ui.table_next_column();
+                ui.set_next_item_width(-1.0);
+                ui.slider_config(format!("##{:?}_delay_max", class), 0, 250)
+                    .display_format("%dms")
+                    .build(&mut timing.delay_max);
+                timing.delay_max = timing.delay_max.max(timing.delay_min);
+
+                ui.table_next_column();
+                ui.set_next_item_width(-1.0);
+                ui.slider_config(format!("##{:?}_burst", class), 0, 1000)
+                    .display_format("%dms")
+                    .build(&mut timing.burst);
+            }
+        }
+    }
+
+    fn render_session_timer(
+        &mut self,
+        settings: &mut AppSettings,
+        app: &Application,
+        ui: &imgui::Ui,
+    ) {
+        ui.checkbox(tr!("显示游戏时长"), &mut settings.session_timer);
+        ui.slider_config(tr!("休息提醒间隔 (分钟, 0 = 关闭)"), 0, 240)
+            .build(&mut settings.session_break_interval);
+
+        if let Ok(history) = app.app_state.resolve::<PlaytimeHistory>(()) {
+            ui.text(tr!("最近游戏时长:"));
+            for (day, playtime) in history.recent_days(7) {
+                ui.text(format!(
+                    "{}  {}",
+                    day,
+                    enhancements::format_duration(playtime)
+                ));
+            }
+        }
+    }
+
+    fn render_remote_control(
+        &mut self,
+        settings: &mut AppSettings,
+        app: &Application,
+        ui: &imgui::Ui,
+    ) {
+        ui.checkbox(tr!("远程控制网页"), &mut settings.remote_control);
+        ui.text(tr!(
+            "通过手机或其他设备上的浏览器切换 ESP、自动扳机等功能。"
+        ));
+
+        {
+            let _disabled = ui.begin_disabled(settings.remote_control);
+            ui.text(tr!("监听地址:"));
+            ui.same_line();
+            ui.set_next_item_width(200.0);
+            ui.input_text(
+                "##remote_control_address",
+                &mut settings.remote_control_address,
+            )
+            .build();
+        }
+        ui.text_disabled(tr!(
+            "默认仅本机可访问。如需从手机等其他设备访问，请将监听地址改为 0.0.0.0:7230。"
+        ));
+
+        if let Some(remote_control) = &app.remote_control {
+            let remote_control = remote_control.lock().unwrap();
+            let address = remote_control.address();
+            let token = remote_control.token().to_string();
+
+            ui.text(tr!("访问令牌:"));
+            ui.same_line();
+            ui.text(&token);
+            ui.same_line();
+            if ui.button(tr!("复制")) {
+                ui.set_clipboard_text(&token);
+            }
+            ui.same_line();
+            if ui.button(tr!("重新生成")) {
+                /* a new token will be generated, invalidating the old one */
+                settings.remote_control_token.clear();
+            }
+
+            if address.ip().is_unspecified() {
+                ui.text(format!(
+                    "{} http://<{}>:{}/?token={}",
+                    tr!("请在浏览器中打开"),
+                    tr!("本机局域网 IP"),
+                    address.port(),
+                    token
+                ));
+            } else {
+                let url = format!("http://{}/?token={}", address, token);
+                ui.text(format!("{} {}", tr!("请在浏览器中打开"), url));
+                ui.same_line();
+                if ui.button(tr!("打开")) {
+                    utils::open_url(&url);
+                }
+            }
+        }
+    }
+
+    fn render_cloud_sync(&mut self, settings: &mut AppSettings, app: &Application, ui: &imgui::Ui) {
+        ui.checkbox(tr!("云同步"), &mut settings.cloud_sync);
+        ui.text(tr!(
+            "在多台电脑之间同步当前配置方案。冲突时以最后修改的一方为准，被覆盖的配置会备份到本地。"
+        ));
+
+        {
+            let _disabled = ui.begin_disabled(settings.cloud_sync);
+            ui.set_next_item_width(200.0);
+            ui.combo_enum(
+                tr!("同步方式"),
+                &[
+                    (CloudSyncProvider::WebDav, "WebDAV"),
+                    (CloudSyncProvider::Gist, "GitHub Gist"),
+                ],
+                &mut settings.cloud_sync_provider,
+            );
+
+            ui.set_next_item_width(300.0);
+            match settings.cloud_sync_provider {
+                CloudSyncProvider::WebDav => {
+                    ui.input_text(tr!("WebDAV 目录地址"), &mut settings.cloud_sync_url)
+                        .build();
+                    ui.set_next_item_width(200.0);
+                    ui.input_text(tr!("用户名"), &mut settings.cloud_sync_username)
+                        .build();
+                    ui.set_next_item_width(200.0);
+                    ui.input_text(tr!("密码"), &mut settings.cloud_sync_token)
+                        .password(true)
+                        .build();
+                }
+                CloudSyncProvider::Gist => {
+                    ui.input_text(tr!("Gist ID"), &mut settings.cloud_sync_url)
+                        .build();
+                    ui.set_next_item_width(300.0);
+                    ui.input_text(tr!("GitHub 访问令牌"), &mut settings.cloud_sync_token)
+                        .password(true)
+                        .build();
+                    if ui.is_item_hovered() {
+                        ui.tooltip_text(tr!("令牌需要 gist 权限"));
+                    }
+                }
+            }
+        }
+
+        if !settings.cloud_sync {
+            return;
+        }
+
+        let mut cloud_sync = app.cloud_sync.borrow_mut();
+        match cloud_sync.status() {
+            CloudSyncStatus::Idle => ui.text(tr!("尚未同步")),
+            CloudSyncStatus::Syncing => ui.text(tr!("正在同步...")),
+            CloudSyncStatus::Synced(timestamp) => ui.text(format!(
+                "{} {}",
+                tr!("上次同步:"),
+                chrono::DateTime::<chrono::Local>::from(timestamp).format("%H:%M:%S")
+            )),
+            CloudSyncStatus::Failed(error) => ui.text_colored(
+                [1.0, 0.4, 0.4, 1.0],
+                format!("{} {}", tr!("同步失败:"), error),
+            ),
+        }
+
+        ui.same_line();
+        if ui.button(tr!("立即同步")) {
+            cloud_sync.request_sync();
+        }
+    }
+
+    fn render_privacy(&mut self, settings: &mut AppSettings, app: &Application, ui: &imgui::Ui) {
+        ui.text(tr!("隐私"));
+        ui.checkbox(tr!("发送匿名使用数据"), &mut settings.metrics);
+        ui.text_wrapped(tr!(
+            "使用数据通过内核驱动上报, 用于统计版本和功能的使用情况。不包含任何游戏数据或个人信息。"
+        ));
+
+        if let Some(_table) =
+            ui.begin_table_with_flags("metrics_record_types", 2, TableFlags::BORDERS)
+        {
+            ui.table_setup_column(tr!("记录类型"));
+            ui.table_setup_column(tr!("内容"));
+            ui.table_headers_row();
+
+            for (record_type, description) in METRICS_RECORD_TYPES {
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(record_type);
+                ui.table_next_column();
+                ui.text(translate(description));
+            }
+        }
+
+        if !ui.collapsing_header(tr!("最近发送的记录"), TreeNodeFlags::empty()) {
+            return;
+        }
 
-                ui.text("Web 雷达是一个全面详细的雷达，可以从任何地方进行访问。");
-                ui.text("这意味着您还可以将包含所有敌人信息的雷达显示给您的队友。");
+        if !settings.metrics {
+            ui.text_disabled(tr!("使用数据上报已关闭, 不会发送任何记录"));
+        }
 
-                if settings.web_radar_advanced_settings {
-                    ui.new_line();
-                    ui.text("高级设置");
-                    ui.text("雷达服务器:");
-                    ui.same_line();
-                    let _style_red_boarder =
-                        ui.push_style_color(StyleColor::Border, [1.0, 0.0, 0.0, 1.0]);
-                    ui.set_next_item_width(ui.content_region_avail()[0]);
-                    if ui.input_text("##url", &mut current_url).build() {
-                        settings.web_radar_url = Some(current_url);
-                    }
-                }
+        let history = app.cs2.metrics_history();
+        if history.is_empty() {
+            ui.text_disabled(tr!("尚未发送任何记录"));
+            return;
+        }
+
+        if let Some(_table) = ui.begin_table_with_flags(
+            "metrics_history",
+            3,
+            TableFlags::BORDERS | TableFlags::SIZING_STRETCH_PROP,
+        ) {
+            ui.table_setup_column(tr!("时间"));
+            ui.table_setup_column(tr!("记录类型"));
+            ui.table_setup_column(tr!("内容"));
+            ui.table_headers_row();
+
+            for record in history.iter().rev() {
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(
+                    chrono::DateTime::<chrono::Local>::from(record.timestamp)
+                        .format("%H:%M:%S")
+                        .to_string(),
+                );
+                ui.table_next_column();
+                ui.text(&record.record_type);
+                ui.table_next_column();
+                ui.text_wrapped(&record.payload);
             }
         }
     }
@@ -518,6 +2158,7 @@ impl SettingsUI {
                 .selected(target == &self.esp_selected_target)
                 .flags(SelectableFlags::SPAN_ALL_COLUMNS)
                 .build();
+            let context_clicked = ui.is_item_clicked_with_button(MouseButton::Right);
 
             let indicator_color = if target_enabled {
                 ImColor32::from_rgb(0x4C, 0xAF, 0x50)
@@ -544,6 +2185,55 @@ impl SettingsUI {
             if clicked {
                 self.esp_pending_target = Some(target.clone());
             }
+
+            let popup_id = format!("esp_target_context##{}", config_key);
+            if context_clicked {
+                ui.open_popup(&popup_id);
+            }
+
+            if let Some(_popup) = ui.begin_popup(&popup_id) {
+                let current_config = settings.esp_settings.get(&config_key).cloned();
+                if ui
+                    .menu_item_config("复制样式 (JSON)")
+                    .enabled(current_config.is_some())
+                    .build()
+                {
+                    if let Some(config) = &current_config {
+                        match serde_json::to_string_pretty(config) {
+                            Ok(value) => ui.set_clipboard_text(value),
+                            Err(error) => log::warn!("序列化 ESP 样式失败: {:#}", error),
+                        }
+                    }
+                }
+
+                if ui
+                    .menu_item_config(tr!("复制分享码"))
+                    .enabled(current_config.is_some())
+                    .build()
+                {
+                    if let Some(config) = current_config {
+                        let share_code = EspShareCode::Config {
+                            target: config_key.clone(),
+                            config,
+                        };
+                        match share_code.encode() {
+                            Ok(value) => ui.set_clipboard_text(value),
+                            Err(error) => log::warn!("生成 ESP 分享码失败: {:#}", error),
+                        }
+                    }
+                }
+
+                if ui.menu_item("粘贴样式") {
+                    let clipboard = ui.clipboard_text().unwrap_or_default();
+                    self.esp_style_import = Some(Self::prepare_esp_style_import(
+                        target,
+                        current_config.as_ref(),
+                        &clipboard,
+                    ));
+                }
+
+                self.render_esp_copy_from_menu(settings, ui, target, current_config.as_ref());
+            }
         }
 
         let children = target.children();
@@ -556,6 +2246,273 @@ impl SettingsUI {
         }
     }
 
+    /// Context menu to copy the config of another target onto the given target
+    fn render_esp_copy_from_menu(
+        &mut self,
+        settings: &AppSettings,
+        ui: &imgui::Ui,
+        target: &EspSelector,
+        current_config: Option<&EspConfig>,
+    ) {
+        let mut sources = Vec::new();
+        let mut pending = vec![EspSelector::Player];
+        while let Some(source) = pending.pop() {
+            pending.extend(source.children().into_iter().rev());
+            if &source == target {
+                continue;
+            }
+
+            if let Some(config) = settings.esp_settings.get(&source.config_key()) {
+                if config.matches_target(target) {
+                    sources.push((source, config));
+                }
+            }
+        }
+
+        let _menu = match ui.begin_menu_with_enabled(tr!("复制自..."), !sources.is_empty()) {
+            Some(menu) => menu,
+            None => return,
+        };
+
+        for (source, config) in sources {
+            let mut title = source.config_display();
+            let mut parent = source.parent();
+            while let Some(current) = parent {
+                title = format!("{} > {}", current.config_display(), title);
+                parent = current.parent();
+            }
+
+            if ui.menu_item(format!("{}##{}", title, source.config_key())) {
+                self.esp_style_import = Some(Self::prepare_esp_style_replace(
+                    target,
+                    current_config,
+                    *config,
+                ));
+            }
+        }
+    }
+
+    fn prepare_esp_style_import(
+        target: &EspSelector,
+        current_config: Option<&EspConfig>,
+        content: &str,
+    ) -> EspStyleImport {
+        let config = if EspShareCode::is_share_code(content) {
+            match EspShareCode::decode(content) {
+                Ok(EspShareCode::Config { config, .. }) => config,
+                Ok(EspShareCode::Tree { .. }) => {
+                    return EspStyleImport::Invalid {
+                        message: tr!(
+                            "该分享码包含全部 ESP 目标, 请在 ESP 目标列表的分享码菜单中导入"
+                        )
+                        .to_string(),
+                    }
+                }
+                Err(error) => {
+                    return EspStyleImport::Invalid {
+                        message: format!("{}:\n{:#}", tr!("无效的 ESP 分享码"), error),
+                    }
+                }
+            }
+        } else {
+            match serde_json::from_str::<EspConfig>(content.trim()) {
+                Ok(config) => config,
+                Err(error) => {
+                    return EspStyleImport::Invalid {
+                        message: format!("剪贴板中没有有效的 ESP 样式:\n{}", error),
+                    }
+                }
+            }
+        };
+
+        if !config.matches_target(target) {
+            return EspStyleImport::Invalid {
+                message: format!("该样式不能用于 {}", target.config_display()),
+            };
+        }
+
+        Self::prepare_esp_style_replace(target, current_config, config)
+    }
+
+    /// Replace the config of the target after the changes have been confirmed
+    fn prepare_esp_style_replace(
+        target: &EspSelector,
+        current_config: Option<&EspConfig>,
+        config: EspConfig,
+    ) -> EspStyleImport {
+        let changes = match current_config {
+            Some(current_config) => match esp_config_changes(current_config, &config) {
+                Ok(changes) => changes,
+                Err(error) => {
+                    return EspStyleImport::Invalid {
+                        message: format!("比较 ESP 样式失败: {:#}", error),
+                    }
+                }
+            },
+            None => vec!["当前目标没有样式, 将创建新的样式".to_string()],
+        };
+
+        EspStyleImport::Pending {
+            target: target.clone(),
+            config,
+            changes,
+        }
+    }
+
+    fn prepare_esp_tree_import(settings: &AppSettings, content: &str) -> EspStyleImport {
+        let (configs, enabled) = match EspShareCode::decode(content) {
+            Ok(EspShareCode::Tree { configs, enabled }) => (configs, enabled),
+            Ok(EspShareCode::Config { target, config }) => (
+                BTreeMap::from([(target.clone(), config)]),
+                BTreeMap::from([(target, true)]),
+            ),
+            Err(error) => {
+                return EspStyleImport::Invalid {
+                    message: format!("{}:\n{:#}", tr!("无效的 ESP 分享码"), error),
+                }
+            }
+        };
+
+        let mut changes = Vec::new();
+        for (target, config) in configs.iter() {
+            let current_config = match settings.esp_settings.get(target) {
+                Some(current_config) => current_config,
+                None => {
+                    changes.push(format!("{}: {}", target, tr!("新的样式")));
+                    continue;
+                }
+            };
+
+            match esp_config_changes(current_config, config) {
+                Ok(target_changes) => changes.extend(
+                    target_changes
+                        .into_iter()
+                        .map(|change| format!("{}: {}", target, change)),
+                ),
+                Err(error) => {
+                    return EspStyleImport::Invalid {
+                        message: format!("比较 ESP 样式失败: {:#}", error),
+                    }
+                }
+            }
+        }
+
+        for (target, target_enabled) in enabled.iter() {
+            let current_enabled = settings
+                .esp_settings_enabled
+                .get(target)
+                .cloned()
+                .unwrap_or_default();
+            if current_enabled != *target_enabled {
+                let state = if *target_enabled {
+                    tr!("启用").to_string()
+                } else {
+                    tr!("禁用").to_string()
+                };
+                changes.push(format!("{}: {}", target, state));
+            }
+        }
+
+        EspStyleImport::PendingTree {
+            configs,
+            enabled,
+            changes,
+        }
+    }
+
+    fn render_esp_share_code_menu(&mut self, settings: &AppSettings, ui: &imgui::Ui) {
+        if ui.small_button(tr!("分享码")) {
+            ui.open_popup("esp_share_code");
+        }
+
+        if let Some(_popup) = ui.begin_popup("esp_share_code") {
+            if ui.menu_item(tr!("复制全部 ESP 分享码")) {
+                let share_code = EspShareCode::Tree {
+                    configs: settings.esp_settings.clone(),
+                    enabled: settings.esp_settings_enabled.clone(),
+                };
+                match share_code.encode() {
+                    Ok(value) => ui.set_clipboard_text(value),
+                    Err(error) => log::warn!("生成 ESP 分享码失败: {:#}", error),
+                }
+            }
+
+            if ui.menu_item(tr!("从剪贴板导入分享码")) {
+                let clipboard = ui.clipboard_text().unwrap_or_default();
+                self.esp_style_import = Some(Self::prepare_esp_tree_import(settings, &clipboard));
+            }
+        }
+    }
+
+    fn render_esp_style_import(&mut self, settings: &mut AppSettings, ui: &imgui::Ui) {
+        let popup_id = "导入 ESP 样式";
+        if self.esp_style_import.is_some() && !ui.is_popup_open(popup_id) {
+            ui.open_popup(popup_id);
+        }
+
+        let _popup = match ui.begin_modal_popup(popup_id) {
+            Some(popup) => popup,
+            None => return,
+        };
+
+        let mut close = false;
+        match &self.esp_style_import {
+            Some(EspStyleImport::Invalid { message }) => {
+                ui.text_colored([1.0, 0.0, 0.0, 1.0], message);
+                close = ui.button(tr!("确定"));
+            }
+            Some(EspStyleImport::Pending {
+                target,
+                config,
+                changes,
+            }) => {
+                ui.text(format!("将以下更改应用到 {}:", target.config_display()));
+                if changes.is_empty() {
+                    ui.text_disabled(tr!("(无更改)"));
+                }
+                for change in changes.iter() {
+                    ui.bullet_text(change);
+                }
+
+                ui.separator();
+                if ui.button(tr!("应用")) {
+                    settings.esp_settings.insert(target.config_key(), *config);
+                    close = true;
+                }
+                ui.same_line();
+                close |= ui.button(tr!("取消"));
+            }
+            Some(EspStyleImport::PendingTree {
+                configs,
+                enabled,
+                changes,
+            }) => {
+                ui.text(tr!("将以下更改应用到 ESP 目标:"));
+                if changes.is_empty() {
+                    ui.text_disabled(tr!("(无更改)"));
+                }
+                for change in changes.iter() {
+                    ui.bullet_text(change);
+                }
+
+                ui.separator();
+                if ui.button(tr!("应用")) {
+                    settings.esp_settings.extend(configs.clone());
+                    settings.esp_settings_enabled.extend(enabled.clone());
+                    close = true;
+                }
+                ui.same_line();
+                close |= ui.button(tr!("取消"));
+            }
+            None => close = true,
+        }
+
+        if close {
+            self.esp_style_import = None;
+            ui.close_current_popup();
+        }
+    }
+
     fn render_esp_settings_player(
         &mut self,
         settings: &mut AppSettings,
@@ -607,7 +2564,7 @@ impl SettingsUI {
                 0,
             );
         };
-        if ui.collapsing_header("功能", TreeNodeFlags::empty()) {
+        if ui.collapsing_header(tr!("功能"), TreeNodeFlags::empty()) {
             self.esp_player_active_header = EspPlayerActiveHeader::Features;
             if let Some(_token) = {
                 ui.child_window("features")
@@ -617,6 +2574,12 @@ impl SettingsUI {
                 ui.indent_by(5.0);
                 ui.dummy([0.0, 5.0]);
 
+                if Self::render_reset_button(ui, "esp_features", &tr!("重置功能设置")) {
+                    if let Err(error) = config.reset_fields(&target, ESP_PLAYER_FEATURE_FIELDS) {
+                        log::warn!("重置 ESP 功能设置失败: {:#}", error);
+                    }
+                }
+
                 const COMBO_WIDTH: f32 = 150.0;
                 {
                     const ESP_BOX_TYPES: [(EspBoxType, &'static str); 3] = [
@@ -626,7 +2589,7 @@ impl SettingsUI {
                     ];
 
                     ui.set_next_item_width(COMBO_WIDTH);
-                    ui.combo_enum(obfstr!("显示方框"), &ESP_BOX_TYPES, &mut config.box_type);
+                    ui.combo_enum(tr!("显示方框"), &ESP_BOX_TYPES, &mut config.box_type);
                 }
 
                 {
@@ -648,11 +2611,8 @@ impl SettingsUI {
                     };
 
                     ui.set_next_item_width(COMBO_WIDTH);
-                    let value_changed = ui.combo_enum(
-                        obfstr!("显示骨架"),
-                        &PLAYER_SKELETON_TYPES,
-                        &mut skeleton_type,
-                    );
+                    let value_changed =
+                        ui.combo_enum(tr!("显示骨架"), &PLAYER_SKELETON_TYPES, &mut skeleton_type);
 
                     if value_changed {
                         config.skeleton = matches!(skeleton_type, PlayerSkeletonType::Skeleton);
@@ -671,11 +2631,7 @@ impl SettingsUI {
                     ];
 
                     ui.set_next_item_width(COMBO_WIDTH);
-                    ui.combo_enum(
-                        obfstr!("追踪线"),
-                        &TRACER_LINE_TYPES,
-                        &mut config.tracer_lines,
-                    );
+                    ui.combo_enum(tr!("追踪线"), &TRACER_LINE_TYPES, &mut config.tracer_lines);
                 }
 
                 {
@@ -688,21 +2644,39 @@ impl SettingsUI {
                     ];
 
                     ui.set_next_item_width(COMBO_WIDTH);
-                    ui.combo_enum(obfstr!("血量条"), &HEALTH_BAR_TYPES, &mut config.health_bar);
+                    ui.combo_enum(tr!("血量条"), &HEALTH_BAR_TYPES, &mut config.health_bar);
+                    if config.health_bar != EspHealthBar::None {
+                        ui.same_line();
+                        ui.checkbox(tr!("受伤闪烁"), &mut config.health_bar_damage_flash);
+                    }
                 }
+                ui.checkbox(tr!("护甲条"), &mut config.armor_bar);
                 ui.dummy([0.0, 10.0]);
 
-                ui.text("显示玩家信息");
-                ui.checkbox(obfstr!("名称"), &mut config.info_name);
-                ui.checkbox(obfstr!("武器"), &mut config.info_weapon);
-                ui.checkbox(obfstr!("距离"), &mut config.info_distance);
-                ui.checkbox(obfstr!("生命值"), &mut config.info_hp_text);
-                ui.checkbox(obfstr!("工具包"), &mut config.info_flag_kit);
-                ui.checkbox(obfstr!("被闪了"), &mut config.info_flag_flashed);
-                ui.checkbox(obfstr!("仅显示附近玩家"), &mut config.near_players);
+                ui.text(tr!("显示玩家信息"));
+                ui.checkbox(tr!("名称"), &mut config.info_name);
+                ui.checkbox(tr!("Steam 名称"), &mut config.info_steam_name);
+                ui.checkbox(tr!("战队标签"), &mut config.info_clan_tag);
+                ui.checkbox(tr!("段位"), &mut config.info_rank);
+                ui.checkbox(tr!("武器"), &mut config.info_weapon);
+                if config.info_weapon {
+                    ui.same_line();
+                    ui.checkbox(tr!("显示为图标"), &mut config.info_weapon_icon);
+                }
+                ui.checkbox(tr!("投掷物"), &mut config.info_grenades);
+                ui.checkbox(tr!("距离"), &mut config.info_distance);
+                ui.checkbox(tr!("生命值"), &mut config.info_hp_text);
+                ui.checkbox(tr!("护甲"), &mut config.info_armor);
+                ui.checkbox(tr!("工具包"), &mut config.info_flag_kit);
+                ui.checkbox(tr!("被闪了"), &mut config.info_flag_flashed);
+                ui.checkbox(tr!("开镜"), &mut config.info_flag_scoped);
+                ui.checkbox(tr!("换弹"), &mut config.info_flag_reloading);
+                ui.checkbox(tr!("拆弹"), &mut config.info_flag_defusing);
+                ui.checkbox(tr!("安放炸弹"), &mut config.info_flag_planting);
+                ui.checkbox(tr!("仅显示附近玩家"), &mut config.near_players);
                 if config.near_players {
                     ui.same_line();
-                    ui.slider_config("最大距离", 0.0, 50.0)
+                    ui.slider_config(tr!("最大距离"), 0.0, 50.0)
                         .build(&mut config.near_players_distance);
                 }
             }
@@ -714,7 +2688,7 @@ impl SettingsUI {
                 0,
             );
         };
-        if ui.collapsing_header("外观", TreeNodeFlags::empty()) {
+        if ui.collapsing_header(tr!("外观"), TreeNodeFlags::empty()) {
             self.esp_player_active_header = EspPlayerActiveHeader::Style;
             if let Some(_token) = {
                 ui.child_window("styles")
@@ -724,6 +2698,12 @@ impl SettingsUI {
                 ui.indent_by(5.0);
                 ui.dummy([0.0, 5.0]);
 
+                if Self::render_reset_button(ui, "esp_style", &tr!("重置外观设置")) {
+                    if let Err(error) = config.reset_fields(&target, ESP_PLAYER_STYLE_FIELDS) {
+                        log::warn!("重置 ESP 外观设置失败: {:#}", error);
+                    }
+                }
+
                 if let Some(_token) = {
                     let mut column_type = TableColumnSetup::new("类型");
                     column_type.init_width_or_weight = 100.0;
@@ -745,14 +2725,14 @@ impl SettingsUI {
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("ESP 方框颜色"),
+                        &tr!("ESP 方框颜色"),
                         &mut config.box_color,
                     );
 
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_width(
                         ui,
-                        obfstr!("ESP 方框线宽"),
+                        &tr!("ESP 方框线宽"),
                         1.0,
                         10.0,
                         &mut config.box_width,
@@ -761,23 +2741,60 @@ impl SettingsUI {
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("玩家骨架颜色"),
+                        &tr!("玩家骨架颜色"),
                         &mut config.skeleton_color,
                     );
 
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_width(
                         ui,
-                        obfstr!("玩家骨架线宽"),
+                        &tr!("玩家骨架线宽"),
                         1.0,
                         10.0,
                         &mut config.skeleton_width,
                     );
 
+                    let group_styles = &mut config.skeleton_group_styles;
+                    for (name, style) in [
+                        ("头部骨架", &mut group_styles.head),
+                        ("躯干骨架", &mut group_styles.spine),
+                        ("手臂骨架", &mut group_styles.arms),
+                        ("腿部骨架", &mut group_styles.legs),
+                    ] {
+                        ui.table_next_row();
+                        ui.table_next_column();
+                        ui.text(&format!("{}单独样式", name));
+                        ui.table_next_column();
+                        ui.table_next_column();
+                        ui.checkbox(
+                            &format!("##{}_style_enabled", ui.table_row_index()),
+                            &mut style.enabled,
+                        );
+                        if !style.enabled {
+                            continue;
+                        }
+
+                        ui.table_next_row();
+                        Self::render_esp_settings_player_style_color(
+                            ui,
+                            &format!("{}颜色", name),
+                            &mut style.color,
+                        );
+
+                        ui.table_next_row();
+                        Self::render_esp_settings_player_style_width(
+                            ui,
+                            &format!("{}线宽", name),
+                            1.0,
+                            10.0,
+                            &mut style.width,
+                        );
+                    }
+
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_width(
                         ui,
-                        obfstr!("血量条宽度"),
+                        &tr!("血量条宽度"),
                         5.0,
                         30.0,
                         &mut config.health_bar_width,
@@ -786,14 +2803,14 @@ impl SettingsUI {
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("追踪线颜色"),
+                        &tr!("追踪线颜色"),
                         &mut config.tracer_lines_color,
                     );
 
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_width(
                         ui,
-                        obfstr!("追踪线宽度"),
+                        &tr!("追踪线宽度"),
                         1.0,
                         10.0,
                         &mut config.tracer_lines_width,
@@ -802,35 +2819,62 @@ impl SettingsUI {
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("名字文本颜色"),
+                        &tr!("名字文本颜色"),
                         &mut config.info_name_color,
                     );
+                    Self::render_esp_settings_player_text_style(
+                        ui,
+                        &tr!("名字文本"),
+                        &mut config.info_name_style,
+                    );
 
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("距离文本颜色"),
+                        &tr!("距离文本颜色"),
                         &mut config.info_distance_color,
                     );
+                    Self::render_esp_settings_player_text_style(
+                        ui,
+                        &tr!("距离文本"),
+                        &mut config.info_distance_style,
+                    );
 
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("武器文本颜色"),
+                        &tr!("武器文本颜色"),
                         &mut config.info_weapon_color,
                     );
+                    Self::render_esp_settings_player_text_style(
+                        ui,
+                        &tr!("武器文本"),
+                        &mut config.info_weapon_style,
+                    );
 
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("生命值文本颜色"),
+                        &tr!("生命值文本颜色"),
                         &mut config.info_hp_text_color,
                     );
+                    Self::render_esp_settings_player_text_style(
+                        ui,
+                        &tr!("生命值文本"),
+                        &mut config.info_hp_text_style,
+                    );
+
+                    ui.table_next_row();
+                    Self::render_esp_settings_player_style_color(
+                        ui,
+                        &tr!("护甲文本颜色"),
+                        &mut config.info_armor_color,
+                    );
 
                     ui.table_next_row();
                     Self::render_esp_settings_player_style_color(
                         ui,
-                        obfstr!("玩家标志文本颜色"),
+                        &tr!("玩家标志文本颜色"),
                         &mut config.info_flags_color,
                     );
                 }
@@ -840,6 +2884,30 @@ impl SettingsUI {
         drop(_ui_enable_token);
     }
 
+    /// Button which asks for a confirmation before resetting a section of the settings.
+    /// Returns true once the reset has been confirmed.
+    fn render_reset_button(ui: &imgui::Ui, id: &str, label: &str) -> bool {
+        let _id = ui.push_id(id);
+        if ui.button(label) {
+            ui.open_popup("reset_confirm");
+        }
+
+        let mut confirmed = false;
+        ui.popup("reset_confirm", || {
+            ui.text(tr!("确定要将这些设置恢复为默认值吗?"));
+            if ui.button(tr!("确定")) {
+                confirmed = true;
+                ui.close_current_popup();
+            }
+            ui.same_line();
+            if ui.button(tr!("取消")) {
+                ui.close_current_popup();
+            }
+        });
+
+        confirmed
+    }
+
     fn render_esp_settings_player_style_width(
         ui: &imgui::Ui,
         label: &str,
@@ -865,6 +2933,57 @@ impl SettingsUI {
         }
     }
 
+    fn render_esp_settings_player_text_style(
+        ui: &imgui::Ui,
+        label: &str,
+        style: &mut EspTextStyle,
+    ) {
+        ui.table_next_row();
+        Self::render_esp_settings_player_style_width(
+            ui,
+            &format!("{}字号", label),
+            0.5,
+            2.0,
+            &mut style.font_size,
+        );
+
+        ui.table_next_row();
+        ui.table_next_column();
+        ui.text(&format!("{}样式", label));
+
+        ui.table_next_column();
+        ui.set_next_item_width(ui.content_region_avail()[0]);
+        ui.combo_enum(
+            &format!("##{}_text_effect", ui.table_row_index()),
+            &[
+                (EspTextEffect::None, "普通"),
+                (EspTextEffect::Bold, "粗体"),
+                (EspTextEffect::Outline, "描边"),
+                (EspTextEffect::Shadow, "阴影"),
+            ],
+            &mut style.effect,
+        );
+
+        ui.table_next_column();
+        ui.checkbox(
+            &format!("背景##{}_text_background", ui.table_row_index()),
+            &mut style.background,
+        );
+    }
+
+    fn render_color_picker(ui: &imgui::Ui, label: &str, color: &mut Color) {
+        let mut color_value = color.as_f32();
+        if ui
+            .color_edit4_config(label, &mut color_value)
+            .alpha_bar(true)
+            .inputs(false)
+            .label(false)
+            .build()
+        {
+            *color = Color::from_f32(color_value);
+        }
+    }
+
     fn render_esp_settings_player_style_color(ui: &imgui::Ui, label: &str, color: &mut EspColor) {
         ui.table_next_column();
         ui.text(label);
@@ -880,6 +2999,8 @@ impl SettingsUI {
                     (EspColorType::HealthBased, "基于生命值"),
                     (EspColorType::HealthBasedRainbow, "花里胡哨"),
                     (EspColorType::DistanceBased, "基于距离"),
+                    (EspColorType::PlayerColor, "玩家颜色"),
+                    (EspColorType::Gradient, "渐变"),
                 ],
                 &mut color_type,
             );
@@ -893,8 +3014,20 @@ impl SettingsUI {
                         max: Color::from_f32([0.0, 1.0, 0.0, 1.0]),
                         min: Color::from_f32([1.0, 0.0, 0.0, 1.0]),
                     },
-                    EspColorType::HealthBasedRainbow => EspColor::HealthBasedRainbow,
+                    EspColorType::HealthBasedRainbow => {
+                        EspColor::HealthBasedRainbow(Some(Default::default()))
+                    }
                     EspColorType::DistanceBased => EspColor::DistanceBased,
+                    EspColorType::PlayerColor => EspColor::PlayerColor {
+                        fallback: Color::from_f32([1.0, 1.0, 1.0, 1.0]),
+                    },
+                    EspColorType::Gradient => EspColor::Gradient {
+                        source: EspGradientSource::Health,
+                        gradient: EspGradient::new(
+                            Color::from_f32([1.0, 0.0, 0.0, 1.0]),
+                            Color::from_f32([0.0, 1.0, 0.0, 1.0]),
+                        ),
+                    },
                 }
             }
         }
@@ -902,7 +3035,34 @@ impl SettingsUI {
         ui.table_next_column();
         {
             match color {
-                EspColor::HealthBasedRainbow => ui.text("花里胡哨"),
+                EspColor::HealthBasedRainbow(options) => {
+                    let options = options.get_or_insert_with(Default::default);
+                    let item_width = (ui.content_region_avail()[0] - 5.0) / 2.0;
+
+                    ui.set_next_item_width(item_width);
+                    ui.slider_config(
+                        &format!("##{}_rainbow_speed", ui.table_row_index()),
+                        0.0,
+                        5.0,
+                    )
+                    .display_format(&format!("{} %.1f/s", tr!("速度")))
+                    .build(&mut options.speed);
+
+                    ui.same_line_with_spacing(0.0, 5.0);
+                    let mut saturation = options.saturation * 100.0;
+                    ui.set_next_item_width(item_width);
+                    if ui
+                        .slider_config(
+                            &format!("##{}_rainbow_saturation", ui.table_row_index()),
+                            0.0,
+                            100.0,
+                        )
+                        .display_format(&format!("{} %.0f%%", tr!("饱和度")))
+                        .build(&mut saturation)
+                    {
+                        options.saturation = saturation / 100.0;
+                    }
+                }
                 EspColor::Static { value } => {
                     let mut color_value = value.as_f32();
 
@@ -953,8 +3113,127 @@ impl SettingsUI {
                     }
                 }
                 EspColor::DistanceBased => ui.text("Distance"),
+                EspColor::PlayerColor { fallback } => {
+                    let mut fallback_value = fallback.as_f32();
+                    if {
+                        ui.color_edit4_config(
+                            &format!("##{}_player_color_fallback", ui.table_row_index()),
+                            &mut fallback_value,
+                        )
+                        .alpha_bar(true)
+                        .inputs(false)
+                        .label(false)
+                        .build()
+                    } {
+                        *fallback = Color::from_f32(fallback_value);
+                    }
+
+                    ui.same_line();
+                    ui.text(tr!("无颜色时"));
+                }
+                EspColor::Gradient { source, gradient } => {
+                    Self::render_esp_color_gradient(ui, source, gradient);
+                }
+            }
+
+            if !matches!(
+                color,
+                EspColor::Static { .. } | EspColor::PlayerColor { .. }
+            ) {
+                Self::render_esp_color_preview(ui, color);
+            }
+        }
+    }
+
+    fn render_esp_color_gradient(
+        ui: &imgui::Ui,
+        source: &mut EspGradientSource,
+        gradient: &mut EspGradient,
+    ) {
+        let row_index = ui.table_row_index();
+
+        ui.set_next_item_width(ui.content_region_avail()[0]);
+        ui.combo_enum(
+            &format!("##{}_gradient_source", row_index),
+            &[
+                (EspGradientSource::Health, "基于生命值"),
+                (EspGradientSource::Distance, "基于距离"),
+            ],
+            source,
+        );
+
+        let mut remove_stop = None;
+        let stop_count = gradient.stops().len();
+        for (index, stop) in gradient.stops_mut().iter_mut().enumerate() {
+            let _id = ui.push_id_usize(index);
+
+            let mut color_value = stop.color.as_f32();
+            if {
+                ui.color_edit4_config(&format!("##{}_gradient_color", row_index), &mut color_value)
+                    .alpha_bar(true)
+                    .inputs(false)
+                    .label(false)
+                    .build()
+            } {
+                stop.color = Color::from_f32(color_value);
+            }
+
+            ui.same_line();
+            let mut position = stop.position * 100.0;
+            ui.set_next_item_width(ui.content_region_avail()[0] - ui.frame_height() - 5.0);
+            if ui
+                .slider_config(&format!("##{}_gradient_position", row_index), 0.0, 100.0)
+                .display_format("%.0f%%")
+                .build(&mut position)
+            {
+                stop.position = position / 100.0;
+            }
+
+            ui.same_line_with_spacing(0.0, 5.0);
+            let _enabled = ui.begin_enabled(stop_count > 2);
+            if ui.button_with_size(
+                format!("-##{}_gradient_remove", row_index),
+                [ui.frame_height(), 0.0],
+            ) {
+                remove_stop = Some(index);
             }
         }
+
+        if let Some(index) = remove_stop {
+            gradient.remove_stop(index);
+        }
+
+        let _enabled = ui.begin_enabled(stop_count < ESP_GRADIENT_MAX_STOPS);
+        if ui.small_button(format!("{}##{}_gradient_add", tr!("添加颜色"), row_index)) {
+            gradient.add_stop();
+        }
+    }
+
+    /// Draw the color from zero to full health (or max distance) as a bar
+    fn render_esp_color_preview(ui: &imgui::Ui, color: &EspColor) {
+        const PREVIEW_SEGMENTS: usize = 24;
+
+        let [x, y] = ui.cursor_screen_pos();
+        let width = ui.content_region_avail()[0];
+        let height = ui.text_line_height() * 0.5;
+
+        let draw = ui.get_window_draw_list();
+        for segment in 0..PREVIEW_SEGMENTS {
+            let from = segment as f32 / PREVIEW_SEGMENTS as f32;
+            let to = (segment + 1) as f32 / PREVIEW_SEGMENTS as f32;
+            let from_color = ImColor32::from(color.preview_color(from));
+            let to_color = ImColor32::from(color.preview_color(to));
+
+            draw.add_rect_filled_multicolor(
+                [x + width * from, y],
+                [x + width * to, y + height],
+                from_color,
+                to_color,
+                to_color,
+                from_color,
+            );
+        }
+        ui.dummy([width, height]);
     }
 
     fn render_esp_settings_chicken(
@@ -975,18 +3254,37 @@ impl SettingsUI {
         ui.text("Weapon!");
     }
 
-    fn render_esp_settings(&mut self, settings: &mut AppSettings, ui: &imgui::Ui) {
+    fn render_esp_settings(
+        &mut self,
+        settings: &mut AppSettings,
+        app: &Application,
+        ui: &imgui::Ui,
+    ) {
         if let Some(target) = self.esp_pending_target.take() {
             self.esp_selected_target = target;
         }
+        self.render_esp_style_import(settings, ui);
 
         /* the left tree */
         let content_region = ui.content_region_avail();
         let original_style = ui.clone_style();
         let tree_width = (content_region[0] * 0.25).max(150.0);
-        let content_width = (content_region[0] - tree_width - 5.0).max(300.0);
+        let show_preview = matches!(
+            self.esp_selected_target,
+            EspSelector::Player
+                | EspSelector::PlayerTeam { .. }
+                | EspSelector::PlayerTeamVisibility { .. }
+        ) && content_region[0] - tree_width - ESP_PREVIEW_WIDTH >= 400.0;
+        let preview_width = if show_preview {
+            ESP_PREVIEW_WIDTH + 5.0
+        } else {
+            0.0
+        };
+        let content_width = (content_region[0] - tree_width - preview_width - 5.0).max(300.0);
 
-        ui.text("ESP 目标");
+        ui.text(tr!("ESP 目标"));
+        ui.same_line();
+        self.render_esp_share_code_menu(settings, ui);
         ui.same_line_with_pos(
             original_style.window_padding[0] * 2.0 + tree_width + original_style.window_border_size,
         );
@@ -999,19 +3297,19 @@ impl SettingsUI {
 
             ui.checkbox(self.esp_selected_target.config_title(), target_enabled);
 
-            let reset_text = "重置配置";
+            let reset_text = tr!("重置配置").to_string();
             let reset_text_width = ui.calc_text_size(&reset_text)[0];
 
             let total_width = ui.content_region_avail()[0] + 2.0;
             ui.same_line_with_pos(total_width - reset_text_width);
 
             let _enabled = ui.begin_enabled(*target_enabled);
-            if ui.button(reset_text) {
+            if ui.button(&reset_text) {
                 /* just removing the key will work as a default config will be emplaced later */
                 settings.esp_settings.remove(&target_key);
             }
         } else {
-            ui.text("目标配置");
+            ui.text(tr!("目标配置"));
         };
 
         //ui.dummy([0.0, 10.0]);
@@ -1065,5 +3363,21 @@ impl SettingsUI {
                 }
             }
         }
+
+        if show_preview {
+            ui.same_line();
+            if let Some(_token) = {
+                ui.child_window("Preview")
+                    .size([ESP_PREVIEW_WIDTH, 0.0])
+                    .border(true)
+                    .begin()
+            } {
+                ui.text(tr!("预览"));
+                let config_key = self.esp_selected_target.config_key();
+                if let Some(EspConfig::Player(config)) = settings.esp_settings.get(&config_key) {
+                    enhancements::render_esp_preview(ui, &app.fonts, config);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,433 @@
+use std::{
+    fmt,
+    net::SocketAddr,
+};
+
+use anyhow::Context;
+
+use super::{
+    AppSettings,
+    ConfigFormat,
+    FpsLimitMode,
+    HotKey,
+    MAX_GLYPH_CODE_POINT,
+};
+
+/// Fields which have been used by older versions but are no longer supported
+const DEPRECATED_FIELDS: &[(&str, &str)] = &[
+    ("mouse_x_360", "鼠标灵敏度现在从游戏设置中读取"),
+    ("esp_toogle", "已自动迁移到 hotkeys"),
+    ("key_trigger_bot", "已自动迁移到 hotkeys"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigIssueSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+pub struct ConfigIssue {
+    pub severity: ConfigIssueSeverity,
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn warning(field: &str, message: String) -> Self {
+        Self {
+            severity: ConfigIssueSeverity::Warning,
+            field: field.to_string(),
+            message,
+        }
+    }
+
+    fn error(field: &str, message: String) -> Self {
+        Self {
+            severity: ConfigIssueSeverity::Error,
+            field: field.to_string(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            ConfigIssueSeverity::Warning => "警告",
+            ConfigIssueSeverity::Error => "错误",
+        };
+
+        write!(f, "[{}] {}: {}", severity, self.field, self.message)
+    }
+}
+
+fn check_range(issues: &mut Vec<ConfigIssue>, field: &str, value: u32, min: u32, max: u32) {
+    if value < min || value > max {
+        issues.push(ConfigIssue::error(
+            field,
+            format!("值 {} 超出范围 ({} - {})", value, min, max),
+        ));
+    }
+}
+
+fn check_hotkey_conflicts(issues: &mut Vec<ConfigIssue>, hotkeys: &[(String, &HotKey)]) {
+    for (index, (field, key)) in hotkeys.iter().enumerate() {
+        for (other_field, other_key) in hotkeys.iter().skip(index + 1) {
+            if key != other_key {
+                continue;
+            }
+
+            issues.push(ConfigIssue::error(
+                field,
+                format!("热键 {} 与 {} 冲突", key, other_field),
+            ));
+        }
+    }
+}
+
+/// Validate the given config file content.
+/// Returns an error if the config is not a valid document of the given format.
+pub fn validate_config(content: &str, format: ConfigFormat) -> anyhow::Result<Vec<ConfigIssue>> {
+    let document: serde_yaml::Value = format.parse(content).context("failed to parse config")?;
+
+    let mut issues = Vec::new();
+
+    let known_fields = serde_yaml::to_value(
+        serde_yaml::from_str::<AppSettings>("").context("failed to create default config")?,
+    )?;
+    let known_fields = known_fields
+        .as_mapping()
+        .context("expected the default config to be a mapping")?;
+
+    match &document {
+        serde_yaml::Value::Mapping(fields) => {
+            for key in fields.keys() {
+                let key = match key.as_str() {
+                    Some(key) => key,
+                    None => {
+                        issues.push(ConfigIssue::error(
+                            &format!("{:?}", key),
+                            "字段名称必须为字符串".to_string(),
+                        ));
+                        continue;
+                    }
+                };
+
+                if let Some((_, reason)) = DEPRECATED_FIELDS.iter().find(|(name, _)| *name == key) {
+                    issues.push(ConfigIssue::warning(
+                        key,
+                        format!("该字段已弃用: {}", reason),
+                    ));
+                } else if !known_fields.contains_key(key) {
+                    issues.push(ConfigIssue::warning(key, "未知字段".to_string()));
+                }
+            }
+        }
+        serde_yaml::Value::Null => {}
+        _ => anyhow::bail!("the config must be a mapping"),
+    }
+
+    let mut settings: AppSettings = match serde_yaml::from_value(document) {
+        Ok(settings) => settings,
+        Err(error) => {
+            issues.push(ConfigIssue::error("<config>", format!("{}", error)));
+            return Ok(issues);
+        }
+    };
+
+    check_range(&mut issues, "ui_scale", settings.ui_scale, 50, 300);
+    if settings.fps_limit_mode() == FpsLimitMode::Custom {
+        check_range(
+            &mut issues,
+            "overlay_fps_limit",
+            settings.fps_limit(),
+            1,
+            960,
+        );
+    }
+    check_range(
+        &mut issues,
+        "overlay_fps_limit_background",
+        settings.overlay_fps_limit_background,
+        0,
+        240,
+    );
+    check_range(
+        &mut issues,
+        "overlay_fps_limit_idle",
+        settings.overlay_fps_limit_idle,
+        1,
+        5,
+    );
+    check_range(&mut issues, "update_rate", settings.update_rate, 0, 500);
+    check_range(
+        &mut issues,
+        "overlay_hdr_brightness",
+        settings.overlay_hdr_brightness,
+        80,
+        1000,
+    );
+    check_range(
+        &mut issues,
+        "hotkey_hold_duration",
+        settings.hotkey_hold_duration,
+        0,
+        2000,
+    );
+    check_range(
+        &mut issues,
+        "hotkey_double_tap_interval",
+        settings.hotkey_double_tap_interval,
+        100,
+        1000,
+    );
+    check_range(
+        &mut issues,
+        "esp_max_entries",
+        settings.esp_max_entries,
+        0,
+        64,
+    );
+    check_range(
+        &mut issues,
+        "esp_glow_radius",
+        settings.esp_glow_radius,
+        1,
+        32,
+    );
+    check_range(
+        &mut issues,
+        "esp_glow_intensity",
+        settings.esp_glow_intensity,
+        50,
+        400,
+    );
+    check_range(&mut issues, "audio_volume", settings.audio_volume, 0, 100);
+    check_range(
+        &mut issues,
+        "proximity_alert_distance",
+        settings.proximity_alert_distance,
+        1,
+        50,
+    );
+    check_range(
+        &mut issues,
+        "spectators_list_max_rows",
+        settings.spectators_list_max_rows,
+        0,
+        32,
+    );
+    check_range(
+        &mut issues,
+        "session_break_interval",
+        settings.session_break_interval,
+        0,
+        240,
+    );
+    check_range(
+        &mut issues,
+        "match_timeline_position_interval",
+        settings.match_timeline_position_interval,
+        1,
+        60,
+    );
+    check_range(
+        &mut issues,
+        "trigger_bot_delay_min",
+        settings.trigger_bot_delay_min,
+        0,
+        250,
+    );
+    check_range(
+        &mut issues,
+        "trigger_bot_delay_max",
+        settings.trigger_bot_delay_max,
+        0,
+        250,
+    );
+    check_range(
+        &mut issues,
+        "trigger_bot_burst",
+        settings.trigger_bot_burst,
+        0,
+        1000,
+    );
+    for (name, timing) in [
+        ("pistol", &settings.trigger_bot_class_timings.pistol),
+        ("rifle", &settings.trigger_bot_class_timings.rifle),
+        ("sniper", &settings.trigger_bot_class_timings.sniper),
+        (
+            "auto_sniper",
+            &settings.trigger_bot_class_timings.auto_sniper,
+        ),
+    ] {
+        check_range(
+            &mut issues,
+            &format!("trigger_bot_class_timings.{}.delay_min", name),
+            timing.delay_min,
+            0,
+            250,
+        );
+        check_range(
+            &mut issues,
+            &format!("trigger_bot_class_timings.{}.delay_max", name),
+            timing.delay_max,
+            0,
+            250,
+        );
+        check_range(
+            &mut issues,
+            &format!("trigger_bot_class_timings.{}.burst", name),
+            timing.burst,
+            0,
+            1000,
+        );
+        if timing.enabled && timing.delay_min > timing.delay_max {
+            issues.push(ConfigIssue::error(
+                &format!("trigger_bot_class_timings.{}.delay_min", name),
+                format!(
+                    "最小延迟 ({}) 大于最大延迟 ({})",
+                    timing.delay_min, timing.delay_max
+                ),
+            ));
+        }
+    }
+    check_range(
+        &mut issues,
+        "recoil_control_strength_x",
+        settings.recoil_control_strength_x,
+        0,
+        100,
+    );
+    check_range(
+        &mut issues,
+        "recoil_control_strength_y",
+        settings.recoil_control_strength_y,
+        0,
+        100,
+    );
+    check_range(
+        &mut issues,
+        "flash_indicator_esp_alpha",
+        settings.flash_indicator_esp_alpha,
+        0,
+        100,
+    );
+    check_range(
+        &mut issues,
+        "trigger_bot_warmup_profile.delay_min",
+        settings.trigger_bot_warmup_profile.delay_min,
+        0,
+        250,
+    );
+    check_range(
+        &mut issues,
+        "trigger_bot_warmup_profile.delay_max",
+        settings.trigger_bot_warmup_profile.delay_max,
+        0,
+        250,
+    );
+    check_range(
+        &mut issues,
+        "trigger_bot_warmup_profile.burst",
+        settings.trigger_bot_warmup_profile.burst,
+        0,
+        1000,
+    );
+    for (field, profile) in [
+        ("trigger_bot_delay_min", settings.trigger_bot_profile()),
+        (
+            "trigger_bot_warmup_profile.delay_min",
+            settings.trigger_bot_warmup_profile,
+        ),
+    ] {
+        if profile.delay_min > profile.delay_max {
+            issues.push(ConfigIssue::error(
+                field,
+                format!(
+                    "最小延迟 ({}) 大于最大延迟 ({})",
+                    profile.delay_min, profile.delay_max
+                ),
+            ));
+        }
+    }
+
+    for (index, font) in settings.custom_fonts.iter().enumerate() {
+        check_range(
+            &mut issues,
+            &format!("custom_fonts.{}.size", index),
+            font.size,
+            6,
+            64,
+        );
+        for [start, end] in &font.glyph_ranges {
+            if start > end || *end > MAX_GLYPH_CODE_POINT {
+                issues.push(ConfigIssue::warning(
+                    &format!("custom_fonts.{}.glyph_ranges", index),
+                    format!("无效的字符范围 0x{:04X} - 0x{:04X}", start, end),
+                ));
+            }
+        }
+    }
+
+    if settings
+        .remote_control_address
+        .parse::<SocketAddr>()
+        .is_err()
+    {
+        issues.push(ConfigIssue::error(
+            "remote_control_address",
+            format!("无效的地址 {}", settings.remote_control_address),
+        ));
+    }
+
+    settings.migrate_legacy_hotkeys();
+
+    let mut hotkeys = vec![("key_settings".to_string(), &settings.key_settings)];
+    hotkeys.extend(
+        settings.hotkeys.iter().filter_map(|(action, key)| {
+            key.as_ref().map(|key| (format!("hotkeys.{}", action), key))
+        }),
+    );
+    check_hotkey_conflicts(&mut issues, &hotkeys);
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        validate_config,
+        ConfigFormat,
+        ConfigIssueSeverity,
+    };
+
+    #[test]
+    fn empty_config_is_valid() {
+        let issues = validate_config("", ConfigFormat::Yaml).unwrap();
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn reports_unknown_fields_and_conflicts() {
+        let issues = validate_config(
+            "mouse_x_360: 1000\nunknown_field: true\nkey_settings: Pause\nhotkeys:\n  trigger_bot: Pause\n",
+            ConfigFormat::Yaml,
+        )
+        .unwrap();
+
+        let fields = issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.severity))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("mouse_x_360", ConfigIssueSeverity::Warning),
+                ("unknown_field", ConfigIssueSeverity::Warning),
+                ("key_settings", ConfigIssueSeverity::Error),
+            ]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    path::PathBuf,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+};
+
+use super::AppSettings;
+
+/// Interval in which the config file will be checked for modifications
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Detects external modifications of the config file.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+    last_poll: Instant,
+
+    /// Serialized settings as they have been read from or written to disk
+    synced_content: Option<String>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        let mut result = Self {
+            path,
+            last_modified: None,
+            last_poll: Instant::now(),
+            synced_content: None,
+        };
+        result.last_modified = result.read_modified_time();
+        result
+    }
+
+    pub fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+        self.last_modified = self.read_modified_time();
+    }
+
+    fn read_modified_time(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Remember the current settings as the content of the config file.
+    /// Should be called every time the settings have been loaded or saved.
+    pub fn mark_synced(&mut self, settings: &AppSettings) {
+        self.synced_content = serde_yaml::to_string(settings).ok();
+        self.last_modified = self.read_modified_time();
+    }
+
+    /// Check if the settings differ from what has been read from or written to disk
+    pub fn has_local_changes(&self, settings: &AppSettings) -> bool {
+        match &self.synced_content {
+            Some(content) => serde_yaml::to_string(settings)
+                .map(|current| current != *content)
+                .unwrap_or(true),
+            None => true,
+        }
+    }
+
+    /// Returns true once if the config file has been modified by someone else.
+    pub fn poll_external_change(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        let modified = self.read_modified_time();
+        if modified.is_none() || modified == self.last_modified {
+            return false;
+        }
+
+        self.last_modified = modified;
+        true
+    }
+}
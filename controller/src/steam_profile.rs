@@ -0,0 +1,290 @@
+use std::{
+    collections::HashMap,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use anyhow::Context;
+use obfstr::obfstr;
+use overlay::{
+    OverlayTexture,
+    TextureUploader,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::settings::get_settings_path;
+
+/// Time after which a profile stored on disk will be fetched again
+const DISK_CACHE_LIFETIME: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Time to wait before retrying a failed profile lookup
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Maximum amount of profiles kept in memory.
+/// Every loaded profile holds an avatar texture and the overlay only supports a limited amount of textures.
+const MAX_LOADED_PROFILES: usize = 64;
+
+pub struct SteamProfile {
+    pub persona_name: String,
+    pub avatar: Option<OverlayTexture>,
+}
+
+enum ProfileEntry {
+    Pending,
+    Loaded {
+        profile: Arc<SteamProfile>,
+        last_used: Instant,
+    },
+    Failed(Instant),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedProfile {
+    persona_name: String,
+    avatar_url: String,
+
+    /// Unix timestamp (seconds) of the time the profile has been fetched
+    fetched_at: u64,
+}
+
+#[derive(Deserialize)]
+struct PlayerSummariesResponse {
+    response: PlayerSummaries,
+}
+
+#[derive(Deserialize)]
+struct PlayerSummaries {
+    players: Vec<PlayerSummary>,
+}
+
+#[derive(Deserialize)]
+struct PlayerSummary {
+    personaname: String,
+    avatarmedium: String,
+}
+
+pub fn get_steam_profile_cache_directory() -> anyhow::Result<PathBuf> {
+    let settings_path = get_settings_path()?;
+    let base_dir = settings_path
+        .parent()
+        .context("could not get config directory")?;
+
+    Ok(base_dir.join("steam_profiles"))
+}
+
+/// Resolves SteamIDs to persona names and avatars using the Steam Web API.
+/// Profiles are fetched in the background and stored on disk.
+pub struct SteamProfileCache {
+    texture_uploader: TextureUploader,
+    entries: Arc<Mutex<HashMap<u64, ProfileEntry>>>,
+}
+
+impl SteamProfileCache {
+    pub fn new(texture_uploader: TextureUploader) -> Self {
+        Self {
+            texture_uploader,
+            entries: Default::default(),
+        }
+    }
+
+    /// Lookup the profile for the given SteamID.
+    /// Returns None while the profile is being fetched or the lookup failed.
+    pub fn lookup(&self, steam_id: u64, api_key: &str) -> Option<Arc<SteamProfile>> {
+        if steam_id == 0 {
+            /* bots do not have a steam id */
+            return None;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get_mut(&steam_id) {
+            Some(ProfileEntry::Loaded { profile, last_used }) => {
+                *last_used = Instant::now();
+                return Some(profile.clone());
+            }
+            Some(ProfileEntry::Pending) => return None,
+            Some(ProfileEntry::Failed(timestamp)) if timestamp.elapsed() < RETRY_DELAY => {
+                return None
+            }
+            _ => {}
+        }
+
+        entries.insert(steam_id, ProfileEntry::Pending);
+        drop(entries);
+
+        let entries = self.entries.clone();
+        let texture_uploader = self.texture_uploader.clone();
+        let api_key = api_key.to_string();
+        tokio::spawn(async move {
+            let entry = match fetch_profile(steam_id, &api_key, &texture_uploader).await {
+                Ok(profile) => ProfileEntry::Loaded {
+                    profile: Arc::new(profile),
+                    last_used: Instant::now(),
+                },
+                Err(error) => {
+                    log::warn!("获取 Steam 资料 {} 失败: {:#}", steam_id, error);
+                    ProfileEntry::Failed(Instant::now())
+                }
+            };
+
+            let mut entries = entries.lock().unwrap();
+            entries.insert(steam_id, entry);
+            evict_least_recently_used(&mut entries, &texture_uploader);
+        });
+
+        None
+    }
+}
+
+/// Remove the least recently used profiles until at most `MAX_LOADED_PROFILES` are loaded
+/// and release their avatar textures.
+fn evict_least_recently_used(
+    entries: &mut HashMap<u64, ProfileEntry>,
+    texture_uploader: &TextureUploader,
+) {
+    let mut loaded = entries
+        .iter()
+        .filter_map(|(steam_id, entry)| match entry {
+            ProfileEntry::Loaded { last_used, .. } => Some((*last_used, *steam_id)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if loaded.len() <= MAX_LOADED_PROFILES {
+        return;
+    }
+
+    loaded.sort_unstable();
+    let evict_count = loaded.len() - MAX_LOADED_PROFILES;
+    for (_, steam_id) in loaded.into_iter().take(evict_count) {
+        if let Some(ProfileEntry::Loaded { profile, .. }) = entries.remove(&steam_id) {
+            if let Some(avatar) = &profile.avatar {
+                texture_uploader.delete(avatar);
+            }
+        }
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn load_cached_profile(cache_dir: &Path, steam_id: u64) -> Option<(CachedProfile, Vec<u8>)> {
+    let profile = std::fs::read(cache_dir.join(format!("{}.json", steam_id))).ok()?;
+    let profile = serde_json::from_slice::<CachedProfile>(&profile).ok()?;
+    if unix_timestamp().saturating_sub(profile.fetched_at) > DISK_CACHE_LIFETIME.as_secs() {
+        return None;
+    }
+
+    let avatar = std::fs::read(cache_dir.join(format!("{}.avatar", steam_id))).ok()?;
+    Some((profile, avatar))
+}
+
+async fn fetch_remote_profile(
+    steam_id: u64,
+    api_key: &str,
+) -> anyhow::Result<(CachedProfile, Vec<u8>)> {
+    if api_key.is_empty() {
+        anyhow::bail!("{}", obfstr!("missing Steam Web API key"));
+    }
+
+    let url = url::Url::parse_with_params(
+        obfstr!("https://api.steampowered.com/ISteamUser/GetPlayerSummaries/v2/"),
+        &[
+            ("key", api_key.to_string()),
+            ("steamids", steam_id.to_string()),
+        ],
+    )?;
+    let summaries = reqwest::get(url)
+        .await?
+        .error_for_status()?
+        .json::<PlayerSummariesResponse>()
+        .await?;
+
+    let summary = summaries
+        .response
+        .players
+        .into_iter()
+        .next()
+        .context("profile not found")?;
+
+    let avatar = reqwest::get(&summary.avatarmedium)
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?
+        .to_vec();
+
+    let profile = CachedProfile {
+        persona_name: summary.personaname,
+        avatar_url: summary.avatarmedium,
+        fetched_at: unix_timestamp(),
+    };
+    Ok((profile, avatar))
+}
+
+fn store_cached_profile(
+    cache_dir: &Path,
+    steam_id: u64,
+    profile: &CachedProfile,
+    avatar: &[u8],
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(cache_dir)?;
+    std::fs::write(cache_dir.join(format!("{}.avatar", steam_id)), avatar)?;
+    std::fs::write(
+        cache_dir.join(format!("{}.json", steam_id)),
+        serde_json::to_vec(profile)?,
+    )?;
+    Ok(())
+}
+
+async fn fetch_profile(
+    steam_id: u64,
+    api_key: &str,
+    texture_uploader: &TextureUploader,
+) -> anyhow::Result<SteamProfile> {
+    let cache_dir = get_steam_profile_cache_directory()?;
+    let (profile, avatar) = match load_cached_profile(&cache_dir, steam_id) {
+        Some(cached) => cached,
+        None => {
+            let (profile, avatar) = fetch_remote_profile(steam_id, api_key).await?;
+            if let Err(error) = store_cached_profile(&cache_dir, steam_id, &profile, &avatar) {
+                log::warn!("缓存 Steam 资料失败: {:#}", error);
+            }
+
+            (profile, avatar)
+        }
+    };
+
+    let avatar = match image::load_from_memory(&avatar) {
+        Ok(image) => {
+            let image = image.to_rgba8();
+            Some(texture_uploader.upload(image.width(), image.height(), image.into_raw()))
+        }
+        Err(error) => {
+            log::warn!("解码 Steam 头像失败: {:#}", error);
+            None
+        }
+    };
+
+    Ok(SteamProfile {
+        persona_name: profile.persona_name,
+        avatar,
+    })
+}
@@ -0,0 +1,343 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use anyhow::Context;
+use obfstr::obfstr;
+use serde::Deserialize;
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+/// Suffix of the previous executable which has been replaced by an update
+const OLD_EXECUTABLE_SUFFIX: &str = "old";
+
+/// Name of the controller executable attached to a release
+const RELEASE_EXECUTABLE_NAME: &str = "controller.exe";
+
+/// Name of the release asset containing the SHA-256 checksum of the executable
+const RELEASE_CHECKSUM_NAME: &str = "controller.exe.sha256";
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GithubReleaseAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Clone)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub title: String,
+    pub changelog: String,
+    pub url: String,
+
+    /// Download of the controller executable.
+    /// None if the release does not contain the executable and its checksum.
+    pub download: Option<ReleaseDownload>,
+}
+
+#[derive(Clone)]
+pub struct ReleaseDownload {
+    pub executable_url: String,
+    pub checksum_url: String,
+}
+
+#[derive(Clone)]
+pub enum UpdateStatus {
+    Idle,
+    Checking,
+    UpToDate,
+    Available(ReleaseInfo),
+    Downloading(ReleaseInfo),
+
+    /// The executable has been replaced and the update will be active after a restart
+    Installed(ReleaseInfo),
+    Failed(String),
+}
+
+/// Checks the GitHub releases of the CHS fork for a newer version
+/// and replaces the controller executable with the downloaded release.
+pub struct UpdateChecker {
+    status: Arc<Mutex<UpdateStatus>>,
+}
+
+impl UpdateChecker {
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(Mutex::new(UpdateStatus::Idle)),
+        }
+    }
+
+    pub fn status(&self) -> UpdateStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn check(&self) {
+        {
+            let mut status = self.status.lock().unwrap();
+            if matches!(
+                *status,
+                UpdateStatus::Checking | UpdateStatus::Downloading(_) | UpdateStatus::Installed(_)
+            ) {
+                return;
+            }
+
+            *status = UpdateStatus::Checking;
+        }
+
+        let status = self.status.clone();
+        tokio::spawn(async move {
+            let result = match fetch_latest_release().await {
+                Ok(release) if is_newer_version(&release.version, env!("CARGO_PKG_VERSION")) => {
+                    log::info!("发现新版本 {}", release.version);
+                    UpdateStatus::Available(release)
+                }
+                Ok(_) => UpdateStatus::UpToDate,
+                Err(error) => {
+                    log::warn!("检查更新失败: {:#}", error);
+                    UpdateStatus::Failed(format!("{:#}", error))
+                }
+            };
+
+            *status.lock().unwrap() = result;
+        });
+    }
+
+    /// Download the release and replace the current executable.
+    /// The new version will be used the next time the controller gets started.
+    pub fn install(&self, release: ReleaseInfo) {
+        *self.status.lock().unwrap() = UpdateStatus::Downloading(release.clone());
+
+        let status = self.status.clone();
+        tokio::spawn(async move {
+            let result = match install_release(&release).await {
+                Ok(_) => {
+                    log::info!("已安装版本 {}, 重启后生效", release.version);
+                    UpdateStatus::Installed(release)
+                }
+                Err(error) => {
+                    log::warn!("安装更新失败: {:#}", error);
+                    UpdateStatus::Failed(format!("{:#}", error))
+                }
+            };
+
+            *status.lock().unwrap() = result;
+        });
+    }
+}
+
+fn old_executable_path(executable: &Path) -> PathBuf {
+    let mut path = executable.as_os_str().to_owned();
+    path.push(".");
+    path.push(OLD_EXECUTABLE_SUFFIX);
+    PathBuf::from(path)
+}
+
+/// Remove the executable which has been replaced by the last update
+pub fn cleanup_previous_update() {
+    let executable = match std::env::current_exe() {
+        Ok(executable) => executable,
+        Err(_) => return,
+    };
+
+    let old_executable = old_executable_path(&executable);
+    if old_executable.is_file() {
+        if let Err(error) = std::fs::remove_file(&old_executable) {
+            log::debug!(
+                "删除旧版本 {} 失败: {}",
+                old_executable.to_string_lossy(),
+                error
+            );
+        }
+    }
+}
+
+/// Parse the numeric components of a version like `v0.4.5` or `0.4.5-chs`
+fn parse_version(version: &str) -> Vec<u32> {
+    version
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+fn is_newer_version(remote: &str, current: &str) -> bool {
+    let remote = parse_version(remote);
+    !remote.is_empty() && remote > parse_version(current)
+}
+
+async fn fetch_latest_release() -> anyhow::Result<ReleaseInfo> {
+    let url = format!(
+        "https://api.github.com/repos/{}/{}/releases/latest",
+        obfstr!("nkxingxh"),
+        obfstr!("Valthrun-CHS")
+    );
+    let release = reqwest::Client::new()
+        .get(url)
+        .header(reqwest::header::USER_AGENT, obfstr!("Valthrun-CHS"))
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .context("request")?
+        .error_for_status()
+        .context("status")?
+        .json::<GithubRelease>()
+        .await
+        .context("parse")?;
+
+    let asset_url = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .map(|asset| asset.browser_download_url.clone())
+    };
+    let download = match (
+        asset_url(RELEASE_EXECUTABLE_NAME),
+        asset_url(RELEASE_CHECKSUM_NAME),
+    ) {
+        (Some(executable_url), Some(checksum_url)) => Some(ReleaseDownload {
+            executable_url,
+            checksum_url,
+        }),
+        _ => None,
+    };
+
+    Ok(ReleaseInfo {
+        title: release.name.unwrap_or_else(|| release.tag_name.clone()),
+        version: release.tag_name,
+        changelog: release.body.unwrap_or_default(),
+        url: release.html_url,
+        download,
+    })
+}
+
+async fn download_asset(client: &reqwest::Client, url: &str) -> anyhow::Result<Vec<u8>> {
+    let content = client
+        .get(url)
+        .header(reqwest::header::USER_AGENT, obfstr!("Valthrun-CHS"))
+        .send()
+        .await
+        .context("request")?
+        .error_for_status()
+        .context("status")?
+        .bytes()
+        .await
+        .context("download")?;
+
+    Ok(content.to_vec())
+}
+
+/// Parse a checksum file as written by `sha256sum` (`<hex digest>  <file name>`)
+fn parse_checksum(content: &str) -> Option<[u8; 32]> {
+    let digest = content.split_whitespace().next()?;
+    if digest.len() != 64 {
+        return None;
+    }
+
+    let mut result = [0u8; 32];
+    for (index, byte) in result.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(digest.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(result)
+}
+
+async fn install_release(release: &ReleaseInfo) -> anyhow::Result<()> {
+    let download = release
+        .download
+        .as_ref()
+        .context("the release does not contain the executable and its checksum")?;
+
+    let executable = std::env::current_exe().context("missing current exe path")?;
+    let download_path = executable.with_extension("update");
+
+    let client = reqwest::Client::new();
+    let checksum = download_asset(&client, &download.checksum_url)
+        .await
+        .context("checksum")?;
+    let checksum = String::from_utf8(checksum)
+        .ok()
+        .as_deref()
+        .and_then(parse_checksum)
+        .context("invalid checksum file")?;
+
+    let content = download_asset(&client, &download.executable_url)
+        .await
+        .context("executable")?;
+
+    if Sha256::digest(&content).as_slice() != checksum {
+        anyhow::bail!("checksum mismatch of the downloaded executable");
+    }
+
+    if !content.starts_with(b"MZ") {
+        anyhow::bail!("the downloaded file is not an executable");
+    }
+
+    std::fs::write(&download_path, &content).context("failed to write the update")?;
+
+    /* Windows allows renaming the running executable, but not overwriting it */
+    let old_executable = old_executable_path(&executable);
+    let _ = std::fs::remove_file(&old_executable);
+    std::fs::rename(&executable, &old_executable)
+        .context("failed to move the current executable")?;
+    if let Err(error) = std::fs::rename(&download_path, &executable) {
+        let _ = std::fs::rename(&old_executable, &executable);
+        return Err(error).context("failed to move the update into place");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        is_newer_version,
+        parse_checksum,
+    };
+
+    #[test]
+    fn compares_versions() {
+        assert!(is_newer_version("v0.4.5", "0.4.4"));
+        assert!(is_newer_version("0.5.0-chs", "0.4.4"));
+        assert!(is_newer_version("v0.4.4.1", "0.4.4"));
+        assert!(!is_newer_version("v0.4.4", "0.4.4"));
+        assert!(!is_newer_version("v0.3.9", "0.4.4"));
+        assert!(!is_newer_version("nightly", "0.4.4"));
+    }
+
+    #[test]
+    fn parses_checksums() {
+        let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let checksum = parse_checksum(&format!("{}  controller.exe\n", digest)).unwrap();
+        assert_eq!(checksum[0], 0xBA);
+        assert_eq!(checksum[31], 0xAD);
+
+        assert_eq!(parse_checksum(digest), Some(checksum));
+        assert_eq!(parse_checksum("ba7816bf"), None);
+        assert_eq!(parse_checksum(""), None);
+        assert_eq!(parse_checksum(&digest.replace('b', "x")), None);
+    }
+}
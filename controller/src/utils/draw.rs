@@ -0,0 +1,192 @@
+use std::f32::consts::TAU;
+
+use imgui::{
+    DrawListMut,
+    ImColor32,
+};
+
+/// Max length (in pixels) of a single segment when approximating curves
+const CURVE_SEGMENT_LENGTH: f32 = 4.0;
+const CURVE_MAX_SEGMENTS: usize = 256;
+
+/// Points on an ellipse from `angle_min` to `angle_max` (in radians).
+/// The angle zero points to the right and positive angles turn clockwise (screen coordinates).
+pub fn ellipse_points(
+    center: [f32; 2],
+    radius: [f32; 2],
+    angle_min: f32,
+    angle_max: f32,
+) -> Vec<[f32; 2]> {
+    let span = angle_max - angle_min;
+    let length = span.abs() * radius[0].max(radius[1]);
+    let segments = ((length / CURVE_SEGMENT_LENGTH).ceil() as usize).clamp(1, CURVE_MAX_SEGMENTS);
+
+    (0..=segments)
+        .map(|index| {
+            let angle = angle_min + span * index as f32 / segments as f32;
+            [
+                center[0] + angle.cos() * radius[0],
+                center[1] + angle.sin() * radius[1],
+            ]
+        })
+        .collect()
+}
+
+/// Split a line into dashes of `dash_length` separated by gaps of `gap_length`
+pub fn dash_segments(
+    start: [f32; 2],
+    end: [f32; 2],
+    dash_length: f32,
+    gap_length: f32,
+) -> Vec<([f32; 2], [f32; 2])> {
+    let delta = [end[0] - start[0], end[1] - start[1]];
+    let length = (delta[0] * delta[0] + delta[1] * delta[1]).sqrt();
+    if length <= 0.0 || dash_length <= 0.0 {
+        return Vec::new();
+    }
+
+    let point_at = |distance: f32| {
+        let factor = distance / length;
+        [start[0] + delta[0] * factor, start[1] + delta[1] * factor]
+    };
+
+    let mut segments = Vec::new();
+    let mut offset = 0.0;
+    while offset < length {
+        let dash_end = (offset + dash_length).min(length);
+        segments.push((point_at(offset), point_at(dash_end)));
+        offset = dash_end + gap_length.max(0.0);
+    }
+
+    segments
+}
+
+/// Additional drawing primitives for the ImGui draw list
+pub trait DrawListEx {
+    /// Fill a convex polygon. The points must be ordered along the outline.
+    fn add_convex_poly_filled(&self, points: Vec<[f32; 2]>, color: impl Into<ImColor32>);
+
+    fn add_dashed_line(
+        &self,
+        start: [f32; 2],
+        end: [f32; 2],
+        color: impl Into<ImColor32>,
+        thickness: f32,
+        dash_length: f32,
+        gap_length: f32,
+    );
+
+    /// Outline of a circle from `angle_min` to `angle_max` (see `ellipse_points`)
+    fn add_arc(
+        &self,
+        center: [f32; 2],
+        radius: f32,
+        angle_min: f32,
+        angle_max: f32,
+        color: impl Into<ImColor32>,
+        thickness: f32,
+    );
+
+    /// Axis aligned ellipse, `radius` contains the horizontal and vertical radius.
+    /// A thickness of zero fills the ellipse.
+    fn add_ellipse(
+        &self,
+        center: [f32; 2],
+        radius: [f32; 2],
+        color: impl Into<ImColor32>,
+        thickness: f32,
+    );
+}
+
+impl DrawListEx for DrawListMut<'_> {
+    fn add_convex_poly_filled(&self, points: Vec<[f32; 2]>, color: impl Into<ImColor32>) {
+        if points.len() < 3 {
+            return;
+        }
+
+        self.add_polyline(points, color).filled(true).build();
+    }
+
+    fn add_dashed_line(
+        &self,
+        start: [f32; 2],
+        end: [f32; 2],
+        color: impl Into<ImColor32>,
+        thickness: f32,
+        dash_length: f32,
+        gap_length: f32,
+    ) {
+        let color = color.into();
+        for (start, end) in dash_segments(start, end, dash_length, gap_length) {
+            self.add_line(start, end, color)
+                .thickness(thickness)
+                .build();
+        }
+    }
+
+    fn add_arc(
+        &self,
+        center: [f32; 2],
+        radius: f32,
+        angle_min: f32,
+        angle_max: f32,
+        color: impl Into<ImColor32>,
+        thickness: f32,
+    ) {
+        let points = ellipse_points(center, [radius, radius], angle_min, angle_max);
+        self.add_polyline(points, color)
+            .thickness(thickness)
+            .build();
+    }
+
+    fn add_ellipse(
+        &self,
+        center: [f32; 2],
+        radius: [f32; 2],
+        color: impl Into<ImColor32>,
+        thickness: f32,
+    ) {
+        /* the last point equals the first one, hence the outline is closed */
+        let points = ellipse_points(center, radius, 0.0, TAU);
+        if thickness <= 0.0 {
+            self.add_convex_poly_filled(points, color);
+        } else {
+            self.add_polyline(points, color)
+                .thickness(thickness)
+                .build();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        dash_segments,
+        ellipse_points,
+    };
+
+    #[test]
+    fn dashes_cover_line() {
+        let segments = dash_segments([0.0, 0.0], [10.0, 0.0], 3.0, 1.0);
+        assert_eq!(
+            segments,
+            vec![
+                ([0.0, 0.0], [3.0, 0.0]),
+                ([4.0, 0.0], [7.0, 0.0]),
+                ([8.0, 0.0], [10.0, 0.0]),
+            ]
+        );
+
+        assert!(dash_segments([1.0, 1.0], [1.0, 1.0], 3.0, 1.0).is_empty());
+    }
+
+    #[test]
+    fn ellipse_points_span_angles() {
+        let points = ellipse_points([10.0, 10.0], [5.0, 2.0], 0.0, std::f32::consts::PI);
+        let first = points.first().unwrap();
+        let last = points.last().unwrap();
+
+        assert!((first[0] - 15.0).abs() < 0.001 && (first[1] - 10.0).abs() < 0.001);
+        assert!((last[0] - 5.0).abs() < 0.001 && (last[1] - 10.0).abs() < 0.001);
+    }
+}
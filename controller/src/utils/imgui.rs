@@ -1,6 +1,11 @@
 use std::borrow::Cow;
 
-use crate::settings::HotKey;
+use imgui::ImColor32;
+
+use crate::{
+    i18n,
+    settings::HotKey,
+};
 
 pub trait ImguiUiEx {
     fn set_cursor_pos_x(&self, pos: f32);
@@ -17,6 +22,135 @@ impl ImguiUiEx for imgui::Ui {
     }
 }
 
+/// Text rendering with an explicit font size.
+/// The current font will be used, but the window font scale will be ignored.
+pub trait ImguiTextEx {
+    fn calc_text_size_with_font_size(&self, text: &str, font_size: f32) -> [f32; 2];
+    fn add_text_with_font_size(
+        &self,
+        position: [f32; 2],
+        color: ImColor32,
+        text: &str,
+        font_size: f32,
+    );
+
+    /// Text surrounded by an outline of `thickness` pixels, readable on any background.
+    /// The outline is rendered by drawing the text shifted into all eight directions.
+    fn add_text_outlined(
+        &self,
+        position: [f32; 2],
+        color: ImColor32,
+        outline_color: ImColor32,
+        text: &str,
+        font_size: f32,
+        thickness: f32,
+    );
+
+    /// Text with a drop shadow offset by `offset` pixels to the bottom right
+    fn add_text_shadowed(
+        &self,
+        position: [f32; 2],
+        color: ImColor32,
+        shadow_color: ImColor32,
+        text: &str,
+        font_size: f32,
+        offset: f32,
+    );
+}
+
+impl ImguiTextEx for imgui::Ui {
+    fn calc_text_size_with_font_size(&self, text: &str, font_size: f32) -> [f32; 2] {
+        let mut result = imgui::sys::ImVec2::zero();
+        unsafe {
+            imgui::sys::ImFont_CalcTextSizeA(
+                &mut result,
+                imgui::sys::igGetFont(),
+                font_size,
+                f32::MAX,
+                0.0,
+                text.as_ptr() as *const _,
+                text.as_ptr().add(text.len()) as *const _,
+                std::ptr::null_mut(),
+            );
+        }
+
+        [result.x, result.y]
+    }
+
+    fn add_text_with_font_size(
+        &self,
+        position: [f32; 2],
+        color: ImColor32,
+        text: &str,
+        font_size: f32,
+    ) {
+        unsafe {
+            imgui::sys::ImDrawList_AddText_FontPtr(
+                imgui::sys::igGetWindowDrawList(),
+                imgui::sys::igGetFont(),
+                font_size,
+                position.into(),
+                color.into(),
+                text.as_ptr() as *const _,
+                text.as_ptr().add(text.len()) as *const _,
+                0.0,
+                std::ptr::null(),
+            );
+        }
+    }
+
+    fn add_text_outlined(
+        &self,
+        position: [f32; 2],
+        color: ImColor32,
+        outline_color: ImColor32,
+        text: &str,
+        font_size: f32,
+        thickness: f32,
+    ) {
+        for [direction_x, direction_y] in [
+            [-1.0, -1.0],
+            [0.0, -1.0],
+            [1.0, -1.0],
+            [-1.0, 0.0],
+            [1.0, 0.0],
+            [-1.0, 1.0],
+            [0.0, 1.0],
+            [1.0, 1.0],
+        ] {
+            self.add_text_with_font_size(
+                [
+                    position[0] + direction_x * thickness,
+                    position[1] + direction_y * thickness,
+                ],
+                outline_color,
+                text,
+                font_size,
+            );
+        }
+
+        self.add_text_with_font_size(position, color, text, font_size);
+    }
+
+    fn add_text_shadowed(
+        &self,
+        position: [f32; 2],
+        color: ImColor32,
+        shadow_color: ImColor32,
+        text: &str,
+        font_size: f32,
+        offset: f32,
+    ) {
+        self.add_text_with_font_size(
+            [position[0] + offset, position[1] + offset],
+            shadow_color,
+            text,
+            font_size,
+        );
+        self.add_text_with_font_size(position, color, text, font_size);
+    }
+}
+
 pub trait ImGuiKey {
     fn button_key(&self, label: &str, key: &mut HotKey, size: [f32; 2]) -> bool;
     fn button_key_optional(&self, label: &str, key: &mut Option<HotKey>, size: [f32; 2]) -> bool;
@@ -60,9 +194,10 @@ impl ImguiComboEnum for imgui::Ui {
             .unwrap_or_default();
 
         fn display_name<'a, T>(entry: &'a (T, &'static str)) -> Cow<'a, str> {
-            entry.1.into()
+            i18n::translate(entry.1)
         }
 
+        let label = i18n::translate(label.as_ref());
         if self.combo(label, &mut type_index, values, &display_name) {
             *value = values[type_index].0;
             true
@@ -75,7 +210,44 @@ impl ImguiComboEnum for imgui::Ui {
 mod hotkey {
     use imgui::Key;
 
-    use crate::settings::HotKey;
+    use crate::{
+        i18n::{
+            self,
+            tr,
+        },
+        settings::{
+            HotKey,
+            HotKeyModifier,
+        },
+    };
+
+    /// Keys which can be held down as part of a key combination
+    fn is_combination_key(key: Key) -> bool {
+        let name = format!("{:?}", key);
+        !name.contains("Mod") && !name.starts_with("MouseWheel")
+    }
+
+    /// Create a hotkey for the pressed key including all held down modifiers and keys
+    fn capture_hotkey(ui: &imgui::Ui, pressed_key: Key) -> HotKey {
+        let mut hotkey = HotKey::new(pressed_key);
+        for key in Key::VARIANTS {
+            if key == pressed_key || !is_combination_key(key) || !ui.is_key_down(key) {
+                continue;
+            }
+
+            match HotKeyModifier::from_key(key) {
+                Some(modifier) => {
+                    if !hotkey.modifiers.contains(&modifier) {
+                        hotkey.modifiers.push(modifier);
+                    }
+                }
+                None => hotkey.chord.push(key),
+            }
+        }
+
+        hotkey.modifiers.sort();
+        hotkey
+    }
 
     pub fn render_button_key(
         ui: &imgui::Ui,
@@ -87,13 +259,13 @@ mod hotkey {
         let _container = ui.push_id(label);
 
         let button_label = if let Some(key) = &key {
-            format!("{:?}", key.0)
+            key.to_string()
         } else {
             "None".to_string()
         };
 
         if !label.starts_with("##") {
-            ui.text(label);
+            ui.text(i18n::translate(label));
             ui.same_line();
         }
 
@@ -125,16 +297,32 @@ mod hotkey {
             .resizable(false)
             .title_bar(false)
             .build(|| {
-                ui.text("Press any key or ESC to exit");
+                ui.text(tr!(
+                    "按下任意键、手柄按键或组合键 (例如 Ctrl + F)，按 ESC 退出"
+                ));
 
                 if ui.is_key_pressed(Key::Escape) {
                     ui.close_current_popup();
                 } else {
                     for key_variant in Key::VARIANTS {
-                        if ui.is_key_pressed(key_variant) {
-                            *key = Some(HotKey(key_variant));
+                        if !is_combination_key(key_variant) {
+                            continue;
+                        }
+
+                        let captured = if HotKeyModifier::from_key(key_variant).is_some() {
+                            /* modifiers on their own are bound once released */
+                            ui.is_key_released(key_variant)
+                                .then(|| capture_hotkey(ui, key_variant))
+                        } else {
+                            ui.is_key_pressed(key_variant)
+                                .then(|| capture_hotkey(ui, key_variant))
+                        };
+
+                        if let Some(hotkey) = captured {
+                            *key = Some(hotkey);
                             updated = true;
                             ui.close_current_popup();
+                            break;
                         }
                     }
                 }
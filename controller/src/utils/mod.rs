@@ -1,3 +1,4 @@
+mod draw;
 mod imgui;
 use std::ffi::CString;
 
@@ -12,7 +13,10 @@ use windows::{
     },
 };
 
-pub use self::imgui::*;
+pub use self::{
+    draw::*,
+    imgui::*,
+};
 
 pub fn open_url(url: &str) {
     unsafe {
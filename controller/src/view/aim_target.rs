@@ -0,0 +1,157 @@
+use cs2::{
+    CEntityIdentityEx,
+    CS2Model,
+    ClassNameCache,
+    EntitySystem,
+    LocalCameraControllerTarget,
+    PlayerPawnState,
+};
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use super::ViewController;
+use crate::settings::{
+    AppSettings,
+    TargetSelectionPolicy,
+};
+
+/// Player selected as target for the aim features
+#[derive(Debug, Clone)]
+pub struct AimTargetInfo {
+    pub entity_id: u32,
+
+    pub position: nalgebra::Vector3<f32>,
+    pub model_address: u64,
+
+    /// Screen position of the targets center
+    pub screen_position: mint::Vector2<f32>,
+}
+
+struct TargetCandidate {
+    info: AimTargetInfo,
+
+    /// Distance to the crosshair in pixels
+    crosshair_distance: f32,
+
+    /// Distance to the camera in world units
+    distance: f32,
+
+    health: i32,
+
+    /// Yaw difference (in degrees) between the view direction of the target and the direction to the camera
+    view_angle_to_camera: f32,
+}
+
+impl TargetCandidate {
+    fn score(&self, policy: TargetSelectionPolicy) -> f32 {
+        /* lower is better */
+        match policy {
+            TargetSelectionPolicy::ClosestToCrosshair => self.crosshair_distance,
+            TargetSelectionPolicy::LowestHealth => self.health as f32,
+            TargetSelectionPolicy::ClosestDistance => self.distance,
+            TargetSelectionPolicy::HighestThreat => self.view_angle_to_camera,
+        }
+    }
+}
+
+/// The current target of the aim features (trigger bot, target indicator, ...).
+/// Only enemies within the aim FOV are considered.
+pub struct AimTarget {
+    pub target: Option<AimTargetInfo>,
+}
+
+impl State for AimTarget {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let settings = states.resolve::<AppSettings>(())?;
+        let view = states.resolve::<ViewController>(())?;
+        let entities = states.resolve::<EntitySystem>(())?;
+        let class_name_cache = states.resolve::<ClassNameCache>(())?;
+
+        let view_world_position = match view.get_camera_world_position() {
+            Some(position) => position,
+            None => return Ok(Self { target: None }),
+        };
+
+        let local_controller = entities.get_local_player_controller()?;
+        if local_controller.is_null()? {
+            return Ok(Self { target: None });
+        }
+        let local_team_id = local_controller.reference_schema()?.m_iPendingTeamNum()?;
+
+        let view_target = states.resolve::<LocalCameraControllerTarget>(())?;
+        let screen_center =
+            nalgebra::Vector2::new(view.screen_bounds.x / 2.0, view.screen_bounds.y / 2.0);
+        let fov_radius = view.fov_radius(settings.aim_fov);
+
+        let mut candidates = Vec::new();
+        for entity_identity in entities.all_identities() {
+            let entity_id = entity_identity.handle::<()>()?.get_entity_index();
+            if view_target.target_entity_id == Some(entity_id) {
+                continue;
+            }
+
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            if !entity_class
+                .map(|name| *name == "C_CSPlayerPawn")
+                .unwrap_or(false)
+            {
+                /* entity is not a player pawn */
+                continue;
+            }
+
+            let pawn_state = states.resolve::<PlayerPawnState>(entity_id)?;
+            let pawn = match &*pawn_state {
+                PlayerPawnState::Alive(pawn) if pawn.team_id != local_team_id => pawn,
+                _ => continue,
+            };
+
+            let model = states.resolve::<CS2Model>(pawn.model_address)?;
+            let center = pawn.position + (model.vhull_min + model.vhull_max) / 2.0;
+            let screen_position = match view.world_to_screen(&center, false) {
+                Some(position) => position,
+                None => continue,
+            };
+
+            let crosshair_distance = (nalgebra::Vector2::new(screen_position.x, screen_position.y)
+                - screen_center)
+                .norm();
+            if crosshair_distance > fov_radius {
+                continue;
+            }
+
+            let delta = view_world_position - pawn.position;
+            let yaw_to_camera = delta.y.atan2(delta.x).to_degrees();
+            let yaw_difference = (yaw_to_camera - pawn.rotation).rem_euclid(360.0);
+
+            candidates.push(TargetCandidate {
+                info: AimTargetInfo {
+                    entity_id,
+                    position: pawn.position,
+                    model_address: pawn.model_address,
+                    screen_position,
+                },
+                crosshair_distance,
+                distance: delta.norm(),
+                health: pawn.player_health,
+                view_angle_to_camera: yaw_difference.min(360.0 - yaw_difference),
+            });
+        }
+
+        let policy = settings.aim_target_policy;
+        let target = candidates
+            .into_iter()
+            .min_by(|a, b| a.score(policy).total_cmp(&b.score(policy)))
+            .map(|candidate| candidate.info);
+
+        Ok(Self { target })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
@@ -33,9 +33,7 @@ impl State for LocalCrosshair {
     type Parameter = ();
 
     fn create(_states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
-        Ok(Self {
-            current_target: None,
-        })
+        Ok(Self::new(None))
     }
 
     fn cache_type() -> StateCacheType {
@@ -80,6 +78,10 @@ impl State for LocalCrosshair {
 }
 
 impl LocalCrosshair {
+    pub fn new(current_target: Option<CrosshairTarget>) -> Self {
+        Self { current_target }
+    }
+
     pub fn current_target(&self) -> Option<&CrosshairTarget> {
         self.current_target.as_ref()
     }
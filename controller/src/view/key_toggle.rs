@@ -1,16 +1,100 @@
-use std::time::Instant;
+use std::time::{
+    Duration,
+    Instant,
+};
 
 use crate::{
     settings::{
+        AppSettings,
         HotKey,
         KeyToggleMode,
     },
     KeyboardInput,
 };
 
+/// Presses closer together than this are considered to be repeated reports of the same press.
+/// Sometimes is_key_pressed with repeating set to false still triggers a few times.
+const PRESS_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Timings for the activation semantics of hotkey bindings
+#[derive(Clone, Copy, Debug)]
+pub struct KeyToggleTimings {
+    /// Minimum hold duration for `TriggerHold`
+    pub hold_duration: Duration,
+
+    /// Maximum time between both presses for `DoubleTap`
+    pub double_tap_interval: Duration,
+}
+
+impl KeyToggleTimings {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            hold_duration: Duration::from_millis(settings.hotkey_hold_duration as u64),
+            double_tap_interval: Duration::from_millis(settings.hotkey_double_tap_interval as u64),
+        }
+    }
+}
+
+/// Press history of a single hotkey binding
+#[derive(Default)]
+struct KeyStateTracker {
+    /// Since when the hotkey is held down
+    down_since: Option<Instant>,
+
+    /// Last accepted press
+    last_press: Option<Instant>,
+
+    /// Last press which has not yet been consumed by a double tap
+    pending_tap: Option<Instant>,
+}
+
+impl KeyStateTracker {
+    /// Update the tracker and return if the hotkey has been freshly pressed
+    fn update(&mut self, now: Instant, down: bool, pressed: bool) -> bool {
+        /* the press itself may have happened within a frame without an update */
+        let pressed = pressed || (down && self.down_since.is_none());
+        if down {
+            self.down_since.get_or_insert(now);
+        } else {
+            self.down_since = None;
+        }
+
+        if !pressed {
+            return false;
+        }
+
+        if self
+            .last_press
+            .map_or(false, |last| now.duration_since(last) < PRESS_DEBOUNCE)
+        {
+            return false;
+        }
+
+        self.last_press = Some(now);
+        true
+    }
+
+    fn held_for(&self, now: Instant) -> Option<Duration> {
+        self.down_since.map(|since| now.duration_since(since))
+    }
+
+    /// Register a fresh press and return true if it completes a double tap
+    fn register_tap(&mut self, now: Instant, interval: Duration) -> bool {
+        match self.pending_tap.take() {
+            Some(first) if now.duration_since(first) <= interval => true,
+            _ => {
+                self.pending_tap = Some(now);
+                false
+            }
+        }
+    }
+}
+
 pub struct KeyToggle {
     pub last_state_changed: Instant,
     pub enabled: bool,
+
+    tracker: KeyStateTracker,
 }
 
 impl KeyToggle {
@@ -18,6 +102,8 @@ impl KeyToggle {
         Self {
             enabled: false,
             last_state_changed: Instant::now(),
+
+            tracker: Default::default(),
         }
     }
 
@@ -25,32 +111,60 @@ impl KeyToggle {
         &mut self,
         mode: &KeyToggleMode,
         input: &dyn KeyboardInput,
-        hotkey: &Option<HotKey>,
+        hotkey: Option<&HotKey>,
+        timings: &KeyToggleTimings,
+    ) -> bool {
+        let (down, pressed) = match hotkey {
+            Some(hotkey) => (hotkey.is_down(input), hotkey.is_pressed(input, false)),
+            None => (false, false),
+        };
+
+        self.update_state(
+            mode,
+            hotkey.is_some(),
+            down,
+            pressed,
+            timings,
+            Instant::now(),
+        )
+    }
+
+    fn update_state(
+        &mut self,
+        mode: &KeyToggleMode,
+        has_hotkey: bool,
+        down: bool,
+        pressed: bool,
+        timings: &KeyToggleTimings,
+        now: Instant,
     ) -> bool {
+        let pressed = self.tracker.update(now, down, pressed);
         let new_state = match mode {
             KeyToggleMode::AlwaysOn => true,
-            KeyToggleMode::Trigger | KeyToggleMode::TriggerInverted => {
-                if let Some(hotkey) = hotkey {
-                    input.is_key_down(hotkey.0) == (*mode == KeyToggleMode::Trigger)
-                } else {
+            KeyToggleMode::Trigger => down,
+            KeyToggleMode::TriggerInverted => has_hotkey && !down,
+            KeyToggleMode::TriggerHold => self
+                .tracker
+                .held_for(now)
+                .map_or(false, |held| held >= timings.hold_duration),
+            KeyToggleMode::Toggle => {
+                if !has_hotkey {
                     false
+                } else if pressed && now.duration_since(self.last_state_changed).as_millis() > 250 {
+                    self.last_state_changed = now;
+                    !self.enabled
+                } else {
+                    self.enabled
                 }
             }
-            KeyToggleMode::Toggle => {
-                if let Some(hotkey) = hotkey {
-                    if input.is_key_pressed(hotkey.0, false) {
-                        if self.last_state_changed.elapsed().as_millis() > 250 {
-                            self.last_state_changed = Instant::now();
-                            !self.enabled
-                        } else {
-                            /* sometimes is_key_pressed with repeating set to false still triggers a few times */
-                            self.enabled
-                        }
-                    } else {
-                        self.enabled
-                    }
-                } else {
+            KeyToggleMode::DoubleTap => {
+                if !has_hotkey {
                     false
+                } else if pressed && self.tracker.register_tap(now, timings.double_tap_interval) {
+                    self.last_state_changed = now;
+                    !self.enabled
+                } else {
+                    self.enabled
                 }
             }
             KeyToggleMode::Off => false,
@@ -65,3 +179,77 @@ impl KeyToggle {
         true
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::{
+        Duration,
+        Instant,
+    };
+
+    use super::{
+        KeyToggle,
+        KeyToggleTimings,
+    };
+    use crate::settings::KeyToggleMode;
+
+    const TIMINGS: KeyToggleTimings = KeyToggleTimings {
+        hold_duration: Duration::from_millis(200),
+        double_tap_interval: Duration::from_millis(300),
+    };
+
+    #[test]
+    fn double_tap() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut toggle = KeyToggle::new();
+        let mode = KeyToggleMode::DoubleTap;
+
+        /* single press and repeated reports of the same press */
+        toggle.update_state(&mode, true, true, true, &TIMINGS, at(0));
+        toggle.update_state(&mode, true, true, true, &TIMINGS, at(10));
+        assert!(!toggle.enabled);
+
+        /* too slow */
+        toggle.update_state(&mode, true, true, true, &TIMINGS, at(500));
+        assert!(!toggle.enabled);
+
+        toggle.update_state(&mode, true, true, true, &TIMINGS, at(700));
+        assert!(toggle.enabled);
+    }
+
+    #[test]
+    fn toggle_press_between_updates() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut toggle = KeyToggle::new();
+        let mode = KeyToggleMode::Toggle;
+
+        /* the frame reporting the press has not been updated */
+        toggle.update_state(&mode, true, false, false, &TIMINGS, at(0));
+        toggle.last_state_changed = at(0);
+        toggle.update_state(&mode, true, true, false, &TIMINGS, at(300));
+        assert!(toggle.enabled);
+
+        toggle.update_state(&mode, true, true, false, &TIMINGS, at(600));
+        assert!(toggle.enabled);
+    }
+
+    #[test]
+    fn hold_min_duration() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut toggle = KeyToggle::new();
+        let mode = KeyToggleMode::TriggerHold;
+
+        toggle.update_state(&mode, true, true, true, &TIMINGS, at(0));
+        toggle.update_state(&mode, true, true, false, &TIMINGS, at(150));
+        assert!(!toggle.enabled);
+
+        toggle.update_state(&mode, true, true, false, &TIMINGS, at(250));
+        assert!(toggle.enabled);
+
+        toggle.update_state(&mode, true, false, false, &TIMINGS, at(300));
+        assert!(!toggle.enabled);
+    }
+}
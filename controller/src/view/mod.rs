@@ -6,3 +6,6 @@ pub use crosshair::*;
 
 mod key_toggle;
 pub use key_toggle::*;
+
+mod aim_target;
+pub use aim_target::*;
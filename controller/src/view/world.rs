@@ -9,6 +9,11 @@ use utils_state::{
     StateRegistry,
 };
 
+use crate::utils::DrawListEx;
+
+/// Segments used to approximate a full circle in world space
+const WORLD_CIRCLE_SEGMENTS: usize = 48;
+
 /// View controller which helps resolve in game
 /// coordinates into 2d screen coordinates.
 pub struct ViewController {
@@ -40,6 +45,8 @@ impl State for ViewController {
 }
 
 impl ViewController {
+    /// Update the size of the game viewport in logical pixels.
+    /// Must be called every frame as the overlay follows the game across monitors with different resolutions and DPI.
     pub fn update_screen_bounds(&mut self, bounds: mint::Vector2<f32>) {
         self.screen_bounds = bounds;
     }
@@ -69,6 +76,14 @@ impl ViewController {
         Some(nalgebra::Vector3::new(-x, -y, -z))
     }
 
+    /// Radius (in pixels) of a circle around the screen center which covers
+    /// all directions within the given angle (in degrees) of the view direction.
+    pub fn fov_radius(&self, fov: f32) -> f32 {
+        /* the length of the first column is the horizontal projection scale */
+        let projection_scale = self.view_matrix.fixed_view::<3, 1>(0, 0).norm();
+        fov.to_radians().tan() * projection_scale * self.screen_bounds.x / 2.0
+    }
+
     /// Returning an mint::Vector2<f32> as the result should be used via ImGui.
     pub fn world_to_screen(
         &self,
@@ -153,6 +168,19 @@ impl ViewController {
         color: ImColor32,
         thickness: f32,
     ) {
+        for (start, end) in self.box_3d_lines(vmin, vmax) {
+            draw.add_line(start, end, color)
+                .thickness(thickness)
+                .build();
+        }
+    }
+
+    /// Screen space edges of the box. Edges with a corner behind the camera are skipped.
+    pub fn box_3d_lines(
+        &self,
+        vmin: &nalgebra::Vector3<f32>,
+        vmax: &nalgebra::Vector3<f32>,
+    ) -> Vec<(mint::Vector2<f32>, mint::Vector2<f32>)> {
         type Vec3 = nalgebra::Vector3<f32>;
 
         let lines = [
@@ -209,15 +237,102 @@ impl ViewController {
             ),
         ];
 
-        for (start, end) in lines {
-            if let (Some(start), Some(end)) = (
-                self.world_to_screen(&start, true),
-                self.world_to_screen(&end, true),
-            ) {
-                draw.add_line(start, end, color)
+        lines
+            .iter()
+            .filter_map(|(start, end)| {
+                Some((
+                    self.world_to_screen(start, true)?,
+                    self.world_to_screen(end, true)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Project points on a horizontal arc around `center` from `yaw_min` to `yaw_max` (in degrees).
+    /// Points behind the camera are `None`.
+    fn world_arc_points(
+        &self,
+        center: &nalgebra::Vector3<f32>,
+        radius: f32,
+        yaw_min: f32,
+        yaw_max: f32,
+    ) -> Vec<Option<[f32; 2]>> {
+        let span = yaw_max - yaw_min;
+        let segments = ((WORLD_CIRCLE_SEGMENTS as f32 * span.abs() / 360.0).ceil() as usize).max(1);
+
+        (0..=segments)
+            .map(|index| {
+                let yaw = (yaw_min + span * index as f32 / segments as f32).to_radians();
+                let point = center + nalgebra::Vector3::new(yaw.cos(), yaw.sin(), 0.0) * radius;
+                self.world_to_screen(&point, true)
+                    .map(|point| [point.x, point.y])
+            })
+            .collect()
+    }
+
+    /// Draw the projected points as outline, or fill them if the thickness is zero.
+    /// Filling requires all points to be in front of the camera.
+    fn draw_projected_outline(
+        &self,
+        draw: &imgui::DrawListMut,
+        points: Vec<Option<[f32; 2]>>,
+        color: ImColor32,
+        thickness: f32,
+    ) {
+        if thickness <= 0.0 {
+            if let Some(points) = points.into_iter().collect::<Option<Vec<_>>>() {
+                draw.add_convex_poly_filled(points, color);
+            }
+            return;
+        }
+
+        for pair in points.windows(2) {
+            if let [Some(start), Some(end)] = pair {
+                draw.add_line(*start, *end, color)
                     .thickness(thickness)
                     .build();
             }
         }
     }
+
+    /// Draw a horizontal circle in world space (e.g. the radius of a smoke or molotov).
+    /// A thickness of zero fills the circle.
+    pub fn draw_world_circle(
+        &self,
+        draw: &imgui::DrawListMut,
+        center: &nalgebra::Vector3<f32>,
+        radius: f32,
+        color: ImColor32,
+        thickness: f32,
+    ) {
+        let points = self.world_arc_points(center, radius, 0.0, 360.0);
+        self.draw_projected_outline(draw, points, color, thickness);
+    }
+
+    /// Draw a horizontal circle sector in world space (e.g. the view cone of a player).
+    /// The sector is centered around `yaw` and spans `fov` degrees.
+    /// A thickness of zero fills the sector, which only works for sectors up to 180 degrees.
+    pub fn draw_world_sector(
+        &self,
+        draw: &imgui::DrawListMut,
+        origin: &nalgebra::Vector3<f32>,
+        radius: f32,
+        yaw: f32,
+        fov: f32,
+        color: ImColor32,
+        thickness: f32,
+    ) {
+        let origin_screen = self
+            .world_to_screen(origin, true)
+            .map(|point| [point.x, point.y]);
+
+        let mut points = Vec::with_capacity(WORLD_CIRCLE_SEGMENTS + 3);
+        points.push(origin_screen);
+        points.extend(self.world_arc_points(origin, radius, yaw - fov / 2.0, yaw + fov / 2.0));
+        if thickness > 0.0 {
+            points.push(origin_screen);
+        }
+
+        self.draw_projected_outline(draw, points, color, thickness);
+    }
 }
@@ -1,14 +1,27 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
 
+use anyhow::Context;
 use cs2_schema_declaration::{
     define_schema,
     Ptr,
     PtrCStr,
 };
 use obfstr::obfstr;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
 
 use crate::{
     CS2Handle,
+    CS2HandleState,
     Module,
     Signature,
 };
@@ -31,6 +44,8 @@ define_schema! {
         pub fl_value: f32 = 0x40,
         pub fl_value_min: f32 = 0x48,
         pub fl_value_default: f32 = 0x50,
+
+        pub sz_value: PtrCStr = 0x40,
     }
 
     pub struct CCVarEntry[0x10] {
@@ -82,3 +97,140 @@ impl ConVars {
         Ok(None)
     }
 }
+
+impl State for ConVars {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let cs2 = states.resolve::<CS2HandleState>(())?;
+        Self::new(cs2.handle().clone())
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+/// Default field of view of the local player when not zoomed
+pub const DEFAULT_FOV: f32 = 90.0;
+
+/// Mouse sensitivity settings of the local client
+#[derive(Debug, Clone)]
+pub struct MouseSensitivity {
+    pub sensitivity: f32,
+    pub zoom_sensitivity_ratio: f32,
+    pub m_yaw: f32,
+
+    last_update: Instant,
+}
+
+/// Interval in which the sensitivity cvars will be read again
+const MOUSE_SENSITIVITY_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+
+impl MouseSensitivity {
+    fn read_cvars(&mut self, cvars: &ConVars) -> anyhow::Result<()> {
+        let sensitivity = cvars
+            .find_cvar(obfstr!("sensitivity"))?
+            .with_context(|| obfstr!("missing sensitivity cvar").to_string())?
+            .fl_value()?;
+
+        let zoom_sensitivity_ratio = match cvars.find_cvar(obfstr!("zoom_sensitivity_ratio"))? {
+            Some(cvar) => cvar.fl_value()?,
+            None => 1.0,
+        };
+
+        let m_yaw = match cvars.find_cvar(obfstr!("m_yaw"))? {
+            Some(cvar) => cvar.fl_value()?,
+            None => 0.022,
+        };
+
+        if sensitivity <= 0.0 || m_yaw <= 0.0 {
+            anyhow::bail!("invalid sensitivity ({}) or m_yaw ({})", sensitivity, m_yaw);
+        }
+
+        if zoom_sensitivity_ratio <= 0.0 {
+            anyhow::bail!(
+                "invalid zoom_sensitivity_ratio ({})",
+                zoom_sensitivity_ratio
+            );
+        }
+
+        self.sensitivity = sensitivity;
+        self.zoom_sensitivity_ratio = zoom_sensitivity_ratio;
+        self.m_yaw = m_yaw;
+        self.last_update = Instant::now();
+        Ok(())
+    }
+
+    /// Mouse counts required to rotate the view by one degree.
+    /// When zoomed the current fov must be provided as it scales the effective sensitivity.
+    pub fn counts_per_degree(&self, zoom_fov: Option<f32>) -> f32 {
+        let zoom_scale = match zoom_fov {
+            Some(fov) if fov > 0.0 && fov < DEFAULT_FOV => {
+                self.zoom_sensitivity_ratio * fov / DEFAULT_FOV
+            }
+            _ => 1.0,
+        };
+
+        1.0 / (self.sensitivity * self.m_yaw * zoom_scale)
+    }
+}
+
+impl State for MouseSensitivity {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let cvars = states.resolve::<ConVars>(())?;
+
+        let mut result = Self {
+            sensitivity: 1.0,
+            zoom_sensitivity_ratio: 1.0,
+            m_yaw: 0.022,
+
+            last_update: Instant::now(),
+        };
+        result.read_cvars(&cvars)?;
+        Ok(result)
+    }
+
+    fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+        if self.last_update.elapsed() < MOUSE_SENSITIVITY_UPDATE_INTERVAL {
+            return Ok(());
+        }
+
+        /* looking up cvars is expensive, therefore we only do it periodically */
+        let cvars = states.resolve::<ConVars>(())?;
+        self.read_cvars(&cvars)
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use super::MouseSensitivity;
+
+    #[test]
+    fn counts_per_degree_zoomed() {
+        let sensitivity = MouseSensitivity {
+            sensitivity: 2.0,
+            zoom_sensitivity_ratio: 1.0,
+            m_yaw: 0.022,
+
+            last_update: Instant::now(),
+        };
+
+        let unzoomed = sensitivity.counts_per_degree(None);
+        assert!((unzoomed - 1.0 / 0.044).abs() < 0.001);
+        assert_eq!(sensitivity.counts_per_degree(Some(90.0)), unzoomed);
+
+        /* zooming to half the fov halves the effective sensitivity */
+        let zoomed = sensitivity.counts_per_degree(Some(45.0));
+        assert!(zoomed.is_finite());
+        assert!((zoomed - unzoomed * 2.0).abs() < 0.001);
+    }
+}
@@ -2,13 +2,21 @@
 
 use std::{
     any::Any,
+    collections::VecDeque,
     ffi::CStr,
     fmt::Debug,
     ops::Deref,
     sync::{
+        atomic::{
+            AtomicBool,
+            AtomicUsize,
+            Ordering,
+        },
         Arc,
+        Mutex,
         Weak,
     },
+    time::SystemTime,
 };
 
 use anyhow::Context;
@@ -77,15 +85,30 @@ impl Module {
     }
 }
 
+/// Amount of sent metrics records which are kept for inspection
+const METRICS_HISTORY_SIZE: usize = 50;
+
+/// A metrics record which has been sent
+#[derive(Debug, Clone)]
+pub struct MetricsRecord {
+    pub timestamp: SystemTime,
+    pub record_type: String,
+    pub payload: String,
+}
+
 /// Handle to the CS2 process
 pub struct CS2Handle {
     weak_self: Weak<Self>,
-    metrics: bool,
+    metrics: AtomicBool,
+    metrics_history: Mutex<VecDeque<MetricsRecord>>,
 
     modules: Vec<ModuleInfo>,
     process_id: i32,
 
     pub ke_interface: KernelInterface,
+
+    /// Total amount of memory reads which failed
+    failed_read_calls: AtomicUsize,
 }
 
 impl CS2Handle {
@@ -122,11 +145,13 @@ impl CS2Handle {
 
         Ok(Arc::new_cyclic(|weak_self| Self {
             weak_self: weak_self.clone(),
-            metrics,
+            metrics: AtomicBool::new(metrics),
+            metrics_history: Default::default(),
             modules,
             process_id,
 
             ke_interface: interface,
+            failed_read_calls: AtomicUsize::new(0),
         }))
     }
 
@@ -151,7 +176,7 @@ impl CS2Handle {
     }
 
     pub fn add_metrics_record(&self, record_type: &str, record_payload: &str) {
-        if !self.metrics {
+        if !self.metrics.load(Ordering::Relaxed) {
             /* user opted out */
             return;
         }
@@ -159,6 +184,35 @@ impl CS2Handle {
         let _ = self
             .ke_interface
             .add_metrics_record(record_type, record_payload);
+
+        let mut history = self.metrics_history.lock().unwrap();
+        if history.len() >= METRICS_HISTORY_SIZE {
+            history.pop_front();
+        }
+        history.push_back(MetricsRecord {
+            timestamp: SystemTime::now(),
+            record_type: record_type.to_string(),
+            payload: record_payload.to_string(),
+        });
+    }
+
+    pub fn metrics_enabled(&self) -> bool {
+        self.metrics.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable sending metrics records
+    pub fn set_metrics_enabled(&self, enabled: bool) {
+        self.metrics.store(enabled, Ordering::Relaxed);
+    }
+
+    /// The most recent metrics records which have been sent (oldest first)
+    pub fn metrics_history(&self) -> Vec<MetricsRecord> {
+        self.metrics_history
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
     }
 
     pub fn module_address(&self, module: Module, address: u64) -> Option<u64> {
@@ -180,14 +234,28 @@ impl CS2Handle {
             + offset)
     }
 
+    pub fn total_failed_read_calls(&self) -> usize {
+        self.failed_read_calls.load(Ordering::Relaxed)
+    }
+
     pub fn read_sized<T: Copy>(&self, offsets: &[u64]) -> anyhow::Result<T> {
-        Ok(self.ke_interface.read(self.process_id, offsets)?)
+        let result = self.ke_interface.read(self.process_id, offsets);
+        if result.is_err() {
+            self.failed_read_calls.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(result?)
     }
 
     pub fn read_slice<T: Copy>(&self, offsets: &[u64], buffer: &mut [T]) -> anyhow::Result<()> {
-        Ok(self
+        let result = self
             .ke_interface
-            .read_slice(self.process_id, offsets, buffer)?)
+            .read_slice(self.process_id, offsets, buffer);
+        if result.is_err() {
+            self.failed_read_calls.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(result?)
     }
 
     pub fn read_string(
@@ -19,6 +19,10 @@ define_schema! {
     pub struct CNetworkGameClient[0x290] {
         pub map_path: PtrCStr = 0x240,
         pub map_name: PtrCStr = 0x248,
+
+        /// Address of the server as passed to the connect command
+        /// (ip:port or a steam datagram relay address)
+        pub server_address: PtrCStr = 0x258,
     }
 }
 
@@ -25,7 +25,7 @@ pub struct BombDefuser {
     pub player_name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum PlantedC4State {
     /// Bomb is currently actively ticking
     Active {
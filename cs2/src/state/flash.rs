@@ -0,0 +1,80 @@
+use anyhow::Context;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::{
+    EntitySystem,
+    Globals,
+};
+
+/// Flashbang effect of the local player
+pub struct LocalPlayerFlash {
+    /// Remaining blind time in seconds
+    pub remaining_time: f32,
+
+    /// Total duration of the current flash in seconds
+    pub duration: f32,
+}
+
+impl LocalPlayerFlash {
+    pub fn is_flashed(&self) -> bool {
+        self.remaining_time > 0.0
+    }
+
+    /// Remaining portion of the flash in [0.0;1.0]
+    pub fn remaining_fraction(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.remaining_time / self.duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl State for LocalPlayerFlash {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let not_flashed = Self {
+            remaining_time: 0.0,
+            duration: 0.0,
+        };
+
+        let entities = states.resolve::<EntitySystem>(())?;
+        let local_controller = entities.get_local_player_controller()?;
+        if local_controller.is_null()? {
+            return Ok(not_flashed);
+        }
+
+        let pawn_handle = local_controller.reference_schema()?.m_hPlayerPawn()?;
+        let local_pawn = match entities.get_by_handle(&pawn_handle)? {
+            Some(identity) => identity
+                .entity()?
+                .reference_schema()
+                .context("local player pawn")?,
+            None => return Ok(not_flashed),
+        };
+
+        let duration = local_pawn.m_flFlashDuration()?;
+        if duration <= 0.0 {
+            return Ok(not_flashed);
+        }
+
+        /* m_flFlashBangTime contains the game time at which the flash wears off */
+        let globals = states.resolve::<Globals>(())?;
+        let remaining_time =
+            (local_pawn.m_flFlashBangTime()? - globals.time_2()?).clamp(0.0, duration);
+
+        Ok(Self {
+            remaining_time,
+            duration,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
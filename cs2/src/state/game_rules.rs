@@ -0,0 +1,218 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use anyhow::Context;
+use cs2_schema_generated::cs2::client::{
+    C_CSGameRules,
+    C_CSGameRulesProxy,
+};
+use obfstr::obfstr;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::{
+    CEntityIdentityEx,
+    ClassNameCache,
+    ConVars,
+    EntitySystem,
+    Globals,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundPhase {
+    /// Warmup before the match starts
+    Warmup,
+
+    /// Players can not move yet
+    FreezeTime,
+
+    /// The round is currently being played
+    Live,
+
+    /// The round has been won by one team
+    RoundOver,
+}
+
+/// Find the game rules of the current match.
+/// Returns `None` if the game rules proxy entity does not exist (e.g. while not connected to a server).
+pub fn find_game_rules(states: &StateRegistry) -> anyhow::Result<Option<C_CSGameRules>> {
+    let entities = states.resolve::<EntitySystem>(())?;
+    let class_name_cache = states.resolve::<ClassNameCache>(())?;
+
+    for entity_identity in entities.all_identities().iter() {
+        let class_name = class_name_cache
+            .lookup(&entity_identity.entity_class_info()?)
+            .context("class name")?;
+
+        if !class_name
+            .map(|name| name == "C_CSGameRulesProxy")
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        let game_rules = entity_identity
+            .entity_ptr::<C_CSGameRulesProxy>()?
+            .reference_schema()?
+            .m_pGameRules()?
+            .reference_schema()
+            .context("game rules")?;
+
+        return Ok(Some(game_rules));
+    }
+
+    Ok(None)
+}
+
+/// Current round information of the game rules
+#[derive(Debug)]
+pub struct GameRules {
+    pub phase: RoundPhase,
+
+    /// Time remaining (in seconds) until the round ends
+    pub round_time_remaining: f32,
+
+    /// Time remaining (in seconds) of the freeze period.
+    /// Zero if the freeze period is over.
+    pub freeze_time_remaining: f32,
+
+    /// Time remaining (in seconds) for buying items.
+    /// None if buying is not possible any more.
+    pub buy_time_remaining: Option<f32>,
+
+    /// The current round is a knife round.
+    /// Detected by buying being disabled for both teams while the players
+    /// do not receive a default pistol.
+    pub knife_round: bool,
+}
+
+impl State for GameRules {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let convars = states.resolve::<GameRulesConVars>(())?;
+        let buy_time = convars.buy_time;
+        let globals = states.resolve::<Globals>(())?;
+        let game_rules = find_game_rules(states)?
+            .with_context(|| obfstr!("missing game rules proxy").to_string())?;
+
+        let phase = if game_rules.m_bWarmupPeriod()? {
+            RoundPhase::Warmup
+        } else if game_rules.m_bFreezePeriod()? {
+            RoundPhase::FreezeTime
+        } else if game_rules.m_iRoundWinStatus()? != 0 {
+            RoundPhase::RoundOver
+        } else {
+            RoundPhase::Live
+        };
+
+        let current_time = globals.time_2()?;
+        let round_start = game_rules.m_fRoundStartTime()?.m_Value()?;
+        let round_time = game_rules.m_iRoundTime()? as f32;
+
+        let round_time_remaining = (round_start + round_time - current_time)
+            .min(round_time)
+            .max(0.0);
+
+        let freeze_time_remaining = if phase == RoundPhase::FreezeTime {
+            (round_start - current_time).max(0.0)
+        } else {
+            0.0
+        };
+
+        let buying_disabled = game_rules.m_bTCantBuy()? && game_rules.m_bCTCantBuy()?;
+        let knife_round =
+            phase != RoundPhase::Warmup && buying_disabled && !convars.default_secondaries;
+
+        let buy_time_remaining = round_start + buy_time - current_time;
+        let buy_time_remaining =
+            if buy_time_remaining <= 0.0 || phase == RoundPhase::RoundOver || buying_disabled {
+                None
+            } else {
+                Some(buy_time_remaining)
+            };
+
+        Ok(Self {
+            phase,
+            round_time_remaining,
+            freeze_time_remaining,
+            buy_time_remaining,
+            knife_round,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
+
+/// Server convars required to calculate the round timings
+#[derive(Debug, Clone)]
+pub struct GameRulesConVars {
+    /// Time (in seconds) after the freeze period in which players can still buy
+    pub buy_time: f32,
+
+    /// Players receive a default pistol at the round start
+    pub default_secondaries: bool,
+
+    last_update: Instant,
+}
+
+/// Interval in which the game rule cvars will be read again
+const GAME_RULES_CONVARS_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
+impl GameRulesConVars {
+    fn read_cvars(&mut self, cvars: &ConVars) -> anyhow::Result<()> {
+        self.buy_time = match cvars.find_cvar(obfstr!("mp_buytime"))? {
+            Some(cvar) => cvar.fl_value()?,
+            None => 20.0,
+        };
+
+        let has_default_secondary = |name: &str| -> anyhow::Result<bool> {
+            Ok(match cvars.find_cvar(name)? {
+                Some(cvar) => !cvar.sz_value()?.read_string()?.is_empty(),
+                None => true,
+            })
+        };
+        let default_secondaries = has_default_secondary(obfstr!("mp_t_default_secondary"))?
+            || has_default_secondary(obfstr!("mp_ct_default_secondary"))?;
+        self.default_secondaries = default_secondaries;
+
+        self.last_update = Instant::now();
+        Ok(())
+    }
+}
+
+impl State for GameRulesConVars {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let cvars = states.resolve::<ConVars>(())?;
+
+        let mut result = Self {
+            buy_time: 20.0,
+            default_secondaries: true,
+            last_update: Instant::now(),
+        };
+        result.read_cvars(&cvars)?;
+        Ok(result)
+    }
+
+    fn update(&mut self, states: &StateRegistry) -> anyhow::Result<()> {
+        if self.last_update.elapsed() < GAME_RULES_CONVARS_UPDATE_INTERVAL {
+            return Ok(());
+        }
+
+        let cvars = states.resolve::<ConVars>(())?;
+        self.read_cvars(&cvars)
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Persistent
+    }
+}
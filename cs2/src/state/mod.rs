@@ -1,8 +1,20 @@
 mod player;
 pub use player::*;
 
+mod player_controller;
+pub use player_controller::*;
+
 mod observer;
 pub use observer::*;
 
 mod bomb;
 pub use bomb::*;
+
+mod game_rules;
+pub use game_rules::*;
+
+mod server_info;
+pub use server_info::*;
+
+mod flash;
+pub use flash::*;
@@ -17,6 +17,7 @@ use crate::{
 
 pub struct SpectatorInfo {
     pub spectator_name: String,
+    pub steam_id: u64,
 }
 
 pub struct SpectatorList {
@@ -77,7 +78,10 @@ impl State for SpectatorList {
                 .context("invalid player name")?
                 .to_string();
 
-            spectators.push(SpectatorInfo { spectator_name });
+            spectators.push(SpectatorInfo {
+                spectator_name,
+                steam_id: player_controller.m_steamID()?,
+            });
         }
 
         Ok(Self {
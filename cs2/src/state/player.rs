@@ -1,5 +1,3 @@
-use std::ffi::CStr;
-
 use anyhow::{
     Context,
     Result,
@@ -10,10 +8,12 @@ use cs2_schema_declaration::{
 };
 use cs2_schema_generated::{
     cs2::client::{
+        CCSPlayerController,
         CCSPlayer_ItemServices,
         CModelState,
         CSkeletonInstance,
         C_CSPlayerPawn,
+        C_C4,
     },
     EntityHandle,
 };
@@ -25,8 +25,11 @@ use utils_state::{
 
 use crate::{
     CS2Model,
+    CompetitiveRank,
     EntitySystem,
+    PlayerControllerInfo,
     WeaponId,
+    WEAPON_FLAG_TYPE_GRANADE,
 };
 
 #[derive(Debug, Clone)]
@@ -36,12 +39,28 @@ pub struct PlayerPawnInfo {
 
     pub player_health: i32,
     pub player_has_defuser: bool,
+    pub player_armor: i32,
+    pub player_has_helmet: bool,
     pub player_name: String,
+    pub player_steam_name: String,
+    pub player_clan_tag: String,
+    pub player_rank: Option<CompetitiveRank>,
+    pub player_color: i32,
     pub weapon: WeaponId,
     pub player_flashtime: f32,
 
+    /// All grenades the player is carrying (sorted by weapon id).
+    /// Note: A second flashbang is stored as ammo of the first one and therefore not listed.
+    pub grenades: Vec<WeaponId>,
+
+    pub player_is_scoped: bool,
+    pub player_is_reloading: bool,
+    pub player_is_defusing: bool,
+    pub player_is_planting: bool,
+
     pub position: nalgebra::Vector3<f32>,
     pub rotation: f32,
+    pub pitch: f32,
 
     pub model_address: u64,
     pub bone_states: Vec<BoneStateData>,
@@ -87,6 +106,38 @@ impl TryFrom<CBoneStateData> for BoneStateData {
     }
 }
 
+fn read_grenades(entities: &EntitySystem, player_pawn: &C_CSPlayerPawn) -> Result<Vec<WeaponId>> {
+    let weapon_services = player_pawn.m_pWeaponServices()?.reference_schema()?;
+    let weapons = weapon_services.m_hMyWeapons()?;
+
+    let weapon_count = weapons.element_count()?;
+    if !(0..=64).contains(&weapon_count) {
+        anyhow::bail!("{} {}", obfstr!("invalid weapon count"), weapon_count);
+    }
+
+    let mut grenades = Vec::new();
+    for index in 0..weapon_count as usize {
+        let weapon_handle = weapons.read_element(index)?;
+        let weapon = match entities.get_by_handle(&weapon_handle)? {
+            Some(identity) => identity.entity()?.read_schema()?,
+            None => continue,
+        };
+
+        let weapon_id = weapon
+            .m_AttributeManager()?
+            .m_Item()?
+            .m_iItemDefinitionIndex()?;
+        if let Some(weapon) = WeaponId::from_id(weapon_id) {
+            if (weapon.flags() & WEAPON_FLAG_TYPE_GRANADE) > 0 {
+                grenades.push(weapon);
+            }
+        }
+    }
+
+    grenades.sort_by_key(|weapon| weapon.id());
+    Ok(grenades)
+}
+
 #[derive(Debug, Clone)]
 pub enum PlayerPawnState {
     Alive(PlayerPawnInfo),
@@ -130,13 +181,12 @@ impl State for PlayerPawnState {
         let current_controller = entities.get_by_handle(&controller_handle)?;
 
         let player_team = player_pawn.m_iTeamNum()?;
-        let player_name = if let Some(identity) = &current_controller {
-            let player_controller = identity.entity()?.reference_schema()?;
-            CStr::from_bytes_until_nul(&player_controller.m_iszPlayerName()?)
-                .context("player name missing nul terminator")?
-                .to_str()
-                .context("invalid player name")?
-                .to_string()
+        let controller_info = if let Some(identity) = &current_controller {
+            let player_controller = identity
+                .entity()?
+                .cast::<CCSPlayerController>()
+                .reference_schema()?;
+            PlayerControllerInfo::read(&player_controller)?
         } else {
             /*
              * This is the case for pawns which are not controllel by a player controller.
@@ -152,11 +202,13 @@ impl State for PlayerPawnState {
             return Ok(Self::Dead);
         };
 
-        let player_has_defuser = player_pawn
+        let item_services = player_pawn
             .m_pItemServices()?
             .cast::<CCSPlayer_ItemServices>()
-            .reference_schema()?
-            .m_bHasDefuser()?;
+            .reference_schema()?;
+        let player_has_defuser = item_services.m_bHasDefuser()?;
+        let player_has_helmet = item_services.m_bHasHelmet()?;
+        let player_armor = player_pawn.m_ArmorValue()?;
 
         let position =
             nalgebra::Vector3::<f32>::from_column_slice(&game_screen_node.m_vecAbsOrigin()?);
@@ -176,30 +228,62 @@ impl State for PlayerPawnState {
             .map(|bone| bone.try_into())
             .collect::<Result<Vec<_>>>()?;
 
-        let weapon = player_pawn.m_pClippingWeapon()?.try_read_schema()?;
-        let weapon_type = if let Some(weapon) = weapon {
-            weapon
-                .m_AttributeManager()?
-                .m_Item()?
-                .m_iItemDefinitionIndex()?
+        let weapon_ptr = player_pawn.m_pClippingWeapon()?;
+        let (weapon_type, player_is_reloading) =
+            if let Some(weapon) = weapon_ptr.try_read_schema()? {
+                (
+                    weapon
+                        .m_AttributeManager()?
+                        .m_Item()?
+                        .m_iItemDefinitionIndex()?,
+                    weapon.m_bInReload()?,
+                )
+            } else {
+                (WeaponId::Knife.id(), false)
+            };
+        let weapon = WeaponId::from_id(weapon_type).unwrap_or(WeaponId::Unknown);
+
+        let player_is_planting = if weapon == WeaponId::C4 {
+            weapon_ptr
+                .cast::<C_C4>()
+                .reference_schema()?
+                .m_bStartedArming()?
         } else {
-            WeaponId::Knife.id()
+            false
         };
 
+        let grenades = read_grenades(&entities, &player_pawn)?;
+
+        let eye_angles = player_pawn.m_angEyeAngles()?;
         let player_flashtime = player_pawn.m_flFlashBangTime()?;
+        let player_is_scoped = player_pawn.m_bIsScoped()?;
+        let player_is_defusing = player_pawn.m_bIsDefusing()?;
 
         Ok(Self::Alive(PlayerPawnInfo {
             controller_entity_id: controller_handle.get_entity_index(),
             team_id: player_team,
 
-            player_name,
+            player_name: controller_info.player_name,
+            player_steam_name: controller_info.player_steam_name,
+            player_clan_tag: controller_info.player_clan_tag,
+            player_rank: controller_info.player_rank,
+            player_color: controller_info.player_color,
             player_has_defuser,
             player_health,
-            weapon: WeaponId::from_id(weapon_type).unwrap_or(WeaponId::Unknown),
+            player_armor,
+            player_has_helmet,
+            weapon,
             player_flashtime,
+            grenades,
+
+            player_is_scoped,
+            player_is_reloading,
+            player_is_defusing,
+            player_is_planting,
 
             position,
-            rotation: player_pawn.m_angEyeAngles()?[1],
+            rotation: eye_angles[1],
+            pitch: eye_angles[0],
 
             bone_states,
             model_address,
@@ -0,0 +1,166 @@
+use std::ffi::CStr;
+
+use anyhow::Context;
+use cs2_schema_generated::cs2::client::CCSPlayerController;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::EntitySystem;
+
+const RANK_TYPE_WINGMAN: i8 = 7;
+const RANK_TYPE_PREMIER: i8 = 11;
+const RANK_TYPE_COMPETITIVE: i8 = 12;
+
+const SKILL_GROUP_NAMES: [&str; 18] = [
+    "白银 I",
+    "白银 II",
+    "白银 III",
+    "白银 IV",
+    "白银精英",
+    "精英白银大师",
+    "黄金新星 I",
+    "黄金新星 II",
+    "黄金新星 III",
+    "黄金新星大师",
+    "大师守卫 I",
+    "大师守卫 II",
+    "精英大师守卫",
+    "杰出大师守卫",
+    "传奇之鹰",
+    "传奇之鹰大师",
+    "无上之首席大师",
+    "全球精英",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompetitiveRank {
+    /// CS rating of the premier mode
+    Premier(i32),
+
+    /// Skill group (1 - 18) of the competitive mode
+    Competitive(i32),
+
+    /// Skill group (1 - 18) of the wingman mode
+    Wingman(i32),
+}
+
+impl CompetitiveRank {
+    fn from_raw(rank_type: i8, ranking: i32) -> Option<Self> {
+        if ranking <= 0 {
+            /* unranked or rank hidden */
+            return None;
+        }
+
+        match rank_type {
+            RANK_TYPE_PREMIER => Some(Self::Premier(ranking)),
+            RANK_TYPE_COMPETITIVE => Some(Self::Competitive(ranking)),
+            RANK_TYPE_WINGMAN => Some(Self::Wingman(ranking)),
+            _ => None,
+        }
+    }
+
+    pub fn display_text(&self) -> String {
+        let skill_group_name = |ranking: i32| {
+            SKILL_GROUP_NAMES
+                .get(ranking as usize - 1)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("等级 {}", ranking))
+        };
+
+        match self {
+            Self::Premier(rating) => format!("优先 {}", rating),
+            Self::Competitive(ranking) => skill_group_name(*ranking),
+            Self::Wingman(ranking) => format!("搭档 {}", skill_group_name(*ranking)),
+        }
+    }
+}
+
+/// Player information which is only available on the player controller
+#[derive(Debug, Clone)]
+pub struct PlayerControllerInfo {
+    pub team_id: u8,
+
+    /// Name shown in game
+    pub player_name: String,
+
+    /// Steam persona name of the player
+    pub player_steam_name: String,
+
+    pub player_clan_tag: String,
+    pub player_rank: Option<CompetitiveRank>,
+
+    /// Competitive teammate color index (-1 if no color has been assigned)
+    pub player_color: i32,
+
+    pub player_is_alive: bool,
+}
+
+impl PlayerControllerInfo {
+    pub fn read(controller: &CCSPlayerController) -> anyhow::Result<Self> {
+        let player_steam_name = CStr::from_bytes_until_nul(&controller.m_iszPlayerName()?)
+            .context("player name missing nul terminator")?
+            .to_str()
+            .context("invalid player name")?
+            .to_string();
+
+        /* the sanitized name is not set for bots */
+        let player_name = controller.m_sSanitizedPlayerName()?.read_string()?;
+        let player_name = if player_name.is_empty() {
+            player_steam_name.clone()
+        } else {
+            player_name
+        };
+
+        let player_clan_tag = controller
+            .m_szClan()?
+            .try_read_string()?
+            .unwrap_or_default();
+
+        let player_rank = CompetitiveRank::from_raw(
+            controller.m_iCompetitiveRankType()?,
+            controller.m_iCompetitiveRanking()?,
+        );
+
+        Ok(Self {
+            team_id: controller.m_iTeamNum()?,
+
+            player_name,
+            player_steam_name,
+
+            player_clan_tag,
+            player_rank,
+
+            player_color: controller.m_iCompTeammateColor()?,
+
+            player_is_alive: controller.m_bPawnIsAlive()?,
+        })
+    }
+}
+
+/// All player controllers of the current match
+pub struct PlayerControllerList {
+    pub players: Vec<PlayerControllerInfo>,
+}
+
+impl State for PlayerControllerList {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let entities = states.resolve::<EntitySystem>(())?;
+
+        let mut players = Vec::new();
+        for controller in entities.get_player_controllers()? {
+            let controller = controller.reference_schema()?;
+            players.push(PlayerControllerInfo::read(&controller)?);
+        }
+
+        Ok(Self { players })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
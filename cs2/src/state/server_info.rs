@@ -0,0 +1,124 @@
+use std::net::SocketAddr;
+
+use cs2_schema_declaration::Ptr;
+use obfstr::obfstr;
+use utils_state::{
+    State,
+    StateCacheType,
+    StateRegistry,
+};
+
+use crate::{
+    find_game_rules,
+    CNetworkGameClient,
+    CS2HandleState,
+    CS2Offsets,
+    ConVars,
+    Globals,
+};
+
+/// Information about the game server the client is currently connected to
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    /// Host name of the server.
+    /// Not all servers replicate their host name to the clients.
+    pub server_name: Option<String>,
+
+    /// Address of the server which can be used with the connect command
+    pub server_address: Option<String>,
+
+    /// Server ticks per second
+    pub tick_rate: Option<f32>,
+
+    /// The server is an official Valve server
+    pub valve_server: bool,
+
+    /// The match has been created by the matchmaking
+    pub queued_matchmaking: bool,
+}
+
+impl ServerInfo {
+    /// Valve servers are always VAC secured.
+    /// For community servers the secure state can not be determined by the client.
+    pub fn vac_secured(&self) -> Option<bool> {
+        if self.valve_server {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Command to reconnect to this server using the in game console
+    pub fn connect_command(&self) -> Option<String> {
+        self.server_address
+            .as_ref()
+            .map(|address| format!("connect {}", address))
+    }
+}
+
+fn is_valid_server_address(address: &str) -> bool {
+    /* steam datagram relay addresses look like =[A:1:123456789:12345] */
+    address.parse::<SocketAddr>().is_ok() || (address.starts_with("=[") && address.ends_with(']'))
+}
+
+impl State for ServerInfo {
+    type Parameter = ();
+
+    fn create(states: &StateRegistry, _param: Self::Parameter) -> anyhow::Result<Self> {
+        let cs2 = states.resolve::<CS2HandleState>(())?;
+        let offsets = states.resolve::<CS2Offsets>(())?;
+        let cvars = states.resolve::<ConVars>(())?;
+        let globals = states.resolve::<Globals>(())?;
+
+        let network_game_client = cs2
+            .read_schema::<Ptr<CNetworkGameClient>>(&[offsets.network_game_client_instance])?
+            .try_read_schema()?;
+
+        let server_address = match network_game_client {
+            /* The address is not available while connecting and disconnecting */
+            Some(instance) => instance
+                .server_address()?
+                .read_string()
+                .ok()
+                .filter(|address| is_valid_server_address(address)),
+            None => None,
+        };
+
+        let server_name = match cvars.find_cvar(obfstr!("hostname"))? {
+            Some(cvar) => cvar
+                .sz_value()?
+                .read_string()
+                .ok()
+                .filter(|name| !name.is_empty()),
+            None => None,
+        };
+
+        let two_tick_time = globals.two_tick_time()?;
+        let tick_rate = if two_tick_time > 0.0 {
+            Some(2.0 / two_tick_time)
+        } else {
+            None
+        };
+
+        let (valve_server, queued_matchmaking) = match find_game_rules(states)? {
+            Some(game_rules) => (
+                game_rules.m_bIsValveDS()?,
+                game_rules.m_bIsQueuedMatchmaking()?,
+            ),
+            None => (false, false),
+        };
+
+        Ok(Self {
+            server_name,
+            server_address,
+            tick_rate,
+
+            valve_server,
+            queued_matchmaking,
+        })
+    }
+
+    fn cache_type() -> StateCacheType {
+        StateCacheType::Volatile
+    }
+}
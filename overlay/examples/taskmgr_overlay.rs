@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use overlay::OverlayTarget;
 
 fn main() -> anyhow::Result<()> {
@@ -10,7 +12,7 @@ fn main() -> anyhow::Result<()> {
     let overlay = overlay::init(&overlay::OverlayOptions {
         title: "Task Manager Overlay".to_string(),
         target: OverlayTarget::WindowTitle("Task Manager".into()),
-        font_init: Some(Box::new(|_imgui| {
+        font_init: Some(Rc::new(|_imgui, _scale| {
             // imgui.fonts().add_font(font_sources)
             // imgui.fonts().add_font(&[FontSource::TtfData {
             //     data: include_bytes!("../resources/unifont-15.1.03.otf"),
@@ -21,6 +23,9 @@ fn main() -> anyhow::Result<()> {
             //     }),
             // }]);
         })),
+        merged_fonts: Vec::new(),
+        render_backend: Default::default(),
+        vulkan_device: Default::default(),
     })?;
     let mut text_input = Default::default();
     overlay.main_loop(
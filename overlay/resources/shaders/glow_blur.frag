@@ -0,0 +1,28 @@
+#version 450
+
+// One direction of a separable gaussian blur.
+// The input and output colors use premultiplied alpha.
+layout(location = 0) in vec2 uv;
+layout(location = 0) out vec4 color;
+
+layout(set = 0, binding = 0) uniform texture2D source_texture;
+layout(set = 0, binding = 1) uniform sampler source_sampler;
+
+layout(push_constant) uniform Parameters {
+    // Distance between two samples in texture coordinates
+    vec2 step;
+    float intensity;
+} parameters;
+
+const float WEIGHTS[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+void main() {
+    vec4 sum = texture(sampler2D(source_texture, source_sampler), uv) * WEIGHTS[0];
+    for (int index = 1; index < 5; index++) {
+        vec2 offset = parameters.step * float(index);
+        sum += texture(sampler2D(source_texture, source_sampler), uv + offset) * WEIGHTS[index];
+        sum += texture(sampler2D(source_texture, source_sampler), uv - offset) * WEIGHTS[index];
+    }
+
+    color = min(sum * parameters.intensity, vec4(1.0));
+}
@@ -0,0 +1,27 @@
+#version 450
+
+// Converts the rendered overlay (gamma encoded, premultiplied alpha)
+// into linear scRGB where 1.0 equals 80 nits.
+layout(location = 0) in vec2 uv;
+layout(location = 0) out vec4 color;
+
+layout(set = 0, binding = 0) uniform texture2D overlay_texture;
+layout(set = 0, binding = 1) uniform sampler overlay_sampler;
+
+layout(push_constant) uniform Parameters {
+    // Brightness of white relative to 80 nits
+    float white_scale;
+} parameters;
+
+void main() {
+    vec4 value = texture(sampler2D(overlay_texture, overlay_sampler), uv);
+    if (value.a <= 0.0) {
+        color = vec4(0.0);
+        return;
+    }
+
+    // Decode with gamma 2.2 (instead of the piecewise sRGB curve) so dark colors don't look washed out
+    vec3 straight = value.rgb / value.a;
+    vec3 linear = pow(straight, vec3(2.2)) * parameters.white_scale;
+    color = vec4(linear * value.a, value.a);
+}
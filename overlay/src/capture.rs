@@ -0,0 +1,201 @@
+use std::{
+    ffi::c_void,
+    mem,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use windows::Win32::{
+    Foundation::{
+        HWND,
+        RECT,
+    },
+    Graphics::Gdi::{
+        BitBlt,
+        CreateCompatibleBitmap,
+        CreateCompatibleDC,
+        DeleteDC,
+        DeleteObject,
+        GetDC,
+        GetDIBits,
+        ReleaseDC,
+        SelectObject,
+        BITMAPINFO,
+        BITMAPINFOHEADER,
+        BI_RGB,
+        CAPTUREBLT,
+        DIB_RGB_COLORS,
+        SRCCOPY,
+    },
+};
+
+/// RGBA8 image of a captured frame
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl CapturedFrame {
+    /// Convert the premultiplied colors of a rendered overlay frame into straight alpha
+    fn unpremultiply(&mut self) {
+        for pixel in self.data.chunks_exact_mut(4) {
+            let alpha = pixel[3] as u32;
+            if alpha == 0 || alpha == 255 {
+                continue;
+            }
+
+            for channel in &mut pixel[0..3] {
+                *channel = (*channel as u32 * 255 / alpha).min(255) as u8;
+            }
+        }
+    }
+
+    /// Blend a rendered overlay frame (premultiplied alpha) on top of this frame
+    fn blend_premultiplied(&mut self, overlay: &CapturedFrame) {
+        let width = self.width.min(overlay.width) as usize;
+        let height = self.height.min(overlay.height) as usize;
+        for y in 0..height {
+            for x in 0..width {
+                let target = (y * self.width as usize + x) * 4;
+                let source = (y * overlay.width as usize + x) * 4;
+
+                let inverse_alpha = 255 - overlay.data[source + 3] as u32;
+                for channel in 0..3 {
+                    let value = overlay.data[source + channel] as u32
+                        + self.data[target + channel] as u32 * inverse_alpha / 255;
+                    self.data[target + channel] = value.min(255) as u8;
+                }
+            }
+        }
+    }
+}
+
+/// Capture a region of the screen as composited by the desktop window manager
+fn capture_screen(bounds: RECT) -> Result<CapturedFrame, String> {
+    let width = bounds.right - bounds.left;
+    let height = bounds.bottom - bounds.top;
+    if width <= 0 || height <= 0 {
+        return Err("empty capture region".to_string());
+    }
+
+    let mut data = vec![0u8; (width * height * 4) as usize];
+    let lines = unsafe {
+        let screen_dc = GetDC(HWND::default());
+        let memory_dc = CreateCompatibleDC(screen_dc);
+        let bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+        let previous = SelectObject(memory_dc, bitmap);
+
+        let mut lines = 0;
+        if BitBlt(
+            memory_dc,
+            0,
+            0,
+            width,
+            height,
+            screen_dc,
+            bounds.left,
+            bounds.top,
+            SRCCOPY | CAPTUREBLT,
+        )
+        .as_bool()
+        {
+            let mut info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    /* negative height for a top-down bitmap */
+                    biHeight: -height,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0 as u32,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            lines = GetDIBits(
+                memory_dc,
+                bitmap,
+                0,
+                height as u32,
+                Some(data.as_mut_ptr() as *mut c_void),
+                &mut info,
+                DIB_RGB_COLORS,
+            );
+        }
+
+        SelectObject(memory_dc, previous);
+        DeleteObject(bitmap);
+        DeleteDC(memory_dc);
+        ReleaseDC(HWND::default(), screen_dc);
+        lines
+    };
+
+    if lines != height {
+        return Err("failed to copy the screen contents".to_string());
+    }
+
+    for pixel in data.chunks_exact_mut(4) {
+        /* BGRX to RGBA */
+        pixel.swap(0, 2);
+        pixel[3] = 255;
+    }
+
+    Ok(CapturedFrame {
+        width: width as u32,
+        height: height as u32,
+        data,
+    })
+}
+
+/// Handle to a requested frame capture.
+/// The result becomes available once the next frame has been rendered.
+#[derive(Clone)]
+pub struct FrameCapture {
+    include_screen: bool,
+    result: Arc<Mutex<Option<Result<CapturedFrame, String>>>>,
+}
+
+impl FrameCapture {
+    pub(crate) fn new(include_screen: bool) -> Self {
+        Self {
+            include_screen,
+            result: Default::default(),
+        }
+    }
+
+    /// Take the captured frame (or the reason why the capture failed)
+    pub fn take_result(&self) -> Option<Result<CapturedFrame, String>> {
+        self.result.lock().unwrap().take()
+    }
+
+    /// Finish the capture with the read back overlay frame.
+    /// `screen_bounds` are the bounds of the overlay on the screen and `overlay_on_screen`
+    /// indicates that the overlay is already part of a screen capture.
+    pub(crate) fn complete(
+        &self,
+        overlay: Result<CapturedFrame, String>,
+        screen_bounds: RECT,
+        overlay_on_screen: bool,
+    ) {
+        let result = if self.include_screen {
+            capture_screen(screen_bounds).and_then(|mut screen| {
+                if !overlay_on_screen {
+                    screen.blend_premultiplied(&overlay?);
+                }
+
+                Ok(screen)
+            })
+        } else {
+            overlay.map(|mut overlay| {
+                overlay.unpremultiply();
+                overlay
+            })
+        };
+
+        *self.result.lock().unwrap() = Some(result);
+    }
+}
@@ -0,0 +1,1060 @@
+use std::{
+    ffi::c_void,
+    mem,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use imgui::{
+    internal::RawWrapper,
+    DrawCmd,
+    DrawCmdParams,
+    DrawData,
+    DrawIdx,
+    DrawVert,
+    TextureId,
+    Textures,
+};
+use imgui_winit_support::winit::{
+    dpi::PhysicalSize,
+    platform::windows::WindowExtWindows,
+    window::Window,
+};
+use obfstr::obfstr;
+use windows::{
+    core::PCSTR,
+    Win32::{
+        Foundation::{
+            BOOL,
+            E_FAIL,
+            HMODULE,
+            HWND,
+            RECT,
+        },
+        Graphics::{
+            Direct3D::{
+                Fxc::D3DCompile,
+                ID3DBlob,
+                ID3DInclude,
+                D3D11_PRIMITIVE_TOPOLOGY_TRIANGLELIST,
+                D3D11_SRV_DIMENSION_TEXTURE2D,
+                D3D_DRIVER_TYPE,
+                D3D_DRIVER_TYPE_HARDWARE,
+                D3D_DRIVER_TYPE_WARP,
+                D3D_FEATURE_LEVEL_10_0,
+                D3D_FEATURE_LEVEL_11_0,
+            },
+            Direct3D11::{
+                D3D11CreateDeviceAndSwapChain,
+                ID3D11BlendState,
+                ID3D11Buffer,
+                ID3D11ClassLinkage,
+                ID3D11DepthStencilState,
+                ID3D11DepthStencilView,
+                ID3D11Device,
+                ID3D11DeviceContext,
+                ID3D11InputLayout,
+                ID3D11PixelShader,
+                ID3D11RasterizerState,
+                ID3D11RenderTargetView,
+                ID3D11SamplerState,
+                ID3D11ShaderResourceView,
+                ID3D11Texture2D,
+                ID3D11VertexShader,
+                D3D11_BIND_CONSTANT_BUFFER,
+                D3D11_BIND_FLAG,
+                D3D11_BIND_INDEX_BUFFER,
+                D3D11_BIND_SHADER_RESOURCE,
+                D3D11_BIND_VERTEX_BUFFER,
+                D3D11_BLEND_DESC,
+                D3D11_BLEND_INV_SRC_ALPHA,
+                D3D11_BLEND_ONE,
+                D3D11_BLEND_OP_ADD,
+                D3D11_BLEND_SRC_ALPHA,
+                D3D11_BUFFER_DESC,
+                D3D11_COLOR_WRITE_ENABLE_ALL,
+                D3D11_COMPARISON_ALWAYS,
+                D3D11_CPU_ACCESS_READ,
+                D3D11_CPU_ACCESS_WRITE,
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                D3D11_CULL_NONE,
+                D3D11_DEPTH_STENCILOP_DESC,
+                D3D11_DEPTH_STENCIL_DESC,
+                D3D11_DEPTH_WRITE_MASK_ALL,
+                D3D11_FILL_SOLID,
+                D3D11_FILTER_MIN_MAG_MIP_LINEAR,
+                D3D11_INPUT_ELEMENT_DESC,
+                D3D11_INPUT_PER_VERTEX_DATA,
+                D3D11_MAPPED_SUBRESOURCE,
+                D3D11_MAP_READ,
+                D3D11_MAP_WRITE_DISCARD,
+                D3D11_RASTERIZER_DESC,
+                D3D11_RENDER_TARGET_BLEND_DESC,
+                D3D11_SAMPLER_DESC,
+                D3D11_SDK_VERSION,
+                D3D11_SHADER_RESOURCE_VIEW_DESC,
+                D3D11_SHADER_RESOURCE_VIEW_DESC_0,
+                D3D11_STENCIL_OP_KEEP,
+                D3D11_SUBRESOURCE_DATA,
+                D3D11_TEX2D_SRV,
+                D3D11_TEXTURE2D_DESC,
+                D3D11_TEXTURE_ADDRESS_WRAP,
+                D3D11_USAGE_DEFAULT,
+                D3D11_USAGE_DYNAMIC,
+                D3D11_USAGE_STAGING,
+                D3D11_VIEWPORT,
+            },
+            Dxgi::{
+                Common::{
+                    DXGI_FORMAT,
+                    DXGI_FORMAT_R16_UINT,
+                    DXGI_FORMAT_R32G32_FLOAT,
+                    DXGI_FORMAT_R32_UINT,
+                    DXGI_FORMAT_R8G8B8A8_UNORM,
+                    DXGI_FORMAT_UNKNOWN,
+                    DXGI_MODE_DESC,
+                    DXGI_SAMPLE_DESC,
+                },
+                IDXGIAdapter,
+                IDXGISwapChain,
+                DXGI_ERROR_DEVICE_REMOVED,
+                DXGI_ERROR_DEVICE_RESET,
+                DXGI_SWAP_CHAIN_DESC,
+                DXGI_SWAP_EFFECT_DISCARD,
+                DXGI_USAGE_RENDER_TARGET_OUTPUT,
+            },
+        },
+    },
+};
+
+use crate::{
+    capture::CapturedFrame,
+    render_backend::RenderBackend,
+    texture::{
+        OverlayTexture,
+        PendingUpload,
+        MAX_TEXTURES,
+    },
+    OverlayError,
+    PerfTracker,
+    Result,
+};
+
+const SHADER_SOURCE: &str = r#"
+cbuffer vertexBuffer : register(b0)
+{
+    float4x4 ProjectionMatrix;
+};
+
+struct VS_INPUT
+{
+    float2 pos : POSITION;
+    float4 col : COLOR0;
+    float2 uv  : TEXCOORD0;
+};
+
+struct PS_INPUT
+{
+    float4 pos : SV_POSITION;
+    float4 col : COLOR0;
+    float2 uv  : TEXCOORD0;
+};
+
+sampler sampler0;
+Texture2D texture0;
+
+PS_INPUT vs_main(VS_INPUT input)
+{
+    PS_INPUT output;
+    output.pos = mul(ProjectionMatrix, float4(input.pos.xy, 0.f, 1.f));
+    output.col = input.col;
+    output.uv  = input.uv;
+    return output;
+}
+
+float4 ps_main(PS_INPUT input) : SV_Target
+{
+    return input.col * texture0.Sample(sampler0, input.uv);
+}
+"#;
+
+/// Additional space allocated when growing the vertex / index buffers
+const VERTEX_BUFFER_RESERVE: usize = 5000;
+const INDEX_BUFFER_RESERVE: usize = 10000;
+
+fn compile_shader(entry_point: &str, target: &str) -> Result<ID3DBlob> {
+    let entry_point = std::ffi::CString::new(entry_point)?;
+    let target = std::ffi::CString::new(target)?;
+
+    let mut blob = None;
+    let mut errors = None;
+    let result = unsafe {
+        D3DCompile(
+            SHADER_SOURCE.as_ptr() as *const c_void,
+            SHADER_SOURCE.len(),
+            PCSTR::null(),
+            None,
+            None::<&ID3DInclude>,
+            PCSTR(entry_point.as_ptr() as *const u8),
+            PCSTR(target.as_ptr() as *const u8),
+            0,
+            0,
+            &mut blob,
+            Some(&mut errors),
+        )
+    };
+
+    if let Err(error) = result {
+        let message = match errors {
+            Some(errors) => unsafe {
+                let message = std::slice::from_raw_parts(
+                    errors.GetBufferPointer() as *const u8,
+                    errors.GetBufferSize(),
+                );
+                String::from_utf8_lossy(message).trim_end().to_string()
+            },
+            None => format!("{}", error),
+        };
+
+        return Err(OverlayError::D3D11ShaderCompileFailed(message));
+    }
+
+    blob.ok_or(OverlayError::D3D11ShaderCompileFailed(
+        "missing shader byte code".to_string(),
+    ))
+}
+
+fn blob_bytes(blob: &ID3DBlob) -> &[u8] {
+    unsafe {
+        std::slice::from_raw_parts(blob.GetBufferPointer() as *const u8, blob.GetBufferSize())
+    }
+}
+
+fn create_device_and_swapchain(
+    hwnd: HWND,
+    size: PhysicalSize<u32>,
+) -> Result<(ID3D11Device, ID3D11DeviceContext, IDXGISwapChain)> {
+    let swapchain_desc = DXGI_SWAP_CHAIN_DESC {
+        BufferDesc: DXGI_MODE_DESC {
+            Width: size.width,
+            Height: size.height,
+            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            ..Default::default()
+        },
+        SampleDesc: DXGI_SAMPLE_DESC {
+            Count: 1,
+            Quality: 0,
+        },
+        BufferUsage: DXGI_USAGE_RENDER_TARGET_OUTPUT,
+        BufferCount: 2,
+        OutputWindow: hwnd,
+        Windowed: BOOL::from(true),
+        SwapEffect: DXGI_SWAP_EFFECT_DISCARD,
+        Flags: 0,
+    };
+    let feature_levels = [D3D_FEATURE_LEVEL_11_0, D3D_FEATURE_LEVEL_10_0];
+
+    let create = |driver_type: D3D_DRIVER_TYPE| -> windows::core::Result<_> {
+        let mut swapchain = None;
+        let mut device = None;
+        let mut context = None;
+        unsafe {
+            D3D11CreateDeviceAndSwapChain(
+                None::<&IDXGIAdapter>,
+                driver_type,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                Some(&feature_levels[..]),
+                D3D11_SDK_VERSION,
+                Some(&swapchain_desc),
+                Some(&mut swapchain),
+                Some(&mut device),
+                None,
+                Some(&mut context),
+            )?;
+        }
+
+        match (device, context, swapchain) {
+            (Some(device), Some(context), Some(swapchain)) => Ok((device, context, swapchain)),
+            _ => Err(windows::core::Error::from(E_FAIL)),
+        }
+    };
+
+    match create(D3D_DRIVER_TYPE_HARDWARE) {
+        Ok(result) => Ok(result),
+        Err(error) => {
+            log::warn!(
+                "{}: {}. {}",
+                obfstr!("Failed to create a hardware D3D11 device"),
+                error,
+                obfstr!("Falling back to WARP.")
+            );
+            Ok(create(D3D_DRIVER_TYPE_WARP)?)
+        }
+    }
+}
+
+fn create_texture(
+    device: &ID3D11Device,
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Result<ID3D11ShaderResourceView> {
+    let texture_desc = D3D11_TEXTURE2D_DESC {
+        Width: width,
+        Height: height,
+        MipLevels: 1,
+        ArraySize: 1,
+        Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+        SampleDesc: DXGI_SAMPLE_DESC {
+            Count: 1,
+            Quality: 0,
+        },
+        Usage: D3D11_USAGE_DEFAULT,
+        BindFlags: D3D11_BIND_SHADER_RESOURCE,
+        ..Default::default()
+    };
+    let initial_data = D3D11_SUBRESOURCE_DATA {
+        pSysMem: data.as_ptr() as *const c_void,
+        SysMemPitch: width * 4,
+        SysMemSlicePitch: 0,
+    };
+
+    let mut texture: Option<ID3D11Texture2D> = None;
+    unsafe { device.CreateTexture2D(&texture_desc, Some(&initial_data), Some(&mut texture))? };
+    let texture = texture.ok_or(OverlayError::D3D11ResourceCreationFailed)?;
+
+    let view_desc = D3D11_SHADER_RESOURCE_VIEW_DESC {
+        Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+        ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2D,
+        Anonymous: D3D11_SHADER_RESOURCE_VIEW_DESC_0 {
+            Texture2D: D3D11_TEX2D_SRV {
+                MostDetailedMip: 0,
+                MipLevels: 1,
+            },
+        },
+    };
+
+    let mut view = None;
+    unsafe { device.CreateShaderResourceView(&texture, Some(&view_desc), Some(&mut view))? };
+    view.ok_or(OverlayError::D3D11ResourceCreationFailed)
+}
+
+fn create_dynamic_buffer(
+    device: &ID3D11Device,
+    byte_width: usize,
+    bind_flags: D3D11_BIND_FLAG,
+) -> Result<ID3D11Buffer> {
+    let desc = D3D11_BUFFER_DESC {
+        ByteWidth: byte_width as u32,
+        Usage: D3D11_USAGE_DYNAMIC,
+        BindFlags: bind_flags,
+        CPUAccessFlags: D3D11_CPU_ACCESS_WRITE,
+        ..Default::default()
+    };
+
+    let mut buffer = None;
+    unsafe { device.CreateBuffer(&desc, None, Some(&mut buffer))? };
+    buffer.ok_or(OverlayError::D3D11ResourceCreationFailed)
+}
+
+/// Pipeline state objects required to render the imgui draw data
+struct D3D11Pipeline {
+    vertex_shader: ID3D11VertexShader,
+    pixel_shader: ID3D11PixelShader,
+    input_layout: ID3D11InputLayout,
+    constant_buffer: ID3D11Buffer,
+    sampler: ID3D11SamplerState,
+    blend_state: ID3D11BlendState,
+    rasterizer_state: ID3D11RasterizerState,
+    depth_stencil_state: ID3D11DepthStencilState,
+}
+
+impl D3D11Pipeline {
+    fn new(device: &ID3D11Device) -> Result<Self> {
+        let vertex_shader_blob = compile_shader("vs_main", "vs_4_0")?;
+        let pixel_shader_blob = compile_shader("ps_main", "ps_4_0")?;
+
+        let mut vertex_shader = None;
+        let mut pixel_shader = None;
+        unsafe {
+            device.CreateVertexShader(
+                blob_bytes(&vertex_shader_blob),
+                None::<&ID3D11ClassLinkage>,
+                Some(&mut vertex_shader),
+            )?;
+            device.CreatePixelShader(
+                blob_bytes(&pixel_shader_blob),
+                None::<&ID3D11ClassLinkage>,
+                Some(&mut pixel_shader),
+            )?;
+        }
+
+        /* layout of imgui::DrawVert */
+        let input_elements = [
+            D3D11_INPUT_ELEMENT_DESC {
+                SemanticName: PCSTR(b"POSITION\0".as_ptr()),
+                SemanticIndex: 0,
+                Format: DXGI_FORMAT_R32G32_FLOAT,
+                InputSlot: 0,
+                AlignedByteOffset: 0,
+                InputSlotClass: D3D11_INPUT_PER_VERTEX_DATA,
+                InstanceDataStepRate: 0,
+            },
+            D3D11_INPUT_ELEMENT_DESC {
+                SemanticName: PCSTR(b"TEXCOORD\0".as_ptr()),
+                SemanticIndex: 0,
+                Format: DXGI_FORMAT_R32G32_FLOAT,
+                InputSlot: 0,
+                AlignedByteOffset: 8,
+                InputSlotClass: D3D11_INPUT_PER_VERTEX_DATA,
+                InstanceDataStepRate: 0,
+            },
+            D3D11_INPUT_ELEMENT_DESC {
+                SemanticName: PCSTR(b"COLOR\0".as_ptr()),
+                SemanticIndex: 0,
+                Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+                InputSlot: 0,
+                AlignedByteOffset: 16,
+                InputSlotClass: D3D11_INPUT_PER_VERTEX_DATA,
+                InstanceDataStepRate: 0,
+            },
+        ];
+        let mut input_layout = None;
+        unsafe {
+            device.CreateInputLayout(
+                &input_elements,
+                blob_bytes(&vertex_shader_blob),
+                Some(&mut input_layout),
+            )?
+        };
+
+        let constant_buffer = create_dynamic_buffer(
+            device,
+            mem::size_of::<[[f32; 4]; 4]>(),
+            D3D11_BIND_CONSTANT_BUFFER,
+        )?;
+
+        let sampler_desc = D3D11_SAMPLER_DESC {
+            Filter: D3D11_FILTER_MIN_MAG_MIP_LINEAR,
+            AddressU: D3D11_TEXTURE_ADDRESS_WRAP,
+            AddressV: D3D11_TEXTURE_ADDRESS_WRAP,
+            AddressW: D3D11_TEXTURE_ADDRESS_WRAP,
+            MipLODBias: 0.0,
+            MaxAnisotropy: 0,
+            ComparisonFunc: D3D11_COMPARISON_ALWAYS,
+            BorderColor: [0.0; 4],
+            MinLOD: 0.0,
+            MaxLOD: 0.0,
+        };
+        let mut sampler = None;
+        unsafe { device.CreateSamplerState(&sampler_desc, Some(&mut sampler))? };
+
+        let mut blend_desc = D3D11_BLEND_DESC::default();
+        blend_desc.RenderTarget[0] = D3D11_RENDER_TARGET_BLEND_DESC {
+            BlendEnable: BOOL::from(true),
+            SrcBlend: D3D11_BLEND_SRC_ALPHA,
+            DestBlend: D3D11_BLEND_INV_SRC_ALPHA,
+            BlendOp: D3D11_BLEND_OP_ADD,
+            SrcBlendAlpha: D3D11_BLEND_ONE,
+            DestBlendAlpha: D3D11_BLEND_INV_SRC_ALPHA,
+            BlendOpAlpha: D3D11_BLEND_OP_ADD,
+            RenderTargetWriteMask: D3D11_COLOR_WRITE_ENABLE_ALL.0 as u8,
+        };
+        let mut blend_state = None;
+        unsafe { device.CreateBlendState(&blend_desc, Some(&mut blend_state))? };
+
+        let rasterizer_desc = D3D11_RASTERIZER_DESC {
+            FillMode: D3D11_FILL_SOLID,
+            CullMode: D3D11_CULL_NONE,
+            ScissorEnable: BOOL::from(true),
+            DepthClipEnable: BOOL::from(true),
+            ..Default::default()
+        };
+        let mut rasterizer_state = None;
+        unsafe { device.CreateRasterizerState(&rasterizer_desc, Some(&mut rasterizer_state))? };
+
+        let stencil_op = D3D11_DEPTH_STENCILOP_DESC {
+            StencilFailOp: D3D11_STENCIL_OP_KEEP,
+            StencilDepthFailOp: D3D11_STENCIL_OP_KEEP,
+            StencilPassOp: D3D11_STENCIL_OP_KEEP,
+            StencilFunc: D3D11_COMPARISON_ALWAYS,
+        };
+        let depth_stencil_desc = D3D11_DEPTH_STENCIL_DESC {
+            DepthEnable: BOOL::from(false),
+            DepthWriteMask: D3D11_DEPTH_WRITE_MASK_ALL,
+            DepthFunc: D3D11_COMPARISON_ALWAYS,
+            StencilEnable: BOOL::from(false),
+            FrontFace: stencil_op,
+            BackFace: stencil_op,
+            ..Default::default()
+        };
+        let mut depth_stencil_state = None;
+        unsafe {
+            device.CreateDepthStencilState(&depth_stencil_desc, Some(&mut depth_stencil_state))?
+        };
+
+        match (
+            vertex_shader,
+            pixel_shader,
+            input_layout,
+            sampler,
+            blend_state,
+            rasterizer_state,
+            depth_stencil_state,
+        ) {
+            (
+                Some(vertex_shader),
+                Some(pixel_shader),
+                Some(input_layout),
+                Some(sampler),
+                Some(blend_state),
+                Some(rasterizer_state),
+                Some(depth_stencil_state),
+            ) => Ok(Self {
+                vertex_shader,
+                pixel_shader,
+                input_layout,
+                constant_buffer,
+                sampler,
+                blend_state,
+                rasterizer_state,
+                depth_stencil_state,
+            }),
+            _ => Err(OverlayError::D3D11ResourceCreationFailed),
+        }
+    }
+}
+
+/// Renders the overlay using Direct3D 11
+pub struct D3D11RenderBackend {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    swapchain: IDXGISwapChain,
+    render_target: Option<ID3D11RenderTargetView>,
+    swapchain_size: PhysicalSize<u32>,
+    vsync: bool,
+    present_time: Duration,
+
+    capture_requested: bool,
+    captured_frame: Option<std::result::Result<CapturedFrame, String>>,
+
+    pipeline: D3D11Pipeline,
+    vertex_buffer: Option<(ID3D11Buffer, usize)>,
+    index_buffer: Option<(ID3D11Buffer, usize)>,
+
+    textures: Textures<ID3D11ShaderResourceView>,
+    font_texture: Option<TextureId>,
+    uploaded_textures: usize,
+
+    device_lost: bool,
+}
+
+impl D3D11RenderBackend {
+    pub fn new(window: &Window, imgui: &mut imgui::Context) -> Result<Self> {
+        let hwnd = HWND(window.hwnd());
+        let swapchain_size = window.inner_size();
+        let (device, context, swapchain) = create_device_and_swapchain(hwnd, swapchain_size)?;
+        let pipeline = D3D11Pipeline::new(&device)?;
+
+        let mut backend = Self {
+            device,
+            context,
+            swapchain,
+            render_target: None,
+            swapchain_size,
+            vsync: false,
+            present_time: Duration::ZERO,
+
+            capture_requested: false,
+            captured_frame: None,
+
+            pipeline,
+            vertex_buffer: None,
+            index_buffer: None,
+
+            textures: Textures::new(),
+            font_texture: None,
+            uploaded_textures: 0,
+
+            device_lost: false,
+        };
+        backend.create_render_target()?;
+        backend.update_fonts_texture(imgui)?;
+
+        imgui.set_renderer_name(Some(obfstr!("valthrun-d3d11").to_string()));
+        imgui
+            .io_mut()
+            .backend_flags
+            .insert(imgui::BackendFlags::RENDERER_HAS_VTX_OFFSET);
+
+        log::debug!("D3D11 render backend initialized");
+        Ok(backend)
+    }
+
+    fn create_render_target(&mut self) -> Result<()> {
+        let back_buffer: ID3D11Texture2D = unsafe { self.swapchain.GetBuffer(0)? };
+
+        let mut render_target = None;
+        unsafe {
+            self.device
+                .CreateRenderTargetView(&back_buffer, None, Some(&mut render_target))?
+        };
+        self.render_target = render_target;
+        Ok(())
+    }
+
+    /// Copy the back buffer into host memory
+    fn read_back_buffer(&self) -> Result<CapturedFrame> {
+        let back_buffer: ID3D11Texture2D = unsafe { self.swapchain.GetBuffer(0)? };
+
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        unsafe { back_buffer.GetDesc(&mut desc) };
+        desc.Usage = D3D11_USAGE_STAGING;
+        desc.BindFlags = D3D11_BIND_FLAG(0);
+        desc.CPUAccessFlags = D3D11_CPU_ACCESS_READ;
+        desc.MiscFlags = Default::default();
+
+        let mut staging: Option<ID3D11Texture2D> = None;
+        unsafe {
+            self.device
+                .CreateTexture2D(&desc, None, Some(&mut staging))?
+        };
+        let staging = staging.ok_or(OverlayError::D3D11ResourceCreationFailed)?;
+
+        let mut resource = D3D11_MAPPED_SUBRESOURCE::default();
+        unsafe {
+            self.context.CopyResource(&staging, &back_buffer);
+            self.context
+                .Map(&staging, 0, D3D11_MAP_READ, 0, Some(&mut resource))?;
+        }
+
+        /* the back buffer is R8G8B8A8, rows may be padded */
+        let row_length = desc.Width as usize * 4;
+        let mut data = Vec::with_capacity(row_length * desc.Height as usize);
+        for row in 0..desc.Height as usize {
+            let row_data = unsafe {
+                std::slice::from_raw_parts(
+                    (resource.pData as *const u8).add(row * resource.RowPitch as usize),
+                    row_length,
+                )
+            };
+            data.extend_from_slice(row_data);
+        }
+        unsafe { self.context.Unmap(&staging, 0) };
+
+        Ok(CapturedFrame {
+            width: desc.Width,
+            height: desc.Height,
+            data,
+        })
+    }
+
+    fn resize_swapchain(&mut self, size: PhysicalSize<u32>) -> Result<()> {
+        log::debug!("Resizing the swapchain to {}x{}", size.width, size.height);
+
+        /* all references to the back buffers must be released before resizing */
+        unsafe {
+            self.context
+                .OMSetRenderTargets(None, None::<&ID3D11DepthStencilView>)
+        };
+        self.render_target = None;
+
+        unsafe {
+            self.swapchain
+                .ResizeBuffers(0, size.width, size.height, DXGI_FORMAT_UNKNOWN, 0)?
+        };
+        self.swapchain_size = size;
+        self.create_render_target()
+    }
+
+    /// Ensure the buffer can hold at least `count` elements of `T`
+    fn ensure_buffer_capacity<T>(
+        device: &ID3D11Device,
+        buffer: &mut Option<(ID3D11Buffer, usize)>,
+        count: usize,
+        reserve: usize,
+        bind_flags: D3D11_BIND_FLAG,
+    ) -> Result<()> {
+        if buffer
+            .as_ref()
+            .map_or(false, |(_, capacity)| *capacity >= count)
+        {
+            return Ok(());
+        }
+
+        let capacity = count + reserve;
+        *buffer = Some((
+            create_dynamic_buffer(device, capacity * mem::size_of::<T>(), bind_flags)?,
+            capacity,
+        ));
+        Ok(())
+    }
+
+    fn upload_draw_data(&mut self, draw_data: &DrawData) -> Result<()> {
+        let vertex_count = draw_data.total_vtx_count as usize;
+        let index_count = draw_data.total_idx_count as usize;
+        Self::ensure_buffer_capacity::<DrawVert>(
+            &self.device,
+            &mut self.vertex_buffer,
+            vertex_count,
+            VERTEX_BUFFER_RESERVE,
+            D3D11_BIND_VERTEX_BUFFER,
+        )?;
+        Self::ensure_buffer_capacity::<DrawIdx>(
+            &self.device,
+            &mut self.index_buffer,
+            index_count,
+            INDEX_BUFFER_RESERVE,
+            D3D11_BIND_INDEX_BUFFER,
+        )?;
+
+        let (vertex_buffer, _) = self.vertex_buffer.as_ref().unwrap();
+        let (index_buffer, _) = self.index_buffer.as_ref().unwrap();
+        unsafe {
+            let mut vertex_resource = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context.Map(
+                vertex_buffer,
+                0,
+                D3D11_MAP_WRITE_DISCARD,
+                0,
+                Some(&mut vertex_resource),
+            )?;
+
+            let mut index_resource = D3D11_MAPPED_SUBRESOURCE::default();
+            if let Err(error) = self.context.Map(
+                index_buffer,
+                0,
+                D3D11_MAP_WRITE_DISCARD,
+                0,
+                Some(&mut index_resource),
+            ) {
+                self.context.Unmap(vertex_buffer, 0);
+                return Err(error.into());
+            }
+
+            let mut vertex_target = vertex_resource.pData as *mut DrawVert;
+            let mut index_target = index_resource.pData as *mut DrawIdx;
+            for draw_list in draw_data.draw_lists() {
+                let vertices = draw_list.vtx_buffer();
+                std::ptr::copy_nonoverlapping(vertices.as_ptr(), vertex_target, vertices.len());
+                vertex_target = vertex_target.add(vertices.len());
+
+                let indices = draw_list.idx_buffer();
+                std::ptr::copy_nonoverlapping(indices.as_ptr(), index_target, indices.len());
+                index_target = index_target.add(indices.len());
+            }
+
+            self.context.Unmap(index_buffer, 0);
+            self.context.Unmap(vertex_buffer, 0);
+        }
+
+        /* orthographic projection from the imgui display space into clip space */
+        let left = draw_data.display_pos[0];
+        let right = left + draw_data.display_size[0];
+        let top = draw_data.display_pos[1];
+        let bottom = top + draw_data.display_size[1];
+        let projection: [[f32; 4]; 4] = [
+            [2.0 / (right - left), 0.0, 0.0, 0.0],
+            [0.0, 2.0 / (top - bottom), 0.0, 0.0],
+            [0.0, 0.0, 0.5, 0.0],
+            [
+                (right + left) / (left - right),
+                (top + bottom) / (bottom - top),
+                0.5,
+                1.0,
+            ],
+        ];
+
+        unsafe {
+            let mut resource = D3D11_MAPPED_SUBRESOURCE::default();
+            self.context.Map(
+                &self.pipeline.constant_buffer,
+                0,
+                D3D11_MAP_WRITE_DISCARD,
+                0,
+                Some(&mut resource),
+            )?;
+            std::ptr::copy_nonoverlapping(
+                &projection as *const [[f32; 4]; 4],
+                resource.pData as *mut [[f32; 4]; 4],
+                1,
+            );
+            self.context.Unmap(&self.pipeline.constant_buffer, 0);
+        }
+
+        Ok(())
+    }
+
+    fn setup_render_state(&self, draw_data: &DrawData) {
+        let pipeline = &self.pipeline;
+        let viewport = D3D11_VIEWPORT {
+            TopLeftX: 0.0,
+            TopLeftY: 0.0,
+            Width: draw_data.display_size[0] * draw_data.framebuffer_scale[0],
+            Height: draw_data.display_size[1] * draw_data.framebuffer_scale[1],
+            MinDepth: 0.0,
+            MaxDepth: 1.0,
+        };
+
+        let stride = mem::size_of::<DrawVert>() as u32;
+        let offset = 0;
+        let vertex_buffer = self
+            .vertex_buffer
+            .as_ref()
+            .map(|(buffer, _)| buffer.clone());
+        let index_buffer = self.index_buffer.as_ref().map(|(buffer, _)| buffer);
+
+        unsafe {
+            let context = &self.context;
+            context.RSSetViewports(Some(&[viewport]));
+            context.IASetInputLayout(&pipeline.input_layout);
+            context.IASetVertexBuffers(0, 1, Some(&vertex_buffer), Some(&stride), Some(&offset));
+            context.IASetIndexBuffer(index_buffer, index_format(), 0);
+            context.IASetPrimitiveTopology(D3D11_PRIMITIVE_TOPOLOGY_TRIANGLELIST);
+            context.VSSetShader(&pipeline.vertex_shader, None);
+            context.VSSetConstantBuffers(0, Some(&[Some(pipeline.constant_buffer.clone())]));
+            context.PSSetShader(&pipeline.pixel_shader, None);
+            context.PSSetSamplers(0, Some(&[Some(pipeline.sampler.clone())]));
+            context.OMSetBlendState(
+                &pipeline.blend_state,
+                Some(&[0.0; 4] as *const f32),
+                u32::MAX,
+            );
+            context.OMSetDepthStencilState(&pipeline.depth_stencil_state, 0);
+            context.RSSetState(&pipeline.rasterizer_state);
+        }
+    }
+
+    fn render_draw_data(&mut self, draw_data: &DrawData) -> Result<()> {
+        let render_target = match &self.render_target {
+            Some(render_target) => render_target.clone(),
+            None => return Ok(()),
+        };
+
+        unsafe {
+            self.context.OMSetRenderTargets(
+                Some(&[Some(render_target.clone())]),
+                None::<&ID3D11DepthStencilView>,
+            );
+            self.context
+                .ClearRenderTargetView(&render_target, [0.0f32; 4].as_ptr());
+        }
+
+        if draw_data.display_size[0] <= 0.0
+            || draw_data.display_size[1] <= 0.0
+            || draw_data.total_vtx_count == 0
+        {
+            return Ok(());
+        }
+
+        self.upload_draw_data(draw_data)?;
+        self.setup_render_state(draw_data);
+
+        let clip_offset = draw_data.display_pos;
+        let clip_scale = draw_data.framebuffer_scale;
+
+        let mut vertex_offset = 0;
+        let mut index_offset = 0;
+        for draw_list in draw_data.draw_lists() {
+            for command in draw_list.commands() {
+                match command {
+                    DrawCmd::Elements {
+                        count,
+                        cmd_params:
+                            DrawCmdParams {
+                                clip_rect,
+                                texture_id,
+                                vtx_offset,
+                                idx_offset,
+                            },
+                    } => {
+                        let clip_min = [
+                            (clip_rect[0] - clip_offset[0]) * clip_scale[0],
+                            (clip_rect[1] - clip_offset[1]) * clip_scale[1],
+                        ];
+                        let clip_max = [
+                            (clip_rect[2] - clip_offset[0]) * clip_scale[0],
+                            (clip_rect[3] - clip_offset[1]) * clip_scale[1],
+                        ];
+                        if clip_max[0] <= clip_min[0] || clip_max[1] <= clip_min[1] {
+                            continue;
+                        }
+
+                        let texture = match self.textures.get(texture_id) {
+                            Some(texture) => texture.clone(),
+                            None => {
+                                log::warn!("Missing D3D11 texture {:?}", texture_id);
+                                continue;
+                            }
+                        };
+
+                        unsafe {
+                            self.context.RSSetScissorRects(Some(&[RECT {
+                                left: clip_min[0] as i32,
+                                top: clip_min[1] as i32,
+                                right: clip_max[0] as i32,
+                                bottom: clip_max[1] as i32,
+                            }]));
+                            self.context.PSSetShaderResources(0, Some(&[Some(texture)]));
+                            self.context.DrawIndexed(
+                                count as u32,
+                                (index_offset + idx_offset) as u32,
+                                (vertex_offset + vtx_offset) as i32,
+                            );
+                        }
+                    }
+                    DrawCmd::ResetRenderState => self.setup_render_state(draw_data),
+                    DrawCmd::RawCallback { callback, raw_cmd } => unsafe {
+                        callback(draw_list.raw(), raw_cmd)
+                    },
+                }
+            }
+
+            vertex_offset += draw_list.vtx_buffer().len();
+            index_offset += draw_list.idx_buffer().len();
+        }
+
+        Ok(())
+    }
+}
+
+const fn index_format() -> DXGI_FORMAT {
+    if mem::size_of::<DrawIdx>() == 2 {
+        DXGI_FORMAT_R16_UINT
+    } else {
+        DXGI_FORMAT_R32_UINT
+    }
+}
+
+impl RenderBackend for D3D11RenderBackend {
+    fn prepare_frame(&mut self, window: &Window) -> bool {
+        let size = window.inner_size();
+        if size.width == 0 || size.height == 0 {
+            return false;
+        }
+
+        if size != self.swapchain_size {
+            if let Err(error) = self.resize_swapchain(size) {
+                log::error!("Failed to resize the swapchain: {}", error);
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn update_fonts_texture(&mut self, imgui: &mut imgui::Context) -> Result<()> {
+        let fonts = imgui.fonts();
+        let atlas = fonts.build_rgba32_texture();
+        let view = create_texture(&self.device, atlas.width, atlas.height, atlas.data)?;
+
+        let texture_id = match self.font_texture {
+            Some(texture_id) => {
+                self.textures.replace(texture_id, view);
+                texture_id
+            }
+            None => self.textures.insert(view),
+        };
+        self.font_texture = Some(texture_id);
+        fonts.tex_id = texture_id;
+        Ok(())
+    }
+
+    fn process_uploads(&mut self, uploads: Vec<PendingUpload>) {
+        for upload in uploads {
+            if self.uploaded_textures >= MAX_TEXTURES as usize {
+                log::warn!(
+                    "Texture limit of {} reached. Dropping upload.",
+                    MAX_TEXTURES
+                );
+                continue;
+            }
+
+            match create_texture(&self.device, upload.width, upload.height, &upload.data) {
+                Ok(view) => {
+                    let texture_id = self.textures.insert(view);
+                    upload.texture.set_texture_id(texture_id);
+                    self.uploaded_textures += 1;
+                }
+                Err(error) => {
+                    log::warn!("Failed to upload texture: {}", error);
+                }
+            }
+        }
+    }
+
+    fn delete_textures(&mut self, textures: Vec<OverlayTexture>) {
+        for texture_id in textures.iter().filter_map(OverlayTexture::take_texture_id) {
+            if self.textures.remove(texture_id).is_some() {
+                self.uploaded_textures -= 1;
+            }
+        }
+    }
+
+    fn set_vsync(&mut self, enabled: bool) {
+        self.vsync = enabled;
+    }
+
+    fn request_capture(&mut self) {
+        self.capture_requested = true;
+    }
+
+    fn take_capture(&mut self) -> Option<std::result::Result<CapturedFrame, String>> {
+        self.captured_frame.take()
+    }
+
+    fn render_frame(
+        &mut self,
+        draw_data: &DrawData,
+        _glow_draw_data: Option<&DrawData>,
+        perf: &mut PerfTracker,
+    ) -> bool {
+        if let Err(error) = self.render_draw_data(draw_data) {
+            log::error!("Failed to render the D3D11 frame: {}", error);
+            return false;
+        }
+
+        if self.capture_requested {
+            /* the contents of the back buffer are discarded when presenting */
+            self.capture_requested = false;
+            self.captured_frame = Some(
+                self.read_back_buffer()
+                    .map_err(|error| format!("{}", error)),
+            );
+            perf.mark("capture");
+        }
+        perf.mark("before present");
+
+        let sync_interval = if self.vsync { 1 } else { 0 };
+        let present_start = Instant::now();
+        let result = unsafe { self.swapchain.Present(sync_interval, 0) };
+        self.present_time = present_start.elapsed();
+        if let Err(error) = result.ok() {
+            if error.code() == DXGI_ERROR_DEVICE_REMOVED || error.code() == DXGI_ERROR_DEVICE_RESET
+            {
+                let reason = unsafe { self.device.GetDeviceRemovedReason() }
+                    .err()
+                    .unwrap_or(error);
+                log::warn!("The D3D11 device has been removed: {}", reason);
+            } else {
+                log::warn!("Failed to present the frame: {}", error);
+            }
+
+            self.device_lost = true;
+            return false;
+        }
+
+        true
+    }
+
+    fn present_time(&self) -> Duration {
+        self.present_time
+    }
+
+    fn device_lost(&self) -> bool {
+        self.device_lost
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::{
+    atomic::{
+        AtomicBool,
+        Ordering,
+    },
+    Mutex,
+};
+
+use imgui::StyleColor;
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::HWND,
+        UI::WindowsAndMessaging::{
+            MessageBoxW,
+            MB_ICONERROR,
+            MB_OK,
+        },
+    },
+};
+
+use crate::util;
+
+struct Dialog {
+    title: String,
+    message: String,
+}
+
+/// Set as soon as the overlay is able to render dialogs by itself
+static OVERLAY_RUNNING: AtomicBool = AtomicBool::new(false);
+static PENDING_DIALOGS: Mutex<Vec<Dialog>> = Mutex::new(Vec::new());
+
+/// Show an error message to the user.
+/// While the overlay is running the message will be shown as an in-overlay dialog,
+/// as a blocking message box would minimize the game.
+/// Before the overlay has been initialized a regular Win32 message box will be used.
+pub fn show_error_message(title: &str, message: &str) {
+    if OVERLAY_RUNNING.load(Ordering::Relaxed) {
+        PENDING_DIALOGS.lock().unwrap().push(Dialog {
+            title: title.to_string(),
+            message: message.to_string(),
+        });
+        return;
+    }
+
+    unsafe {
+        MessageBoxW(
+            HWND::default(),
+            PCWSTR::from_raw(util::to_wide_chars(message).as_ptr()),
+            PCWSTR::from_raw(util::to_wide_chars(title).as_ptr()),
+            MB_ICONERROR | MB_OK,
+        );
+    }
+}
+
+pub(crate) fn set_overlay_running(running: bool) {
+    OVERLAY_RUNNING.store(running, Ordering::Relaxed);
+}
+
+pub(crate) fn has_pending_dialogs() -> bool {
+    !PENDING_DIALOGS.lock().unwrap().is_empty()
+}
+
+/// Render the oldest pending dialog as a modal popup
+pub(crate) fn render_dialogs(ui: &imgui::Ui) {
+    let mut dialogs = PENDING_DIALOGS.lock().unwrap();
+    let dialog = match dialogs.first() {
+        Some(dialog) => dialog,
+        None => return,
+    };
+
+    let popup_id = format!("{}###overlay_error_dialog", dialog.title);
+    if !ui.is_popup_open(&popup_id) {
+        ui.open_popup(&popup_id);
+    }
+
+    let _title_color = ui.push_style_color(StyleColor::TitleBgActive, [0.6, 0.1, 0.1, 1.0]);
+    let _button_color = ui.push_style_color(StyleColor::Button, [0.6, 0.1, 0.1, 1.0]);
+    let _button_hovered_color =
+        ui.push_style_color(StyleColor::ButtonHovered, [0.75, 0.15, 0.15, 1.0]);
+
+    let dismissed = ui
+        .modal_popup_config(&popup_id)
+        .always_auto_resize(true)
+        .build(|| {
+            {
+                let _wrap = ui.push_text_wrap_pos_with_pos(500.0);
+                ui.text(&dialog.message);
+            }
+
+            ui.spacing();
+            if ui.button_with_size("确定", [120.0, 0.0]) {
+                ui.close_current_popup();
+                true
+            } else {
+                false
+            }
+        })
+        .unwrap_or(false);
+
+    if dismissed {
+        dialogs.remove(0);
+    }
+}
@@ -53,4 +53,28 @@ pub enum OverlayError {
 
     #[error("failed to create a vulkan surface: {0}")]
     VulkanSurfaceCreationFailed(VkResult),
+
+    #[error("invalid vulkan shader: {0}")]
+    VulkanShaderInvalid(std::io::Error),
+
+    #[error("failed to compile the D3D11 shaders: {0}")]
+    D3D11ShaderCompileFailed(String),
+
+    #[error("failed to create a D3D11 resource")]
+    D3D11ResourceCreationFailed,
+
+    #[error("failed to load font: {0}")]
+    FontLoadFailed(std::io::Error),
+
+    #[error("failed to start the input hook thread: {0}")]
+    InputHookThreadFailed(std::io::Error),
+
+    #[error("the input hook thread exited unexpectedly")]
+    InputHookThreadExited,
+
+    #[error("failed to start the window event hook thread: {0}")]
+    WindowEventHookThreadFailed(std::io::Error),
+
+    #[error("failed to install the window event hook")]
+    WindowEventHookFailed,
 }
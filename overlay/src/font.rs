@@ -0,0 +1,60 @@
+use std::{
+    path::Path,
+    rc::Rc,
+};
+
+use imgui::{
+    FontConfig,
+    FontGlyphRanges,
+    FontSource,
+};
+
+use crate::{
+    font_oversample,
+    OverlayError,
+    Result,
+};
+
+/// Font which gets merged into the default font.
+/// Only glyphs missing within the default font will be taken from this font.
+#[derive(Clone)]
+pub struct MergedFont {
+    data: Rc<[u8]>,
+    size_pixels: f32,
+    glyph_ranges: &'static [u32],
+}
+
+impl MergedFont {
+    /// Load a TTF font from disk.
+    /// `glyph_ranges` contains inclusive code point ranges (start, end) which should be taken from this font.
+    pub fn load(path: &Path, size_pixels: f32, glyph_ranges: &[(u32, u32)]) -> Result<Self> {
+        let data = std::fs::read(path).map_err(OverlayError::FontLoadFailed)?;
+
+        /* imgui requires the ranges to be zero terminated and to outlive the font atlas */
+        let mut ranges = glyph_ranges
+            .iter()
+            .flat_map(|(start, end)| [*start, *end])
+            .collect::<Vec<_>>();
+        ranges.push(0);
+
+        Ok(Self {
+            data: data.into(),
+            size_pixels,
+            glyph_ranges: Box::leak(ranges.into_boxed_slice()),
+        })
+    }
+
+    pub(crate) fn font_source(&self, scale: f32) -> FontSource<'_> {
+        FontSource::TtfData {
+            data: &self.data,
+            size_pixels: self.size_pixels * scale,
+            config: Some(FontConfig {
+                glyph_ranges: FontGlyphRanges::from_slice(self.glyph_ranges),
+                rasterizer_multiply: 1.5,
+                oversample_h: font_oversample(2, scale),
+                oversample_v: 1,
+                ..FontConfig::default()
+            }),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use windows::Win32::Media::{
+    timeBeginPeriod,
+    timeEndPeriod,
+};
+
+/// Strategy used to wait for the next frame when the frame rate is limited
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FramePacingMode {
+    /// Only sleep until the next frame.
+    /// Lowest CPU usage, but the frame times jitter by the scheduler granularity.
+    Sleep,
+
+    /// Sleep until shortly before the next frame and spin for the remaining time
+    #[default]
+    Hybrid,
+}
+
+/// Remaining time which will be spinned instead of slept in hybrid mode
+const SPIN_THRESHOLD: Duration = Duration::from_millis(2);
+
+/// Long waits (e.g. very low frame limits) check if they should be interrupted at this interval
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Limits the frame rate by keeping a fixed cadence of frame deadlines.
+/// Frames which take too long shift the cadence instead of causing a burst of catch up frames.
+pub struct FramePacer {
+    mode: FramePacingMode,
+    frame_limit: Option<u32>,
+    next_frame: Option<Instant>,
+
+    /// The system timer resolution has been raised to 1ms
+    timer_period_raised: bool,
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        Self {
+            mode: Default::default(),
+            frame_limit: None,
+            next_frame: None,
+
+            timer_period_raised: false,
+        }
+    }
+
+    pub fn mode(&self) -> FramePacingMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: FramePacingMode) {
+        self.mode = mode;
+    }
+
+    pub fn frame_limit(&self) -> Option<u32> {
+        self.frame_limit
+    }
+
+    pub fn set_frame_limit(&mut self, limit: Option<u32>) {
+        let limit = limit.filter(|limit| *limit > 0);
+        if limit == self.frame_limit {
+            return;
+        }
+
+        self.frame_limit = limit;
+        self.next_frame = None;
+        self.set_timer_period_raised(limit.is_some());
+    }
+
+    /// Raise the timer resolution so sleeps are accurate to about a millisecond
+    /// instead of the default scheduler granularity of ~15.6ms.
+    fn set_timer_period_raised(&mut self, raised: bool) {
+        if raised == self.timer_period_raised {
+            return;
+        }
+
+        unsafe {
+            if raised {
+                timeBeginPeriod(1);
+            } else {
+                timeEndPeriod(1);
+            }
+        }
+        self.timer_period_raised = raised;
+    }
+
+    /// Wait until the next frame should be started.
+    /// The wait ends early as soon as `interrupt` returns true.
+    pub fn wait(&mut self, mut interrupt: impl FnMut() -> bool) {
+        let frame_time = match self.frame_limit {
+            Some(limit) => Duration::from_secs_f64(1.0 / limit as f64),
+            None => return,
+        };
+
+        let now = Instant::now();
+        let deadline = match self.next_frame {
+            Some(deadline) if deadline + frame_time > now => deadline,
+            /* first frame or we fell behind by more than a frame, restart the cadence */
+            _ => now,
+        };
+
+        while deadline.saturating_duration_since(Instant::now()) > INTERRUPT_POLL_INTERVAL {
+            std::thread::sleep(INTERRUPT_POLL_INTERVAL);
+            if interrupt() {
+                self.next_frame = None;
+                return;
+            }
+        }
+
+        self.wait_until(deadline);
+        self.next_frame = Some(deadline + frame_time);
+    }
+
+    fn wait_until(&self, deadline: Instant) {
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            let remaining = deadline - now;
+            match self.mode {
+                FramePacingMode::Sleep => {
+                    std::thread::sleep(remaining);
+                    break;
+                }
+                FramePacingMode::Hybrid => {
+                    if remaining > SPIN_THRESHOLD {
+                        std::thread::sleep(remaining - SPIN_THRESHOLD);
+                    } else {
+                        std::hint::spin_loop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for FramePacer {
+    fn drop(&mut self) {
+        self.set_timer_period_raised(false);
+    }
+}
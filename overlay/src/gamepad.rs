@@ -0,0 +1,155 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use imgui::Key;
+use windows::Win32::UI::Input::XboxController::{
+    XInputGetState,
+    XINPUT_GAMEPAD_A,
+    XINPUT_GAMEPAD_B,
+    XINPUT_GAMEPAD_BACK,
+    XINPUT_GAMEPAD_BUTTON_FLAGS,
+    XINPUT_GAMEPAD_DPAD_DOWN,
+    XINPUT_GAMEPAD_DPAD_LEFT,
+    XINPUT_GAMEPAD_DPAD_RIGHT,
+    XINPUT_GAMEPAD_DPAD_UP,
+    XINPUT_GAMEPAD_LEFT_SHOULDER,
+    XINPUT_GAMEPAD_LEFT_THUMB,
+    XINPUT_GAMEPAD_RIGHT_SHOULDER,
+    XINPUT_GAMEPAD_RIGHT_THUMB,
+    XINPUT_GAMEPAD_START,
+    XINPUT_GAMEPAD_TRIGGER_THRESHOLD,
+    XINPUT_GAMEPAD_X,
+    XINPUT_GAMEPAD_Y,
+    XINPUT_STATE,
+    XUSER_MAX_COUNT,
+};
+
+/// Polling disconnected controllers is expensive, therefore only check them periodically.
+const DISCONNECTED_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+const BUTTON_MAPPING: [(XINPUT_GAMEPAD_BUTTON_FLAGS, Key); 14] = [
+    (XINPUT_GAMEPAD_A, Key::GamepadFaceDown),
+    (XINPUT_GAMEPAD_B, Key::GamepadFaceRight),
+    (XINPUT_GAMEPAD_X, Key::GamepadFaceLeft),
+    (XINPUT_GAMEPAD_Y, Key::GamepadFaceUp),
+    (XINPUT_GAMEPAD_DPAD_UP, Key::GamepadDpadUp),
+    (XINPUT_GAMEPAD_DPAD_DOWN, Key::GamepadDpadDown),
+    (XINPUT_GAMEPAD_DPAD_LEFT, Key::GamepadDpadLeft),
+    (XINPUT_GAMEPAD_DPAD_RIGHT, Key::GamepadDpadRight),
+    (XINPUT_GAMEPAD_START, Key::GamepadStart),
+    (XINPUT_GAMEPAD_BACK, Key::GamepadBack),
+    (XINPUT_GAMEPAD_LEFT_SHOULDER, Key::GamepadL1),
+    (XINPUT_GAMEPAD_RIGHT_SHOULDER, Key::GamepadR1),
+    (XINPUT_GAMEPAD_LEFT_THUMB, Key::GamepadL3),
+    (XINPUT_GAMEPAD_RIGHT_THUMB, Key::GamepadR3),
+];
+
+/// Gamepad buttons reported to imgui. The triggers are reported as L2 / R2.
+const GAMEPAD_KEYS: [Key; 16] = [
+    Key::GamepadFaceDown,
+    Key::GamepadFaceRight,
+    Key::GamepadFaceLeft,
+    Key::GamepadFaceUp,
+    Key::GamepadDpadUp,
+    Key::GamepadDpadDown,
+    Key::GamepadDpadLeft,
+    Key::GamepadDpadRight,
+    Key::GamepadStart,
+    Key::GamepadBack,
+    Key::GamepadL1,
+    Key::GamepadR1,
+    Key::GamepadL3,
+    Key::GamepadR3,
+    Key::GamepadL2,
+    Key::GamepadR2,
+];
+
+struct ControllerSlot {
+    connected: bool,
+    last_poll: Option<Instant>,
+}
+
+/// Polls the state of all XInput controllers and forwards the button states to imgui.
+/// Buttons of all connected controllers are merged.
+pub struct GamepadInputSystem {
+    controllers: Vec<ControllerSlot>,
+    key_states: [bool; GAMEPAD_KEYS.len()],
+}
+
+impl GamepadInputSystem {
+    pub fn new() -> Self {
+        Self {
+            controllers: (0..XUSER_MAX_COUNT)
+                .map(|_| ControllerSlot {
+                    connected: false,
+                    last_poll: None,
+                })
+                .collect(),
+            key_states: Default::default(),
+        }
+    }
+
+    fn poll_pressed_keys(&mut self) -> Vec<Key> {
+        let mut pressed_keys = Vec::new();
+        for (index, controller) in self.controllers.iter_mut().enumerate() {
+            if !controller.connected
+                && controller.last_poll.map_or(false, |last_poll| {
+                    last_poll.elapsed() < DISCONNECTED_POLL_INTERVAL
+                })
+            {
+                continue;
+            }
+
+            controller.last_poll = Some(Instant::now());
+
+            let mut state = XINPUT_STATE::default();
+            let result = unsafe { XInputGetState(index as u32, &mut state) };
+            controller.connected = result == 0;
+            if !controller.connected {
+                continue;
+            }
+
+            let gamepad = &state.Gamepad;
+            for (button, key) in BUTTON_MAPPING {
+                if gamepad.wButtons.0 & button.0 != 0 {
+                    pressed_keys.push(key);
+                }
+            }
+
+            if gamepad.bLeftTrigger as u32 > XINPUT_GAMEPAD_TRIGGER_THRESHOLD as u32 {
+                pressed_keys.push(Key::GamepadL2);
+            }
+
+            if gamepad.bRightTrigger as u32 > XINPUT_GAMEPAD_TRIGGER_THRESHOLD as u32 {
+                pressed_keys.push(Key::GamepadR2);
+            }
+        }
+
+        pressed_keys
+    }
+
+    pub fn update(&mut self, io: &mut imgui::Io) {
+        let pressed_keys = self.poll_pressed_keys();
+        for (index, key) in GAMEPAD_KEYS.iter().enumerate() {
+            let pressed = pressed_keys.contains(key);
+            if self.key_states[index] == pressed {
+                continue;
+            }
+
+            self.key_states[index] = pressed;
+            io.add_key_event(*key, pressed);
+        }
+    }
+
+    /// Release all buttons which are currently held down
+    pub fn reset(&mut self, io: &mut imgui::Io) {
+        for (index, key) in GAMEPAD_KEYS.iter().enumerate() {
+            if self.key_states[index] {
+                self.key_states[index] = false;
+                io.add_key_event(*key, false);
+            }
+        }
+    }
+}
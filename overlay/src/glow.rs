@@ -0,0 +1,148 @@
+use std::sync::Mutex;
+
+use imgui::{
+    internal::RawCast,
+    sys,
+    Condition,
+    DrawData,
+    DrawList,
+    ImColor32,
+    WindowFlags,
+};
+
+/// Parameters of the glow post effect
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlowSettings {
+    /// Blur radius in pixels
+    pub radius: f32,
+
+    /// Brightness multiplier of the blurred shapes
+    pub intensity: f32,
+}
+
+enum GlowShape {
+    Line {
+        start: [f32; 2],
+        end: [f32; 2],
+        color: ImColor32,
+        thickness: f32,
+    },
+    Rect {
+        min: [f32; 2],
+        max: [f32; 2],
+        color: ImColor32,
+        thickness: f32,
+    },
+}
+
+/// Shapes queued for the glow layer of the current frame
+static GLOW_SHAPES: Mutex<Vec<GlowShape>> = Mutex::new(Vec::new());
+
+/// Add a line to the glow layer.
+/// The line itself is not visible, only its blurred glow gets composited below the overlay.
+pub fn glow_line(start: [f32; 2], end: [f32; 2], color: impl Into<ImColor32>, thickness: f32) {
+    GLOW_SHAPES.lock().unwrap().push(GlowShape::Line {
+        start,
+        end,
+        color: color.into(),
+        thickness,
+    });
+}
+
+/// Add a rectangle outline to the glow layer (see `glow_line`)
+pub fn glow_rect(min: [f32; 2], max: [f32; 2], color: impl Into<ImColor32>, thickness: f32) {
+    GLOW_SHAPES.lock().unwrap().push(GlowShape::Rect {
+        min,
+        max,
+        color: color.into(),
+        thickness,
+    });
+}
+
+/// Draw all queued shapes into a dedicated window.
+/// Returns the draw list of that window or None if no shapes have been queued.
+/// The queue is cleared in either case.
+pub(crate) fn render_glow_layer(ui: &imgui::Ui, enabled: bool) -> Option<*const sys::ImDrawList> {
+    let shapes = std::mem::take(&mut *GLOW_SHAPES.lock().unwrap());
+    if !enabled || shapes.is_empty() {
+        return None;
+    }
+
+    ui.window("##overlay_glow")
+        .position([0.0, 0.0], Condition::Always)
+        .size(ui.io().display_size, Condition::Always)
+        .flags(
+            WindowFlags::NO_DECORATION
+                | WindowFlags::NO_BACKGROUND
+                | WindowFlags::NO_INPUTS
+                | WindowFlags::NO_DOCKING
+                | WindowFlags::NO_NAV
+                | WindowFlags::NO_SAVED_SETTINGS
+                | WindowFlags::NO_FOCUS_ON_APPEARING
+                | WindowFlags::NO_BRING_TO_FRONT_ON_FOCUS,
+        )
+        .build(|| {
+            let draw = ui.get_window_draw_list();
+            for shape in shapes {
+                match shape {
+                    GlowShape::Line {
+                        start,
+                        end,
+                        color,
+                        thickness,
+                    } => draw
+                        .add_line(start, end, color)
+                        .thickness(thickness)
+                        .build(),
+                    GlowShape::Rect {
+                        min,
+                        max,
+                        color,
+                        thickness,
+                    } => draw.add_rect(min, max, color).thickness(thickness).build(),
+                }
+            }
+
+            unsafe { sys::igGetWindowDrawList() as *const _ }
+        })
+}
+
+/// Draw data referencing a subset of the draw lists of the rendered frame
+pub(crate) struct FilteredDrawData {
+    raw: sys::ImDrawData,
+    draw_lists: Vec<*mut sys::ImDrawList>,
+}
+
+impl FilteredDrawData {
+    /// The draw lists are only valid until the next frame gets started
+    pub fn new(draw_data: &DrawData, filter: impl Fn(*const sys::ImDrawList) -> bool) -> Self {
+        let draw_lists = draw_data
+            .draw_lists()
+            .filter(|draw_list| filter(*draw_list as *const DrawList as *const _))
+            .collect::<Vec<_>>();
+
+        let mut raw = *unsafe { draw_data.raw() };
+        raw.CmdListsCount = draw_lists.len() as i32;
+        raw.TotalIdxCount = draw_lists
+            .iter()
+            .map(|draw_list| draw_list.idx_buffer().len() as i32)
+            .sum();
+        raw.TotalVtxCount = draw_lists
+            .iter()
+            .map(|draw_list| draw_list.vtx_buffer().len() as i32)
+            .sum();
+
+        Self {
+            raw,
+            draw_lists: draw_lists
+                .into_iter()
+                .map(|draw_list| draw_list as *const DrawList as *mut _)
+                .collect(),
+        }
+    }
+
+    pub fn draw_data(&mut self) -> &DrawData {
+        self.raw.CmdLists = self.draw_lists.as_mut_ptr();
+        unsafe { DrawData::from_raw(&self.raw) }
+    }
+}
@@ -0,0 +1,63 @@
+use windows::{
+    core::ComInterface,
+    Win32::Graphics::Dxgi::{
+        Common::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020,
+        CreateDXGIFactory1,
+        IDXGIFactory1,
+        IDXGIOutput6,
+        DXGI_OUTPUT_DESC1,
+    },
+};
+
+/// Color space the overlay is presented in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverlayColorSpace {
+    /// Use HDR if the monitor the overlay is displayed on has HDR enabled
+    #[default]
+    Auto,
+
+    /// Always present in sRGB, Windows maps the colors onto the HDR desktop
+    Srgb,
+
+    /// Always present in scRGB (linear, FP16)
+    Hdr,
+}
+
+/// Reference white of scRGB. A value of 1.0 is displayed with 80 nits.
+pub const SCRGB_REFERENCE_WHITE_NITS: f32 = 80.0;
+
+/// Check if Windows HDR ("Use HDR") is enabled for the given monitor
+pub(crate) fn is_monitor_hdr(monitor: isize) -> bool {
+    let factory = match unsafe { CreateDXGIFactory1::<IDXGIFactory1>() } {
+        Ok(factory) => factory,
+        Err(error) => {
+            log::debug!("Failed to create a DXGI factory: {}", error);
+            return false;
+        }
+    };
+
+    let mut adapter_index = 0;
+    while let Ok(adapter) = unsafe { factory.EnumAdapters1(adapter_index) } {
+        adapter_index += 1;
+
+        let mut output_index = 0;
+        while let Ok(output) = unsafe { adapter.EnumOutputs(output_index) } {
+            output_index += 1;
+
+            /* IDXGIOutput6 is available since Windows 10 1703 */
+            let output = match output.cast::<IDXGIOutput6>() {
+                Ok(output) => output,
+                Err(_) => continue,
+            };
+
+            let mut desc = DXGI_OUTPUT_DESC1::default();
+            if unsafe { output.GetDesc1(&mut desc) }.is_err() || desc.Monitor.0 != monitor {
+                continue;
+            }
+
+            return desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020;
+        }
+    }
+
+    false
+}
@@ -35,6 +35,11 @@ use windows::Win32::{
     },
 };
 
+use crate::{
+    input_hook::InputHook,
+    Result,
+};
+
 const VK_KEY_MAX: usize = 256;
 
 #[derive(Debug, Default)]
@@ -60,10 +65,12 @@ impl MouseInputSystem {
 
 /// Simple input system using the global mouse / keyboard state.
 /// This does not require the need to process window messages or the imgui overlay to be active.
-#[derive(Debug, Default)]
+/// If the input hook is enabled, key events will be received from a low level keyboard / mouse hook instead.
+#[derive(Default)]
 #[allow(unused)]
 pub struct KeyboardInputSystem {
     key_states: Vec<bool>,
+    input_hook: Option<InputHook>,
 }
 
 #[allow(unused)]
@@ -71,38 +78,71 @@ impl KeyboardInputSystem {
     pub fn new() -> Self {
         Self {
             key_states: vec![false; VK_KEY_MAX],
+            input_hook: None,
+        }
+    }
+
+    pub fn input_hook_enabled(&self) -> bool {
+        self.input_hook.is_some()
+    }
+
+    pub fn set_input_hook_enabled(&mut self, enabled: bool) -> Result<()> {
+        if enabled == self.input_hook.is_some() {
+            return Ok(());
         }
+
+        self.input_hook = if enabled {
+            Some(InputHook::install()?)
+        } else {
+            None
+        };
+        Ok(())
     }
 
     pub fn update(&mut self, _window: &Window, io: &mut imgui::Io) {
+        if let Some(input_hook) = &self.input_hook {
+            for (vkey, pressed) in input_hook.take_events() {
+                self.update_key_state(io, vkey, pressed);
+            }
+
+            return;
+        }
+
         for vkey in 0..VK_KEY_MAX {
             let key_state = unsafe { GetAsyncKeyState(vkey as i32) as u16 };
             let pressed = (key_state & 0x8000) > 0;
-            if self.key_states[vkey] == pressed {
-                continue;
-            }
+            self.update_key_state(io, VIRTUAL_KEY(vkey as u16), pressed);
+        }
+    }
 
-            self.key_states[vkey] = pressed;
-            let vkey = VIRTUAL_KEY(vkey as u16);
-
-            handle_key_modifier(io, vkey, pressed);
-            let mouse_button = match vkey {
-                VK_LBUTTON => Some(MouseButton::Left),
-                VK_RBUTTON => Some(MouseButton::Right),
-                VK_MBUTTON => Some(MouseButton::Middle),
-                VK_XBUTTON1 => Some(MouseButton::Extra1),
-                VK_XBUTTON2 => Some(MouseButton::Extra2),
-                _ => None,
-            };
-
-            if let Some(button) = mouse_button {
-                io.add_mouse_button_event(button, pressed);
-            } else if let Some(key) = to_imgui_key(vkey) {
-                // log::trace!("Key toogle {:?}: {}", key, pressed);
-                io.add_key_event(key, pressed);
-            } else {
-                log::trace!("Missing ImGui key for {:?}", vkey);
-            }
+    fn update_key_state(&mut self, io: &mut imgui::Io, vkey: VIRTUAL_KEY, pressed: bool) {
+        let state = match self.key_states.get_mut(vkey.0 as usize) {
+            Some(state) => state,
+            None => return,
+        };
+
+        if *state == pressed {
+            return;
+        }
+        *state = pressed;
+
+        handle_key_modifier(io, vkey, pressed);
+        let mouse_button = match vkey {
+            VK_LBUTTON => Some(MouseButton::Left),
+            VK_RBUTTON => Some(MouseButton::Right),
+            VK_MBUTTON => Some(MouseButton::Middle),
+            VK_XBUTTON1 => Some(MouseButton::Extra1),
+            VK_XBUTTON2 => Some(MouseButton::Extra2),
+            _ => None,
+        };
+
+        if let Some(button) = mouse_button {
+            io.add_mouse_button_event(button, pressed);
+        } else if let Some(key) = to_imgui_key(vkey) {
+            // log::trace!("Key toogle {:?}: {}", key, pressed);
+            io.add_key_event(key, pressed);
+        } else {
+            log::trace!("Missing ImGui key for {:?}", vkey);
         }
     }
 }
@@ -0,0 +1,194 @@
+use std::{
+    sync::{
+        mpsc,
+        Mutex,
+    },
+    thread::JoinHandle,
+};
+
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::{
+            HINSTANCE,
+            LPARAM,
+            LRESULT,
+            WPARAM,
+        },
+        System::{
+            LibraryLoader::GetModuleHandleW,
+            Threading::GetCurrentThreadId,
+        },
+        UI::{
+            Input::KeyboardAndMouse::{
+                VIRTUAL_KEY,
+                VK_LBUTTON,
+                VK_MBUTTON,
+                VK_RBUTTON,
+                VK_XBUTTON1,
+                VK_XBUTTON2,
+            },
+            WindowsAndMessaging::{
+                CallNextHookEx,
+                DispatchMessageW,
+                GetMessageW,
+                PostThreadMessageW,
+                SetWindowsHookExW,
+                TranslateMessage,
+                UnhookWindowsHookEx,
+                HHOOK,
+                KBDLLHOOKSTRUCT,
+                MSG,
+                MSLLHOOKSTRUCT,
+                WH_KEYBOARD_LL,
+                WH_MOUSE_LL,
+                WM_KEYDOWN,
+                WM_KEYUP,
+                WM_LBUTTONDOWN,
+                WM_LBUTTONUP,
+                WM_MBUTTONDOWN,
+                WM_MBUTTONUP,
+                WM_QUIT,
+                WM_RBUTTONDOWN,
+                WM_RBUTTONUP,
+                WM_SYSKEYDOWN,
+                WM_SYSKEYUP,
+                WM_XBUTTONDOWN,
+                WM_XBUTTONUP,
+            },
+        },
+    },
+};
+
+use crate::{
+    OverlayError,
+    Result,
+};
+
+/// Key events captured by the hooks since the last frame
+static HOOK_EVENTS: Mutex<Vec<(VIRTUAL_KEY, bool)>> = Mutex::new(Vec::new());
+
+fn push_event(vkey: VIRTUAL_KEY, pressed: bool) {
+    if let Ok(mut events) = HOOK_EVENTS.lock() {
+        events.push((vkey, pressed));
+    }
+}
+
+unsafe extern "system" fn keyboard_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        match wparam.0 as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN => push_event(VIRTUAL_KEY(info.vkCode as u16), true),
+            WM_KEYUP | WM_SYSKEYUP => push_event(VIRTUAL_KEY(info.vkCode as u16), false),
+            _ => {}
+        }
+    }
+
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+unsafe extern "system" fn mouse_hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        let xbutton = if (info.mouseData >> 16) == 1 {
+            VK_XBUTTON1
+        } else {
+            VK_XBUTTON2
+        };
+
+        match wparam.0 as u32 {
+            WM_LBUTTONDOWN => push_event(VK_LBUTTON, true),
+            WM_LBUTTONUP => push_event(VK_LBUTTON, false),
+            WM_RBUTTONDOWN => push_event(VK_RBUTTON, true),
+            WM_RBUTTONUP => push_event(VK_RBUTTON, false),
+            WM_MBUTTONDOWN => push_event(VK_MBUTTON, true),
+            WM_MBUTTONUP => push_event(VK_MBUTTON, false),
+            WM_XBUTTONDOWN => push_event(xbutton, true),
+            WM_XBUTTONUP => push_event(xbutton, false),
+            _ => {}
+        }
+    }
+
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+/// Low level keyboard and mouse hook.
+/// In contrast to polling the key state once per frame, no key presses get lost
+/// even if they are shorter than a frame or the overlay does not receive any input.
+///
+/// The hooks run on a dedicated thread as they require a message loop.
+pub struct InputHook {
+    thread_id: u32,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl InputHook {
+    pub fn install() -> Result<Self> {
+        let (init_tx, init_rx) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("input hook".to_string())
+            .spawn(move || unsafe {
+                let hooks = GetModuleHandleW(PCWSTR::null()).and_then(|module| {
+                    let module = HINSTANCE(module.0);
+                    let keyboard =
+                        SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook_proc), module, 0)?;
+                    match SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook_proc), module, 0) {
+                        Ok(mouse) => Ok([keyboard, mouse]),
+                        Err(error) => {
+                            UnhookWindowsHookEx(keyboard);
+                            Err(error)
+                        }
+                    }
+                });
+
+                let hooks = match hooks {
+                    Ok(hooks) => {
+                        let _ = init_tx.send(Ok(GetCurrentThreadId()));
+                        hooks
+                    }
+                    Err(error) => {
+                        let _ = init_tx.send(Err(error));
+                        return;
+                    }
+                };
+
+                let mut message = MSG::default();
+                while GetMessageW(&mut message, None, 0, 0).as_bool() {
+                    TranslateMessage(&message);
+                    DispatchMessageW(&message);
+                }
+
+                for hook in hooks {
+                    UnhookWindowsHookEx(hook);
+                }
+            })
+            .map_err(OverlayError::InputHookThreadFailed)?;
+
+        let thread_id = init_rx
+            .recv()
+            .map_err(|_| OverlayError::InputHookThreadExited)??;
+        HOOK_EVENTS.lock().unwrap().clear();
+
+        Ok(Self {
+            thread_id,
+            thread: Some(thread),
+        })
+    }
+
+    /// Take all key events which occurred since the last call
+    pub fn take_events(&self) -> Vec<(VIRTUAL_KEY, bool)> {
+        std::mem::take(&mut *HOOK_EVENTS.lock().unwrap())
+    }
+}
+
+impl Drop for InputHook {
+    fn drop(&mut self) {
+        unsafe {
+            PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use imgui::Io;
+use imgui_winit_support::winit::{
+    platform::windows::WindowExtWindows,
+    window::Window,
+};
+use windows::Win32::{
+    Foundation::HWND,
+    UI::{
+        Input::KeyboardAndMouse::SetActiveWindow,
+        WindowsAndMessaging::{
+            GetForegroundWindow,
+            GetWindowLongPtrA,
+            SetForegroundWindow,
+            SetWindowLongPtrA,
+            GWL_EXSTYLE,
+            WS_EX_NOACTIVATE,
+            WS_EX_TRANSPARENT,
+        },
+    },
+};
+
+/// Toggles the overlay noactive and transparent state.
+/// The overlay receives inputs whenever ImGui wants mouse/cursor grab or
+/// the overlay has been made interactive (e.g. while a menu is open).
+/// Otherwise all inputs pass through to the window below.
+pub struct InputPassthrough {
+    currently_active: bool,
+
+    /// Receive all clicks, even if no ImGui window has been hit
+    interactive: bool,
+}
+
+impl InputPassthrough {
+    pub fn new() -> Self {
+        Self {
+            currently_active: true,
+            interactive: false,
+        }
+    }
+
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+    }
+
+    pub fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    /// Update the window style of the overlay.
+    /// When returning to pass-through the focus is given back to the target window.
+    pub fn update(&mut self, window: &Window, io: &Io, target: HWND) {
+        let window_active = self.interactive || io.want_capture_mouse || io.want_capture_keyboard;
+        if window_active == self.currently_active {
+            return;
+        }
+
+        self.currently_active = window_active;
+        unsafe {
+            let hwnd = HWND(window.hwnd());
+            let mut style = GetWindowLongPtrA(hwnd, GWL_EXSTYLE);
+            if window_active {
+                style &= !((WS_EX_NOACTIVATE | WS_EX_TRANSPARENT).0 as isize);
+            } else {
+                style |= (WS_EX_NOACTIVATE | WS_EX_TRANSPARENT).0 as isize;
+            }
+
+            log::trace!("设置 UI 为活动状态: {window_active}");
+            SetWindowLongPtrA(hwnd, GWL_EXSTYLE, style);
+            if window_active {
+                SetActiveWindow(hwnd);
+            } else if GetForegroundWindow() == hwnd {
+                /* the overlay took the focus, hand it back so the game receives the inputs again */
+                SetForegroundWindow(target);
+            }
+        }
+    }
+}
@@ -1,22 +1,22 @@
-use std::time::Instant;
+use std::{
+    rc::Rc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
 
-use ash::vk;
 use clipboard::ClipboardSupport;
 use copypasta::ClipboardContext;
 use imgui::{
+    ConfigFlags,
     Context,
     FontConfig,
     FontGlyphRanges,
     FontSource,
-    Io,
-};
-use imgui_rs_vulkan_renderer::{
-    Options,
-    Renderer,
 };
 use imgui_winit_support::{
     winit::{
-        dpi::PhysicalSize,
         event::{
             Event,
             WindowEvent,
@@ -25,7 +25,10 @@ use imgui_winit_support::{
             ControlFlow,
             EventLoop,
         },
-        platform::windows::WindowExtWindows,
+        platform::windows::{
+            MonitorHandleExtWindows,
+            WindowExtWindows,
+        },
         window::{
             Window,
             WindowBuilder,
@@ -40,68 +43,121 @@ use input::{
 };
 use obfstr::obfstr;
 use window_tracker::WindowTracker;
-use windows::{
-    core::PCWSTR,
-    Win32::{
-        Foundation::{
-            BOOL,
-            HWND,
-        },
-        Graphics::{
-            Dwm::{
-                DwmEnableBlurBehindWindow,
-                DWM_BB_BLURREGION,
-                DWM_BB_ENABLE,
-                DWM_BLURBEHIND,
-            },
-            Gdi::CreateRectRgn,
-        },
-        UI::{
-            Input::KeyboardAndMouse::SetActiveWindow,
-            WindowsAndMessaging::{
-                GetWindowLongPtrA,
-                MessageBoxW,
-                SetWindowDisplayAffinity,
-                SetWindowLongA,
-                SetWindowLongPtrA,
-                SetWindowPos,
-                ShowWindow,
-                GWL_EXSTYLE,
-                GWL_STYLE,
-                HWND_TOPMOST,
-                MB_ICONERROR,
-                MB_OK,
-                SWP_NOACTIVATE,
-                SWP_NOMOVE,
-                SWP_NOSIZE,
-                SW_SHOWNOACTIVATE,
-                WDA_EXCLUDEFROMCAPTURE,
-                WDA_NONE,
-                WS_CLIPSIBLINGS,
-                WS_EX_LAYERED,
-                WS_EX_NOACTIVATE,
-                WS_EX_TOOLWINDOW,
-                WS_EX_TRANSPARENT,
-                WS_POPUP,
-                WS_VISIBLE,
-            },
+use windows::Win32::{
+    Foundation::{
+        BOOL,
+        HWND,
+        RECT,
+    },
+    Graphics::{
+        Dwm::{
+            DwmEnableBlurBehindWindow,
+            DWM_BB_BLURREGION,
+            DWM_BB_ENABLE,
+            DWM_BLURBEHIND,
         },
+        Gdi::CreateRectRgn,
+    },
+    UI::WindowsAndMessaging::{
+        SetWindowDisplayAffinity,
+        SetWindowLongA,
+        SetWindowLongPtrA,
+        SetWindowPos,
+        ShowWindow,
+        GWL_EXSTYLE,
+        GWL_STYLE,
+        HWND_TOPMOST,
+        SWP_NOACTIVATE,
+        SWP_NOMOVE,
+        SWP_NOSIZE,
+        SW_SHOWNOACTIVATE,
+        WDA_EXCLUDEFROMCAPTURE,
+        WDA_NONE,
+        WS_CLIPSIBLINGS,
+        WS_EX_LAYERED,
+        WS_EX_NOACTIVATE,
+        WS_EX_TOOLWINDOW,
+        WS_EX_TRANSPARENT,
+        WS_POPUP,
+        WS_VISIBLE,
     },
 };
 
+mod capture;
+pub use capture::{
+    CapturedFrame,
+    FrameCapture,
+};
 mod clipboard;
+mod dialog;
+pub use dialog::show_error_message;
 mod error;
 pub use error::*;
+mod font;
+pub use font::*;
+mod gamepad;
+use gamepad::GamepadInputSystem;
+mod glow;
+use glow::FilteredDrawData;
+pub use glow::{
+    glow_line,
+    glow_rect,
+    GlowSettings,
+};
+mod hdr;
+pub use hdr::{
+    OverlayColorSpace,
+    SCRGB_REFERENCE_WHITE_NITS,
+};
 mod input;
+mod input_hook;
+mod input_passthrough;
+use input_passthrough::InputPassthrough;
+mod notification;
+pub use notification::{
+    notify,
+    NotificationLevel,
+};
+mod window_event_hook;
 mod window_tracker;
-pub use window_tracker::OverlayTarget;
+pub use window_tracker::{
+    OverlayMonitor,
+    OverlayTarget,
+};
 
 mod vulkan;
 
+mod d3d11_render;
+
+mod render_backend;
+pub use render_backend::RenderBackendType;
+use render_backend::{
+    RenderBackend,
+    RenderBackendOptions,
+};
+
+mod frame_pacer;
+use frame_pacer::FramePacer;
+pub use frame_pacer::FramePacingMode;
+
 mod perf;
-pub use perf::PerfTracker;
+pub use perf::{
+    PerfTracker,
+    PresentTiming,
+};
+
+mod texture;
+pub use texture::{
+    OverlayTexture,
+    TextureUploader,
+};
+use texture::{
+    PendingUpload,
+    MAX_TEXTURES,
+};
 
 mod vulkan_render;
+pub use vulkan_render::PhysicalDeviceSelector;
 use vulkan_render::*;
 
 mod util;
@@ -118,19 +174,6 @@ pub fn to_wide_chars(s: &str) -> Vec<u16> {
         .collect::<Vec<_>>()
 }
 
-pub fn show_error_message(title: &str, message: &str) {
-    // let title = CString::new(title).unwrap_or_else(|_| CString::new("[[ NulError ]]").unwrap());
-    // let message = CString::new(message).unwrap_or_else(|_| CString::new("[[ NulError ]]").unwrap());
-    unsafe {
-        MessageBoxW(
-            HWND::default(),
-            PCWSTR::from_raw(to_wide_chars(message).as_ptr()),
-            PCWSTR::from_raw(to_wide_chars(title).as_ptr()),
-            MB_ICONERROR | MB_OK,
-        );
-    }
-}
-
 fn create_window(event_loop: &EventLoop<()>, title: &str) -> Result<Window> {
     let window = WindowBuilder::new()
         .with_title(title.to_owned())
@@ -175,32 +218,59 @@ fn create_window(event_loop: &EventLoop<()>, title: &str) -> Result<Window> {
     Ok(window)
 }
 
+/// Callback for registering additional fonts.
+/// The second argument is the scale which should be applied to the font size.
+pub type FontInitCallback = Rc<dyn Fn(&mut imgui::Context, f32) -> ()>;
+
 pub struct OverlayOptions {
     pub title: String,
     pub target: OverlayTarget,
-    pub font_init: Option<Box<dyn Fn(&mut imgui::Context) -> ()>>,
+
+    /// Will be called every time the font atlas gets (re)build
+    pub font_init: Option<FontInitCallback>,
+
+    /// Fonts filling glyphs missing in the default font
+    pub merged_fonts: Vec<MergedFont>,
+
+    /// Graphics API used to render the overlay
+    pub render_backend: RenderBackendType,
+
+    /// GPU used by the Vulkan render backend
+    pub vulkan_device: PhysicalDeviceSelector,
 }
 
-fn create_imgui_context(options: &OverlayOptions) -> Result<(WinitPlatform, imgui::Context)> {
-    let mut imgui = Context::create();
-    imgui.set_ini_filename(None);
+/// Default font size in logical pixels
+const FONT_SIZE: f32 = 18.0;
 
-    let platform = WinitPlatform::init(&mut imgui);
+/// Maximum scale the fonts will be rasterized at.
+/// The font atlas contains the full CJK glyph set and would exceed the maximum texture size
+/// at larger scales. Beyond this scale imgui scales the text up instead.
+const MAX_FONT_RASTER_SCALE: f32 = 3.0;
 
-    match ClipboardContext::new() {
-        Ok(backend) => imgui.set_clipboard_backend(ClipboardSupport(backend)),
-        Err(error) => log::warn!("Failed to initialize clipboard: {}", error),
-    };
+/// Scale the fonts will be rasterized at for the given DPI and UI scale
+fn font_raster_scale(scale: f32) -> f32 {
+    scale.min(MAX_FONT_RASTER_SCALE)
+}
+
+/// Oversampling for the given raster scale.
+/// Large glyphs do not benefit from oversampling, reducing it keeps the atlas size
+/// similar to the atlas at a raster scale of 1.0.
+pub(crate) fn font_oversample(base: i32, scale: f32) -> i32 {
+    ((base as f32 / scale).floor() as i32).clamp(1, base)
+}
 
-    // Fixed font size. Note imgui_winit_support uses "logical
-    // pixels", which are physical pixels scaled by the devices
-    // scaling factor. Meaning, 13.0 pixels should look the same size
-    // on two different screens, and thus we do not need to scale this
-    // value (as the scaling is handled by winit)
-    let font_size = 18.0;
-    imgui.fonts().add_font(&[FontSource::TtfData {
+/// Add all fonts to the atlas.
+/// The fonts will be rasterized at `raster_scale` and scaled down by imgui again to keep
+/// the text sharp on high DPI displays (imgui_winit_support uses "logical pixels").
+fn register_fonts(
+    imgui: &mut imgui::Context,
+    font_init: Option<&FontInitCallback>,
+    merged_fonts: &[MergedFont],
+    scale: f32,
+) {
+    let mut sources = vec![FontSource::TtfData {
         data: include_bytes!("../resources/SourceHanSerifCN-VF.ttf"),
-        size_pixels: font_size,
+        size_pixels: FONT_SIZE * scale,
         config: Some(FontConfig {
             glyph_ranges: FontGlyphRanges::chinese_full(),
             // As imgui-glium-renderer isn't gamma-correct with
@@ -210,14 +280,43 @@ fn create_imgui_context(options: &OverlayOptions) -> Result<(WinitPlatform, imgu
             rasterizer_multiply: 1.5,
             // Oversampling font helps improve text rendering at
             // expense of larger font atlas texture.
-            oversample_h: 4,
-            oversample_v: 4,
+            oversample_h: font_oversample(4, scale),
+            oversample_v: font_oversample(4, scale),
             ..FontConfig::default()
         }),
-    }]);
-    if let Some(callback) = &options.font_init {
-        callback(&mut imgui);
+    }];
+
+    /* every source after the first one gets merged into the first font */
+    sources.extend(merged_fonts.iter().map(|font| font.font_source(scale)));
+    imgui.fonts().add_font(&sources);
+
+    if let Some(callback) = font_init {
+        callback(imgui, scale);
     }
+}
+
+fn create_imgui_context(
+    options: &OverlayOptions,
+    dpi_scale: f32,
+) -> Result<(WinitPlatform, imgui::Context)> {
+    let mut imgui = Context::create();
+    imgui.set_ini_filename(None);
+
+    let platform = WinitPlatform::init(&mut imgui);
+
+    match ClipboardContext::new() {
+        Ok(backend) => imgui.set_clipboard_backend(ClipboardSupport(backend)),
+        Err(error) => log::warn!("Failed to initialize clipboard: {}", error),
+    };
+
+    let raster_scale = font_raster_scale(dpi_scale);
+    register_fonts(
+        &mut imgui,
+        options.font_init.as_ref(),
+        &options.merged_fonts,
+        raster_scale,
+    );
+    imgui.io_mut().font_global_scale = 1.0 / raster_scale;
 
     Ok((platform, imgui))
 }
@@ -228,17 +327,14 @@ pub struct System {
     pub window: Window,
     pub platform: WinitPlatform,
 
-    pub vulkan_context: VulkanContext,
-    command_buffer: vk::CommandBuffer,
-    swapchain: Swapchain,
-    image_available_semaphore: vk::Semaphore,
-    render_finished_semaphore: vk::Semaphore,
-    fence: vk::Fence,
-
     pub imgui: Context,
-    pub renderer: Renderer,
+    render_backend: Box<dyn RenderBackend>,
+    render_backend_options: RenderBackendOptions,
+    texture_uploader: TextureUploader,
 
     pub window_tracker: WindowTracker,
+    font_init: Option<FontInitCallback>,
+    merged_fonts: Vec<MergedFont>,
 }
 
 pub fn init(options: &OverlayOptions) -> Result<System> {
@@ -247,125 +343,52 @@ pub fn init(options: &OverlayOptions) -> Result<System> {
     let event_loop = EventLoop::new();
     let window = create_window(&event_loop, &options.title)?;
 
-    let vulkan_context = VulkanContext::new(&window, &options.title)?;
-    let command_buffer = {
-        let allocate_info = vk::CommandBufferAllocateInfo::builder()
-            .command_pool(vulkan_context.command_pool)
-            .level(vk::CommandBufferLevel::PRIMARY)
-            .command_buffer_count(1);
-
-        unsafe {
-            vulkan_context
-                .device
-                .allocate_command_buffers(&allocate_info)?[0]
-        }
-    };
-
-    let swapchain = Swapchain::new(&vulkan_context)?;
-    let image_available_semaphore = {
-        let semaphore_info = vk::SemaphoreCreateInfo::builder();
-        unsafe {
-            vulkan_context
-                .device
-                .create_semaphore(&semaphore_info, None)?
-        }
-    };
-    let render_finished_semaphore = {
-        let semaphore_info = vk::SemaphoreCreateInfo::builder();
-        unsafe {
-            vulkan_context
-                .device
-                .create_semaphore(&semaphore_info, None)?
-        }
-    };
-    let fence = {
-        let fence_info = vk::FenceCreateInfo::builder().flags(vk::FenceCreateFlags::SIGNALED);
-        unsafe { vulkan_context.device.create_fence(&fence_info, None)? }
-    };
-
-    let (mut platform, mut imgui) = create_imgui_context(&options)?;
+    let dpi_scale = window.scale_factor() as f32;
+    let (mut platform, mut imgui) = create_imgui_context(&options, dpi_scale)?;
     platform.attach_window(imgui.io_mut(), &window, HiDpiMode::Default);
 
-    let renderer = Renderer::with_default_allocator(
-        &vulkan_context.instance,
-        vulkan_context.physical_device,
-        vulkan_context.device.clone(),
-        vulkan_context.graphics_queue,
-        vulkan_context.command_pool,
-        swapchain.render_pass,
-        &mut imgui,
-        Some(Options {
-            in_flight_frames: 1,
-            ..Default::default()
-        }),
-    )?;
-
-    /* The Vulkan backend can handle 32bit vertex offsets, but forgets to insert that flag... */
-    imgui
-        .io_mut()
-        .backend_flags
-        .insert(imgui::BackendFlags::RENDERER_HAS_VTX_OFFSET);
+    let render_backend_options = RenderBackendOptions {
+        title: options.title.clone(),
+        backend_type: options.render_backend,
+        vulkan_device: options.vulkan_device.clone(),
+    };
+    let render_backend = render_backend_options.create(&window, &mut imgui)?;
 
     Ok(System {
         event_loop,
         window,
 
-        vulkan_context,
-        swapchain,
-        command_buffer,
-        image_available_semaphore,
-        render_finished_semaphore,
-        fence,
-
         imgui,
         platform,
-        renderer,
+        render_backend,
+        render_backend_options,
+        texture_uploader: Default::default(),
 
         window_tracker,
+        font_init: options.font_init.clone(),
+        merged_fonts: options.merged_fonts.clone(),
     })
 }
 
-/// Toggles the overlay noactive and transparent state
-/// according to whenever ImGui wants mouse/cursor grab.
-struct OverlayActiveTracker {
-    currently_active: bool,
-}
-
-impl OverlayActiveTracker {
-    pub fn new() -> Self {
-        Self {
-            currently_active: true,
-        }
-    }
+const PERF_RECORDS: usize = 2048;
 
-    pub fn update(&mut self, window: &Window, io: &Io) {
-        let window_active = io.want_capture_mouse | io.want_capture_keyboard;
-        if window_active == self.currently_active {
-            return;
-        }
+/// Minimum time between two attempts to recreate a lost render backend
+const RENDER_BACKEND_RECOVERY_INTERVAL: Duration = Duration::from_secs(1);
 
-        self.currently_active = window_active;
-        unsafe {
-            let hwnd = HWND(window.hwnd());
-            let mut style = GetWindowLongPtrA(hwnd, GWL_EXSTYLE);
-            if window_active {
-                style &= !((WS_EX_NOACTIVATE | WS_EX_TRANSPARENT).0 as isize);
-            } else {
-                style |= (WS_EX_NOACTIVATE | WS_EX_TRANSPARENT).0 as isize;
-            }
+/// Interval in which the HDR state of the monitor gets checked
+const HDR_CHECK_INTERVAL: Duration = Duration::from_secs(2);
 
-            log::trace!("设置 UI 为活动状态: {window_active}");
-            SetWindowLongPtrA(hwnd, GWL_EXSTYLE, style);
-            if window_active {
-                SetActiveWindow(hwnd);
-            }
-        }
+impl System {
+    /// Get a handle for uploading textures which can be used within imgui.
+    pub fn texture_uploader(&self) -> TextureUploader {
+        self.texture_uploader.clone()
     }
-}
 
-const PERF_RECORDS: usize = 2048;
+    /// Names of the GPUs available to the render backend in enumeration order
+    pub fn physical_devices(&self) -> Vec<String> {
+        self.render_backend.physical_devices().to_vec()
+    }
 
-impl System {
     pub fn main_loop<U, R>(self, mut update: U, mut render: R) -> !
     where
         U: FnMut(&mut SystemRuntimeController) -> bool + 'static,
@@ -375,36 +398,61 @@ impl System {
             event_loop,
             window,
 
-            vulkan_context,
-            mut swapchain,
-            command_buffer,
-            fence,
-            image_available_semaphore,
-            render_finished_semaphore,
-
             imgui,
             mut platform,
-            mut renderer,
+            render_backend,
+            render_backend_options,
+            texture_uploader,
 
             window_tracker,
-            ..
+            font_init,
+            merged_fonts,
         } = self;
         let mut last_frame = Instant::now();
 
+        /* None while the backend is being recreated after the device has been lost */
+        let mut render_backend = Some(render_backend);
+        let mut last_recovery_attempt: Option<Instant> = None;
+
+        /* uploaded textures are kept to restore them on a new render backend */
+        let mut uploaded_textures: Vec<PendingUpload> = Vec::new();
+
         let mut runtime_controller = SystemRuntimeController {
             hwnd: HWND(window.hwnd() as isize),
+            dpi_scale: window.scale_factor() as f32,
+            ui_scale: 1.0,
+            fonts_dirty: false,
             imgui,
 
-            active_tracker: OverlayActiveTracker::new(),
+            input_passthrough: InputPassthrough::new(),
             key_input_system: KeyboardInputSystem::new(),
             mouse_input_system: MouseInputSystem::new(),
+            gamepad_input_system: None,
             window_tracker,
+            texture_uploader,
 
             frame_count: 0,
+            frame_pacer: FramePacer::new(),
+            target_hidden: false,
+            vsync: false,
+            color_space: OverlayColorSpace::Auto,
+            hdr_white_level: 200.0,
+            monitor_hdr: false,
+            monitor_hdr_checked: None,
+            glow: None,
+            monitor_refresh_rate: None,
+            present_timing: Default::default(),
+            present_timing_smoothed: Default::default(),
             debug_overlay_shown: false,
+
+            screen_capture_visible: true,
+            pending_capture: None,
+            notifications_visible: true,
         };
 
-        let mut dirty_swapchain = false;
+        /* exit code to use as soon as all pending dialogs have been dismissed */
+        let mut pending_exit: Option<i32> = None;
+        dialog::set_overlay_running(true);
 
         let mut perf = PerfTracker::new(PERF_RECORDS);
         event_loop.run(move |event, _, control_flow| {
@@ -426,6 +474,8 @@ impl System {
                 // End of event processing
                 Event::MainEventsCleared => {
                     perf.mark("events cleared");
+                    /* the input state gets read within update_state */
+                    let frame_start = Instant::now();
 
                     /* Update */
                     {
@@ -435,31 +485,105 @@ impl System {
                             return;
                         }
 
-                        if !update(&mut runtime_controller) {
-                            *control_flow = ControlFlow::Exit;
-                            return;
+                        if pending_exit.is_none() && !update(&mut runtime_controller) {
+                            pending_exit = Some(0);
+                        }
+
+                        if let Some(exit_code) = pending_exit {
+                            if !dialog::has_pending_dialogs() {
+                                *control_flow = ControlFlow::ExitWithCode(exit_code);
+                                return;
+                            }
+                        }
+
+                        if runtime_controller.fonts_dirty {
+                            runtime_controller.fonts_dirty = false;
+                            runtime_controller.rebuild_fonts(font_init.as_ref(), &merged_fonts);
+                            if let Some(render_backend) = render_backend.as_mut() {
+                                if let Err(error) = render_backend
+                                    .update_fonts_texture(&mut runtime_controller.imgui)
+                                {
+                                    log::error!("Failed to update font texture: {}", error);
+                                }
+                            }
+                        }
+
+                        let uploads = runtime_controller.texture_uploader.take_pending();
+                        let retained_uploads =
+                            (MAX_TEXTURES as usize).saturating_sub(uploaded_textures.len());
+                        uploaded_textures.extend(uploads.iter().take(retained_uploads).cloned());
+                        if let Some(render_backend) = render_backend.as_mut() {
+                            render_backend.process_uploads(uploads);
                         }
 
+                        let deletions =
+                            runtime_controller.texture_uploader.take_pending_deletions();
+                        if !deletions.is_empty() {
+                            uploaded_textures.retain(|upload| {
+                                !deletions
+                                    .iter()
+                                    .any(|texture| texture.ptr_eq(&upload.texture))
+                            });
+                            if let Some(render_backend) = render_backend.as_mut() {
+                                render_backend.delete_textures(deletions);
+                            } else {
+                                /* the textures will not be recreated with the next backend */
+                                for texture in deletions {
+                                    texture.take_texture_id();
+                                }
+                            }
+                        }
                         perf.mark("update");
                     }
 
-                    /* render */
+                    /* recover from a lost device */
+                    if render_backend
+                        .as_ref()
+                        .map_or(true, |render_backend| render_backend.device_lost())
                     {
-                        // If swapchain must be recreated wait for windows to not be minimized anymore
-                        if dirty_swapchain {
-                            let PhysicalSize { width, height } = window.inner_size();
-                            if width > 0 && height > 0 {
-                                swapchain
-                                    .recreate(&vulkan_context)
-                                    .expect("Failed to recreate swapchain");
-                                renderer
-                                    .set_render_pass(swapchain.render_pass)
-                                    .expect("Failed to rebuild renderer pipeline");
-                                dirty_swapchain = false;
-                            } else {
+                        /* the window can only be bound to one swapchain at a time */
+                        render_backend = None;
+
+                        let size = window.inner_size();
+                        let recovery_pending = last_recovery_attempt.map_or(false, |attempt| {
+                            attempt.elapsed() < RENDER_BACKEND_RECOVERY_INTERVAL
+                        });
+                        if size.width == 0 || size.height == 0 || recovery_pending {
+                            return;
+                        }
+
+                        last_recovery_attempt = Some(Instant::now());
+                        log::warn!(
+                            "The render device has been lost. Recreating the render backend."
+                        );
+                        match render_backend_options.create(&window, &mut runtime_controller.imgui)
+                        {
+                            Ok(mut new_backend) => {
+                                new_backend.process_uploads(uploaded_textures.clone());
+                                render_backend = Some(new_backend);
+                            }
+                            Err(error) => {
+                                log::error!("Failed to recreate the render backend: {}", error);
                                 return;
                             }
                         }
+                    }
+                    let render_backend = match render_backend.as_mut() {
+                        Some(render_backend) => render_backend,
+                        None => return,
+                    };
+
+                    /* render */
+                    {
+                        render_backend.set_vsync(runtime_controller.vsync);
+                        render_backend.set_hdr(
+                            runtime_controller.hdr_active(),
+                            runtime_controller.hdr_white_level / SCRGB_REFERENCE_WHITE_NITS,
+                        );
+                        render_backend.set_glow(runtime_controller.glow);
+                        if !render_backend.prepare_frame(&window) {
+                            return;
+                        }
 
                         if let Err(error) =
                             platform.prepare_frame(runtime_controller.imgui.io_mut(), &window)
@@ -470,11 +594,20 @@ impl System {
                         }
 
                         let ui = runtime_controller.imgui.frame();
-                        let run = render(ui);
-                        if !run {
-                            *control_flow = ControlFlow::ExitWithCode(0);
-                            return;
+                        if pending_exit.is_none() && !render(ui) {
+                            if !dialog::has_pending_dialogs() {
+                                *control_flow = ControlFlow::ExitWithCode(0);
+                                return;
+                            }
+
+                            pending_exit = Some(0);
                         }
+                        dialog::render_dialogs(ui);
+                        if runtime_controller.notifications_visible {
+                            notification::render_notifications(ui);
+                        }
+                        let glow_draw_list =
+                            glow::render_glow_layer(ui, runtime_controller.glow.is_some());
                         if runtime_controller.debug_overlay_shown {
                             ui.window("渲染调试")
                                 .position([200.0, 200.0], imgui::Condition::FirstUseEver)
@@ -499,6 +632,16 @@ impl System {
                                     {
                                         perf.set_history_length(history_length);
                                     }
+                                    let timing = &runtime_controller.present_timing_smoothed;
+                                    ui.text(format!(
+                                        "CPU: {:.2}ms  GPU: {}  Present: {:.2}ms  Latency: {:.2}ms",
+                                        timing.cpu_time.as_secs_f32() * 1000.0,
+                                        timing.gpu_time.map_or("n/a".to_string(), |gpu_time| {
+                                            format!("{:.2}ms", gpu_time.as_secs_f32() * 1000.0)
+                                        }),
+                                        timing.present_time.as_secs_f32() * 1000.0,
+                                        timing.latency.as_secs_f32() * 1000.0,
+                                    ));
                                     perf.render(ui, ui.content_region_avail());
                                 });
                         }
@@ -507,99 +650,44 @@ impl System {
                         platform.prepare_render(ui, &window);
                         let draw_data = runtime_controller.imgui.render();
 
-                        unsafe {
-                            vulkan_context
-                                .device
-                                .wait_for_fences(&[fence], true, std::u64::MAX)
-                                .expect("Failed to wait ")
-                        };
-
-                        perf.mark("fence");
-                        let next_image_result = unsafe {
-                            swapchain.loader.acquire_next_image(
-                                swapchain.khr,
-                                std::u64::MAX,
-                                image_available_semaphore,
-                                vk::Fence::null(),
+                        /* the glow layer is rendered by a separate pass of the render backend */
+                        let mut glow_layer = glow_draw_list.map(|glow_draw_list| {
+                            (
+                                FilteredDrawData::new(draw_data, |draw_list| {
+                                    draw_list != glow_draw_list
+                                }),
+                                FilteredDrawData::new(draw_data, |draw_list| {
+                                    draw_list == glow_draw_list
+                                }),
                             )
-                        };
-                        let image_index = match next_image_result {
-                            Ok((image_index, _)) => image_index,
-                            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
-                                dirty_swapchain = true;
-                                return;
-                            }
-                            Err(error) => {
-                                panic!("Error while acquiring next image. Cause: {}", error)
-                            }
+                        });
+                        let (draw_data, glow_draw_data) = match glow_layer.as_mut() {
+                            Some((overlay, glow)) => (overlay.draw_data(), Some(glow.draw_data())),
+                            None => (draw_data, None),
                         };
 
-                        unsafe {
-                            vulkan_context
-                                .device
-                                .reset_fences(&[fence])
-                                .expect("Failed to reset fences")
-                        };
+                        if runtime_controller.pending_capture.is_some() {
+                            render_backend.request_capture();
+                        }
 
-                        let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
-                        let wait_semaphores = [image_available_semaphore];
-                        let signal_semaphores = [render_finished_semaphore];
-
-                        // Re-record commands to draw geometry
-                        record_command_buffers(
-                            &vulkan_context.device,
-                            vulkan_context.command_pool,
-                            command_buffer,
-                            swapchain.framebuffers[image_index as usize],
-                            swapchain.render_pass,
-                            swapchain.extent,
-                            &mut renderer,
-                            &draw_data,
-                        )
-                        .expect("Failed to record command buffer");
-
-                        let command_buffers = [command_buffer];
-                        let submit_info = [vk::SubmitInfo::builder()
-                            .wait_semaphores(&wait_semaphores)
-                            .wait_dst_stage_mask(&wait_stages)
-                            .command_buffers(&command_buffers)
-                            .signal_semaphores(&signal_semaphores)
-                            .build()];
-
-                        perf.mark("before submit");
-                        unsafe {
-                            vulkan_context
-                                .device
-                                .queue_submit(vulkan_context.graphics_queue, &submit_info, fence)
-                                .expect("Failed to submit work to gpu.")
-                        };
-                        perf.mark("after submit");
-
-                        let swapchains = [swapchain.khr];
-                        let images_indices = [image_index];
-                        let present_info = vk::PresentInfoKHR::builder()
-                            .wait_semaphores(&signal_semaphores)
-                            .swapchains(&swapchains)
-                            .image_indices(&images_indices);
-
-                        let present_result = unsafe {
-                            swapchain
-                                .loader
-                                .queue_present(vulkan_context.present_queue, &present_info)
+                        let cpu_time = frame_start.elapsed();
+                        if !render_backend.render_frame(draw_data, glow_draw_data, &mut perf) {
+                            return;
+                        }
+                        let timing = PresentTiming {
+                            cpu_time,
+                            gpu_time: render_backend.gpu_time(),
+                            present_time: render_backend.present_time(),
+                            latency: frame_start.elapsed(),
                         };
-                        match present_result {
-                            Ok(is_suboptimal) if is_suboptimal => {
-                                dirty_swapchain = true;
-                            }
-                            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
-                                dirty_swapchain = true;
-                            }
-                            Err(error) => panic!("Failed to present queue. Cause: {}", error),
-                            _ => {}
+
+                        if let Some(frame) = render_backend.take_capture() {
+                            runtime_controller.complete_capture(&window, frame);
                         }
                         perf.finish("present");
 
-                        runtime_controller.frame_rendered();
+                        runtime_controller.frame_rendered(timing);
+                        runtime_controller.wait_next_frame();
                     }
                 }
                 Event::WindowEvent {
@@ -615,33 +703,134 @@ impl System {
 pub struct SystemRuntimeController {
     pub hwnd: HWND,
 
+    /// Scaling factor of the monitor the overlay is displayed on
+    dpi_scale: f32,
+    /// User defined scale of the UI
+    ui_scale: f32,
+    /// The font atlas must be rebuild before the next frame
+    fonts_dirty: bool,
+
     pub imgui: imgui::Context,
     debug_overlay_shown: bool,
 
-    active_tracker: OverlayActiveTracker,
+    input_passthrough: InputPassthrough,
     mouse_input_system: MouseInputSystem,
     key_input_system: KeyboardInputSystem,
+    gamepad_input_system: Option<GamepadInputSystem>,
 
     window_tracker: WindowTracker,
+    texture_uploader: TextureUploader,
 
     frame_count: u64,
+
+    /// Limits the frames per second
+    frame_pacer: FramePacer,
+    /// The target window is minimized or hidden
+    target_hidden: bool,
+    vsync: bool,
+    monitor_refresh_rate: Option<u32>,
+
+    /// Timings of the last presented frame
+    present_timing: PresentTiming,
+    /// Averaged timings shown within the debug overlay
+    present_timing_smoothed: PresentTiming,
+
+    color_space: OverlayColorSpace,
+    /// Brightness of white UI elements in nits when presenting in HDR
+    hdr_white_level: f32,
+    /// HDR is enabled for the monitor the overlay is displayed on
+    monitor_hdr: bool,
+    monitor_hdr_checked: Option<Instant>,
+
+    /// Blur the glow layer and composite it below the overlay
+    glow: Option<GlowSettings>,
+
+    /// The overlay is not excluded from screen captures
+    screen_capture_visible: bool,
+    /// Capture which will be filled with the next rendered frame
+    pending_capture: Option<FrameCapture>,
+
+    notifications_visible: bool,
 }
 
 impl SystemRuntimeController {
     fn update_state(&mut self, window: &Window) -> bool {
+        self.target_hidden = self.window_tracker.is_target_hidden();
+        self.monitor_refresh_rate = window
+            .current_monitor()
+            .and_then(|monitor| monitor.refresh_rate_millihertz())
+            .map(|millihertz| (millihertz + 500) / 1000);
+
+        if self.color_space == OverlayColorSpace::Auto
+            && self
+                .monitor_hdr_checked
+                .map_or(true, |checked| checked.elapsed() >= HDR_CHECK_INTERVAL)
+        {
+            /* the HDR state can be toggled at any time within the Windows display settings */
+            self.monitor_hdr_checked = Some(Instant::now());
+            let monitor_hdr = window
+                .current_monitor()
+                .map_or(false, |monitor| hdr::is_monitor_hdr(monitor.hmonitor()));
+            if monitor_hdr != self.monitor_hdr {
+                log::debug!("Monitor HDR state changed to {}", monitor_hdr);
+                self.monitor_hdr = monitor_hdr;
+            }
+        }
+
+        let dpi_scale = window.scale_factor() as f32;
+        if dpi_scale != self.dpi_scale {
+            log::debug!("Display scale changed to {:.2}", dpi_scale);
+            self.dpi_scale = dpi_scale;
+            self.fonts_dirty = true;
+
+            /* Windows resizes the overlay when moving it to a monitor with a different DPI */
+            self.window_tracker.mark_force_update();
+        }
+
         self.mouse_input_system.update(window, self.imgui.io_mut());
         self.key_input_system.update(window, self.imgui.io_mut());
-        self.active_tracker.update(window, self.imgui.io());
+        if let Some(gamepad_input_system) = &mut self.gamepad_input_system {
+            gamepad_input_system.update(self.imgui.io_mut());
+        }
+        self.input_passthrough
+            .update(window, self.imgui.io(), self.window_tracker.target_hwnd());
         if !self.window_tracker.update(window) {
             log::info!("目标窗口已关闭。正在退出叠加层...");
             return false;
         }
 
+        /* the resize event of the overlay window is only handled after this frame */
+        let size = window.inner_size().to_logical::<f32>(window.scale_factor());
+        self.imgui.io_mut().display_size = [size.width, size.height];
+
         true
     }
 
-    fn frame_rendered(&mut self) {
+    fn rebuild_fonts(&mut self, font_init: Option<&FontInitCallback>, merged_fonts: &[MergedFont]) {
+        let raster_scale = font_raster_scale(self.dpi_scale * self.ui_scale);
+        self.imgui.fonts().clear();
+        register_fonts(&mut self.imgui, font_init, merged_fonts, raster_scale);
+
+        /* fonts are rasterized in physical pixels, imgui renders in logical pixels */
+        self.imgui.io_mut().font_global_scale = self.ui_scale / raster_scale;
+    }
+
+    fn wait_next_frame(&mut self) {
+        /*
+         * resume instantly when the target gets moved or restored
+         * while waiting with a low (idle) frame limit
+         */
+        let target_hidden = self.target_hidden;
+        let window_tracker = &self.window_tracker;
+        self.frame_pacer.wait(|| {
+            window_tracker.target_changed() || (target_hidden && !window_tracker.is_target_hidden())
+        });
+    }
+
+    fn frame_rendered(&mut self, timing: PresentTiming) {
         self.frame_count += 1;
+        self.present_timing = timing;
+        self.present_timing_smoothed = timing.smoothed(&self.present_timing_smoothed, 0.05);
         if self.frame_count == 1 {
             /* initial frame */
             unsafe { ShowWindow(self.hwnd, SW_SHOWNOACTIVATE) };
@@ -650,13 +839,45 @@ impl SystemRuntimeController {
         }
     }
 
-    pub fn toggle_screen_capture_visibility(&self, should_be_visible: bool) {
+    fn complete_capture(
+        &mut self,
+        window: &Window,
+        frame: std::result::Result<CapturedFrame, String>,
+    ) {
+        let capture = match self.pending_capture.take() {
+            Some(capture) => capture,
+            None => return,
+        };
+
+        let position = window.inner_position().unwrap_or_default();
+        let size = window.inner_size();
+        let screen_bounds = RECT {
+            left: position.x,
+            top: position.y,
+            right: position.x + size.width as i32,
+            bottom: position.y + size.height as i32,
+        };
+
+        let overlay_on_screen = self.screen_capture_visible;
+        std::thread::spawn(move || capture.complete(frame, screen_bounds, overlay_on_screen));
+    }
+
+    /// Capture the next rendered overlay frame.
+    /// If `include_screen` is set, the frame will be composited on top of the screen contents below the overlay.
+    pub fn capture_frame(&mut self, include_screen: bool) -> FrameCapture {
+        let capture = FrameCapture::new(include_screen);
+        self.pending_capture = Some(capture.clone());
+        capture
+    }
+
+    pub fn toggle_screen_capture_visibility(&mut self, should_be_visible: bool) {
         unsafe {
             let (target_state, state_name) = if should_be_visible {
                 (WDA_NONE, "normal")
             } else {
                 (WDA_EXCLUDEFROMCAPTURE, "exclude from capture")
             };
+            self.screen_capture_visible = should_be_visible;
 
             if !SetWindowDisplayAffinity(self.hwnd, target_state).as_bool() {
                 log::warn!(
@@ -668,6 +889,11 @@ impl SystemRuntimeController {
         }
     }
 
+    /// Get a handle for uploading textures which can be used within imgui.
+    pub fn texture_uploader(&self) -> TextureUploader {
+        self.texture_uploader.clone()
+    }
+
     pub fn toggle_debug_overlay(&mut self, visible: bool) {
         self.debug_overlay_shown = visible;
     }
@@ -675,4 +901,191 @@ impl SystemRuntimeController {
     pub fn debug_overlay_shown(&self) -> bool {
         self.debug_overlay_shown
     }
+
+    /// Render the messages queued by `notify`.
+    /// Notifications keep expiring while they are hidden.
+    pub fn set_notifications_visible(&mut self, visible: bool) {
+        self.notifications_visible = visible;
+    }
+
+    pub fn notifications_visible(&self) -> bool {
+        self.notifications_visible
+    }
+
+    /// Receive key events from a low level keyboard / mouse hook instead of polling the key states.
+    /// This ensures no key presses get lost, even if they are shorter than a frame.
+    pub fn set_input_hook(&mut self, enabled: bool) -> Result<()> {
+        self.key_input_system.set_input_hook_enabled(enabled)
+    }
+
+    pub fn input_hook(&self) -> bool {
+        self.key_input_system.input_hook_enabled()
+    }
+
+    /// Poll XInput controllers and report their buttons as imgui gamepad keys
+    pub fn set_gamepad_input(&mut self, enabled: bool) {
+        if enabled == self.gamepad_input_system.is_some() {
+            return;
+        }
+
+        let io = self.imgui.io_mut();
+        if enabled {
+            io.backend_flags.insert(imgui::BackendFlags::HAS_GAMEPAD);
+            self.gamepad_input_system = Some(GamepadInputSystem::new());
+        } else {
+            io.backend_flags.remove(imgui::BackendFlags::HAS_GAMEPAD);
+            if let Some(mut gamepad_input_system) = self.gamepad_input_system.take() {
+                gamepad_input_system.reset(io);
+            }
+        }
+    }
+
+    pub fn gamepad_input(&self) -> bool {
+        self.gamepad_input_system.is_some()
+    }
+
+    /// Scale the UI (fonts and widget sizes) by the given factor.
+    /// The display scaling (DPI) is applied automatically on top of this.
+    pub fn set_ui_scale(&mut self, scale: f32) {
+        let scale = scale.clamp(0.5, 3.0);
+        if (scale - self.ui_scale).abs() < 0.001 {
+            return;
+        }
+
+        self.imgui
+            .style_mut()
+            .scale_all_sizes(scale / self.ui_scale);
+        self.ui_scale = scale;
+        self.fonts_dirty = true;
+    }
+
+    pub fn ui_scale(&self) -> f32 {
+        self.ui_scale
+    }
+
+    /// Limit the overlay frame rate. Zero or None disables the limit.
+    pub fn set_frame_limit(&mut self, limit: Option<u32>) {
+        self.frame_pacer.set_frame_limit(limit);
+    }
+
+    pub fn frame_limit(&self) -> Option<u32> {
+        self.frame_pacer.frame_limit()
+    }
+
+    /// Strategy used to wait for the next frame when the frame rate is limited
+    pub fn set_frame_pacing(&mut self, mode: FramePacingMode) {
+        self.frame_pacer.set_mode(mode);
+    }
+
+    pub fn frame_pacing(&self) -> FramePacingMode {
+        self.frame_pacer.mode()
+    }
+
+    /// Synchronize the overlay with the vertical blank of the monitor.
+    /// Can be combined with the frame limit.
+    pub fn set_vsync(&mut self, enabled: bool) {
+        self.vsync = enabled;
+    }
+
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    /// Color space the overlay is presented in.
+    /// HDR output is only supported by the Vulkan render backend.
+    pub fn set_color_space(&mut self, color_space: OverlayColorSpace) {
+        self.color_space = color_space;
+    }
+
+    pub fn color_space(&self) -> OverlayColorSpace {
+        self.color_space
+    }
+
+    /// Brightness (in nits) of white when presenting in HDR
+    pub fn set_hdr_white_level(&mut self, nits: f32) {
+        self.hdr_white_level = nits.max(SCRGB_REFERENCE_WHITE_NITS);
+    }
+
+    pub fn hdr_white_level(&self) -> f32 {
+        self.hdr_white_level
+    }
+
+    /// Check if the overlay should currently be presented in HDR
+    pub fn hdr_active(&self) -> bool {
+        match self.color_space {
+            OverlayColorSpace::Auto => self.monitor_hdr,
+            OverlayColorSpace::Srgb => false,
+            OverlayColorSpace::Hdr => true,
+        }
+    }
+
+    /// Enable the glow post effect for shapes drawn with `glow_line` and `glow_rect`.
+    /// Only supported by the Vulkan render backend.
+    pub fn set_glow(&mut self, glow: Option<GlowSettings>) {
+        self.glow = glow;
+    }
+
+    pub fn glow(&self) -> Option<GlowSettings> {
+        self.glow
+    }
+
+    /// Timings of the last presented frame
+    pub fn present_timing(&self) -> &PresentTiming {
+        &self.present_timing
+    }
+
+    /// Refresh rate (in Hz) of the monitor the overlay is currently displayed on
+    pub fn monitor_refresh_rate(&self) -> Option<u32> {
+        self.monitor_refresh_rate
+    }
+
+    /// Pin the overlay to a specific monitor instead of following the target window
+    pub fn set_monitor(&mut self, monitor: OverlayMonitor) {
+        self.window_tracker.set_monitor(monitor);
+    }
+
+    pub fn monitor(&self) -> OverlayMonitor {
+        self.window_tracker.monitor()
+    }
+
+    /// Display names of all connected monitors in the order used by `OverlayMonitor::Pinned`
+    pub fn monitor_names(&self) -> &[String] {
+        self.window_tracker.monitor_names()
+    }
+
+    /// Make the whole overlay click-interactive instead of only the ImGui windows.
+    /// Inputs pass through to the game again as soon as this is disabled.
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.input_passthrough.set_interactive(interactive);
+    }
+
+    pub fn interactive(&self) -> bool {
+        self.input_passthrough.interactive()
+    }
+
+    /// Allow ImGui windows to be docked into each other by dragging their title bar
+    pub fn set_docking(&mut self, enabled: bool) {
+        self.imgui
+            .io_mut()
+            .config_flags
+            .set(ConfigFlags::DOCKING_ENABLE, enabled);
+    }
+
+    pub fn docking(&self) -> bool {
+        self.imgui
+            .io()
+            .config_flags
+            .contains(ConfigFlags::DOCKING_ENABLE)
+    }
+
+    /// Check if the target window (or the overlay itself) has the focus
+    pub fn target_focused(&self) -> bool {
+        self.window_tracker.is_focused(self.hwnd)
+    }
+
+    /// Check if the target window is minimized, hidden or cloaked.
+    /// Nothing rendered by the overlay is visible in that case.
+    pub fn target_hidden(&self) -> bool {
+        self.target_hidden
+    }
 }
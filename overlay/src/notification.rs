@@ -0,0 +1,132 @@
+use std::{
+    sync::Mutex,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use imgui::{
+    Condition,
+    StyleColor,
+    StyleVar,
+    WindowFlags,
+};
+
+/// Severity of a notification, determines the accent color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    fn color(&self) -> [f32; 4] {
+        match self {
+            Self::Info => [0.26, 0.59, 0.98, 1.0],
+            Self::Success => [0.2, 0.8, 0.3, 1.0],
+            Self::Warning => [1.0, 0.75, 0.1, 1.0],
+            Self::Error => [0.9, 0.2, 0.2, 1.0],
+        }
+    }
+}
+
+struct Notification {
+    id: u64,
+    level: NotificationLevel,
+    text: String,
+    created: Instant,
+    duration: Duration,
+}
+
+struct NotificationQueue {
+    next_id: u64,
+    notifications: Vec<Notification>,
+}
+
+static NOTIFICATIONS: Mutex<NotificationQueue> = Mutex::new(NotificationQueue {
+    next_id: 0,
+    notifications: Vec::new(),
+});
+
+/// Notifications exceeding this limit are dropped, oldest first
+const MAX_NOTIFICATIONS: usize = 5;
+
+/// Time notifications take to fade in and out
+const FADE_DURATION: Duration = Duration::from_millis(250);
+
+/// Margin to the bottom right screen corner and between notifications
+const NOTIFICATION_MARGIN: f32 = 10.0;
+
+/// Show a short message in the bottom right corner of the overlay.
+/// Identical messages which are still visible get restarted instead of being stacked.
+/// This function can be called from any thread.
+pub fn notify(level: NotificationLevel, text: &str, duration: Duration) {
+    let mut queue = NOTIFICATIONS.lock().unwrap();
+    queue
+        .notifications
+        .retain(|notification| notification.level != level || notification.text != text);
+
+    let id = queue.next_id;
+    queue.next_id += 1;
+    queue.notifications.push(Notification {
+        id,
+        level,
+        text: text.to_string(),
+        created: Instant::now(),
+        duration,
+    });
+
+    let overflow = queue.notifications.len().saturating_sub(MAX_NOTIFICATIONS);
+    queue.notifications.drain(0..overflow);
+}
+
+/// Render all active notifications stacked from the bottom right corner upwards, newest at the bottom
+pub(crate) fn render_notifications(ui: &imgui::Ui) {
+    let mut queue = NOTIFICATIONS.lock().unwrap();
+    let now = Instant::now();
+    queue
+        .notifications
+        .retain(|notification| now - notification.created < notification.duration);
+
+    let display_size = ui.io().display_size;
+    let mut offset_y = display_size[1] - NOTIFICATION_MARGIN;
+    for notification in queue.notifications.iter().rev() {
+        let elapsed = now - notification.created;
+        let remaining = notification.duration - elapsed;
+        let alpha = (elapsed.min(remaining).as_secs_f32() / FADE_DURATION.as_secs_f32()).min(1.0);
+
+        let _alpha = ui.push_style_var(StyleVar::Alpha(alpha));
+        let _border_size = ui.push_style_var(StyleVar::WindowBorderSize(1.0));
+        let _border_color = ui.push_style_color(StyleColor::Border, notification.level.color());
+
+        let window_height = ui
+            .window(format!("##overlay_notification_{}", notification.id))
+            .position(
+                [display_size[0] - NOTIFICATION_MARGIN, offset_y],
+                Condition::Always,
+            )
+            .position_pivot([1.0, 1.0])
+            .bg_alpha(0.85)
+            .flags(
+                WindowFlags::NO_DECORATION
+                    | WindowFlags::NO_INPUTS
+                    | WindowFlags::NO_DOCKING
+                    | WindowFlags::NO_NAV
+                    | WindowFlags::NO_SAVED_SETTINGS
+                    | WindowFlags::NO_FOCUS_ON_APPEARING
+                    | WindowFlags::ALWAYS_AUTO_RESIZE,
+            )
+            .build(|| {
+                let _wrap = ui.push_text_wrap_pos_with_pos(400.0);
+                ui.text(&notification.text);
+
+                ui.window_size()[1]
+            })
+            .unwrap_or(0.0);
+
+        offset_y -= window_height + NOTIFICATION_MARGIN;
+    }
+}
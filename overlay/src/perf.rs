@@ -1,4 +1,7 @@
-use std::time::Instant;
+use std::time::{
+    Duration,
+    Instant,
+};
 
 use imgui::{
     ImColor32,
@@ -101,6 +104,43 @@ impl PerfTracker {
     }
 }
 
+/// Timings of a presented overlay frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PresentTiming {
+    /// CPU time from reading the input state until the frame has been handed to the render backend
+    pub cpu_time: Duration,
+
+    /// GPU time spent rendering the overlay.
+    /// None if the render backend can not measure it (D3D11).
+    pub gpu_time: Option<Duration>,
+
+    /// Time the present call has been blocking
+    pub present_time: Duration,
+
+    /// Time from reading the input state until the frame has been queued for presenting.
+    /// The desktop compositor adds another refresh interval until the frame is visible.
+    pub latency: Duration,
+}
+
+impl PresentTiming {
+    /// Exponential moving average for displaying values which do not flicker
+    pub fn smoothed(&self, previous: &Self, factor: f32) -> Self {
+        let blend = |value: Duration, previous: Duration| {
+            previous.mul_f32(1.0 - factor) + value.mul_f32(factor)
+        };
+
+        Self {
+            cpu_time: blend(self.cpu_time, previous.cpu_time),
+            gpu_time: match (self.gpu_time, previous.gpu_time) {
+                (Some(value), Some(previous)) => Some(blend(value, previous)),
+                (value, _) => value,
+            },
+            present_time: blend(self.present_time, previous.present_time),
+            latency: blend(self.latency, previous.latency),
+        }
+    }
+}
+
 const BAR_COLORS: [ImColor32; 8] = [
     ImColor32::from_rgb(25, 179, 28),   // green
     ImColor32::from_rgb(179, 25, 25),   // redish
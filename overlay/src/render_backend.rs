@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use imgui::DrawData;
+use imgui_winit_support::winit::window::Window;
+
+use crate::{
+    capture::CapturedFrame,
+    d3d11_render::D3D11RenderBackend,
+    glow::GlowSettings,
+    texture::{
+        OverlayTexture,
+        PendingUpload,
+    },
+    vulkan_render::VulkanRenderBackend,
+    PerfTracker,
+    PhysicalDeviceSelector,
+    Result,
+};
+
+/// Graphics API used to render the overlay
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderBackendType {
+    #[default]
+    Vulkan,
+
+    /// Direct3D 11 for systems without a working vulkan-1.dll
+    D3D11,
+}
+
+pub(crate) trait RenderBackend {
+    /// Prepare the backend for the next frame.
+    /// Returns false if no frame can be rendered right now (e.g. the window is minimized).
+    fn prepare_frame(&mut self, window: &Window) -> bool;
+
+    /// Upload the font atlas after it has been rebuild
+    fn update_fonts_texture(&mut self, imgui: &mut imgui::Context) -> Result<()>;
+
+    /// Create the textures queued by the `TextureUploader`
+    fn process_uploads(&mut self, uploads: Vec<PendingUpload>);
+
+    /// Release the textures deleted through the `TextureUploader`
+    fn delete_textures(&mut self, textures: Vec<OverlayTexture>);
+
+    /// Render and present the frame.
+    /// `glow_draw_data` contains the glow layer which has been removed from `draw_data`.
+    /// Returns false if the frame could not be presented and should be skipped.
+    fn render_frame(
+        &mut self,
+        draw_data: &DrawData,
+        glow_draw_data: Option<&DrawData>,
+        perf: &mut PerfTracker,
+    ) -> bool;
+
+    /// Time the present call of the last frame has been blocking
+    fn present_time(&self) -> Duration;
+
+    /// GPU time of the most recent frame which has finished rendering
+    fn gpu_time(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Synchronize presenting with the vertical blank of the monitor
+    fn set_vsync(&mut self, enabled: bool);
+
+    /// Present in linear scRGB instead of sRGB.
+    /// `white_scale` is the brightness of white relative to the scRGB reference white (80 nits).
+    /// Backends without HDR support keep presenting in sRGB.
+    fn set_hdr(&mut self, _enabled: bool, _white_scale: f32) {}
+
+    /// Blur the glow layer and composite it below the overlay.
+    /// Backends without support for the glow pass do not render the glow layer.
+    fn set_glow(&mut self, _glow: Option<GlowSettings>) {}
+
+    /// Read back the next rendered frame before presenting it
+    fn request_capture(&mut self);
+
+    /// Take the read back frame as RGBA with premultiplied alpha
+    fn take_capture(&mut self) -> Option<std::result::Result<CapturedFrame, String>>;
+
+    /// Names of the GPUs the backend can select from
+    fn physical_devices(&self) -> &[String] {
+        &[]
+    }
+
+    /// The GPU device or the window surface has been lost (e.g. driver reset).
+    /// The backend can not render anymore and must be recreated.
+    fn device_lost(&self) -> bool {
+        false
+    }
+}
+
+/// Everything required to (re)create the render backend
+pub(crate) struct RenderBackendOptions {
+    pub title: String,
+    pub backend_type: RenderBackendType,
+    pub vulkan_device: PhysicalDeviceSelector,
+}
+
+impl RenderBackendOptions {
+    pub fn create(
+        &self,
+        window: &Window,
+        imgui: &mut imgui::Context,
+    ) -> Result<Box<dyn RenderBackend>> {
+        log::debug!("Using the {:?} render backend", self.backend_type);
+        Ok(match self.backend_type {
+            RenderBackendType::Vulkan => Box::new(VulkanRenderBackend::new(
+                window,
+                &self.title,
+                &self.vulkan_device,
+                imgui,
+            )?),
+            RenderBackendType::D3D11 => Box::new(D3D11RenderBackend::new(window, imgui)?),
+        })
+    }
+}
@@ -0,0 +1,263 @@
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use ash::vk;
+use imgui::TextureId;
+use imgui_rs_vulkan_renderer::{
+    vulkan::{
+        create_vulkan_descriptor_set,
+        create_vulkan_descriptor_set_layout,
+    },
+    Renderer,
+    RendererResult,
+};
+
+use crate::{
+    vulkan::texture::Texture,
+    VulkanContext,
+};
+
+/// Maximum amount of user textures which can be uploaded to the overlay.
+pub(crate) const MAX_TEXTURES: u32 = 128;
+
+#[derive(Clone)]
+pub(crate) struct PendingUpload {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+    pub texture: OverlayTexture,
+}
+
+/// Handle to a texture which has been queued for upload.
+/// The texture id becomes available once the overlay processed the upload.
+#[derive(Clone, Default)]
+pub struct OverlayTexture {
+    texture_id: Arc<Mutex<Option<TextureId>>>,
+}
+
+impl OverlayTexture {
+    pub fn texture_id(&self) -> Option<TextureId> {
+        *self.texture_id.lock().unwrap()
+    }
+
+    pub(crate) fn set_texture_id(&self, texture_id: TextureId) {
+        *self.texture_id.lock().unwrap() = Some(texture_id);
+    }
+
+    pub(crate) fn take_texture_id(&self) -> Option<TextureId> {
+        self.texture_id.lock().unwrap().take()
+    }
+
+    pub(crate) fn ptr_eq(&self, other: &OverlayTexture) -> bool {
+        Arc::ptr_eq(&self.texture_id, &other.texture_id)
+    }
+}
+
+/// Queue textures for upload to the GPU.
+/// The uploader can be shared across threads, uploads will be processed on the render thread.
+#[derive(Clone, Default)]
+pub struct TextureUploader {
+    pending: Arc<Mutex<Vec<PendingUpload>>>,
+    pending_deletions: Arc<Mutex<Vec<OverlayTexture>>>,
+}
+
+impl TextureUploader {
+    /// Queue an RGBA8 image for upload.
+    pub fn upload(&self, width: u32, height: u32, data: Vec<u8>) -> OverlayTexture {
+        let texture = OverlayTexture::default();
+        if data.len() != (width * height * 4) as usize {
+            log::warn!(
+                "Invalid texture data length {} for {}x{}. Ignoring upload.",
+                data.len(),
+                width,
+                height
+            );
+            return texture;
+        }
+
+        self.pending.lock().unwrap().push(PendingUpload {
+            width,
+            height,
+            data,
+            texture: texture.clone(),
+        });
+        texture
+    }
+
+    /// Queue the deletion of a previously uploaded texture.
+    /// The texture id becomes invalid once the overlay processed the deletion.
+    pub fn delete(&self, texture: &OverlayTexture) {
+        let mut pending = self.pending.lock().unwrap();
+        if let Some(index) = pending
+            .iter()
+            .position(|upload| upload.texture.ptr_eq(texture))
+        {
+            /* the texture has not been uploaded yet */
+            pending.remove(index);
+            return;
+        }
+        drop(pending);
+
+        self.pending_deletions.lock().unwrap().push(texture.clone());
+    }
+
+    pub(crate) fn take_pending(&self) -> Vec<PendingUpload> {
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+
+    pub(crate) fn take_pending_deletions(&self) -> Vec<OverlayTexture> {
+        std::mem::take(&mut *self.pending_deletions.lock().unwrap())
+    }
+}
+
+/// Textures uploaded through the Vulkan renderer
+pub(crate) struct TextureStore {
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+    textures: Vec<(TextureId, Texture, vk::DescriptorSet)>,
+}
+
+impl TextureStore {
+    pub fn new(vulkan_context: &VulkanContext) -> RendererResult<Self> {
+        let descriptor_set_layout = create_vulkan_descriptor_set_layout(&vulkan_context.device)?;
+
+        /* descriptor sets must be freed individually when a texture gets deleted */
+        let pool_sizes = [vk::DescriptorPoolSize {
+            ty: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+            descriptor_count: MAX_TEXTURES,
+        }];
+        let pool_info = vk::DescriptorPoolCreateInfo::builder()
+            .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+            .pool_sizes(&pool_sizes)
+            .max_sets(MAX_TEXTURES);
+        let descriptor_pool = unsafe {
+            vulkan_context
+                .device
+                .create_descriptor_pool(&pool_info, None)?
+        };
+
+        Ok(Self {
+            descriptor_set_layout,
+            descriptor_pool,
+            textures: Default::default(),
+        })
+    }
+
+    pub fn destroy(&mut self, vulkan_context: &VulkanContext) {
+        let device = &vulkan_context.device;
+        for (_, texture, _) in self.textures.iter_mut() {
+            texture.destroy(device);
+        }
+        self.textures.clear();
+
+        unsafe {
+            device.destroy_descriptor_pool(self.descriptor_pool, None);
+            device.destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+        }
+    }
+
+    pub fn process_uploads(
+        &mut self,
+        vulkan_context: &VulkanContext,
+        renderer: &mut Renderer,
+        uploads: Vec<PendingUpload>,
+    ) {
+        for upload in uploads {
+            if self.textures.len() >= MAX_TEXTURES as usize {
+                log::warn!(
+                    "Texture limit of {} reached. Dropping upload.",
+                    MAX_TEXTURES
+                );
+                continue;
+            }
+
+            match self.create_texture(vulkan_context, &upload) {
+                Ok((texture, descriptor_set)) => {
+                    let texture_id = renderer.textures().insert(descriptor_set);
+                    upload.texture.set_texture_id(texture_id);
+                    self.textures.push((texture_id, texture, descriptor_set));
+                }
+                Err(error) => {
+                    log::warn!("Failed to upload texture: {}", error);
+                }
+            }
+        }
+    }
+
+    pub fn delete_textures(
+        &mut self,
+        vulkan_context: &VulkanContext,
+        renderer: &mut Renderer,
+        textures: Vec<OverlayTexture>,
+    ) {
+        let texture_ids = textures
+            .iter()
+            .filter_map(OverlayTexture::take_texture_id)
+            .collect::<Vec<_>>();
+        if texture_ids.is_empty() {
+            return;
+        }
+
+        /* the textures might still be referenced by frames in flight */
+        let device = &vulkan_context.device;
+        if let Err(error) = unsafe { device.device_wait_idle() } {
+            log::warn!("Failed to wait for device idle: {}", error);
+        }
+
+        for texture_id in texture_ids {
+            let index = match self.textures.iter().position(|(id, ..)| *id == texture_id) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            let (_, mut texture, descriptor_set) = self.textures.swap_remove(index);
+            renderer.textures().remove(texture_id);
+            unsafe {
+                if let Err(error) =
+                    device.free_descriptor_sets(self.descriptor_pool, &[descriptor_set])
+                {
+                    log::warn!("Failed to free texture descriptor set: {}", error);
+                }
+            }
+            texture.destroy(device);
+        }
+    }
+
+    fn create_texture(
+        &self,
+        vulkan_context: &VulkanContext,
+        upload: &PendingUpload,
+    ) -> RendererResult<(Texture, vk::DescriptorSet)> {
+        let mem_properties = unsafe {
+            vulkan_context
+                .instance
+                .get_physical_device_memory_properties(vulkan_context.physical_device)
+        };
+
+        let mut texture = Texture::from_rgba8(
+            &vulkan_context.device,
+            vulkan_context.graphics_queue,
+            vulkan_context.command_pool,
+            mem_properties,
+            upload.width,
+            upload.height,
+            &upload.data,
+        )?;
+
+        match create_vulkan_descriptor_set(
+            &vulkan_context.device,
+            self.descriptor_set_layout,
+            self.descriptor_pool,
+            texture.image_view,
+            texture.sampler,
+        ) {
+            Ok(descriptor_set) => Ok((texture, descriptor_set)),
+            Err(error) => {
+                texture.destroy(&vulkan_context.device);
+                Err(error)
+            }
+        }
+    }
+}
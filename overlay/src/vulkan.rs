@@ -85,7 +85,7 @@ mod buffer {
     }
 }
 
-mod texture {
+pub mod texture {
 
     use ash::{
         vk,
@@ -118,7 +118,6 @@ mod texture {
         /// * `width` - The width of the image.
         /// * `height` - The height of the image.
         /// * `data` - The image data.
-        #[allow(dead_code)]
         pub fn from_rgba8(
             device: &Device,
             transfer_queue: vk::Queue,
@@ -320,7 +319,6 @@ mod texture {
         }
 
         /// Free texture's resources.
-        #[allow(dead_code)]
         pub fn destroy(&mut self, device: &Device) {
             unsafe {
                 device.destroy_sampler(self.sampler, None);
@@ -331,7 +329,7 @@ mod texture {
         }
     }
 
-    fn execute_one_time_commands<R, F: FnOnce(vk::CommandBuffer) -> R>(
+    pub(super) fn execute_one_time_commands<R, F: FnOnce(vk::CommandBuffer) -> R>(
         device: &Device,
         queue: vk::Queue,
         pool: vk::CommandPool,
@@ -378,3 +376,867 @@ mod texture {
         Ok(executor_result)
     }
 }
+
+pub mod capture {
+
+    use ash::{
+        vk,
+        Device,
+    };
+    use imgui_rs_vulkan_renderer::RendererResult;
+
+    use super::{
+        buffer::*,
+        texture::execute_one_time_commands,
+    };
+
+    /// Copy a presentable swapchain image into host memory.
+    ///
+    /// The image must have been created with `TRANSFER_SRC` usage and be in the
+    /// `PRESENT_SRC_KHR` layout. Returns the tightly packed pixels in the image format.
+    pub fn read_back_image(
+        device: &Device,
+        queue: vk::Queue,
+        command_pool: vk::CommandPool,
+        mem_properties: vk::PhysicalDeviceMemoryProperties,
+        image: vk::Image,
+        extent: vk::Extent2D,
+    ) -> RendererResult<Vec<u8>> {
+        let size = extent.width as usize * extent.height as usize * 4;
+        let (buffer, buffer_mem) = create_buffer(
+            size,
+            device,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            mem_properties,
+        )?;
+
+        let result = execute_one_time_commands(device, queue, command_pool, |command_buffer| {
+            let subresource_range = vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                layer_count: 1,
+            };
+
+            let to_transfer = vk::ImageMemoryBarrier::builder()
+                .old_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(subresource_range)
+                .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+                .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+                .build();
+
+            let region = vk::BufferImageCopy::builder()
+                .buffer_offset(0)
+                .buffer_row_length(0)
+                .buffer_image_height(0)
+                .image_subresource(vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: 0,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                })
+                .image_offset(vk::Offset3D { x: 0, y: 0, z: 0 })
+                .image_extent(vk::Extent3D {
+                    width: extent.width,
+                    height: extent.height,
+                    depth: 1,
+                })
+                .build();
+
+            let to_present = vk::ImageMemoryBarrier::builder()
+                .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+                .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(subresource_range)
+                .src_access_mask(vk::AccessFlags::TRANSFER_READ)
+                .dst_access_mask(vk::AccessFlags::empty())
+                .build();
+
+            unsafe {
+                device.cmd_pipeline_barrier(
+                    command_buffer,
+                    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    &[to_transfer],
+                );
+                device.cmd_copy_image_to_buffer(
+                    command_buffer,
+                    image,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    buffer,
+                    &[region],
+                );
+                device.cmd_pipeline_barrier(
+                    command_buffer,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                    vk::DependencyFlags::empty(),
+                    &[],
+                    &[],
+                    &[to_present],
+                );
+            }
+        })
+        .and_then(|_| unsafe {
+            let data_ptr =
+                device.map_memory(buffer_mem, 0, size as _, vk::MemoryMapFlags::empty())?;
+            let data = std::slice::from_raw_parts(data_ptr as *const u8, size).to_vec();
+            device.unmap_memory(buffer_mem);
+            Ok(data)
+        });
+
+        unsafe {
+            device.destroy_buffer(buffer, None);
+            device.free_memory(buffer_mem, None);
+        }
+
+        result
+    }
+}
+
+/// Helpers for post processing passes drawing a single fullscreen triangle
+pub mod fullscreen {
+
+    use std::io::Cursor;
+
+    use ash::{
+        vk,
+        Device,
+    };
+
+    use super::buffer::*;
+    use crate::{
+        OverlayError,
+        Result,
+    };
+
+    /* compiled from the GLSL sources next to them */
+    const VERTEX_SHADER: &[u8] = include_bytes!("../resources/shaders/fullscreen.vert.spv");
+
+    /// Image which can be rendered into and sampled afterwards
+    pub struct RenderTarget {
+        image: vk::Image,
+        image_mem: vk::DeviceMemory,
+        pub image_view: vk::ImageView,
+    }
+
+    impl RenderTarget {
+        pub fn new(
+            device: &Device,
+            mem_properties: vk::PhysicalDeviceMemoryProperties,
+            extent: vk::Extent2D,
+            format: vk::Format,
+        ) -> Result<Self> {
+            let image_info = vk::ImageCreateInfo::builder()
+                .image_type(vk::ImageType::TYPE_2D)
+                .extent(vk::Extent3D {
+                    width: extent.width,
+                    height: extent.height,
+                    depth: 1,
+                })
+                .mip_levels(1)
+                .array_layers(1)
+                .format(format)
+                .tiling(vk::ImageTiling::OPTIMAL)
+                .initial_layout(vk::ImageLayout::UNDEFINED)
+                .usage(vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED)
+                .sharing_mode(vk::SharingMode::EXCLUSIVE)
+                .samples(vk::SampleCountFlags::TYPE_1);
+            let image = unsafe { device.create_image(&image_info, None)? };
+
+            let mem_requirements = unsafe { device.get_image_memory_requirements(image) };
+            let alloc_info = vk::MemoryAllocateInfo::builder()
+                .allocation_size(mem_requirements.size)
+                .memory_type_index(find_memory_type(
+                    mem_requirements,
+                    mem_properties,
+                    vk::MemoryPropertyFlags::DEVICE_LOCAL,
+                ));
+            let image_mem = unsafe {
+                let mem = device.allocate_memory(&alloc_info, None)?;
+                device.bind_image_memory(image, mem, 0)?;
+                mem
+            };
+
+            let image_view_info = vk::ImageViewCreateInfo::builder()
+                .image(image)
+                .view_type(vk::ImageViewType::TYPE_2D)
+                .format(format)
+                .subresource_range(vk::ImageSubresourceRange {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    base_mip_level: 0,
+                    level_count: 1,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                });
+            let image_view = unsafe { device.create_image_view(&image_view_info, None)? };
+
+            Ok(Self {
+                image,
+                image_mem,
+                image_view,
+            })
+        }
+
+        pub fn destroy(&mut self, device: &Device) {
+            unsafe {
+                device.destroy_image_view(self.image_view, None);
+                device.destroy_image(self.image, None);
+                device.free_memory(self.image_mem, None);
+            }
+        }
+    }
+
+    pub fn create_sampler(device: &Device, filter: vk::Filter) -> Result<vk::Sampler> {
+        let sampler_info = vk::SamplerCreateInfo::builder()
+            .mag_filter(filter)
+            .min_filter(filter)
+            .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_w(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
+            .max_lod(0.0);
+        Ok(unsafe { device.create_sampler(&sampler_info, None)? })
+    }
+
+    /// One descriptor set (sampled image and sampler) for every source image of a pass
+    pub struct SourceDescriptors {
+        pub layout: vk::DescriptorSetLayout,
+        pool: vk::DescriptorPool,
+        pub sets: Vec<vk::DescriptorSet>,
+    }
+
+    impl SourceDescriptors {
+        /// The images must be in the `SHADER_READ_ONLY_OPTIMAL` layout when being sampled
+        pub fn new(
+            device: &Device,
+            sampler: vk::Sampler,
+            image_views: &[vk::ImageView],
+        ) -> Result<Self> {
+            let bindings = [
+                vk::DescriptorSetLayoutBinding::builder()
+                    .binding(0)
+                    .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+                    .build(),
+                vk::DescriptorSetLayoutBinding::builder()
+                    .binding(1)
+                    .descriptor_type(vk::DescriptorType::SAMPLER)
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+                    .build(),
+            ];
+            let layout_info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+            let layout = unsafe { device.create_descriptor_set_layout(&layout_info, None)? };
+
+            let set_count = image_views.len() as u32;
+            let pool_sizes = [
+                vk::DescriptorPoolSize {
+                    ty: vk::DescriptorType::SAMPLED_IMAGE,
+                    descriptor_count: set_count,
+                },
+                vk::DescriptorPoolSize {
+                    ty: vk::DescriptorType::SAMPLER,
+                    descriptor_count: set_count,
+                },
+            ];
+            let pool_info = vk::DescriptorPoolCreateInfo::builder()
+                .pool_sizes(&pool_sizes)
+                .max_sets(set_count);
+            let pool = unsafe { device.create_descriptor_pool(&pool_info, None)? };
+
+            let set_layouts = vec![layout; image_views.len()];
+            let allocate_info = vk::DescriptorSetAllocateInfo::builder()
+                .descriptor_pool(pool)
+                .set_layouts(&set_layouts);
+            let sets = unsafe { device.allocate_descriptor_sets(&allocate_info)? };
+
+            for (set, image_view) in sets.iter().zip(image_views) {
+                let image_infos = [vk::DescriptorImageInfo {
+                    sampler: vk::Sampler::null(),
+                    image_view: *image_view,
+                    image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                }];
+                let sampler_infos = [vk::DescriptorImageInfo {
+                    sampler,
+                    image_view: vk::ImageView::null(),
+                    image_layout: vk::ImageLayout::UNDEFINED,
+                }];
+                let writes = [
+                    vk::WriteDescriptorSet::builder()
+                        .dst_set(*set)
+                        .dst_binding(0)
+                        .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+                        .image_info(&image_infos)
+                        .build(),
+                    vk::WriteDescriptorSet::builder()
+                        .dst_set(*set)
+                        .dst_binding(1)
+                        .descriptor_type(vk::DescriptorType::SAMPLER)
+                        .image_info(&sampler_infos)
+                        .build(),
+                ];
+                unsafe { device.update_descriptor_sets(&writes, &[]) };
+            }
+
+            Ok(Self { layout, pool, sets })
+        }
+
+        pub fn destroy(&mut self, device: &Device) {
+            unsafe {
+                device.destroy_descriptor_pool(self.pool, None);
+                device.destroy_descriptor_set_layout(self.layout, None);
+            }
+            self.sets.clear();
+        }
+    }
+
+    pub fn create_pipeline_layout(
+        device: &Device,
+        set_layout: vk::DescriptorSetLayout,
+        push_constants_size: u32,
+    ) -> Result<vk::PipelineLayout> {
+        let set_layouts = [set_layout];
+        let push_constant_ranges = [vk::PushConstantRange {
+            stage_flags: vk::ShaderStageFlags::FRAGMENT,
+            offset: 0,
+            size: push_constants_size,
+        }];
+        let pipeline_layout_info = vk::PipelineLayoutCreateInfo::builder()
+            .set_layouts(&set_layouts)
+            .push_constant_ranges(&push_constant_ranges);
+        Ok(unsafe { device.create_pipeline_layout(&pipeline_layout_info, None)? })
+    }
+
+    fn create_shader_module(device: &Device, code: &[u8]) -> Result<vk::ShaderModule> {
+        /* include_bytes does not guarantee the alignment required for the SPIR-V words */
+        let code = ash::util::read_spv(&mut Cursor::new(code))
+            .map_err(OverlayError::VulkanShaderInvalid)?;
+        let create_info = vk::ShaderModuleCreateInfo::builder().code(&code);
+        Ok(unsafe { device.create_shader_module(&create_info, None)? })
+    }
+
+    /// Create a pipeline drawing a fullscreen triangle with the given fragment shader.
+    /// If `blend` is set, the (premultiplied) output gets blended over the existing contents.
+    pub fn create_pipeline(
+        device: &Device,
+        render_pass: vk::RenderPass,
+        pipeline_layout: vk::PipelineLayout,
+        fragment_shader: &[u8],
+        blend: bool,
+    ) -> Result<vk::Pipeline> {
+        let entry_point = c"main";
+        let vertex_module = create_shader_module(device, VERTEX_SHADER)?;
+        let fragment_module = match create_shader_module(device, fragment_shader) {
+            Ok(module) => module,
+            Err(error) => {
+                unsafe { device.destroy_shader_module(vertex_module, None) };
+                return Err(error);
+            }
+        };
+
+        let shader_stages = [
+            vk::PipelineShaderStageCreateInfo::builder()
+                .stage(vk::ShaderStageFlags::VERTEX)
+                .module(vertex_module)
+                .name(entry_point)
+                .build(),
+            vk::PipelineShaderStageCreateInfo::builder()
+                .stage(vk::ShaderStageFlags::FRAGMENT)
+                .module(fragment_module)
+                .name(entry_point)
+                .build(),
+        ];
+
+        /* the fullscreen triangle is generated within the vertex shader */
+        let vertex_input_info = vk::PipelineVertexInputStateCreateInfo::builder();
+        let input_assembly_info = vk::PipelineInputAssemblyStateCreateInfo::builder()
+            .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
+        let viewport_info = vk::PipelineViewportStateCreateInfo::builder()
+            .viewport_count(1)
+            .scissor_count(1);
+        let rasterizer_info = vk::PipelineRasterizationStateCreateInfo::builder()
+            .polygon_mode(vk::PolygonMode::FILL)
+            .line_width(1.0)
+            .cull_mode(vk::CullModeFlags::NONE)
+            .front_face(vk::FrontFace::CLOCKWISE);
+        let multisampling_info = vk::PipelineMultisampleStateCreateInfo::builder()
+            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+        let color_blend_attachments = [vk::PipelineColorBlendAttachmentState::builder()
+            .color_write_mask(vk::ColorComponentFlags::RGBA)
+            .blend_enable(blend)
+            .src_color_blend_factor(vk::BlendFactor::ONE)
+            .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+            .color_blend_op(vk::BlendOp::ADD)
+            .src_alpha_blend_factor(vk::BlendFactor::ONE)
+            .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+            .alpha_blend_op(vk::BlendOp::ADD)
+            .build()];
+        let color_blending_info =
+            vk::PipelineColorBlendStateCreateInfo::builder().attachments(&color_blend_attachments);
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state_info =
+            vk::PipelineDynamicStateCreateInfo::builder().dynamic_states(&dynamic_states);
+
+        let pipeline_info = [vk::GraphicsPipelineCreateInfo::builder()
+            .stages(&shader_stages)
+            .vertex_input_state(&vertex_input_info)
+            .input_assembly_state(&input_assembly_info)
+            .viewport_state(&viewport_info)
+            .rasterization_state(&rasterizer_info)
+            .multisample_state(&multisampling_info)
+            .color_blend_state(&color_blending_info)
+            .dynamic_state(&dynamic_state_info)
+            .layout(pipeline_layout)
+            .render_pass(render_pass)
+            .subpass(0)
+            .build()];
+
+        let pipeline = unsafe {
+            device.create_graphics_pipelines(vk::PipelineCache::null(), &pipeline_info, None)
+        };
+        unsafe {
+            device.destroy_shader_module(vertex_module, None);
+            device.destroy_shader_module(fragment_module, None);
+        }
+
+        Ok(pipeline.map_err(|(_, error)| error)?[0])
+    }
+
+    /// Record the fullscreen draw. Must be called within a render pass.
+    pub fn cmd_draw(
+        device: &Device,
+        command_buffer: vk::CommandBuffer,
+        pipeline: vk::Pipeline,
+        pipeline_layout: vk::PipelineLayout,
+        descriptor_set: vk::DescriptorSet,
+        extent: vk::Extent2D,
+        push_constants: &[u8],
+    ) {
+        unsafe {
+            device.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::GRAPHICS, pipeline);
+            device.cmd_set_viewport(
+                command_buffer,
+                0,
+                &[vk::Viewport {
+                    x: 0.0,
+                    y: 0.0,
+                    width: extent.width as f32,
+                    height: extent.height as f32,
+                    min_depth: 0.0,
+                    max_depth: 1.0,
+                }],
+            );
+            device.cmd_set_scissor(
+                command_buffer,
+                0,
+                &[vk::Rect2D {
+                    offset: vk::Offset2D { x: 0, y: 0 },
+                    extent,
+                }],
+            );
+            device.cmd_bind_descriptor_sets(
+                command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                pipeline_layout,
+                0,
+                &[descriptor_set],
+                &[],
+            );
+            device.cmd_push_constants(
+                command_buffer,
+                pipeline_layout,
+                vk::ShaderStageFlags::FRAGMENT,
+                0,
+                push_constants,
+            );
+            device.cmd_draw(command_buffer, 3, 1, 0, 0);
+        }
+    }
+
+    /// Begin a render pass clearing its single color attachment
+    pub fn cmd_begin_render_pass(
+        device: &Device,
+        command_buffer: vk::CommandBuffer,
+        render_pass: vk::RenderPass,
+        framebuffer: vk::Framebuffer,
+        extent: vk::Extent2D,
+    ) {
+        let render_pass_begin_info = vk::RenderPassBeginInfo::builder()
+            .render_pass(render_pass)
+            .framebuffer(framebuffer)
+            .render_area(vk::Rect2D {
+                offset: vk::Offset2D { x: 0, y: 0 },
+                extent,
+            })
+            .clear_values(&[vk::ClearValue {
+                color: vk::ClearColorValue {
+                    float32: [0.0, 0.0, 0.0, 0.0],
+                },
+            }]);
+
+        unsafe {
+            device.cmd_begin_render_pass(
+                command_buffer,
+                &render_pass_begin_info,
+                vk::SubpassContents::INLINE,
+            )
+        };
+    }
+
+    pub fn create_framebuffer(
+        device: &Device,
+        render_pass: vk::RenderPass,
+        extent: vk::Extent2D,
+        image_view: vk::ImageView,
+    ) -> Result<vk::Framebuffer> {
+        let attachments = [image_view];
+        let framebuffer_info = vk::FramebufferCreateInfo::builder()
+            .render_pass(render_pass)
+            .attachments(&attachments)
+            .width(extent.width)
+            .height(extent.height)
+            .layers(1);
+        Ok(unsafe { device.create_framebuffer(&framebuffer_info, None)? })
+    }
+}
+
+pub mod hdr {
+
+    use ash::{
+        vk,
+        Device,
+    };
+
+    use super::fullscreen::{
+        self,
+        RenderTarget,
+        SourceDescriptors,
+    };
+    use crate::Result;
+
+    /// Format of the image the overlay gets rendered into before being converted to scRGB
+    pub const OFFSCREEN_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+
+    const FRAGMENT_SHADER: &[u8] = include_bytes!("../resources/shaders/hdr_compose.frag.spv");
+
+    /// The ImGui renderer only produces gamma encoded colors.
+    /// Therefore the overlay gets rendered into an offscreen image first,
+    /// which is then converted into the linear scRGB swapchain images.
+    pub struct HdrCompose {
+        target: RenderTarget,
+        sampler: vk::Sampler,
+
+        /// Render pass and framebuffers for the swapchain images
+        render_pass: vk::RenderPass,
+        framebuffers: Vec<vk::Framebuffer>,
+
+        descriptors: SourceDescriptors,
+        pipeline_layout: vk::PipelineLayout,
+        pipeline: vk::Pipeline,
+    }
+
+    impl HdrCompose {
+        /// Create the offscreen image and the conversion pipeline.
+        ///
+        /// # Arguments
+        ///
+        /// * `render_pass` - Render pass targeting the swapchain images. It will be destroyed with this instance.
+        /// * `image_views` - Views of all swapchain images.
+        pub fn new(
+            device: &Device,
+            mem_properties: vk::PhysicalDeviceMemoryProperties,
+            extent: vk::Extent2D,
+            render_pass: vk::RenderPass,
+            image_views: &[vk::ImageView],
+        ) -> Result<Self> {
+            let target = RenderTarget::new(device, mem_properties, extent, OFFSCREEN_FORMAT)?;
+
+            /* the offscreen image has the same size as the swapchain, no filtering required */
+            let sampler = fullscreen::create_sampler(device, vk::Filter::NEAREST)?;
+
+            let framebuffers = image_views
+                .iter()
+                .map(|view| fullscreen::create_framebuffer(device, render_pass, extent, *view))
+                .collect::<Result<Vec<_>>>()?;
+
+            let descriptors = SourceDescriptors::new(device, sampler, &[target.image_view])?;
+            let pipeline_layout = fullscreen::create_pipeline_layout(
+                device,
+                descriptors.layout,
+                std::mem::size_of::<f32>() as u32,
+            )?;
+            let pipeline = fullscreen::create_pipeline(
+                device,
+                render_pass,
+                pipeline_layout,
+                FRAGMENT_SHADER,
+                false,
+            )?;
+
+            Ok(Self {
+                target,
+                sampler,
+
+                render_pass,
+                framebuffers,
+
+                descriptors,
+                pipeline_layout,
+                pipeline,
+            })
+        }
+
+        /// View of the offscreen image the overlay must be rendered into
+        pub fn image_view(&self) -> vk::ImageView {
+            self.target.image_view
+        }
+
+        /// Record the conversion of the offscreen image into the swapchain image.
+        /// The offscreen image must be in the `SHADER_READ_ONLY_OPTIMAL` layout.
+        pub fn cmd_draw(
+            &self,
+            device: &Device,
+            command_buffer: vk::CommandBuffer,
+            image_index: usize,
+            extent: vk::Extent2D,
+            white_scale: f32,
+        ) {
+            fullscreen::cmd_begin_render_pass(
+                device,
+                command_buffer,
+                self.render_pass,
+                self.framebuffers[image_index],
+                extent,
+            );
+            fullscreen::cmd_draw(
+                device,
+                command_buffer,
+                self.pipeline,
+                self.pipeline_layout,
+                self.descriptors.sets[0],
+                extent,
+                &white_scale.to_ne_bytes(),
+            );
+            unsafe { device.cmd_end_render_pass(command_buffer) };
+        }
+
+        pub fn destroy(&mut self, device: &Device) {
+            unsafe {
+                device.destroy_pipeline(self.pipeline, None);
+                device.destroy_pipeline_layout(self.pipeline_layout, None);
+                self.framebuffers
+                    .iter()
+                    .for_each(|fb| device.destroy_framebuffer(*fb, None));
+                self.framebuffers.clear();
+                device.destroy_render_pass(self.render_pass, None);
+                device.destroy_sampler(self.sampler, None);
+            }
+            self.descriptors.destroy(device);
+            self.target.destroy(device);
+        }
+    }
+}
+
+pub mod glow {
+
+    use ash::{
+        vk,
+        Device,
+    };
+
+    use super::fullscreen::{
+        self,
+        RenderTarget,
+        SourceDescriptors,
+    };
+    use crate::Result;
+
+    const FRAGMENT_SHADER: &[u8] = include_bytes!("../resources/shaders/glow_blur.frag.spv");
+
+    /// Blurs the shapes of the glow layer and composites them below the overlay.
+    ///
+    /// The glow layer is drawn into `shapes`, blurred horizontally into `blurred`
+    /// and finally blurred vertically while being blended onto the overlay.
+    pub struct GlowPass {
+        shapes: RenderTarget,
+        blurred: RenderTarget,
+        sampler: vk::Sampler,
+
+        /// Render pass for the offscreen images, compatible with the overlay render pass
+        render_pass: vk::RenderPass,
+        shapes_framebuffer: vk::Framebuffer,
+        blurred_framebuffer: vk::Framebuffer,
+
+        /// Sets for sampling `shapes` and `blurred`
+        descriptors: SourceDescriptors,
+        pipeline_layout: vk::PipelineLayout,
+        blur_pipeline: vk::Pipeline,
+        composite_pipeline: vk::Pipeline,
+    }
+
+    impl GlowPass {
+        /// # Arguments
+        ///
+        /// * `format` - Format of the images the overlay is rendered into.
+        /// * `render_pass` - Render pass for the offscreen images. It will be destroyed with this instance.
+        /// * `overlay_render_pass` - Render pass the overlay is rendered with.
+        pub fn new(
+            device: &Device,
+            mem_properties: vk::PhysicalDeviceMemoryProperties,
+            extent: vk::Extent2D,
+            format: vk::Format,
+            render_pass: vk::RenderPass,
+            overlay_render_pass: vk::RenderPass,
+        ) -> Result<Self> {
+            let shapes = RenderTarget::new(device, mem_properties, extent, format)?;
+            let blurred = RenderTarget::new(device, mem_properties, extent, format)?;
+            let sampler = fullscreen::create_sampler(device, vk::Filter::LINEAR)?;
+
+            let shapes_framebuffer =
+                fullscreen::create_framebuffer(device, render_pass, extent, shapes.image_view)?;
+            let blurred_framebuffer =
+                fullscreen::create_framebuffer(device, render_pass, extent, blurred.image_view)?;
+
+            let descriptors =
+                SourceDescriptors::new(device, sampler, &[shapes.image_view, blurred.image_view])?;
+            let pipeline_layout = fullscreen::create_pipeline_layout(
+                device,
+                descriptors.layout,
+                std::mem::size_of::<[f32; 3]>() as u32,
+            )?;
+            let blur_pipeline = fullscreen::create_pipeline(
+                device,
+                render_pass,
+                pipeline_layout,
+                FRAGMENT_SHADER,
+                false,
+            )?;
+            let composite_pipeline = fullscreen::create_pipeline(
+                device,
+                overlay_render_pass,
+                pipeline_layout,
+                FRAGMENT_SHADER,
+                true,
+            )?;
+
+            Ok(Self {
+                shapes,
+                blurred,
+                sampler,
+
+                render_pass,
+                shapes_framebuffer,
+                blurred_framebuffer,
+
+                descriptors,
+                pipeline_layout,
+                blur_pipeline,
+                composite_pipeline,
+            })
+        }
+
+        /// Begin the render pass for drawing the glow layer shapes.
+        /// Pipelines created for the overlay render pass can be used within this pass.
+        pub fn cmd_begin_shapes(
+            &self,
+            device: &Device,
+            command_buffer: vk::CommandBuffer,
+            extent: vk::Extent2D,
+        ) {
+            fullscreen::cmd_begin_render_pass(
+                device,
+                command_buffer,
+                self.render_pass,
+                self.shapes_framebuffer,
+                extent,
+            );
+        }
+
+        /// Blur the drawn shapes horizontally. Must be called outside of a render pass.
+        pub fn cmd_blur(
+            &self,
+            device: &Device,
+            command_buffer: vk::CommandBuffer,
+            extent: vk::Extent2D,
+            radius: f32,
+        ) {
+            fullscreen::cmd_begin_render_pass(
+                device,
+                command_buffer,
+                self.render_pass,
+                self.blurred_framebuffer,
+                extent,
+            );
+            fullscreen::cmd_draw(
+                device,
+                command_buffer,
+                self.blur_pipeline,
+                self.pipeline_layout,
+                self.descriptors.sets[0],
+                extent,
+                &push_constants([sample_step(radius) / extent.width as f32, 0.0], 1.0),
+            );
+            unsafe { device.cmd_end_render_pass(command_buffer) };
+        }
+
+        /// Blur vertically and blend the glow onto the overlay.
+        /// Must be called within the overlay render pass before anything else has been drawn.
+        pub fn cmd_composite(
+            &self,
+            device: &Device,
+            command_buffer: vk::CommandBuffer,
+            extent: vk::Extent2D,
+            radius: f32,
+            intensity: f32,
+        ) {
+            fullscreen::cmd_draw(
+                device,
+                command_buffer,
+                self.composite_pipeline,
+                self.pipeline_layout,
+                self.descriptors.sets[1],
+                extent,
+                &push_constants([0.0, sample_step(radius) / extent.height as f32], intensity),
+            );
+        }
+
+        pub fn destroy(&mut self, device: &Device) {
+            unsafe {
+                device.destroy_pipeline(self.composite_pipeline, None);
+                device.destroy_pipeline(self.blur_pipeline, None);
+                device.destroy_pipeline_layout(self.pipeline_layout, None);
+                device.destroy_framebuffer(self.shapes_framebuffer, None);
+                device.destroy_framebuffer(self.blurred_framebuffer, None);
+                device.destroy_render_pass(self.render_pass, None);
+                device.destroy_sampler(self.sampler, None);
+            }
+            self.descriptors.destroy(device);
+            self.shapes.destroy(device);
+            self.blurred.destroy(device);
+        }
+    }
+
+    /// The blur samples four steps into each direction
+    fn sample_step(radius: f32) -> f32 {
+        radius.max(0.0) / 4.0
+    }
+
+    fn push_constants(step: [f32; 2], intensity: f32) -> Vec<u8> {
+        [step[0], step[1], intensity]
+            .iter()
+            .flat_map(|value| value.to_ne_bytes())
+            .collect()
+    }
+}
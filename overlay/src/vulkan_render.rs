@@ -1,7 +1,13 @@
-use std::ffi::{
-    c_void,
-    CStr,
-    CString,
+use std::{
+    ffi::{
+        c_void,
+        CStr,
+        CString,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 use ash::{
@@ -18,22 +24,61 @@ use ash::{
     Instance,
 };
 use imgui::DrawData;
-use imgui_rs_vulkan_renderer::Renderer;
-use imgui_winit_support::winit::window::Window;
+use imgui_rs_vulkan_renderer::{
+    Options,
+    Renderer,
+};
+use imgui_winit_support::winit::{
+    dpi::PhysicalSize,
+    window::Window,
+};
 use raw_window_handle::{
     HasRawDisplayHandle,
     HasRawWindowHandle,
 };
 
 use crate::{
+    capture::CapturedFrame,
     error::Result,
+    glow::GlowSettings,
+    render_backend::RenderBackend,
+    texture::{
+        OverlayTexture,
+        PendingUpload,
+        TextureStore,
+    },
+    vulkan::{
+        capture,
+        glow::GlowPass,
+        hdr::{
+            self,
+            HdrCompose,
+        },
+    },
     vulkan_driver::get_vulkan_entry,
     OverlayError,
+    PerfTracker,
 };
 
 const WIDTH: u32 = 1024;
 const HEIGHT: u32 = 768;
 
+/// Selects the GPU used by the Vulkan render backend.
+/// Systems with hybrid graphics should use the GPU the game is rendered on,
+/// as presenting across adapters causes stutter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PhysicalDeviceSelector {
+    /// Use the first device which is able to render the overlay
+    #[default]
+    Auto,
+
+    /// Index of the device in enumeration order
+    Index(usize),
+
+    /// Case insensitive part of the device name
+    Name(String),
+}
+
 pub struct VulkanContext {
     _entry: Entry,
     pub instance: Instance,
@@ -42,6 +87,8 @@ pub struct VulkanContext {
     surface: Surface,
     surface_khr: vk::SurfaceKHR,
     pub physical_device: vk::PhysicalDevice,
+    /// Names of all physical devices in enumeration order
+    pub physical_device_names: Vec<String>,
     graphics_q_index: u32,
     present_q_index: u32,
     pub device: Device,
@@ -51,7 +98,11 @@ pub struct VulkanContext {
 }
 
 impl VulkanContext {
-    pub fn new(window: &Window, name: &str) -> crate::error::Result<Self> {
+    pub fn new(
+        window: &Window,
+        name: &str,
+        device_selector: &PhysicalDeviceSelector,
+    ) -> crate::error::Result<Self> {
         // Vulkan instance
         let entry = get_vulkan_entry()?;
         let (instance, debug_utils, debug_utils_messenger) =
@@ -71,11 +122,12 @@ impl VulkanContext {
         };
 
         // Vulkan physical device and queue families indices (graphics and present)
-        let (physical_device, graphics_q_index, present_q_index) =
+        let (physical_device, graphics_q_index, present_q_index, physical_device_names) =
             create_vulkan_physical_device_and_get_graphics_and_present_qs_indices(
                 &instance,
                 &surface,
                 surface_khr,
+                device_selector,
             )?;
 
         // Vulkan logical device and queues
@@ -103,6 +155,7 @@ impl VulkanContext {
             surface,
             surface_khr,
             physical_device,
+            physical_device_names,
             graphics_q_index,
             present_q_index,
             device,
@@ -131,67 +184,146 @@ pub struct Swapchain {
     pub loader: SwapchainLoader,
     pub extent: vk::Extent2D,
     pub khr: vk::SwapchainKHR,
+    pub format: vk::Format,
+    /// The images can be copied for capturing a frame
+    pub readable: bool,
     pub images: Vec<vk::Image>,
     pub image_views: Vec<vk::ImageView>,
+    /// Render pass ImGui renders into
     pub render_pass: vk::RenderPass,
+    /// One framebuffer per swapchain image or the offscreen framebuffer when presenting in HDR
     pub framebuffers: Vec<vk::Framebuffer>,
+    /// Conversion into scRGB if the swapchain has been created for HDR
+    pub hdr: Option<HdrCompose>,
+    /// Blur pass if the swapchain has been created with glow enabled
+    pub glow: Option<GlowPass>,
 }
 
 impl Swapchain {
-    pub fn new(vulkan_context: &VulkanContext) -> Result<Self> {
-        // Swapchain
-        let (loader, khr, extent, format, images, image_views) =
-            create_vulkan_swapchain(&vulkan_context)?;
+    pub fn new(vulkan_context: &VulkanContext, vsync: bool, hdr: bool, glow: bool) -> Result<Self> {
+        let device = &vulkan_context.device;
+        let mem_properties = unsafe {
+            vulkan_context
+                .instance
+                .get_physical_device_memory_properties(vulkan_context.physical_device)
+        };
 
-        // Renderpass
-        let render_pass = create_vulkan_render_pass(&vulkan_context.device, format)?;
+        // Swapchain
+        let (loader, khr, extent, surface_format, readable, images, image_views) =
+            create_vulkan_swapchain(vulkan_context, vsync, hdr)?;
+
+        // Renderpass and framebuffers
+        let (render_pass, framebuffers, hdr) =
+            if surface_format.color_space == vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT {
+                let compose_render_pass = create_vulkan_render_pass(
+                    device,
+                    surface_format.format,
+                    vk::ImageLayout::PRESENT_SRC_KHR,
+                )?;
+                let hdr_compose = HdrCompose::new(
+                    device,
+                    mem_properties,
+                    extent,
+                    compose_render_pass,
+                    &image_views,
+                )?;
+
+                let render_pass = create_vulkan_render_pass(
+                    device,
+                    hdr::OFFSCREEN_FORMAT,
+                    vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                )?;
+                let framebuffers = create_vulkan_framebuffers(
+                    device,
+                    render_pass,
+                    extent,
+                    &[hdr_compose.image_view()],
+                )?;
+                (render_pass, framebuffers, Some(hdr_compose))
+            } else {
+                let render_pass = create_vulkan_render_pass(
+                    device,
+                    surface_format.format,
+                    vk::ImageLayout::PRESENT_SRC_KHR,
+                )?;
+                let framebuffers =
+                    create_vulkan_framebuffers(device, render_pass, extent, &image_views)?;
+                (render_pass, framebuffers, None)
+            };
 
-        // Framebuffers
-        let framebuffers =
-            create_vulkan_framebuffers(&vulkan_context.device, render_pass, extent, &image_views)?;
+        let glow = if glow {
+            /* the glow shapes are rendered with the ImGui pipeline, hence the formats must match */
+            let format = if hdr.is_some() {
+                hdr::OFFSCREEN_FORMAT
+            } else {
+                surface_format.format
+            };
+            let glow_render_pass = create_vulkan_render_pass(
+                device,
+                format,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            )?;
+            Some(GlowPass::new(
+                device,
+                mem_properties,
+                extent,
+                format,
+                glow_render_pass,
+                render_pass,
+            )?)
+        } else {
+            None
+        };
 
         Ok(Self {
             loader,
             extent,
             khr,
+            format: surface_format.format,
+            readable,
             images,
             image_views,
             render_pass,
             framebuffers,
+            hdr,
+            glow,
         })
     }
 
-    pub fn recreate(&mut self, vulkan_context: &VulkanContext) -> Result<()> {
+    pub fn recreate(
+        &mut self,
+        vulkan_context: &VulkanContext,
+        vsync: bool,
+        hdr: bool,
+        glow: bool,
+    ) -> Result<()> {
         log::debug!("Recreating the swapchain");
 
         unsafe { vulkan_context.device.device_wait_idle()? };
 
         self.destroy(vulkan_context);
-
-        // Swapchain
-        let (loader, khr, extent, format, images, image_views) =
-            create_vulkan_swapchain(vulkan_context)?;
-
-        // Renderpass
-        let render_pass = create_vulkan_render_pass(&vulkan_context.device, format)?;
-
-        // Framebuffers
-        let framebuffers =
-            create_vulkan_framebuffers(&vulkan_context.device, render_pass, extent, &image_views)?;
-
-        self.loader = loader;
-        self.extent = extent;
-        self.khr = khr;
-        self.images = images;
-        self.image_views = image_views;
-        self.render_pass = render_pass;
-        self.framebuffers = framebuffers;
+        *self = Self::new(vulkan_context, vsync, hdr, glow)?;
 
         Ok(())
     }
 
+    /// The framebuffer ImGui should render into for the given swapchain image
+    pub fn framebuffer(&self, image_index: usize) -> vk::Framebuffer {
+        if self.hdr.is_some() {
+            self.framebuffers[0]
+        } else {
+            self.framebuffers[image_index]
+        }
+    }
+
     fn destroy(&mut self, vulkan_context: &VulkanContext) {
         unsafe {
+            if let Some(mut glow) = self.glow.take() {
+                glow.destroy(&vulkan_context.device);
+            }
+            if let Some(mut hdr) = self.hdr.take() {
+                hdr.destroy(&vulkan_context.device);
+            }
             self.framebuffers
                 .iter()
                 .for_each(|fb| vulkan_context.device.destroy_framebuffer(*fb, None));
@@ -199,11 +331,13 @@ impl Swapchain {
             vulkan_context
                 .device
                 .destroy_render_pass(self.render_pass, None);
+            self.render_pass = vk::RenderPass::null();
             self.image_views
                 .iter()
                 .for_each(|v| vulkan_context.device.destroy_image_view(*v, None));
             self.image_views.clear();
             self.loader.destroy_swapchain(self.khr, None);
+            self.khr = vk::SwapchainKHR::null();
         }
     }
 }
@@ -238,6 +372,20 @@ fn create_vulkan_instance(
         ash_window::enumerate_required_extensions(window.raw_display_handle())?.to_vec();
     extension_names.push(DebugUtils::name().as_ptr());
 
+    /* required to present in scRGB (HDR) */
+    let colorspace_supported = entry
+        .enumerate_instance_extension_properties(None)?
+        .iter()
+        .any(|extension| unsafe {
+            CStr::from_ptr(extension.extension_name.as_ptr())
+                == vk::ExtSwapchainColorspaceFn::name()
+        });
+    if colorspace_supported {
+        extension_names.push(vk::ExtSwapchainColorspaceFn::name().as_ptr());
+    } else {
+        log::debug!("VK_EXT_swapchain_colorspace is not supported, HDR will not be available");
+    }
+
     let enabled_layer_names = Vec::new();
     /* If debug, may add some extra layers here */
 
@@ -294,98 +442,147 @@ unsafe extern "system" fn vulkan_debug_callback(
     vk::FALSE
 }
 
-fn create_vulkan_physical_device_and_get_graphics_and_present_qs_indices(
+/// Returns the graphics and present queue family indices if the device can render to the surface
+fn get_graphics_and_present_qs_indices(
     instance: &Instance,
     surface: &Surface,
     surface_khr: vk::SurfaceKHR,
-) -> crate::Result<(vk::PhysicalDevice, u32, u32)> {
-    log::debug!("Creating vulkan physical device");
-    let devices = unsafe { instance.enumerate_physical_devices()? };
+    device: vk::PhysicalDevice,
+) -> Option<(u32, u32)> {
     let mut graphics = None;
     let mut present = None;
 
-    log::debug!("可用设备:");
-    for device in &devices {
-        unsafe {
-            let props = instance.get_physical_device_properties(*device);
-            let device_name = CStr::from_ptr(props.device_name.as_ptr());
-            log::debug!("- {device_name:?}");
+    // Does device supports graphics and present queues
+    let props = unsafe { instance.get_physical_device_queue_family_properties(device) };
+    for (index, family) in props.iter().filter(|f| f.queue_count > 0).enumerate() {
+        let index = index as u32;
+        graphics = None;
+        present = None;
+
+        if family.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+            && family.queue_flags.contains(vk::QueueFlags::COMPUTE)
+            && graphics.is_none()
+        {
+            graphics = Some(index);
+        }
+
+        let present_support = unsafe {
+            surface
+                .get_physical_device_surface_support(device, index, surface_khr)
+                .expect("Failed to get surface support")
+        };
+        if present_support && present.is_none() {
+            present = Some(index);
+        }
+
+        if graphics.is_some() && present.is_some() {
+            break;
         }
     }
 
-    let device = devices
-        .into_iter()
-        .find(|device| {
-            let device = *device;
+    // Does device support desired extensions
+    let extension_props = unsafe {
+        instance
+            .enumerate_device_extension_properties(device)
+            .expect("Failed to get device ext properties")
+    };
+    let extention_support = extension_props.iter().any(|ext| {
+        let name = unsafe { CStr::from_ptr(ext.extension_name.as_ptr()) };
+        SwapchainLoader::name() == name
+    });
+
+    // Does the device have available formats for the given surface
+    let formats = unsafe {
+        surface
+            .get_physical_device_surface_formats(device, surface_khr)
+            .expect("Failed to get physical device surface formats")
+    };
 
-            // Does device supports graphics and present queues
-            let props = unsafe { instance.get_physical_device_queue_family_properties(device) };
-            for (index, family) in props.iter().filter(|f| f.queue_count > 0).enumerate() {
-                let index = index as u32;
-                graphics = None;
-                present = None;
+    // Does the device have available present modes for the given surface
+    let present_modes = unsafe {
+        surface
+            .get_physical_device_surface_present_modes(device, surface_khr)
+            .expect("Failed to get physical device surface present modes")
+    };
 
-                if family.queue_flags.contains(vk::QueueFlags::GRAPHICS)
-                    && family.queue_flags.contains(vk::QueueFlags::COMPUTE)
-                    && graphics.is_none()
-                {
-                    graphics = Some(index);
-                }
+    if !extention_support || formats.is_empty() || present_modes.is_empty() {
+        return None;
+    }
 
-                let present_support = unsafe {
-                    surface
-                        .get_physical_device_surface_support(device, index, surface_khr)
-                        .expect("Failed to get surface support")
-                };
-                if present_support && present.is_none() {
-                    present = Some(index);
-                }
+    Some((graphics?, present?))
+}
 
-                if graphics.is_some() && present.is_some() {
-                    break;
-                }
-            }
+fn create_vulkan_physical_device_and_get_graphics_and_present_qs_indices(
+    instance: &Instance,
+    surface: &Surface,
+    surface_khr: vk::SurfaceKHR,
+    selector: &PhysicalDeviceSelector,
+) -> crate::Result<(vk::PhysicalDevice, u32, u32, Vec<String>)> {
+    log::debug!("Creating vulkan physical device");
+    let devices = unsafe { instance.enumerate_physical_devices()? };
 
-            // Does device support desired extensions
-            let extension_props = unsafe {
-                instance
-                    .enumerate_device_extension_properties(device)
-                    .expect("Failed to get device ext properties")
-            };
-            let extention_support = extension_props.iter().any(|ext| {
-                let name = unsafe { CStr::from_ptr(ext.extension_name.as_ptr()) };
-                SwapchainLoader::name() == name
-            });
-
-            // Does the device have available formats for the given surface
-            let formats = unsafe {
-                surface
-                    .get_physical_device_surface_formats(device, surface_khr)
-                    .expect("Failed to get physical device surface formats")
-            };
+    log::debug!("可用设备:");
+    let device_names = devices
+        .iter()
+        .enumerate()
+        .map(|(index, device)| unsafe {
+            let props = instance.get_physical_device_properties(*device);
+            let device_name = CStr::from_ptr(props.device_name.as_ptr())
+                .to_string_lossy()
+                .into_owned();
+            log::debug!("- #{index} {device_name:?}");
+            device_name
+        })
+        .collect::<Vec<_>>();
+
+    let requested = match selector {
+        PhysicalDeviceSelector::Auto => None,
+        PhysicalDeviceSelector::Index(index) => Some(*index).filter(|index| *index < devices.len()),
+        PhysicalDeviceSelector::Name(name) => {
+            let name = name.to_lowercase();
+            device_names
+                .iter()
+                .position(|device_name| device_name.to_lowercase().contains(&name))
+        }
+    };
 
-            // Does the device have available present modes for the given surface
-            let present_modes = unsafe {
-                surface
-                    .get_physical_device_surface_present_modes(device, surface_khr)
-                    .expect("Failed to get physical device surface present modes")
-            };
+    let requested = match requested {
+        Some(index) => {
+            match get_graphics_and_present_qs_indices(
+                instance,
+                surface,
+                surface_khr,
+                devices[index],
+            ) {
+                Some((graphics, present)) => Some((index, graphics, present)),
+                None => {
+                    log::warn!(
+                        "请求的物理设备 {:?} 无法用于渲染叠加层，将自动选择设备",
+                        device_names[index]
+                    );
+                    None
+                }
+            }
+        }
+        None => {
+            if *selector != PhysicalDeviceSelector::Auto {
+                log::warn!("未找到请求的物理设备 {:?}，将自动选择设备", selector);
+            }
+            None
+        }
+    };
 
-            graphics.is_some()
-                && present.is_some()
-                && extention_support
-                && !formats.is_empty()
-                && !present_modes.is_empty()
+    let (index, graphics, present) = requested
+        .or_else(|| {
+            devices.iter().enumerate().find_map(|(index, device)| {
+                get_graphics_and_present_qs_indices(instance, surface, surface_khr, *device)
+                    .map(|(graphics, present)| (index, graphics, present))
+            })
         })
         .expect("Could not find a suitable device");
 
-    unsafe {
-        let props = instance.get_physical_device_properties(device);
-        let device_name = CStr::from_ptr(props.device_name.as_ptr());
-        log::debug!("选定物理设备: {device_name:?}");
-    }
-
-    Ok((device, graphics.unwrap(), present.unwrap()))
+    log::debug!("选定物理设备: {:?}", device_names[index]);
+    Ok((devices[index], graphics, present, device_names))
 }
 
 fn create_vulkan_device_and_graphics_and_present_qs(
@@ -426,11 +623,14 @@ fn create_vulkan_device_and_graphics_and_present_qs(
 
 fn create_vulkan_swapchain(
     vulkan_context: &VulkanContext,
+    vsync: bool,
+    hdr: bool,
 ) -> Result<(
     SwapchainLoader,
     vk::SwapchainKHR,
     vk::Extent2D,
-    vk::Format,
+    vk::SurfaceFormatKHR,
+    bool,
     Vec<vk::Image>,
     Vec<vk::ImageView>,
 )> {
@@ -443,7 +643,18 @@ fn create_vulkan_swapchain(
                 vulkan_context.surface_khr,
             )?
         };
-        if formats.len() == 1 && formats[0].format == vk::Format::UNDEFINED {
+        let hdr_format = formats.iter().find(|format| {
+            format.format == vk::Format::R16G16B16A16_SFLOAT
+                && format.color_space == vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT
+        });
+
+        if hdr && hdr_format.is_none() {
+            log::warn!("The surface does not support scRGB. Presenting the overlay in sRGB.");
+        }
+
+        if let Some(format) = hdr_format.filter(|_| hdr) {
+            *format
+        } else if formats.len() == 1 && formats[0].format == vk::Format::UNDEFINED {
             vk::SurfaceFormatKHR {
                 format: vk::Format::B8G8R8A8_UNORM,
                 color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
@@ -470,7 +681,7 @@ fn create_vulkan_swapchain(
                     vulkan_context.surface_khr,
                 )?
         };
-        if present_modes.contains(&vk::PresentModeKHR::IMMEDIATE) {
+        if !vsync && present_modes.contains(&vk::PresentModeKHR::IMMEDIATE) {
             vk::PresentModeKHR::IMMEDIATE
         } else {
             vk::PresentModeKHR::FIFO
@@ -505,6 +716,16 @@ fn create_vulkan_swapchain(
     let image_count = capabilities.min_image_count;
     log::debug!("交换链图像数量: {image_count:?}");
 
+    // Swapchain images are copied when capturing a frame
+    let readable = capabilities
+        .supported_usage_flags
+        .contains(vk::ImageUsageFlags::TRANSFER_SRC);
+    let image_usage = if readable {
+        vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC
+    } else {
+        vk::ImageUsageFlags::COLOR_ATTACHMENT
+    };
+
     // Swapchain
     let families_indices = [
         vulkan_context.graphics_q_index,
@@ -518,7 +739,7 @@ fn create_vulkan_swapchain(
             .image_color_space(format.color_space)
             .image_extent(extent)
             .image_array_layers(1)
-            .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT);
+            .image_usage(image_usage);
 
         builder = if vulkan_context.graphics_q_index != vulkan_context.present_q_index {
             builder
@@ -563,13 +784,18 @@ fn create_vulkan_swapchain(
         swapchain,
         swapchain_khr,
         extent,
-        format.format,
+        format,
+        readable,
         images,
         views,
     ))
 }
 
-fn create_vulkan_render_pass(device: &Device, format: vk::Format) -> Result<vk::RenderPass> {
+fn create_vulkan_render_pass(
+    device: &Device,
+    format: vk::Format,
+    final_layout: vk::ImageLayout,
+) -> Result<vk::RenderPass> {
     log::debug!("创建 Vulkan 渲染传递");
     let attachment_descs = [vk::AttachmentDescription::builder()
         .format(format)
@@ -577,7 +803,7 @@ fn create_vulkan_render_pass(device: &Device, format: vk::Format) -> Result<vk::
         .load_op(vk::AttachmentLoadOp::CLEAR)
         .store_op(vk::AttachmentStoreOp::STORE)
         .initial_layout(vk::ImageLayout::UNDEFINED)
-        .final_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+        .final_layout(final_layout)
         .build()];
 
     let color_attachment_refs = [vk::AttachmentReference::builder()
@@ -590,7 +816,7 @@ fn create_vulkan_render_pass(device: &Device, format: vk::Format) -> Result<vk::
         .color_attachments(&color_attachment_refs)
         .build()];
 
-    let subpass_deps = [vk::SubpassDependency::builder()
+    let mut subpass_deps = vec![vk::SubpassDependency::builder()
         .src_subpass(vk::SUBPASS_EXTERNAL)
         .dst_subpass(0)
         .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
@@ -601,6 +827,20 @@ fn create_vulkan_render_pass(device: &Device, format: vk::Format) -> Result<vk::
         )
         .build()];
 
+    if final_layout == vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL {
+        /* the rendered image gets sampled by the following (glow or HDR compose) render pass */
+        subpass_deps.push(
+            vk::SubpassDependency::builder()
+                .src_subpass(0)
+                .dst_subpass(vk::SUBPASS_EXTERNAL)
+                .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+                .dst_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+                .dst_access_mask(vk::AccessFlags::SHADER_READ)
+                .build(),
+        );
+    }
+
     let render_pass_info = vk::RenderPassCreateInfo::builder()
         .attachments(&attachment_descs)
         .subpasses(&subpass_descs)
@@ -635,9 +875,11 @@ pub fn record_command_buffers(
     device: &Device,
     command_pool: vk::CommandPool,
     command_buffer: vk::CommandBuffer,
-    framebuffer: vk::Framebuffer,
-    render_pass: vk::RenderPass,
-    extent: vk::Extent2D,
+    timestamp_pool: Option<vk::QueryPool>,
+    swapchain: &Swapchain,
+    image_index: usize,
+    hdr_white_scale: f32,
+    glow: Option<(GlowSettings, &DrawData)>,
     renderer: &mut Renderer,
     draw_data: &DrawData,
 ) -> Result<()> {
@@ -647,12 +889,33 @@ pub fn record_command_buffers(
         vk::CommandBufferBeginInfo::builder().flags(vk::CommandBufferUsageFlags::SIMULTANEOUS_USE);
     unsafe { device.begin_command_buffer(command_buffer, &command_buffer_begin_info)? };
 
+    if let Some(timestamp_pool) = timestamp_pool {
+        unsafe {
+            device.cmd_reset_query_pool(command_buffer, timestamp_pool, 0, 2);
+            device.cmd_write_timestamp(
+                command_buffer,
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                timestamp_pool,
+                0,
+            );
+        }
+    }
+
+    let glow = swapchain.glow.as_ref().zip(glow);
+    if let Some((glow_pass, (settings, glow_draw_data))) = glow {
+        glow_pass.cmd_begin_shapes(device, command_buffer, swapchain.extent);
+        renderer.cmd_draw(command_buffer, glow_draw_data)?;
+        unsafe { device.cmd_end_render_pass(command_buffer) };
+
+        glow_pass.cmd_blur(device, command_buffer, swapchain.extent, settings.radius);
+    }
+
     let render_pass_begin_info = vk::RenderPassBeginInfo::builder()
-        .render_pass(render_pass)
-        .framebuffer(framebuffer)
+        .render_pass(swapchain.render_pass)
+        .framebuffer(swapchain.framebuffer(image_index))
         .render_area(vk::Rect2D {
             offset: vk::Offset2D { x: 0, y: 0 },
-            extent,
+            extent: swapchain.extent,
         })
         .clear_values(&[vk::ClearValue {
             color: vk::ClearColorValue {
@@ -668,11 +931,521 @@ pub fn record_command_buffers(
         )
     };
 
+    if let Some((glow_pass, (settings, _))) = glow {
+        glow_pass.cmd_composite(
+            device,
+            command_buffer,
+            swapchain.extent,
+            settings.radius,
+            settings.intensity,
+        );
+    }
+
     renderer.cmd_draw(command_buffer, draw_data)?;
 
     unsafe { device.cmd_end_render_pass(command_buffer) };
 
+    if let Some(hdr) = &swapchain.hdr {
+        hdr.cmd_draw(
+            device,
+            command_buffer,
+            image_index,
+            swapchain.extent,
+            hdr_white_scale,
+        );
+    }
+
+    if let Some(timestamp_pool) = timestamp_pool {
+        unsafe {
+            device.cmd_write_timestamp(
+                command_buffer,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                timestamp_pool,
+                1,
+            )
+        };
+    }
+
     unsafe { device.end_command_buffer(command_buffer)? };
 
     Ok(())
 }
+
+/// Create a query pool for two timestamps.
+/// Returns None if the graphics queue does not support timestamps.
+fn create_timestamp_query_pool(
+    vulkan_context: &VulkanContext,
+) -> Result<(Option<vk::QueryPool>, f32)> {
+    let instance = &vulkan_context.instance;
+    let queue_families = unsafe {
+        instance.get_physical_device_queue_family_properties(vulkan_context.physical_device)
+    };
+    let timestamp_valid_bits = queue_families
+        .get(vulkan_context.graphics_q_index as usize)
+        .map_or(0, |family| family.timestamp_valid_bits);
+    let timestamp_period = unsafe {
+        instance
+            .get_physical_device_properties(vulkan_context.physical_device)
+            .limits
+            .timestamp_period
+    };
+
+    if timestamp_valid_bits == 0 || timestamp_period <= 0.0 {
+        log::debug!("The graphics queue does not support timestamps");
+        return Ok((None, timestamp_period));
+    }
+
+    let create_info = vk::QueryPoolCreateInfo::builder()
+        .query_type(vk::QueryType::TIMESTAMP)
+        .query_count(2);
+    let pool = unsafe {
+        vulkan_context
+            .device
+            .create_query_pool(&create_info, None)?
+    };
+    Ok((Some(pool), timestamp_period))
+}
+
+/// Renders the overlay using Vulkan
+pub struct VulkanRenderBackend {
+    command_buffer: vk::CommandBuffer,
+    swapchain: Swapchain,
+    dirty_swapchain: bool,
+    device_lost: bool,
+    vsync: bool,
+    hdr: bool,
+    hdr_white_scale: f32,
+    glow: Option<GlowSettings>,
+    present_time: Duration,
+    image_available_semaphore: vk::Semaphore,
+    render_finished_semaphore: vk::Semaphore,
+    fence: vk::Fence,
+
+    /// Query pool for the begin and end timestamp of the frame (None if not supported by the queue)
+    timestamp_pool: Option<vk::QueryPool>,
+    /// Nanoseconds per timestamp tick
+    timestamp_period: f32,
+    timestamps_written: bool,
+    gpu_time: Option<Duration>,
+
+    capture_requested: bool,
+    captured_frame: Option<std::result::Result<CapturedFrame, String>>,
+
+    renderer: Renderer,
+    texture_store: TextureStore,
+
+    /* must be dropped last as all other resources have been created from it */
+    vulkan_context: VulkanContext,
+}
+
+impl VulkanRenderBackend {
+    pub fn new(
+        window: &Window,
+        title: &str,
+        device_selector: &PhysicalDeviceSelector,
+        imgui: &mut imgui::Context,
+    ) -> Result<Self> {
+        let vulkan_context = VulkanContext::new(window, title, device_selector)?;
+        let command_buffer = {
+            let allocate_info = vk::CommandBufferAllocateInfo::builder()
+                .command_pool(vulkan_context.command_pool)
+                .level(vk::CommandBufferLevel::PRIMARY)
+                .command_buffer_count(1);
+
+            unsafe {
+                vulkan_context
+                    .device
+                    .allocate_command_buffers(&allocate_info)?[0]
+            }
+        };
+
+        let swapchain = Swapchain::new(&vulkan_context, false, false, false)?;
+        let image_available_semaphore = {
+            let semaphore_info = vk::SemaphoreCreateInfo::builder();
+            unsafe {
+                vulkan_context
+                    .device
+                    .create_semaphore(&semaphore_info, None)?
+            }
+        };
+        let render_finished_semaphore = {
+            let semaphore_info = vk::SemaphoreCreateInfo::builder();
+            unsafe {
+                vulkan_context
+                    .device
+                    .create_semaphore(&semaphore_info, None)?
+            }
+        };
+        let fence = {
+            let fence_info = vk::FenceCreateInfo::builder().flags(vk::FenceCreateFlags::SIGNALED);
+            unsafe { vulkan_context.device.create_fence(&fence_info, None)? }
+        };
+
+        let (timestamp_pool, timestamp_period) = create_timestamp_query_pool(&vulkan_context)?;
+
+        let renderer = Renderer::with_default_allocator(
+            &vulkan_context.instance,
+            vulkan_context.physical_device,
+            vulkan_context.device.clone(),
+            vulkan_context.graphics_queue,
+            vulkan_context.command_pool,
+            swapchain.render_pass,
+            imgui,
+            Some(Options {
+                /* the glow layer and the overlay are drawn with separate buffers */
+                in_flight_frames: 2,
+                ..Default::default()
+            }),
+        )?;
+        let texture_store = TextureStore::new(&vulkan_context)?;
+
+        /* The Vulkan backend can handle 32bit vertex offsets, but forgets to insert that flag... */
+        imgui
+            .io_mut()
+            .backend_flags
+            .insert(imgui::BackendFlags::RENDERER_HAS_VTX_OFFSET);
+
+        Ok(Self {
+            command_buffer,
+            swapchain,
+            dirty_swapchain: false,
+            device_lost: false,
+            vsync: false,
+            hdr: false,
+            hdr_white_scale: 1.0,
+            glow: None,
+            present_time: Duration::ZERO,
+            image_available_semaphore,
+            render_finished_semaphore,
+            fence,
+
+            timestamp_pool,
+            timestamp_period,
+            timestamps_written: false,
+            gpu_time: None,
+
+            capture_requested: false,
+            captured_frame: None,
+
+            renderer,
+            texture_store,
+
+            vulkan_context,
+        })
+    }
+}
+
+impl Drop for VulkanRenderBackend {
+    fn drop(&mut self) {
+        log::debug!("Destroying Vulkan render backend");
+        unsafe {
+            /* fails if the device has been lost, but the resources can be destroyed regardless */
+            let _ = self.vulkan_context.device.device_wait_idle();
+
+            let device = &self.vulkan_context.device;
+            device.destroy_fence(self.fence, None);
+            if let Some(timestamp_pool) = self.timestamp_pool {
+                device.destroy_query_pool(timestamp_pool, None);
+            }
+            device.destroy_semaphore(self.image_available_semaphore, None);
+            device.destroy_semaphore(self.render_finished_semaphore, None);
+        }
+
+        self.swapchain.destroy(&self.vulkan_context);
+        self.texture_store.destroy(&self.vulkan_context);
+    }
+}
+
+impl VulkanRenderBackend {
+    fn recreate_swapchain(&mut self) -> Result<()> {
+        self.swapchain.recreate(
+            &self.vulkan_context,
+            self.vsync,
+            self.hdr,
+            self.glow.is_some(),
+        )?;
+        self.renderer.set_render_pass(self.swapchain.render_pass)?;
+        Ok(())
+    }
+
+    /// Render the frame and queue it for presenting.
+    /// Returns false if the swapchain is out of date and the frame has been skipped.
+    fn render_and_present(
+        &mut self,
+        draw_data: &DrawData,
+        glow_draw_data: Option<&DrawData>,
+        perf: &mut PerfTracker,
+    ) -> Result<bool> {
+        let device = &self.vulkan_context.device;
+        unsafe { device.wait_for_fences(&[self.fence], true, std::u64::MAX)? };
+        if self.timestamps_written {
+            self.gpu_time = self.read_gpu_time();
+        }
+
+        perf.mark("fence");
+        let next_image_result = unsafe {
+            self.swapchain.loader.acquire_next_image(
+                self.swapchain.khr,
+                std::u64::MAX,
+                self.image_available_semaphore,
+                vk::Fence::null(),
+            )
+        };
+        let image_index = match next_image_result {
+            Ok((image_index, _)) => image_index,
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                self.dirty_swapchain = true;
+                return Ok(false);
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        unsafe { device.reset_fences(&[self.fence])? };
+
+        let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
+        let wait_semaphores = [self.image_available_semaphore];
+        let signal_semaphores = [self.render_finished_semaphore];
+
+        // Re-record commands to draw geometry
+        record_command_buffers(
+            device,
+            self.vulkan_context.command_pool,
+            self.command_buffer,
+            self.timestamp_pool,
+            &self.swapchain,
+            image_index as usize,
+            self.hdr_white_scale,
+            self.glow.zip(glow_draw_data),
+            &mut self.renderer,
+            draw_data,
+        )?;
+
+        let command_buffers = [self.command_buffer];
+        let submit_info = [vk::SubmitInfo::builder()
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_stages)
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores)
+            .build()];
+
+        perf.mark("before submit");
+        unsafe {
+            device.queue_submit(self.vulkan_context.graphics_queue, &submit_info, self.fence)?
+        };
+        self.timestamps_written = self.timestamp_pool.is_some();
+        perf.mark("after submit");
+
+        if self.capture_requested {
+            self.capture_requested = false;
+            self.captured_frame = Some(self.read_back_image(image_index));
+            perf.mark("capture");
+        }
+
+        let swapchains = [self.swapchain.khr];
+        let images_indices = [image_index];
+        let present_info = vk::PresentInfoKHR::builder()
+            .wait_semaphores(&signal_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&images_indices);
+
+        let present_start = Instant::now();
+        let present_result = unsafe {
+            self.swapchain
+                .loader
+                .queue_present(self.vulkan_context.present_queue, &present_info)
+        };
+        self.present_time = present_start.elapsed();
+        match present_result {
+            Ok(is_suboptimal) if is_suboptimal => {
+                self.dirty_swapchain = true;
+            }
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                self.dirty_swapchain = true;
+            }
+            Err(error) => return Err(error.into()),
+            _ => {}
+        }
+
+        Ok(true)
+    }
+
+    /// GPU time of the last submitted frame. The frame must have finished rendering.
+    fn read_gpu_time(&self) -> Option<Duration> {
+        let timestamp_pool = self.timestamp_pool?;
+        let mut timestamps = [0u64; 2];
+        unsafe {
+            self.vulkan_context.device.get_query_pool_results(
+                timestamp_pool,
+                0,
+                2,
+                &mut timestamps,
+                vk::QueryResultFlags::TYPE_64,
+            )
+        }
+        .ok()?;
+
+        let ticks = timestamps[1].saturating_sub(timestamps[0]);
+        Some(Duration::from_nanos(
+            (ticks as f64 * self.timestamp_period as f64) as u64,
+        ))
+    }
+
+    /// Copy the rendered swapchain image before it gets presented
+    fn read_back_image(&self, image_index: u32) -> std::result::Result<CapturedFrame, String> {
+        if !self.swapchain.readable {
+            return Err("the swapchain images can not be copied".to_string());
+        }
+
+        if self.swapchain.hdr.is_some() {
+            return Err("frames can not be captured while presenting in HDR".to_string());
+        }
+
+        let mem_properties = unsafe {
+            self.vulkan_context
+                .instance
+                .get_physical_device_memory_properties(self.vulkan_context.physical_device)
+        };
+        let mut data = capture::read_back_image(
+            &self.vulkan_context.device,
+            self.vulkan_context.graphics_queue,
+            self.vulkan_context.command_pool,
+            mem_properties,
+            self.swapchain.images[image_index as usize],
+            self.swapchain.extent,
+        )
+        .map_err(|error| format!("{}", error))?;
+
+        match self.swapchain.format {
+            vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB => {}
+            vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB => {
+                for pixel in data.chunks_exact_mut(4) {
+                    pixel.swap(0, 2);
+                }
+            }
+            format => return Err(format!("unsupported swapchain format {:?}", format)),
+        }
+
+        Ok(CapturedFrame {
+            width: self.swapchain.extent.width,
+            height: self.swapchain.extent.height,
+            data,
+        })
+    }
+}
+
+impl RenderBackend for VulkanRenderBackend {
+    fn physical_devices(&self) -> &[String] {
+        &self.vulkan_context.physical_device_names
+    }
+
+    fn prepare_frame(&mut self, window: &Window) -> bool {
+        /* recreate the swapchain right away instead of waiting for it to become suboptimal */
+        let PhysicalSize { width, height } = window.inner_size();
+        if width != self.swapchain.extent.width || height != self.swapchain.extent.height {
+            self.dirty_swapchain = true;
+        }
+
+        if !self.dirty_swapchain {
+            return true;
+        }
+
+        // If swapchain must be recreated wait for windows to not be minimized anymore
+        if width == 0 || height == 0 {
+            return false;
+        }
+
+        if let Err(error) = self.recreate_swapchain() {
+            log::warn!("Failed to recreate the swapchain: {}", error);
+            self.device_lost = true;
+            return false;
+        }
+
+        self.dirty_swapchain = false;
+        true
+    }
+
+    fn update_fonts_texture(&mut self, imgui: &mut imgui::Context) -> Result<()> {
+        self.renderer.update_fonts_texture(
+            self.vulkan_context.graphics_queue,
+            self.vulkan_context.command_pool,
+            imgui,
+        )?;
+        Ok(())
+    }
+
+    fn process_uploads(&mut self, uploads: Vec<PendingUpload>) {
+        self.texture_store
+            .process_uploads(&self.vulkan_context, &mut self.renderer, uploads);
+    }
+
+    fn delete_textures(&mut self, textures: Vec<OverlayTexture>) {
+        self.texture_store
+            .delete_textures(&self.vulkan_context, &mut self.renderer, textures);
+    }
+
+    fn set_vsync(&mut self, enabled: bool) {
+        if self.vsync == enabled {
+            return;
+        }
+
+        /* the present mode can only be changed by recreating the swapchain */
+        self.vsync = enabled;
+        self.dirty_swapchain = true;
+    }
+
+    fn set_hdr(&mut self, enabled: bool, white_scale: f32) {
+        self.hdr_white_scale = white_scale;
+        if self.hdr == enabled {
+            return;
+        }
+
+        /* the color space can only be changed by recreating the swapchain */
+        self.hdr = enabled;
+        self.dirty_swapchain = true;
+    }
+
+    fn set_glow(&mut self, glow: Option<GlowSettings>) {
+        /* the glow render targets are part of the swapchain */
+        if self.glow.is_some() != glow.is_some() {
+            self.dirty_swapchain = true;
+        }
+        self.glow = glow;
+    }
+
+    fn request_capture(&mut self) {
+        self.capture_requested = true;
+    }
+
+    fn take_capture(&mut self) -> Option<std::result::Result<CapturedFrame, String>> {
+        self.captured_frame.take()
+    }
+
+    fn render_frame(
+        &mut self,
+        draw_data: &DrawData,
+        glow_draw_data: Option<&DrawData>,
+        perf: &mut PerfTracker,
+    ) -> bool {
+        match self.render_and_present(draw_data, glow_draw_data, perf) {
+            Ok(presented) => presented,
+            Err(error) => {
+                /* the device state is unknown (e.g. VK_ERROR_DEVICE_LOST), recreate everything */
+                log::warn!("Failed to render the Vulkan frame: {}", error);
+                self.device_lost = true;
+                false
+            }
+        }
+    }
+
+    fn present_time(&self) -> Duration {
+        self.present_time
+    }
+
+    fn gpu_time(&self) -> Option<Duration> {
+        self.gpu_time
+    }
+
+    fn device_lost(&self) -> bool {
+        self.device_lost
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicIsize,
+            Ordering,
+        },
+        mpsc,
+    },
+    thread::JoinHandle,
+};
+
+use windows::Win32::{
+    Foundation::{
+        HMODULE,
+        HWND,
+        LPARAM,
+        WPARAM,
+    },
+    System::Threading::GetCurrentThreadId,
+    UI::{
+        Accessibility::{
+            SetWinEventHook,
+            UnhookWinEvent,
+            HWINEVENTHOOK,
+        },
+        WindowsAndMessaging::{
+            DispatchMessageW,
+            GetMessageW,
+            PostThreadMessageW,
+            TranslateMessage,
+            CHILDID_SELF,
+            EVENT_OBJECT_DESTROY,
+            EVENT_OBJECT_LOCATIONCHANGE,
+            MSG,
+            OBJID_WINDOW,
+            WINEVENT_OUTOFCONTEXT,
+            WM_QUIT,
+        },
+    },
+};
+
+use crate::{
+    OverlayError,
+    Result,
+};
+
+/// Window which is watched by the hook
+static WATCHED_WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+/// The watched window has been moved, resized, shown, hidden or destroyed
+static WINDOW_CHANGED: AtomicBool = AtomicBool::new(false);
+
+unsafe extern "system" fn win_event_proc(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    if hwnd.0 != WATCHED_WINDOW.load(Ordering::Relaxed)
+        || id_object != OBJID_WINDOW.0
+        || id_child != CHILDID_SELF as i32
+    {
+        return;
+    }
+
+    WINDOW_CHANGED.store(true, Ordering::Release);
+}
+
+/// Receives move, resize and visibility events of a window through a WinEvent hook.
+/// Moving a window to a monitor with a different DPI resizes it and is reported as well.
+///
+/// The events are delivered out of context to a dedicated thread,
+/// hence they are received even while the overlay waits for the next frame.
+pub struct WindowEventHook {
+    process_id: u32,
+    thread_id: u32,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WindowEventHook {
+    /// Watch the window. Only events of the process owning the window are received.
+    pub fn install(hwnd: HWND, process_id: u32) -> Result<Self> {
+        WATCHED_WINDOW.store(hwnd.0, Ordering::Relaxed);
+
+        let (init_tx, init_rx) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("window event hook".to_string())
+            .spawn(move || unsafe {
+                /* covers the create, destroy, show, hide, state and location change events */
+                let hook = SetWinEventHook(
+                    EVENT_OBJECT_DESTROY,
+                    EVENT_OBJECT_LOCATIONCHANGE,
+                    HMODULE::default(),
+                    Some(win_event_proc),
+                    process_id,
+                    0,
+                    WINEVENT_OUTOFCONTEXT,
+                );
+                if hook.is_invalid() {
+                    let _ = init_tx.send(Err(OverlayError::WindowEventHookFailed));
+                    return;
+                }
+
+                let _ = init_tx.send(Ok(GetCurrentThreadId()));
+
+                let mut message = MSG::default();
+                while GetMessageW(&mut message, None, 0, 0).as_bool() {
+                    TranslateMessage(&message);
+                    DispatchMessageW(&message);
+                }
+
+                UnhookWinEvent(hook);
+            })
+            .map_err(OverlayError::WindowEventHookThreadFailed)?;
+
+        let thread_id = init_rx
+            .recv()
+            .map_err(|_| OverlayError::WindowEventHookFailed)??;
+
+        Ok(Self {
+            process_id,
+            thread_id,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn process_id(&self) -> u32 {
+        self.process_id
+    }
+
+    /// Watch another window of the same process
+    pub fn set_window(&self, hwnd: HWND) {
+        WATCHED_WINDOW.store(hwnd.0, Ordering::Relaxed);
+        WINDOW_CHANGED.store(true, Ordering::Release);
+    }
+
+    /// Check if the window changed since the last call to `take_changed`
+    pub fn changed(&self) -> bool {
+        WINDOW_CHANGED.load(Ordering::Acquire)
+    }
+
+    pub fn take_changed(&self) -> bool {
+        WINDOW_CHANGED.swap(false, Ordering::AcqRel)
+    }
+}
+
+impl Drop for WindowEventHook {
+    fn drop(&mut self) {
+        unsafe {
+            PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -1,3 +1,8 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
 use imgui_winit_support::winit::{
     platform::windows::WindowExtWindows,
     window::Window,
@@ -14,15 +19,24 @@ use windows::{
             RECT,
             WPARAM,
         },
-        Graphics::Gdi::ClientToScreen,
+        Graphics::{
+            Dwm::{
+                DwmGetWindowAttribute,
+                DWMWA_CLOAKED,
+            },
+            Gdi::ClientToScreen,
+        },
         UI::{
             Input::KeyboardAndMouse::GetFocus,
             WindowsAndMessaging::{
                 FindWindowExA,
                 FindWindowW,
                 GetClientRect,
+                GetForegroundWindow,
                 GetWindowRect,
                 GetWindowThreadProcessId,
+                IsIconic,
+                IsWindowVisible,
                 MoveWindow,
                 SendMessageA,
                 WM_PAINT,
@@ -37,8 +51,10 @@ use crate::{
         Result,
     },
     util,
+    window_event_hook::WindowEventHook,
 };
 
+#[derive(Clone)]
 pub enum OverlayTarget {
     Window(HWND),
     WindowTitle(String),
@@ -105,11 +121,48 @@ impl OverlayTarget {
     }
 }
 
+/// Where the overlay is placed on the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverlayMonitor {
+    /// Cover the client area of the target window
+    #[default]
+    FollowTarget,
+
+    /// Cover the whole monitor with the given index.
+    /// Intended for fullscreen games where the target window bounds are not reliable.
+    /// Falls back to the target window if the monitor is not connected.
+    Pinned(usize),
+}
+
+struct MonitorInfo {
+    name: String,
+    bounds: RECT,
+}
+
+/// Time to wait for the target window to be recreated before the overlay exits
+const REATTACH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval in which the connected monitors are enumerated
+const MONITOR_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Track the CS2 window and adjust overlay accordingly.
 /// This is only required when playing in windowed mode.
 pub struct WindowTracker {
+    target: OverlayTarget,
     cs2_hwnd: HWND,
     current_bounds: RECT,
+
+    /// Time since when the tracked window handle is invalid
+    window_lost_since: Option<Instant>,
+
+    /// Notifies about bounds changes of the tracked window.
+    /// The bounds are polled every frame if the hook could not be installed.
+    event_hook: Option<WindowEventHook>,
+
+    monitor: OverlayMonitor,
+    monitors: Vec<MonitorInfo>,
+    monitor_names: Vec<String>,
+    monitors_updated: Option<Instant>,
 }
 
 impl WindowTracker {
@@ -119,23 +172,191 @@ impl WindowTracker {
             return Err(OverlayError::WindowNotFound);
         }
 
-        Ok(Self {
+        let mut tracker = Self {
+            target: target.clone(),
             cs2_hwnd: hwnd,
             current_bounds: Default::default(),
-        })
+
+            window_lost_since: None,
+            event_hook: None,
+
+            monitor: Default::default(),
+            monitors: Vec::new(),
+            monitor_names: Vec::new(),
+            monitors_updated: None,
+        };
+        tracker.update_event_hook();
+        Ok(tracker)
+    }
+
+    /// (Re)install the event hook for the process of the tracked window
+    fn update_event_hook(&mut self) {
+        let mut process_id = 0;
+        unsafe { GetWindowThreadProcessId(self.cs2_hwnd, Some(&mut process_id)) };
+
+        if let Some(hook) = &self.event_hook {
+            if hook.process_id() == process_id {
+                hook.set_window(self.cs2_hwnd);
+                return;
+            }
+        }
+
+        /* only one hook can be active at a time */
+        self.event_hook = None;
+        match WindowEventHook::install(self.cs2_hwnd, process_id) {
+            Ok(hook) => self.event_hook = Some(hook),
+            Err(error) => log::warn!("无法监听目标窗口的移动事件, 将改为轮询窗口位置: {}", error),
+        }
+    }
+
+    /// The tracked window has been moved or resized and the overlay has not been updated yet
+    pub fn target_changed(&self) -> bool {
+        self.event_hook
+            .as_ref()
+            .map_or(false, |hook| hook.changed())
+    }
+
+    pub fn set_monitor(&mut self, monitor: OverlayMonitor) {
+        if self.monitor == monitor {
+            return;
+        }
+
+        log::debug!("Overlay monitor changed to {:?}", monitor);
+        self.monitor = monitor;
+        self.mark_force_update();
+    }
+
+    pub fn monitor(&self) -> OverlayMonitor {
+        self.monitor
+    }
+
+    /// Display names of all connected monitors in the order used by `OverlayMonitor::Pinned`
+    pub fn monitor_names(&self) -> &[String] {
+        &self.monitor_names
+    }
+
+    fn update_monitors(&mut self, overlay: &Window) {
+        if self.monitors_updated.map_or(false, |updated| {
+            updated.elapsed() < MONITOR_REFRESH_INTERVAL
+        }) {
+            return;
+        }
+
+        self.monitors_updated = Some(Instant::now());
+        self.monitors = overlay
+            .available_monitors()
+            .enumerate()
+            .map(|(index, monitor)| {
+                let position = monitor.position();
+                let size = monitor.size();
+                MonitorInfo {
+                    name: format!(
+                        "#{} {} ({}x{})",
+                        index + 1,
+                        monitor.name().unwrap_or_default(),
+                        size.width,
+                        size.height
+                    ),
+                    bounds: RECT {
+                        left: position.x,
+                        top: position.y,
+                        right: position.x + size.width as i32,
+                        bottom: position.y + size.height as i32,
+                    },
+                }
+            })
+            .collect();
+        self.monitor_names = self
+            .monitors
+            .iter()
+            .map(|monitor| monitor.name.clone())
+            .collect();
+    }
+
+    pub fn target_hwnd(&self) -> HWND {
+        self.cs2_hwnd
+    }
+
+    /// Check if the tracked window or the overlay itself is the current foreground window.
+    pub fn is_focused(&self, overlay: HWND) -> bool {
+        let foreground = unsafe { GetForegroundWindow() };
+        foreground == self.cs2_hwnd || foreground == overlay
+    }
+
+    /// Check if the tracked window is minimized, hidden or cloaked (e.g. on another virtual desktop)
+    pub fn is_target_hidden(&self) -> bool {
+        unsafe {
+            if IsIconic(self.cs2_hwnd).as_bool() || !IsWindowVisible(self.cs2_hwnd).as_bool() {
+                return true;
+            }
+
+            let mut cloaked = 0u32;
+            DwmGetWindowAttribute(
+                self.cs2_hwnd,
+                DWMWA_CLOAKED,
+                &mut cloaked as *mut _ as *mut _,
+                std::mem::size_of::<u32>() as u32,
+            )
+            .map_or(false, |_| cloaked != 0)
+        }
     }
 
     pub fn mark_force_update(&mut self) {
         self.current_bounds = Default::default();
     }
 
+    /// Try to find the recreated target window after the tracked window handle became invalid.
+    /// Returns false if the window could not be found within the reattach timeout.
+    fn reattach(&mut self) -> bool {
+        if matches!(self.target, OverlayTarget::Window(_)) {
+            /* a fixed window handle can not be resolved again */
+            return false;
+        }
+
+        let hwnd = match self.target.resolve_target_window() {
+            Ok(hwnd) => hwnd,
+            Err(error) => {
+                log::warn!("无法重新查找目标窗口: {}", error);
+                HWND::default()
+            }
+        };
+
+        if hwnd.0 != 0 && hwnd != self.cs2_hwnd {
+            log::info!("目标窗口已重建。重新附加到窗口 0x{:X}", hwnd.0);
+            self.cs2_hwnd = hwnd;
+            self.window_lost_since = None;
+            self.update_event_hook();
+            self.mark_force_update();
+            return true;
+        }
+
+        let lost_since = *self.window_lost_since.get_or_insert_with(|| {
+            log::debug!("目标窗口句柄无效，等待窗口重建");
+            Instant::now()
+        });
+        lost_since.elapsed() < REATTACH_TIMEOUT
+    }
+
     pub fn update(&mut self, overlay: &Window) -> bool {
+        let target_changed = self
+            .event_hook
+            .as_ref()
+            .map_or(true, |hook| hook.take_changed());
+        if !target_changed
+            && self.window_lost_since.is_none()
+            && self.current_bounds != RECT::default()
+            && self.monitor == OverlayMonitor::FollowTarget
+        {
+            /* nothing changed since the last update */
+            return true;
+        }
+
         let mut rect: RECT = Default::default();
         let success = unsafe { GetClientRect(self.cs2_hwnd, &mut rect) };
         if !success.as_bool() {
             let error = unsafe { GetLastError() };
             if error == ERROR_INVALID_WINDOW_HANDLE {
-                return false;
+                return self.reattach();
             }
 
             log::warn!("GetClientRect failed for tracked window: {:?}", error);
@@ -147,6 +368,13 @@ impl WindowTracker {
             ClientToScreen(self.cs2_hwnd, &mut rect.right as *mut _ as *mut POINT);
         }
 
+        self.update_monitors(overlay);
+        if let OverlayMonitor::Pinned(index) = self.monitor {
+            if let Some(monitor) = self.monitors.get(index) {
+                rect = monitor.bounds;
+            }
+        }
+
         if unsafe { GetFocus() } != self.cs2_hwnd {
             /*
              * CS2 will render a black screen as soon as CS2 does not have the focus and is completely covered by
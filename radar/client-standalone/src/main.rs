@@ -1,4 +1,14 @@
-use std::fs;
+use std::{
+    fs,
+    net::{
+        Ipv4Addr,
+        Ipv6Addr,
+        SocketAddr,
+        ToSocketAddrs,
+    },
+    path::PathBuf,
+    sync::Arc,
+};
 
 use anyhow::Context;
 use clap::Parser;
@@ -11,6 +21,11 @@ use radar_client::{
     CS2RadarGenerator,
     WebRadarPublisher,
 };
+use radar_server::{
+    HttpServeDirectory,
+    RadarServer,
+};
+use tokio::sync::RwLock;
 use url::Url;
 use utils_state::StateRegistry;
 
@@ -26,6 +41,54 @@ struct Args {
     // 一个 bool 型参数用来指示是否要将 sessionid 保存到本地
     #[arg(short, long)]
     session_id_write_to_file: bool,
+
+    /// Host the web radar on this address (e.g. 0.0.0.0:8080) instead of publishing to the publish url.
+    /// Other devices within the LAN can open the radar using the IP address of this computer.
+    #[arg(long, value_name = "ADDRESS")]
+    serve: Option<String>,
+
+    /// Directory of the build web radar (radar/web/dist) served by --serve.
+    /// Defaults to the files bundled with the executable (static-bundle feature).
+    #[arg(long)]
+    static_dir: Option<PathBuf>,
+
+    /// Password viewers have to enter before they can see the radar
+    #[arg(long)]
+    password: Option<String>,
+}
+
+/// Start the radar server and return it with the address to publish onto
+async fn start_local_server(
+    address: &str,
+    static_dir: Option<PathBuf>,
+) -> anyhow::Result<(Arc<RwLock<RadarServer>>, SocketAddr)> {
+    let address = address
+        .to_socket_addrs()?
+        .next()
+        .context("invalid serve address")?;
+
+    let static_serve = match static_dir {
+        Some(path) => HttpServeDirectory::Disk { path },
+        None => HttpServeDirectory::Bundled,
+    };
+    let server = RadarServer::new();
+    let mut address = server
+        .write()
+        .await
+        .listen_http(address, static_serve)
+        .await
+        .context("failed to start the radar server (use --static-dir to serve the web radar)")?;
+
+    /* the server accepts connections on all interfaces, publish via loopback */
+    if address.ip().is_unspecified() {
+        address.set_ip(if address.is_ipv4() {
+            Ipv4Addr::LOCALHOST.into()
+        } else {
+            Ipv6Addr::LOCALHOST.into()
+        });
+    }
+
+    Ok((server, address))
 }
 
 #[tokio::main]
@@ -37,7 +100,20 @@ async fn main() -> anyhow::Result<()> {
         .parse_default_env()
         .init();
 
-    let url = Url::parse(&args.publish_url).context("invalid target server address")?;
+    /* the local server must be kept alive while publishing */
+    let (url, _local_server) = match &args.serve {
+        Some(address) => {
+            let (server, address) = start_local_server(address, args.static_dir.clone()).await?;
+            (
+                Url::parse(&format!("ws://{}/publish", address))?,
+                Some(server),
+            )
+        }
+        None => (
+            Url::parse(&args.publish_url).context("invalid target server address")?,
+            None,
+        ),
+    };
 
     let radar_generator = {
         let cs2 = CS2Handle::create(true)?;
@@ -48,7 +124,8 @@ async fn main() -> anyhow::Result<()> {
 
         Box::new(CS2RadarGenerator::new(states)?)
     };
-    let radar_client = WebRadarPublisher::connect(radar_generator, &url).await?;
+    let radar_client =
+        WebRadarPublisher::connect(radar_generator, &url, args.password.as_deref()).await?;
 
     let mut radar_url = url.clone();
     radar_url.set_path(&format!("/session/{}", radar_client.session_id));
@@ -60,6 +137,9 @@ async fn main() -> anyhow::Result<()> {
 
     log::info!("Radar session {}", radar_client.session_id);
     log::info!("Available at {}", radar_url);
+    if args.serve.is_some() {
+        log::info!("Other devices within the LAN can replace the host with the IP address of this computer");
+    }
 
     if (args.session_id_write_to_file) {
         fs::write("session.txt", radar_url.as_str()).unwrap();
@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+
+use radar_shared::{
+    C4State,
+    RadarBombCarrierEvent,
+    RadarBombInfo,
+    RadarBombTrail,
+};
+
+/// Minimum distance the bomb has to move before a new trail position will be recorded
+const TRAIL_MIN_DISTANCE: f32 = 32.0;
+
+/// Max amount of recorded trail positions
+const TRAIL_MAX_LENGTH: usize = 64;
+
+/// Max amount of recorded carrier events
+const CARRIER_HISTORY_MAX_LENGTH: usize = 16;
+
+/// Track the C4 movement and its carriers across the round.
+#[derive(Default)]
+pub struct BombTracker {
+    positions: VecDeque<[f32; 3]>,
+    carrier_history: VecDeque<RadarBombCarrierEvent>,
+    current_carrier: Option<String>,
+}
+
+impl BombTracker {
+    pub fn reset(&mut self) {
+        self.positions.clear();
+        self.carrier_history.clear();
+        self.current_carrier = None;
+    }
+
+    fn push_carrier_event(&mut self, event: RadarBombCarrierEvent) {
+        self.carrier_history.push_back(event);
+        while self.carrier_history.len() > CARRIER_HISTORY_MAX_LENGTH {
+            self.carrier_history.pop_front();
+        }
+    }
+
+    fn push_position(&mut self, position: [f32; 3]) {
+        if let Some(last) = self.positions.back() {
+            let distance = last
+                .iter()
+                .zip(position.iter())
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>()
+                .sqrt();
+
+            if distance < TRAIL_MIN_DISTANCE {
+                return;
+            }
+        }
+
+        self.positions.push_back(position);
+        while self.positions.len() > TRAIL_MAX_LENGTH {
+            self.positions.pop_front();
+        }
+    }
+
+    /// Update the tracker with the current bomb info.
+    /// The carrier is the name of the player currently holding the C4.
+    pub fn update(&mut self, bomb: Option<&RadarBombInfo>, carrier: Option<String>) {
+        let bomb = match bomb {
+            Some(bomb) => bomb,
+            None => return,
+        };
+
+        if !matches!(bomb.state, C4State::Carried | C4State::Dropped) {
+            /* the bomb has been planted, keep the trail as it is */
+            return;
+        }
+
+        if carrier != self.current_carrier {
+            if let Some(player_name) = self.current_carrier.take() {
+                self.push_carrier_event(RadarBombCarrierEvent {
+                    player_name,
+                    picked_up: false,
+                    position: bomb.position,
+                });
+            }
+
+            if let Some(player_name) = &carrier {
+                self.push_carrier_event(RadarBombCarrierEvent {
+                    player_name: player_name.clone(),
+                    picked_up: true,
+                    position: bomb.position,
+                });
+            }
+
+            self.current_carrier = carrier;
+        }
+
+        self.push_position(bomb.position);
+    }
+
+    pub fn trail(&self) -> RadarBombTrail {
+        RadarBombTrail {
+            positions: self.positions.iter().cloned().collect(),
+            carrier_history: self.carrier_history.iter().cloned().collect(),
+        }
+    }
+}
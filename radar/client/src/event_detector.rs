@@ -0,0 +1,260 @@
+use radar_shared::{
+    C4State,
+    RadarBombInfo,
+    RadarEvent,
+    RadarEventPlayer,
+    RadarTeamScore,
+};
+
+/// Player state required to detect kills
+#[derive(Debug, Clone)]
+pub struct EventFramePlayer {
+    pub controller_entity_id: u32,
+    pub player_name: String,
+    pub team_id: u8,
+    pub alive: bool,
+
+    /// Kills within the current round
+    pub round_kills: i32,
+    pub round_headshot_kills: i32,
+
+    /// Active weapon if the player is alive
+    pub weapon: Option<u16>,
+}
+
+impl EventFramePlayer {
+    fn event_player(&self) -> RadarEventPlayer {
+        RadarEventPlayer {
+            controller_entity_id: self.controller_entity_id,
+            player_name: self.player_name.clone(),
+            team_id: self.team_id,
+        }
+    }
+}
+
+/// Game state snapshot the events are detected from
+#[derive(Debug, Clone, Default)]
+pub struct EventFrame {
+    /// Amount of rounds played. None if the game rules are not available.
+    pub rounds_played: Option<u32>,
+
+    /// The current round has been won
+    pub round_over: bool,
+
+    /// Team id of the round winner
+    pub round_winner: Option<u8>,
+
+    pub score: Option<RadarTeamScore>,
+    pub players: Vec<EventFramePlayer>,
+
+    pub bomb: Option<RadarBombInfo>,
+
+    /// Name of the player currently carrying the bomb
+    pub bomb_carrier: Option<String>,
+}
+
+/// Detect match events by comparing consecutive frames.
+#[derive(Default)]
+pub struct EventDetector {
+    last_frame: Option<EventFrame>,
+    last_bomb_carrier: Option<String>,
+}
+
+impl EventDetector {
+    pub fn reset(&mut self) {
+        self.last_frame = None;
+        self.last_bomb_carrier = None;
+    }
+
+    pub fn update(&mut self, frame: EventFrame) -> Vec<RadarEvent> {
+        let mut events = Vec::new();
+
+        let last_bomb_carrier = self.last_bomb_carrier.clone();
+        if frame.bomb_carrier.is_some() {
+            self.last_bomb_carrier = frame.bomb_carrier.clone();
+        }
+
+        let Some(last_frame) = self.last_frame.replace(frame) else {
+            /* publish the initial score so viewers know it before the first change */
+            let frame = self.last_frame.as_ref().unwrap();
+            if let Some(score) = frame.score {
+                events.push(RadarEvent::ScoreChanged { score });
+            }
+            return events;
+        };
+        let frame = self.last_frame.as_ref().unwrap();
+
+        Self::detect_kills(&last_frame, frame, &mut events);
+        Self::detect_bomb_events(&last_frame, frame, last_bomb_carrier, &mut events);
+
+        match (last_frame.round_over, frame.round_over) {
+            (false, true) => events.push(RadarEvent::RoundEnd {
+                winner_team: frame.round_winner,
+            }),
+            (true, false) => events.push(RadarEvent::RoundStart {
+                round: frame.rounds_played.unwrap_or_default() + 1,
+            }),
+            _ => {}
+        }
+
+        if let Some(score) = frame.score {
+            if last_frame.score != Some(score) {
+                events.push(RadarEvent::ScoreChanged { score });
+            }
+        }
+
+        events
+    }
+
+    fn detect_kills(last_frame: &EventFrame, frame: &EventFrame, events: &mut Vec<RadarEvent>) {
+        /* (player, kills, headshot kills) gained since the last frame */
+        let mut killers = frame
+            .players
+            .iter()
+            .filter_map(|player| {
+                let last_player = last_frame
+                    .players
+                    .iter()
+                    .find(|last| last.controller_entity_id == player.controller_entity_id)?;
+
+                let kills = player.round_kills - last_player.round_kills;
+                if kills <= 0 {
+                    /* no new kills or the kill counter has been reset */
+                    return None;
+                }
+
+                let headshot_kills =
+                    (player.round_headshot_kills - last_player.round_headshot_kills).max(0);
+                Some((player, kills, headshot_kills))
+            })
+            .collect::<Vec<_>>();
+
+        for victim in frame.players.iter().filter(|player| !player.alive) {
+            let died = last_frame
+                .players
+                .iter()
+                .any(|last| last.controller_entity_id == victim.controller_entity_id && last.alive);
+            if !died {
+                continue;
+            }
+
+            /* kills against enemies are counted, hence prefer killers of the opposite team */
+            let killer = killers
+                .iter_mut()
+                .filter(|(_, kills, _)| *kills > 0)
+                .min_by_key(|(killer, _, _)| killer.team_id == victim.team_id);
+
+            let event = match killer {
+                Some((killer, kills, headshot_kills)) => {
+                    *kills -= 1;
+                    let headshot = *headshot_kills > 0;
+                    if headshot {
+                        *headshot_kills -= 1;
+                    }
+
+                    RadarEvent::Kill {
+                        killer: Some(killer.event_player()),
+                        victim: victim.event_player(),
+                        weapon: killer.weapon,
+                        headshot,
+                    }
+                }
+                None => RadarEvent::Kill {
+                    killer: None,
+                    victim: victim.event_player(),
+                    weapon: None,
+                    headshot: false,
+                },
+            };
+            events.push(event);
+        }
+    }
+
+    fn detect_bomb_events(
+        last_frame: &EventFrame,
+        frame: &EventFrame,
+        last_bomb_carrier: Option<String>,
+        events: &mut Vec<RadarEvent>,
+    ) {
+        let last_state = last_frame.bomb.as_ref().map(|bomb| &bomb.state);
+        let Some(bomb) = &frame.bomb else {
+            return;
+        };
+
+        match (last_state, &bomb.state) {
+            (Some(C4State::Active { .. }), C4State::Active { .. }) => {}
+            (_, C4State::Active { .. }) => events.push(RadarEvent::BombPlanted {
+                player_name: last_bomb_carrier,
+                bomb_site: bomb.bomb_site,
+            }),
+            (Some(C4State::Active { defuse, .. }), C4State::Defused) => {
+                events.push(RadarEvent::BombDefused {
+                    player_name: defuse.as_ref().map(|defuse| defuse.player_name.clone()),
+                })
+            }
+            (Some(C4State::Active { .. }), C4State::Detonated) => {
+                events.push(RadarEvent::BombDetonated)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use radar_shared::RadarEvent;
+
+    use super::{
+        EventDetector,
+        EventFrame,
+        EventFramePlayer,
+    };
+
+    fn player(controller_entity_id: u32, team_id: u8) -> EventFramePlayer {
+        EventFramePlayer {
+            controller_entity_id,
+            player_name: format!("player {}", controller_entity_id),
+            team_id,
+            alive: true,
+            round_kills: 0,
+            round_headshot_kills: 0,
+            weapon: Some(7),
+        }
+    }
+
+    #[test]
+    fn detect_kills() {
+        let mut frame = EventFrame {
+            rounds_played: Some(3),
+            players: vec![player(1, 2), player(2, 3), player(3, 3)],
+            ..Default::default()
+        };
+
+        let mut detector = EventDetector::default();
+        assert!(detector.update(frame.clone()).is_empty());
+
+        frame.players[0].round_kills = 1;
+        frame.players[0].round_headshot_kills = 1;
+        frame.players[1].alive = false;
+        frame.players[2].alive = false;
+
+        let events = detector.update(frame.clone());
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            RadarEvent::Kill {
+                killer: Some(killer),
+                victim,
+                headshot: true,
+                ..
+            } => {
+                assert_eq!(killer.controller_entity_id, 1);
+                assert_eq!(victim.controller_entity_id, 2);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert!(matches!(&events[1], RadarEvent::Kill { killer: None, .. }));
+
+        /* dead players do not die again */
+        assert!(detector.update(frame).is_empty());
+    }
+}
@@ -2,34 +2,98 @@ use std::ffi::CStr;
 
 use anyhow::Context;
 use cs2::{
+    find_game_rules,
     CEntityIdentityEx,
     ClassNameCache,
     CurrentMapState,
     EntitySystem,
+    GameRules,
     Globals,
+    PlayerControllerInfo,
     PlayerPawnState,
+    RoundPhase,
 };
 use cs2_schema_generated::cs2::{
     client::{
+        CCSPlayerController,
         CEntityIdentity,
+        C_BaseCSGrenadeProjectile,
+        C_CSTeam,
+        C_CSWeaponBase,
+        C_Inferno,
+        C_MolotovProjectile,
         C_PlantedC4,
+        C_SmokeGrenadeProjectile,
         C_C4,
     },
     globals::CSWeaponState_t,
 };
 use obfstr::obfstr;
 use radar_shared::{
+    map_projection,
     BombDefuser,
     C4State,
+    GrenadeType,
     RadarBombInfo,
+    RadarDroppedWeapon,
+    RadarEvent,
+    RadarGrenade,
+    RadarInferno,
     RadarPlayerInfo,
     RadarSettings,
+    RadarSmoke,
     RadarState,
+    RadarTeamScore,
 };
 use utils_state::StateRegistry;
 
+use crate::{
+    BombTracker,
+    EventDetector,
+    EventFrame,
+    EventFramePlayer,
+};
+
+/// Duration of a server tick. Effect start times are given in ticks.
+const TICK_INTERVAL: f32 = 1.0 / 64.0;
+
+/// Time a smoke cloud lasts including fading out
+const SMOKE_DURATION: f32 = 20.0;
+
+const TEAM_ID_T: u8 = 2;
+const TEAM_ID_CT: u8 = 3;
+
+/// Entity classes of weapons which can be dropped.
+/// Most weapons have their own class, the remaining ones use the generic gun class.
+fn is_weapon_class(entity_class: &str) -> bool {
+    entity_class.starts_with("C_Weapon")
+        || matches!(
+            entity_class,
+            "C_CSWeaponBaseGun"
+                | "C_AK47"
+                | "C_DEagle"
+                | "C_Flashbang"
+                | "C_HEGrenade"
+                | "C_SmokeGrenade"
+                | "C_MolotovGrenade"
+                | "C_IncendiaryGrenade"
+                | "C_DecoyGrenade"
+        )
+}
+
+enum Utility {
+    Grenade(RadarGrenade),
+    Smoke(RadarSmoke),
+    Inferno(RadarInferno),
+}
+
 pub trait RadarGenerator: Send {
     fn generate_state(&mut self, settings: &RadarSettings) -> anyhow::Result<RadarState>;
+
+    /// Take all events which occurred since the last call
+    fn take_events(&mut self) -> Vec<RadarEvent> {
+        Vec::new()
+    }
 }
 
 trait BombData {
@@ -40,7 +104,10 @@ impl BombData for C_C4 {
     fn read_bomb_data(&self, _generator: &CS2RadarGenerator) -> anyhow::Result<RadarBombInfo> {
         let position = self.m_pGameSceneNode()?.read_schema()?.m_vecAbsOrigin()?;
 
-        if self.m_iState()? as u32 == CSWeaponState_t::WEAPON_NOT_CARRIED as u32 {
+        /* the weapon state may lag behind when the carrier dies */
+        if self.m_iState()? as u32 == CSWeaponState_t::WEAPON_NOT_CARRIED as u32
+            || !self.m_hOwnerEntity()?.is_valid()
+        {
             return Ok(RadarBombInfo {
                 position,
                 state: C4State::Dropped,
@@ -126,11 +193,38 @@ impl BombData for C_PlantedC4 {
 
 pub struct CS2RadarGenerator {
     states: StateRegistry,
+    bomb_tracker: BombTracker,
+
+    event_detector: EventDetector,
+    events: Vec<RadarEvent>,
 }
 
 impl CS2RadarGenerator {
     pub fn new(states: StateRegistry) -> anyhow::Result<Self> {
-        Ok(Self { states })
+        Ok(Self {
+            states,
+            bomb_tracker: Default::default(),
+
+            event_detector: Default::default(),
+            events: Default::default(),
+        })
+    }
+
+    /// Name of the player currently carrying the C4
+    fn read_bomb_carrier(&self, bomb: &C_C4) -> anyhow::Result<Option<String>> {
+        let owner = bomb.m_hOwnerEntity()?;
+        if !owner.is_valid() {
+            return Ok(None);
+        }
+
+        let player_info = self
+            .states
+            .resolve::<PlayerPawnState>(owner.get_entity_index())?;
+
+        match &*player_info {
+            PlayerPawnState::Alive(info) => Ok(Some(info.player_name.clone())),
+            PlayerPawnState::Dead => Ok(None),
+        }
     }
 
     fn generate_player_info(
@@ -159,6 +253,206 @@ impl CS2RadarGenerator {
             _ => Ok(None),
         }
     }
+
+    fn read_event_player(
+        &self,
+        identity: &CEntityIdentity,
+    ) -> anyhow::Result<Option<EventFramePlayer>> {
+        let controller = identity
+            .entity_ptr::<CCSPlayerController>()?
+            .read_schema()?;
+        let info = PlayerControllerInfo::read(&controller)?;
+        if info.team_id != TEAM_ID_T && info.team_id != TEAM_ID_CT {
+            /* spectators can not kill or die */
+            return Ok(None);
+        }
+
+        let action_tracking = controller.m_pActionTrackingServices()?.read_schema()?;
+        Ok(Some(EventFramePlayer {
+            controller_entity_id: identity.handle::<()>()?.get_entity_index(),
+            player_name: info.player_name,
+            team_id: info.team_id,
+            alive: info.player_is_alive,
+            round_kills: action_tracking.m_iNumRoundKills()?,
+            round_headshot_kills: action_tracking.m_iNumRoundKillsHeadshots()?,
+            weapon: None,
+        }))
+    }
+
+    /// Collect the game state the events are detected from
+    fn read_event_frame(&self, radar_state: &RadarState) -> anyhow::Result<EventFrame> {
+        let mut frame = EventFrame {
+            bomb: radar_state.bomb.clone(),
+            ..Default::default()
+        };
+
+        if let Some(game_rules) = find_game_rules(&self.states)? {
+            let round_win_status = game_rules.m_iRoundWinStatus()?;
+
+            frame.rounds_played = Some(game_rules.m_totalRoundsPlayed()?.max(0) as u32);
+            frame.round_over = round_win_status != 0 && !game_rules.m_bWarmupPeriod()?;
+            frame.round_winner = match round_win_status as u8 {
+                TEAM_ID_T | TEAM_ID_CT => Some(round_win_status as u8),
+                _ => None,
+            };
+        }
+
+        let entities = self.states.resolve::<EntitySystem>(())?;
+        let class_name_cache = self.states.resolve::<ClassNameCache>(())?;
+        let mut score = RadarTeamScore::default();
+        let mut score_known = false;
+        for entity_identity in entities.all_identities() {
+            let entity_class = class_name_cache.lookup(&entity_identity.entity_class_info()?)?;
+            match entity_class.map(String::as_str) {
+                Some("CCSPlayerController") => match self.read_event_player(entity_identity) {
+                    Ok(Some(mut player)) => {
+                        player.weapon = radar_state
+                            .players
+                            .iter()
+                            .find(|info| info.controller_entity_id == player.controller_entity_id)
+                            .map(|info| info.weapon);
+                        frame.players.push(player);
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        log::debug!(
+                            "Failed to read player controller {}: {:#}",
+                            entity_identity.handle::<()>()?.get_entity_index(),
+                            error
+                        );
+                    }
+                },
+                Some("C_CSTeam") => {
+                    let team = entity_identity.entity_ptr::<C_CSTeam>()?.read_schema()?;
+                    match team.m_iTeamNum()? {
+                        TEAM_ID_T => score.score_t = team.m_iScore()?,
+                        TEAM_ID_CT => score.score_ct = team.m_iScore()?,
+                        _ => continue,
+                    }
+                    score_known = true;
+                }
+                _ => {}
+            }
+        }
+
+        if score_known {
+            frame.score = Some(score);
+        }
+        Ok(frame)
+    }
+
+    fn read_dropped_weapon(
+        &self,
+        identity: &CEntityIdentity,
+    ) -> anyhow::Result<Option<RadarDroppedWeapon>> {
+        let weapon = identity.entity_ptr::<C_CSWeaponBase>()?.read_schema()?;
+        if weapon.m_iState()? as u32 != CSWeaponState_t::WEAPON_NOT_CARRIED as u32
+            || weapon.m_hOwnerEntity()?.is_valid()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(RadarDroppedWeapon {
+            entity_id: identity.handle::<()>()?.get_entity_index(),
+            weapon: weapon
+                .m_AttributeManager()?
+                .m_Item()?
+                .m_iItemDefinitionIndex()?,
+            position: weapon.m_pGameSceneNode()?.read_schema()?.m_vecAbsOrigin()?,
+        }))
+    }
+
+    fn read_grenade(
+        &self,
+        identity: &CEntityIdentity,
+        grenade_type: GrenadeType,
+    ) -> anyhow::Result<Option<Utility>> {
+        let grenade = identity
+            .entity_ptr::<C_BaseCSGrenadeProjectile>()?
+            .read_schema()?;
+
+        if grenade.m_nExplodeEffectTickBegin()? > 0 {
+            /* the grenade has already exploded */
+            return Ok(None);
+        }
+
+        Ok(Some(Utility::Grenade(RadarGrenade {
+            entity_id: identity.handle::<()>()?.get_entity_index(),
+            grenade_type,
+            team_id: grenade.m_iTeamNum()?,
+            position: grenade
+                .m_pGameSceneNode()?
+                .read_schema()?
+                .m_vecAbsOrigin()?,
+        })))
+    }
+
+    fn read_molotov(&self, identity: &CEntityIdentity) -> anyhow::Result<Option<Utility>> {
+        let molotov = identity
+            .entity_ptr::<C_MolotovProjectile>()?
+            .read_schema()?;
+        let grenade_type = if molotov.m_bIsIncGrenade()? {
+            GrenadeType::Incendiary
+        } else {
+            GrenadeType::Molotov
+        };
+
+        self.read_grenade(identity, grenade_type)
+    }
+
+    fn read_smoke(&self, identity: &CEntityIdentity) -> anyhow::Result<Option<Utility>> {
+        let smoke = identity
+            .entity_ptr::<C_SmokeGrenadeProjectile>()?
+            .read_schema()?;
+
+        if !smoke.m_bDidSmokeEffect()? {
+            return self.read_grenade(identity, GrenadeType::Smoke);
+        }
+
+        let globals = self.states.resolve::<Globals>(())?;
+        let time_begin = smoke.m_nSmokeEffectTickBegin()? as f32 * TICK_INTERVAL;
+        let time_remaining = SMOKE_DURATION - (globals.time_2()? - time_begin);
+        if time_remaining <= 0.0 {
+            return Ok(None);
+        }
+
+        Ok(Some(Utility::Smoke(RadarSmoke {
+            entity_id: identity.handle::<()>()?.get_entity_index(),
+            team_id: smoke.m_iTeamNum()?,
+            position: smoke.m_vSmokeDetonationPos()?,
+            time_remaining,
+        })))
+    }
+
+    fn read_inferno(&self, identity: &CEntityIdentity) -> anyhow::Result<Option<Utility>> {
+        let inferno = identity.entity_ptr::<C_Inferno>()?.read_schema()?;
+
+        let fire_positions = inferno.m_firePositions()?;
+        let fire_burning = inferno.m_bFireIsBurning()?;
+        let fire_count = (inferno.m_fireCount()?.max(0) as usize).min(fire_positions.len());
+        let fire_positions = fire_positions[0..fire_count]
+            .iter()
+            .zip(fire_burning.iter())
+            .filter(|(_, burning)| **burning)
+            .map(|(position, _)| *position)
+            .collect::<Vec<_>>();
+
+        if fire_positions.is_empty() {
+            return Ok(None);
+        }
+
+        let globals = self.states.resolve::<Globals>(())?;
+        let time_begin = inferno.m_nFireEffectTickBegin()? as f32 * TICK_INTERVAL;
+        let time_remaining =
+            (inferno.m_nFireLifetime()? - (globals.time_2()? - time_begin)).max(0.0);
+
+        Ok(Some(Utility::Inferno(RadarInferno {
+            entity_id: identity.handle::<()>()?.get_entity_index(),
+            team_id: inferno.m_iTeamNum()?,
+            fire_positions,
+            time_remaining,
+        })))
+    }
 }
 
 impl RadarGenerator for CS2RadarGenerator {
@@ -166,17 +460,34 @@ impl RadarGenerator for CS2RadarGenerator {
         self.states.invalidate_states();
 
         let current_map = self.states.resolve::<CurrentMapState>(())?;
+        let world_name = current_map
+            .current_map
+            .as_ref()
+            .map(|v| v.as_str())
+            .unwrap_or("<empty>")
+            .to_string();
         let mut radar_state = RadarState {
             players: Vec::with_capacity(16),
-            world_name: current_map
-                .current_map
-                .as_ref()
-                .map(|v| v.as_str())
-                .unwrap_or("<empty>")
-                .to_string(),
+            map_calibration: map_projection::map_calibration(&world_name),
+            world_name,
             bomb: None,
+            bomb_trail: Default::default(),
+            grenades: Vec::new(),
+            smokes: Vec::new(),
+            infernos: Vec::new(),
+            dropped_weapons: Vec::new(),
         };
 
+        let new_round = self
+            .states
+            .resolve::<GameRules>(())
+            .map(|rules| rules.phase == RoundPhase::FreezeTime)
+            .unwrap_or(false);
+        if new_round {
+            self.bomb_tracker.reset();
+        }
+        let mut bomb_carrier = None;
+
         let entities = self.states.resolve::<EntitySystem>(())?;
         let class_name_cache = self.states.resolve::<ClassNameCache>(())?;
 
@@ -207,7 +518,11 @@ impl RadarGenerator for CS2RadarGenerator {
                 },
                 "C_C4" | "C_PlantedC4" => {
                     let bomb_ptr: Box<dyn BombData> = match entity_class.as_str() {
-                        "C_C4" => Box::new(entity_identity.entity_ptr::<C_C4>()?.read_schema()?),
+                        "C_C4" => {
+                            let bomb = entity_identity.entity_ptr::<C_C4>()?.read_schema()?;
+                            bomb_carrier = self.read_bomb_carrier(&bomb).unwrap_or_default();
+                            Box::new(bomb)
+                        }
                         "C_PlantedC4" => {
                             Box::new(entity_identity.entity_ptr::<C_PlantedC4>()?.read_schema()?)
                         }
@@ -218,10 +533,74 @@ impl RadarGenerator for CS2RadarGenerator {
                         radar_state.bomb = Some(bomb_data);
                     }
                 }
-                _ => {}
+                entity_class if is_weapon_class(entity_class) => {
+                    match self.read_dropped_weapon(entity_identity) {
+                        Ok(Some(weapon)) => radar_state.dropped_weapons.push(weapon),
+                        Ok(None) => {}
+                        Err(error) => {
+                            log::debug!(
+                                "Failed to read {} for {}: {:#}",
+                                entity_class,
+                                entity_identity.handle::<()>()?.get_entity_index(),
+                                error
+                            );
+                        }
+                    }
+                }
+                entity_class => {
+                    let utility = match entity_class {
+                        "C_HEGrenadeProjectile" => {
+                            self.read_grenade(entity_identity, GrenadeType::HighExplosive)
+                        }
+                        "C_FlashbangProjectile" => {
+                            self.read_grenade(entity_identity, GrenadeType::Flashbang)
+                        }
+                        "C_DecoyProjectile" => {
+                            self.read_grenade(entity_identity, GrenadeType::Decoy)
+                        }
+                        "C_MolotovProjectile" => self.read_molotov(entity_identity),
+                        "C_SmokeGrenadeProjectile" => self.read_smoke(entity_identity),
+                        "C_Inferno" => self.read_inferno(entity_identity),
+                        _ => continue,
+                    };
+
+                    match utility {
+                        Ok(Some(Utility::Grenade(grenade))) => radar_state.grenades.push(grenade),
+                        Ok(Some(Utility::Smoke(smoke))) => radar_state.smokes.push(smoke),
+                        Ok(Some(Utility::Inferno(inferno))) => radar_state.infernos.push(inferno),
+                        Ok(None) => {}
+                        Err(error) => {
+                            log::debug!(
+                                "Failed to read {} for {}: {:#}",
+                                entity_class,
+                                entity_identity.handle::<()>()?.get_entity_index(),
+                                error
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        self.bomb_tracker
+            .update(radar_state.bomb.as_ref(), bomb_carrier.clone());
+        radar_state.bomb_trail = self.bomb_tracker.trail();
+
+        match self.read_event_frame(&radar_state) {
+            Ok(mut frame) => {
+                frame.bomb_carrier = bomb_carrier;
+                let events = self.event_detector.update(frame);
+                self.events.extend(events);
+            }
+            Err(error) => {
+                log::debug!("Failed to read event frame: {:#}", error);
             }
         }
 
         Ok(radar_state)
     }
+
+    fn take_events(&mut self) -> Vec<RadarEvent> {
+        std::mem::take(&mut self.events)
+    }
 }
@@ -1,3 +1,9 @@
+mod bomb_tracker;
+pub use bomb_tracker::*;
+
+mod event_detector;
+pub use event_detector::*;
+
 mod generator;
 pub use generator::*;
 
@@ -12,13 +12,18 @@ use anyhow::{
     Error,
 };
 use radar_shared::{
+    hash_session_password,
     protocol::{
         C2SMessage,
         ClientEvent,
         RadarUpdate,
         S2CMessage,
+        PROTOCOL_VERSION,
     },
+    MessageCompression,
     RadarSettings,
+    RadarState,
+    RadarStateDelta,
 };
 use tokio::{
     self,
@@ -36,8 +41,13 @@ use url::Url;
 use crate::{
     create_ws_connection,
     RadarGenerator,
+    TransportCompression,
 };
 
+/// Amount of delta updates after which the full state is sent again
+/// so new or out of sync receivers are able to recover
+const KEYFRAME_INTERVAL: u32 = 100;
+
 pub struct WebRadarPublisher {
     pub session_id: String,
 
@@ -46,22 +56,52 @@ pub struct WebRadarPublisher {
 
     settings: RadarSettings,
 
+    /// The server supports delta updates
+    delta_updates: bool,
+
+    /// The server supports match events
+    event_updates: bool,
+
+    /// Last state sent to the server, None if the next update must be a keyframe
+    last_state: Option<RadarState>,
+    updates_since_keyframe: u32,
+
     transport_tx: Sender<C2SMessage>,
     transport_rx: Receiver<ClientEvent<S2CMessage>>,
 }
 
 impl WebRadarPublisher {
-    pub async fn connect(generator: Box<dyn RadarGenerator>, url: &Url) -> anyhow::Result<Self> {
-        let (tx, rx) = create_ws_connection(url).await?;
-        Self::create_from_transport(generator, tx, rx).await
+    /// Publish a new session.
+    /// If a password is given, viewers have to enter it before they can see the radar.
+    pub async fn connect(
+        generator: Box<dyn RadarGenerator>,
+        url: &Url,
+        password: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let (tx, rx, compression) = create_ws_connection(url).await?;
+        Self::create_from_transport(generator, tx, rx, Some(compression), password).await
     }
 
+    /// Publish a new session using an existing transport.
+    /// Messages will be compressed if the transport supports compression and the server accepts it.
     pub async fn create_from_transport(
         generator: Box<dyn RadarGenerator>,
         tx: Sender<C2SMessage>,
         mut rx: Receiver<ClientEvent<S2CMessage>>,
+        compression: Option<TransportCompression>,
+        password: Option<&str>,
     ) -> anyhow::Result<Self> {
-        let _ = tx.send(C2SMessage::InitializePublish { version: 1 }).await;
+        let _ = tx
+            .send(C2SMessage::InitializePublish {
+                version: PROTOCOL_VERSION,
+                password_hash: password.map(hash_session_password),
+                compression: if compression.is_some() {
+                    vec![MessageCompression::Zstd]
+                } else {
+                    vec![]
+                },
+            })
+            .await;
         let event = tokio::select! {
             message = rx.recv() => message.context("unexpected client disconnect")?,
             _ = time::sleep(Duration::from_secs(5)) => {
@@ -69,19 +109,32 @@ impl WebRadarPublisher {
             }
         };
 
-        let session_id = match event {
+        let (session_id, version, negotiated_compression) = match event {
             ClientEvent::RecvMessage(message) => match message {
                 S2CMessage::ResponseError { error } => {
                     anyhow::bail!("server error: {}", error)
                 }
-                S2CMessage::ResponseInitializePublish { session_id, .. } => session_id,
+                S2CMessage::ResponseInitializePublish {
+                    session_id,
+                    version,
+                    compression,
+                } => (session_id, version, compression),
                 _ => anyhow::bail!("invalid response"),
             },
             ClientEvent::RecvError(err) => anyhow::bail!("recv err: {:#}", err),
             ClientEvent::SendError(err) => anyhow::bail!("send err: {:#}", err),
         };
 
-        log::debug!("Connected with session id {}", session_id);
+        if let Some(compression) = &compression {
+            compression.set(negotiated_compression);
+        }
+
+        log::debug!(
+            "Connected with session id {} (protocol version {}, compression {:?})",
+            session_id,
+            version,
+            negotiated_compression
+        );
         Ok(Self {
             session_id,
             generator: RefCell::new(generator),
@@ -95,11 +148,49 @@ impl WebRadarPublisher {
                 show_team_players: true,
                 show_enemy_players: true,
             },
+
+            delta_updates: version >= 2,
+            event_updates: version >= 3,
+            last_state: None,
+            updates_since_keyframe: 0,
         })
     }
 
-    fn send_message(&self, message: C2SMessage) {
-        let _ = self.transport_tx.try_send(message);
+    fn send_message(&self, message: C2SMessage) -> bool {
+        self.transport_tx.try_send(message).is_ok()
+    }
+
+    /// Create the update for the new state.
+    /// Returns None if nothing has changed since the last update.
+    fn create_state_update(&mut self, state: RadarState) -> Option<RadarUpdate> {
+        if !self.delta_updates {
+            return Some(RadarUpdate::State {
+                state: Box::new(state),
+            });
+        }
+
+        match &self.last_state {
+            Some(last_state) if self.updates_since_keyframe < KEYFRAME_INTERVAL => {
+                let delta = RadarStateDelta::diff(last_state, &state);
+                self.last_state = Some(state);
+                self.updates_since_keyframe += 1;
+
+                if delta.is_empty() {
+                    None
+                } else {
+                    Some(RadarUpdate::StateDelta {
+                        delta: Box::new(delta),
+                    })
+                }
+            }
+            _ => {
+                self.last_state = Some(state.clone());
+                self.updates_since_keyframe = 0;
+                Some(RadarUpdate::State {
+                    state: Box::new(state),
+                })
+            }
+        }
     }
 
     pub async fn close_connection(self) {
@@ -139,14 +230,27 @@ impl Future for WebRadarPublisher {
         }
 
         while let Poll::Ready(_) = self.generate_interval.poll_tick(cx) {
-            match self.generator.borrow_mut().generate_state(&self.settings) {
-                Ok(state) => self.send_message(C2SMessage::RadarUpdate {
-                    update: RadarUpdate::State { state },
-                }),
+            let state = self.generator.borrow_mut().generate_state(&self.settings);
+            match state {
+                Ok(state) => {
+                    if let Some(update) = self.create_state_update(state) {
+                        if !self.send_message(C2SMessage::RadarUpdate { update }) {
+                            /* the server misses this update, hence the next deltas would be invalid */
+                            self.last_state = None;
+                        }
+                    }
+                }
                 Err(err) => {
                     log::warn!("Failed to generate radar state: {:#}", err);
                 }
             }
+
+            let events = self.generator.borrow_mut().take_events();
+            if self.event_updates && !events.is_empty() {
+                self.send_message(C2SMessage::RadarUpdate {
+                    update: RadarUpdate::Events { events },
+                });
+            }
         }
 
         Poll::Pending
@@ -1,11 +1,19 @@
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
 use futures_util::{
     SinkExt,
     StreamExt,
 };
-use radar_shared::protocol::{
-    C2SMessage,
-    ClientEvent,
-    S2CMessage,
+use radar_shared::{
+    protocol::{
+        C2SMessage,
+        ClientEvent,
+        S2CMessage,
+    },
+    MessageCompression,
 };
 use tokio::sync::mpsc::{
     self,
@@ -14,16 +22,39 @@ use tokio::sync::mpsc::{
 };
 use tokio_tungstenite::tungstenite::Message;
 
+/// Compression of the outgoing messages.
+/// Messages are sent uncompressed until a compression has been negotiated with the server.
+#[derive(Clone, Default)]
+pub struct TransportCompression {
+    compression: Arc<Mutex<Option<MessageCompression>>>,
+}
+
+impl TransportCompression {
+    pub fn get(&self) -> Option<MessageCompression> {
+        *self.compression.lock().unwrap()
+    }
+
+    pub fn set(&self, compression: Option<MessageCompression>) {
+        *self.compression.lock().unwrap() = compression;
+    }
+}
+
 pub async fn create_ws_connection(
     url: &url::Url,
-) -> anyhow::Result<(Sender<C2SMessage>, Receiver<ClientEvent<S2CMessage>>)> {
+) -> anyhow::Result<(
+    Sender<C2SMessage>,
+    Receiver<ClientEvent<S2CMessage>>,
+    TransportCompression,
+)> {
     let (socket, _) = tokio_tungstenite::connect_async(url).await?;
     let (mut socket_tx, mut socket_rx) = socket.split();
 
     let (channel_rx_tx, channel_rx) = mpsc::channel(16);
     let (channel_tx, mut channel_tx_rx) = mpsc::channel(16);
+    let compression = TransportCompression::default();
     tokio::spawn({
         let channel_rx_tx = channel_rx_tx.clone();
+        let compression = compression.clone();
         async move {
             while let Some(message) = channel_tx_rx.recv().await {
                 let message = match serde_json::to_string(&message) {
@@ -34,7 +65,18 @@ pub async fn create_ws_connection(
                     }
                 };
 
-                if let Err(err) = socket_tx.send(Message::Text(message)).await {
+                let message = match compression.get() {
+                    Some(compression) => match compression.compress(message.as_bytes()) {
+                        Ok(message) => Message::Binary(message),
+                        Err(err) => {
+                            let _ = channel_rx_tx.send(ClientEvent::SendError(err)).await;
+                            break;
+                        }
+                    },
+                    None => Message::Text(message),
+                };
+
+                if let Err(err) = socket_tx.send(message).await {
                     let _ = channel_rx_tx.send(ClientEvent::SendError(err.into())).await;
                     break;
                 }
@@ -90,5 +132,5 @@ pub async fn create_ws_connection(
         }
     });
 
-    Ok((channel_tx, channel_rx))
+    Ok((channel_tx, channel_rx, compression))
 }
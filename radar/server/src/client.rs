@@ -16,6 +16,9 @@ pub struct PubClient {
 
     pub state: ClientState,
 
+    /// Failed attempts to subscribe to a password protected session
+    pub failed_password_attempts: u32,
+
     pub tx: Sender<S2CMessage>,
 }
 
@@ -26,6 +29,7 @@ impl PubClient {
             address,
 
             state: ClientState::Uninitialized,
+            failed_password_attempts: 0,
             tx,
         }
     }
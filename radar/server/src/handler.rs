@@ -1,8 +1,12 @@
 use std::sync::Arc;
 
-use radar_shared::protocol::{
-    C2SMessage,
-    S2CMessage,
+use radar_shared::{
+    protocol::{
+        C2SMessage,
+        S2CMessage,
+        PROTOCOL_VERSION,
+    },
+    MessageCompression,
 };
 use tokio::sync::RwLock;
 
@@ -22,21 +26,40 @@ pub struct ServerCommandHandler {
 impl ServerCommandHandler {
     pub async fn handle_command(&self, command: C2SMessage) -> S2CMessage {
         match command {
-            C2SMessage::InitializePublish { .. } => {
+            C2SMessage::InitializePublish {
+                version,
+                password_hash,
+                compression,
+            } => {
                 let mut server = self.server.write().await;
-                let Some(session) = server.pub_session_create(self.client_id).await else {
+                let Some(session) = server
+                    .pub_session_create(self.client_id, password_hash)
+                    .await
+                else {
                     return S2CMessage::ResponseInvalidClientState;
                 };
 
                 S2CMessage::ResponseInitializePublish {
                     session_id: session.session_id.clone(),
-                    version: 1,
+                    version: version.min(PROTOCOL_VERSION),
+                    compression: compression
+                        .into_iter()
+                        .find(|compression| *compression == MessageCompression::Zstd),
                 }
             }
-            C2SMessage::InitializeSubscribe { session_id, .. } => {
+            C2SMessage::InitializeSubscribe {
+                version,
+                session_id,
+                password,
+            } => {
                 let mut server = self.server.write().await;
                 match server
-                    .pub_session_subscribe(&session_id, self.client_id)
+                    .pub_session_subscribe(
+                        &session_id,
+                        self.client_id,
+                        version,
+                        password.as_deref(),
+                    )
                     .await
                 {
                     PubSessionSubscribeResult::Success => S2CMessage::ResponseSubscribeSuccess,
@@ -49,10 +72,21 @@ impl ServerCommandHandler {
                     PubSessionSubscribeResult::InvalidSessionId => {
                         S2CMessage::ResponseSessionInvalidId
                     }
+                    PubSessionSubscribeResult::PasswordRequired => {
+                        S2CMessage::ResponseSessionRequiresPassword
+                    }
+                    PubSessionSubscribeResult::InvalidPassword => {
+                        S2CMessage::ResponseSessionInvalidPassword
+                    }
+                    PubSessionSubscribeResult::TooManyPasswordAttempts => {
+                        S2CMessage::ResponseError {
+                            error: "too many password attempts".to_string(),
+                        }
+                    }
                 }
             }
             C2SMessage::RadarUpdate { update } => {
-                let server = self.server.read().await;
+                let mut server = self.server.write().await;
                 let client = self.client.read().await;
 
                 let session_id = match &client.state {
@@ -60,7 +94,7 @@ impl ServerCommandHandler {
                     _ => return S2CMessage::ResponseInvalidClientState,
                 };
 
-                let session = match server.pub_session_find(session_id) {
+                let session = match server.pub_session_find_mut(session_id) {
                     Some(session) => session,
                     None => return S2CMessage::ResponseSessionInvalidId,
                 };
@@ -71,9 +105,7 @@ impl ServerCommandHandler {
                     };
                 }
 
-                session.broadcast(&S2CMessage::NotifyRadarUpdate {
-                    update: update.clone(),
-                });
+                session.handle_update(update);
 
                 S2CMessage::ResponseSuccess
             }
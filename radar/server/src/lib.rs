@@ -5,3 +5,4 @@ mod server;
 pub use server::*;
 
 mod handler;
+mod password;
@@ -0,0 +1,115 @@
+use std::{
+    collections::VecDeque,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use radar_shared::hash_session_password;
+use rand::RngCore;
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+/// Failed password attempts a single connection may make
+pub const MAX_CONNECTION_PASSWORD_ATTEMPTS: u32 = 5;
+
+/// Failed password attempts a session accepts within `SESSION_PASSWORD_ATTEMPT_WINDOW`
+const MAX_SESSION_PASSWORD_ATTEMPTS: usize = 20;
+const SESSION_PASSWORD_ATTEMPT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Viewer password of a session.
+/// Only a salted hash of the password hash sent by the publisher is kept.
+pub struct SessionPassword {
+    salt: [u8; 16],
+    hash: [u8; 32],
+
+    /// Timestamps of the recent failed attempts
+    failed_attempts: VecDeque<Instant>,
+}
+
+impl SessionPassword {
+    pub fn new(password_hash: &str) -> Self {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+
+        Self {
+            hash: Self::salted_hash(&salt, password_hash),
+            salt,
+            failed_attempts: Default::default(),
+        }
+    }
+
+    fn salted_hash(salt: &[u8], password_hash: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(salt);
+        hasher.update(password_hash.as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Too many failed attempts have been made recently.
+    /// No further attempts will be accepted until they expire.
+    pub fn is_locked(&mut self) -> bool {
+        let now = Instant::now();
+        while self
+            .failed_attempts
+            .front()
+            .is_some_and(|attempt| now - *attempt > SESSION_PASSWORD_ATTEMPT_WINDOW)
+        {
+            self.failed_attempts.pop_front();
+        }
+
+        self.failed_attempts.len() >= MAX_SESSION_PASSWORD_ATTEMPTS
+    }
+
+    /// Verify the password given by a viewer
+    pub fn verify(&mut self, password: &str) -> bool {
+        let hash = Self::salted_hash(&self.salt, &hash_session_password(password));
+        let valid = constant_time_eq(&hash, &self.hash);
+        if !valid {
+            self.failed_attempts.push_back(Instant::now());
+        }
+
+        valid
+    }
+}
+
+/// Compare two byte slices without leaking the position of the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod test {
+    use radar_shared::hash_session_password;
+
+    use super::{
+        SessionPassword,
+        MAX_SESSION_PASSWORD_ATTEMPTS,
+    };
+
+    #[test]
+    fn verify_password() {
+        let mut password = SessionPassword::new(&hash_session_password("secret"));
+        assert!(password.verify("secret"));
+        assert!(!password.verify("Secret"));
+        assert!(!password.verify(""));
+    }
+
+    #[test]
+    fn lock_after_failed_attempts() {
+        let mut password = SessionPassword::new(&hash_session_password("secret"));
+        for _ in 0..MAX_SESSION_PASSWORD_ATTEMPTS {
+            assert!(!password.is_locked());
+            password.verify("guess");
+        }
+
+        assert!(password.is_locked());
+    }
+}
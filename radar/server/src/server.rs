@@ -14,10 +14,18 @@ use futures_util::{
     SinkExt,
     StreamExt,
 };
-use radar_shared::protocol::{
-    C2SMessage,
-    ClientEvent,
-    S2CMessage,
+use radar_shared::{
+    map_projection::map_calibration,
+    protocol::{
+        C2SMessage,
+        ClientEvent,
+        RadarUpdate,
+        S2CMessage,
+    },
+    MessageCompression,
+    RadarEvent,
+    RadarState,
+    RadarTeamScore,
 };
 use rand::{
     distributions::Alphanumeric,
@@ -41,19 +49,114 @@ use warp::{
 use crate::{
     client::PubClient,
     handler::ServerCommandHandler,
+    password::{
+        SessionPassword,
+        MAX_CONNECTION_PASSWORD_ATTEMPTS,
+    },
     ClientState,
 };
 
+struct SessionSubscriber {
+    tx: mpsc::Sender<S2CMessage>,
+
+    /// Protocol version requested by the subscriber
+    version: u32,
+}
+
 pub struct PubSession {
     pub owner_id: u32,
     pub session_id: String,
-    subscriber: BTreeMap<u32, mpsc::Sender<S2CMessage>>,
+    password: Option<SessionPassword>,
+    subscriber: BTreeMap<u32, SessionSubscriber>,
+
+    /// Latest radar state including all received deltas.
+    /// Sent to new subscribers and to subscribers which do not support delta updates.
+    state: Option<RadarState>,
+
+    /// Latest team score, sent to new subscribers
+    score: Option<RadarTeamScore>,
 }
 
 impl PubSession {
     pub fn broadcast(&self, message: &S2CMessage) {
         for subscriber in self.subscriber.values() {
-            let _ = subscriber.try_send(message.clone());
+            let _ = subscriber.tx.try_send(message.clone());
+        }
+    }
+
+    /// Update the session state and forward the update to all subscribers
+    pub fn handle_update(&mut self, update: RadarUpdate) {
+        /* controllers predating the map calibration do not publish it */
+        let delta = match update {
+            RadarUpdate::StateDelta { mut delta } => {
+                if let Some(world_name) = &delta.world_name {
+                    if delta.map_calibration.is_none() {
+                        delta.map_calibration = map_calibration(world_name);
+                    }
+                }
+
+                delta
+            }
+            RadarUpdate::State { mut state } => {
+                if state.map_calibration.is_none() {
+                    state.map_calibration = map_calibration(&state.world_name);
+                }
+
+                self.state = Some(state.as_ref().clone());
+                self.broadcast(&S2CMessage::NotifyRadarUpdate {
+                    update: RadarUpdate::State { state },
+                });
+                return;
+            }
+            RadarUpdate::Events { events } => {
+                self.handle_events(events);
+                return;
+            }
+            update => {
+                self.broadcast(&S2CMessage::NotifyRadarUpdate { update });
+                return;
+            }
+        };
+
+        let Some(state) = &mut self.state else {
+            /* deltas can only be applied after the first keyframe */
+            return;
+        };
+        delta.apply(state);
+
+        let delta_message = S2CMessage::NotifyRadarUpdate {
+            update: RadarUpdate::StateDelta { delta },
+        };
+        let mut state_message = None;
+        for subscriber in self.subscriber.values() {
+            let message = if subscriber.version >= 2 {
+                &delta_message
+            } else {
+                state_message.get_or_insert_with(|| S2CMessage::NotifyRadarUpdate {
+                    update: RadarUpdate::State {
+                        state: Box::new(state.clone()),
+                    },
+                })
+            };
+
+            let _ = subscriber.tx.try_send(message.clone());
+        }
+    }
+
+    fn handle_events(&mut self, events: Vec<RadarEvent>) {
+        for event in &events {
+            if let RadarEvent::ScoreChanged { score } = event {
+                self.score = Some(*score);
+            }
+        }
+
+        let message = S2CMessage::NotifyRadarUpdate {
+            update: RadarUpdate::Events { events },
+        };
+        for subscriber in self.subscriber.values() {
+            if subscriber.version >= 3 {
+                let _ = subscriber.tx.try_send(message.clone());
+            }
         }
     }
 
@@ -69,12 +172,42 @@ pub enum HttpServeDirectory {
     /// Serve static HTTP files at a specific path
     Disk { path: PathBuf },
 
-    /// Bundle all static HTTP files with the server executable
+    /// Bundle all static HTTP files with the server executable.
+    /// Requires the `static-bundle` feature and the web radar to be build (`radar/web/dist`).
     Bundled,
 }
 
 impl HttpServeDirectory {}
 
+#[cfg(feature = "static-bundle")]
+static WEB_BUNDLE: include_dir::Dir<'static> =
+    include_dir::include_dir!("$CARGO_MANIFEST_DIR/../web/dist");
+
+/// Serve the bundled web radar. Unknown paths resolve to the index, as the routing is done by the web app.
+#[cfg(feature = "static-bundle")]
+fn bundled_files() -> warp::filters::BoxedFilter<(Box<dyn warp::Reply>,)> {
+    warp::get()
+        .and(warp::path::tail())
+        .map(|tail: warp::path::Tail| {
+            let file = WEB_BUNDLE
+                .get_file(tail.as_str())
+                .or_else(|| WEB_BUNDLE.get_file("index.html"));
+
+            match file {
+                Some(file) => {
+                    let mime = mime_guess::from_path(file.path()).first_or_octet_stream();
+                    Box::new(warp::reply::with_header(
+                        file.contents(),
+                        "content-type",
+                        mime.as_ref(),
+                    )) as Box<dyn warp::Reply>
+                }
+                None => Box::new(warp::http::StatusCode::NOT_FOUND),
+            }
+        })
+        .boxed()
+}
+
 pub struct RadarServer {
     ref_self: Weak<RwLock<RadarServer>>,
     client_id_counter: u32,
@@ -103,11 +236,12 @@ impl RadarServer {
         })
     }
 
+    /// Start the HTTP server and return the address it is listening on
     pub async fn listen_http(
         &mut self,
         addr: impl Into<SocketAddr>,
         static_serve: HttpServeDirectory,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<SocketAddr> {
         if self.www_acceptor.is_some() {
             anyhow::bail!("www already started");
         }
@@ -142,10 +276,7 @@ impl RadarServer {
 
                         let mut server = server.write().await;
                         let client_fut = server
-                            .register_client(
-                                PubClient::new(message_tx, address.clone()),
-                                message_rx,
-                            )
+                            .register_client(PubClient::new(message_tx, address), message_rx)
                             .await;
 
                         tokio::spawn(client_fut);
@@ -168,28 +299,39 @@ impl RadarServer {
                                         }
                                     };
 
-                                    if message.is_text() {
-                                        let message =
-                                            match serde_json::from_slice(message.as_bytes()) {
-                                                Ok(message) => message,
-                                                Err(err) => {
-                                                    let _ = message_rx_tx
-                                                        .send(ClientEvent::RecvError(err.into()))
-                                                        .await;
-                                                    break;
-                                                }
-                                            };
-
-                                        if let Err(err) = {
-                                            message_rx_tx
-                                                .send(ClientEvent::RecvMessage(message))
-                                                .await
-                                        } {
-                                            log::warn!(
-                                                "Failed to submit message to queue: {}",
-                                                err
-                                            );
+                                    let payload = if message.is_text() {
+                                        message.as_bytes().to_vec()
+                                    } else if message.is_binary() {
+                                        /* zstd is the only supported compression */
+                                        match MessageCompression::Zstd
+                                            .decompress(message.as_bytes())
+                                        {
+                                            Ok(payload) => payload,
+                                            Err(err) => {
+                                                let _ = message_rx_tx
+                                                    .send(ClientEvent::RecvError(err))
+                                                    .await;
+                                                break;
+                                            }
                                         }
+                                    } else {
+                                        continue;
+                                    };
+
+                                    let message = match serde_json::from_slice(&payload) {
+                                        Ok(message) => message,
+                                        Err(err) => {
+                                            let _ = message_rx_tx
+                                                .send(ClientEvent::RecvError(err.into()))
+                                                .await;
+                                            break;
+                                        }
+                                    };
+
+                                    if let Err(err) = {
+                                        message_rx_tx.send(ClientEvent::RecvMessage(message)).await
+                                    } {
+                                        log::warn!("Failed to submit message to queue: {}", err);
                                     }
                                 }
                             }
@@ -239,8 +381,14 @@ impl RadarServer {
                 .or(warp::fs::file(path.join("index.html")))
                 .map(|reply| Box::new(reply) as Box<dyn warp::Reply>)
                 .boxed(),
+            #[cfg(feature = "static-bundle")]
+            HttpServeDirectory::Bundled => ws_route
+                .or(bundled_files())
+                .map(|reply| Box::new(reply) as Box<dyn warp::Reply>)
+                .boxed(),
+            #[cfg(not(feature = "static-bundle"))]
             HttpServeDirectory::Bundled => {
-                anyhow::bail!("bundled is currently not supported");
+                anyhow::bail!("bundled web files require the static-bundle feature");
             }
             HttpServeDirectory::None => ws_route
                 .map(|reply| Box::new(reply) as Box<dyn warp::Reply>)
@@ -252,7 +400,7 @@ impl RadarServer {
 
         log::info!("Started server on {}", address);
 
-        Ok(())
+        Ok(address)
     }
 
     pub async fn unregister_client(&mut self, client_id: u32) {
@@ -335,7 +483,11 @@ impl RadarServer {
         }
     }
 
-    pub async fn pub_session_create(&mut self, owner_id: u32) -> Option<&PubSession> {
+    pub async fn pub_session_create(
+        &mut self,
+        owner_id: u32,
+        password_hash: Option<String>,
+    ) -> Option<&PubSession> {
         let owner = match self.clients.get(&owner_id) {
             Some(client) => client,
             None => return None,
@@ -352,16 +504,28 @@ impl RadarServer {
             .take(6)
             .collect::<String>();
 
+        let password_protected = password_hash.is_some();
         self.pub_sessions.insert(
             session_id.clone(),
             PubSession {
                 owner_id,
                 session_id: session_id.clone(),
+                password: password_hash.as_deref().map(SessionPassword::new),
                 subscriber: Default::default(),
+                state: None,
+                score: None,
             },
         );
 
-        log::info!("Created new session {}", session_id);
+        log::info!(
+            "Created new session {}{}",
+            session_id,
+            if password_protected {
+                " (password protected)"
+            } else {
+                ""
+            }
+        );
         owner.state = ClientState::Publisher {
             session_id: session_id.clone(),
         };
@@ -392,6 +556,10 @@ impl RadarServer {
         self.pub_sessions.get(session_id)
     }
 
+    pub fn pub_session_find_mut(&mut self, session_id: &str) -> Option<&mut PubSession> {
+        self.pub_sessions.get_mut(session_id)
+    }
+
     pub async fn pub_session_unsubscribe(&mut self, session_id: &String, client_id: u32) {
         if let Some(session) = self.pub_sessions.get_mut(session_id) {
             session.subscriber.remove(&client_id);
@@ -421,6 +589,8 @@ impl RadarServer {
         &mut self,
         session_id: &String,
         client_id: u32,
+        version: u32,
+        password: Option<&str>,
     ) -> PubSessionSubscribeResult {
         let client = match self.clients.get(&client_id) {
             Some(client) => client,
@@ -437,9 +607,54 @@ impl RadarServer {
             None => return PubSessionSubscribeResult::InvalidSessionId,
         };
 
-        session
-            .subscriber
-            .insert(client.client_id, client.tx.clone());
+        if let Some(session_password) = &mut session.password {
+            let Some(password) = password else {
+                return PubSessionSubscribeResult::PasswordRequired;
+            };
+
+            if client.failed_password_attempts >= MAX_CONNECTION_PASSWORD_ATTEMPTS
+                || session_password.is_locked()
+            {
+                return PubSessionSubscribeResult::TooManyPasswordAttempts;
+            }
+
+            if !session_password.verify(password) {
+                client.failed_password_attempts += 1;
+                log::debug!(
+                    "Client {} entered an invalid password for session {}",
+                    client.client_id,
+                    session.session_id
+                );
+                return PubSessionSubscribeResult::InvalidPassword;
+            }
+        }
+
+        session.subscriber.insert(
+            client.client_id,
+            SessionSubscriber {
+                tx: client.tx.clone(),
+                version,
+            },
+        );
+
+        if let Some(state) = &session.state {
+            /* deltas are relative to this state */
+            client.send_command(S2CMessage::NotifyRadarUpdate {
+                update: RadarUpdate::State {
+                    state: Box::new(state.clone()),
+                },
+            });
+        }
+
+        if let Some(score) = &session.score {
+            if version >= 3 {
+                client.send_command(S2CMessage::NotifyRadarUpdate {
+                    update: RadarUpdate::Events {
+                        events: vec![RadarEvent::ScoreChanged { score: *score }],
+                    },
+                });
+            }
+        }
 
         session.broadcast(&S2CMessage::NotifyViewCount {
             viewers: session.subscriber.len(),
@@ -456,5 +671,8 @@ pub enum PubSessionSubscribeResult {
     Success,
     InvalidClientState,
     InvalidSessionId,
+    PasswordRequired,
+    InvalidPassword,
+    TooManyPasswordAttempts,
     InvalidClientId,
 }
@@ -0,0 +1,33 @@
+use anyhow::Context;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// Compression of the binary web socket messages.
+/// Text messages are always uncompressed JSON.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageCompression {
+    Zstd,
+}
+
+/// Upper limit of a decompressed message to guard against decompression bombs
+const MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+const ZSTD_LEVEL: i32 = 3;
+
+impl MessageCompression {
+    pub fn compress(&self, payload: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Zstd => zstd::bulk::compress(payload, ZSTD_LEVEL).context("zstd compress"),
+        }
+    }
+
+    pub fn decompress(&self, payload: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Zstd => {
+                zstd::bulk::decompress(payload, MAX_MESSAGE_SIZE).context("zstd decompress")
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! Delta encoding of the radar state.
+//! Most of the radar state stays the same between two ticks, hence only the changes are transmitted.
+//! Full states (keyframes) are sent periodically so receivers are able to resynchronize.
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    map_projection::MapCalibration,
+    RadarBombInfo,
+    RadarBombTrail,
+    RadarDroppedWeapon,
+    RadarGrenade,
+    RadarInferno,
+    RadarPlayerInfo,
+    RadarSmoke,
+    RadarState,
+};
+
+fn changed<T: PartialEq + Clone>(old: Option<&T>, new: &T) -> Option<T> {
+    match old {
+        Some(old) if old == new => None,
+        _ => Some(new.clone()),
+    }
+}
+
+/// Changed fields of a player. Unchanged fields are None.
+/// All fields are set for players which have not been known before.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarPlayerDelta {
+    pub controller_entity_id: u32,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<u8>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_health: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_has_defuser: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weapon: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_flashtime: Option<f32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[f32; 3]>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<f32>,
+}
+
+impl RadarPlayerDelta {
+    /// Returns None if the player has not changed
+    pub fn diff(old: Option<&RadarPlayerInfo>, new: &RadarPlayerInfo) -> Option<Self> {
+        let delta = Self {
+            controller_entity_id: new.controller_entity_id,
+            team_id: changed(old.map(|old| &old.team_id), &new.team_id),
+            player_health: changed(old.map(|old| &old.player_health), &new.player_health),
+            player_has_defuser: changed(
+                old.map(|old| &old.player_has_defuser),
+                &new.player_has_defuser,
+            ),
+            player_name: changed(old.map(|old| &old.player_name), &new.player_name),
+            weapon: changed(old.map(|old| &old.weapon), &new.weapon),
+            player_flashtime: changed(old.map(|old| &old.player_flashtime), &new.player_flashtime),
+            position: changed(old.map(|old| &old.position), &new.position),
+            rotation: changed(old.map(|old| &old.rotation), &new.rotation),
+        };
+
+        let unchanged = delta.team_id.is_none()
+            && delta.player_health.is_none()
+            && delta.player_has_defuser.is_none()
+            && delta.player_name.is_none()
+            && delta.weapon.is_none()
+            && delta.player_flashtime.is_none()
+            && delta.position.is_none()
+            && delta.rotation.is_none();
+
+        if unchanged {
+            None
+        } else {
+            Some(delta)
+        }
+    }
+
+    pub fn apply(&self, player: &mut RadarPlayerInfo) {
+        if let Some(value) = self.team_id {
+            player.team_id = value;
+        }
+        if let Some(value) = self.player_health {
+            player.player_health = value;
+        }
+        if let Some(value) = self.player_has_defuser {
+            player.player_has_defuser = value;
+        }
+        if let Some(value) = &self.player_name {
+            player.player_name = value.clone();
+        }
+        if let Some(value) = self.weapon {
+            player.weapon = value;
+        }
+        if let Some(value) = self.player_flashtime {
+            player.player_flashtime = value;
+        }
+        if let Some(value) = self.position {
+            player.position = value;
+        }
+        if let Some(value) = self.rotation {
+            player.rotation = value;
+        }
+    }
+
+    /// Create the player info of a new player.
+    /// Returns None if not all fields are present.
+    pub fn to_player(&self) -> Option<RadarPlayerInfo> {
+        Some(RadarPlayerInfo {
+            controller_entity_id: self.controller_entity_id,
+            team_id: self.team_id?,
+            player_health: self.player_health?,
+            player_has_defuser: self.player_has_defuser?,
+            player_name: self.player_name.clone()?,
+            weapon: self.weapon?,
+            player_flashtime: self.player_flashtime?,
+            position: self.position?,
+            rotation: self.rotation?,
+        })
+    }
+}
+
+/// Changes between two radar states
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarStateDelta {
+    /// Players which have been added or changed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub players: Vec<RadarPlayerDelta>,
+
+    /// Controller entity ids of the players which have been removed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub players_removed: Vec<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bomb: Option<RadarBombInfo>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bomb_removed: bool,
+
+    /// Set if the world has changed.
+    /// The map calibration is only updated if the world name is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_calibration: Option<MapCalibration>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bomb_trail: Option<RadarBombTrail>,
+
+    /// Utility is replaced as a whole if anything has changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grenades: Option<Vec<RadarGrenade>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smokes: Option<Vec<RadarSmoke>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infernos: Option<Vec<RadarInferno>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dropped_weapons: Option<Vec<RadarDroppedWeapon>>,
+}
+
+impl RadarStateDelta {
+    pub fn diff(old: &RadarState, new: &RadarState) -> Self {
+        let players = new
+            .players
+            .iter()
+            .filter_map(|player| {
+                let old_player = old
+                    .players
+                    .iter()
+                    .find(|old| old.controller_entity_id == player.controller_entity_id);
+
+                RadarPlayerDelta::diff(old_player, player)
+            })
+            .collect();
+
+        let players_removed = old
+            .players
+            .iter()
+            .map(|player| player.controller_entity_id)
+            .filter(|entity_id| {
+                !new.players
+                    .iter()
+                    .any(|player| player.controller_entity_id == *entity_id)
+            })
+            .collect();
+
+        let world_changed =
+            old.world_name != new.world_name || old.map_calibration != new.map_calibration;
+
+        Self {
+            players,
+            players_removed,
+
+            bomb: new
+                .bomb
+                .as_ref()
+                .and_then(|bomb| changed(old.bomb.as_ref(), bomb)),
+            bomb_removed: old.bomb.is_some() && new.bomb.is_none(),
+
+            world_name: world_changed.then(|| new.world_name.clone()),
+            map_calibration: world_changed.then(|| new.map_calibration.clone()).flatten(),
+
+            bomb_trail: changed(Some(&old.bomb_trail), &new.bomb_trail),
+
+            grenades: changed(Some(&old.grenades), &new.grenades),
+            smokes: changed(Some(&old.smokes), &new.smokes),
+            infernos: changed(Some(&old.infernos), &new.infernos),
+
+            dropped_weapons: changed(Some(&old.dropped_weapons), &new.dropped_weapons),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the changes onto the state the delta has been created against.
+    /// New players without all fields present are ignored and will be added by the next keyframe.
+    pub fn apply(&self, state: &mut RadarState) {
+        state
+            .players
+            .retain(|player| !self.players_removed.contains(&player.controller_entity_id));
+
+        for delta in &self.players {
+            match state
+                .players
+                .iter_mut()
+                .find(|player| player.controller_entity_id == delta.controller_entity_id)
+            {
+                Some(player) => delta.apply(player),
+                None => state.players.extend(delta.to_player()),
+            }
+        }
+
+        if let Some(bomb) = &self.bomb {
+            state.bomb = Some(bomb.clone());
+        } else if self.bomb_removed {
+            state.bomb = None;
+        }
+
+        if let Some(world_name) = &self.world_name {
+            state.world_name = world_name.clone();
+            state.map_calibration = self.map_calibration.clone();
+        }
+
+        if let Some(bomb_trail) = &self.bomb_trail {
+            state.bomb_trail = bomb_trail.clone();
+        }
+
+        if let Some(grenades) = &self.grenades {
+            state.grenades = grenades.clone();
+        }
+        if let Some(smokes) = &self.smokes {
+            state.smokes = smokes.clone();
+        }
+        if let Some(infernos) = &self.infernos {
+            state.infernos = infernos.clone();
+        }
+
+        if let Some(dropped_weapons) = &self.dropped_weapons {
+            state.dropped_weapons = dropped_weapons.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RadarStateDelta;
+    use crate::{
+        C4State,
+        RadarBombInfo,
+        RadarBombTrail,
+        RadarPlayerInfo,
+        RadarSmoke,
+        RadarState,
+    };
+
+    fn player(controller_entity_id: u32, position: [f32; 3]) -> RadarPlayerInfo {
+        RadarPlayerInfo {
+            controller_entity_id,
+            team_id: 2,
+            player_health: 100,
+            player_has_defuser: false,
+            player_name: format!("player {}", controller_entity_id),
+            weapon: 7,
+            player_flashtime: 0.0,
+            position,
+            rotation: 90.0,
+        }
+    }
+
+    #[test]
+    fn apply_restores_state() {
+        let old = RadarState {
+            players: vec![
+                player(1, [0.0; 3]),
+                player(2, [0.0; 3]),
+                player(3, [0.0; 3]),
+            ],
+            bomb: None,
+            world_name: "de_dust2".to_string(),
+            map_calibration: None,
+            bomb_trail: Default::default(),
+            grenades: vec![],
+            smokes: vec![],
+            infernos: vec![],
+            dropped_weapons: vec![],
+        };
+
+        let mut new = old.clone();
+        new.players.remove(1);
+        new.players[0].position = [10.0, 20.0, 0.0];
+        new.players[1].player_health = 42;
+        new.players.push(player(4, [5.0; 3]));
+        new.bomb = Some(RadarBombInfo {
+            position: [1.0, 2.0, 3.0],
+            state: C4State::Dropped,
+            bomb_site: None,
+        });
+        new.bomb_trail = RadarBombTrail {
+            positions: vec![[1.0, 2.0, 3.0]],
+            carrier_history: vec![],
+        };
+        new.smokes = vec![RadarSmoke {
+            entity_id: 100,
+            team_id: 3,
+            position: [1.0, 2.0, 3.0],
+            time_remaining: 12.5,
+        }];
+
+        let delta = RadarStateDelta::diff(&old, &new);
+        assert_eq!(delta.players.len(), 3);
+        assert_eq!(delta.players_removed, vec![2]);
+        assert!(delta.players[0].player_name.is_none());
+        assert!(delta.world_name.is_none());
+
+        let mut state = old.clone();
+        delta.apply(&mut state);
+        assert_eq!(state, new);
+
+        assert!(RadarStateDelta::diff(&new, &new).is_empty());
+    }
+}
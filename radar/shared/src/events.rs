@@ -0,0 +1,59 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarEventPlayer {
+    pub controller_entity_id: u32,
+    pub player_name: String,
+    pub team_id: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarTeamScore {
+    pub score_t: i32,
+    pub score_ct: i32,
+}
+
+/// Match events detected by comparing consecutive game states
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum RadarEvent {
+    Kill {
+        /// None if the killer is unknown (e.g. fall damage or the bomb explosion)
+        killer: Option<RadarEventPlayer>,
+        victim: RadarEventPlayer,
+
+        /// Active weapon of the killer at the time of the kill
+        weapon: Option<u16>,
+        headshot: bool,
+    },
+
+    BombPlanted {
+        /// Name of the last player carrying the bomb
+        player_name: Option<String>,
+
+        /// 0 = A
+        /// 1 = B
+        bomb_site: Option<u8>,
+    },
+    BombDefused {
+        player_name: Option<String>,
+    },
+    BombDetonated,
+
+    RoundStart {
+        /// Round number starting at 1
+        round: u32,
+    },
+    RoundEnd {
+        /// Team id of the team which has won the round
+        winner_team: Option<u8>,
+    },
+    ScoreChanged {
+        score: RadarTeamScore,
+    },
+}
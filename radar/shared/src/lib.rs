@@ -1,4 +1,17 @@
+pub mod map_projection;
 pub mod protocol;
 
+mod compression;
+pub use compression::*;
+
+mod delta;
+pub use delta::*;
+
+mod events;
+pub use events::*;
+
+mod password;
+pub use password::*;
+
 mod types;
 pub use types::*;
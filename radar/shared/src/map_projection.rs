@@ -0,0 +1,76 @@
+//! Calibration of the map overview images.
+//! The calibration data is published to the web radar together with the radar state.
+//! The radar server falls back to the built-in calibration if the publisher did not send it.
+//! Positions are projected onto the overview image by `projectPosition` of the web radar.
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// A floor of a map with multiple levels which has been drawn at a different
+/// location of the overview image.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MapFloor {
+    /// Offset of the floor in percent of the overview image size
+    pub offset: [f32; 2],
+
+    /// Height range of the floor (min, max)
+    pub z_range: [f32; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MapCalibration {
+    /// World units per overview image pixel (the overview image is 1024x1024 pixels)
+    pub resolution: f32,
+
+    /// Offset which needs to be added to a world position so the
+    /// bottom left corner of the overview image will be at zero
+    pub offset: [f32; 2],
+
+    pub floors: Vec<MapFloor>,
+}
+
+fn calibration(
+    resolution: f32,
+    offset: [f32; 2],
+    floors: &[([f32; 2], [f32; 2])],
+) -> MapCalibration {
+    MapCalibration {
+        resolution,
+        offset,
+        floors: floors
+            .iter()
+            .map(|(offset, z_range)| MapFloor {
+                offset: *offset,
+                z_range: *z_range,
+            })
+            .collect(),
+    }
+}
+
+/// Get the calibration of the overview image for the given map.
+/// Returns `None` if the map is not supported.
+pub fn map_calibration(map_name: &str) -> Option<MapCalibration> {
+    let calibration = match map_name {
+        "de_ancient" => calibration(4.26, [2590.0, 2520.0], &[]),
+        "de_anubis" => calibration(5.25, [2830.0, 2030.0], &[]),
+        "de_cache" => calibration(5.54, [2020.0, 2390.0], &[]),
+        "de_dust2" => calibration(4.40, [2470.0, 1255.0], &[]),
+        "de_inferno" => calibration(4.91, [2090.0, 1150.0], &[]),
+        "de_mirage" => calibration(5.02, [3240.0, 3410.0], &[]),
+        "de_nuke" => calibration(6.98, [3290.0, 5990.0], &[([0.0, -46.0], [-780.0, -480.0])]),
+        "de_overpass" => calibration(5.18, [4830.0, 3540.0], &[]),
+        "de_train" => calibration(4.74, [2510.0, 2440.0], &[]),
+        "de_vertigo" => calibration(
+            4.96,
+            [3890.0, 3800.0],
+            &[([0.2, -42.6], [11485.0, 11680.0])],
+        ),
+        _ => return None,
+    };
+
+    Some(calibration)
+}
@@ -0,0 +1,26 @@
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+/// Hash a session viewer password.
+/// Publishers only transmit the hash, the server compares it against the hash of the password given by the viewer.
+pub fn hash_session_password(password: &str) -> String {
+    Sha256::digest(password.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::hash_session_password;
+
+    #[test]
+    fn hashes_sha256_hex() {
+        assert_eq!(
+            hash_session_password("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -4,47 +4,101 @@ use serde::{
 };
 
 use crate::{
+    MessageCompression,
+    RadarEvent,
     RadarSettings,
     RadarState,
+    RadarStateDelta,
 };
 
+/// Latest protocol version.
+/// Version 2 added delta state updates (`RadarUpdate::StateDelta`).
+/// Version 3 added match events (`RadarUpdate::Events`).
+pub const PROTOCOL_VERSION: u32 = 3;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum RadarUpdate {
-    Settings { settings: RadarSettings },
-    State { state: RadarState },
-}
+    Settings {
+        settings: RadarSettings,
+    },
+    State {
+        state: Box<RadarState>,
+    },
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub enum SubscribeResult {
-    Success,
-    SessionDoesNotExists,
-    // SessionRequiresPassword,
+    /// Changes since the last state. Only sent to clients supporting protocol version 2.
+    StateDelta {
+        delta: Box<RadarStateDelta>,
+    },
+
+    /// Events which occurred since the last update. Only sent to clients supporting protocol version 3.
+    Events {
+        events: Vec<RadarEvent>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum S2CMessage {
     // Generic responses
     ResponseSuccess,
-    ResponseError { error: String },
+    ResponseError {
+        error: String,
+    },
 
     ResponseInvalidClientState,
-    ResponseInitializePublish { session_id: String, version: u32 },
+    ResponseInitializePublish {
+        session_id: String,
+
+        /// Negotiated protocol version
+        version: u32,
+
+        /// Compression the publisher should use for binary messages.
+        /// Uncompressed text messages are always accepted.
+        #[serde(default)]
+        compression: Option<MessageCompression>,
+    },
     ResponseSubscribeSuccess,
     ResponseSessionInvalidId,
+    ResponseSessionRequiresPassword,
+    ResponseSessionInvalidPassword,
 
-    NotifyRadarUpdate { update: RadarUpdate },
-    NotifyViewCount { viewers: usize },
+    NotifyRadarUpdate {
+        update: RadarUpdate,
+    },
+    NotifyViewCount {
+        viewers: usize,
+    },
     NotifySessionClosed,
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum C2SMessage {
-    InitializePublish { version: u32 },
-    InitializeSubscribe { version: u32, session_id: String },
+    InitializePublish {
+        version: u32,
+
+        /// Hash of the viewer password (see `hash_session_password`).
+        /// Viewers of a password protected session have to send the password when subscribing.
+        #[serde(default)]
+        password_hash: Option<String>,
+
+        /// Supported message compressions, ordered by preference
+        #[serde(default)]
+        compression: Vec<MessageCompression>,
+    },
+    InitializeSubscribe {
+        version: u32,
+        session_id: String,
+
+        #[serde(default)]
+        password: Option<String>,
+    },
 
-    RadarUpdate { update: RadarUpdate },
+    RadarUpdate {
+        update: RadarUpdate,
+    },
 
-    Disconnect { message: String },
+    Disconnect {
+        message: String,
+    },
 }
 
 pub enum ClientEvent<T> {
@@ -3,14 +3,16 @@ use serde::{
     Serialize,
 };
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+use crate::map_projection::MapCalibration;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RadarSettings {
     pub show_team_players: bool,
     pub show_enemy_players: bool,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct BombDefuser {
     /// Total time remaining for a successful bomb defuse
@@ -20,7 +22,7 @@ pub struct BombDefuser {
     pub player_name: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum C4State {
     /// Bomb is dropped
@@ -45,15 +47,39 @@ pub enum C4State {
     Defused,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RadarState {
     pub players: Vec<RadarPlayerInfo>,
     pub bomb: Option<RadarBombInfo>,
     pub world_name: String,
+
+    /// Calibration of the overview image of the current map.
+    /// None if the map is not supported.
+    #[serde(default)]
+    pub map_calibration: Option<MapCalibration>,
+
+    #[serde(default)]
+    pub bomb_trail: RadarBombTrail,
+
+    /// Grenades which are currently flying
+    #[serde(default)]
+    pub grenades: Vec<RadarGrenade>,
+
+    /// Active smoke clouds
+    #[serde(default)]
+    pub smokes: Vec<RadarSmoke>,
+
+    /// Burning molotov and incendiary areas
+    #[serde(default)]
+    pub infernos: Vec<RadarInferno>,
+
+    /// Weapons lying on the ground
+    #[serde(default)]
+    pub dropped_weapons: Vec<RadarDroppedWeapon>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RadarPlayerInfo {
     pub controller_entity_id: u32,
@@ -69,7 +95,7 @@ pub struct RadarPlayerInfo {
     pub rotation: f32,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RadarBombInfo {
     pub position: [f32; 3],
@@ -80,3 +106,86 @@ pub struct RadarBombInfo {
     /// 1 = B
     pub bomb_site: Option<u8>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarBombCarrierEvent {
+    /// Name of the player who picked up or dropped the bomb
+    pub player_name: String,
+
+    /// True if the bomb has been picked up, false if it has been dropped
+    pub picked_up: bool,
+
+    /// Position where the bomb has been picked up or dropped
+    pub position: [f32; 3],
+}
+
+/// Movement of the C4 during the current round
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarBombTrail {
+    /// Recent positions of the bomb (oldest first)
+    pub positions: Vec<[f32; 3]>,
+
+    /// Players who picked up or dropped the bomb (oldest first)
+    pub carrier_history: Vec<RadarBombCarrierEvent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GrenadeType {
+    HighExplosive,
+    Flashbang,
+    Smoke,
+    Molotov,
+    Incendiary,
+    Decoy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarGrenade {
+    pub entity_id: u32,
+    pub grenade_type: GrenadeType,
+
+    /// Team of the player who has thrown the grenade
+    pub team_id: u8,
+
+    pub position: [f32; 3],
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarSmoke {
+    pub entity_id: u32,
+    pub team_id: u8,
+
+    /// Center of the smoke cloud
+    pub position: [f32; 3],
+
+    /// Time remaining (in seconds) until the smoke fades
+    pub time_remaining: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarInferno {
+    pub entity_id: u32,
+    pub team_id: u8,
+
+    /// Positions of the individual fires which are still burning
+    pub fire_positions: Vec<[f32; 3]>,
+
+    /// Time remaining (in seconds) until the fires extinguish
+    pub time_remaining: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarDroppedWeapon {
+    pub entity_id: u32,
+
+    /// Item definition index of the weapon
+    pub weapon: u16,
+    pub position: [f32; 3],
+}
@@ -3,6 +3,10 @@ import { EventEmitter } from "../utils/ee";
 
 export type SubscriberClientState = {
     state: "new" | "connecting" | "initializing" | "connected" | "disconnected",
+} | {
+    /* the session is password protected, the connection stays open until a password has been submitted */
+    state: "password-required",
+    passwordInvalid: boolean,
 } | {
     state: "failed",
     reason: string
@@ -11,6 +15,7 @@ export type SubscriberClientState = {
 export interface SubscriberClientEvents {
     "state_changed": SubscriberClientState,
     "radar.state": RadarState,
+    "radar.events": RadarEvent[],
 }
 
 export class SubscriberClient {
@@ -18,6 +23,9 @@ export class SubscriberClient {
 
     private currentState: SubscriberClientState;
     private connection: WebSocket | null;
+    private sessionId: string | null;
+    private radarState: RadarState | null;
+    private teamScore: RadarTeamScore | null;
 
     private commandHandler: { [T in keyof S2CMessage]?: (payload: S2CMessage[T]) => void } = {};
 
@@ -27,6 +35,9 @@ export class SubscriberClient {
         this.events = new EventEmitter();
         this.currentState = { state: "new" };
         this.connection = null;
+        this.sessionId = null;
+        this.radarState = null;
+        this.teamScore = null;
 
         this.commandHandler = {};
         this.commandHandler["ResponseError"] = payload => {
@@ -39,12 +50,36 @@ export class SubscriberClient {
             this.closeSocket();
         };
 
+        this.commandHandler["ResponseSessionRequiresPassword"] = () => {
+            this.updateState({ state: "password-required", passwordInvalid: false });
+        };
+
+        this.commandHandler["ResponseSessionInvalidPassword"] = () => {
+            this.updateState({ state: "password-required", passwordInvalid: true });
+        };
+
         this.commandHandler["ResponseSubscribeSuccess"] = () => {
             this.updateState({ state: "connected" });
         };
 
         this.commandHandler["NotifyRadarUpdate"] = payload => {
-            this.events.emit("radar.state", payload.update.State.state)
+            if ("State" in payload.update) {
+                this.radarState = payload.update.State.state;
+            } else if ("Events" in payload.update) {
+                this.handleEvents(payload.update.Events.events);
+                return;
+            } else if ("StateDelta" in payload.update) {
+                if (!this.radarState) {
+                    /* the server always sends the full state first */
+                    return;
+                }
+
+                this.radarState = applyRadarStateDelta(this.radarState, payload.update.StateDelta.delta);
+            } else {
+                return;
+            }
+
+            this.events.emit("radar.state", this.radarState);
         };
 
         this.commandHandler["NotifySessionClosed"] = () => {
@@ -56,6 +91,24 @@ export class SubscriberClient {
         return this.currentState;
     }
 
+    public getRadarState(): RadarState | null {
+        return this.radarState;
+    }
+
+    public getTeamScore(): RadarTeamScore | null {
+        return this.teamScore;
+    }
+
+    private handleEvents(events: RadarEvent[]) {
+        for (const event of events) {
+            if (typeof event === "object" && "scoreChanged" in event) {
+                this.teamScore = event.scoreChanged.score;
+            }
+        }
+
+        this.events.emit("radar.events", events);
+    }
+
     private updateState(newState: SubscriberClientState) {
         if (this.currentState === newState) {
             return;
@@ -85,13 +138,15 @@ export class SubscriberClient {
             throw new Error(`invalid session state`);
         }
 
+        this.sessionId = sessionId;
         this.updateState({ state: "connecting" });
         this.connection = new WebSocket(this.targetAddress);
         this.connection.onopen = () => {
             this.updateState({ state: "initializing" });
             this.sendCommand("InitializeSubscribe", {
-                version: 1,
-                session_id: sessionId
+                version: kProtocolVersion,
+                session_id: sessionId,
+                password: null,
             });
         };
 
@@ -122,6 +177,18 @@ export class SubscriberClient {
         };
     }
 
+    public submitPassword(password: string) {
+        if (this.currentState.state !== "password-required") {
+            throw new Error(`invalid session state`);
+        }
+
+        this.sendCommand("InitializeSubscribe", {
+            version: kProtocolVersion,
+            session_id: this.sessionId,
+            password,
+        });
+    }
+
     public sendCommand<T extends keyof C2SMessage>(command: T, payload: C2SMessage[T]) {
         this.connection.send(JSON.stringify({
             [command]: payload
@@ -129,8 +196,59 @@ export class SubscriberClient {
     }
 }
 
+/* version 2 receives delta state updates, version 3 match events */
+const kProtocolVersion = 3;
+
+function applyRadarStateDelta(state: RadarState, delta: RadarStateDelta): RadarState {
+    const playersRemoved = delta.playersRemoved ?? [];
+    const players = state.players
+        .filter(player => !playersRemoved.includes(player.controllerEntityId))
+        .map(player => ({ ...player }));
+
+    for (const playerDelta of delta.players ?? []) {
+        const player = players.find(player => player.controllerEntityId === playerDelta.controllerEntityId);
+        if (player) {
+            Object.assign(player, playerDelta);
+        } else if (kPlayerFields.every(field => field in playerDelta)) {
+            players.push(playerDelta as RadarPlayerInfo);
+        }
+    }
+
+    const result = { ...state, players };
+    if (delta.bomb) {
+        result.bomb = delta.bomb;
+    } else if (delta.bombRemoved) {
+        result.bomb = null;
+    }
+
+    if (typeof delta.worldName === "string") {
+        result.worldName = delta.worldName;
+        result.mapCalibration = delta.mapCalibration ?? null;
+    }
+
+    if (delta.bombTrail) {
+        result.bombTrail = delta.bombTrail;
+    }
+
+    if (delta.grenades) {
+        result.grenades = delta.grenades;
+    }
+    if (delta.smokes) {
+        result.smokes = delta.smokes;
+    }
+    if (delta.infernos) {
+        result.infernos = delta.infernos;
+    }
+
+    if (delta.droppedWeapons) {
+        result.droppedWeapons = delta.droppedWeapons;
+    }
+
+    return result;
+}
+
 export type C2SMessage = {
-    "InitializeSubscribe": { version: number, session_id: string },
+    "InitializeSubscribe": { version: number, session_id: string, password: string | null },
 }
 
 export type S2CMessage = {
@@ -140,6 +258,8 @@ export type S2CMessage = {
     "ResponseInitializePublish": { session_id: string, version: number },
     "ResponseSubscribeSuccess": void,
     "ResponseSessionInvalidId": void,
+    "ResponseSessionRequiresPassword": void,
+    "ResponseSessionInvalidPassword": void,
 
     "NotifyRadarUpdate": {
         update: RadarUpdate
@@ -150,13 +270,85 @@ export type S2CMessage = {
 
 export type RadarUpdate = {
     "State": { state: RadarState },
-    /* "Settings": any */
+} | {
+    "StateDelta": { delta: RadarStateDelta },
+} | {
+    "Events": { events: RadarEvent[] },
+} | {
+    "Settings": any,
+};
+
+export type RadarEventPlayer = {
+    controllerEntityId: number,
+    playerName: string,
+    teamId: number,
+};
+
+export type RadarTeamScore = {
+    scoreT: number,
+    scoreCt: number,
+};
+
+export type RadarEvent =
+    | {
+        kill: {
+            /* null if the killer is unknown (e.g. fall damage or the bomb explosion) */
+            killer: RadarEventPlayer | null,
+            victim: RadarEventPlayer,
+            weapon: number | null,
+            headshot: boolean,
+        }
+    }
+    | { bombPlanted: { playerName: string | null, bombSite: number | null } }
+    | { bombDefused: { playerName: string | null } }
+    | "bombDetonated"
+    | { roundStart: { round: number } }
+    | { roundEnd: { winnerTeam: number | null } }
+    | { scoreChanged: { score: RadarTeamScore } };
+
+export type RadarStateDelta = {
+    players?: RadarPlayerDelta[],
+    playersRemoved?: number[],
+    bomb?: RadarBombInfo,
+    bombRemoved?: boolean,
+    worldName?: string,
+    mapCalibration?: MapCalibration,
+    bombTrail?: RadarBombTrail,
+    grenades?: RadarGrenade[],
+    smokes?: RadarSmoke[],
+    infernos?: RadarInferno[],
+    droppedWeapons?: RadarDroppedWeapon[],
+};
+
+export type RadarPlayerDelta = Partial<RadarPlayerInfo> & {
+    controllerEntityId: number,
 };
 
 export type RadarState = {
     players: RadarPlayerInfo[],
     worldName: string,
+    mapCalibration: MapCalibration | null,
     bomb: RadarBombInfo,
+    bombTrail: RadarBombTrail,
+
+    /* missing if the publisher does not support utility */
+    grenades?: RadarGrenade[],
+    smokes?: RadarSmoke[],
+    infernos?: RadarInferno[],
+
+    /* missing if the publisher does not support dropped weapons */
+    droppedWeapons?: RadarDroppedWeapon[],
+};
+
+export type MapFloor = {
+    offset: [number, number],
+    zRange: [number, number],
+};
+
+export type MapCalibration = {
+    resolution: number,
+    offset: [number, number],
+    floors: MapFloor[],
 };
 
 export type RadarPlayerInfo = {
@@ -174,12 +366,65 @@ export type RadarPlayerInfo = {
     rotation: number,
 };
 
+const kPlayerFields: (keyof RadarPlayerInfo)[] = [
+    "teamId",
+    "playerHealth",
+    "playerHasDefuser",
+    "playerName",
+    "playerFlashtime",
+    "weapon",
+    "position",
+    "rotation",
+];
+
 export type RadarBombInfo = {
     position: [number, number, number],
     state: C4State,
     bombSite: number | null,
 };
 
+export type RadarBombCarrierEvent = {
+    playerName: string,
+    pickedUp: boolean,
+    position: [number, number, number],
+};
+
+export type RadarBombTrail = {
+    positions: [number, number, number][],
+    carrierHistory: RadarBombCarrierEvent[],
+};
+
+export type GrenadeType = "highExplosive" | "flashbang" | "smoke" | "molotov" | "incendiary" | "decoy";
+
+export type RadarGrenade = {
+    entityId: number,
+    grenadeType: GrenadeType,
+    teamId: number,
+    position: [number, number, number],
+};
+
+export type RadarSmoke = {
+    entityId: number,
+    teamId: number,
+    position: [number, number, number],
+    timeRemaining: number,
+};
+
+export type RadarInferno = {
+    entityId: number,
+    teamId: number,
+    firePositions: [number, number, number][],
+    timeRemaining: number,
+};
+
+export type RadarDroppedWeapon = {
+    entityId: number,
+
+    /* item definition index */
+    weapon: number,
+    position: [number, number, number],
+};
+
 export type C4State =
     | { variant: 'Carried' }
     | { variant: 'Dropped'}
@@ -0,0 +1,124 @@
+import * as React from "react";
+import { Box, Typography } from "@mui/material";
+import { useSubscriberClient } from "../../../components/connection";
+import { RadarEvent, RadarEventPlayer, RadarTeamScore } from "../../../../backend/connection";
+import { teamColor } from "./radar";
+
+/* max amount of entries shown in the kill feed */
+const kFeedLength = 6;
+
+type FeedEntry = {
+    id: number,
+    event: RadarEvent,
+};
+
+const bombSiteName = (bombSite: number | null) => bombSite === 1 ? "B" : bombSite === 0 ? "A" : "?";
+const teamName = (teamId: number) => teamId === 3 ? "CT" : "T";
+
+export const MatchEvents = React.memo(() => {
+    return (
+        <Box sx={{
+            position: "absolute",
+            top: 48,
+            right: 24,
+            zIndex: 1,
+
+            display: "flex",
+            flexDirection: "column",
+            alignItems: "flex-end",
+            gap: 1,
+
+            pointerEvents: "none",
+        }}>
+            <TeamScore />
+            <KillFeed />
+        </Box>
+    );
+});
+
+const TeamScore = React.memo(() => {
+    const client = useSubscriberClient();
+    const [score, setScore] = React.useState<RadarTeamScore | null>(() => client.getTeamScore());
+    React.useEffect(() => client.events.on("radar.events", () => setScore(client.getTeamScore())), [client]);
+
+    if (!score) {
+        return null;
+    }
+
+    return (
+        <Typography variant={"h6"}>
+            <span style={{ color: teamColor(3) }}>CT {score.scoreCt}</span>
+            {" : "}
+            <span style={{ color: teamColor(2) }}>{score.scoreT} T</span>
+        </Typography>
+    );
+});
+
+const KillFeed = React.memo(() => {
+    const client = useSubscriberClient();
+    const [entries, setEntries] = React.useState<FeedEntry[]>([]);
+    const nextId = React.useRef(0);
+
+    React.useEffect(() => client.events.on("radar.events", events => {
+        setEntries(entries => {
+            for (const event of events) {
+                if (typeof event === "object" && "roundStart" in event) {
+                    entries = [];
+                    continue;
+                }
+
+                if (typeof event === "object" && "scoreChanged" in event) {
+                    continue;
+                }
+
+                entries = [...entries, { id: nextId.current++, event }];
+            }
+
+            return entries.slice(-kFeedLength);
+        });
+    }), [client]);
+
+    return (
+        <React.Fragment>
+            {entries.map(entry => <FeedEntryRenderer key={entry.id} event={entry.event} />)}
+        </React.Fragment>
+    );
+});
+
+const PlayerName = (props: { player: RadarEventPlayer }) => (
+    <span style={{ color: teamColor(props.player.teamId) }}>{props.player.playerName}</span>
+);
+
+const FeedEntryRenderer = React.memo((props: { event: RadarEvent }) => {
+    const { event } = props;
+
+    let content: React.ReactNode;
+    if (event === "bombDetonated") {
+        content = "The bomb has exploded";
+    } else if ("kill" in event) {
+        const { killer, victim, headshot } = event.kill;
+        content = (
+            <React.Fragment>
+                {killer ? <PlayerName player={killer} /> : "World"}
+                {headshot ? " ⌖ " : " ➜ "}
+                <PlayerName player={victim} />
+            </React.Fragment>
+        );
+    } else if ("bombPlanted" in event) {
+        const { playerName, bombSite } = event.bombPlanted;
+        content = `${playerName ?? "Unknown"} planted the bomb at ${bombSiteName(bombSite)}`;
+    } else if ("bombDefused" in event) {
+        content = `${event.bombDefused.playerName ?? "Unknown"} defused the bomb`;
+    } else if ("roundEnd" in event) {
+        const { winnerTeam } = event.roundEnd;
+        content = typeof winnerTeam === "number" ? `Round won by ${teamName(winnerTeam)}` : "Round over";
+    } else {
+        return null;
+    }
+
+    return (
+        <Typography variant={"body2"} sx={{ px: 1, borderRadius: 1, backgroundColor: "rgba(0, 0, 0, 0.6)" }}>
+            {content}
+        </Typography>
+    );
+});
@@ -1,9 +1,10 @@
-import { Box, Typography, CircularProgress, Alert } from "@mui/material";
+import { Box, Typography, CircularProgress, Alert, TextField, Button } from "@mui/material";
 import * as React from "react";
 import { SubscriberClientProvider, useSubscriberClient } from "../../../components/connection";
 import { useParams } from "react-router-dom";
 import { RadarState } from "../../../../backend/connection";
 import { ContextRadarState, RadarRenderer } from "./radar";
+import { MatchEvents } from "./events";
 
 const kServerUrl: string | null = process.env.SERVER_URL;
 export default React.memo(() => {
@@ -39,6 +40,7 @@ export default React.memo(() => {
             <SubscriberClientProvider address={targetUrl}>
                 <ClientStateNew />
                 <ClientStateConnecting />
+                <ClientStatePasswordRequired />
                 <ClientStateFailed />
                 <ClientStateConnected />
                 <ClientStateDisconnected />
@@ -96,6 +98,49 @@ const ClientStateConnecting = React.memo(() => {
     );
 });
 
+const ClientStatePasswordRequired = React.memo(() => {
+    const client = useSubscriberClient();
+    const state = useSubscriberClientState();
+    const [password, setPassword] = React.useState("");
+
+    if (state.state !== "password-required") {
+        return;
+    }
+
+    return (
+        <Box
+            component={"form"}
+            sx={{ alignSelf: "center", display: "flex", flexDirection: "column", gap: ".5em" }}
+            onSubmit={event => {
+                event.preventDefault();
+                client.submitPassword(password);
+            }}
+        >
+            <Typography variant={"h5"}>Password required</Typography>
+            <TextField
+                type={"password"}
+                value={password}
+                onChange={event => setPassword(event.target.value)}
+                placeholder={"Password"}
+                error={state.passwordInvalid}
+                helperText={state.passwordInvalid ? "Invalid password" : undefined}
+                sx={{
+                    width: "20em",
+                }}
+                size={"small"}
+                autoFocus
+            />
+            <Button
+                type={"submit"}
+                sx={{ alignSelf: "right", marginLeft: "auto", pl: 2, pr: 2 }}
+                disabled={password === ""}
+            >
+                Connect
+            </Button>
+        </Box>
+    );
+});
+
 const ClientStateFailed = React.memo(() => {
     const state = useSubscriberClientState();
     if (state.state !== "failed") {
@@ -126,10 +171,12 @@ const ClientStateDisconnected = React.memo(() => {
 const ClientStateConnected = React.memo(() => {
     const client = useSubscriberClient();
     const state = useSubscriberClientState();
-    const [radarState, setRadarState] = React.useState<RadarState>({
+    const [radarState, setRadarState] = React.useState<RadarState>(() => client.getRadarState() ?? {
         players: [],
         worldName: "de_anubis",
+        mapCalibration: null,
         bomb: null,
+        bombTrail: null,
     });
 
     React.useEffect(() => client.events.on("radar.state", update => setRadarState(update)), [client]);
@@ -139,10 +186,11 @@ const ClientStateConnected = React.memo(() => {
     }
 
     return (
-        <Box sx={{ alignSelf: "center", height: "100%", width: "100%", display: "flex", flexDirection: "column", justifyContent: "center" }}>
+        <Box sx={{ alignSelf: "center", height: "100%", width: "100%", display: "flex", flexDirection: "column", justifyContent: "center", position: "relative" }}>
             <ContextRadarState.Provider value={radarState}>
                 <RadarRenderer />
             </ContextRadarState.Provider>
+            <MatchEvents />
         </Box>
     );
 })
\ No newline at end of file
@@ -1,6 +1,7 @@
 import * as React from "react";
-import { RadarPlayerInfo, RadarBombInfo, RadarState } from "../../../../backend/connection";
-import { LoadedMap, loadMap } from "../../../../map-info";
+import { RadarPlayerInfo, RadarBombInfo, RadarBombTrail, RadarState, RadarGrenade, RadarSmoke, RadarInferno, GrenadeType, RadarDroppedWeapon } from "../../../../backend/connection";
+import { kPrimaryWeapons, weaponName } from "../../../../utils/weapons";
+import { LoadedMap, loadMap, projectDistance, projectPosition } from "../../../../map-info";
 import { Box, Drawer, IconButton, Typography, Slider } from "@mui/material";
 import ImageBlueCross from "../../../../assets/blue_cross.png";
 import ImageBlueDot from "../../../../assets/blue_dot.png";
@@ -12,7 +13,13 @@ import MenuIcon from "@mui/icons-material/Menu";
 export const ContextRadarState = React.createContext<RadarState>({
     players: [],
     worldName: "de_anubis",
+    mapCalibration: null,
     bomb: null,
+    bombTrail: null,
+    grenades: [],
+    smokes: [],
+    infernos: [],
+    droppedWeapons: [],
 });
 
 
@@ -153,7 +160,7 @@ const SqareContainer = React.memo((props: {
 });
 
 const MapRenderer = React.memo(() => {
-    const { players, bomb } = React.useContext(ContextRadarState);
+    const { players, bomb, bombTrail, grenades, smokes, infernos, droppedWeapons } = React.useContext(ContextRadarState);
     const map = React.useContext(ContextMap);
 
     return (
@@ -167,6 +174,11 @@ const MapRenderer = React.memo(() => {
                     backgroundSize: "contain",
                 }}
             />
+            <MapBombTrail bombTrail={bombTrail} />
+            {(infernos ?? []).map(inferno => <MapInferno inferno={inferno} key={`inferno-${inferno.entityId}`} />)}
+            {(droppedWeapons ?? []).map(weapon => <MapDroppedWeapon weapon={weapon} key={`weapon-${weapon.entityId}`} />)}
+            {(smokes ?? []).map(smoke => <MapSmoke smoke={smoke} key={`smoke-${smoke.entityId}`} />)}
+            {(grenades ?? []).map(grenade => <MapGrenade grenade={grenade} key={`grenade-${grenade.entityId}`} />)}
             {players.map(player => <MapPlayerPing playerInfo={player} key={`player-${player.controllerEntityId}`} />)}
             <MapBombPing bombInfo={bomb} />
         </Box>
@@ -180,10 +192,10 @@ const MapPlayerPing = React.memo((props: {
     playerInfo: RadarPlayerInfo
 }) => {
     const { playerInfo } = props;
-    const map = React.useContext(ContextMap);
+    const { mapCalibration } = React.useContext(ContextRadarState);
     const { iconSize } = React.useContext(IconSizeContext);
-    if (!map) {
-        /* we need the map info */
+    if (!mapCalibration) {
+        /* we need the map calibration */
         return null;
     }
 
@@ -202,13 +214,7 @@ const MapPlayerPing = React.memo((props: {
         }
     }
 
-    const offsets = map.metaInfo.offset;
-    const mapSize = map.metaInfo.resolution * 1024;
-
-    const [floor] = map.metaInfo.floors.filter(floor => floor.zRange.min <= props.playerInfo.position[2] && props.playerInfo.position[2] <= floor.zRange.max);
-
-    const playerX = props.playerInfo.position[0] + offsets.x;
-    const playerY = props.playerInfo.position[1] + offsets.y;
+    const [playerX, playerY] = projectPosition(mapCalibration, props.playerInfo.position);
 
     return (
         <Box
@@ -229,8 +235,8 @@ const MapPlayerPing = React.memo((props: {
             }}
 
             style={{
-                "--pos-x": `${playerX * 100 / mapSize - iconSize / 2 + (floor?.offset.x ?? 0)}%`,
-                "--pos-y": `${playerY * 100 / mapSize - iconSize / 2 + (floor?.offset.y ?? 0)}%`,
+                "--pos-x": `${playerX - iconSize / 2}%`,
+                "--pos-y": `${playerY - iconSize / 2}%`,
                 "--rotation": `${playerInfo.playerHealth <= 0 ? 0 : playerInfo.rotation * -1}deg`
             } as any}
         />
@@ -239,22 +245,14 @@ const MapPlayerPing = React.memo((props: {
 const MapBombPing = React.memo((props: {
     bombInfo: RadarBombInfo,
 }) => {
-    const map = React.useContext(ContextMap);
+    const { mapCalibration } = React.useContext(ContextRadarState);
     const { iconSize } = React.useContext(IconSizeContext);
-    if (!map || !props.bombInfo) {
-        /* we need the map and bomb info */
+    if (!mapCalibration || !props.bombInfo) {
+        /* we need the map calibration and bomb info */
         return null;
     }
 
-    let bombX = props.bombInfo.position[0];
-    let bombY = props.bombInfo.position[1];
-
-    const mapSize = map.metaInfo.resolution * 1024;
-    const offsets = map.metaInfo.offset;
-    bombX += offsets.x;
-    bombY += offsets.y;
-
-    const [floor] = map.metaInfo.floors.filter(floor => floor.zRange.min <= props.bombInfo.position[2] && props.bombInfo.position[2] <= floor.zRange.max);
+    const [bombX, bombY] = projectPosition(mapCalibration, props.bombInfo.position);
 
     return (
         <Box
@@ -273,9 +271,274 @@ const MapBombPing = React.memo((props: {
             }}
 
             style={{
-                "--pos-x": `${bombX * 100 / mapSize - iconSize / 2 + (floor?.offset.x ?? 0)}%`,
-                "--pos-y": `${bombY * 100 / mapSize - iconSize / 2 + (floor?.offset.y ?? 0)}%`,
+                "--pos-x": `${bombX - iconSize / 2}%`,
+                "--pos-y": `${bombY - iconSize / 2}%`,
             } as any}
         />
     )
-});
\ No newline at end of file
+});
+const MapBombTrail = React.memo((props: {
+    bombTrail: RadarBombTrail,
+}) => {
+    const { mapCalibration } = React.useContext(ContextRadarState);
+    const { iconSize } = React.useContext(IconSizeContext);
+    if (!mapCalibration || !props.bombTrail) {
+        /* we need the map calibration and the bomb trail */
+        return null;
+    }
+
+    const mapPosition = (position: [number, number, number], size: number) => {
+        const [x, y] = projectPosition(mapCalibration, position);
+        return {
+            "--pos-x": `${x - size / 2}%`,
+            "--pos-y": `${y - size / 2}%`,
+        };
+    };
+
+    const { positions, carrierHistory } = props.bombTrail;
+    const dotSize = iconSize / 4;
+    const eventSize = iconSize / 2;
+
+    return (
+        <React.Fragment>
+            {positions.map((position, index) => (
+                <Box
+                    key={`bomb-trail-${index}`}
+                    sx={{
+                        bottom: "var(--pos-y)",
+                        left: "var(--pos-x)",
+
+                        height: `${dotSize}%`,
+                        width: `${dotSize}%`,
+
+                        position: "absolute",
+                        borderRadius: "50%",
+                        backgroundColor: "#ff5722",
+                    }}
+
+                    style={{
+                        ...mapPosition(position, dotSize),
+                        opacity: 0.2 + 0.6 * (index + 1) / positions.length,
+                    } as any}
+                />
+            ))}
+            {carrierHistory.filter(event => !event.pickedUp).map((event, index) => (
+                <Box
+                    key={`bomb-drop-${index}`}
+                    title={`${event.playerName} dropped the bomb`}
+                    sx={{
+                        bottom: "var(--pos-y)",
+                        left: "var(--pos-x)",
+
+                        height: `${eventSize}%`,
+                        width: `${eventSize}%`,
+
+                        position: "absolute",
+                        borderRadius: "50%",
+                        border: "2px solid #ff5722",
+                    }}
+
+                    style={mapPosition(event.position, eventSize) as any}
+                />
+            ))}
+        </React.Fragment>
+    )
+});
+
+/* radius of a smoke cloud and of a single fire in world units */
+const kSmokeRadius = 144;
+const kFireRadius = 40;
+
+const kGrenadeColors: Record<GrenadeType, string> = {
+    highExplosive: "#f44336",
+    flashbang: "#ffffff",
+    smoke: "#9e9e9e",
+    molotov: "#ff9800",
+    incendiary: "#ff9800",
+    decoy: "#795548",
+};
+
+export const teamColor = (teamId: number) => teamId === 3 ? "#5d9cec" : "#f6bb42";
+
+const MapSmoke = React.memo((props: {
+    smoke: RadarSmoke,
+}) => {
+    const { mapCalibration } = React.useContext(ContextRadarState);
+    if (!mapCalibration) {
+        return null;
+    }
+
+    const { smoke } = props;
+    const [x, y] = projectPosition(mapCalibration, smoke.position);
+    const size = projectDistance(mapCalibration, kSmokeRadius) * 2;
+
+    return (
+        <Box
+            title={`Smoke (${Math.ceil(smoke.timeRemaining)}s)`}
+            sx={{
+                bottom: "var(--pos-y)",
+                left: "var(--pos-x)",
+
+                height: `${size}%`,
+                width: `${size}%`,
+
+                position: "absolute",
+                borderRadius: "50%",
+                backgroundColor: "rgba(200, 200, 200, 0.6)",
+                border: "2px solid var(--team-color)",
+            }}
+
+            style={{
+                "--pos-x": `${x - size / 2}%`,
+                "--pos-y": `${y - size / 2}%`,
+                "--team-color": teamColor(smoke.teamId),
+                opacity: Math.min(1, smoke.timeRemaining / 2),
+            } as any}
+        />
+    );
+});
+
+const MapInferno = React.memo((props: {
+    inferno: RadarInferno,
+}) => {
+    const { mapCalibration } = React.useContext(ContextRadarState);
+    if (!mapCalibration) {
+        return null;
+    }
+
+    const { inferno } = props;
+    const size = projectDistance(mapCalibration, kFireRadius) * 2;
+
+    return (
+        <React.Fragment>
+            {inferno.firePositions.map((position, index) => {
+                const [x, y] = projectPosition(mapCalibration, position);
+                return (
+                    <Box
+                        key={`fire-${index}`}
+                        sx={{
+                            bottom: "var(--pos-y)",
+                            left: "var(--pos-x)",
+
+                            height: `${size}%`,
+                            width: `${size}%`,
+
+                            position: "absolute",
+                            borderRadius: "50%",
+                            backgroundColor: "rgba(255, 87, 34, 0.5)",
+                        }}
+
+                        style={{
+                            "--pos-x": `${x - size / 2}%`,
+                            "--pos-y": `${y - size / 2}%`,
+                        } as any}
+                    />
+                );
+            })}
+        </React.Fragment>
+    );
+});
+
+const MapGrenade = React.memo((props: {
+    grenade: RadarGrenade,
+}) => {
+    const { mapCalibration } = React.useContext(ContextRadarState);
+    const { iconSize } = React.useContext(IconSizeContext);
+    if (!mapCalibration) {
+        return null;
+    }
+
+    const { grenade } = props;
+    const [x, y] = projectPosition(mapCalibration, grenade.position);
+    const size = iconSize / 2;
+
+    return (
+        <Box
+            sx={{
+                bottom: "var(--pos-y)",
+                left: "var(--pos-x)",
+
+                height: `${size}%`,
+                width: `${size}%`,
+
+                position: "absolute",
+                borderRadius: "50%",
+                backgroundColor: "var(--grenade-color)",
+                border: "2px solid var(--team-color)",
+            }}
+
+            style={{
+                "--pos-x": `${x - size / 2}%`,
+                "--pos-y": `${y - size / 2}%`,
+                "--grenade-color": kGrenadeColors[grenade.grenadeType],
+                "--team-color": teamColor(grenade.teamId),
+            } as any}
+        />
+    );
+});
+
+const MapDroppedWeapon = React.memo((props: {
+    weapon: RadarDroppedWeapon,
+}) => {
+    const { mapCalibration } = React.useContext(ContextRadarState);
+    const { iconSize } = React.useContext(IconSizeContext);
+    if (!mapCalibration) {
+        return null;
+    }
+
+    const { weapon } = props;
+    const [x, y] = projectPosition(mapCalibration, weapon.position);
+    const size = iconSize / 3;
+
+    if (!kPrimaryWeapons.has(weapon.weapon)) {
+        return (
+            <Box
+                title={weaponName(weapon.weapon)}
+                sx={{
+                    bottom: "var(--pos-y)",
+                    left: "var(--pos-x)",
+
+                    height: `${size}%`,
+                    width: `${size}%`,
+
+                    position: "absolute",
+                    backgroundColor: "#bdbdbd",
+                    border: "1px solid #424242",
+                }}
+
+                style={{
+                    "--pos-x": `${x - size / 2}%`,
+                    "--pos-y": `${y - size / 2}%`,
+                } as any}
+            />
+        );
+    }
+
+    return (
+        <Typography
+            variant={"caption"}
+            sx={{
+                bottom: "var(--pos-y)",
+                left: "var(--pos-x)",
+                transform: "translate(-50%, 50%)",
+
+                position: "absolute",
+                px: 0.5,
+                borderRadius: 0.5,
+                lineHeight: 1.4,
+                whiteSpace: "nowrap",
+
+                color: "#ffffff",
+                backgroundColor: "rgba(66, 66, 66, 0.8)",
+                border: "1px solid #bdbdbd",
+            }}
+
+            style={{
+                "--pos-x": `${x}%`,
+                "--pos-y": `${y}%`,
+            } as any}
+        >
+            {weaponName(weapon.weapon)}
+        </Typography>
+    );
+});
@@ -31,6 +31,10 @@ use anyhow::{
     Context,
 };
 
+/// Time after which the last error of a state will be forgotten
+const ERROR_RETENTION: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum StateCacheType {
     /// The state will be cached and never removed
     Persistent,
@@ -74,11 +78,68 @@ struct InternalState {
     value: Box<dyn Any + Send>,
     value_update: fn(&mut Box<dyn Any + Send>, states: &StateRegistry) -> anyhow::Result<()>,
 
+    type_name: &'static str,
     cache_key: (TypeId, u64),
     cache_type: StateCacheType,
 
     dirty: bool,
+    created: Instant,
     last_access: Instant,
+    last_resolve: Option<Duration>,
+}
+
+struct StateError {
+    type_name: &'static str,
+    message: String,
+    timestamp: Instant,
+}
+
+/// Diagnostic information about a state within the registry
+#[derive(Clone, Debug)]
+pub struct StateInfo {
+    /// Full type name of the state
+    pub type_name: &'static str,
+
+    /// Hash of the state parameter
+    pub params_hash: u64,
+
+    /// Cache type or None if the state failed to resolve and is not cached
+    pub cache_type: Option<StateCacheType>,
+
+    /// Time since the state has been created
+    pub age: Option<Duration>,
+
+    /// Time it took to create and update the state the last time it was resolved
+    pub resolve_latency: Option<Duration>,
+
+    /// The state is currently borrowed and could not be inspected
+    pub borrowed: bool,
+
+    /// Last error while creating or updating the state and the time since it occurred
+    pub last_error: Option<(String, Duration)>,
+}
+
+impl StateInfo {
+    /// Type name without module paths
+    pub fn short_type_name(&self) -> String {
+        let mut result = String::with_capacity(self.type_name.len());
+        let mut segment_start = 0;
+        let mut chars = self.type_name.char_indices().peekable();
+        while let Some((_, char)) = chars.next() {
+            if char == ':' && matches!(chars.peek(), Some((_, ':'))) {
+                chars.next();
+                result.truncate(segment_start);
+                continue;
+            }
+
+            result.push(char);
+            if !(char.is_alphanumeric() || char == '_') {
+                segment_start = result.len();
+            }
+        }
+
+        result
+    }
 }
 
 struct StateAllocator {
@@ -153,6 +214,7 @@ fn transpose_ref_mut_opt<T>(x: RefMut<'_, Option<T>>) -> Option<RefMut<'_, T>> {
 pub struct StateRegistry {
     allocator: RefCell<StateAllocator>,
     states: Vec<RefCell<Option<InternalState>>>,
+    errors: RefCell<HashMap<(TypeId, u64), StateError>>,
 }
 
 impl StateRegistry {
@@ -162,6 +224,7 @@ impl StateRegistry {
         Self {
             allocator: RefCell::new(StateAllocator::new(capacity)),
             states,
+            errors: Default::default(),
         }
     }
 
@@ -194,6 +257,10 @@ impl StateRegistry {
                 *state_ref = None;
             }
         }
+
+        self.errors
+            .get_mut()
+            .retain(|_, error| error.timestamp.elapsed() < ERROR_RETENTION);
     }
 
     /// Preset a specific state
@@ -209,12 +276,16 @@ impl StateRegistry {
             value: Box::new(value),
             value_update: value_update_proxy::<T>,
 
+            type_name: any::type_name::<T>(),
             cache_key,
             cache_type: T::cache_type(),
 
             dirty: false,
+            created: Instant::now(),
             last_access: Instant::now(),
+            last_resolve: None,
         });
+        self.errors.get_mut().remove(&cache_key);
         Ok(())
     }
 
@@ -262,6 +333,35 @@ impl StateRegistry {
         value: &mut RefMut<'_, Option<InternalState>>,
         params: T::Parameter,
     ) -> anyhow::Result<()> {
+        let result = self.initialize_value_inner::<T>(cache_key, value, params);
+        match &result {
+            Ok(true) => {
+                self.errors.borrow_mut().remove(&cache_key);
+            }
+            Ok(false) => {}
+            Err(error) => {
+                self.errors.borrow_mut().insert(
+                    cache_key,
+                    StateError {
+                        type_name: any::type_name::<T>(),
+                        message: format!("{:#}", error),
+                        timestamp: Instant::now(),
+                    },
+                );
+            }
+        }
+
+        result.map(|_| ())
+    }
+
+    /// Returns true if the state has been created or updated
+    fn initialize_value_inner<T: State>(
+        &self,
+        cache_key: (TypeId, u64),
+        value: &mut RefMut<'_, Option<InternalState>>,
+        params: T::Parameter,
+    ) -> anyhow::Result<bool> {
+        let resolve_start = Instant::now();
         let value = match value.as_mut() {
             Some(value) => value,
             None => {
@@ -274,24 +374,111 @@ impl StateRegistry {
                     value: state,
                     value_update: value_update_proxy::<T>,
 
+                    type_name: any::type_name::<T>(),
                     cache_key,
                     cache_type: T::cache_type(),
 
                     dirty: false,
+                    created: Instant::now(),
                     last_access: Instant::now(),
+                    last_resolve: Some(resolve_start.elapsed()),
                 });
 
-                value.as_mut().unwrap()
+                return Ok(true);
             }
         };
 
-        if value.dirty {
-            (value.value_update)(&mut value.value, self)
-                .with_context(|| format!("update {}", any::type_name::<T>()))?;
-            value.dirty = false;
+        if !value.dirty {
+            return Ok(false);
         }
 
-        Ok(())
+        (value.value_update)(&mut value.value, self)
+            .with_context(|| format!("update {}", any::type_name::<T>()))?;
+        value.dirty = false;
+        value.last_resolve = Some(resolve_start.elapsed());
+        Ok(true)
+    }
+
+    /// Collect diagnostic information about all cached states
+    /// and the states which failed to resolve.
+    pub fn inspect(&self) -> Vec<StateInfo> {
+        let mut result = Vec::new();
+        let mut errors = self
+            .errors
+            .borrow()
+            .iter()
+            .map(|(cache_key, error)| {
+                (
+                    *cache_key,
+                    (
+                        error.type_name,
+                        error.message.clone(),
+                        error.timestamp.elapsed(),
+                    ),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let allocator = self.allocator.borrow();
+        for (cache_key, index) in allocator.index_lookup.iter() {
+            let state = match self.states[*index].try_borrow() {
+                Ok(state) => state,
+                Err(_) => {
+                    let error = errors.remove(cache_key);
+                    result.push(StateInfo {
+                        type_name: error.as_ref().map_or("<borrowed>", |error| error.0),
+                        params_hash: cache_key.1,
+                        cache_type: None,
+                        age: None,
+                        resolve_latency: None,
+                        borrowed: true,
+                        last_error: error.map(|(_, message, age)| (message, age)),
+                    });
+                    continue;
+                }
+            };
+
+            let state = match state.as_ref() {
+                Some(state) => state,
+                /* failed to create, will be reported with the errors below */
+                None => continue,
+            };
+
+            result.push(StateInfo {
+                type_name: state.type_name,
+                params_hash: cache_key.1,
+                cache_type: Some(state.cache_type),
+                age: Some(state.created.elapsed()),
+                resolve_latency: state.last_resolve,
+                borrowed: false,
+                last_error: errors
+                    .remove(cache_key)
+                    .map(|(_, message, age)| (message, age)),
+            });
+        }
+
+        for (cache_key, (type_name, message, age)) in errors {
+            result.push(StateInfo {
+                type_name,
+                params_hash: cache_key.1,
+                cache_type: None,
+                age: None,
+                resolve_latency: None,
+                borrowed: false,
+                last_error: Some((message, age)),
+            });
+        }
+
+        result.sort_by(|a, b| {
+            a.type_name
+                .cmp(b.type_name)
+                .then(a.params_hash.cmp(&b.params_hash))
+        });
+        result
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.states.len()
     }
 
     pub fn resolve_mut<T: State>(&self, params: T::Parameter) -> anyhow::Result<RefMut<'_, T>> {
@@ -410,6 +597,43 @@ mod test {
         assert!(states.resolve::<StateC>(0).is_ok());
     }
 
+    struct StateFailing;
+    impl State for StateFailing {
+        type Parameter = ();
+
+        fn create(_states: &StateRegistry, _params: Self::Parameter) -> anyhow::Result<Self> {
+            anyhow::bail!("not available")
+        }
+
+        fn cache_type() -> StateCacheType {
+            StateCacheType::Persistent
+        }
+    }
+
+    #[test]
+    fn test_inspect() {
+        let states = StateRegistry::new(4);
+        assert!(states.resolve::<StateB>(()).is_ok());
+        assert!(states.resolve::<StateFailing>(()).is_err());
+
+        let info = states.inspect();
+        assert_eq!(info.len(), 2);
+
+        let state_b = info
+            .iter()
+            .find(|info| info.short_type_name() == "StateB")
+            .unwrap();
+        assert_eq!(state_b.cache_type, Some(StateCacheType::Persistent));
+        assert!(state_b.last_error.is_none());
+
+        let failing = info
+            .iter()
+            .find(|info| info.short_type_name() == "StateFailing")
+            .unwrap();
+        assert!(failing.cache_type.is_none());
+        assert!(failing.last_error.is_some());
+    }
+
     #[test]
     fn test_expire() {
         let mut states = StateRegistry::new(2);